@@ -12,31 +12,62 @@
 //! # Handlers
 //! - `create_block_handler`: Creates a block handler function that processes new blocks from the
 //!   blockchain
+//! - `create_pending_block_handler`: Creates a block handler function that evaluates head blocks
+//!   for monitors with two-phase alerts
 //! - `create_trigger_handler`: Creates a trigger handler function that processes trigger events
 //!   from the block processing pipeline
+//! - `create_outbox_trigger_handler`: Creates a trigger handler function that queues matches in a
+//!   durable outbox, delivered by `run_outbox_delivery`
+//! - `create_match_writer_handler`: Creates a trigger handler function that writes matches to a
+//!   JSONL file instead of sending notifications
+//!
+//! # Reports
+//! - `schedule_reports`: Schedules the summary reports of the monitors, built from the match
+//!   history recorded by `create_outbox_trigger_handler`
+//!
+//! # Reload
+//! - `ConfigReloader`: Reloads the configuration of a running service and swaps it into the
+//!   handlers, keeping the last good configuration if the new one is invalid
 
 use futures::future::BoxFuture;
-use std::{collections::HashMap, error::Error, sync::Arc};
+use std::{collections::HashMap, error::Error, path::Path, sync::Arc};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::{watch, Mutex};
+use tokio_cron_scheduler::Job;
 
 use crate::{
 	models::{
-		BlockChainType, BlockType, Monitor, MonitorMatch, Network, ProcessedBlock, ScriptLanguage,
-		TriggerConditions,
+		BlockChainType, BlockType, Monitor, MonitorMatch, Network, ProcessedBlock, ReportSchedule,
+		ScriptLanguage, TriggerConditions,
 	},
 	repositories::{
 		MonitorRepositoryTrait, MonitorService, NetworkRepositoryTrait, NetworkService,
 		TriggerRepositoryTrait, TriggerService,
 	},
 	services::{
-		blockchain::{BlockChainClient, BlockFilterFactory, ClientPoolTrait},
-		filter::{handle_match, FilterService},
+		blockchain::{
+			BlockChainClient, BlockFilterFactory, ClientPoolTrait, EvmClientTrait,
+			StellarClientTrait,
+		},
+		blockwatcher::{JobSchedulerTrait, PendingBlockHandler},
+		filter::{handle_match, EvaluationTrace, FilterService},
 		notification::NotificationService,
-		trigger::{TriggerError, TriggerExecutionService, TriggerExecutionServiceTrait},
+		report::{MatchHistory, MatchRecord, MonitorReport},
+		trigger::{
+			deliver_due_matches, AlertThrottle, DeliverySummary, MatchOutbox, OutboxEntry,
+			TriggerError, TriggerExecutionService, TriggerExecutionServiceTrait,
+		},
 	},
 	utils::{ScriptError, ScriptExecutorFactory},
 };
 
+mod reload;
+
+pub use reload::{
+	listen_for_reload_signal, watch_config_dir, ConfigReloader, NetworkChanges, ReloadRequest,
+	Reloadable, WatchedNetwork,
+};
+
 /// Type alias for handling ServiceResult
 pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
@@ -129,7 +160,8 @@ where
 /// # Arguments
 /// * `shutdown_tx` - Watch channel for shutdown signals
 /// * `filter_service` - Service for filtering blockchain data
-/// * `active_monitors` - List of active monitors
+/// * `active_monitors` - List of active monitors, read again for every block so a reloaded
+///   configuration applies to the next block
 /// * `client_pools` - Client pools for accessing blockchain clients
 ///
 /// # Returns
@@ -137,17 +169,66 @@ where
 pub fn create_block_handler<P: ClientPoolTrait + 'static>(
 	shutdown_tx: watch::Sender<bool>,
 	filter_service: Arc<FilterService>,
-	active_monitors: Vec<Monitor>,
+	active_monitors: impl Into<Reloadable<Vec<Monitor>>>,
 	client_pools: Arc<P>,
+) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync> {
+	block_handler(
+		shutdown_tx,
+		filter_service,
+		active_monitors.into(),
+		client_pools,
+		false,
+	)
+}
+
+/// Creates a block handler function that evaluates blocks at head depth for two-phase alerts.
+///
+/// Only monitors with `two_phase_alerts` enabled are evaluated, so the resulting matches can be
+/// sent as pending alerts before their block is confirmed.
+///
+/// # Arguments
+/// * `shutdown_tx` - Watch channel for shutdown signals
+/// * `filter_service` - Service for filtering blockchain data
+/// * `active_monitors` - List of active monitors, read again for every block
+/// * `client_pools` - Client pools for accessing blockchain clients
+///
+/// # Returns
+/// Returns a function that handles incoming head blocks
+pub fn create_pending_block_handler<P: ClientPoolTrait + 'static>(
+	shutdown_tx: watch::Sender<bool>,
+	filter_service: Arc<FilterService>,
+	active_monitors: impl Into<Reloadable<Vec<Monitor>>>,
+	client_pools: Arc<P>,
+) -> PendingBlockHandler {
+	block_handler(
+		shutdown_tx,
+		filter_service,
+		active_monitors.into(),
+		client_pools,
+		true,
+	)
+}
+
+/// Builds a block handler, optionally restricted to monitors with two-phase alerts.
+fn block_handler<P: ClientPoolTrait + 'static>(
+	shutdown_tx: watch::Sender<bool>,
+	filter_service: Arc<FilterService>,
+	active_monitors: Reloadable<Vec<Monitor>>,
+	client_pools: Arc<P>,
+	two_phase_only: bool,
 ) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync> {
 	Arc::new(
 		move |block: BlockType, network: Network| -> BoxFuture<'static, ProcessedBlock> {
 			let filter_service = filter_service.clone();
-			let active_monitors = active_monitors.clone();
+			let active_monitors = active_monitors.get();
 			let client_pools = client_pools.clone();
 			let shutdown_tx = shutdown_tx.clone();
 			Box::pin(async move {
-				let applicable_monitors = filter_network_monitors(&active_monitors, &network.slug);
+				let mut applicable_monitors =
+					filter_network_monitors(&active_monitors, &network.slug);
+				if two_phase_only {
+					applicable_monitors.retain(|m| m.two_phase_alerts);
+				}
 
 				let mut processed_block = ProcessedBlock {
 					block_number: block.number().unwrap_or(0),
@@ -239,18 +320,26 @@ where
 /// # Arguments
 /// * `shutdown_tx` - Watch channel for shutdown signals
 /// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Trigger scripts of the monitors, read again for every
+///   block so reloaded scripts apply to the next block
 ///
 /// # Returns
-/// Returns a function that handles trigger execution for matching monitors
+/// Returns a function that handles trigger execution for matching monitors. Failed notifications
+/// are logged and do not fail the handler.
 pub fn create_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 'static>(
 	shutdown_tx: watch::Sender<bool>,
 	trigger_service: Arc<S>,
-	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
-) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync> {
+	active_monitors_trigger_scripts: impl Into<Reloadable<HashMap<String, (ScriptLanguage, String)>>>,
+) -> Arc<
+	impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<std::result::Result<(), TriggerError>>
+		+ Send
+		+ Sync,
+> {
+	let active_monitors_trigger_scripts = active_monitors_trigger_scripts.into();
 	Arc::new(move |block: &ProcessedBlock| {
 		let mut shutdown_rx = shutdown_tx.subscribe();
 		let trigger_service = trigger_service.clone();
-		let trigger_scripts = active_monitors_trigger_scripts.clone();
+		let trigger_scripts = active_monitors_trigger_scripts.get();
 		let block = block.clone();
 
 		tokio::spawn(async move {
@@ -270,10 +359,369 @@ pub fn create_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 's
 					tracing::info!("Shutting down trigger handling task");
 				}
 			}
+			// Notifications are sent directly, so a failed one is logged rather than failing
+			// the block, which would send the others again
+			Ok(())
+		})
+	})
+}
+
+/// Creates a trigger handler function that queues matches in a durable outbox instead of
+/// sending notifications directly.
+///
+/// Matches go through the trigger conditions first, then one entry per trigger of the monitor is
+/// written to the outbox. The block watcher waits for the handler before it advances its
+/// checkpoint, so queued matches are not lost if the service stops. If the matches cannot be
+/// queued the handler fails and the checkpoint stays put, so the block is processed again; the
+/// outbox skips the entries that were already queued. The handler does not stop on shutdown to
+/// avoid leaving a block half queued. Queued matches are also recorded in the match history
+/// summarized by reports.
+///
+/// # Arguments
+/// * `outbox` - Outbox the matches are queued in
+/// * `history` - History the matches are recorded in
+/// * `active_monitors_trigger_scripts` - Trigger scripts of the monitors, read again for every
+///   block so reloaded scripts apply to the next block
+///
+/// # Returns
+/// Returns a function that queues the matches of a processed block, failing if they cannot be
+/// queued
+pub fn create_outbox_trigger_handler<O: MatchOutbox + 'static, H: MatchHistory + 'static>(
+	outbox: Arc<O>,
+	history: Arc<H>,
+	active_monitors_trigger_scripts: impl Into<Reloadable<HashMap<String, (ScriptLanguage, String)>>>,
+) -> Arc<
+	impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<std::result::Result<(), TriggerError>>
+		+ Send
+		+ Sync,
+> {
+	let active_monitors_trigger_scripts = active_monitors_trigger_scripts.into();
+	Arc::new(move |block: &ProcessedBlock| {
+		let outbox = outbox.clone();
+		let history = history.clone();
+		let trigger_scripts = active_monitors_trigger_scripts.get();
+		let block = block.clone();
+
+		tokio::spawn(async move {
+			if block.processing_results.is_empty() {
+				return Ok(());
+			}
+			let filtered_matches = run_trigger_filters(
+				&block.processing_results,
+				&block.network_slug,
+				&trigger_scripts,
+			)
+			.await;
+			let entries = filtered_matches
+				.iter()
+				.flat_map(|monitor_match| {
+					monitor_match.monitor().triggers.iter().map(|trigger| {
+						OutboxEntry::new(
+							&block.network_slug,
+							block.block_number,
+							monitor_match.clone(),
+							trigger,
+						)
+					})
+				})
+				.collect();
+			outbox.enqueue(entries).await.map_err(|e| {
+				TriggerError::execution_error(
+					format!(
+						"Failed to queue matches of block {} on {}",
+						block.block_number, block.network_slug
+					),
+					Some(e.into()),
+					None,
+				)
+			})?;
+
+			let now = chrono::Utc::now().timestamp_millis();
+			let records = filtered_matches
+				.iter()
+				.map(|monitor_match| {
+					MatchRecord::new(&block.network_slug, block.block_number, monitor_match, now)
+				})
+				.collect();
+			// The history only feeds reports and the matches are already queued for delivery,
+			// so failing to record them is logged without failing the block
+			if let Err(e) = history.record(records).await {
+				TriggerError::execution_error(
+					format!(
+						"Failed to record matches of block {} on {}",
+						block.block_number, block.network_slug
+					),
+					Some(e.into()),
+					None,
+				);
+			}
+			Ok(())
 		})
 	})
 }
 
+/// Delivers the matches queued in an outbox until shutdown.
+///
+/// The outbox is drained every `interval`, and keys of delivered matches are forgotten once they
+/// are older than `retention`. Alerts are throttled according to the alert policies of their
+/// monitors and triggers.
+///
+/// # Arguments
+/// * `outbox` - Outbox to drain
+/// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Trigger scripts of the monitors, needed by script
+///   triggers
+/// * `throttle` - Throttle applying the alert policies
+/// * `interval` - Time between two delivery runs
+/// * `retention` - How long keys of delivered matches are kept to skip duplicates
+/// * `shutdown_rx` - Watch channel for shutdown signals
+pub async fn run_outbox_delivery<O: MatchOutbox, S: TriggerExecutionServiceTrait + Sync>(
+	outbox: Arc<O>,
+	trigger_service: Arc<S>,
+	active_monitors_trigger_scripts: impl Into<Reloadable<HashMap<String, (ScriptLanguage, String)>>>,
+	throttle: Arc<AlertThrottle>,
+	interval: std::time::Duration,
+	retention: std::time::Duration,
+	mut shutdown_rx: watch::Receiver<bool>,
+) {
+	let active_monitors_trigger_scripts = active_monitors_trigger_scripts.into();
+	loop {
+		let trigger_scripts = active_monitors_trigger_scripts.get();
+		match deliver_due_matches(&*outbox, &*trigger_service, &trigger_scripts, &throttle).await {
+			Ok(summary) if summary != DeliverySummary::default() => {
+				tracing::info!(
+					"Outbox delivery: {} delivered, {} failed, {} dead-lettered, {} suppressed, {} \
+					 digested",
+					summary.delivered,
+					summary.failed,
+					summary.dead_lettered,
+					summary.suppressed,
+					summary.digested
+				);
+			}
+			Ok(_) => {}
+			Err(e) => tracing::error!("Failed to deliver queued matches: {}", e),
+		}
+		if let Err(e) = outbox.prune_delivered(retention).await {
+			tracing::error!("Failed to prune delivered matches: {}", e);
+		}
+
+		tokio::select! {
+			_ = tokio::time::sleep(interval) => {}
+			_ = shutdown_rx.changed() => {
+				tracing::info!("Shutting down outbox delivery");
+				return;
+			}
+		}
+	}
+}
+
+/// Builds the summary report of a monitor and sends it through the triggers of its schedule.
+///
+/// The report covers the matches recorded during the `period_ms` of the schedule before `now`,
+/// and the deliveries of the monitor dead-lettered during that period.
+///
+/// # Arguments
+/// * `monitor_name` - Name of the reported monitor
+/// * `schedule` - Report schedule of the monitor
+/// * `history` - History the matches were recorded in
+/// * `outbox` - Outbox holding the dead-lettered deliveries
+/// * `trigger_service` - Service sending the report through triggers
+/// * `now` - End of the reported period in milliseconds since the Unix epoch
+///
+/// # Returns
+/// Returns the sent report, or an error if the history or dead-letter queue cannot be read or a
+/// trigger fails
+pub async fn send_monitor_report<
+	H: MatchHistory,
+	O: MatchOutbox,
+	S: TriggerExecutionServiceTrait + Sync,
+>(
+	monitor_name: &str,
+	schedule: &ReportSchedule,
+	history: &H,
+	outbox: &O,
+	trigger_service: &S,
+	now: i64,
+) -> std::result::Result<MonitorReport, anyhow::Error> {
+	let period_start = now - schedule.period_ms as i64;
+	let records = history.matches(monitor_name, period_start, now).await?;
+	let dead_letters = outbox.dead_letters().await?;
+	let report = MonitorReport::new(
+		monitor_name,
+		period_start,
+		now,
+		&records,
+		&dead_letters,
+		schedule.top,
+	);
+	trigger_service
+		.send_report(&schedule.triggers, &report)
+		.await?;
+	Ok(report)
+}
+
+/// Schedules the summary reports of the active monitors.
+///
+/// One job is added per report schedule. Jobs look up their monitor again when they run, so
+/// reloaded triggers, periods and list sizes apply to the next report. Added or removed schedules
+/// need the jobs to be scheduled again on a new scheduler.
+///
+/// # Arguments
+/// * `scheduler` - Scheduler to add the report jobs to
+/// * `monitors` - Monitors of the service
+/// * `history` - History the matches are recorded in
+/// * `outbox` - Outbox holding the dead-lettered deliveries
+/// * `trigger_service` - Service sending the reports through triggers
+///
+/// # Returns
+/// Returns the number of scheduled reports, or an error if a job cannot be added
+pub async fn schedule_reports<J, H, O, S>(
+	scheduler: &J,
+	monitors: impl Into<Reloadable<Vec<Monitor>>>,
+	history: Arc<H>,
+	outbox: Arc<O>,
+	trigger_service: Arc<S>,
+) -> Result<usize>
+where
+	J: JobSchedulerTrait,
+	H: MatchHistory + 'static,
+	O: MatchOutbox + 'static,
+	S: TriggerExecutionServiceTrait + Send + Sync + 'static,
+{
+	let monitors = monitors.into();
+	let mut scheduled = 0;
+	for monitor in monitors.get().iter().filter(|monitor| !monitor.paused) {
+		for (index, schedule) in monitor.reports.iter().enumerate() {
+			let monitor_name = monitor.name.clone();
+			let monitors = monitors.clone();
+			let history = history.clone();
+			let outbox = outbox.clone();
+			let trigger_service = trigger_service.clone();
+
+			let job = Job::new_async(schedule.cron_schedule.as_str(), move |_uuid, _lock| {
+				let monitor_name = monitor_name.clone();
+				let monitors = monitors.clone();
+				let history = history.clone();
+				let outbox = outbox.clone();
+				let trigger_service = trigger_service.clone();
+				Box::pin(async move {
+					let schedule = monitors
+						.get()
+						.iter()
+						.find(|monitor| monitor.name == monitor_name && !monitor.paused)
+						.and_then(|monitor| monitor.reports.get(index).cloned());
+					let Some(schedule) = schedule else {
+						tracing::warn!(
+							"Skipping report of monitor {}, which is no longer configured",
+							monitor_name
+						);
+						return;
+					};
+
+					match send_monitor_report(
+						&monitor_name,
+						&schedule,
+						&*history,
+						&*outbox,
+						&*trigger_service,
+						chrono::Utc::now().timestamp_millis(),
+					)
+					.await
+					{
+						Ok(report) => tracing::info!(
+							"Sent report of monitor {} ({} matches)",
+							monitor_name,
+							report.match_count
+						),
+						Err(e) => {
+							tracing::error!(
+								"Failed to send report of monitor {}: {}",
+								monitor_name,
+								e
+							)
+						}
+					}
+				})
+			})?;
+			scheduler.add(job).await.map_err(|e| e as Box<dyn Error>)?;
+			scheduled += 1;
+		}
+	}
+	Ok(scheduled)
+}
+
+/// Creates a trigger handler function that writes matches to a JSONL file instead of sending
+/// notifications.
+///
+/// Matches go through the same trigger conditions as regular triggers and each remaining match
+/// is appended to the file as one JSON line.
+///
+/// # Arguments
+/// * `shutdown_tx` - Watch channel for shutdown signals
+/// * `output_path` - Path of the JSONL file to append matches to
+/// * `active_monitors_trigger_scripts` - Trigger condition scripts of the monitors
+///
+/// # Returns
+/// Returns a function that handles writing matches, failing if they cannot be written, or an
+/// error if the file cannot be opened
+pub async fn create_match_writer_handler(
+	shutdown_tx: watch::Sender<bool>,
+	output_path: &Path,
+	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+) -> Result<
+	Arc<
+		impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<std::result::Result<(), TriggerError>>
+			+ Send
+			+ Sync,
+	>,
+> {
+	let file = tokio::fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(output_path)
+		.await?;
+	let file = Arc::new(Mutex::new(file));
+
+	Ok(Arc::new(move |block: &ProcessedBlock| {
+		let mut shutdown_rx = shutdown_tx.subscribe();
+		let trigger_scripts = active_monitors_trigger_scripts.clone();
+		let file = file.clone();
+		let block = block.clone();
+
+		tokio::spawn(async move {
+			tokio::select! {
+				result = async {
+					if block.processing_results.is_empty() {
+						return Ok(());
+					}
+					let filtered_matches = run_trigger_filters(&block.processing_results, &block.network_slug, &trigger_scripts).await;
+					let mut lines = String::new();
+					for monitor_match in &filtered_matches {
+						match serde_json::to_string(monitor_match) {
+							Ok(line) => {
+								lines.push_str(&line);
+								lines.push('\n');
+							}
+							Err(e) => {
+								TriggerError::execution_error(format!("Failed to serialize match: {}", e), None, None);
+							}
+						}
+					}
+					let mut file = file.lock().await;
+					file.write_all(lines.as_bytes()).await.map_err(|e| {
+						TriggerError::execution_error(format!("Failed to write matches: {}", e), None, None)
+					})
+				} => result,
+				_ = shutdown_rx.changed() => {
+					tracing::info!("Shutting down match writing task");
+					Ok(())
+				}
+			}
+		})
+	}))
+}
+
 /// Checks if a network has any active monitors.
 ///
 /// # Arguments
@@ -288,6 +736,20 @@ pub fn has_active_monitors(monitors: &[Monitor], network_slug: &String) -> bool
 		.any(|m| m.networks.contains(network_slug) && !m.paused)
 }
 
+/// Checks if a network has any active monitors with two-phase alerts enabled.
+///
+/// # Arguments
+/// * `monitors` - List of monitors to check
+/// * `network_slug` - Network identifier to check for
+///
+/// # Returns
+/// Returns true if there are any active two-phase monitors for the given network
+pub fn has_two_phase_monitors(monitors: &[Monitor], network_slug: &String) -> bool {
+	monitors
+		.iter()
+		.any(|m| m.networks.contains(network_slug) && !m.paused && m.two_phase_alerts)
+}
+
 /// Filters out paused monitors from the provided collection.
 ///
 /// # Arguments
@@ -344,7 +806,16 @@ async fn execute_trigger_condition(
 	}
 }
 
-async fn run_trigger_filters(
+/// Runs the trigger condition scripts of each match's monitor.
+///
+/// # Arguments
+/// * `matches` - Monitor matches to check
+/// * `_network` - Network the matches belong to
+/// * `trigger_scripts` - Trigger condition scripts of the monitors
+///
+/// # Returns
+/// Returns the matches that were not filtered out by any trigger condition
+pub async fn run_trigger_filters(
 	matches: &[MonitorMatch],
 	_network: &str,
 	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
@@ -388,6 +859,94 @@ async fn run_trigger_filters(
 	filtered_matches
 }
 
+/// Explains how a monitor is evaluated against a block or a single transaction.
+///
+/// On EVM networks the block is looked up from the transaction receipt when only a transaction
+/// hash is given. Other networks require the block number.
+///
+/// # Arguments
+/// * `client_pools` - Client pools for accessing blockchain clients
+/// * `filter_service` - Service for filtering blockchain data
+/// * `network` - Network to evaluate on
+/// * `monitor` - Monitor to explain
+/// * `block_number` - Block to evaluate
+/// * `transaction_hash` - Only explain this transaction, if set
+///
+/// # Returns
+/// Returns one evaluation trace per transaction
+pub async fn explain_monitor<P: ClientPoolTrait>(
+	client_pools: &P,
+	filter_service: &FilterService,
+	network: &Network,
+	monitor: &Monitor,
+	block_number: Option<u64>,
+	transaction_hash: Option<&str>,
+) -> Result<Vec<EvaluationTrace>> {
+	let monitors = [monitor.clone()];
+	let traces = match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pools.get_evm_client(network).await?;
+			let block_number = match (block_number, transaction_hash) {
+				(Some(block_number), _) => block_number,
+				(None, Some(transaction_hash)) => client
+					.get_transaction_receipt(transaction_hash.to_string())
+					.await?
+					.block_number
+					.map(|n| n.to::<u64>())
+					.ok_or_else(|| format!("Transaction {} is still pending", transaction_hash))?,
+				(None, None) => return Err("A block number or transaction hash is required".into()),
+			};
+			let block = fetch_block(client.as_ref(), block_number).await?;
+			filter_service
+				.explain_block(
+					client.as_ref(),
+					network,
+					&block,
+					&monitors,
+					transaction_hash,
+				)
+				.await?
+		}
+		BlockChainType::Stellar => {
+			let client = client_pools.get_stellar_client(network).await?;
+			let block_number = match (block_number, transaction_hash) {
+				(Some(block_number), _) => block_number,
+				(None, Some(transaction_hash)) => client
+					.get_transaction_ledger(transaction_hash)
+					.await?
+					.map(u64::from)
+					.ok_or_else(|| format!("Transaction {} not found", transaction_hash))?,
+				(None, None) => return Err("A block number or transaction hash is required".into()),
+			};
+			let block = fetch_block(client.as_ref(), block_number).await?;
+			filter_service
+				.explain_block(
+					client.as_ref(),
+					network,
+					&block,
+					&monitors,
+					transaction_hash,
+				)
+				.await?
+		}
+		BlockChainType::Midnight | BlockChainType::Solana => {
+			return Err(format!("Explain is not supported on network {}", network.slug).into())
+		}
+	};
+
+	Ok(traces)
+}
+
+/// Fetches a single block by number.
+async fn fetch_block<C: BlockChainClient>(client: &C, block_number: u64) -> Result<BlockType> {
+	client
+		.get_blocks(block_number, None)
+		.await?
+		.into_iter()
+		.next()
+		.ok_or_else(|| format!("Block {} not found", block_number).into())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -502,6 +1061,8 @@ mod tests {
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}))
 	}
 
@@ -516,6 +1077,8 @@ mod tests {
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}))
 	}
 
@@ -781,6 +1344,8 @@ print(result)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		let mut trigger_scripts = HashMap::new();
@@ -855,6 +1420,8 @@ print(True)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		// Test case 1: All conditions return true - match should be kept
@@ -907,6 +1474,8 @@ print(True)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		let mut trigger_scripts = HashMap::new();
@@ -958,6 +1527,8 @@ print(True)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		let mut trigger_scripts = HashMap::new();
@@ -1015,6 +1586,8 @@ print(True)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		let mut trigger_scripts = HashMap::new();
@@ -1076,6 +1649,8 @@ print(True)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		let mut trigger_scripts = HashMap::new();
@@ -1184,6 +1759,8 @@ print(result)
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}));
 
 		let mut trigger_scripts = HashMap::new();
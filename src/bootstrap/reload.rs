@@ -0,0 +1,647 @@
+//! Configuration reload support.
+//!
+//! Running block and trigger handlers read their monitors and trigger scripts through
+//! [`Reloadable`] values, so a new configuration can be swapped in without restarting the
+//! service. [`ConfigReloader`] loads the configuration storage again, validates it as a whole
+//! and only then swaps it in, keeping the last good configuration if anything fails. Reloads are
+//! requested with SIGHUP, by polling the configuration directory or database for changes, or by
+//! the management API, which waits for the outcome of its [`ReloadRequest`].
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::{Duration, SystemTime},
+};
+use tokio::sync::{mpsc, oneshot, watch, Mutex};
+
+use crate::{
+	bootstrap::{has_active_monitors, has_two_phase_monitors, initialize_services, Result},
+	models::{Monitor, Network, ScriptLanguage},
+	repositories::{
+		validate_config, validate_config_dir, ConfigReport, ConfigStorage, IssueSeverity,
+		MonitorRepositoryTrait, MonitorService, NetworkRepositoryTrait, NetworkService,
+		RepositoryError, TriggerRepositoryTrait, TriggerService,
+	},
+	services::trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+};
+
+/// Trigger scripts of the active monitors keyed by `monitor name|script path`
+type TriggerScripts = HashMap<String, (ScriptLanguage, String)>;
+
+/// Monitor, network and trigger services shared with the metrics server
+type SharedServices<M, N, T> = (
+	Arc<Mutex<MonitorService<M, N, T>>>,
+	Arc<Mutex<NetworkService<N>>>,
+	Arc<Mutex<TriggerService<T>>>,
+);
+
+/// Value shared with running handlers that can be replaced while they are running
+///
+/// Handlers read the current value for every block they process, so a replaced value applies
+/// from the next block on.
+#[derive(Clone)]
+pub struct Reloadable<T> {
+	receiver: watch::Receiver<Arc<T>>,
+}
+
+impl<T> Reloadable<T> {
+	/// Creates a reloadable value along with the sender used to replace it
+	pub fn channel(value: T) -> (watch::Sender<Arc<T>>, Self) {
+		let (sender, receiver) = watch::channel(Arc::new(value));
+		(sender, Self { receiver })
+	}
+
+	/// Returns the current value
+	pub fn get(&self) -> Arc<T> {
+		self.receiver.borrow().clone()
+	}
+}
+
+impl<T> From<T> for Reloadable<T> {
+	/// Wraps a value that is never replaced
+	fn from(value: T) -> Self {
+		Self::channel(value).1
+	}
+}
+
+/// Request to reload the configuration
+///
+/// Requests created with [`ReloadRequest::with_response`] are answered with the outcome of the
+/// reload, the others are fire and forget.
+#[derive(Debug, Default)]
+pub struct ReloadRequest {
+	responder: Option<oneshot::Sender<std::result::Result<(), String>>>,
+}
+
+impl ReloadRequest {
+	/// Creates a request along with the receiver of the reload outcome
+	pub fn with_response() -> (Self, oneshot::Receiver<std::result::Result<(), String>>) {
+		let (responder, response) = oneshot::channel();
+		(
+			Self {
+				responder: Some(responder),
+			},
+			response,
+		)
+	}
+
+	/// Sends the outcome of the reload to the requester, if it waits for one
+	pub fn respond(self, result: std::result::Result<(), String>) {
+		if let Some(responder) = self.responder {
+			// The requester may have given up waiting
+			let _ = responder.send(result);
+		}
+	}
+}
+
+/// Network that needs a block watcher for the current configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedNetwork {
+	/// Network configuration the watcher runs with
+	pub network: Network,
+	/// Whether any active monitor of the network has two-phase alerts enabled
+	pub two_phase: bool,
+}
+
+/// Block watcher changes needed to apply a reloaded configuration
+///
+/// A network whose configuration changed appears in both lists, as its watcher has to be
+/// restarted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkChanges {
+	/// Slugs of the networks whose watcher must be stopped
+	pub stopped: Vec<String>,
+	/// Networks whose watcher must be started, after the stopped ones were stopped
+	pub started: Vec<WatchedNetwork>,
+}
+
+impl NetworkChanges {
+	/// Returns true if no watcher has to be started or stopped
+	pub fn is_empty(&self) -> bool {
+		self.stopped.is_empty() && self.started.is_empty()
+	}
+}
+
+/// Reloads the configuration of a running service
+///
+/// Holds the senders of the [`Reloadable`] values given to the handlers, the services shared
+/// with the metrics server and the networks currently watched.
+pub struct ConfigReloader<M, N, T>
+where
+	M: MonitorRepositoryTrait<N, T>,
+	N: NetworkRepositoryTrait,
+	T: TriggerRepositoryTrait,
+{
+	storage: ConfigStorage,
+	trigger_execution_service: Arc<TriggerExecutionService<T>>,
+	monitor_service: Arc<Mutex<MonitorService<M, N, T>>>,
+	network_service: Arc<Mutex<NetworkService<N>>>,
+	trigger_service: Arc<Mutex<TriggerService<T>>>,
+	monitors: watch::Sender<Arc<Vec<Monitor>>>,
+	trigger_scripts: watch::Sender<Arc<TriggerScripts>>,
+	watched_networks: BTreeMap<String, WatchedNetwork>,
+}
+
+impl<M, N, T> ConfigReloader<M, N, T>
+where
+	M: MonitorRepositoryTrait<N, T>,
+	N: NetworkRepositoryTrait,
+	T: TriggerRepositoryTrait + Send + Sync,
+{
+	/// Creates a reloader for the configuration the service started with
+	///
+	/// # Arguments
+	/// * `storage` - Storage the configuration is loaded from
+	/// * `trigger_execution_service` - Service whose triggers are replaced on reload
+	/// * `services` - Monitor, network and trigger services shared with the metrics server
+	/// * `active_monitors` - Active monitors the service started with
+	/// * `networks` - Networks the service started with, keyed by slug
+	/// * `trigger_scripts` - Trigger scripts loaded at startup
+	pub fn new(
+		storage: ConfigStorage,
+		trigger_execution_service: Arc<TriggerExecutionService<T>>,
+		services: SharedServices<M, N, T>,
+		active_monitors: Vec<Monitor>,
+		networks: &HashMap<String, Network>,
+		trigger_scripts: TriggerScripts,
+	) -> Self {
+		let (monitor_service, network_service, trigger_service) = services;
+		let watched_networks = watched_networks(&active_monitors, networks);
+		let (monitors, _) = Reloadable::channel(active_monitors);
+		let (trigger_scripts, _) = Reloadable::channel(trigger_scripts);
+
+		Self {
+			storage,
+			trigger_execution_service,
+			monitor_service,
+			network_service,
+			trigger_service,
+			monitors,
+			trigger_scripts,
+			watched_networks,
+		}
+	}
+
+	/// Returns the active monitors, updated on every successful reload
+	pub fn monitors(&self) -> Reloadable<Vec<Monitor>> {
+		Reloadable {
+			receiver: self.monitors.subscribe(),
+		}
+	}
+
+	/// Returns the trigger scripts, updated on every successful reload
+	pub fn trigger_scripts(&self) -> Reloadable<TriggerScripts> {
+		Reloadable {
+			receiver: self.trigger_scripts.subscribe(),
+		}
+	}
+
+	/// Returns the networks that need a block watcher, sorted by slug
+	pub fn watched_networks(&self) -> Vec<WatchedNetwork> {
+		self.watched_networks.values().cloned().collect()
+	}
+
+	/// Reloads the configuration storage and swaps the new configuration in
+	///
+	/// The whole configuration is validated, loaded and its trigger scripts read before anything
+	/// is replaced, so on error the running configuration stays untouched. A config directory is
+	/// validated before it is loaded, the database is validated once loaded, as its
+	/// configurations are checked when they are read.
+	///
+	/// # Returns
+	/// * `Result<NetworkChanges>` - Block watchers to stop and start for the new configuration
+	///
+	/// # Errors
+	/// Returns an error if the configuration is invalid or cannot be loaded
+	pub async fn reload(&mut self) -> Result<NetworkChanges> {
+		if let ConfigStorage::Files(config_dir) = &self.storage {
+			check_report(validate_config_dir(config_dir))?;
+		}
+		let (monitor_service, network_service, trigger_service) =
+			self.storage.load_services::<M, N, T>()?;
+		if let ConfigStorage::Sqlite(_) = &self.storage {
+			check_report(validate_config(
+				&monitor_service.get_all(),
+				&trigger_service.get_all(),
+				&network_service.get_all(),
+			))?;
+		}
+
+		let (_, trigger_execution_service, active_monitors, networks, _, _, _) =
+			initialize_services(
+				Some(monitor_service.clone()),
+				Some(network_service.clone()),
+				Some(trigger_service.clone()),
+			)?;
+		let trigger_scripts = trigger_execution_service
+			.load_scripts(&active_monitors)
+			.await?;
+
+		// Everything loaded, swap the new configuration in
+		self.trigger_execution_service
+			.replace_triggers(trigger_service.clone())
+			.await;
+		*self.monitor_service.lock().await = monitor_service;
+		*self.network_service.lock().await = network_service;
+		*self.trigger_service.lock().await = trigger_service;
+
+		let watched_networks = watched_networks(&active_monitors, &networks);
+		let changes = network_changes(&self.watched_networks, &watched_networks);
+		self.watched_networks = watched_networks;
+
+		self.monitors.send_replace(Arc::new(active_monitors));
+		self.trigger_scripts.send_replace(Arc::new(trigger_scripts));
+
+		Ok(changes)
+	}
+}
+
+/// Fails with the errors of a validation report, if it has any
+fn check_report(report: ConfigReport) -> Result<()> {
+	if !report.has_errors() {
+		return Ok(());
+	}
+
+	let errors = report
+		.issues
+		.iter()
+		.filter(|issue| issue.severity == IssueSeverity::Error)
+		.map(|issue| format!("[{}] {}", issue.kind, issue.message))
+		.collect::<Vec<_>>();
+	Err(RepositoryError::validation_error(
+		format!("Configuration validation failed:\n{}", errors.join("\n")),
+		None,
+		None,
+	)
+	.into())
+}
+
+/// Determines the networks that need a block watcher
+///
+/// # Arguments
+/// * `monitors` - Active monitors
+/// * `networks` - Available networks keyed by slug
+///
+/// # Returns
+/// * `BTreeMap<String, WatchedNetwork>` - Networks with active monitors keyed by slug
+fn watched_networks(
+	monitors: &[Monitor],
+	networks: &HashMap<String, Network>,
+) -> BTreeMap<String, WatchedNetwork> {
+	networks
+		.values()
+		.filter(|network| has_active_monitors(monitors, &network.slug))
+		.map(|network| {
+			(
+				network.slug.clone(),
+				WatchedNetwork {
+					network: network.clone(),
+					two_phase: has_two_phase_monitors(monitors, &network.slug),
+				},
+			)
+		})
+		.collect()
+}
+
+/// Compares the watched networks before and after a reload
+fn network_changes(
+	previous: &BTreeMap<String, WatchedNetwork>,
+	current: &BTreeMap<String, WatchedNetwork>,
+) -> NetworkChanges {
+	NetworkChanges {
+		stopped: previous
+			.iter()
+			.filter(|(slug, watched)| current.get(*slug) != Some(*watched))
+			.map(|(slug, _)| slug.clone())
+			.collect(),
+		started: current
+			.iter()
+			.filter(|(slug, watched)| previous.get(*slug) != Some(*watched))
+			.map(|(_, watched)| watched.clone())
+			.collect(),
+	}
+}
+
+/// Requests a reload every time the process receives SIGHUP
+///
+/// Does nothing on platforms without SIGHUP.
+///
+/// # Arguments
+/// * `reload_tx` - Channel reload requests are sent to
+pub async fn listen_for_reload_signal(reload_tx: mpsc::Sender<ReloadRequest>) {
+	#[cfg(unix)]
+	{
+		use tokio::signal::unix::{signal, SignalKind};
+
+		let mut hangup = match signal(SignalKind::hangup()) {
+			Ok(hangup) => hangup,
+			Err(e) => {
+				tracing::error!("Failed to listen for SIGHUP: {}", e);
+				return;
+			}
+		};
+		while hangup.recv().await.is_some() {
+			tracing::info!("SIGHUP received, reloading configuration");
+			// A pending request already covers this one
+			let _ = reload_tx.try_send(ReloadRequest::default());
+		}
+	}
+	#[cfg(not(unix))]
+	{
+		let _ = reload_tx;
+	}
+}
+
+/// Requests a reload whenever a file in the configuration directory changes
+///
+/// The directory is polled, comparing the path, size and modification time of every file in it,
+/// including trigger scripts. The path can also be a single file, e.g. the configuration
+/// database.
+///
+/// # Arguments
+/// * `config_dir` - Directory or file to watch
+/// * `interval` - Time between two polls
+/// * `reload_tx` - Channel reload requests are sent to
+pub async fn watch_config_dir(
+	config_dir: PathBuf,
+	interval: Duration,
+	reload_tx: mpsc::Sender<ReloadRequest>,
+) {
+	let mut fingerprint = config_fingerprint(&config_dir);
+	let mut ticker = tokio::time::interval(interval);
+	ticker.tick().await;
+
+	loop {
+		ticker.tick().await;
+		let current = config_fingerprint(&config_dir);
+		if current != fingerprint {
+			tracing::info!(
+				"Configuration directory {} changed, reloading configuration",
+				config_dir.display()
+			);
+			fingerprint = current;
+			if reload_tx.try_send(ReloadRequest::default()).is_err() && reload_tx.is_closed() {
+				return;
+			}
+		}
+	}
+}
+
+/// Lists the path, size and modification time of every file below a directory
+///
+/// A path to a file lists only that file.
+fn config_fingerprint(dir: &Path) -> Vec<(PathBuf, u64, Option<SystemTime>)> {
+	if let Ok(metadata) = std::fs::metadata(dir) {
+		if metadata.is_file() {
+			return vec![(dir.to_path_buf(), metadata.len(), metadata.modified().ok())];
+		}
+	}
+
+	let mut files = Vec::new();
+	let mut pending = vec![dir.to_path_buf()];
+
+	while let Some(dir) = pending.pop() {
+		let Ok(entries) = std::fs::read_dir(&dir) else {
+			continue;
+		};
+		for entry in entries.flatten() {
+			let Ok(metadata) = entry.metadata() else {
+				continue;
+			};
+			if metadata.is_dir() {
+				pending.push(entry.path());
+			} else {
+				files.push((entry.path(), metadata.len(), metadata.modified().ok()));
+			}
+		}
+	}
+
+	files.sort();
+	files
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::BlockChainType,
+		repositories::{
+			import_config_dir, MonitorRepository, NetworkRepository, SqliteMonitorRepository,
+			SqliteNetworkRepository, SqliteTriggerRepository, TriggerRepository,
+		},
+		services::notification::NotificationService,
+	};
+	use std::fs;
+	use tempfile::TempDir;
+
+	type TestReloader = ConfigReloader<
+		MonitorRepository<NetworkRepository, TriggerRepository>,
+		NetworkRepository,
+		TriggerRepository,
+	>;
+
+	fn write_network(dir: &Path, slug: &str, cron_schedule: &str) {
+		fs::write(
+			dir.join(format!("networks/{}.json", slug)),
+			format!(
+				r#"{{
+					"network_type": "EVM",
+					"slug": "{slug}",
+					"name": "{slug}",
+					"rpc_urls": [{{"type_": "rpc", "url": "http://localhost:8545", "weight": 100}}],
+					"chain_id": 1,
+					"block_time_ms": 12000,
+					"confirmation_blocks": 1,
+					"cron_schedule": "{cron_schedule}",
+					"max_past_blocks": 10,
+					"store_blocks": false
+				}}"#
+			),
+		)
+		.unwrap();
+	}
+
+	fn write_monitor(dir: &Path, name: &str, networks: &[&str]) {
+		fs::write(
+			dir.join(format!("monitors/{}.json", name)),
+			format!(
+				r#"{{
+					"name": "{name}",
+					"networks": {},
+					"paused": false,
+					"addresses": [{{"address": "0x0000000000000000000000000000000000000000"}}],
+					"match_conditions": {{"functions": [], "events": [], "transactions": []}},
+					"trigger_conditions": [],
+					"triggers": []
+				}}"#,
+				serde_json::to_string(networks).unwrap()
+			),
+		)
+		.unwrap();
+	}
+
+	fn create_config_dir() -> TempDir {
+		let temp_dir = TempDir::new().unwrap();
+		for dir in ["networks", "monitors", "triggers"] {
+			fs::create_dir(temp_dir.path().join(dir)).unwrap();
+		}
+		write_network(temp_dir.path(), "ethereum_mainnet", "0 */1 * * * *");
+		write_network(temp_dir.path(), "sepolia", "0 */1 * * * *");
+		write_monitor(temp_dir.path(), "monitor", &["ethereum_mainnet"]);
+		temp_dir
+	}
+
+	fn create_reloader(config_dir: &Path) -> TestReloader {
+		create_storage_reloader(ConfigStorage::Files(config_dir.to_path_buf()))
+	}
+
+	fn create_storage_reloader<M, N, T>(storage: ConfigStorage) -> ConfigReloader<M, N, T>
+	where
+		M: MonitorRepositoryTrait<N, T>,
+		N: NetworkRepositoryTrait,
+		T: TriggerRepositoryTrait + Send + Sync,
+	{
+		let (monitor_service, network_service, trigger_service) = storage.load_services().unwrap();
+		let active_monitors = monitor_service.get_all().into_values().collect();
+		let networks = network_service.get_all();
+
+		ConfigReloader::new(
+			storage,
+			Arc::new(TriggerExecutionService::new(
+				trigger_service.clone(),
+				NotificationService::new(),
+			)),
+			(
+				Arc::new(Mutex::new(monitor_service)),
+				Arc::new(Mutex::new(network_service)),
+				Arc::new(Mutex::new(trigger_service)),
+			),
+			active_monitors,
+			&networks,
+			HashMap::new(),
+		)
+	}
+
+	fn slugs(watched: &[WatchedNetwork]) -> Vec<&str> {
+		watched.iter().map(|w| w.network.slug.as_str()).collect()
+	}
+
+	#[test]
+	fn test_reloadable_returns_replaced_value() {
+		let (sender, reloadable) = Reloadable::channel(vec![1]);
+		assert_eq!(*reloadable.get(), vec![1]);
+
+		sender.send_replace(Arc::new(vec![2]));
+		assert_eq!(*reloadable.get(), vec![2]);
+
+		// A value that is never replaced stays readable
+		let fixed: Reloadable<Vec<i32>> = vec![3].into();
+		assert_eq!(*fixed.get(), vec![3]);
+	}
+
+	#[tokio::test]
+	async fn test_reload_applies_new_configuration() {
+		let config_dir = create_config_dir();
+		let mut reloader = create_reloader(config_dir.path());
+		let monitors = reloader.monitors();
+		assert_eq!(
+			slugs(&reloader.watched_networks()),
+			vec!["ethereum_mainnet"]
+		);
+
+		// Move the monitor to sepolia and add a second one on both networks
+		write_monitor(config_dir.path(), "monitor", &["sepolia"]);
+		write_monitor(config_dir.path(), "other", &["ethereum_mainnet", "sepolia"]);
+		let changes = reloader.reload().await.unwrap();
+		assert!(changes.stopped.is_empty());
+		assert_eq!(slugs(&changes.started), vec!["sepolia"]);
+		assert_eq!(monitors.get().len(), 2);
+
+		// Reloading an unchanged configuration leaves the watchers alone
+		assert!(reloader.reload().await.unwrap().is_empty());
+
+		// Changing a watched network restarts its watcher
+		write_network(config_dir.path(), "sepolia", "*/10 * * * * *");
+		fs::remove_file(config_dir.path().join("monitors/other.json")).unwrap();
+		let changes = reloader.reload().await.unwrap();
+		assert_eq!(changes.stopped, vec!["ethereum_mainnet", "sepolia"]);
+		assert_eq!(slugs(&changes.started), vec!["sepolia"]);
+		assert_eq!(changes.started[0].network.cron_schedule, "*/10 * * * * *");
+		assert_eq!(monitors.get().len(), 1);
+		assert_eq!(
+			reloader.network_service.lock().await.get_all()["sepolia"].network_type,
+			BlockChainType::EVM
+		);
+	}
+
+	#[tokio::test]
+	async fn test_reload_keeps_last_good_configuration() {
+		let config_dir = create_config_dir();
+		let mut reloader = create_reloader(config_dir.path());
+		let monitors = reloader.monitors();
+
+		write_monitor(config_dir.path(), "monitor", &["unknown_network"]);
+		let result = reloader.reload().await;
+
+		assert!(result
+			.unwrap_err()
+			.to_string()
+			.contains("Configuration validation failed"));
+		assert_eq!(monitors.get()[0].networks, vec!["ethereum_mainnet"]);
+		assert_eq!(
+			slugs(&reloader.watched_networks()),
+			vec!["ethereum_mainnet"]
+		);
+		assert!(reloader
+			.monitor_service
+			.lock()
+			.await
+			.get("monitor")
+			.is_some_and(|m| m.networks == vec!["ethereum_mainnet"]));
+	}
+
+	#[tokio::test]
+	async fn test_reload_from_database() {
+		let config_dir = create_config_dir();
+		let database = config_dir.path().join("config.db");
+		import_config_dir(Some(&database), config_dir.path()).unwrap();
+		let mut reloader = create_storage_reloader::<
+			SqliteMonitorRepository<SqliteNetworkRepository, SqliteTriggerRepository>,
+			SqliteNetworkRepository,
+			SqliteTriggerRepository,
+		>(ConfigStorage::Sqlite(database.clone()));
+		assert_eq!(
+			slugs(&reloader.watched_networks()),
+			vec!["ethereum_mainnet"]
+		);
+
+		// Changes to the config directory are ignored, the database is reloaded
+		write_monitor(config_dir.path(), "monitor", &["sepolia"]);
+		assert!(reloader.reload().await.unwrap().is_empty());
+
+		import_config_dir(Some(&database), config_dir.path()).unwrap();
+		let changes = reloader.reload().await.unwrap();
+		assert_eq!(changes.stopped, vec!["ethereum_mainnet"]);
+		assert_eq!(slugs(&changes.started), vec!["sepolia"]);
+	}
+
+	#[test]
+	fn test_config_fingerprint_detects_changes() {
+		let config_dir = create_config_dir();
+		let fingerprint = config_fingerprint(config_dir.path());
+		assert_eq!(fingerprint.len(), 3);
+		assert_eq!(fingerprint, config_fingerprint(config_dir.path()));
+
+		fs::write(config_dir.path().join("triggers/slack.json"), "{}").unwrap();
+		assert_ne!(fingerprint, config_fingerprint(config_dir.path()));
+
+		// A single file, like the configuration database, is watched on its own
+		let database = config_dir.path().join("config.db");
+		fs::write(&database, "").unwrap();
+		let fingerprint = config_fingerprint(&database);
+		assert_eq!(fingerprint.len(), 1);
+		fs::write(&database, "changed").unwrap();
+		assert_ne!(fingerprint, config_fingerprint(&database));
+	}
+}
@@ -0,0 +1,335 @@
+//! `backfill` subcommand.
+//!
+//! Runs monitors over a historical block range of a network without touching the live
+//! checkpoint.
+
+use clap::ArgMatches;
+use futures::future::BoxFuture;
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::watch;
+use tracing::info;
+
+use crate::{
+	bootstrap::{
+		create_block_handler, create_match_writer_handler, create_trigger_handler,
+		initialize_services, Result,
+	},
+	models::{BlockChainType, BlockType, Monitor, Network, ProcessedBlock},
+	repositories::{MonitorRepository, NetworkRepository, TriggerRepository},
+	services::{
+		blockchain::{ClientPool, ClientPoolTrait},
+		blockwatcher::{run_backfill, BackfillOptions, BackfillSummary},
+		trigger::{TriggerError, TriggerExecutionServiceTrait},
+	},
+};
+
+/// Runs monitors over a historical block range for the `backfill` subcommand.
+///
+/// Matches are either sent through the monitors' triggers or written to a JSONL file. Progress
+/// is saved after every chunk, so running the same command again after an interruption resumes
+/// the backfill.
+///
+/// # Errors
+/// Returns an error if the network or a monitor cannot be found, or if the backfill fails.
+pub async fn backfill(matches: &ArgMatches) -> Result<()> {
+	let network_slug = matches
+		.get_one::<String>("network")
+		.expect("network is required");
+	let options = backfill_options(matches);
+
+	let (
+		filter_service,
+		trigger_execution_service,
+		active_monitors,
+		networks,
+		monitor_service,
+		_,
+		_,
+	) = initialize_services::<
+		MonitorRepository<NetworkRepository, TriggerRepository>,
+		NetworkRepository,
+		TriggerRepository,
+	>(None, None, None)
+	.map_err(|e| anyhow::anyhow!("Failed to initialize services: {}", e))?;
+
+	let network = networks
+		.get(network_slug)
+		.cloned()
+		.ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network_slug))?;
+
+	let monitor_names: Vec<String> = matches
+		.get_many::<String>("monitor")
+		.map(|names| names.cloned().collect())
+		.unwrap_or_default();
+	let all_monitors = monitor_service.lock().await.get_all();
+	let monitors = select_monitors(network_slug, &monitor_names, active_monitors, &all_monitors)?;
+
+	if monitors.is_empty() {
+		info!("No monitors to backfill for network {}", network_slug);
+		return Ok(());
+	}
+
+	info!(
+		"Backfilling blocks {} to {} on {} for {} monitor(s)",
+		options.from_block,
+		options.to_block,
+		network_slug,
+		monitors.len()
+	);
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let trigger_scripts = trigger_execution_service.load_scripts(&monitors).await?;
+	let client_pool = Arc::new(ClientPool::new());
+	let block_handler = create_block_handler(
+		shutdown_tx.clone(),
+		filter_service,
+		monitors,
+		client_pool.clone(),
+	);
+
+	let run = async {
+		match matches.get_one::<String>("output") {
+			Some(path) => {
+				let match_writer = create_match_writer_handler(
+					shutdown_tx.clone(),
+					&PathBuf::from(path),
+					trigger_scripts,
+				)
+				.await?;
+				backfill_network(
+					&network,
+					&client_pool,
+					block_handler,
+					match_writer,
+					&options,
+				)
+				.await
+			}
+			None => {
+				let trigger_handler = create_trigger_handler(
+					shutdown_tx.clone(),
+					trigger_execution_service,
+					trigger_scripts,
+				);
+				backfill_network(
+					&network,
+					&client_pool,
+					block_handler,
+					trigger_handler,
+					&options,
+				)
+				.await
+			}
+		}
+	};
+
+	tokio::select! {
+		result = run => {
+			let summary = result?;
+			info!(
+				"Backfill complete: processed {} blocks from block {} with {} matches",
+				summary.blocks_processed, summary.start_block, summary.matches
+			);
+		}
+		_ = tokio::signal::ctrl_c() => {
+			let _ = shutdown_tx.send(true);
+			info!("Backfill interrupted, run the same command again to resume");
+		}
+	}
+
+	Ok(())
+}
+
+/// Builds the backfill options from the arguments of the `backfill` subcommand
+fn backfill_options(matches: &ArgMatches) -> BackfillOptions {
+	let defaults = BackfillOptions::default();
+	BackfillOptions {
+		from_block: *matches.get_one::<u64>("from").expect("from is required"),
+		to_block: *matches.get_one::<u64>("to").expect("to is required"),
+		chunk_size: matches
+			.get_one::<u64>("chunk-size")
+			.copied()
+			.unwrap_or(defaults.chunk_size),
+		chunk_delay: matches
+			.get_one::<u64>("delay-ms")
+			.map(|ms| Duration::from_millis(*ms))
+			.unwrap_or(defaults.chunk_delay),
+		..defaults
+	}
+}
+
+/// Selects the monitors to backfill on a network
+///
+/// Without monitor names, every active monitor of the network is selected. Named monitors may
+/// be paused, e.g. while they are being backfilled before going live.
+///
+/// # Arguments
+/// * `network_slug` - Slug of the network to backfill
+/// * `monitor_names` - Names of the monitors requested on the command line
+/// * `active_monitors` - Active monitors of every network
+/// * `all_monitors` - Every configured monitor, including paused ones
+///
+/// # Errors
+/// Returns an error if a named monitor does not exist or is not configured for the network
+fn select_monitors(
+	network_slug: &str,
+	monitor_names: &[String],
+	active_monitors: Vec<Monitor>,
+	all_monitors: &HashMap<String, Monitor>,
+) -> Result<Vec<Monitor>> {
+	if monitor_names.is_empty() {
+		return Ok(active_monitors
+			.into_iter()
+			.filter(|m| m.networks.iter().any(|n| n == network_slug))
+			.collect());
+	}
+
+	monitor_names
+		.iter()
+		.map(|name| {
+			let monitor = all_monitors
+				.values()
+				.find(|m| &m.name == name)
+				.ok_or_else(|| anyhow::anyhow!("Monitor '{}' not found", name))?;
+			if !monitor.networks.iter().any(|n| n == network_slug) {
+				return Err(anyhow::anyhow!(
+					"Monitor '{}' is not configured for network '{}'",
+					name,
+					network_slug
+				)
+				.into());
+			}
+			Ok(monitor.clone())
+		})
+		.collect()
+}
+
+/// Runs a backfill with the client matching the network type.
+async fn backfill_network<H, T>(
+	network: &Network,
+	client_pool: &ClientPool,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+	options: &BackfillOptions,
+) -> Result<BackfillSummary>
+where
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<std::result::Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	let summary = match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pool.get_evm_client(network).await?;
+			run_backfill(
+				network,
+				client.as_ref(),
+				block_handler,
+				trigger_handler,
+				options,
+			)
+			.await?
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(network).await?;
+			run_backfill(
+				network,
+				client.as_ref(),
+				block_handler,
+				trigger_handler,
+				options,
+			)
+			.await?
+		}
+		BlockChainType::Midnight => unimplemented!("Midnight not implemented"),
+		BlockChainType::Solana => unimplemented!("Solana not implemented"),
+	};
+	Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::build_cli;
+
+	fn create_monitor(name: &str, network: &str, paused: bool) -> Monitor {
+		Monitor {
+			name: name.to_string(),
+			networks: vec![network.to_string()],
+			paused,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_backfill_options() {
+		let matches = build_cli()
+			.try_get_matches_from([
+				"openzeppelin-monitor",
+				"backfill",
+				"--network",
+				"ethereum_mainnet",
+				"--from",
+				"10",
+				"--to",
+				"20",
+				"--delay-ms",
+				"0",
+			])
+			.unwrap();
+		let options = backfill_options(matches.subcommand_matches("backfill").unwrap());
+
+		assert_eq!(options.from_block, 10);
+		assert_eq!(options.to_block, 20);
+		assert_eq!(options.chunk_size, BackfillOptions::default().chunk_size);
+		assert_eq!(options.chunk_delay, Duration::ZERO);
+	}
+
+	#[test]
+	fn test_select_monitors() {
+		let active = vec![
+			create_monitor("transfers", "ethereum_mainnet", false),
+			create_monitor("payments", "stellar_mainnet", false),
+		];
+		let all = HashMap::from([
+			(
+				"transfers".to_string(),
+				create_monitor("transfers", "ethereum_mainnet", false),
+			),
+			(
+				"paused".to_string(),
+				create_monitor("paused", "ethereum_mainnet", true),
+			),
+			(
+				"payments".to_string(),
+				create_monitor("payments", "stellar_mainnet", false),
+			),
+		]);
+
+		let selected = select_monitors("ethereum_mainnet", &[], active.clone(), &all).unwrap();
+		assert_eq!(selected.len(), 1);
+		assert_eq!(selected[0].name, "transfers");
+
+		// Paused monitors can be backfilled by name
+		let selected = select_monitors(
+			"ethereum_mainnet",
+			&["paused".to_string()],
+			active.clone(),
+			&all,
+		)
+		.unwrap();
+		assert_eq!(selected[0].name, "paused");
+
+		assert!(select_monitors(
+			"ethereum_mainnet",
+			&["missing".to_string()],
+			active.clone(),
+			&all
+		)
+		.is_err());
+		assert!(
+			select_monitors("ethereum_mainnet", &["payments".to_string()], active, &all).is_err()
+		);
+	}
+}
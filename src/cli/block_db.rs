@@ -0,0 +1,98 @@
+//! `block-db` subcommand.
+//!
+//! Imports the files of the file-based block storage into the SQLite block storage.
+
+use clap::ArgMatches;
+use std::{io::Write, path::Path};
+
+use crate::{
+	bootstrap::Result,
+	services::blockwatcher::{BlockImportSummary, SqliteBlockStorage},
+};
+
+/// Imports the block storage files for the `block-db import` subcommand.
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or a file cannot be imported.
+pub async fn block_db(matches: &ArgMatches) -> Result<()> {
+	let (_, import_matches) = matches
+		.subcommand()
+		.expect("block-db requires a subcommand");
+	let database = import_matches
+		.get_one::<String>("database")
+		.expect("database has a default value");
+	let data_dir = import_matches
+		.get_one::<String>("data-dir")
+		.expect("data-dir has a default value");
+
+	let storage = SqliteBlockStorage::new(Some(Path::new(database)))?;
+	let summary = storage.import_files(Path::new(data_dir)).await?;
+	write_summary(&mut std::io::stdout().lock(), &summary)
+}
+
+/// Writes the number of imported records
+fn write_summary<W: Write>(out: &mut W, summary: &BlockImportSummary) -> Result<()> {
+	writeln!(
+		out,
+		"Imported {} checkpoint(s), {} block(s), {} missed block(s)",
+		summary.checkpoints, summary.blocks, summary.missed_blocks
+	)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::build_cli, services::blockwatcher::BlockStorage};
+	use tempfile::TempDir;
+
+	#[tokio::test]
+	async fn test_block_db_import() {
+		let temp_dir = TempDir::new().unwrap();
+		std::fs::write(temp_dir.path().join("ethereum_mainnet_last_block.txt"), "7").unwrap();
+		let database = temp_dir.path().join("blocks.db");
+
+		let matches = build_cli()
+			.try_get_matches_from([
+				"openzeppelin-monitor",
+				"block-db",
+				"import",
+				"--data-dir",
+				temp_dir.path().to_str().unwrap(),
+				"--database",
+				database.to_str().unwrap(),
+			])
+			.unwrap();
+		block_db(matches.subcommand_matches("block-db").unwrap())
+			.await
+			.unwrap();
+
+		let storage = SqliteBlockStorage::new(Some(&database)).unwrap();
+		assert_eq!(
+			storage
+				.get_last_processed_block("ethereum_mainnet")
+				.await
+				.unwrap(),
+			Some(7)
+		);
+	}
+
+	#[test]
+	fn test_write_summary() {
+		let mut out = Vec::new();
+		write_summary(
+			&mut out,
+			&BlockImportSummary {
+				checkpoints: 1,
+				blocks: 2,
+				missed_blocks: 3,
+			},
+		)
+		.unwrap();
+
+		assert_eq!(
+			String::from_utf8(out).unwrap(),
+			"Imported 1 checkpoint(s), 2 block(s), 3 missed block(s)\n"
+		);
+	}
+}
@@ -0,0 +1,70 @@
+//! `config-db` subcommand.
+//!
+//! Imports a JSON configuration directory into the SQLite configuration database, or exports
+//! the database back to the directory layout.
+
+use clap::ArgMatches;
+use std::{io::Write, path::Path};
+
+use crate::{
+	bootstrap::Result,
+	repositories::{export_config_dir, import_config_dir, ConfigCounts},
+};
+
+/// Imports or exports the configuration database for the `config-db` subcommand.
+///
+/// # Errors
+/// Returns an error if the configuration cannot be loaded, validated or written.
+pub fn config_db(matches: &ArgMatches) -> Result<()> {
+	let (action, action_matches) = matches
+		.subcommand()
+		.expect("config-db requires a subcommand");
+	let database = action_matches
+		.get_one::<String>("database")
+		.expect("database has a default value");
+	let config_dir = action_matches
+		.get_one::<String>("config-dir")
+		.expect("config-dir has a default value");
+
+	let counts = match action {
+		"import" => import_config_dir(Some(Path::new(database)), Path::new(config_dir))?,
+		_ => export_config_dir(Some(Path::new(database)), Path::new(config_dir))?,
+	};
+	write_summary(&mut std::io::stdout().lock(), action, &counts)
+}
+
+/// Writes the number of imported or exported configurations
+fn write_summary<W: Write>(out: &mut W, action: &str, counts: &ConfigCounts) -> Result<()> {
+	let verb = if action == "import" {
+		"Imported"
+	} else {
+		"Exported"
+	};
+	writeln!(
+		out,
+		"{} {} network(s), {} monitor(s), {} trigger(s)",
+		verb, counts.networks, counts.monitors, counts.triggers
+	)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_write_summary() {
+		let counts = ConfigCounts {
+			networks: 1,
+			monitors: 2,
+			triggers: 3,
+		};
+		let mut out = Vec::new();
+		write_summary(&mut out, "export", &counts).unwrap();
+
+		assert_eq!(
+			String::from_utf8(out).unwrap(),
+			"Exported 1 network(s), 2 monitor(s), 3 trigger(s)\n"
+		);
+	}
+}
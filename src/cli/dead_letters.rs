@@ -0,0 +1,158 @@
+//! `dead-letters` subcommand.
+//!
+//! Lists the trigger deliveries in the dead-letter queue or moves them back to the outbox.
+
+use clap::ArgMatches;
+use std::io::Write;
+
+use crate::{
+	bootstrap::Result,
+	services::trigger::{FileMatchOutbox, MatchOutbox},
+};
+
+/// Lists or replays dead-lettered deliveries for the `dead-letters` subcommand.
+///
+/// Replayed deliveries are moved back to the outbox with a fresh retry budget and are delivered
+/// by the running service.
+///
+/// # Errors
+/// Returns an error if the outbox cannot be read or updated, or if the key to replay is unknown.
+pub async fn dead_letters(matches: &ArgMatches) -> Result<()> {
+	run_dead_letters(
+		&FileMatchOutbox::default(),
+		matches,
+		&mut std::io::stdout().lock(),
+	)
+	.await
+}
+
+/// Runs the `dead-letters` subcommand against an outbox
+///
+/// # Arguments
+/// * `outbox` - Outbox holding the dead letters
+/// * `matches` - Arguments of the `dead-letters` subcommand
+/// * `out` - Writer the output is written to
+async fn run_dead_letters<O: MatchOutbox, W: Write>(
+	outbox: &O,
+	matches: &ArgMatches,
+	out: &mut W,
+) -> Result<()> {
+	if let Some(key) = matches.get_one::<String>("replay") {
+		if !outbox.replay_dead_letter(key).await? {
+			return Err(anyhow::anyhow!("Dead letter '{}' not found", key).into());
+		}
+		writeln!(out, "Replayed dead letter {}", key)?;
+		return Ok(());
+	}
+
+	let entries = outbox.dead_letters().await?;
+	if matches.get_flag("replay-all") {
+		for entry in &entries {
+			outbox.replay_dead_letter(&entry.key).await?;
+		}
+		writeln!(out, "Replayed {} dead letter(s)", entries.len())?;
+		return Ok(());
+	}
+
+	match matches.get_one::<String>("format").map(String::as_str) {
+		Some("json") => writeln!(out, "{}", serde_json::to_string_pretty(&entries)?)?,
+		_ => {
+			for entry in &entries {
+				writeln!(
+					out,
+					"{}  trigger={} network={} block={} attempts={} error={}",
+					entry.key,
+					entry.trigger,
+					entry.network_slug,
+					entry.block_number,
+					entry.attempts,
+					entry.last_error.as_deref().unwrap_or("-")
+				)?;
+			}
+			writeln!(out, "{} dead letter(s)", entries.len())?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		cli::build_cli,
+		models::{
+			MatchConditions, Monitor, MonitorMatch, StellarBlock, StellarMonitorMatch,
+			StellarTransaction, StellarTransactionInfo,
+		},
+		services::trigger::OutboxEntry,
+	};
+	use tempfile::TempDir;
+
+	fn create_entry(hash: &str) -> OutboxEntry {
+		let monitor_match = MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+			monitor: Monitor {
+				name: "transfers".to_string(),
+				..Default::default()
+			},
+			transaction: StellarTransaction::from(StellarTransactionInfo {
+				transaction_hash: hash.to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}));
+		let mut entry = OutboxEntry::new("stellar_mainnet", 7, monitor_match, "slack");
+		entry.attempts = 3;
+		entry.last_error = Some("timeout".to_string());
+		entry
+	}
+
+	fn subcommand_matches(args: &[&str]) -> ArgMatches {
+		let matches = build_cli()
+			.try_get_matches_from(
+				["openzeppelin-monitor", "dead-letters"]
+					.iter()
+					.chain(args.iter()),
+			)
+			.unwrap();
+		matches.subcommand_matches("dead-letters").unwrap().clone()
+	}
+
+	async fn run(outbox: &FileMatchOutbox, args: &[&str]) -> Result<String> {
+		let mut out = Vec::new();
+		run_dead_letters(outbox, &subcommand_matches(args), &mut out).await?;
+		Ok(String::from_utf8(out).unwrap())
+	}
+
+	#[tokio::test]
+	async fn test_dead_letters_list_and_replay() {
+		let temp_dir = TempDir::new().unwrap();
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let first = create_entry("tx1");
+		outbox.dead_letter(&first).await.unwrap();
+		outbox.dead_letter(&create_entry("tx2")).await.unwrap();
+
+		let listing = run(&outbox, &[]).await.unwrap();
+		assert!(listing.contains(&format!(
+			"{}  trigger=slack network=stellar_mainnet block=7 attempts=3 error=timeout",
+			first.key
+		)));
+		assert!(listing.ends_with("2 dead letter(s)\n"));
+
+		let json = run(&outbox, &["--format", "json"]).await.unwrap();
+		let entries: Vec<OutboxEntry> = serde_json::from_str(&json).unwrap();
+		assert_eq!(entries.len(), 2);
+
+		let replayed = run(&outbox, &["--replay", &first.key]).await.unwrap();
+		assert_eq!(replayed, format!("Replayed dead letter {}\n", first.key));
+		assert!(outbox.pending(&first.key).await.unwrap().is_some());
+		assert!(run(&outbox, &["--replay", &first.key]).await.is_err());
+
+		let replayed = run(&outbox, &["--replay-all"]).await.unwrap();
+		assert_eq!(replayed, "Replayed 1 dead letter(s)\n");
+		assert!(outbox.dead_letters().await.unwrap().is_empty());
+	}
+}
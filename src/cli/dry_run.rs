@@ -0,0 +1,270 @@
+//! `test` subcommand.
+//!
+//! Dry-runs a monitor against a transaction or block and prints the rendered notifications
+//! without sending them.
+
+use clap::ArgMatches;
+use std::{io::Write, path::Path};
+
+use crate::{
+	bootstrap::{explain_monitor, initialize_services, run_trigger_filters, Result},
+	models::{BlockChainType, ConfigLoader, Monitor, MonitorMatch, Trigger},
+	repositories::{MonitorRepository, NetworkRepository, TriggerRepository},
+	services::{
+		blockchain::{
+			BlockChainClient, ClientPool, ClientPoolTrait, EvmClientTrait, StellarClientTrait,
+		},
+		filter::build_match_variables,
+		notification::NotificationService,
+		trigger::TriggerExecutionServiceTrait,
+	},
+};
+
+/// Dry-runs a monitor against a transaction or block for the `test` subcommand.
+///
+/// The block is fetched and filtered for the given monitor only. Matches that pass the trigger
+/// conditions are printed along with the rendered notification of each trigger. Nothing is
+/// sent.
+///
+/// # Errors
+/// Returns an error if the monitor, network or block cannot be loaded.
+pub async fn test_monitor(matches: &ArgMatches) -> Result<()> {
+	let monitor_path = matches
+		.get_one::<String>("monitor")
+		.expect("monitor is required");
+	let network_slug = matches
+		.get_one::<String>("network")
+		.expect("network is required");
+	let tx_hash = matches.get_one::<String>("tx");
+	let block_number = matches.get_one::<u64>("block").copied();
+
+	let monitor = Monitor::load_from_path(Path::new(monitor_path))
+		.map_err(|e| anyhow::anyhow!("Failed to load monitor {}: {}", monitor_path, e))?;
+
+	let (filter_service, trigger_execution_service, _, networks, _, _, trigger_service) =
+		initialize_services::<
+			MonitorRepository<NetworkRepository, TriggerRepository>,
+			NetworkRepository,
+			TriggerRepository,
+		>(None, None, None)
+		.map_err(|e| anyhow::anyhow!("Failed to initialize services: {}", e))?;
+
+	let network = networks
+		.get(network_slug)
+		.cloned()
+		.ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network_slug))?;
+
+	let client_pool = ClientPool::new();
+	let monitors = [monitor.clone()];
+	let (block_number, mut monitor_matches) = match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pool.get_evm_client(&network).await?;
+			let block_number = match (block_number, tx_hash) {
+				(Some(block_number), _) => block_number,
+				(None, Some(tx_hash)) => client
+					.get_transaction_receipt(tx_hash.clone())
+					.await?
+					.block_number
+					.map(|n| n.to::<u64>())
+					.ok_or_else(|| anyhow::anyhow!("Transaction {} is still pending", tx_hash))?,
+				(None, None) => unreachable!("either --tx or --block is required"),
+			};
+			let block = client
+				.get_blocks(block_number, None)
+				.await?
+				.into_iter()
+				.next()
+				.ok_or_else(|| anyhow::anyhow!("Block {} not found", block_number))?;
+			let monitor_matches = filter_service
+				.filter_block(client.as_ref(), &network, &block, &monitors)
+				.await?;
+			(block_number, monitor_matches)
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(&network).await?;
+			let block_number = match (block_number, tx_hash) {
+				(Some(block_number), _) => block_number,
+				(None, Some(tx_hash)) => client
+					.get_transaction_ledger(tx_hash)
+					.await?
+					.map(u64::from)
+					.ok_or_else(|| anyhow::anyhow!("Transaction {} not found", tx_hash))?,
+				(None, None) => unreachable!("either --tx or --block is required"),
+			};
+			let block = client
+				.get_blocks(block_number, None)
+				.await?
+				.into_iter()
+				.next()
+				.ok_or_else(|| anyhow::anyhow!("Block {} not found", block_number))?;
+			let monitor_matches = filter_service
+				.filter_block(client.as_ref(), &network, &block, &monitors)
+				.await?;
+			(block_number, monitor_matches)
+		}
+		BlockChainType::Midnight => unimplemented!("Midnight not implemented"),
+		BlockChainType::Solana => unimplemented!("Solana not implemented"),
+	};
+
+	if let Some(tx_hash) = tx_hash {
+		retain_transaction(&mut monitor_matches, tx_hash);
+	}
+
+	let trigger_scripts = trigger_execution_service.load_scripts(&monitors).await?;
+	let filtered_matches =
+		run_trigger_filters(&monitor_matches, network_slug, &trigger_scripts).await;
+
+	let mut out = std::io::stdout().lock();
+	writeln!(
+		out,
+		"Monitor '{}' on {} block {}: {} match(es), {} filtered out by trigger conditions",
+		monitor.name,
+		network_slug,
+		block_number,
+		monitor_matches.len(),
+		monitor_matches.len() - filtered_matches.len()
+	)?;
+
+	if matches.get_flag("explain") {
+		let traces = explain_monitor(
+			&client_pool,
+			&filter_service,
+			&network,
+			&monitor,
+			Some(block_number),
+			tx_hash.map(String::as_str),
+		)
+		.await?;
+		writeln!(out, "\n=== Evaluation trace ===")?;
+		for trace in &traces {
+			writeln!(out, "{}", trace)?;
+		}
+	}
+
+	let trigger_service = trigger_service.lock().await;
+	write_matches(&mut out, &monitor, &filtered_matches, |trigger_name| {
+		trigger_service.get(trigger_name)
+	})
+}
+
+/// Keeps the matches of a single transaction, comparing hashes without `0x` prefix and case
+fn retain_transaction(monitor_matches: &mut Vec<MonitorMatch>, tx_hash: &str) {
+	let normalize = |hash: &str| hash.trim_start_matches("0x").to_lowercase();
+	monitor_matches.retain(|m| normalize(&m.transaction_hash()) == normalize(tx_hash));
+}
+
+/// Writes every match with the rendered notification of each trigger of the monitor
+///
+/// # Arguments
+/// * `out` - Writer the matches are written to
+/// * `monitor` - Monitor that produced the matches
+/// * `monitor_matches` - Matches that passed the trigger conditions
+/// * `get_trigger` - Looks up a trigger by name
+fn write_matches<W: Write>(
+	out: &mut W,
+	monitor: &Monitor,
+	monitor_matches: &[MonitorMatch],
+	get_trigger: impl Fn(&str) -> Option<Trigger>,
+) -> Result<()> {
+	let notification_service = NotificationService::new();
+	for (idx, monitor_match) in monitor_matches.iter().enumerate() {
+		writeln!(out, "\n=== Match {} ===", idx + 1)?;
+		writeln!(out, "{}", serde_json::to_string_pretty(monitor_match)?)?;
+
+		let variables = build_match_variables(monitor_match);
+		for trigger_name in &monitor.triggers {
+			writeln!(out, "\n--- Trigger '{}' ---", trigger_name)?;
+			let Some(trigger) = get_trigger(trigger_name) else {
+				writeln!(out, "Trigger not found")?;
+				continue;
+			};
+			match notification_service.render(&trigger, &variables) {
+				Ok(Some(message)) => writeln!(out, "{}", message)?,
+				Ok(None) => writeln!(
+					out,
+					"Script trigger, the match above is passed to the script"
+				)?,
+				Err(e) => writeln!(out, "Failed to render notification: {}", e)?,
+			}
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{
+		MatchConditions, NotificationMessage, StellarBlock, StellarMonitorMatch,
+		StellarTransaction, StellarTransactionInfo, TriggerType, TriggerTypeConfig,
+	};
+
+	fn create_match(hash: &str) -> MonitorMatch {
+		MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+			monitor: Monitor {
+				name: "payments".to_string(),
+				triggers: vec!["webhook".to_string(), "missing".to_string()],
+				..Default::default()
+			},
+			transaction: StellarTransaction::from(StellarTransactionInfo {
+				transaction_hash: hash.to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}))
+	}
+
+	fn create_trigger() -> Trigger {
+		Trigger {
+			name: "webhook".to_string(),
+			trigger_type: TriggerType::Webhook,
+			config: TriggerTypeConfig::Webhook {
+				url: "https://example.com/webhook".to_string(),
+				method: None,
+				headers: None,
+				secret: None,
+				secrets: None,
+				message: NotificationMessage {
+					title: "Payment".to_string(),
+					body: "Transaction ${transaction_hash}".to_string(),
+				},
+				payload: None,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		}
+	}
+
+	#[test]
+	fn test_retain_transaction_normalizes_hashes() {
+		let mut monitor_matches = vec![create_match("0xABC"), create_match("def")];
+		retain_transaction(&mut monitor_matches, "abc");
+
+		assert_eq!(monitor_matches.len(), 1);
+		assert_eq!(monitor_matches[0].transaction_hash(), "0xABC");
+	}
+
+	#[test]
+	fn test_write_matches_renders_triggers() {
+		let monitor_match = create_match("abc");
+		let mut out = Vec::new();
+		write_matches(
+			&mut out,
+			monitor_match.monitor(),
+			std::slice::from_ref(&monitor_match),
+			|name| (name == "webhook").then(create_trigger),
+		)
+		.unwrap();
+		let out = String::from_utf8(out).unwrap();
+
+		assert!(out.contains("=== Match 1 ==="));
+		assert!(out.contains("--- Trigger 'webhook' ---"));
+		assert!(out.contains("Transaction abc"));
+		assert!(out.contains("--- Trigger 'missing' ---\nTrigger not found"));
+	}
+}
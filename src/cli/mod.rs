@@ -0,0 +1,362 @@
+//! Command-line interface of the service.
+//!
+//! This module defines the command-line arguments of the service and implements its
+//! subcommands. The service itself is started by the binary entry point when no subcommand is
+//! given.
+//!
+//! # Subcommands
+//! - `backfill`: Runs monitors over a historical block range of a network
+//! - `test`: Dry-runs a monitor against a transaction or block without sending notifications
+//! - `validate`: Validates and lints the configuration directory
+//! - `dead-letters`: Lists or replays trigger deliveries in the dead-letter queue
+//! - `config-db`: Imports or exports the SQLite configuration database
+//! - `block-db`: Imports the block storage files into the SQLite block storage
+
+mod backfill;
+mod block_db;
+mod config_db;
+mod dead_letters;
+mod dry_run;
+mod validate;
+
+use clap::{Arg, ArgGroup, Command};
+
+pub use backfill::backfill;
+pub use block_db::block_db;
+pub use config_db::config_db;
+pub use dead_letters::dead_letters;
+pub use dry_run::test_monitor;
+pub use validate::validate_config;
+
+/// Builds the command-line interface of the service
+///
+/// # Returns
+/// * `Command` - Command with the service options and every subcommand
+pub fn build_cli() -> Command {
+	Command::new("openzeppelin-monitor")
+		.version(env!("CARGO_PKG_VERSION"))
+		.about(
+			"A blockchain monitoring service that watches for specific on-chain activities and \
+			 triggers notifications based on configurable conditions.",
+		)
+		.arg(
+			Arg::new("log-file")
+				.long("log-file")
+				.help("Write logs to file instead of stdout")
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("log-level")
+				.long("log-level")
+				.help("Set log level (trace, debug, info, warn, error)")
+				.value_name("LEVEL"),
+		)
+		.arg(
+			Arg::new("log-path")
+				.long("log-path")
+				.help("Path to store log files (default: logs/)")
+				.value_name("PATH"),
+		)
+		.arg(
+			Arg::new("log-max-size")
+				.long("log-max-size")
+				.help("Maximum log file size in bytes before rolling (default: 1GB)")
+				.value_name("BYTES"),
+		)
+		.arg(
+			Arg::new("metrics-address")
+				.long("metrics-address")
+				.help("Address to start the metrics server on (default: 127.0.0.1:8081)")
+				.value_name("HOST:PORT"),
+		)
+		.arg(
+			Arg::new("metrics")
+				.long("metrics")
+				.help("Enable metrics server")
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new("watch-config")
+				.long("watch-config")
+				.help("Reload the configuration when files in the config directory change")
+				.action(clap::ArgAction::SetTrue),
+		)
+		.subcommand(
+			Command::new("backfill")
+				.about(
+					"Run monitors over a historical block range without touching the live \
+					 checkpoint",
+				)
+				.arg(
+					Arg::new("network")
+						.long("network")
+						.help("Slug of the network to backfill")
+						.value_name("SLUG")
+						.required(true),
+				)
+				.arg(
+					Arg::new("from")
+						.long("from")
+						.help("First block of the range (inclusive)")
+						.value_name("BLOCK")
+						.value_parser(clap::value_parser!(u64))
+						.required(true),
+				)
+				.arg(
+					Arg::new("to")
+						.long("to")
+						.help("Last block of the range (inclusive)")
+						.value_name("BLOCK")
+						.value_parser(clap::value_parser!(u64))
+						.required(true),
+				)
+				.arg(
+					Arg::new("monitor")
+						.long("monitor")
+						.help(
+							"Name of a monitor to run, can be repeated (default: all active \
+							 monitors of the network)",
+						)
+						.value_name("NAME")
+						.action(clap::ArgAction::Append),
+				)
+				.arg(
+					Arg::new("output")
+						.long("output")
+						.help("Write matches to a JSONL file instead of sending notifications")
+						.value_name("PATH"),
+				)
+				.arg(
+					Arg::new("chunk-size")
+						.long("chunk-size")
+						.help("Number of blocks fetched per request (default: 100)")
+						.value_name("BLOCKS")
+						.value_parser(clap::value_parser!(u64)),
+				)
+				.arg(
+					Arg::new("delay-ms")
+						.long("delay-ms")
+						.help("Delay between chunks to respect RPC rate limits (default: 250)")
+						.value_name("MS")
+						.value_parser(clap::value_parser!(u64)),
+				),
+		)
+		.subcommand(
+			Command::new("test")
+				.about(
+					"Dry-run a monitor against a transaction or block and print the rendered \
+					 notifications without sending them",
+				)
+				.arg(
+					Arg::new("monitor")
+						.long("monitor")
+						.help("Path to the monitor configuration file")
+						.value_name("FILE")
+						.required(true),
+				)
+				.arg(
+					Arg::new("network")
+						.long("network")
+						.help("Slug of the network to fetch data from")
+						.value_name("SLUG")
+						.required(true),
+				)
+				.arg(
+					Arg::new("tx")
+						.long("tx")
+						.help("Hash of the transaction to test")
+						.value_name("HASH"),
+				)
+				.arg(
+					Arg::new("block")
+						.long("block")
+						.help("Number of the block to test")
+						.value_name("NUMBER")
+						.value_parser(clap::value_parser!(u64)),
+				)
+				.arg(
+					Arg::new("explain")
+						.long("explain")
+						.help(
+							"Print a trace of how each transaction was evaluated against the \
+							 monitor",
+						)
+						.action(clap::ArgAction::SetTrue),
+				)
+				.group(ArgGroup::new("target").args(["tx", "block"]).required(true)),
+		)
+		.subcommand(
+			Command::new("validate")
+				.about(
+					"Validate and lint the network, monitor and trigger configuration without \
+					 starting the service",
+				)
+				.arg(
+					Arg::new("config-dir")
+						.long("config-dir")
+						.help("Directory containing the networks, monitors and triggers folders")
+						.value_name("DIR")
+						.default_value("config"),
+				)
+				.arg(
+					Arg::new("format")
+						.long("format")
+						.help("Output format")
+						.value_name("FORMAT")
+						.value_parser(["human", "json"])
+						.default_value("human"),
+				),
+		)
+		.subcommand(
+			Command::new("dead-letters")
+				.about("List or replay trigger deliveries in the dead-letter queue")
+				.arg(
+					Arg::new("replay")
+						.long("replay")
+						.help("Move the dead letter with this key back to the outbox")
+						.value_name("KEY"),
+				)
+				.arg(
+					Arg::new("replay-all")
+						.long("replay-all")
+						.help("Move every dead letter back to the outbox")
+						.action(clap::ArgAction::SetTrue)
+						.conflicts_with("replay"),
+				)
+				.arg(
+					Arg::new("format")
+						.long("format")
+						.help("Output format of the listing")
+						.value_name("FORMAT")
+						.value_parser(["human", "json"])
+						.default_value("human"),
+				),
+		)
+		.subcommand(
+			Command::new("config-db")
+				.about("Import or export the SQLite configuration database")
+				.subcommand_required(true)
+				.subcommand(config_db_command(
+					"import",
+					"Replace the database content with a validated configuration directory",
+				))
+				.subcommand(config_db_command(
+					"export",
+					"Write every configuration of the database to a configuration directory",
+				)),
+		)
+		.subcommand(
+			Command::new("block-db")
+				.about("Manage the SQLite block storage")
+				.subcommand_required(true)
+				.subcommand(
+					Command::new("import")
+						.about(
+							"Import the checkpoints, stored blocks and missed blocks written by \
+							 the file storage",
+						)
+						.arg(
+							Arg::new("data-dir")
+								.long("data-dir")
+								.help("Directory the file storage writes to")
+								.value_name("DIR")
+								.default_value("data"),
+						)
+						.arg(
+							Arg::new("database")
+								.long("database")
+								.help("Path of the SQLite block storage database")
+								.value_name("PATH")
+								.default_value("data/blocks.db"),
+						),
+				),
+		)
+}
+
+/// Builds an action of the `config-db` subcommand
+fn config_db_command(name: &'static str, about: &'static str) -> Command {
+	Command::new(name)
+		.about(about)
+		.arg(
+			Arg::new("database")
+				.long("database")
+				.help("Path of the SQLite configuration database")
+				.value_name("PATH")
+				.default_value("data/config.db"),
+		)
+		.arg(
+			Arg::new("config-dir")
+				.long("config-dir")
+				.help("Directory containing the networks, monitors and triggers folders")
+				.value_name("DIR")
+				.default_value("config"),
+		)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_build_cli_is_valid() {
+		build_cli().debug_assert();
+	}
+
+	#[test]
+	fn test_test_subcommand_requires_target() {
+		let result = build_cli().try_get_matches_from([
+			"openzeppelin-monitor",
+			"test",
+			"--monitor",
+			"monitor.json",
+			"--network",
+			"ethereum_mainnet",
+		]);
+		assert!(result.is_err());
+
+		let matches = build_cli()
+			.try_get_matches_from([
+				"openzeppelin-monitor",
+				"test",
+				"--monitor",
+				"monitor.json",
+				"--network",
+				"ethereum_mainnet",
+				"--block",
+				"100",
+			])
+			.unwrap();
+		let (name, test_matches) = matches.subcommand().unwrap();
+		assert_eq!(name, "test");
+		assert_eq!(test_matches.get_one::<u64>("block"), Some(&100));
+	}
+
+	#[test]
+	fn test_config_db_requires_action() {
+		let result = build_cli().try_get_matches_from(["openzeppelin-monitor", "config-db"]);
+		assert!(result.is_err());
+
+		let matches = build_cli()
+			.try_get_matches_from(["openzeppelin-monitor", "config-db", "export"])
+			.unwrap();
+		let (_, config_db_matches) = matches.subcommand().unwrap();
+		let (action, action_matches) = config_db_matches.subcommand().unwrap();
+		assert_eq!(action, "export");
+		assert_eq!(
+			action_matches.get_one::<String>("database").unwrap(),
+			"data/config.db"
+		);
+	}
+
+	#[test]
+	fn test_dead_letters_replay_conflicts() {
+		let result = build_cli().try_get_matches_from([
+			"openzeppelin-monitor",
+			"dead-letters",
+			"--replay",
+			"key",
+			"--replay-all",
+		]);
+		assert!(result.is_err());
+	}
+}
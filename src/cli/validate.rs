@@ -0,0 +1,76 @@
+//! `validate` subcommand.
+//!
+//! Validates and lints the configuration directory without starting the service.
+
+use clap::ArgMatches;
+use std::{io::Write, path::Path};
+
+use crate::{
+	bootstrap::Result,
+	repositories::{validate_config_dir, ConfigReport},
+};
+
+/// Validates and lints the configuration directory and prints the report.
+///
+/// Exits with a non-zero status code if any error is found.
+///
+/// # Arguments
+/// * `matches` - Arguments of the `validate` subcommand
+pub fn validate_config(matches: &ArgMatches) -> Result<()> {
+	let config_dir = matches
+		.get_one::<String>("config-dir")
+		.expect("config-dir has a default value");
+	let report = validate_config_dir(Path::new(config_dir));
+
+	write_report(
+		&mut std::io::stdout().lock(),
+		&report,
+		matches.get_one::<String>("format").map(String::as_str),
+	)?;
+
+	if report.has_errors() {
+		std::process::exit(1);
+	}
+	Ok(())
+}
+
+/// Writes a validation report in the requested format
+///
+/// # Arguments
+/// * `out` - Writer the report is written to
+/// * `report` - Report to write
+/// * `format` - `json`, or the human-readable report otherwise
+fn write_report<W: Write>(out: &mut W, report: &ConfigReport, format: Option<&str>) -> Result<()> {
+	match format {
+		Some("json") => writeln!(out, "{}", serde_json::to_string_pretty(report)?)?,
+		_ => writeln!(out, "{}", report)?,
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_write_report_formats() {
+		let temp_dir = TempDir::new().unwrap();
+		for directory in ["networks", "monitors", "triggers"] {
+			fs::create_dir(temp_dir.path().join(directory)).unwrap();
+		}
+		let report = validate_config_dir(temp_dir.path());
+
+		let mut human = Vec::new();
+		write_report(&mut human, &report, Some("human")).unwrap();
+		let human = String::from_utf8(human).unwrap();
+		assert!(human.starts_with("Loaded 0 network(s), 0 monitor(s), 0 trigger(s)"));
+
+		let mut json = Vec::new();
+		write_report(&mut json, &report, Some("json")).unwrap();
+		let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+		assert_eq!(json["networks"], 0);
+		assert!(json["issues"].is_array());
+	}
+}
@@ -11,12 +11,14 @@
 //! # Module Structure
 //!
 //! - `bootstrap`: Bootstraps the application
+//! - `cli`: Command-line interface and subcommands
 //! - `models`: Data structures for configuration and blockchain data
 //! - `repositories`: Configuration storage and management
 //! - `services`: Core business logic and blockchain interaction
 //! - `utils`: Common utilities and helper functions
 
 pub mod bootstrap;
+pub mod cli;
 pub mod models;
 pub mod repositories;
 pub mod services;
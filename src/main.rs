@@ -12,13 +12,23 @@
 //! - Services: Core functionality including block watching, filtering, and notifications
 //!
 //! # Flow
-//! 1. Loads configurations from the default directory
+//! 1. Loads configurations from the default directory or, with `CONFIG_STORAGE=sqlite`, the
+//!    configuration database
 //! 2. Initializes core services (monitoring, filtering, notifications)
 //! 3. Sets up blockchain watchers for networks with active monitors
 //! 4. Processes blocks and triggers notifications based on configured conditions
-//! 5. Handles graceful shutdown on Ctrl+C
+//! 5. Reloads the configuration on SIGHUP or, with `--watch-config`, when it changes
+//! 6. Handles graceful shutdown on Ctrl+C
+//!
+//! # Subcommands
+//! Subcommands are implemented in the `cli` module and dispatched from here:
+//! - `backfill`: Runs monitors over a historical block range of a network
+//! - `test`: Dry-runs a monitor against a transaction or block without sending notifications
+//! - `validate`: Validates and lints the configuration directory
+//! - `dead-letters`: Lists or replays trigger deliveries in the dead-letter queue
 
 pub mod bootstrap;
+pub mod cli;
 pub mod models;
 pub mod repositories;
 pub mod services;
@@ -26,30 +36,76 @@ pub mod utils;
 
 use crate::{
 	bootstrap::{
-		create_block_handler, create_trigger_handler, has_active_monitors, initialize_services,
-		Result,
+		create_block_handler, create_outbox_trigger_handler, create_pending_block_handler,
+		initialize_services, listen_for_reload_signal, run_outbox_delivery, schedule_reports,
+		watch_config_dir, ConfigReloader, ReloadRequest, Reloadable, Result, WatchedNetwork,
+	},
+	cli::build_cli,
+	models::{BlockChainType, BlockType, Monitor, Network, ProcessedBlock},
+	repositories::{
+		ConfigStorage, MonitorRepository, MonitorRepositoryTrait, NetworkRepository,
+		NetworkRepositoryTrait, SqliteMonitorRepository, SqliteNetworkRepository,
+		SqliteTriggerRepository, TriggerRepository, TriggerRepositoryTrait,
 	},
-	models::{BlockChainType, Network},
-	repositories::{MonitorRepository, NetworkRepository, TriggerRepository},
 	services::{
 		blockchain::{ClientPool, ClientPoolTrait},
-		blockwatcher::{BlockTracker, BlockTrackerTrait, BlockWatcherService, FileBlockStorage},
-		trigger::TriggerExecutionServiceTrait,
+		blockwatcher::{
+			BlockRetention, BlockStorageBackend, BlockTracker, BlockTrackerTrait,
+			BlockWatcherService, FileBlockStorage, PendingAlertTracker, PendingBlockHandler,
+			SqliteBlockStorage,
+		},
+		report::FileMatchHistory,
+		stream::MatchStream,
+		trigger::{
+			AlertThrottle, FileMatchOutbox, TriggerError, TriggerExecutionService,
+			TriggerExecutionServiceTrait,
+		},
 	},
 	utils::{
-		constants::DOCUMENTATION_URL, logging::setup_logging,
-		metrics::server::create_metrics_server,
+		constants::DOCUMENTATION_URL,
+		logging::setup_logging,
+		metrics::{management::ManagementState, server::create_metrics_server},
 	},
 };
 
-use clap::{Arg, Command};
+use clap::ArgMatches;
 use dotenvy::dotenv;
+use futures::future::BoxFuture;
 use std::env::{set_var, var};
-use std::sync::Arc;
-use tokio::sync::watch;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, watch};
 use tokio_cron_scheduler::JobScheduler;
 use tracing::{error, info};
 
+/// Monitors, match history, outbox and trigger service the summary report jobs are built from
+type ReportJobs<T> = (
+	Reloadable<Vec<Monitor>>,
+	Arc<FileMatchHistory>,
+	Arc<FileMatchOutbox>,
+	Arc<TriggerExecutionService<T>>,
+);
+
+/// Directory the service loads its configuration from
+const CONFIG_DIR: &str = "config";
+
+/// Database the service loads its configuration from when `CONFIG_STORAGE` is `sqlite`
+const CONFIG_DATABASE: &str = "data/config.db";
+
+/// Interval between two checks of the config directory when watching it for changes
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Interval between two deliveries of the matches queued in the outbox
+const OUTBOX_DELIVERY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long keys of delivered matches are kept to skip duplicate deliveries
+const OUTBOX_DELIVERED_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// File the alert policy state is persisted to when `PERSIST_ALERT_STATE` is enabled
+const ALERT_STATE_FILE: &str = "data/alert_state.json";
+
+/// File the pending alerts of two-phase monitors are persisted to, next to the block storage
+const PENDING_ALERTS_FILE: &str = "data/pending_alerts.json";
+
 /// Main entry point for the blockchain monitoring service.
 ///
 /// # Errors
@@ -57,49 +113,7 @@ use tracing::{error, info};
 #[tokio::main]
 async fn main() -> Result<()> {
 	// Initialize command-line interface
-	let matches = Command::new("openzeppelin-monitor")
-		.version(env!("CARGO_PKG_VERSION"))
-		.about(
-			"A blockchain monitoring service that watches for specific on-chain activities and \
-			 triggers notifications based on configurable conditions.",
-		)
-		.arg(
-			Arg::new("log-file")
-				.long("log-file")
-				.help("Write logs to file instead of stdout")
-				.action(clap::ArgAction::SetTrue),
-		)
-		.arg(
-			Arg::new("log-level")
-				.long("log-level")
-				.help("Set log level (trace, debug, info, warn, error)")
-				.value_name("LEVEL"),
-		)
-		.arg(
-			Arg::new("log-path")
-				.long("log-path")
-				.help("Path to store log files (default: logs/)")
-				.value_name("PATH"),
-		)
-		.arg(
-			Arg::new("log-max-size")
-				.long("log-max-size")
-				.help("Maximum log file size in bytes before rolling (default: 1GB)")
-				.value_name("BYTES"),
-		)
-		.arg(
-			Arg::new("metrics-address")
-				.long("metrics-address")
-				.help("Address to start the metrics server on (default: 127.0.0.1:8081)")
-				.value_name("HOST:PORT"),
-		)
-		.arg(
-			Arg::new("metrics")
-				.long("metrics")
-				.help("Enable metrics server")
-				.action(clap::ArgAction::SetTrue),
-		)
-		.get_matches();
+	let matches = build_cli().get_matches();
 
 	// Load environment variables from .env file
 	dotenv().ok();
@@ -127,11 +141,61 @@ async fn main() -> Result<()> {
 		}
 	}
 
+	// Validation runs before logging is set up, so its report is the only output
+	if let Some(("validate", validate_matches)) = matches.subcommand() {
+		return cli::validate_config(validate_matches);
+	}
+
 	// Setup logging to stdout
 	setup_logging().unwrap_or_else(|e| {
 		error!("Failed to setup logging: {}", e);
 	});
 
+	match matches.subcommand() {
+		Some(("backfill", backfill_matches)) => return cli::backfill(backfill_matches).await,
+		Some(("test", test_matches)) => return cli::test_monitor(test_matches).await,
+		Some(("dead-letters", dead_letter_matches)) => {
+			return cli::dead_letters(dead_letter_matches).await
+		}
+		Some(("config-db", config_db_matches)) => return cli::config_db(config_db_matches),
+		Some(("block-db", block_db_matches)) => return cli::block_db(block_db_matches).await,
+		_ => {}
+	}
+
+	// The services are started with the repositories of the selected configuration storage
+	match config_storage()? {
+		storage @ ConfigStorage::Files(_) => {
+			run_service::<
+				MonitorRepository<NetworkRepository, TriggerRepository>,
+				NetworkRepository,
+				TriggerRepository,
+			>(&matches, storage)
+			.await
+		}
+		storage @ ConfigStorage::Sqlite(_) => {
+			run_service::<
+				SqliteMonitorRepository<SqliteNetworkRepository, SqliteTriggerRepository>,
+				SqliteNetworkRepository,
+				SqliteTriggerRepository,
+			>(&matches, storage)
+			.await
+		}
+	}
+}
+
+/// Runs the service with the configuration of the given storage until it is shut down.
+///
+/// The configuration is loaded, reloaded and changed through the management API with the
+/// repository types `M`, `N` and `T`, which must match the storage.
+///
+/// # Errors
+/// Returns an error if service initialization fails.
+async fn run_service<M, N, T>(matches: &ArgMatches, storage: ConfigStorage) -> Result<()>
+where
+	M: MonitorRepositoryTrait<N, T> + Send + Sync + 'static,
+	N: NetworkRepositoryTrait + Send + Sync + 'static,
+	T: TriggerRepositoryTrait + Send + Sync + 'static,
+{
 	let (
 		filter_service,
 		trigger_execution_service,
@@ -140,12 +204,17 @@ async fn main() -> Result<()> {
 		monitor_service,
 		network_service,
 		trigger_service,
-	) = initialize_services::<
-		MonitorRepository<NetworkRepository, TriggerRepository>,
-		NetworkRepository,
-		TriggerRepository,
-	>(None, None, None)
-	.map_err(|e| anyhow::anyhow!("Failed to initialize services: {}. Please refer to the documentation quickstart ({}) on how to configure the service.", e, DOCUMENTATION_URL))?;
+	) = storage
+		.load_services::<M, N, T>()
+		.map_err(Into::into)
+		.and_then(|(monitor_service, network_service, trigger_service)| {
+			initialize_services(
+				Some(monitor_service),
+				Some(network_service),
+				Some(trigger_service),
+			)
+		})
+		.map_err(|e| anyhow::anyhow!("Failed to initialize services: {}. Please refer to the documentation quickstart ({}) on how to configure the service.", e, DOCUMENTATION_URL))?;
 
 	// Check if metrics should be enabled from either CLI flag or env var
 	let metrics_enabled =
@@ -165,139 +234,457 @@ async fn main() -> Result<()> {
 			.unwrap_or_else(|| "127.0.0.1:8081".to_string())
 	};
 
-	// Start the metrics server if successful
-	let metrics_server = if metrics_enabled {
-		info!("Metrics server enabled, starting on {}", metrics_address);
-
-		// Create the metrics server future
-		match create_metrics_server(
-			metrics_address,
+	let (shutdown_tx, _) = watch::channel(false);
+	// Pre-load all trigger scripts into memory at startup to reduce file I/O operations.
+	// This prevents repeated file descriptor usage during script execution and improves performance
+	// by keeping scripts readily available in memory.
+	let active_monitors_trigger_scripts = trigger_execution_service
+		.load_scripts(&active_monitors)
+		.await?;
+	// The handlers read monitors and trigger scripts through the reloader, so a reloaded
+	// configuration applies to the next processed block
+	let mut config_reloader = ConfigReloader::new(
+		storage.clone(),
+		trigger_execution_service.clone(),
+		(
 			monitor_service.clone(),
 			network_service.clone(),
 			trigger_service.clone(),
-		) {
-			Ok(server) => Some(server),
-			Err(e) => {
-				error!("Failed to create metrics server: {}", e);
-				None
-			}
-		}
-	} else {
-		info!("Metrics server disabled. Use --metrics flag or METRICS_ENABLED=true to enable");
-		None
-	};
-
-	let networks_with_monitors: Vec<Network> = networks
-		.values()
-		.filter(|network| has_active_monitors(&active_monitors.clone(), &network.slug))
-		.cloned()
-		.collect();
+		),
+		active_monitors,
+		&networks,
+		active_monitors_trigger_scripts,
+	);
 
+	let networks_with_monitors = config_reloader.watched_networks();
 	if networks_with_monitors.is_empty() {
 		info!("No networks with active monitors found. Exiting...");
 		return Ok(());
 	}
 
-	let (shutdown_tx, _) = watch::channel(false);
-	// Pre-load all trigger scripts into memory at startup to reduce file I/O operations.
-	// This prevents repeated file descriptor usage during script execution and improves performance
-	// by keeping scripts readily available in memory.
-	let active_monitors_trigger_scripts = trigger_execution_service
-		.load_scripts(&active_monitors)
-		.await?;
 	let client_pool = Arc::new(ClientPool::new());
+	let pending_block_handler = create_pending_block_handler(
+		shutdown_tx.clone(),
+		filter_service.clone(),
+		config_reloader.monitors(),
+		client_pool.clone(),
+	);
 	let block_handler = create_block_handler(
 		shutdown_tx.clone(),
 		filter_service,
-		active_monitors,
+		config_reloader.monitors(),
 		client_pool.clone(),
 	);
-	let trigger_handler = create_trigger_handler(
-		shutdown_tx.clone(),
+	// Matches are queued in the outbox before the checkpoint advances and delivered with retries
+	// in the background
+	let outbox = Arc::new(FileMatchOutbox::default());
+	let history = Arc::new(FileMatchHistory::default());
+	// Recorded matches are also broadcast to the subscribers of the match stream endpoints
+	let match_stream = Arc::new(MatchStream::new(history.clone()));
+	let trigger_handler = create_outbox_trigger_handler(
+		outbox.clone(),
+		match_stream.clone(),
+		config_reloader.trigger_scripts(),
+	);
+	tokio::spawn(run_outbox_delivery(
+		outbox.clone(),
+		trigger_execution_service.clone(),
+		config_reloader.trigger_scripts(),
+		Arc::new(alert_throttle()),
+		OUTBOX_DELIVERY_INTERVAL,
+		OUTBOX_DELIVERED_RETENTION,
+		shutdown_tx.subscribe(),
+	));
+
+	// Summary reports are scheduled from the active monitors and rescheduled on every reload
+	let report_jobs = (
+		config_reloader.monitors(),
+		history,
+		outbox.clone(),
 		trigger_execution_service,
-		active_monitors_trigger_scripts,
 	);
+	let mut report_scheduler = start_report_scheduler(&report_jobs).await?;
 
-	let file_block_storage = Arc::new(FileBlockStorage::default());
-	let block_watcher = BlockWatcherService::<FileBlockStorage, _, _, JobScheduler>::new(
-		file_block_storage.clone(),
+	let block_storage = Arc::new(block_storage()?);
+	let mut block_watcher = BlockWatcherService::<BlockStorageBackend, _, _, JobScheduler>::new(
+		block_storage.clone(),
 		block_handler,
 		trigger_handler,
-		Arc::new(BlockTracker::new(1000, Some(file_block_storage.clone()))),
+		Arc::new(BlockTracker::new(1000, Some(block_storage.clone()))),
 	)
-	.await?;
+	.await?
+	.with_pending_alert_tracker(pending_alert_tracker());
 
-	for network in networks_with_monitors {
-		match network.network_type {
-			BlockChainType::EVM => {
-				if let Ok(client) = client_pool.get_evm_client(&network).await {
-					let _ = block_watcher
-						.start_network_watcher(&network, (*client).clone())
-						.await
-						.inspect_err(|e| {
-							error!("Failed to start EVM network watcher: {}", e);
-						});
-				} else {
-					error!("Failed to get EVM client for network: {}", network.slug);
-				}
-			}
-			BlockChainType::Stellar => {
-				if let Ok(client) = client_pool.get_stellar_client(&network).await {
-					let _ = block_watcher
-						.start_network_watcher(&network, (*client).clone())
-						.await
-						.inspect_err(|e| {
-							error!("Failed to start Stellar network watcher: {}", e);
-						});
-				} else {
-					error!("Failed to get Stellar client for network: {}", network.slug);
-				}
+	for watched in &networks_with_monitors {
+		start_network_watcher(
+			&mut block_watcher,
+			&client_pool,
+			&pending_block_handler,
+			watched,
+		)
+		.await;
+	}
+
+	// Configuration reloads are requested with SIGHUP and, optionally, by watching the config
+	// directory or database for changes
+	let (reload_tx, mut reload_rx) = mpsc::channel(1);
+	tokio::spawn(listen_for_reload_signal(reload_tx.clone()));
+	let watch_config = matches.get_flag("watch-config")
+		|| var("WATCH_CONFIG").map(|v| v == "true").unwrap_or(false);
+	if watch_config {
+		info!(
+			"Watching {} for configuration changes",
+			storage.location().display()
+		);
+		tokio::spawn(watch_config_dir(
+			storage.location().to_path_buf(),
+			CONFIG_POLL_INTERVAL,
+			reload_tx.clone(),
+		));
+	}
+
+	// Start the metrics server if successful
+	let metrics_server = if metrics_enabled {
+		info!("Metrics server enabled, starting on {}", metrics_address);
+
+		// Create the metrics server future
+		match create_metrics_server(
+			metrics_address,
+			monitor_service.clone(),
+			network_service.clone(),
+			trigger_service.clone(),
+			Some(match_stream),
+			management_state(&storage, &reload_tx, &client_pool, &block_storage, &outbox),
+		) {
+			Ok(server) => Some(server),
+			Err(e) => {
+				error!("Failed to create metrics server: {}", e);
+				None
 			}
-			BlockChainType::Midnight => unimplemented!("Midnight not implemented"),
-			BlockChainType::Solana => unimplemented!("Solana not implemented"),
 		}
-	}
+	} else {
+		info!("Metrics server disabled. Use --metrics flag or METRICS_ENABLED=true to enable");
+		None
+	};
 
 	info!("Service started. Press Ctrl+C to shutdown");
 
 	let ctrl_c = tokio::signal::ctrl_c();
+	tokio::pin!(ctrl_c);
+	let metrics_future = async move {
+		match metrics_server {
+			Some(server) => server.await,
+			None => std::future::pending().await,
+		}
+	};
+	tokio::pin!(metrics_future);
 
-	if let Some(metrics_future) = metrics_server {
+	loop {
 		tokio::select! {
-				result = ctrl_c => {
-					if let Err(e) = result {
-			  error!("Error waiting for Ctrl+C: {}", e);
+			result = &mut ctrl_c => {
+				if let Err(e) = result {
+					error!("Error waiting for Ctrl+C: {}", e);
+				}
+				info!("Shutdown signal received, stopping services...");
+				break;
 			}
-			info!("Shutdown signal received, stopping services...");
-		  }
-		  result = metrics_future => {
-			if let Err(e) = result {
-			  error!("Metrics server error: {}", e);
+			result = &mut metrics_future => {
+				if let Err(e) = result {
+					error!("Metrics server error: {}", e);
+				}
+				info!("Metrics server stopped, shutting down services...");
+				break;
+			}
+			Some(request) = reload_rx.recv() => {
+				let result = config_reloader.reload().await;
+				match &result {
+					Ok(changes) => {
+						for network_slug in &changes.stopped {
+							if let Err(e) = block_watcher.stop_network_watcher(network_slug).await {
+								error!("Failed to stop network watcher: {}", e);
+							}
+							client_pool.remove_clients(network_slug).await;
+						}
+						for watched in &changes.started {
+							start_network_watcher(
+								&mut block_watcher,
+								&client_pool,
+								&pending_block_handler,
+								watched,
+							)
+							.await;
+						}
+						stop_report_scheduler(report_scheduler.take()).await;
+						report_scheduler = start_report_scheduler(&report_jobs)
+							.await
+							.unwrap_or_else(|e| {
+								error!("Failed to schedule monitor reports: {}", e);
+								None
+							});
+						info!(
+							"Configuration reloaded ({} network watcher(s) stopped, {} started)",
+							changes.stopped.len(),
+							changes.started.len()
+						);
+					}
+					Err(e) => {
+						error!("Failed to reload configuration, keeping the current one: {}", e);
+					}
+				}
+				request.respond(result.map(|_| ()).map_err(|e| e.to_string()));
 			}
-			info!("Metrics server stopped, shutting down services...");
-		  }
 		}
-	} else {
-		let _ = ctrl_c.await;
-		info!("Shutdown signal received, stopping services...");
 	}
 
 	// Common shutdown logic
 	let _ = shutdown_tx.send(true);
 
 	// Future for all network shutdown operations
-	let shutdown_futures = networks
-		.values()
-		.map(|network| block_watcher.stop_network_watcher(&network.slug));
+	let running_networks: Vec<String> = block_watcher
+		.active_watchers
+		.read()
+		.await
+		.keys()
+		.cloned()
+		.collect();
+	let shutdown_futures = running_networks
+		.iter()
+		.map(|network_slug| block_watcher.stop_network_watcher(network_slug));
 
 	for result in futures::future::join_all(shutdown_futures).await {
 		if let Err(e) = result {
 			error!("Error during shutdown: {}", e);
 		}
 	}
+	stop_report_scheduler(report_scheduler).await;
 
 	tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
 	info!("Shutdown complete");
 	Ok(())
 }
+
+/// Starts the block watcher of a network.
+///
+/// Two-phase alerts are enabled for the network if any of its active monitors uses them.
+async fn start_network_watcher<H, T>(
+	block_watcher: &mut BlockWatcherService<BlockStorageBackend, H, T, JobScheduler>,
+	client_pool: &ClientPool,
+	pending_block_handler: &PendingBlockHandler,
+	watched: &WatchedNetwork,
+) where
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<std::result::Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	let network = &watched.network;
+	if watched.two_phase {
+		info!("Two-phase alerts enabled for network: {}", network.slug);
+		block_watcher.set_pending_block_handler(&network.slug, Some(pending_block_handler.clone()));
+	} else {
+		block_watcher.set_pending_block_handler(&network.slug, None);
+	}
+
+	match network.network_type {
+		BlockChainType::EVM => {
+			if let Ok(client) = client_pool.get_evm_client(network).await {
+				let _ = block_watcher
+					.start_network_watcher(network, (*client).clone())
+					.await
+					.inspect_err(|e| {
+						error!("Failed to start EVM network watcher: {}", e);
+					});
+			} else {
+				error!("Failed to get EVM client for network: {}", network.slug);
+			}
+		}
+		BlockChainType::Stellar => {
+			if let Ok(client) = client_pool.get_stellar_client(network).await {
+				let _ = block_watcher
+					.start_network_watcher(network, (*client).clone())
+					.await
+					.inspect_err(|e| {
+						error!("Failed to start Stellar network watcher: {}", e);
+					});
+			} else {
+				error!("Failed to get Stellar client for network: {}", network.slug);
+			}
+		}
+		BlockChainType::Midnight => unimplemented!("Midnight not implemented"),
+		BlockChainType::Solana => unimplemented!("Solana not implemented"),
+	}
+}
+
+/// Starts a scheduler running the summary reports of the active monitors.
+///
+/// The jobs are built from the report schedules of the monitors at the time of the call, so the
+/// scheduler is replaced after every reload to pick up added or removed schedules.
+///
+/// # Returns
+/// Returns the running scheduler, or `None` if no active monitor has reports
+///
+/// # Errors
+/// Returns an error if the scheduler cannot be created or a job cannot be added.
+async fn start_report_scheduler<T>(
+	(monitors, history, outbox, trigger_execution_service): &ReportJobs<T>,
+) -> Result<Option<JobScheduler>>
+where
+	T: TriggerRepositoryTrait + Send + Sync + 'static,
+{
+	let report_scheduler = JobScheduler::new().await?;
+	let scheduled_reports = schedule_reports(
+		&report_scheduler,
+		monitors.clone(),
+		history.clone(),
+		outbox.clone(),
+		trigger_execution_service.clone(),
+	)
+	.await?;
+	if scheduled_reports == 0 {
+		return Ok(None);
+	}
+
+	info!("Scheduled {} monitor report(s)", scheduled_reports);
+	report_scheduler.start().await?;
+	Ok(Some(report_scheduler))
+}
+
+/// Stops the scheduler of the summary reports, if one is running.
+async fn stop_report_scheduler(report_scheduler: Option<JobScheduler>) {
+	if let Some(mut report_scheduler) = report_scheduler {
+		if let Err(e) = report_scheduler.shutdown().await {
+			error!("Failed to stop report scheduler: {}", e);
+		}
+	}
+}
+
+/// Creates the block storage selected by `BLOCK_STORAGE`.
+///
+/// Blocks are stored in the data directory files unless `BLOCK_STORAGE` is `sqlite`, in which
+/// case they are stored in the SQLite database with the retention policy set by
+/// `BLOCK_RETENTION_MAX_BLOCKS` and `BLOCK_RETENTION_MAX_AGE_SECS`.
+///
+/// # Errors
+/// Returns an error if the storage is unknown, a retention limit is not a number, or the
+/// database cannot be opened.
+fn block_storage() -> Result<BlockStorageBackend> {
+	match var("BLOCK_STORAGE").as_deref() {
+		Err(_) | Ok("file") => Ok(BlockStorageBackend::File(FileBlockStorage::default())),
+		Ok("sqlite") => {
+			let limit = |name: &str| -> Result<Option<u64>> {
+				var(name)
+					.ok()
+					.map(|value| {
+						value.parse::<u64>().map_err(|e| {
+							anyhow::anyhow!("Invalid {} '{}': {}", name, value, e).into()
+						})
+					})
+					.transpose()
+			};
+			let retention = BlockRetention {
+				max_blocks: limit("BLOCK_RETENTION_MAX_BLOCKS")?,
+				max_age: limit("BLOCK_RETENTION_MAX_AGE_SECS")?.map(Duration::from_secs),
+			};
+			info!("Storing blocks in SQLite with retention {:?}", retention);
+			Ok(BlockStorageBackend::Sqlite(
+				SqliteBlockStorage::new(None)?.with_retention(retention),
+			))
+		}
+		Ok(other) => Err(anyhow::anyhow!("Unknown BLOCK_STORAGE '{}'", other).into()),
+	}
+}
+
+/// Selects the configuration storage with `CONFIG_STORAGE`.
+///
+/// The configuration is read from the `config` directory unless `CONFIG_STORAGE` is `sqlite`,
+/// in which case it is read from the configuration database, which must have been imported with
+/// the `config-db` subcommand.
+///
+/// # Errors
+/// Returns an error if the storage is unknown or the database does not exist.
+fn config_storage() -> Result<ConfigStorage> {
+	match var("CONFIG_STORAGE").as_deref() {
+		Err(_) | Ok("file") => Ok(ConfigStorage::Files(PathBuf::from(CONFIG_DIR))),
+		Ok("sqlite") => {
+			let database = PathBuf::from(CONFIG_DATABASE);
+			if !database.exists() {
+				return Err(anyhow::anyhow!(
+					"Configuration database {} not found, create it with `config-db import`",
+					database.display()
+				)
+				.into());
+			}
+			info!(
+				"Loading configuration from SQLite database {}",
+				database.display()
+			);
+			Ok(ConfigStorage::Sqlite(database))
+		}
+		Ok(other) => Err(anyhow::anyhow!("Unknown CONFIG_STORAGE '{}'", other).into()),
+	}
+}
+
+/// Builds the management API state if `MANAGEMENT_API_TOKEN` is set.
+///
+/// Changes made through the API are saved to the configuration storage and applied by requesting
+/// a configuration reload.
+fn management_state(
+	storage: &ConfigStorage,
+	reload_tx: &mpsc::Sender<ReloadRequest>,
+	client_pool: &Arc<ClientPool>,
+	block_storage: &Arc<BlockStorageBackend>,
+	outbox: &Arc<FileMatchOutbox>,
+) -> Option<ManagementState> {
+	match var("MANAGEMENT_API_TOKEN") {
+		Ok(token) if !token.is_empty() => {
+			info!("Management API enabled");
+			Some(ManagementState {
+				token,
+				storage: storage.clone(),
+				reload_tx: reload_tx.clone(),
+				client_pool: client_pool.clone(),
+				block_storage: block_storage.clone(),
+				outbox: outbox.clone(),
+			})
+		}
+		_ => None,
+	}
+}
+
+/// Creates the throttle applying the alert policies of monitors and triggers.
+///
+/// The state is kept in memory unless `PERSIST_ALERT_STATE` is enabled, in which case it is
+/// saved to the data directory and survives restarts. If the saved state cannot be loaded, the
+/// throttle falls back to memory only.
+fn alert_throttle() -> AlertThrottle {
+	if !var("PERSIST_ALERT_STATE")
+		.map(|v| v == "true")
+		.unwrap_or(false)
+	{
+		return AlertThrottle::new();
+	}
+
+	AlertThrottle::with_persistence(PathBuf::from(ALERT_STATE_FILE)).unwrap_or_else(|e| {
+		error!(
+			"Failed to load alert state, keeping it in memory only: {}",
+			e
+		);
+		AlertThrottle::new()
+	})
+}
+
+/// Creates the tracker of pending two-phase alerts, persisted next to the block storage.
+///
+/// If the saved state cannot be loaded the error is logged and the tracker starts empty, so
+/// the alerts that were pending get no follow-up.
+fn pending_alert_tracker() -> PendingAlertTracker {
+	PendingAlertTracker::with_persistence(PathBuf::from(PENDING_ALERTS_FILE)).unwrap_or_else(|e| {
+		error!(
+			"Failed to load pending alerts, starting without them: {}",
+			e
+		);
+		PendingAlertTracker::new()
+	})
+}
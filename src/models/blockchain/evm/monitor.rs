@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::models::{EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor};
+use crate::models::{
+	AlertContext, EVMTransaction, EVMTransactionReceipt, MatchConditions, MatchContext, Monitor,
+};
 
 /// Result of a successful monitor match on an EVM chain
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -19,6 +21,13 @@ pub struct EVMMonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+	/// Two-phase alert context, set when the monitor has two-phase alerts enabled
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alert: Option<AlertContext>,
+
+	/// Network and block the match was found in
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub context: Option<MatchContext>,
 }
 
 /// Collection of decoded parameters from matched conditions
@@ -32,6 +41,14 @@ pub struct MatchParamsMap {
 
 	/// Raw function/event signature as bytes
 	pub hex_signature: Option<String>,
+
+	/// Address of the contract that emitted the event, not set for functions
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub address: Option<String>,
+
+	/// Index of the event's log in its block, not set for functions
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub log_index: Option<u64>,
 }
 
 /// Single decoded parameter from a function or event
@@ -5,6 +5,9 @@
 //! platform-specific logic for blocks, transactions, and event monitoring.
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::{Monitor, Network};
 
 pub mod evm;
 pub mod stellar;
@@ -70,6 +73,155 @@ pub enum MonitorMatch {
 	Stellar(Box<stellar::StellarMonitorMatch>),
 }
 
+impl MonitorMatch {
+	/// Returns the monitor that produced this match
+	pub fn monitor(&self) -> &Monitor {
+		match self {
+			MonitorMatch::EVM(m) => &m.monitor,
+			MonitorMatch::Stellar(m) => &m.monitor,
+		}
+	}
+
+	/// Returns the hash of the matched transaction in the network's native format
+	pub fn transaction_hash(&self) -> String {
+		match self {
+			MonitorMatch::EVM(m) => m.transaction.hash().to_string(),
+			MonitorMatch::Stellar(m) => m.transaction.hash().clone(),
+		}
+	}
+
+	/// Returns the two-phase alert context attached to this match, if any
+	pub fn alert(&self) -> Option<&AlertContext> {
+		match self {
+			MonitorMatch::EVM(m) => m.alert.as_ref(),
+			MonitorMatch::Stellar(m) => m.alert.as_ref(),
+		}
+	}
+
+	/// Attaches a two-phase alert context to this match
+	pub fn set_alert(&mut self, alert: Option<AlertContext>) {
+		match self {
+			MonitorMatch::EVM(m) => m.alert = alert,
+			MonitorMatch::Stellar(m) => m.alert = alert,
+		}
+	}
+
+	/// Returns the network and block context of this match, if any
+	pub fn context(&self) -> Option<&MatchContext> {
+		match self {
+			MonitorMatch::EVM(m) => m.context.as_ref(),
+			MonitorMatch::Stellar(m) => m.context.as_ref(),
+		}
+	}
+}
+
+/// Phase of a two-phase alert
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertPhase {
+	/// The match was found in a block at head depth that is not yet confirmed
+	Pending,
+	/// The match was found in a block at the network's confirmation depth
+	Confirmed,
+	/// A pending match was not found once its block height reached confirmation depth
+	Dropped,
+}
+
+impl std::fmt::Display for AlertPhase {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AlertPhase::Pending => write!(f, "pending"),
+			AlertPhase::Confirmed => write!(f, "confirmed"),
+			AlertPhase::Dropped => write!(f, "dropped"),
+		}
+	}
+}
+
+/// Two-phase alert metadata attached to a monitor match
+///
+/// The correlation ID is derived from the network, monitor and transaction so the pending alert
+/// and its confirmed or dropped follow-up share the same value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertContext {
+	/// Current phase of the alert
+	pub phase: AlertPhase,
+
+	/// Identifier shared by every phase of the same alert
+	pub correlation_id: String,
+}
+
+impl AlertContext {
+	/// Creates an alert context for a match, deriving its correlation ID
+	///
+	/// # Arguments
+	/// * `phase` - Phase of the alert
+	/// * `network_slug` - Network the match was found on
+	/// * `monitor_match` - The match to correlate
+	pub fn new(phase: AlertPhase, network_slug: &str, monitor_match: &MonitorMatch) -> Self {
+		Self {
+			phase,
+			correlation_id: Self::correlation_id(
+				network_slug,
+				&monitor_match.monitor().name,
+				&monitor_match.transaction_hash(),
+			),
+		}
+	}
+
+	/// Derives the correlation ID for a (network, monitor, transaction) tuple
+	pub fn correlation_id(
+		network_slug: &str,
+		monitor_name: &str,
+		transaction_hash: &str,
+	) -> String {
+		let mut hasher = Sha256::new();
+		hasher.update(format!("{}|{}|{}", network_slug, monitor_name, transaction_hash).as_bytes());
+		hex::encode(&hasher.finalize()[..16])
+	}
+}
+
+/// Network and block a monitor match was found in
+///
+/// Attached to matches by the filters, so notifications can refer to the network without its
+/// configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MatchContext {
+	/// Slug of the network
+	pub network_slug: String,
+
+	/// Human-readable name of the network
+	pub network_name: String,
+
+	/// Chain ID of EVM networks
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub chain_id: Option<u64>,
+
+	/// Base URL of the network's block explorer
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub explorer_url: Option<String>,
+
+	/// Timestamp of the block in seconds since the Unix epoch
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_timestamp: Option<u64>,
+}
+
+impl MatchContext {
+	/// Creates the context of a match found on a network
+	///
+	/// # Arguments
+	/// * `network` - Network the match was found on
+	/// * `block_timestamp` - Timestamp of the block in seconds since the Unix epoch, if known
+	pub fn new(network: &Network, block_timestamp: Option<u64>) -> Self {
+		Self {
+			network_slug: network.slug.clone(),
+			network_name: network.name.clone(),
+			chain_id: network.chain_id,
+			explorer_url: network.explorer_url.clone(),
+			block_timestamp,
+		}
+	}
+}
+
 /// Structure to hold block processing results
 ///
 /// This is used to pass the results of block processing to the trigger handler
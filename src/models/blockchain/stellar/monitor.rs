@@ -3,7 +3,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::models::{MatchConditions, Monitor, StellarBlock, StellarTransaction};
+use crate::models::{
+	AlertContext, MatchConditions, MatchContext, Monitor, StellarBlock, StellarTransaction,
+};
 
 /// Result of a successful monitor match on a Stellar chain
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -22,6 +24,13 @@ pub struct MonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+	/// Two-phase alert context, set when the monitor has two-phase alerts enabled
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alert: Option<AlertContext>,
+
+	/// Network and block the match was found in
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub context: Option<MatchContext>,
 }
 
 /// Collection of decoded parameters from matched conditions
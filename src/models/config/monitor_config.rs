@@ -3,10 +3,13 @@
 //! This module implements the ConfigLoader trait for Monitor configurations,
 //! allowing monitors to be loaded from JSON files.
 
-use std::{collections::HashMap, fs, path::Path};
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
 
 use crate::{
-	models::{config::error::ConfigError, ConfigLoader, Monitor},
+	models::{
+		config::{error::ConfigError, trigger_config::validate_alert_policy},
+		ConfigLoader, Monitor,
+	},
 	utils::validate_script_config,
 };
 
@@ -163,6 +166,42 @@ impl ConfigLoader for Monitor {
 			)?;
 		}
 
+		if let Some(alert_policy) = &self.alert_policy {
+			validate_alert_policy(alert_policy)?;
+		}
+
+		// Validate report schedules
+		for report in &self.reports {
+			if let Err(e) = cron::Schedule::from_str(&report.cron_schedule) {
+				return Err(ConfigError::validation_error(
+					format!("Invalid report cron_schedule: {}", e),
+					None,
+					None,
+				));
+			}
+			if report.triggers.is_empty() {
+				return Err(ConfigError::validation_error(
+					"Report must have at least one trigger",
+					None,
+					None,
+				));
+			}
+			if report.period_ms == 0 {
+				return Err(ConfigError::validation_error(
+					"Report period_ms must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if report.top == 0 {
+				return Err(ConfigError::validation_error(
+					"Report top must be at least 1",
+					None,
+					None,
+				));
+			}
+		}
+
 		Ok(())
 	}
 }
@@ -171,8 +210,8 @@ impl ConfigLoader for Monitor {
 mod tests {
 	use super::*;
 	use crate::models::core::{
-		AddressWithABI, EventCondition, FunctionCondition, MatchConditions, ScriptLanguage,
-		TransactionCondition, TransactionStatus, TriggerConditions,
+		AddressWithABI, EventCondition, FunctionCondition, MatchConditions, ReportSchedule,
+		ScriptLanguage, TransactionCondition, TransactionStatus, TriggerConditions,
 	};
 	use std::collections::HashMap;
 	use tempfile::TempDir;
@@ -342,6 +381,10 @@ mod tests {
 			},
 			trigger_conditions: vec![],
 			triggers: vec!["trigger1".to_string()],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		};
 
 		assert!(valid_monitor.validate().is_ok());
@@ -358,6 +401,10 @@ mod tests {
 			},
 			trigger_conditions: vec![],
 			triggers: vec![],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		};
 
 		assert!(invalid_monitor.validate().is_err());
@@ -404,6 +451,10 @@ mod tests {
 				language: ScriptLanguage::Python,
 			}],
 			triggers: vec![],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		};
 
 		assert!(valid_monitor.validate().is_ok());
@@ -431,6 +482,10 @@ mod tests {
 				language: ScriptLanguage::Python,
 			}],
 			triggers: vec![],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		};
 		assert!(invalid_monitor.validate().is_err());
 	}
@@ -463,6 +518,10 @@ mod tests {
 				language: ScriptLanguage::Python,
 			}],
 			triggers: vec![],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		};
 		assert!(invalid_monitor.validate().is_err());
 
@@ -511,6 +570,10 @@ mod tests {
 					language: language.clone(),
 				}],
 				triggers: vec![],
+				two_phase_alerts: false,
+				alert_policy: None,
+				reports: vec![],
+				severity: None,
 			};
 			assert!(monitor.validate().is_ok());
 
@@ -532,6 +595,49 @@ mod tests {
 
 		// TempDir will automatically clean up when dropped
 	}
+
+	#[test]
+	fn test_validate_monitor_reports() {
+		let report: ReportSchedule = serde_json::from_str(
+			r#"{"cron_schedule": "0 0 9 * * Mon", "triggers": ["email_reports"]}"#,
+		)
+		.unwrap();
+		assert_eq!(report.period_ms, 86_400_000);
+		assert_eq!(report.top, 5);
+
+		let mut monitor = Monitor {
+			name: "TestMonitor".to_string(),
+			networks: vec!["ethereum_mainnet".to_string()],
+			triggers: vec!["trigger1".to_string()],
+			reports: vec![report.clone()],
+			severity: None,
+			..Default::default()
+		};
+		assert!(monitor.validate().is_ok());
+
+		for invalid in [
+			ReportSchedule {
+				cron_schedule: "every monday".to_string(),
+				..report.clone()
+			},
+			ReportSchedule {
+				triggers: vec![],
+				..report.clone()
+			},
+			ReportSchedule {
+				period_ms: 0,
+				..report.clone()
+			},
+			ReportSchedule {
+				top: 0,
+				..report.clone()
+			},
+		] {
+			monitor.reports = vec![invalid];
+			assert!(monitor.validate().is_err());
+		}
+	}
+
 	#[test]
 	fn test_invalid_load_from_path() {
 		let path = Path::new("config/monitors/invalid.json");
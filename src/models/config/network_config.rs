@@ -133,6 +133,7 @@ impl ConfigLoader for Network {
 	/// - At least one RPC URL is specified
 	/// - Required chain-specific parameters are present
 	/// - Block time and confirmation values are reasonable
+	/// - The explorer URL, if any, is an HTTP URL
 	fn validate(&self) -> Result<(), ConfigError> {
 		// Validate network name
 		if self.name.is_empty() {
@@ -260,6 +261,17 @@ impl ConfigLoader for Network {
 			}
 		}
 
+		// Validate explorer URL format
+		if let Some(explorer_url) = &self.explorer_url {
+			if !explorer_url.starts_with("http://") && !explorer_url.starts_with("https://") {
+				return Err(ConfigError::validation_error(
+					"Explorer URL must start with http:// or https://",
+					None,
+					None,
+				));
+			}
+		}
+
 		Ok(())
 	}
 }
@@ -277,6 +289,7 @@ mod tests {
 			chain_id: Some(1),
 			network_passphrase: None,
 			store_blocks: Some(true),
+			explorer_url: None,
 			rpc_urls: vec![RpcUrl {
 				type_: "rpc".to_string(),
 				url: "https://test.network".to_string(),
@@ -402,6 +415,18 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn test_validate_explorer_url() {
+		let mut network = create_valid_network();
+		network.explorer_url = Some("https://etherscan.io".to_string());
+		assert!(network.validate().is_ok());
+		network.explorer_url = Some("etherscan.io".to_string());
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
 	#[test]
 	fn test_validate_empty_cron_schedule() {
 		let mut network = create_valid_network();
@@ -5,11 +5,19 @@
 
 use email_address::EmailAddress;
 use serde::Deserialize;
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+	collections::{HashMap, HashSet},
+	fs,
+	path::Path,
+};
 
 use crate::{
-	models::{config::error::ConfigError, ConfigLoader, Trigger, TriggerType, TriggerTypeConfig},
-	utils::validate_script_config,
+	models::{
+		config::error::ConfigError, AlertPolicy, ConfigLoader, FileColumn, FileFormat,
+		FileRotation, KafkaSasl, KafkaTls, Trigger, TriggerType, TriggerTypeConfig, WebhookPayload,
+		WebhookPayloadFormat,
+	},
+	utils::{compile_json_template, validate_script_config, Template},
 };
 
 /// File structure for trigger configuration files
@@ -134,6 +142,9 @@ impl ConfigLoader for Trigger {
 	/// - Required configuration fields for the trigger type are present
 	/// - URLs are valid for webhook and Slack triggers
 	/// - Script paths exist for script triggers
+	/// - Kafka brokers are `host:port` addresses and TLS files exist for Kafka triggers
+	/// - File triggers have templates matching their format and a valid rotation
+	/// - Message templates compile
 	fn validate(&self) -> Result<(), ConfigError> {
 		// Validate trigger name
 		if self.name.is_empty() {
@@ -146,7 +157,12 @@ impl ConfigLoader for Trigger {
 
 		match &self.trigger_type {
 			TriggerType::Slack => {
-				if let TriggerTypeConfig::Slack { slack_url, message } = &self.config {
+				if let TriggerTypeConfig::Slack {
+					slack_url,
+					message,
+					blocks,
+				} = &self.config
+				{
 					// Validate webhook URL
 					if !slack_url.starts_with("https://hooks.slack.com/") {
 						return Err(ConfigError::validation_error(
@@ -171,6 +187,23 @@ impl ConfigLoader for Trigger {
 							None,
 						));
 					}
+					// Validate Block Kit template
+					if let Some(blocks) = blocks {
+						if !blocks.is_array() {
+							return Err(ConfigError::validation_error(
+								"Slack blocks must be an array",
+								None,
+								None,
+							));
+						}
+						compile_json_template(blocks).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Slack blocks template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
 				}
 			}
 			TriggerType::Email => {
@@ -319,6 +352,8 @@ impl ConfigLoader for Trigger {
 					url,
 					method,
 					message,
+					secrets,
+					payload,
 					..
 				} = &self.config
 				{
@@ -358,6 +393,20 @@ impl ConfigLoader for Trigger {
 							None,
 						));
 					}
+					if secrets
+						.iter()
+						.flatten()
+						.any(|secret| secret.trim().is_empty())
+					{
+						return Err(ConfigError::validation_error(
+							"Webhook secrets cannot be empty",
+							None,
+							None,
+						));
+					}
+					if let Some(payload) = payload {
+						validate_webhook_payload(payload)?;
+					}
 				}
 			}
 			TriggerType::Telegram => {
@@ -427,7 +476,7 @@ impl ConfigLoader for Trigger {
 				if let TriggerTypeConfig::Discord {
 					discord_url,
 					message,
-					..
+					embed,
 				} = &self.config
 				{
 					// Validate webhook URL
@@ -453,6 +502,300 @@ impl ConfigLoader for Trigger {
 							None,
 						));
 					}
+					// Validate embed template
+					if let Some(embed) = embed {
+						if !embed.is_object() {
+							return Err(ConfigError::validation_error(
+								"Discord embed must be an object",
+								None,
+								None,
+							));
+						}
+						compile_json_template(embed).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Discord embed template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+				}
+			}
+			TriggerType::Teams => {
+				if let TriggerTypeConfig::Teams {
+					teams_url,
+					message,
+					card,
+				} = &self.config
+				{
+					// Validate webhook URL
+					let valid = url::Url::parse(teams_url)
+						.map(|url| url.scheme() == "https")
+						.unwrap_or(false);
+					if !valid {
+						return Err(ConfigError::validation_error(
+							"Invalid Teams webhook URL format",
+							None,
+							None,
+						));
+					}
+					// Validate message
+					if message.title.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Title cannot be empty",
+							None,
+							None,
+						));
+					}
+					if message.body.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Body cannot be empty",
+							None,
+							None,
+						));
+					}
+					// Validate card template
+					if let Some(card) = card {
+						if !card.is_object() {
+							return Err(ConfigError::validation_error(
+								"Teams card must be an object",
+								None,
+								None,
+							));
+						}
+						compile_json_template(card).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Teams card template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+				}
+			}
+			TriggerType::Mattermost => {
+				if let TriggerTypeConfig::Mattermost {
+					mattermost_url,
+					message,
+					attachments,
+				} = &self.config
+				{
+					// Validate webhook URL
+					let valid = url::Url::parse(mattermost_url)
+						.map(|url| {
+							matches!(url.scheme(), "http" | "https")
+								&& url.path().contains("/hooks/")
+						})
+						.unwrap_or(false);
+					if !valid {
+						return Err(ConfigError::validation_error(
+							"Invalid Mattermost webhook URL format",
+							None,
+							None,
+						));
+					}
+					// Validate message
+					if message.title.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Title cannot be empty",
+							None,
+							None,
+						));
+					}
+					if message.body.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Body cannot be empty",
+							None,
+							None,
+						));
+					}
+					// Validate attachments template
+					if let Some(attachments) = attachments {
+						if !attachments.is_array() {
+							return Err(ConfigError::validation_error(
+								"Mattermost attachments must be an array",
+								None,
+								None,
+							));
+						}
+						compile_json_template(attachments).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Mattermost attachments template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+				}
+			}
+			TriggerType::PagerDuty => {
+				if let TriggerTypeConfig::PagerDuty {
+					routing_key,
+					message,
+					dedup_key,
+					custom_details,
+					api_url,
+					..
+				} = &self.config
+				{
+					if routing_key.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"PagerDuty routing key cannot be empty",
+							None,
+							None,
+						));
+					}
+					if message.title.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Title cannot be empty",
+							None,
+							None,
+						));
+					}
+					if message.body.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Body cannot be empty",
+							None,
+							None,
+						));
+					}
+					if let Some(custom_details) = custom_details {
+						if !custom_details.is_object() {
+							return Err(ConfigError::validation_error(
+								"PagerDuty custom details must be an object",
+								None,
+								None,
+							));
+						}
+						compile_json_template(custom_details).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid PagerDuty custom details template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+					validate_incident_trigger(
+						"PagerDuty",
+						dedup_key.as_deref(),
+						api_url.as_deref(),
+					)?;
+				}
+			}
+			TriggerType::Opsgenie => {
+				if let TriggerTypeConfig::Opsgenie {
+					api_key,
+					message,
+					dedup_key,
+					details,
+					api_url,
+					..
+				} = &self.config
+				{
+					if api_key.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Opsgenie API key cannot be empty",
+							None,
+							None,
+						));
+					}
+					if message.title.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Title cannot be empty",
+							None,
+							None,
+						));
+					}
+					if message.body.trim().is_empty() {
+						return Err(ConfigError::validation_error(
+							"Body cannot be empty",
+							None,
+							None,
+						));
+					}
+					for source in details.iter().flat_map(|details| details.values()) {
+						Template::compile(source).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Opsgenie details template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+					validate_incident_trigger(
+						"Opsgenie",
+						dedup_key.as_deref(),
+						api_url.as_deref(),
+					)?;
+				}
+			}
+			TriggerType::Kafka => {
+				if let TriggerTypeConfig::Kafka {
+					brokers,
+					topic,
+					key,
+					headers,
+					payload,
+					timeout_ms,
+					sasl,
+					tls,
+					..
+				} = &self.config
+				{
+					validate_kafka_connection(brokers, *timeout_ms, sasl.as_ref(), tls.as_ref())?;
+					let valid_topic = !topic.is_empty()
+						&& topic.len() <= 249
+						&& topic != "." && topic != ".."
+						&& topic
+							.chars()
+							.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+					if !valid_topic {
+						return Err(ConfigError::validation_error(
+							format!("Invalid Kafka topic: {}", topic),
+							None,
+							None,
+						));
+					}
+					let templates = key
+						.iter()
+						.chain(headers.iter().flat_map(|headers| headers.values()));
+					for source in templates {
+						Template::compile(source).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Kafka key or header template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+					if let Some(payload) = payload {
+						compile_json_template(payload).map_err(|e| {
+							ConfigError::validation_error(
+								format!("Invalid Kafka payload template: {}", e),
+								None,
+								None,
+							)
+						})?;
+					}
+				}
+			}
+			TriggerType::File => {
+				if let TriggerTypeConfig::File {
+					path,
+					format,
+					payload,
+					columns,
+					rotation,
+					..
+				} = &self.config
+				{
+					validate_file_sink(
+						path,
+						format.unwrap_or_default(),
+						payload.as_ref(),
+						columns.as_deref(),
+						rotation.as_ref(),
+					)?;
 				}
 			}
 			TriggerType::Script => {
@@ -467,16 +810,405 @@ impl ConfigLoader for Trigger {
 				}
 			}
 		}
+
+		match &self.config {
+			TriggerTypeConfig::Slack { message, .. }
+			| TriggerTypeConfig::Email { message, .. }
+			| TriggerTypeConfig::Webhook { message, .. }
+			| TriggerTypeConfig::Telegram { message, .. }
+			| TriggerTypeConfig::Discord { message, .. }
+			| TriggerTypeConfig::Teams { message, .. }
+			| TriggerTypeConfig::Mattermost { message, .. }
+			| TriggerTypeConfig::PagerDuty { message, .. }
+			| TriggerTypeConfig::Opsgenie { message, .. } => {
+				Template::compile(&message.body).map_err(|e| {
+					ConfigError::validation_error(
+						format!("Invalid message template: {}", e),
+						None,
+						None,
+					)
+				})?;
+			}
+			TriggerTypeConfig::Kafka { .. }
+			| TriggerTypeConfig::File { .. }
+			| TriggerTypeConfig::Script { .. } => {}
+		}
+
+		if let Some(retry) = &self.retry {
+			if retry.max_attempts == 0 {
+				return Err(ConfigError::validation_error(
+					"Retry max_attempts must be at least 1",
+					None,
+					None,
+				));
+			}
+			if retry.initial_backoff_ms > retry.max_backoff_ms {
+				return Err(ConfigError::validation_error(
+					"Retry initial_backoff_ms cannot exceed max_backoff_ms",
+					None,
+					None,
+				));
+			}
+		}
+
+		if let Some(alert_policy) = &self.alert_policy {
+			validate_alert_policy(alert_policy)?;
+		}
+
+		if let Some(digest) = &self.digest {
+			if self.trigger_type == TriggerType::Script {
+				return Err(ConfigError::validation_error(
+					"Digests are not supported by script triggers",
+					None,
+					None,
+				));
+			}
+			if self.trigger_type == TriggerType::Kafka {
+				return Err(ConfigError::validation_error(
+					"Digests are not supported by Kafka triggers",
+					None,
+					None,
+				));
+			}
+			if self.trigger_type == TriggerType::File {
+				return Err(ConfigError::validation_error(
+					"Digests are not supported by file triggers",
+					None,
+					None,
+				));
+			}
+			if digest.window_ms == Some(0) {
+				return Err(ConfigError::validation_error(
+					"Digest window_ms must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if digest.max_items == 0 {
+				return Err(ConfigError::validation_error(
+					"Digest max_items must be at least 1",
+					None,
+					None,
+				));
+			}
+		}
 		Ok(())
 	}
 }
 
+/// Validates an alert policy of a monitor or trigger
+pub(super) fn validate_alert_policy(policy: &AlertPolicy) -> Result<(), ConfigError> {
+	if policy.dedup_key.trim().is_empty() {
+		return Err(ConfigError::validation_error(
+			"Alert policy dedup_key cannot be empty",
+			None,
+			None,
+		));
+	}
+	Template::compile(&policy.dedup_key).map_err(|e| {
+		ConfigError::validation_error(
+			format!("Invalid alert policy dedup_key template: {}", e),
+			None,
+			None,
+		)
+	})?;
+	if policy.max_alerts == Some(0) {
+		return Err(ConfigError::validation_error(
+			"Alert policy max_alerts must be at least 1",
+			None,
+			None,
+		));
+	}
+	if policy.max_alerts.is_some() && policy.window_ms == 0 {
+		return Err(ConfigError::validation_error(
+			"Alert policy window_ms must be greater than 0",
+			None,
+			None,
+		));
+	}
+	Ok(())
+}
+
+/// Validates the dedup key template and API URL of a PagerDuty or Opsgenie trigger
+fn validate_incident_trigger(
+	service: &str,
+	dedup_key: Option<&str>,
+	api_url: Option<&str>,
+) -> Result<(), ConfigError> {
+	if let Some(dedup_key) = dedup_key {
+		if dedup_key.trim().is_empty() {
+			return Err(ConfigError::validation_error(
+				format!("{} dedup key cannot be empty", service),
+				None,
+				None,
+			));
+		}
+		Template::compile(dedup_key).map_err(|e| {
+			ConfigError::validation_error(
+				format!("Invalid {} dedup key template: {}", service, e),
+				None,
+				None,
+			)
+		})?;
+	}
+	if let Some(api_url) = api_url {
+		let valid = url::Url::parse(api_url)
+			.map(|url| matches!(url.scheme(), "http" | "https"))
+			.unwrap_or(false);
+		if !valid {
+			return Err(ConfigError::validation_error(
+				format!("Invalid {} API URL", service),
+				None,
+				None,
+			));
+		}
+	}
+	Ok(())
+}
+
+/// Validates the brokers, timeout, SASL and TLS options of a Kafka trigger
+fn validate_kafka_connection(
+	brokers: &[String],
+	timeout_ms: Option<u32>,
+	sasl: Option<&KafkaSasl>,
+	tls: Option<&KafkaTls>,
+) -> Result<(), ConfigError> {
+	if brokers.is_empty() {
+		return Err(ConfigError::validation_error(
+			"Kafka brokers cannot be empty",
+			None,
+			None,
+		));
+	}
+	for broker in brokers {
+		let valid = broker
+			.rsplit_once(':')
+			.is_some_and(|(host, port)| !host.trim().is_empty() && port.parse::<u16>().is_ok());
+		if !valid {
+			return Err(ConfigError::validation_error(
+				format!(
+					"Invalid Kafka broker address, expected host:port: {}",
+					broker
+				),
+				None,
+				None,
+			));
+		}
+	}
+	if timeout_ms == Some(0) {
+		return Err(ConfigError::validation_error(
+			"Kafka timeout_ms must be greater than 0",
+			None,
+			None,
+		));
+	}
+	if let Some(sasl) = sasl {
+		if sasl.username.is_empty() || sasl.password.is_empty() {
+			return Err(ConfigError::validation_error(
+				"Kafka SASL username and password cannot be empty",
+				None,
+				None,
+			));
+		}
+	}
+	if let Some(tls) = tls {
+		if tls.cert_path.is_some() != tls.key_path.is_some() {
+			return Err(ConfigError::validation_error(
+				"Kafka TLS cert_path and key_path must be set together",
+				None,
+				None,
+			));
+		}
+		let paths = [&tls.ca_path, &tls.cert_path, &tls.key_path];
+		for path in paths.into_iter().flatten() {
+			if !Path::new(path).exists() {
+				return Err(ConfigError::validation_error(
+					format!("Kafka TLS file does not exist: {}", path),
+					None,
+					None,
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Validates the path, templates and rotation of a file trigger
+fn validate_file_sink(
+	path: &str,
+	format: FileFormat,
+	payload: Option<&serde_json::Value>,
+	columns: Option<&[FileColumn]>,
+	rotation: Option<&FileRotation>,
+) -> Result<(), ConfigError> {
+	if path.trim().is_empty() {
+		return Err(ConfigError::validation_error(
+			"File path cannot be empty",
+			None,
+			None,
+		));
+	}
+	if Path::new(path).is_dir() {
+		return Err(ConfigError::validation_error(
+			format!("File path is a directory: {}", path),
+			None,
+			None,
+		));
+	}
+
+	match format {
+		FileFormat::Jsonl => {
+			if columns.is_some() {
+				return Err(ConfigError::validation_error(
+					"File columns are only supported by the csv format",
+					None,
+					None,
+				));
+			}
+			if let Some(payload) = payload {
+				compile_json_template(payload).map_err(|e| {
+					ConfigError::validation_error(
+						format!("Invalid file payload template: {}", e),
+						None,
+						None,
+					)
+				})?;
+			}
+		}
+		FileFormat::Csv => {
+			if payload.is_some() {
+				return Err(ConfigError::validation_error(
+					"File payload is only supported by the jsonl format",
+					None,
+					None,
+				));
+			}
+			let columns = columns.unwrap_or_default();
+			if columns.is_empty() {
+				return Err(ConfigError::validation_error(
+					"File columns cannot be empty for the csv format",
+					None,
+					None,
+				));
+			}
+			let mut names = HashSet::new();
+			for column in columns {
+				if column.name.trim().is_empty() || !names.insert(column.name.as_str()) {
+					return Err(ConfigError::validation_error(
+						format!("Invalid or duplicate file column name: '{}'", column.name),
+						None,
+						None,
+					));
+				}
+				Template::compile(&column.value).map_err(|e| {
+					ConfigError::validation_error(
+						format!("Invalid file column template {}: {}", column.name, e),
+						None,
+						None,
+					)
+				})?;
+			}
+		}
+	}
+
+	if let Some(rotation) = rotation {
+		if rotation.max_size_bytes == Some(0) {
+			return Err(ConfigError::validation_error(
+				"File rotation max_size_bytes must be greater than 0",
+				None,
+				None,
+			));
+		}
+		if rotation.max_size_bytes.is_none() && rotation.interval.is_none() {
+			return Err(ConfigError::validation_error(
+				"File rotation requires max_size_bytes or interval",
+				None,
+				None,
+			));
+		}
+	}
+	Ok(())
+}
+
+/// Validates the payload of a webhook trigger
+fn validate_webhook_payload(payload: &WebhookPayload) -> Result<(), ConfigError> {
+	let compile = |source: &str| {
+		Template::compile(source).map_err(|e| {
+			ConfigError::validation_error(
+				format!("Invalid webhook payload template: {}", e),
+				None,
+				None,
+			)
+		})
+	};
+
+	match (&payload.format, &payload.template) {
+		(WebhookPayloadFormat::Match, _) => {}
+		(_, None) => {
+			return Err(ConfigError::validation_error(
+				"Webhook payload template is required for the json and form formats",
+				None,
+				None,
+			));
+		}
+		(WebhookPayloadFormat::Form, Some(serde_json::Value::Object(fields))) => {
+			if fields
+				.values()
+				.any(|value| value.is_object() || value.is_array())
+			{
+				return Err(ConfigError::validation_error(
+					"Webhook form payload template must be a flat object",
+					None,
+					None,
+				));
+			}
+		}
+		(WebhookPayloadFormat::Form, Some(_)) => {
+			return Err(ConfigError::validation_error(
+				"Webhook form payload template must be an object",
+				None,
+				None,
+			));
+		}
+		(WebhookPayloadFormat::Json, Some(_)) => {}
+	}
+
+	if let Some(template) = &payload.template {
+		compile_json_template(template).map_err(|e| {
+			ConfigError::validation_error(
+				format!("Invalid webhook payload template: {}", e),
+				None,
+				None,
+			)
+		})?;
+	}
+	for source in payload
+		.query
+		.iter()
+		.flat_map(|query| query.values())
+		.chain(&payload.path)
+	{
+		compile(source)?;
+	}
+
+	if let Some(content_type) = &payload.content_type {
+		if content_type.trim().is_empty() || content_type.chars().any(char::is_control) {
+			return Err(ConfigError::validation_error(
+				"Invalid webhook payload content type",
+				None,
+				None,
+			));
+		}
+	}
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::models::{
 		core::{Trigger, TriggerType},
-		NotificationMessage, ScriptLanguage,
+		DigestPolicy, FileSync, KafkaAcks, NotificationMessage, ScriptLanguage,
 	};
 	use std::{fs::File, io::Write, os::unix::fs::PermissionsExt};
 	use tempfile::TempDir;
@@ -492,7 +1224,11 @@ mod tests {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(valid_trigger.validate().is_ok());
 
@@ -506,7 +1242,11 @@ mod tests {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_webhook.validate().is_err());
 
@@ -520,7 +1260,11 @@ mod tests {
 					title: "".to_string(),
 					body: "Test message".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(empty_title.validate().is_err());
 
@@ -534,7 +1278,11 @@ mod tests {
 					title: "Alert".to_string(),
 					body: "".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(empty_body.validate().is_err());
 	}
@@ -556,6 +1304,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(valid_trigger.validate().is_ok());
 
@@ -575,6 +1326,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_host.validate().is_err());
 
@@ -594,6 +1348,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(empty_host.validate().is_err());
 
@@ -613,6 +1370,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("invalid-email"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_email.validate().is_err());
 
@@ -631,6 +1391,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_trigger.validate().is_err());
 
@@ -649,6 +1412,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_trigger.validate().is_err());
 
@@ -668,6 +1434,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(empty_title.validate().is_err());
 
@@ -687,6 +1456,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_title.validate().is_err());
 
@@ -706,6 +1478,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_title.validate().is_err());
 
@@ -725,6 +1500,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_body.validate().is_err());
 
@@ -744,6 +1522,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(empty_body.validate().is_err());
 
@@ -763,6 +1544,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(empty_username.validate().is_err());
 
@@ -782,6 +1566,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("recipient@example.com")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_control_characters.validate().is_err());
 
@@ -801,6 +1588,9 @@ mod tests {
 				sender: EmailAddress::new_unchecked("sender@example.com"),
 				recipients: vec![EmailAddress::new_unchecked("invalid-email")],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_recipient.validate().is_err());
 	}
@@ -813,13 +1603,18 @@ mod tests {
 			config: TriggerTypeConfig::Webhook {
 				url: "https://api.example.com/webhook".to_string(),
 				secret: None,
+				secrets: None,
 				method: Some("POST".to_string()),
 				headers: None,
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(valid_trigger.validate().is_ok());
 
@@ -832,11 +1627,16 @@ mod tests {
 				method: Some("POST".to_string()),
 				headers: None,
 				secret: None,
+				secrets: None,
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_url.validate().is_err());
 
@@ -849,11 +1649,16 @@ mod tests {
 				method: Some("INVALID".to_string()),
 				headers: None,
 				secret: None,
+				secrets: None,
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_method.validate().is_err());
 
@@ -866,11 +1671,16 @@ mod tests {
 				method: Some("POST".to_string()),
 				headers: None,
 				secret: None,
+				secrets: None,
 				message: NotificationMessage {
 					title: "".to_string(),
 					body: "Test message".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_title_message.validate().is_err());
 
@@ -882,13 +1692,33 @@ mod tests {
 				method: Some("POST".to_string()),
 				headers: None,
 				secret: None,
+				secrets: None,
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_body_message.validate().is_err());
+
+		// Test rotated secrets
+		let mut rotated_secrets = valid_trigger.clone();
+		if let TriggerTypeConfig::Webhook {
+			secret, secrets, ..
+		} = &mut rotated_secrets.config
+		{
+			*secret = Some("new-secret".to_string());
+			*secrets = Some(vec!["old-secret".to_string()]);
+		}
+		assert!(rotated_secrets.validate().is_ok());
+		if let TriggerTypeConfig::Webhook { secrets, .. } = &mut rotated_secrets.config {
+			*secrets = Some(vec![" ".to_string()]);
+		}
+		assert!(rotated_secrets.validate().is_err());
 	}
 
 	#[test]
@@ -902,7 +1732,11 @@ mod tests {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				embed: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(valid_trigger.validate().is_ok());
 
@@ -916,7 +1750,11 @@ mod tests {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
 				},
+				embed: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_webhook.validate().is_err());
 
@@ -930,7 +1768,11 @@ mod tests {
 					title: "".to_string(),
 					body: "test".to_string(),
 				},
+				embed: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_title_message.validate().is_err());
 
@@ -943,7 +1785,11 @@ mod tests {
 					title: "test".to_string(),
 					body: "".to_string(),
 				},
+				embed: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_body_message.validate().is_err());
 	}
@@ -962,6 +1808,9 @@ mod tests {
 					body: "Test Body".to_string(),
 				},
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(valid_trigger.validate().is_ok());
 
@@ -978,6 +1827,9 @@ mod tests {
 					body: "Test Body".to_string(),
 				},
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		assert!(invalid_token.validate().is_err());
@@ -995,6 +1847,9 @@ mod tests {
 					body: "Test Body".to_string(),
 				},
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_chat_id.validate().is_err());
 
@@ -1011,6 +1866,9 @@ mod tests {
 					body: "test".to_string(),
 				},
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_title_message.validate().is_err());
 
@@ -1026,6 +1884,9 @@ mod tests {
 					body: "".to_string(),
 				},
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		assert!(invalid_body_message.validate().is_err());
@@ -1046,6 +1907,9 @@ mod tests {
 				language: ScriptLanguage::Bash,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(valid_trigger.validate().is_ok());
 
@@ -1059,12 +1923,586 @@ mod tests {
 				language: ScriptLanguage::Python,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 		assert!(invalid_path.validate().is_err());
 
 		std::fs::remove_file(script_path).unwrap();
 	}
 
+	#[test]
+	fn test_retry_policy_validation() {
+		let trigger: Trigger = serde_json::from_str(
+			r#"{
+				"name": "test_slack",
+				"trigger_type": "slack",
+				"config": {
+					"slack_url": "https://hooks.slack.com/services/xxx",
+					"message": {"title": "Alert", "body": "Test message"}
+				},
+				"retry": {"max_attempts": 3, "retryable_statuses": [503]}
+			}"#,
+		)
+		.unwrap();
+		let retry = trigger.retry.clone().unwrap();
+		assert_eq!(retry.max_attempts, 3);
+		assert_eq!(retry.initial_backoff_ms, 1000);
+		assert!(retry.is_retryable_status(503));
+		assert!(!retry.is_retryable_status(500));
+		assert_eq!(retry.backoff(1), std::time::Duration::from_secs(1));
+		assert_eq!(retry.backoff(3), std::time::Duration::from_secs(4));
+		assert_eq!(retry.backoff(64), std::time::Duration::from_secs(300));
+		assert!(trigger.validate().is_ok());
+
+		let mut no_attempts = trigger.clone();
+		no_attempts.retry = Some(crate::models::RetryPolicy {
+			max_attempts: 0,
+			..retry.clone()
+		});
+		assert!(no_attempts.validate().is_err());
+
+		let mut inverted_backoff = trigger;
+		inverted_backoff.retry = Some(crate::models::RetryPolicy {
+			initial_backoff_ms: 10_000,
+			max_backoff_ms: 1_000,
+			..retry
+		});
+		assert!(inverted_backoff.validate().is_err());
+	}
+
+	#[test]
+	fn test_alert_policy_validation() {
+		let policy: AlertPolicy =
+			serde_json::from_str(r#"{"cooldown_ms": 60000, "max_alerts": 10}"#).unwrap();
+		assert_eq!(policy.dedup_key, "${monitor_name}");
+		assert_eq!(policy.window_ms, 60_000);
+		assert!(validate_alert_policy(&policy).is_ok());
+
+		for invalid in [
+			AlertPolicy {
+				dedup_key: " ".to_string(),
+				..policy.clone()
+			},
+			AlertPolicy {
+				max_alerts: Some(0),
+				..policy.clone()
+			},
+			AlertPolicy {
+				window_ms: 0,
+				..policy.clone()
+			},
+			AlertPolicy {
+				dedup_key: "{{#if monitor_name}}".to_string(),
+				..policy.clone()
+			},
+		] {
+			assert!(validate_alert_policy(&invalid).is_err());
+		}
+
+		// The window only matters with a rate limit
+		assert!(validate_alert_policy(&AlertPolicy {
+			max_alerts: None,
+			window_ms: 0,
+			..policy
+		})
+		.is_ok());
+	}
+
+	#[test]
+	fn test_digest_validation() {
+		let mut trigger: Trigger = serde_json::from_str(
+			r#"{
+				"name": "digest",
+				"trigger_type": "slack",
+				"config": {
+					"slack_url": "https://hooks.slack.com/services/xxx",
+					"message": {
+						"title": "${match_count} matches",
+						"body": "${#matches}- ${transaction_hash}\n${/matches}"
+					}
+				},
+				"digest": {"window_ms": 300000}
+			}"#,
+		)
+		.unwrap();
+		let digest = trigger.digest.clone().unwrap();
+		assert_eq!(digest.window_ms, Some(300_000));
+		assert_eq!(digest.max_items, 20);
+		assert!(trigger.validate().is_ok());
+
+		trigger.digest = Some(DigestPolicy {
+			window_ms: Some(0),
+			..digest.clone()
+		});
+		assert!(trigger.validate().is_err());
+
+		trigger.digest = Some(DigestPolicy {
+			max_items: 0,
+			..digest.clone()
+		});
+		assert!(trigger.validate().is_err());
+
+		let temp_dir = TempDir::new().unwrap();
+		let script_path = temp_dir.path().join("script.sh");
+		std::fs::write(&script_path, "#!/bin/bash\necho 'test'").unwrap();
+		let mut script_trigger = Trigger {
+			name: "test_script".to_string(),
+			trigger_type: TriggerType::Script,
+			config: TriggerTypeConfig::Script {
+				script_path: script_path.to_str().unwrap().to_string(),
+				arguments: None,
+				language: ScriptLanguage::Bash,
+				timeout_ms: 1000,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		};
+		assert!(script_trigger.validate().is_ok());
+		script_trigger.digest = Some(digest);
+		assert!(script_trigger.validate().is_err());
+	}
+
+	#[test]
+	fn test_message_template_validation() {
+		let mut trigger: Trigger = serde_json::from_str(
+			r#"{
+				"name": "templated",
+				"trigger_type": "webhook",
+				"config": {
+					"url": "https://webhook.example.com",
+					"message": {
+						"title": "Alert",
+						"body": "{{#each events}}{{ signature }}: {{ value | format_units(6) }}{{/each}}"
+					}
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(trigger.validate().is_ok());
+
+		if let TriggerTypeConfig::Webhook { message, .. } = &mut trigger.config {
+			message.body = "{{#each events}}{{ value | money }}".to_string();
+		}
+		let error = trigger.validate().unwrap_err();
+		assert!(error.to_string().contains("Invalid message template"));
+	}
+
+	#[test]
+	fn test_webhook_payload_validation() {
+		let mut trigger: Trigger = serde_json::from_str(
+			r#"{
+				"name": "payload",
+				"trigger_type": "webhook",
+				"config": {
+					"url": "https://api.example.com",
+					"message": {
+						"title": "Alert",
+						"body": "Alert"
+					},
+					"payload": {
+						"template": {
+							"summary": "{{ monitor_name }}",
+							"amount": "{{ event_0_value | number }}",
+							"tags": ["{{ network_slug }}"]
+						},
+						"query": { "source": "{{ monitor_name }}" },
+						"path": "alerts/{{ network_slug }}"
+					}
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(trigger.validate().is_ok());
+
+		let TriggerTypeConfig::Webhook {
+			payload: Some(payload),
+			..
+		} = &mut trigger.config
+		else {
+			panic!("Expected a webhook payload");
+		};
+		payload.template = Some(serde_json::json!({ "tags": ["{{ value | money }}"] }));
+		let error = trigger.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Invalid webhook payload template"));
+
+		let mut invalid = trigger.clone();
+		if let TriggerTypeConfig::Webhook {
+			payload: Some(payload),
+			..
+		} = &mut invalid.config
+		{
+			payload.format = WebhookPayloadFormat::Form;
+			payload.template = Some(serde_json::json!({ "tags": ["a"] }));
+		}
+		let error = invalid.validate().unwrap_err();
+		assert!(error.to_string().contains("must be a flat object"));
+
+		if let TriggerTypeConfig::Webhook {
+			payload: Some(payload),
+			..
+		} = &mut invalid.config
+		{
+			payload.format = WebhookPayloadFormat::Json;
+			payload.template = None;
+		}
+		let error = invalid.validate().unwrap_err();
+		assert!(error.to_string().contains("template is required"));
+
+		if let TriggerTypeConfig::Webhook {
+			payload: Some(payload),
+			..
+		} = &mut invalid.config
+		{
+			payload.format = WebhookPayloadFormat::Match;
+		}
+		assert!(invalid.validate().is_ok());
+	}
+
+	#[test]
+	fn test_rich_layout_validation() {
+		let mut slack: Trigger = serde_json::from_str(
+			r#"{
+				"name": "slack_blocks",
+				"trigger_type": "slack",
+				"config": {
+					"slack_url": "https://hooks.slack.com/services/A/B/C",
+					"message": { "title": "Alert", "body": "Alert" },
+					"blocks": [
+						{ "type": "section", "text": { "type": "mrkdwn", "text": "{{ value }}" } }
+					]
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(slack.validate().is_ok());
+
+		if let TriggerTypeConfig::Slack { blocks, .. } = &mut slack.config {
+			*blocks = Some(serde_json::json!({ "type": "section" }));
+		}
+		let error = slack.validate().unwrap_err();
+		assert!(error.to_string().contains("Slack blocks must be an array"));
+
+		if let TriggerTypeConfig::Slack { blocks, .. } = &mut slack.config {
+			*blocks = Some(serde_json::json!([{ "text": "{{#if value}}" }]));
+		}
+		let error = slack.validate().unwrap_err();
+		assert!(error.to_string().contains("Invalid Slack blocks template"));
+
+		let mut discord: Trigger = serde_json::from_str(
+			r##"{
+				"name": "discord_embed",
+				"trigger_type": "discord",
+				"config": {
+					"discord_url": "https://discord.com/api/webhooks/123/abc",
+					"message": { "title": "Alert", "body": "Alert" },
+					"embed": { "title": "{{ monitor_name }}", "color": "#ff0000" }
+				}
+			}"##,
+		)
+		.unwrap();
+		assert!(discord.validate().is_ok());
+
+		if let TriggerTypeConfig::Discord { embed, .. } = &mut discord.config {
+			*embed = Some(serde_json::json!(["{{ monitor_name }}"]));
+		}
+		let error = discord.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Discord embed must be an object"));
+
+		let mut teams: Trigger = serde_json::from_str(
+			r#"{
+				"name": "teams_card",
+				"trigger_type": "teams",
+				"config": {
+					"teams_url": "https://example.webhook.office.com/webhookb2/abc",
+					"message": { "title": "Alert", "body": "Alert" },
+					"card": { "body": [{ "type": "TextBlock", "text": "{{ monitor_name }}" }] }
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(matches!(teams.config, TriggerTypeConfig::Teams { .. }));
+		assert!(teams.validate().is_ok());
+
+		if let TriggerTypeConfig::Teams { card, .. } = &mut teams.config {
+			*card = Some(serde_json::json!({ "body": [{ "text": "{{#each value}}" }] }));
+		}
+		let error = teams.validate().unwrap_err();
+		assert!(error.to_string().contains("Invalid Teams card template"));
+
+		if let TriggerTypeConfig::Teams {
+			teams_url, card, ..
+		} = &mut teams.config
+		{
+			*card = None;
+			*teams_url = "http://example.webhook.office.com/webhookb2/abc".to_string();
+		}
+		let error = teams.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Invalid Teams webhook URL format"));
+
+		let mut mattermost: Trigger = serde_json::from_str(
+			r#"{
+				"name": "mattermost_attachments",
+				"trigger_type": "mattermost",
+				"config": {
+					"mattermost_url": "https://mattermost.example.com/hooks/abc",
+					"message": { "title": "Alert", "body": "Alert" },
+					"attachments": [{ "title": "{{ monitor_name }}" }]
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(matches!(
+			mattermost.config,
+			TriggerTypeConfig::Mattermost { .. }
+		));
+		assert!(mattermost.validate().is_ok());
+
+		if let TriggerTypeConfig::Mattermost { attachments, .. } = &mut mattermost.config {
+			*attachments = Some(serde_json::json!({ "title": "{{ monitor_name }}" }));
+		}
+		let error = mattermost.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Mattermost attachments must be an array"));
+
+		if let TriggerTypeConfig::Mattermost {
+			mattermost_url,
+			attachments,
+			..
+		} = &mut mattermost.config
+		{
+			*attachments = None;
+			*mattermost_url = "https://mattermost.example.com/api/v4/posts".to_string();
+		}
+		let error = mattermost.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Invalid Mattermost webhook URL format"));
+	}
+
+	#[test]
+	fn test_incident_trigger_validation() {
+		let mut pagerduty: Trigger = serde_json::from_str(
+			r#"{
+				"name": "pagerduty",
+				"trigger_type": "pagerduty",
+				"config": {
+					"routing_key": "routing-key",
+					"message": { "title": "Alert", "body": "Alert" },
+					"dedup_key": "{{ monitor_name }}:{{ transaction_hash }}",
+					"custom_details": { "tx": "{{ transaction_hash }}" },
+					"severity_map": { "critical": "error" }
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(matches!(
+			pagerduty.config,
+			TriggerTypeConfig::PagerDuty { .. }
+		));
+		assert!(pagerduty.validate().is_ok());
+
+		if let TriggerTypeConfig::PagerDuty { custom_details, .. } = &mut pagerduty.config {
+			*custom_details = Some(serde_json::json!("{{ transaction_hash }}"));
+		}
+		let error = pagerduty.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("PagerDuty custom details must be an object"));
+
+		if let TriggerTypeConfig::PagerDuty {
+			custom_details,
+			dedup_key,
+			..
+		} = &mut pagerduty.config
+		{
+			*custom_details = None;
+			*dedup_key = Some("{{#if value}}".to_string());
+		}
+		let error = pagerduty.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Invalid PagerDuty dedup key template"));
+
+		let mut opsgenie: Trigger = serde_json::from_str(
+			r#"{
+				"name": "opsgenie",
+				"trigger_type": "opsgenie",
+				"config": {
+					"api_key": "api-key",
+					"message": { "title": "Alert", "body": "Alert" },
+					"severity_map": { "warning": "P2" },
+					"api_url": "https://api.eu.opsgenie.com"
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(matches!(
+			opsgenie.config,
+			TriggerTypeConfig::Opsgenie { .. }
+		));
+		assert!(opsgenie.validate().is_ok());
+
+		if let TriggerTypeConfig::Opsgenie { api_url, .. } = &mut opsgenie.config {
+			*api_url = Some("api.opsgenie.com".to_string());
+		}
+		let error = opsgenie.validate().unwrap_err();
+		assert!(error.to_string().contains("Invalid Opsgenie API URL"));
+
+		if let TriggerTypeConfig::Opsgenie {
+			api_key, api_url, ..
+		} = &mut opsgenie.config
+		{
+			*api_url = None;
+			*api_key = " ".to_string();
+		}
+		let error = opsgenie.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Opsgenie API key cannot be empty"));
+	}
+
+	#[test]
+	fn test_kafka_trigger_validation() {
+		let mut kafka: Trigger = serde_json::from_str(
+			r#"{
+				"name": "kafka",
+				"trigger_type": "kafka",
+				"config": {
+					"brokers": ["localhost:9092"],
+					"topic": "monitor.alerts",
+					"key": "{{ transaction_hash }}",
+					"headers": { "monitor": "{{ monitor_name }}" },
+					"acks": "leader",
+					"sasl": { "mechanism": "SCRAM-SHA-512", "username": "user", "password": "pass" }
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(matches!(
+			kafka.config,
+			TriggerTypeConfig::Kafka {
+				acks: Some(KafkaAcks::Leader),
+				..
+			}
+		));
+		assert!(kafka.validate().is_ok());
+
+		if let TriggerTypeConfig::Kafka { brokers, .. } = &mut kafka.config {
+			*brokers = vec!["localhost".to_string()];
+		}
+		let error = kafka.validate().unwrap_err();
+		assert!(error.to_string().contains("Invalid Kafka broker address"));
+
+		if let TriggerTypeConfig::Kafka { brokers, topic, .. } = &mut kafka.config {
+			*brokers = vec!["localhost:9092".to_string()];
+			*topic = "monitor alerts".to_string();
+		}
+		let error = kafka.validate().unwrap_err();
+		assert!(error.to_string().contains("Invalid Kafka topic"));
+
+		if let TriggerTypeConfig::Kafka { topic, tls, .. } = &mut kafka.config {
+			*topic = "monitor.alerts".to_string();
+			*tls = Some(KafkaTls {
+				ca_path: Some("non_existent_ca.pem".to_string()),
+				..Default::default()
+			});
+		}
+		let error = kafka.validate().unwrap_err();
+		assert!(error.to_string().contains("Kafka TLS file does not exist"));
+
+		if let TriggerTypeConfig::Kafka { tls, .. } = &mut kafka.config {
+			*tls = None;
+		}
+		kafka.digest = Some(DigestPolicy::default());
+		let error = kafka.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Digests are not supported by Kafka triggers"));
+	}
+
+	#[test]
+	fn test_file_trigger_validation() {
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let mut file: Trigger = serde_json::from_str(
+			r#"{
+				"name": "audit_log",
+				"trigger_type": "file",
+				"config": {
+					"path": "data/audit/alerts.csv",
+					"format": "csv",
+					"columns": [
+						{ "name": "monitor", "value": "{{ monitor_name }}" },
+						{ "name": "transaction", "value": "{{ transaction_hash }}" }
+					],
+					"rotation": { "interval": "daily", "compress": true },
+					"fsync": "rotation"
+				}
+			}"#,
+		)
+		.unwrap();
+		assert!(matches!(
+			file.config,
+			TriggerTypeConfig::File {
+				format: Some(FileFormat::Csv),
+				fsync: Some(FileSync::Rotation),
+				..
+			}
+		));
+		assert!(file.validate().is_ok());
+
+		if let TriggerTypeConfig::File { columns, .. } = &mut file.config {
+			columns.as_mut().unwrap()[1].name = "monitor".to_string();
+		}
+		let error = file.validate().unwrap_err();
+		assert!(error.to_string().contains("duplicate file column name"));
+
+		if let TriggerTypeConfig::File {
+			format, columns, ..
+		} = &mut file.config
+		{
+			*format = Some(FileFormat::Jsonl);
+			*columns = None;
+		}
+		assert!(file.validate().is_ok());
+
+		if let TriggerTypeConfig::File { rotation, .. } = &mut file.config {
+			*rotation = Some(FileRotation {
+				compress: Some(true),
+				..Default::default()
+			});
+		}
+		let error = file.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("File rotation requires max_size_bytes or interval"));
+
+		if let TriggerTypeConfig::File { path, rotation, .. } = &mut file.config {
+			*path = temp_dir.path().to_string_lossy().into_owned();
+			*rotation = None;
+		}
+		let error = file.validate().unwrap_err();
+		assert!(error.to_string().contains("File path is a directory"));
+
+		if let TriggerTypeConfig::File { path, .. } = &mut file.config {
+			*path = "data/audit/alerts.jsonl".to_string();
+		}
+		file.digest = Some(DigestPolicy::default());
+		let error = file.validate().unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("Digests are not supported by file triggers"));
+	}
+
 	#[test]
 	fn test_invalid_load_from_path() {
 		let path = Path::new("config/triggers/invalid.json");
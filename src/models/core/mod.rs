@@ -10,8 +10,13 @@ mod network;
 mod trigger;
 
 pub use monitor::{
-	AddressWithABI, EventCondition, FunctionCondition, MatchConditions, Monitor, ScriptLanguage,
-	TransactionCondition, TransactionStatus, TriggerConditions,
+	AddressWithABI, EventCondition, FunctionCondition, MatchConditions, Monitor, ReportSchedule,
+	ScriptLanguage, Severity, TransactionCondition, TransactionStatus, TriggerConditions,
 };
 pub use network::{Network, RpcUrl};
-pub use trigger::{NotificationMessage, Trigger, TriggerType, TriggerTypeConfig};
+pub use trigger::{
+	AlertPolicy, DigestPolicy, FileColumn, FileFormat, FileRotation, FileRotationInterval,
+	FileSync, KafkaAcks, KafkaSasl, KafkaSaslMechanism, KafkaTls, NotificationMessage,
+	OpsgeniePriority, RetryPolicy, Trigger, TriggerType, TriggerTypeConfig, WebhookPayload,
+	WebhookPayloadFormat,
+};
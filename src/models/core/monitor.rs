@@ -1,3 +1,4 @@
+use crate::models::core::AlertPolicy;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for monitoring specific blockchain activity.
@@ -9,6 +10,8 @@ use serde::{Deserialize, Serialize};
 /// - Triggers conditions refers to a custom filter script that being executed apply extra filters
 ///   to the matched transactions before triggering the notifications
 /// - Triggers to execute when conditions are met
+/// - Whether matches should be alerted in two phases (pending at head, then confirmed or dropped)
+/// - Summary reports to send on a schedule
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 pub struct Monitor {
 	/// Unique name identifying this monitor
@@ -31,6 +34,55 @@ pub struct Monitor {
 
 	/// IDs of triggers to execute when conditions match
 	pub triggers: Vec<String>,
+
+	/// Whether to send an unconfirmed alert at head depth, followed by a confirmed or dropped
+	/// alert once the block reaches the network's confirmation depth
+	#[serde(default)]
+	pub two_phase_alerts: bool,
+
+	/// Deduplication, cooldown and rate limit of the alerts of this monitor across all its
+	/// triggers
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alert_policy: Option<AlertPolicy>,
+
+	/// Summary reports of the matches of this monitor, sent on a schedule
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub reports: Vec<ReportSchedule>,
+
+	/// Severity of the alerts of this monitor, used by triggers to format and route them
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub severity: Option<Severity>,
+}
+
+/// Schedule of a summary report of a monitor
+///
+/// At every tick of `cron_schedule`, the matches recorded during the last `period_ms` are
+/// summarized and the report is sent through `triggers`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ReportSchedule {
+	/// Cron expression of the report schedule, e.g. `0 0 9 * * Mon` for weekly reports
+	pub cron_schedule: String,
+
+	/// Length of the reported period in milliseconds, ending when the report is sent
+	pub period_ms: u64,
+
+	/// IDs of the triggers to send the report through
+	pub triggers: Vec<String>,
+
+	/// Number of entries of the top senders, receivers and values lists
+	pub top: usize,
+}
+
+impl Default for ReportSchedule {
+	fn default() -> Self {
+		Self {
+			cron_schedule: "0 0 0 * * *".to_string(),
+			period_ms: 24 * 60 * 60 * 1000,
+			triggers: vec![],
+			top: 5,
+		}
+	}
 }
 
 /// Contract address with optional ABI for decoding transactions and events
@@ -97,6 +149,32 @@ pub enum TransactionStatus {
 	Failure,
 }
 
+/// Severity of the alerts of a monitor
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	/// Informational alert
+	Info,
+	/// Alert worth a look
+	Warning,
+	/// Alert requiring action
+	Error,
+	/// Alert requiring immediate action
+	Critical,
+}
+
+impl std::fmt::Display for Severity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Self::Info => "info",
+			Self::Warning => "warning",
+			Self::Error => "error",
+			Self::Critical => "critical",
+		};
+		write!(f, "{}", name)
+	}
+}
+
 /// Conditions that should be met prior to triggering notifications
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct TriggerConditions {
@@ -40,6 +40,10 @@ pub struct Network {
 
 	/// Whether to store processed blocks
 	pub store_blocks: Option<bool>,
+
+	/// Base URL of a block explorer, used to link transactions and addresses in notifications
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub explorer_url: Option<String>,
 }
 
 /// RPC endpoint configuration with load balancing weight
@@ -1,6 +1,7 @@
-use crate::models::core::ScriptLanguage;
+use crate::models::core::{ScriptLanguage, Severity};
 use email_address::EmailAddress;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Configuration for actions to take when monitored conditions are met.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -8,11 +9,185 @@ pub struct Trigger {
 	/// Unique name identifying this trigger
 	pub name: String,
 
-	/// Type of trigger (Email, Slack, Webhook, Telegram, Discord, Script)
+	/// Type of trigger (Email, Slack, Webhook, Telegram, Discord, Teams, Mattermost, PagerDuty,
+	/// Opsgenie, Kafka, File, Script)
 	pub trigger_type: TriggerType,
 
 	/// Configuration specific to the trigger type
 	pub config: TriggerTypeConfig,
+
+	/// Retry policy for failed deliveries, the default policy applies if not set
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub retry: Option<RetryPolicy>,
+
+	/// Deduplication, cooldown and rate limit of the alerts sent through this trigger
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alert_policy: Option<AlertPolicy>,
+
+	/// Aggregation of the matches delivered through this trigger into digests
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub digest: Option<DigestPolicy>,
+}
+
+/// Policy aggregating matches into digest notifications
+///
+/// Instead of one notification per match, the matches of a processed block, or of a time
+/// window if `window_ms` is set, are delivered as a single notification. The loop section
+/// `${#matches}...${/matches}` of the message template is repeated for every match, up to
+/// `max_items` matches.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct DigestPolicy {
+	/// Length of the digest window in milliseconds, matches are grouped per block if not set
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub window_ms: Option<u64>,
+
+	/// Maximum number of matches rendered by the loop section of the template
+	pub max_items: usize,
+}
+
+impl Default for DigestPolicy {
+	fn default() -> Self {
+		Self {
+			window_ms: None,
+			max_items: 20,
+		}
+	}
+}
+
+/// Policy limiting repeated alerts
+///
+/// Alerts are identified by a key rendered from `dedup_key` with the template variables of the
+/// match. An alert whose key was alerted within `cooldown_ms` is suppressed. At most
+/// `max_alerts` alerts are sent per `window_ms`, the overflow is suppressed and reported as a
+/// summary once the window ends.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct AlertPolicy {
+	/// Template of the key identifying repeated alerts, e.g. `${monitor_name}:${event_0_from}`
+	pub dedup_key: String,
+
+	/// Time in milliseconds during which alerts with the same key are suppressed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cooldown_ms: Option<u64>,
+
+	/// Maximum number of alerts per window
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_alerts: Option<u32>,
+
+	/// Length of the rate limit window in milliseconds
+	pub window_ms: u64,
+}
+
+impl Default for AlertPolicy {
+	fn default() -> Self {
+		Self {
+			dedup_key: "${monitor_name}".to_string(),
+			cooldown_ms: None,
+			max_alerts: None,
+			window_ms: 60_000,
+		}
+	}
+}
+
+/// Retry policy for deliveries of a trigger
+///
+/// Failed deliveries are retried with exponential backoff until `max_attempts` is reached. A
+/// `Retry-After` header on the failed response extends the delay.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+	/// Maximum number of delivery attempts, including the first one
+	pub max_attempts: u32,
+
+	/// Delay before the first retry in milliseconds, doubled for every further attempt
+	pub initial_backoff_ms: u64,
+
+	/// Upper bound of the delay between two attempts in milliseconds
+	pub max_backoff_ms: u64,
+
+	/// HTTP statuses worth retrying, other error statuses fail the delivery immediately
+	pub retryable_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_attempts: 5,
+			initial_backoff_ms: 1000,
+			max_backoff_ms: 300_000,
+			retryable_statuses: vec![408, 425, 429, 500, 502, 503, 504],
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Returns the delay before the next attempt after `attempts` failed attempts
+	pub fn backoff(&self, attempts: u32) -> Duration {
+		let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+		Duration::from_millis(
+			self.initial_backoff_ms
+				.saturating_mul(factor)
+				.min(self.max_backoff_ms),
+		)
+	}
+
+	/// Returns whether a delivery failing with an HTTP status should be retried
+	pub fn is_retryable_status(&self, status: u16) -> bool {
+		self.retryable_statuses.contains(&status)
+	}
+}
+
+/// Request payload of a webhook trigger
+///
+/// Every string of `template` is rendered as a message template. A string made only of an
+/// expression ending with the `number` or `bool` filter, e.g. `"{{ event_0_value | number }}"`,
+/// is sent as a JSON number or boolean instead of a string. The `match` format ignores the
+/// template and sends the serialized monitor match.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WebhookPayload {
+	/// Encoding of the request body
+	#[serde(default)]
+	pub format: WebhookPayloadFormat,
+
+	/// Template of the request body, a flat object of strings for the `form` format
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub template: Option<serde_json::Value>,
+
+	/// Content type of the request, defaults to the content type of the format
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub content_type: Option<String>,
+
+	/// Query parameters added to the URL, their values are rendered as message templates
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub query: Option<std::collections::HashMap<String, String>>,
+
+	/// Path appended to the URL, rendered as a message template with URL-encoded values
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+}
+
+/// Encoding of the request body of a webhook trigger
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookPayloadFormat {
+	/// JSON body rendered from the template
+	#[default]
+	Json,
+	/// Form-encoded body rendered from the template
+	Form,
+	/// Serialized monitor match, the template is ignored
+	Match,
+}
+
+impl WebhookPayloadFormat {
+	/// Returns the default content type of the format
+	pub fn content_type(&self) -> &'static str {
+		match self {
+			Self::Json | Self::Match => "application/json",
+			Self::Form => "application/x-www-form-urlencoded",
+		}
+	}
 }
 
 /// Supported trigger action types
@@ -29,10 +204,188 @@ pub enum TriggerType {
 	Telegram,
 	/// Send notification to Discord
 	Discord,
+	/// Send notification to Microsoft Teams
+	Teams,
+	/// Send notification to Mattermost
+	Mattermost,
+	/// Send event to PagerDuty
+	PagerDuty,
+	/// Create alert in Opsgenie
+	Opsgenie,
+	/// Publish record to Kafka
+	Kafka,
+	/// Append record to a local file
+	File,
 	/// Execute local script
 	Script,
 }
 
+/// Priority of an Opsgenie alert
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum OpsgeniePriority {
+	/// Critical
+	P1,
+	/// High
+	P2,
+	/// Moderate
+	P3,
+	/// Low
+	P4,
+	/// Informational
+	P5,
+}
+
+impl OpsgeniePriority {
+	/// Returns the default priority of the alerts of a monitor severity
+	pub fn from_severity(severity: Severity) -> Self {
+		match severity {
+			Severity::Critical => Self::P1,
+			Severity::Error => Self::P2,
+			Severity::Warning => Self::P3,
+			Severity::Info => Self::P5,
+		}
+	}
+}
+
+/// Acknowledgements a Kafka producer waits for before a record is delivered
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KafkaAcks {
+	/// No acknowledgement, the record is delivered once written to the connection
+	None,
+	/// Acknowledgement of the partition leader
+	Leader,
+	/// Acknowledgement of all in-sync replicas
+	#[default]
+	All,
+}
+
+impl KafkaAcks {
+	/// Returns the value of the acks field of a produce request
+	pub fn as_i16(&self) -> i16 {
+		match self {
+			Self::None => 0,
+			Self::Leader => 1,
+			Self::All => -1,
+		}
+	}
+}
+
+/// SASL mechanisms supported by Kafka triggers
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum KafkaSaslMechanism {
+	/// Username and password sent in clear, to be used over TLS
+	#[serde(rename = "PLAIN")]
+	Plain,
+	/// Salted challenge response with SHA-256
+	#[serde(rename = "SCRAM-SHA-256")]
+	ScramSha256,
+	/// Salted challenge response with SHA-512
+	#[serde(rename = "SCRAM-SHA-512")]
+	ScramSha512,
+}
+
+impl KafkaSaslMechanism {
+	/// Returns the name of the mechanism in the SASL handshake
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Plain => "PLAIN",
+			Self::ScramSha256 => "SCRAM-SHA-256",
+			Self::ScramSha512 => "SCRAM-SHA-512",
+		}
+	}
+}
+
+/// SASL authentication of a Kafka trigger
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct KafkaSasl {
+	/// Authentication mechanism
+	pub mechanism: KafkaSaslMechanism,
+	/// Username
+	pub username: String,
+	/// Password
+	pub password: String,
+}
+
+/// TLS connection of a Kafka trigger
+///
+/// The certificates of the brokers are verified against the system roots, and the CA
+/// certificate if set. A client certificate and key authenticate the producer when both are set.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct KafkaTls {
+	/// Path to the PEM CA certificate of the brokers
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ca_path: Option<String>,
+	/// Path to the PEM client certificate
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cert_path: Option<String>,
+	/// Path to the PEM PKCS #8 client key
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub key_path: Option<String>,
+}
+
+/// Format of the records of a file trigger
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+	/// One JSON document per line
+	#[default]
+	Jsonl,
+	/// Comma-separated values with a header line
+	Csv,
+}
+
+/// Column of the CSV records of a file trigger
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FileColumn {
+	/// Name of the column in the header line
+	pub name: String,
+	/// Template of the value of the column
+	pub value: String,
+}
+
+/// Period of the files of a file trigger
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileRotationInterval {
+	/// A new file every UTC hour
+	Hourly,
+	/// A new file every UTC day
+	Daily,
+}
+
+/// Rotation of the files of a file trigger
+///
+/// The file is renamed with the period and a sequence number of its records, and the next
+/// record starts a new file.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct FileRotation {
+	/// Size in bytes above which the file is rotated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_size_bytes: Option<u64>,
+	/// Period after which the file is rotated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub interval: Option<FileRotationInterval>,
+	/// Compress the rotated files with gzip (default false)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub compress: Option<bool>,
+}
+
+/// When the records of a file trigger are flushed to disk
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSync {
+	/// After every record, so delivered records survive a crash of the host
+	#[default]
+	Always,
+	/// When the file is rotated
+	Rotation,
+	/// When the operating system decides
+	Never,
+}
+
 /// Notification message fields
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct NotificationMessage {
@@ -52,6 +405,9 @@ pub enum TriggerTypeConfig {
 		slack_url: String,
 		/// Notification message
 		message: NotificationMessage,
+		/// Block Kit template of the message, the message text is sent without it
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		blocks: Option<serde_json::Value>,
 	},
 	/// Email notification configuration
 	Email {
@@ -76,12 +432,18 @@ pub enum TriggerTypeConfig {
 		url: String,
 		/// HTTP method to use
 		method: Option<String>,
-		/// Secret
+		/// Secret signing the requests
 		secret: Option<String>,
+		/// Additional secrets signing the requests, to rotate secrets without downtime
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		secrets: Option<Vec<String>>,
 		/// Optional HTTP headers
 		headers: Option<std::collections::HashMap<String, String>>,
 		/// Notification message
 		message: NotificationMessage,
+		/// Payload sent in place of the default title and body message
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payload: Option<WebhookPayload>,
 	},
 	/// Telegram notification configuration
 	Telegram {
@@ -100,6 +462,121 @@ pub enum TriggerTypeConfig {
 		discord_url: String,
 		/// Notification message
 		message: NotificationMessage,
+		/// Embed template of the message, the message text is sent without it
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		embed: Option<serde_json::Value>,
+	},
+	/// Microsoft Teams notification configuration
+	Teams {
+		/// Teams incoming webhook URL
+		teams_url: String,
+		/// Notification message
+		message: NotificationMessage,
+		/// Adaptive Card template of the message, a card of the message text is sent without it
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		card: Option<serde_json::Value>,
+	},
+	/// Mattermost notification configuration
+	Mattermost {
+		/// Mattermost incoming webhook URL
+		mattermost_url: String,
+		/// Notification message
+		message: NotificationMessage,
+		/// Attachments template of the message, the message text is sent without it
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		attachments: Option<serde_json::Value>,
+	},
+	/// PagerDuty Events API v2 configuration
+	PagerDuty {
+		/// Integration key of the PagerDuty service
+		routing_key: String,
+		/// Notification message, the title is the summary of the event
+		message: NotificationMessage,
+		/// Template of the key grouping events into incidents
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		dedup_key: Option<String>,
+		/// Template of the custom details of the event, the match variables if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		custom_details: Option<serde_json::Value>,
+		/// Event severities of the monitor severities, they match if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		severity_map: Option<std::collections::HashMap<Severity, Severity>>,
+		/// Resolve the incident of a dropped two-phase alert (default true)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		auto_resolve: Option<bool>,
+		/// Base URL of the Events API
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		api_url: Option<String>,
+	},
+	/// Opsgenie Alert API configuration
+	Opsgenie {
+		/// API key of the Opsgenie integration
+		api_key: String,
+		/// Notification message, the title is the message of the alert
+		message: NotificationMessage,
+		/// Template of the alias deduplicating alerts
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		dedup_key: Option<String>,
+		/// Templates of the details of the alert, the match variables if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		details: Option<std::collections::HashMap<String, String>>,
+		/// Alert priorities of the monitor severities
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		severity_map: Option<std::collections::HashMap<Severity, OpsgeniePriority>>,
+		/// Close the alert of a dropped two-phase alert (default true)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		auto_resolve: Option<bool>,
+		/// Base URL of the Alert API, e.g. `https://api.eu.opsgenie.com` for the EU instance
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		api_url: Option<String>,
+	},
+	/// Kafka producer configuration
+	Kafka {
+		/// Bootstrap brokers as `host:port`
+		brokers: Vec<String>,
+		/// Topic the records are published to
+		topic: String,
+		/// Template of the record key, records without key are spread over the partitions
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		key: Option<String>,
+		/// Templates of the record headers
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		headers: Option<std::collections::HashMap<String, String>>,
+		/// Template of the record value, the serialized match if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payload: Option<serde_json::Value>,
+		/// Acknowledgements awaited for every record (default all)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		acks: Option<KafkaAcks>,
+		/// Timeout of a delivery in milliseconds (default 10000)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		timeout_ms: Option<u32>,
+		/// SASL authentication
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		sasl: Option<KafkaSasl>,
+		/// TLS connection, plaintext if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		tls: Option<KafkaTls>,
+	},
+	/// File sink configuration
+	File {
+		/// Path to the file the records are appended to
+		path: String,
+		/// Format of the records (default jsonl)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		format: Option<FileFormat>,
+		/// Template of the JSONL records, the serialized match if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payload: Option<serde_json::Value>,
+		/// Columns of the CSV records
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		columns: Option<Vec<FileColumn>>,
+		/// Rotation of the file, the file grows forever if not set
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		rotation: Option<FileRotation>,
+		/// When the records are flushed to disk (default always)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		fsync: Option<FileSync>,
 	},
 	/// Script execution configuration
 	Script {
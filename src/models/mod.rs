@@ -11,7 +11,10 @@ mod config;
 mod core;
 
 // Re-export blockchain types
-pub use blockchain::{BlockChainType, BlockType, MonitorMatch, ProcessedBlock, TransactionType};
+pub use blockchain::{
+	AlertContext, AlertPhase, BlockChainType, BlockType, MatchContext, MonitorMatch,
+	ProcessedBlock, TransactionType,
+};
 
 pub use blockchain::evm::{
 	EVMBaseTransaction, EVMBlock, EVMMatchArguments, EVMMatchParamEntry, EVMMatchParamsMap,
@@ -26,9 +29,12 @@ pub use blockchain::stellar::{
 
 // Re-export core types
 pub use core::{
-	AddressWithABI, EventCondition, FunctionCondition, MatchConditions, Monitor, Network,
-	NotificationMessage, RpcUrl, ScriptLanguage, TransactionCondition, TransactionStatus, Trigger,
-	TriggerConditions, TriggerType, TriggerTypeConfig,
+	AddressWithABI, AlertPolicy, DigestPolicy, EventCondition, FileColumn, FileFormat,
+	FileRotation, FileRotationInterval, FileSync, FunctionCondition, KafkaAcks, KafkaSasl,
+	KafkaSaslMechanism, KafkaTls, MatchConditions, Monitor, Network, NotificationMessage,
+	OpsgeniePriority, ReportSchedule, RetryPolicy, RpcUrl, ScriptLanguage, Severity,
+	TransactionCondition, TransactionStatus, Trigger, TriggerConditions, TriggerType,
+	TriggerTypeConfig, WebhookPayload, WebhookPayloadFormat,
 };
 
 // Re-export config types
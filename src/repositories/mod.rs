@@ -13,13 +13,35 @@
 //!   exist
 //! - Network: Loads network configurations defining blockchain connection details
 //! - Trigger: Loads trigger configurations defining actions to take when conditions match
+//!
+//! Monitors and triggers can also be saved and deleted, e.g. through the management API.
+//!
+//! The SQLite repositories implement the same traits on top of a single database file. Its
+//! schema is managed by versioned migrations and its content can be imported from or exported
+//! to a JSON config directory. The configuration storage selects which of the two the service
+//! loads, reloads and manages its configuration with.
+//!
+//! The validation module loads all three together to validate and lint a config directory
+//! without starting the service.
 
 mod error;
 mod monitor;
 mod network;
+mod persistence;
+mod sqlite;
+mod storage;
 mod trigger;
+mod validation;
 
 pub use error::RepositoryError;
 pub use monitor::{MonitorRepository, MonitorRepositoryTrait, MonitorService};
 pub use network::{NetworkRepository, NetworkRepositoryTrait, NetworkService};
+pub use sqlite::{
+	export_config_dir, import_config_dir, ConfigCounts, SqliteMonitorRepository,
+	SqliteNetworkRepository, SqliteTriggerRepository,
+};
+pub use storage::ConfigStorage;
 pub use trigger::{TriggerRepository, TriggerRepositoryTrait, TriggerService};
+pub use validation::{
+	lint_config, validate_config, validate_config_dir, ConfigIssue, ConfigReport, IssueSeverity,
+};
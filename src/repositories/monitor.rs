@@ -9,14 +9,18 @@
 use std::{collections::HashMap, marker::PhantomData, path::Path};
 
 use crate::{
-	models::{ConfigLoader, Monitor, Network, ScriptLanguage, Trigger},
+	models::{ConfigLoader, Monitor, Network, ScriptLanguage, Trigger, TriggerType},
 	repositories::{
 		error::RepositoryError,
 		network::{NetworkRepository, NetworkRepositoryTrait, NetworkService},
+		persistence::{remove_file, validate_config_id, write_json_file},
 		trigger::{TriggerRepository, TriggerRepositoryTrait, TriggerService},
 	},
 };
 
+/// Default directory of the monitor configuration files
+const DEFAULT_MONITOR_DIR: &str = "config/monitors";
+
 /// Static mapping of script languages to their file extensions
 const LANGUAGE_EXTENSIONS: &[(&ScriptLanguage, &str)] = &[
 	(&ScriptLanguage::Python, "py"),
@@ -83,6 +87,30 @@ impl<N: NetworkRepositoryTrait, T: TriggerRepositoryTrait> MonitorRepository<N,
 				}
 			}
 
+			// Validate report trigger references
+			for trigger_id in monitor.reports.iter().flat_map(|report| &report.triggers) {
+				match triggers.get(trigger_id) {
+					None => {
+						validation_errors.push(format!(
+							"Monitor '{}' sends reports through non-existent trigger '{}'",
+							monitor_name, trigger_id
+						));
+						metadata.insert(
+							format!("monitor_{}_invalid_report_trigger", monitor_name),
+							trigger_id.clone(),
+						);
+					}
+					Some(trigger) if trigger.trigger_type == TriggerType::Script => {
+						validation_errors.push(format!(
+							"Monitor '{}' sends reports through script trigger '{}', which cannot \
+							 send reports",
+							monitor_name, trigger_id
+						));
+					}
+					Some(_) => {}
+				}
+			}
+
 			// Validate network references
 			for network_slug in &monitor.networks {
 				if !networks.contains_key(network_slug) {
@@ -183,6 +211,24 @@ pub trait MonitorRepositoryTrait<N: NetworkRepositoryTrait, T: TriggerRepository
 		trigger_service: Option<TriggerService<T>>,
 	) -> Result<HashMap<String, Monitor>, RepositoryError>;
 
+	/// Save a monitor configuration to the given path
+	///
+	/// If no path is provided, uses the default config directory. The monitor is stored under
+	/// its ID, replacing any previous configuration with the same ID.
+	/// This is a static method that doesn't require an instance.
+	fn save(
+		path: Option<&Path>,
+		monitor_id: &str,
+		monitor: &Monitor,
+	) -> Result<(), RepositoryError>;
+
+	/// Delete a monitor configuration from the given path
+	///
+	/// If no path is provided, uses the default config directory. Deleting a monitor that
+	/// doesn't exist is not an error.
+	/// This is a static method that doesn't require an instance.
+	fn delete(path: Option<&Path>, monitor_id: &str) -> Result<(), RepositoryError>;
+
 	/// Get a specific monitor by ID
 	///
 	/// Returns None if the monitor doesn't exist.
@@ -255,6 +301,22 @@ impl<N: NetworkRepositoryTrait, T: TriggerRepositoryTrait> MonitorRepositoryTrai
 		Ok(monitors)
 	}
 
+	fn save(
+		path: Option<&Path>,
+		monitor_id: &str,
+		monitor: &Monitor,
+	) -> Result<(), RepositoryError> {
+		validate_config_id(monitor_id)?;
+		let monitor_dir = path.unwrap_or(Path::new(DEFAULT_MONITOR_DIR));
+		write_json_file(&monitor_dir.join(format!("{}.json", monitor_id)), monitor)
+	}
+
+	fn delete(path: Option<&Path>, monitor_id: &str) -> Result<(), RepositoryError> {
+		validate_config_id(monitor_id)?;
+		let monitor_dir = path.unwrap_or(Path::new(DEFAULT_MONITOR_DIR));
+		remove_file(&monitor_dir.join(format!("{}.json", monitor_id)))
+	}
+
 	fn get(&self, monitor_id: &str) -> Option<Monitor> {
 		self.monitors.get(monitor_id).cloned()
 	}
@@ -342,7 +404,7 @@ impl<M: MonitorRepositoryTrait<N, T>, N: NetworkRepositoryTrait, T: TriggerRepos
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::models::{MatchConditions, Monitor, ScriptLanguage};
+	use crate::models::{MatchConditions, Monitor, ReportSchedule, ScriptLanguage};
 	use std::fs;
 	use tempfile::TempDir;
 
@@ -521,4 +583,66 @@ mod tests {
 		let err = result.unwrap_err();
 		assert!(err.to_string().contains("references non-existent trigger"));
 	}
+
+	#[test]
+	fn test_report_trigger_validation_error() {
+		let mut monitors = HashMap::new();
+		let monitor = Monitor {
+			name: "test_monitor".to_string(),
+			reports: vec![ReportSchedule {
+				triggers: vec!["non_existent_trigger".to_string()],
+				..Default::default()
+			}],
+			..Default::default()
+		};
+		monitors.insert("test_monitor".to_string(), monitor);
+
+		let networks = HashMap::new();
+		let triggers = HashMap::new();
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&monitors, &triggers, &networks,
+			);
+
+		assert!(result.is_err());
+		let err = result.unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("sends reports through non-existent trigger"));
+	}
+
+	#[test]
+	fn test_save_and_delete_monitor() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = Some(temp_dir.path());
+		let monitor = Monitor {
+			name: "Large Transfer".to_string(),
+			networks: vec!["ethereum_mainnet".to_string()],
+			..Default::default()
+		};
+
+		MonitorRepository::<NetworkRepository, TriggerRepository>::save(
+			path,
+			"large_transfer",
+			&monitor,
+		)
+		.unwrap();
+		let monitors: HashMap<String, Monitor> = Monitor::load_all(path).unwrap();
+		assert_eq!(monitors["large_transfer"], monitor);
+
+		MonitorRepository::<NetworkRepository, TriggerRepository>::delete(path, "large_transfer")
+			.unwrap();
+		assert!(!temp_dir.path().join("large_transfer.json").exists());
+
+		// IDs that are not plain file names are rejected
+		assert!(matches!(
+			MonitorRepository::<NetworkRepository, TriggerRepository>::save(
+				path,
+				"../monitor",
+				&monitor
+			),
+			Err(RepositoryError::ValidationError(_))
+		));
+	}
 }
@@ -0,0 +1,112 @@
+//! Helpers for persisting configuration files.
+//!
+//! Used by the file-based repositories to save and delete configurations, e.g. when they are
+//! changed through the management API.
+
+#![allow(clippy::result_large_err)]
+
+use serde::Serialize;
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::repositories::error::RepositoryError;
+
+/// Checks that an ID can be used as a configuration file name
+///
+/// IDs may only contain ASCII letters, digits, `_` and `-`, so they cannot escape the
+/// configuration directory.
+pub(crate) fn validate_config_id(id: &str) -> Result<(), RepositoryError> {
+	if id.is_empty()
+		|| !id
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+	{
+		return Err(RepositoryError::validation_error(
+			format!(
+				"Invalid configuration ID '{}': only letters, digits, '_' and '-' are allowed",
+				id
+			),
+			None,
+			None,
+		));
+	}
+	Ok(())
+}
+
+/// Writes a value as pretty-printed JSON
+///
+/// The file is written next to its destination first and then renamed, so a concurrent reader
+/// never sees a partially written configuration.
+pub(crate) fn write_json_file<T: Serialize>(path: &Path, value: &T) -> Result<(), RepositoryError> {
+	let metadata = || {
+		Some(HashMap::from([(
+			"path".to_string(),
+			path.display().to_string(),
+		)]))
+	};
+
+	let content = serde_json::to_string_pretty(value).map_err(|e| {
+		RepositoryError::internal_error(
+			"Failed to serialize configuration",
+			Some(Box::new(e)),
+			metadata(),
+		)
+	})?;
+
+	let temp_path = path.with_extension("json.tmp");
+	fs::write(&temp_path, content)
+		.and_then(|_| fs::rename(&temp_path, path))
+		.map_err(|e| {
+			let _ = fs::remove_file(&temp_path);
+			RepositoryError::internal_error(
+				"Failed to write configuration file",
+				Some(Box::new(e)),
+				metadata(),
+			)
+		})
+}
+
+/// Removes a configuration file, succeeding if it does not exist
+pub(crate) fn remove_file(path: &Path) -> Result<(), RepositoryError> {
+	match fs::remove_file(path) {
+		Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(RepositoryError::internal_error(
+			"Failed to remove configuration file",
+			Some(Box::new(e)),
+			Some(HashMap::from([(
+				"path".to_string(),
+				path.display().to_string(),
+			)])),
+		)),
+		_ => Ok(()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_validate_config_id() {
+		assert!(validate_config_id("evm_transfer-usdc1").is_ok());
+		assert!(validate_config_id("").is_err());
+		assert!(validate_config_id("../monitor").is_err());
+		assert!(validate_config_id("large transfer").is_err());
+	}
+
+	#[test]
+	fn test_write_and_remove_json_file() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("config.json");
+
+		write_json_file(&path, &HashMap::from([("key", "value")])).unwrap();
+		let content: HashMap<String, String> =
+			serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+		assert_eq!(content["key"], "value");
+		assert!(!path.with_extension("json.tmp").exists());
+
+		remove_file(&path).unwrap();
+		assert!(!path.exists());
+		// Removing a missing file is not an error
+		remove_file(&path).unwrap();
+	}
+}
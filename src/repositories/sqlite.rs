@@ -0,0 +1,757 @@
+//! SQLite configuration repository implementations.
+//!
+//! This module stores networks, monitors and triggers as JSON documents keyed by ID in a single
+//! SQLite database, as an alternative to the directory of JSON files. The schema is created and
+//! upgraded by versioned migrations whenever the database is opened.
+//!
+//! Configurations are validated when they are loaded, exactly like the file-based repositories,
+//! and can be imported from or exported to the JSON configuration directory layout.
+
+#![allow(clippy::result_large_err)]
+
+use rusqlite::{params, Connection};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, fs, marker::PhantomData, path::Path};
+
+use crate::{
+	models::{ConfigLoader, Monitor, Network, Trigger},
+	repositories::{
+		error::RepositoryError,
+		monitor::{MonitorRepository, MonitorRepositoryTrait},
+		network::{NetworkRepository, NetworkRepositoryTrait, NetworkService},
+		persistence::{validate_config_id, write_json_file},
+		trigger::{TriggerRepository, TriggerRepositoryTrait, TriggerService},
+	},
+	utils::apply_migrations,
+};
+
+/// Default path of the configuration database
+const DEFAULT_DATABASE_PATH: &str = "data/config.db";
+
+/// Table of the network configurations
+const NETWORKS_TABLE: &str = "networks";
+
+/// Table of the monitor configurations
+const MONITORS_TABLE: &str = "monitors";
+
+/// Table of the trigger configurations
+const TRIGGERS_TABLE: &str = "triggers";
+
+/// Schema migrations of the configuration database, keyed by version
+const MIGRATIONS: &[(i64, &str)] = &[(
+	1,
+	"CREATE TABLE networks (
+		id TEXT PRIMARY KEY NOT NULL,
+		config TEXT NOT NULL,
+		updated_at INTEGER NOT NULL
+	);
+	CREATE TABLE monitors (
+		id TEXT PRIMARY KEY NOT NULL,
+		config TEXT NOT NULL,
+		updated_at INTEGER NOT NULL
+	);
+	CREATE TABLE triggers (
+		id TEXT PRIMARY KEY NOT NULL,
+		config TEXT NOT NULL,
+		updated_at INTEGER NOT NULL
+	);",
+)];
+
+/// Number of configurations of each type imported or exported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfigCounts {
+	/// Number of networks
+	pub networks: usize,
+	/// Number of monitors
+	pub monitors: usize,
+	/// Number of triggers
+	pub triggers: usize,
+}
+
+/// Builds the error metadata of a database path
+fn database_metadata(path: &Path) -> Option<HashMap<String, String>> {
+	Some(HashMap::from([(
+		"path".to_string(),
+		path.display().to_string(),
+	)]))
+}
+
+/// Opens the configuration database and applies pending migrations
+///
+/// If no path is provided, uses the default database path. The database and its parent
+/// directory are created if they don't exist.
+pub(crate) fn open_database(path: Option<&Path>) -> Result<Connection, RepositoryError> {
+	let path = path.unwrap_or(Path::new(DEFAULT_DATABASE_PATH));
+	if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+		fs::create_dir_all(parent).map_err(|e| {
+			RepositoryError::internal_error(
+				"Failed to create database directory",
+				Some(Box::new(e)),
+				database_metadata(path),
+			)
+		})?;
+	}
+
+	let mut connection = Connection::open(path).map_err(|e| {
+		RepositoryError::load_error(
+			"Failed to open configuration database",
+			Some(Box::new(e)),
+			database_metadata(path),
+		)
+	})?;
+	apply_migrations(&mut connection, MIGRATIONS).map_err(|e| {
+		RepositoryError::internal_error(
+			"Failed to migrate configuration database",
+			Some(Box::new(e)),
+			database_metadata(path),
+		)
+	})?;
+	Ok(connection)
+}
+
+/// Loads and validates every configuration of a table
+fn load_table<C: ConfigLoader + DeserializeOwned>(
+	connection: &Connection,
+	table: &str,
+) -> Result<HashMap<String, C>, RepositoryError> {
+	let load_error = |e: rusqlite::Error| {
+		RepositoryError::load_error(
+			format!("Failed to load {}", table),
+			Some(Box::new(e)),
+			Some(HashMap::from([("table".to_string(), table.to_string())])),
+		)
+	};
+
+	let mut statement = connection
+		.prepare(&format!("SELECT id, config FROM {} ORDER BY id", table))
+		.map_err(load_error)?;
+	let rows = statement
+		.query_map([], |row| {
+			Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+		})
+		.map_err(load_error)?
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.map_err(load_error)?;
+
+	rows.into_iter()
+		.map(|(id, content)| {
+			let metadata = || {
+				Some(HashMap::from([
+					("table".to_string(), table.to_string()),
+					("id".to_string(), id.clone()),
+				]))
+			};
+			let config: C = serde_json::from_str(&content).map_err(|e| {
+				RepositoryError::load_error(
+					format!("Failed to parse configuration '{}'", id),
+					Some(Box::new(e)),
+					metadata(),
+				)
+			})?;
+			config.validate().map_err(|e| {
+				RepositoryError::validation_error(
+					format!("Validation failed for configuration '{}'", id),
+					Some(Box::new(e)),
+					metadata(),
+				)
+			})?;
+			Ok((id, config))
+		})
+		.collect()
+}
+
+/// Inserts or replaces a configuration of a table
+fn upsert_row<C: Serialize>(
+	connection: &Connection,
+	table: &str,
+	id: &str,
+	config: &C,
+) -> Result<(), RepositoryError> {
+	validate_config_id(id)?;
+	let metadata = || {
+		Some(HashMap::from([
+			("table".to_string(), table.to_string()),
+			("id".to_string(), id.to_string()),
+		]))
+	};
+
+	let content = serde_json::to_string(config).map_err(|e| {
+		RepositoryError::internal_error(
+			"Failed to serialize configuration",
+			Some(Box::new(e)),
+			metadata(),
+		)
+	})?;
+	connection
+		.execute(
+			&format!(
+				"INSERT INTO {} (id, config, updated_at) VALUES (?1, ?2, ?3)
+				 ON CONFLICT(id) DO UPDATE SET config = excluded.config,
+				 updated_at = excluded.updated_at",
+				table
+			),
+			params![id, content, chrono::Utc::now().timestamp()],
+		)
+		.map_err(|e| {
+			RepositoryError::internal_error(
+				"Failed to save configuration",
+				Some(Box::new(e)),
+				metadata(),
+			)
+		})?;
+	Ok(())
+}
+
+/// Deletes a configuration of a table, succeeding if it does not exist
+fn delete_row(path: Option<&Path>, table: &str, id: &str) -> Result<(), RepositoryError> {
+	validate_config_id(id)?;
+	let connection = open_database(path)?;
+	connection
+		.execute(&format!("DELETE FROM {} WHERE id = ?1", table), [id])
+		.map_err(|e| {
+			RepositoryError::internal_error(
+				"Failed to delete configuration",
+				Some(Box::new(e)),
+				Some(HashMap::from([
+					("table".to_string(), table.to_string()),
+					("id".to_string(), id.to_string()),
+				])),
+			)
+		})?;
+	Ok(())
+}
+
+/// Repository for storing and retrieving network configurations in SQLite
+#[derive(Clone)]
+pub struct SqliteNetworkRepository {
+	/// Map of network slugs to their configurations
+	pub networks: HashMap<String, Network>,
+}
+
+impl SqliteNetworkRepository {
+	/// Create a new network repository from the given database
+	///
+	/// Loads all network configurations from the database at the specified path
+	/// (or default database path if None is provided).
+	pub fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		let networks = Self::load_all(path)?;
+		Ok(SqliteNetworkRepository { networks })
+	}
+
+	/// Save a network configuration to the given database
+	///
+	/// Networks are not managed through the repository trait, so this is used to import them.
+	pub fn save(
+		path: Option<&Path>,
+		network_id: &str,
+		network: &Network,
+	) -> Result<(), RepositoryError> {
+		upsert_row(&open_database(path)?, NETWORKS_TABLE, network_id, network)
+	}
+
+	/// Delete a network configuration from the given database
+	pub fn delete(path: Option<&Path>, network_id: &str) -> Result<(), RepositoryError> {
+		delete_row(path, NETWORKS_TABLE, network_id)
+	}
+}
+
+impl NetworkRepositoryTrait for SqliteNetworkRepository {
+	fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		SqliteNetworkRepository::new(path)
+	}
+
+	fn load_all(path: Option<&Path>) -> Result<HashMap<String, Network>, RepositoryError> {
+		load_table(&open_database(path)?, NETWORKS_TABLE)
+	}
+
+	fn get(&self, network_id: &str) -> Option<Network> {
+		self.networks.get(network_id).cloned()
+	}
+
+	fn get_all(&self) -> HashMap<String, Network> {
+		self.networks.clone()
+	}
+}
+
+/// Repository for storing and retrieving trigger configurations in SQLite
+#[derive(Clone)]
+pub struct SqliteTriggerRepository {
+	/// Map of trigger names to their configurations
+	pub triggers: HashMap<String, Trigger>,
+}
+
+impl SqliteTriggerRepository {
+	/// Create a new trigger repository from the given database
+	///
+	/// Loads all trigger configurations from the database at the specified path
+	/// (or default database path if None is provided).
+	pub fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		let triggers = Self::load_all(path)?;
+		Ok(SqliteTriggerRepository { triggers })
+	}
+}
+
+impl TriggerRepositoryTrait for SqliteTriggerRepository {
+	fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		SqliteTriggerRepository::new(path)
+	}
+
+	fn load_all(path: Option<&Path>) -> Result<HashMap<String, Trigger>, RepositoryError> {
+		load_table(&open_database(path)?, TRIGGERS_TABLE)
+	}
+
+	fn save(
+		path: Option<&Path>,
+		trigger_id: &str,
+		trigger: &Trigger,
+	) -> Result<(), RepositoryError> {
+		upsert_row(&open_database(path)?, TRIGGERS_TABLE, trigger_id, trigger)
+	}
+
+	fn delete(path: Option<&Path>, trigger_id: &str) -> Result<(), RepositoryError> {
+		delete_row(path, TRIGGERS_TABLE, trigger_id)
+	}
+
+	fn get(&self, trigger_id: &str) -> Option<Trigger> {
+		self.triggers.get(trigger_id).cloned()
+	}
+
+	fn get_all(&self) -> HashMap<String, Trigger> {
+		self.triggers.clone()
+	}
+}
+
+/// Repository for storing and retrieving monitor configurations in SQLite
+#[derive(Clone)]
+pub struct SqliteMonitorRepository<N: NetworkRepositoryTrait, T: TriggerRepositoryTrait> {
+	/// Map of monitor names to their configurations
+	pub monitors: HashMap<String, Monitor>,
+	_network_repository: PhantomData<N>,
+	_trigger_repository: PhantomData<T>,
+}
+
+impl<N: NetworkRepositoryTrait, T: TriggerRepositoryTrait> SqliteMonitorRepository<N, T> {
+	/// Create a new monitor repository from the given database
+	///
+	/// Loads all monitor configurations from the database at the specified path
+	/// (or default database path if None is provided). Without services, the referenced
+	/// networks and triggers are loaded from the same database.
+	pub fn new(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<Self, RepositoryError> {
+		let monitors = Self::load_all(path, network_service, trigger_service)?;
+		Ok(SqliteMonitorRepository {
+			monitors,
+			_network_repository: PhantomData,
+			_trigger_repository: PhantomData,
+		})
+	}
+}
+
+impl<N: NetworkRepositoryTrait, T: TriggerRepositoryTrait> MonitorRepositoryTrait<N, T>
+	for SqliteMonitorRepository<N, T>
+{
+	fn new(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<Self, RepositoryError> {
+		SqliteMonitorRepository::new(path, network_service, trigger_service)
+	}
+
+	fn load_all(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		let connection = open_database(path)?;
+		let monitors = load_table(&connection, MONITORS_TABLE)?;
+		let networks = match network_service {
+			Some(service) => service.get_all(),
+			None => load_table(&connection, NETWORKS_TABLE)?,
+		};
+		let triggers = match trigger_service {
+			Some(service) => service.get_all(),
+			None => load_table(&connection, TRIGGERS_TABLE)?,
+		};
+
+		MonitorRepository::<N, T>::validate_monitor_references(&monitors, &triggers, &networks)?;
+		Ok(monitors)
+	}
+
+	fn save(
+		path: Option<&Path>,
+		monitor_id: &str,
+		monitor: &Monitor,
+	) -> Result<(), RepositoryError> {
+		upsert_row(&open_database(path)?, MONITORS_TABLE, monitor_id, monitor)
+	}
+
+	fn delete(path: Option<&Path>, monitor_id: &str) -> Result<(), RepositoryError> {
+		delete_row(path, MONITORS_TABLE, monitor_id)
+	}
+
+	fn get(&self, monitor_id: &str) -> Option<Monitor> {
+		self.monitors.get(monitor_id).cloned()
+	}
+
+	fn get_all(&self) -> HashMap<String, Monitor> {
+		self.monitors.clone()
+	}
+}
+
+/// Imports a JSON configuration directory into the configuration database
+///
+/// The directory is loaded and validated like on startup, then replaces the whole content of
+/// the database in a single transaction, so a failed import leaves the database unchanged.
+///
+/// # Arguments
+/// * `database` - Path of the database, or the default database path if None
+/// * `config_dir` - Directory containing the networks, monitors and triggers folders
+///
+/// # Returns
+/// * `Result<ConfigCounts, RepositoryError>` - Number of imported configurations
+pub fn import_config_dir(
+	database: Option<&Path>,
+	config_dir: &Path,
+) -> Result<ConfigCounts, RepositoryError> {
+	let networks = NetworkRepository::new(Some(&config_dir.join("networks")))?;
+	let triggers = TriggerRepository::new(Some(&config_dir.join("triggers")))?;
+	let monitors = MonitorRepository::<NetworkRepository, TriggerRepository>::load_all(
+		Some(&config_dir.join("monitors")),
+		Some(NetworkService::new_with_repository(networks.clone())?),
+		Some(TriggerService::new_with_repository(triggers.clone())?),
+	)?;
+
+	let mut connection = open_database(database)?;
+	let transaction_error = |e: rusqlite::Error| {
+		RepositoryError::internal_error(
+			"Failed to import configuration",
+			Some(Box::new(e)),
+			database_metadata(database.unwrap_or(Path::new(DEFAULT_DATABASE_PATH))),
+		)
+	};
+	let transaction = connection.transaction().map_err(transaction_error)?;
+	for table in [NETWORKS_TABLE, MONITORS_TABLE, TRIGGERS_TABLE] {
+		transaction
+			.execute(&format!("DELETE FROM {}", table), [])
+			.map_err(transaction_error)?;
+	}
+	for (id, network) in &networks.networks {
+		upsert_row(&transaction, NETWORKS_TABLE, id, network)?;
+	}
+	for (id, monitor) in &monitors {
+		upsert_row(&transaction, MONITORS_TABLE, id, monitor)?;
+	}
+	for (id, trigger) in &triggers.triggers {
+		upsert_row(&transaction, TRIGGERS_TABLE, id, trigger)?;
+	}
+	transaction.commit().map_err(transaction_error)?;
+
+	Ok(ConfigCounts {
+		networks: networks.networks.len(),
+		monitors: monitors.len(),
+		triggers: triggers.triggers.len(),
+	})
+}
+
+/// Exports the configuration database to a JSON configuration directory
+///
+/// Every configuration is written to a file named after its ID, in the layout loaded by the
+/// file-based repositories. Existing files with the same name are replaced, other files are
+/// kept.
+///
+/// # Arguments
+/// * `database` - Path of the database, or the default database path if None
+/// * `config_dir` - Directory the networks, monitors and triggers folders are written to
+///
+/// # Returns
+/// * `Result<ConfigCounts, RepositoryError>` - Number of exported configurations
+pub fn export_config_dir(
+	database: Option<&Path>,
+	config_dir: &Path,
+) -> Result<ConfigCounts, RepositoryError> {
+	let connection = open_database(database)?;
+	let networks: HashMap<String, Network> = load_table(&connection, NETWORKS_TABLE)?;
+	let monitors: HashMap<String, Monitor> = load_table(&connection, MONITORS_TABLE)?;
+	let triggers: HashMap<String, Trigger> = load_table(&connection, TRIGGERS_TABLE)?;
+
+	for directory in ["networks", "monitors", "triggers"] {
+		let path = config_dir.join(directory);
+		fs::create_dir_all(&path).map_err(|e| {
+			RepositoryError::internal_error(
+				"Failed to create configuration directory",
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.display().to_string(),
+				)])),
+			)
+		})?;
+	}
+
+	for (id, network) in &networks {
+		write_json_file(
+			&config_dir.join("networks").join(format!("{}.json", id)),
+			network,
+		)?;
+	}
+	for (id, monitor) in &monitors {
+		write_json_file(
+			&config_dir.join("monitors").join(format!("{}.json", id)),
+			monitor,
+		)?;
+	}
+	for (id, trigger) in &triggers {
+		write_json_file(
+			&config_dir.join("triggers").join(format!("{}.json", id)),
+			&HashMap::from([(id, trigger)]),
+		)?;
+	}
+
+	Ok(ConfigCounts {
+		networks: networks.len(),
+		monitors: monitors.len(),
+		triggers: triggers.len(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{
+		BlockChainType, NotificationMessage, RpcUrl, TriggerType, TriggerTypeConfig,
+	};
+	use tempfile::TempDir;
+
+	type Monitors = SqliteMonitorRepository<SqliteNetworkRepository, SqliteTriggerRepository>;
+
+	fn create_network(slug: &str) -> Network {
+		Network {
+			network_type: BlockChainType::EVM,
+			slug: slug.to_string(),
+			name: slug.to_string(),
+			rpc_urls: vec![RpcUrl {
+				type_: "rpc".to_string(),
+				url: "http://localhost:8545".to_string(),
+				weight: 100,
+			}],
+			chain_id: Some(1),
+			network_passphrase: None,
+			block_time_ms: 12000,
+			confirmation_blocks: 1,
+			cron_schedule: "0 */1 * * * *".to_string(),
+			max_past_blocks: None,
+			store_blocks: None,
+			explorer_url: None,
+		}
+	}
+
+	fn create_trigger(name: &str) -> Trigger {
+		Trigger {
+			name: name.to_string(),
+			trigger_type: TriggerType::Webhook,
+			config: TriggerTypeConfig::Webhook {
+				url: "https://example.com/webhook".to_string(),
+				method: Some("POST".to_string()),
+				headers: None,
+				secret: None,
+				secrets: None,
+				message: NotificationMessage {
+					title: "Alert".to_string(),
+					body: "Transaction ${transaction_hash}".to_string(),
+				},
+				payload: None,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		}
+	}
+
+	fn create_monitor(name: &str, network: &str, trigger: &str) -> Monitor {
+		Monitor {
+			name: name.to_string(),
+			networks: vec![network.to_string()],
+			triggers: vec![trigger.to_string()],
+			..Default::default()
+		}
+	}
+
+	fn populate(database: &Path) {
+		SqliteNetworkRepository::save(
+			Some(database),
+			"ethereum_mainnet",
+			&create_network("ethereum_mainnet"),
+		)
+		.unwrap();
+		SqliteTriggerRepository::save(Some(database), "webhook", &create_trigger("webhook"))
+			.unwrap();
+		Monitors::save(
+			Some(database),
+			"transfers",
+			&create_monitor("transfers", "ethereum_mainnet", "webhook"),
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn test_migrations_are_applied_once() {
+		let temp_dir = TempDir::new().unwrap();
+		let database = temp_dir.path().join("nested/config.db");
+
+		open_database(Some(&database)).unwrap();
+		let connection = open_database(Some(&database)).unwrap();
+
+		let versions: Vec<i64> = connection
+			.prepare("SELECT version FROM schema_migrations ORDER BY version")
+			.unwrap()
+			.query_map([], |row| row.get(0))
+			.unwrap()
+			.collect::<rusqlite::Result<_>>()
+			.unwrap();
+		let expected: Vec<i64> = MIGRATIONS.iter().map(|(version, _)| *version).collect();
+		assert_eq!(versions, expected);
+	}
+
+	#[test]
+	fn test_save_load_and_delete() {
+		let temp_dir = TempDir::new().unwrap();
+		let database = temp_dir.path().join("config.db");
+		populate(&database);
+
+		let networks = SqliteNetworkRepository::new(Some(&database)).unwrap();
+		assert_eq!(
+			networks.get("ethereum_mainnet"),
+			Some(create_network("ethereum_mainnet"))
+		);
+		let triggers = SqliteTriggerRepository::new(Some(&database)).unwrap();
+		assert_eq!(triggers.get("webhook"), Some(create_trigger("webhook")));
+		let monitors = Monitors::new(Some(&database), None, None).unwrap();
+		assert_eq!(monitors.get_all().len(), 1);
+
+		// Saving under an existing ID replaces the configuration
+		let mut renamed = create_monitor("renamed", "ethereum_mainnet", "webhook");
+		renamed.paused = true;
+		Monitors::save(Some(&database), "transfers", &renamed).unwrap();
+		let monitors = Monitors::new(Some(&database), None, None).unwrap();
+		assert_eq!(monitors.get("transfers"), Some(renamed));
+
+		Monitors::delete(Some(&database), "transfers").unwrap();
+		Monitors::delete(Some(&database), "transfers").unwrap();
+		SqliteTriggerRepository::delete(Some(&database), "webhook").unwrap();
+		assert!(Monitors::new(Some(&database), None, None)
+			.unwrap()
+			.get_all()
+			.is_empty());
+		assert!(SqliteTriggerRepository::new(Some(&database))
+			.unwrap()
+			.get_all()
+			.is_empty());
+	}
+
+	#[test]
+	fn test_save_rejects_invalid_ids() {
+		let temp_dir = TempDir::new().unwrap();
+		let database = temp_dir.path().join("config.db");
+
+		let result =
+			SqliteTriggerRepository::save(Some(&database), "../webhook", &create_trigger("a"));
+		assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+	}
+
+	#[test]
+	fn test_load_validates_configurations() {
+		let temp_dir = TempDir::new().unwrap();
+		let database = temp_dir.path().join("config.db");
+		populate(&database);
+
+		Monitors::save(
+			Some(&database),
+			"dangling",
+			&create_monitor("dangling", "ethereum_mainnet", "missing"),
+		)
+		.unwrap();
+		assert!(matches!(
+			Monitors::new(Some(&database), None, None),
+			Err(RepositoryError::ValidationError(_))
+		));
+
+		let invalid = create_network("Invalid-Slug");
+		SqliteNetworkRepository::save(Some(&database), "invalid", &invalid).unwrap();
+		assert!(matches!(
+			SqliteNetworkRepository::new(Some(&database)),
+			Err(RepositoryError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_export_and_import_round_trip() {
+		let temp_dir = TempDir::new().unwrap();
+		let database = temp_dir.path().join("config.db");
+		let config_dir = temp_dir.path().join("config");
+		populate(&database);
+
+		let exported = export_config_dir(Some(&database), &config_dir).unwrap();
+		let expected = ConfigCounts {
+			networks: 1,
+			monitors: 1,
+			triggers: 1,
+		};
+		assert_eq!(exported, expected);
+		assert!(config_dir.join("triggers/webhook.json").exists());
+
+		let imported_database = temp_dir.path().join("imported.db");
+		// Configurations that are no longer in the directory are removed by the import
+		SqliteTriggerRepository::save(Some(&imported_database), "stale", &create_trigger("stale"))
+			.unwrap();
+		let imported = import_config_dir(Some(&imported_database), &config_dir).unwrap();
+		assert_eq!(imported, expected);
+
+		assert_eq!(
+			Monitors::new(Some(&imported_database), None, None)
+				.unwrap()
+				.get_all(),
+			Monitors::new(Some(&database), None, None)
+				.unwrap()
+				.get_all()
+		);
+		assert_eq!(
+			SqliteTriggerRepository::new(Some(&imported_database))
+				.unwrap()
+				.get_all(),
+			SqliteTriggerRepository::new(Some(&database))
+				.unwrap()
+				.get_all()
+		);
+		assert_eq!(
+			SqliteNetworkRepository::new(Some(&imported_database))
+				.unwrap()
+				.get_all(),
+			SqliteNetworkRepository::new(Some(&database))
+				.unwrap()
+				.get_all()
+		);
+	}
+
+	#[test]
+	fn test_failed_import_keeps_database() {
+		let temp_dir = TempDir::new().unwrap();
+		let database = temp_dir.path().join("config.db");
+		let config_dir = temp_dir.path().join("config");
+		populate(&database);
+		export_config_dir(Some(&database), &config_dir).unwrap();
+		fs::remove_file(config_dir.join("triggers/webhook.json")).unwrap();
+
+		assert!(import_config_dir(Some(&database), &config_dir).is_err());
+		assert_eq!(
+			Monitors::new(Some(&database), None, None)
+				.unwrap()
+				.get_all()
+				.len(),
+			1
+		);
+	}
+}
@@ -0,0 +1,132 @@
+//! Configuration storage selection.
+//!
+//! The service reads its networks, monitors and triggers either from the JSON files of a config
+//! directory or from the SQLite configuration database. The storage decides which path the
+//! repositories are opened with, so the same repository types are used to load, reload and
+//! manage the configuration.
+
+#![allow(clippy::result_large_err)]
+
+use std::path::{Path, PathBuf};
+
+use crate::repositories::{
+	MonitorRepositoryTrait, MonitorService, NetworkRepositoryTrait, NetworkService,
+	RepositoryError, TriggerRepositoryTrait, TriggerService,
+};
+
+/// Monitor, network and trigger services loaded from a storage
+type Services<M, N, T> = (
+	MonitorService<M, N, T>,
+	NetworkService<N>,
+	TriggerService<T>,
+);
+
+/// Storage the configuration is loaded from and saved to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigStorage {
+	/// Directory containing the networks, monitors and triggers folders
+	Files(PathBuf),
+	/// SQLite configuration database
+	Sqlite(PathBuf),
+}
+
+impl ConfigStorage {
+	/// Returns the directory or database file holding the configuration
+	pub fn location(&self) -> &Path {
+		match self {
+			ConfigStorage::Files(config_dir) => config_dir,
+			ConfigStorage::Sqlite(database) => database,
+		}
+	}
+
+	/// Returns the path the repositories of a configuration type are opened with
+	///
+	/// # Arguments
+	/// * `folder` - Folder of the configuration type in the config directory, e.g. `monitors`
+	///
+	/// # Returns
+	/// * `PathBuf` - The folder of the config directory, or the database for SQLite
+	pub fn path(&self, folder: &str) -> PathBuf {
+		match self {
+			ConfigStorage::Files(config_dir) => config_dir.join(folder),
+			ConfigStorage::Sqlite(database) => database.clone(),
+		}
+	}
+
+	/// Loads the network, trigger and monitor services from the storage
+	///
+	/// Monitors are loaded last so their references to networks and triggers are validated.
+	///
+	/// # Errors
+	/// Returns an error if a configuration cannot be loaded or is invalid
+	pub fn load_services<M, N, T>(&self) -> Result<Services<M, N, T>, RepositoryError>
+	where
+		M: MonitorRepositoryTrait<N, T>,
+		N: NetworkRepositoryTrait,
+		T: TriggerRepositoryTrait,
+	{
+		let network_service =
+			NetworkService::<N>::new_with_repository(N::new(Some(&self.path("networks")))?)?;
+		let trigger_service =
+			TriggerService::<T>::new_with_repository(T::new(Some(&self.path("triggers")))?)?;
+		let monitor_service = MonitorService::<M, N, T>::new_with_repository(M::new(
+			Some(&self.path("monitors")),
+			Some(network_service.clone()),
+			Some(trigger_service.clone()),
+		)?)?;
+
+		Ok((monitor_service, network_service, trigger_service))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::repositories::{
+		MonitorRepository, NetworkRepository, SqliteMonitorRepository, SqliteNetworkRepository,
+		SqliteTriggerRepository, TriggerRepository,
+	};
+	use std::fs;
+	use tempfile::TempDir;
+
+	type FileMonitorRepository = MonitorRepository<NetworkRepository, TriggerRepository>;
+	type DatabaseMonitorRepository =
+		SqliteMonitorRepository<SqliteNetworkRepository, SqliteTriggerRepository>;
+
+	#[test]
+	fn test_paths() {
+		let files = ConfigStorage::Files(PathBuf::from("config"));
+		assert_eq!(files.path("monitors"), PathBuf::from("config/monitors"));
+		assert_eq!(files.location(), Path::new("config"));
+
+		let sqlite = ConfigStorage::Sqlite(PathBuf::from("data/config.db"));
+		assert_eq!(sqlite.path("monitors"), PathBuf::from("data/config.db"));
+		assert_eq!(sqlite.location(), Path::new("data/config.db"));
+	}
+
+	#[test]
+	fn test_load_services() {
+		let temp_dir = TempDir::new().unwrap();
+		for folder in ["networks", "monitors", "triggers"] {
+			fs::create_dir(temp_dir.path().join(folder)).unwrap();
+		}
+
+		let (monitor_service, network_service, trigger_service) =
+			ConfigStorage::Files(temp_dir.path().to_path_buf())
+				.load_services::<FileMonitorRepository, NetworkRepository, TriggerRepository>()
+				.unwrap();
+		assert!(monitor_service.get_all().is_empty());
+		assert!(network_service.get_all().is_empty());
+		assert!(trigger_service.get_all().is_empty());
+
+		// The database is created and migrated when it does not exist yet
+		let (monitor_service, network_service, trigger_service) =
+			ConfigStorage::Sqlite(temp_dir.path().join("config.db"))
+				.load_services::<DatabaseMonitorRepository, SqliteNetworkRepository, SqliteTriggerRepository>(
+				)
+				.unwrap();
+		assert!(monitor_service.get_all().is_empty());
+		assert!(network_service.get_all().is_empty());
+		assert!(trigger_service.get_all().is_empty());
+	}
+}
@@ -6,13 +6,26 @@
 
 #![allow(clippy::result_large_err)]
 
-use std::{collections::HashMap, path::Path};
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
 
 use crate::{
 	models::{ConfigLoader, Trigger},
-	repositories::error::RepositoryError,
+	repositories::{
+		error::RepositoryError,
+		persistence::{remove_file, validate_config_id, write_json_file},
+	},
 };
 
+/// Default directory of the trigger configuration files
+const DEFAULT_TRIGGER_DIR: &str = "config/triggers";
+
+/// Triggers of a single configuration file keyed by ID
+type TriggerFile = serde_json::Map<String, serde_json::Value>;
+
 /// Repository for storing and retrieving trigger configurations
 #[derive(Clone)]
 pub struct TriggerRepository {
@@ -29,6 +42,43 @@ impl TriggerRepository {
 		let triggers = Self::load_all(path)?;
 		Ok(TriggerRepository { triggers })
 	}
+
+	/// Finds the configuration file defining a trigger
+	///
+	/// A file can define several triggers, so the files of the directory are searched for the
+	/// trigger ID.
+	fn find_trigger_file(
+		trigger_dir: &Path,
+		trigger_id: &str,
+	) -> Result<Option<(PathBuf, TriggerFile)>, RepositoryError> {
+		let entries = fs::read_dir(trigger_dir).map_err(|e| {
+			RepositoryError::load_error(
+				"Failed to read triggers directory",
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					trigger_dir.display().to_string(),
+				)])),
+			)
+		})?;
+
+		for entry in entries.flatten() {
+			let file_path = entry.path();
+			if !Trigger::is_json_file(&file_path) {
+				continue;
+			}
+			let Ok(content) = fs::read_to_string(&file_path) else {
+				continue;
+			};
+			if let Ok(triggers) = serde_json::from_str::<TriggerFile>(&content) {
+				if triggers.contains_key(trigger_id) {
+					return Ok(Some((file_path, triggers)));
+				}
+			}
+		}
+
+		Ok(None)
+	}
 }
 
 /// Interface for trigger repository implementations
@@ -47,6 +97,24 @@ pub trait TriggerRepositoryTrait: Clone {
 	/// This is a static method that doesn't require an instance.
 	fn load_all(path: Option<&Path>) -> Result<HashMap<String, Trigger>, RepositoryError>;
 
+	/// Save a trigger configuration to the given path
+	///
+	/// If no path is provided, uses the default config directory. An existing trigger is
+	/// replaced in the file defining it, a new one is stored in a file named after its ID.
+	/// This is a static method that doesn't require an instance.
+	fn save(
+		path: Option<&Path>,
+		trigger_id: &str,
+		trigger: &Trigger,
+	) -> Result<(), RepositoryError>;
+
+	/// Delete a trigger configuration from the given path
+	///
+	/// If no path is provided, uses the default config directory. Other triggers defined in
+	/// the same file are kept. Deleting a trigger that doesn't exist is not an error.
+	/// This is a static method that doesn't require an instance.
+	fn delete(path: Option<&Path>, trigger_id: &str) -> Result<(), RepositoryError>;
+
 	/// Get a specific trigger by ID
 	///
 	/// Returns None if the trigger doesn't exist.
@@ -76,6 +144,44 @@ impl TriggerRepositoryTrait for TriggerRepository {
 		})
 	}
 
+	fn save(
+		path: Option<&Path>,
+		trigger_id: &str,
+		trigger: &Trigger,
+	) -> Result<(), RepositoryError> {
+		validate_config_id(trigger_id)?;
+		let trigger_dir = path.unwrap_or(Path::new(DEFAULT_TRIGGER_DIR));
+		let (file_path, mut triggers) = match Self::find_trigger_file(trigger_dir, trigger_id)? {
+			Some(found) => found,
+			None => (
+				trigger_dir.join(format!("{}.json", trigger_id)),
+				TriggerFile::new(),
+			),
+		};
+
+		let value = serde_json::to_value(trigger).map_err(|e| {
+			RepositoryError::internal_error("Failed to serialize trigger", Some(Box::new(e)), None)
+		})?;
+		triggers.insert(trigger_id.to_string(), value);
+		write_json_file(&file_path, &triggers)
+	}
+
+	fn delete(path: Option<&Path>, trigger_id: &str) -> Result<(), RepositoryError> {
+		validate_config_id(trigger_id)?;
+		let trigger_dir = path.unwrap_or(Path::new(DEFAULT_TRIGGER_DIR));
+		let Some((file_path, mut triggers)) = Self::find_trigger_file(trigger_dir, trigger_id)?
+		else {
+			return Ok(());
+		};
+
+		triggers.remove(trigger_id);
+		if triggers.is_empty() {
+			remove_file(&file_path)
+		} else {
+			write_json_file(&file_path, &triggers)
+		}
+	}
+
 	fn get(&self, trigger_id: &str) -> Option<Trigger> {
 		self.triggers.get(trigger_id).cloned()
 	}
@@ -145,4 +251,61 @@ mod tests {
 			_ => panic!("Expected RepositoryError::LoadError"),
 		}
 	}
+
+	fn create_trigger(name: &str) -> Trigger {
+		Trigger {
+			name: name.to_string(),
+			trigger_type: crate::models::TriggerType::Webhook,
+			config: crate::models::TriggerTypeConfig::Webhook {
+				url: "https://example.com/webhook".to_string(),
+				method: Some("POST".to_string()),
+				secret: None,
+				secrets: None,
+				headers: None,
+				message: crate::models::NotificationMessage {
+					title: "title".to_string(),
+					body: "body".to_string(),
+				},
+				payload: None,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		}
+	}
+
+	#[test]
+	fn test_save_and_delete_trigger() {
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let path = Some(temp_dir.path());
+		fs::write(
+			temp_dir.path().join("shared.json"),
+			serde_json::to_string(&HashMap::from([
+				("first", create_trigger("first")),
+				("second", create_trigger("second")),
+			]))
+			.unwrap(),
+		)
+		.unwrap();
+
+		// Updating a trigger keeps it in the file defining it
+		let mut updated = create_trigger("first");
+		updated.name = "updated".to_string();
+		TriggerRepository::save(path, "first", &updated).unwrap();
+		// A new trigger gets its own file
+		TriggerRepository::save(path, "third", &create_trigger("third")).unwrap();
+
+		let triggers = TriggerRepository::load_all(path).unwrap();
+		assert_eq!(triggers.len(), 3);
+		assert_eq!(triggers["first"].name, "updated");
+		assert!(temp_dir.path().join("third.json").exists());
+
+		// Deleting keeps the other triggers of the file, and removes the file once empty
+		TriggerRepository::delete(path, "first").unwrap();
+		TriggerRepository::delete(path, "third").unwrap();
+		TriggerRepository::delete(path, "missing").unwrap();
+		let triggers = TriggerRepository::load_all(path).unwrap();
+		assert_eq!(triggers.keys().collect::<Vec<_>>(), vec!["second"]);
+		assert!(!temp_dir.path().join("third.json").exists());
+	}
 }
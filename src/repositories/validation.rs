@@ -0,0 +1,572 @@
+//! Configuration validation and linting.
+//!
+//! Loads network, monitor and trigger configurations from a config directory without starting
+//! any service, checks the references between them and lints for configurations that are valid
+//! but likely unintended:
+//! - Triggers that no monitor uses
+//! - Monitors without addresses
+//! - Networks without active monitors
+//! - Scripts that do not exist or are not executable
+//! - Cron schedules that run faster than the network's block time
+
+use serde::Serialize;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	path::Path,
+};
+
+use crate::{
+	models::{ConfigLoader, Monitor, Network, Trigger, TriggerTypeConfig},
+	repositories::{MonitorRepository, NetworkRepository, RepositoryError, TriggerRepository},
+	utils::get_cron_interval_ms,
+};
+
+/// Severity of a configuration issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+	/// The configuration loads, but is likely unintended
+	Warning,
+	/// The configuration would prevent the service from starting
+	Error,
+}
+
+impl fmt::Display for IssueSeverity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			IssueSeverity::Warning => write!(f, "warning"),
+			IssueSeverity::Error => write!(f, "error"),
+		}
+	}
+}
+
+/// A single problem found in the configuration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigIssue {
+	/// Severity of the issue
+	pub severity: IssueSeverity,
+	/// Machine-readable kind of issue, e.g. `unused_trigger`
+	pub kind: String,
+	/// Human-readable description of the issue
+	pub message: String,
+}
+
+impl ConfigIssue {
+	fn error(kind: &str, message: impl Into<String>) -> Self {
+		Self {
+			severity: IssueSeverity::Error,
+			kind: kind.to_string(),
+			message: message.into(),
+		}
+	}
+
+	fn warning(kind: &str, message: impl Into<String>) -> Self {
+		Self {
+			severity: IssueSeverity::Warning,
+			kind: kind.to_string(),
+			message: message.into(),
+		}
+	}
+}
+
+/// Result of validating a configuration directory
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigReport {
+	/// Number of networks loaded
+	pub networks: usize,
+	/// Number of monitors loaded
+	pub monitors: usize,
+	/// Number of triggers loaded
+	pub triggers: usize,
+	/// Issues found, errors first
+	pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+	/// Returns true if any issue would prevent the service from starting
+	pub fn has_errors(&self) -> bool {
+		self.issues
+			.iter()
+			.any(|issue| issue.severity == IssueSeverity::Error)
+	}
+
+	fn count(&self, severity: IssueSeverity) -> usize {
+		self.issues
+			.iter()
+			.filter(|issue| issue.severity == severity)
+			.count()
+	}
+}
+
+impl fmt::Display for ConfigReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"Loaded {} network(s), {} monitor(s), {} trigger(s)",
+			self.networks, self.monitors, self.triggers
+		)?;
+		for issue in &self.issues {
+			writeln!(f, "{} [{}]: {}", issue.severity, issue.kind, issue.message)?;
+		}
+		write!(
+			f,
+			"{} error(s), {} warning(s)",
+			self.count(IssueSeverity::Error),
+			self.count(IssueSeverity::Warning)
+		)
+	}
+}
+
+/// Validates and lints the configuration in a config directory
+///
+/// Networks, monitors and triggers are loaded from the `networks`, `monitors` and `triggers`
+/// subdirectories with their [`ConfigLoader`] implementations. References between monitors,
+/// networks and triggers are only checked when all three load successfully.
+///
+/// # Arguments
+/// * `config_dir` - Directory containing the configuration subdirectories
+///
+/// # Returns
+/// * `ConfigReport` - Loaded configuration counts and all issues found
+pub fn validate_config_dir(config_dir: &Path) -> ConfigReport {
+	let mut report = ConfigReport::default();
+
+	let networks = load_config::<Network>(&config_dir.join("networks"), &mut report.issues);
+	let triggers = load_config::<Trigger>(&config_dir.join("triggers"), &mut report.issues);
+	let monitors = load_config::<Monitor>(&config_dir.join("monitors"), &mut report.issues);
+
+	if let (Some(networks), Some(triggers), Some(monitors)) = (networks, triggers, monitors) {
+		check_config(&mut report, &monitors, &triggers, &networks);
+	}
+
+	report
+		.issues
+		.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+	report
+}
+
+/// Validates and lints a configuration that is already in memory
+///
+/// Used to check a configuration before it is written, e.g. the current configuration with a
+/// change made through the management API. Every configuration is validated like when it is
+/// loaded, then the references between them are checked and the configuration is linted.
+///
+/// # Arguments
+/// * `monitors` - Monitors keyed by name
+/// * `triggers` - Triggers keyed by name
+/// * `networks` - Networks keyed by slug
+///
+/// # Returns
+/// * `ConfigReport` - Configuration counts and all issues found
+pub fn validate_config(
+	monitors: &HashMap<String, Monitor>,
+	triggers: &HashMap<String, Trigger>,
+	networks: &HashMap<String, Network>,
+) -> ConfigReport {
+	let mut report = ConfigReport::default();
+
+	let mut invalid = validation_issues("Network", networks);
+	invalid.extend(validation_issues("Trigger", triggers));
+	invalid.extend(validation_issues("Monitor", monitors));
+	if invalid.is_empty() {
+		check_config(&mut report, monitors, triggers, networks);
+	} else {
+		report.issues = invalid;
+	}
+
+	report
+		.issues
+		.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+	report
+}
+
+/// Checks the references of a loaded configuration and lints it into a report
+fn check_config(
+	report: &mut ConfigReport,
+	monitors: &HashMap<String, Monitor>,
+	triggers: &HashMap<String, Trigger>,
+	networks: &HashMap<String, Network>,
+) {
+	report.networks = networks.len();
+	report.triggers = triggers.len();
+	report.monitors = monitors.len();
+
+	if let Err(e) =
+		MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+			monitors, triggers, networks,
+		) {
+		report.issues.extend(reference_issues(e));
+	}
+	report
+		.issues
+		.extend(lint_config(monitors, triggers, networks));
+}
+
+/// Validates every configuration of one type, in a stable order
+fn validation_issues<C: ConfigLoader>(
+	kind: &str,
+	configs: &HashMap<String, C>,
+) -> Vec<ConfigIssue> {
+	let mut names: Vec<&String> = configs.keys().collect();
+	names.sort();
+	names
+		.into_iter()
+		.filter_map(|name| configs[name].validate().err().map(|e| (name, e)))
+		.map(|(name, e)| {
+			ConfigIssue::error(
+				"invalid_config",
+				format!("{} '{}' is invalid: {}", kind, name, e),
+			)
+		})
+		.collect()
+}
+
+/// Lints a loaded configuration for likely mistakes
+///
+/// # Arguments
+/// * `monitors` - Monitors keyed by name
+/// * `triggers` - Triggers keyed by name
+/// * `networks` - Networks keyed by slug
+///
+/// # Returns
+/// * `Vec<ConfigIssue>` - Issues found, in a stable order
+pub fn lint_config(
+	monitors: &HashMap<String, Monitor>,
+	triggers: &HashMap<String, Trigger>,
+	networks: &HashMap<String, Network>,
+) -> Vec<ConfigIssue> {
+	let mut issues = Vec::new();
+
+	let mut monitor_names: Vec<&String> = monitors.keys().collect();
+	monitor_names.sort();
+	for name in &monitor_names {
+		let monitor = &monitors[*name];
+		if monitor.addresses.is_empty() {
+			issues.push(ConfigIssue::warning(
+				"monitor_without_addresses",
+				format!("Monitor '{}' has no addresses", name),
+			));
+		}
+		for condition in &monitor.trigger_conditions {
+			issues.extend(lint_script(
+				&format!("Monitor '{}' trigger condition", name),
+				&condition.script_path,
+			));
+		}
+	}
+
+	let used_triggers: HashSet<&String> = monitors.values().flat_map(|m| &m.triggers).collect();
+	let mut trigger_names: Vec<&String> = triggers.keys().collect();
+	trigger_names.sort();
+	for name in trigger_names {
+		if !used_triggers.contains(name) {
+			issues.push(ConfigIssue::warning(
+				"unused_trigger",
+				format!("Trigger '{}' is not used by any monitor", name),
+			));
+		}
+		if let TriggerTypeConfig::Script { script_path, .. } = &triggers[name].config {
+			issues.extend(lint_script(&format!("Trigger '{}'", name), script_path));
+		}
+	}
+
+	let monitored_networks: HashSet<&String> = monitors
+		.values()
+		.filter(|m| !m.paused)
+		.flat_map(|m| &m.networks)
+		.collect();
+	let mut network_slugs: Vec<&String> = networks.keys().collect();
+	network_slugs.sort();
+	for slug in network_slugs {
+		let network = &networks[slug];
+		if !monitored_networks.contains(slug) {
+			issues.push(ConfigIssue::warning(
+				"network_without_monitors",
+				format!("Network '{}' has no active monitors", slug),
+			));
+		}
+		if let Some(interval_ms) = get_cron_interval_ms(&network.cron_schedule) {
+			if interval_ms >= 0 && (interval_ms as u64) < network.block_time_ms {
+				issues.push(ConfigIssue::warning(
+					"cron_faster_than_block_time",
+					format!(
+						"Network '{}' checks for blocks every {}ms but produces a block every {}ms",
+						slug, interval_ms, network.block_time_ms
+					),
+				));
+			}
+		}
+	}
+
+	issues
+}
+
+/// Loads all configurations of one type, recording a load failure as an issue
+fn load_config<C: ConfigLoader>(
+	path: &Path,
+	issues: &mut Vec<ConfigIssue>,
+) -> Option<HashMap<String, C>> {
+	match C::load_all(Some(path)) {
+		Ok(configs) => Some(configs),
+		Err(e) => {
+			issues.push(ConfigIssue::error(
+				"load_error",
+				format!("Failed to load {}: {}", path.display(), e),
+			));
+			None
+		}
+	}
+}
+
+/// Splits a reference validation error into one issue per invalid reference
+fn reference_issues(error: RepositoryError) -> Vec<ConfigIssue> {
+	match error {
+		RepositoryError::ValidationError(ctx) => ctx
+			.message
+			.lines()
+			.skip(1)
+			.map(|line| ConfigIssue::error("invalid_reference", line))
+			.collect(),
+		other => vec![ConfigIssue::error("invalid_reference", other.to_string())],
+	}
+}
+
+/// Checks that a script exists and can be executed
+fn lint_script(owner: &str, script_path: &str) -> Vec<ConfigIssue> {
+	let path = Path::new(script_path);
+	let Ok(metadata) = path.metadata() else {
+		return vec![ConfigIssue::error(
+			"script_not_found",
+			format!("{} script does not exist: {}", owner, script_path),
+		)];
+	};
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		if metadata.permissions().mode() & 0o111 == 0 {
+			return vec![ConfigIssue::warning(
+				"script_not_executable",
+				format!("{} script is not executable: {}", owner, script_path),
+			)];
+		}
+	}
+	#[cfg(not(unix))]
+	let _ = metadata;
+
+	vec![]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{BlockChainType, MatchConditions, RpcUrl, TriggerType};
+	use std::fs;
+	use tempfile::TempDir;
+
+	fn create_network(slug: &str, cron_schedule: &str, block_time_ms: u64) -> Network {
+		Network {
+			network_type: BlockChainType::EVM,
+			slug: slug.to_string(),
+			name: slug.to_string(),
+			rpc_urls: vec![RpcUrl {
+				type_: "rpc".to_string(),
+				url: "http://localhost:8545".to_string(),
+				weight: 100,
+			}],
+			chain_id: Some(1),
+			network_passphrase: None,
+			block_time_ms,
+			confirmation_blocks: 1,
+			cron_schedule: cron_schedule.to_string(),
+			max_past_blocks: None,
+			store_blocks: None,
+			explorer_url: None,
+		}
+	}
+
+	fn create_trigger(name: &str, script_path: &str) -> Trigger {
+		Trigger {
+			name: name.to_string(),
+			trigger_type: TriggerType::Script,
+			config: TriggerTypeConfig::Script {
+				language: crate::models::ScriptLanguage::Bash,
+				script_path: script_path.to_string(),
+				arguments: None,
+				timeout_ms: 1000,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		}
+	}
+
+	fn create_monitor(networks: Vec<&str>, triggers: Vec<&str>) -> Monitor {
+		Monitor {
+			name: "monitor".to_string(),
+			networks: networks.into_iter().map(String::from).collect(),
+			triggers: triggers.into_iter().map(String::from).collect(),
+			match_conditions: MatchConditions::default(),
+			..Default::default()
+		}
+	}
+
+	fn kinds(issues: &[ConfigIssue]) -> Vec<&str> {
+		issues.iter().map(|issue| issue.kind.as_str()).collect()
+	}
+
+	#[test]
+	fn test_lint_config_reports_unused_and_empty_entries() {
+		let temp_dir = TempDir::new().unwrap();
+		let script_path = temp_dir.path().join("notify.sh");
+		fs::write(&script_path, "#!/bin/bash").unwrap();
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755)).unwrap();
+		}
+		let script_path = script_path.to_string_lossy().to_string();
+
+		let monitors = HashMap::from([(
+			"monitor".to_string(),
+			create_monitor(vec!["ethereum_mainnet"], vec!["used"]),
+		)]);
+		let triggers = HashMap::from([
+			("used".to_string(), create_trigger("used", &script_path)),
+			("unused".to_string(), create_trigger("unused", &script_path)),
+		]);
+		let networks = HashMap::from([
+			(
+				"ethereum_mainnet".to_string(),
+				create_network("ethereum_mainnet", "0 */1 * * * *", 12000),
+			),
+			(
+				"sepolia".to_string(),
+				create_network("sepolia", "0 */1 * * * *", 12000),
+			),
+		]);
+
+		let issues = lint_config(&monitors, &triggers, &networks);
+
+		assert_eq!(
+			kinds(&issues),
+			vec![
+				"monitor_without_addresses",
+				"unused_trigger",
+				"network_without_monitors"
+			]
+		);
+		assert!(issues
+			.iter()
+			.all(|issue| issue.severity == IssueSeverity::Warning));
+	}
+
+	#[test]
+	fn test_lint_config_reports_scripts_and_cron() {
+		let temp_dir = TempDir::new().unwrap();
+		let script_path = temp_dir.path().join("notify.sh");
+		fs::write(&script_path, "#!/bin/bash").unwrap();
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			fs::set_permissions(&script_path, fs::Permissions::from_mode(0o644)).unwrap();
+		}
+
+		let monitors = HashMap::from([(
+			"monitor".to_string(),
+			create_monitor(vec!["ethereum_mainnet"], vec!["missing", "plain"]),
+		)]);
+		let triggers = HashMap::from([
+			(
+				"missing".to_string(),
+				create_trigger("missing", "/nonexistent/script.sh"),
+			),
+			(
+				"plain".to_string(),
+				create_trigger("plain", &script_path.to_string_lossy()),
+			),
+		]);
+		let networks = HashMap::from([(
+			"ethereum_mainnet".to_string(),
+			create_network("ethereum_mainnet", "*/1 * * * * *", 12000),
+		)]);
+
+		let issues = lint_config(&monitors, &triggers, &networks);
+		let kinds = kinds(&issues);
+
+		assert!(kinds.contains(&"script_not_found"));
+		#[cfg(unix)]
+		assert!(kinds.contains(&"script_not_executable"));
+		assert!(kinds.contains(&"cron_faster_than_block_time"));
+	}
+
+	#[test]
+	fn test_validate_config_dir_missing_directories() {
+		let temp_dir = TempDir::new().unwrap();
+
+		let report = validate_config_dir(temp_dir.path());
+
+		assert!(report.has_errors());
+		assert_eq!(kinds(&report.issues), vec!["load_error"; 3]);
+	}
+
+	#[test]
+	fn test_validate_config_dir_invalid_references() {
+		let temp_dir = TempDir::new().unwrap();
+		for dir in ["networks", "monitors", "triggers"] {
+			fs::create_dir(temp_dir.path().join(dir)).unwrap();
+		}
+		fs::write(
+			temp_dir.path().join("monitors/monitor.json"),
+			r#"{
+				"name": "monitor",
+				"networks": ["ethereum_mainnet"],
+				"paused": false,
+				"addresses": [{"address": "0x0000000000000000000000000000000000000000"}],
+				"match_conditions": {"functions": [], "events": [], "transactions": []},
+				"trigger_conditions": [],
+				"triggers": ["missing_trigger"]
+			}"#,
+		)
+		.unwrap();
+
+		let report = validate_config_dir(temp_dir.path());
+
+		assert!(report.has_errors());
+		assert_eq!(report.monitors, 1);
+		assert_eq!(
+			kinds(&report.issues),
+			vec!["invalid_reference", "invalid_reference"]
+		);
+		assert!(report.to_string().contains("2 error(s), 0 warning(s)"));
+	}
+
+	#[test]
+	fn test_validate_config_checks_configs_and_references() {
+		let networks = HashMap::from([(
+			"ethereum_mainnet".to_string(),
+			create_network("ethereum_mainnet", "0 */1 * * * *", 12000),
+		)]);
+		let monitors = HashMap::from([(
+			"monitor".to_string(),
+			create_monitor(vec!["ethereum_mainnet"], vec!["missing_trigger"]),
+		)]);
+
+		let report = validate_config(&monitors, &HashMap::new(), &networks);
+		assert_eq!(report.monitors, 1);
+		assert_eq!(
+			kinds(&report.issues),
+			vec!["invalid_reference", "monitor_without_addresses"]
+		);
+
+		// Invalid configurations are reported before their references are checked
+		let triggers = HashMap::from([(
+			"missing_trigger".to_string(),
+			create_trigger("missing_trigger", "/non/existent/script.sh"),
+		)]);
+		let report = validate_config(&monitors, &triggers, &networks);
+		assert!(report.has_errors());
+		assert_eq!(kinds(&report.issues), vec!["invalid_config"]);
+	}
+}
@@ -36,6 +36,14 @@ pub trait BlockChainClient: Send + Sync + Clone {
 		start_block: u64,
 		end_block: Option<u64>,
 	) -> Result<Vec<BlockType>, anyhow::Error>;
+
+	/// Retrieves the RPC URL the client currently sends its requests to
+	///
+	/// # Returns
+	/// * `Option<String>` - The active RPC URL, or None if the client doesn't track one
+	async fn get_current_url(&self) -> Option<String> {
+		None
+	}
 }
 
 /// Defines the factory interface for creating block filters
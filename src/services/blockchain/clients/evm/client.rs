@@ -244,4 +244,9 @@ impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for EvmClien
 			.into_iter()
 			.collect::<Result<Vec<_>, _>>()
 	}
+
+	/// Returns the RPC URL the transport currently uses
+	async fn get_current_url(&self) -> Option<String> {
+		Some(self.alloy_client.get_current_url().await)
+	}
 }
@@ -86,6 +86,19 @@ pub trait StellarClientTrait {
 		start_sequence: u32,
 		end_sequence: Option<u32>,
 	) -> Result<Vec<StellarEvent>, anyhow::Error>;
+
+	/// Retrieves the ledger a transaction was included in
+	///
+	/// # Arguments
+	/// * `transaction_hash` - The hash of the transaction to look up
+	///
+	/// # Returns
+	/// * `Result<Option<u32>, anyhow::Error>` - Ledger sequence, or None if the transaction is
+	///   not found within the retention window of the RPC node
+	async fn get_transaction_ledger(
+		&self,
+		transaction_hash: &str,
+	) -> Result<Option<u32>, anyhow::Error>;
 }
 
 #[async_trait]
@@ -242,6 +255,39 @@ impl<T: Send + Sync + Clone + BlockchainTransport> StellarClientTrait for Stella
 		}
 		Ok(events)
 	}
+
+	/// Retrieves the ledger of a transaction with the `getTransaction` RPC method
+	///
+	/// # Errors
+	/// - Returns `anyhow::Error` if the request fails or the response has no ledger
+	#[instrument(skip(self), fields(transaction_hash))]
+	async fn get_transaction_ledger(
+		&self,
+		transaction_hash: &str,
+	) -> Result<Option<u32>, anyhow::Error> {
+		let params = json!({
+			"hash": transaction_hash.trim_start_matches("0x")
+		});
+
+		let response = self
+			.stellar_client
+			.send_raw_request("getTransaction", Some(params))
+			.await
+			.with_context(|| format!("Failed to get transaction {}", transaction_hash))?;
+
+		let result = &response["result"];
+		if result["status"].as_str() == Some("NOT_FOUND") {
+			return Ok(None);
+		}
+
+		let ledger = result["ledger"]
+			.as_u64()
+			.and_then(|ledger| u32::try_from(ledger).ok())
+			.ok_or_else(|| {
+				anyhow::anyhow!("Invalid ledger for transaction {}", transaction_hash)
+			})?;
+		Ok(Some(ledger))
+	}
 }
 
 impl<T: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for StellarClient<T> {
@@ -350,4 +396,9 @@ impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for StellarC
 		}
 		Ok(blocks)
 	}
+
+	/// Returns the RPC URL the transport currently uses
+	async fn get_current_url(&self) -> Option<String> {
+		Some(self.stellar_client.get_current_url().await)
+	}
 }
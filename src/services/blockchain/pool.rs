@@ -120,6 +120,29 @@ impl ClientPool {
 		Ok(client)
 	}
 
+	/// Removes the cached clients of a network.
+	///
+	/// The next request for the network creates a new client, e.g. after its RPC URLs changed.
+	pub async fn remove_clients(&self, network_slug: &str) {
+		self.remove_client::<EvmClient<AlloyTransportClient>>(BlockChainType::EVM, network_slug)
+			.await;
+		self.remove_client::<StellarClient<StellarTransportClient>>(
+			BlockChainType::Stellar,
+			network_slug,
+		)
+		.await;
+	}
+
+	async fn remove_client<T: 'static>(&self, client_type: BlockChainType, network_slug: &str) {
+		if let Some(storage) = self
+			.storages
+			.get(&client_type)
+			.and_then(|s| s.downcast_ref::<ClientStorage<T>>())
+		{
+			storage.clients.write().await.remove(network_slug);
+		}
+	}
+
 	/// Get the number of clients for a given client type.
 	pub async fn get_client_count<T: 'static>(&self, client_type: BlockChainType) -> usize {
 		match self
@@ -0,0 +1,276 @@
+//! Historical backfill for the block watcher service.
+//!
+//! Runs monitors over a fixed range of already confirmed blocks, independently of the live
+//! block watcher. Blocks are fetched in chunks and processed through the same block and trigger
+//! handlers as live blocks. Progress is checkpointed after every chunk to a backfill-specific
+//! file, so an interrupted backfill resumes where it stopped without touching the live
+//! `last_block` checkpoint.
+
+#![allow(clippy::result_large_err)]
+
+use anyhow::Context;
+use futures::{future::BoxFuture, stream, StreamExt};
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+use tracing::instrument;
+
+use crate::{
+	models::{BlockType, Network, ProcessedBlock},
+	services::{
+		blockchain::BlockChainClient, blockwatcher::error::BlockWatcherError, trigger::TriggerError,
+	},
+};
+
+/// Maximum number of blocks filtered concurrently within a chunk
+const BACKFILL_CONCURRENCY: usize = 4;
+
+/// Options controlling a backfill run
+#[derive(Debug, Clone)]
+pub struct BackfillOptions {
+	/// First block of the range (inclusive)
+	pub from_block: u64,
+	/// Last block of the range (inclusive)
+	pub to_block: u64,
+	/// Number of blocks fetched per RPC request
+	pub chunk_size: u64,
+	/// Delay between chunks, used to stay within RPC rate limits
+	pub chunk_delay: Duration,
+	/// Maximum number of attempts to fetch a chunk before giving up
+	pub max_attempts: u32,
+	/// Directory holding the backfill progress files
+	pub progress_dir: PathBuf,
+}
+
+impl Default for BackfillOptions {
+	fn default() -> Self {
+		Self {
+			from_block: 0,
+			to_block: 0,
+			chunk_size: 100,
+			chunk_delay: Duration::from_millis(250),
+			max_attempts: 5,
+			progress_dir: PathBuf::from("data"),
+		}
+	}
+}
+
+impl BackfillOptions {
+	/// Returns the path of the progress file for a network and the configured range
+	///
+	/// The file is named "{network_slug}_backfill_{from_block}_{to_block}.txt"
+	pub fn progress_path(&self, network_slug: &str) -> PathBuf {
+		self.progress_dir.join(format!(
+			"{}_backfill_{}_{}.txt",
+			network_slug, self.from_block, self.to_block
+		))
+	}
+}
+
+/// Summary of a completed backfill run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackfillSummary {
+	/// Block the run started from, after resuming from saved progress
+	pub start_block: u64,
+	/// Number of blocks processed during this run
+	pub blocks_processed: u64,
+	/// Number of monitor matches found during this run
+	pub matches: usize,
+}
+
+/// Runs monitors over a historical block range
+///
+/// Blocks are fetched in chunks of `chunk_size`, filtered with the block handler and passed in
+/// order to the trigger handler. Each chunk waits for its triggers to finish before the
+/// progress file is updated, so a resumed backfill never skips unhandled blocks. Failed fetches
+/// are retried with exponential backoff, and the progress file is removed once the range is
+/// complete.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `block_handler` - Handler function for processed blocks
+/// * `trigger_handler` - Handler function for processed blocks
+/// * `options` - Range, chunking and progress options
+///
+/// # Returns
+/// * `Result<BackfillSummary, BlockWatcherError>` - Summary of the run or error
+#[instrument(skip_all, fields(network = network.slug))]
+pub async fn run_backfill<
+	C: BlockChainClient + Send + Clone + 'static,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
+>(
+	network: &Network,
+	rpc_client: &C,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+	options: &BackfillOptions,
+) -> Result<BackfillSummary, BlockWatcherError> {
+	let metadata = HashMap::from([("network".to_string(), network.slug.clone())]);
+
+	if options.from_block > options.to_block {
+		return Err(BlockWatcherError::processing_error(
+			format!(
+				"Invalid backfill range: from block {} is after to block {}",
+				options.from_block, options.to_block
+			),
+			None,
+			Some(metadata),
+		));
+	}
+
+	let latest_block = rpc_client
+		.get_latest_block_number()
+		.await
+		.with_context(|| "Failed to get latest block number")?;
+	let latest_confirmed_block = latest_block.saturating_sub(network.confirmation_blocks);
+	if options.to_block > latest_confirmed_block {
+		return Err(BlockWatcherError::processing_error(
+			format!(
+				"Backfill range ends at block {} which is past the latest confirmed block {}",
+				options.to_block, latest_confirmed_block
+			),
+			None,
+			Some(metadata),
+		));
+	}
+
+	tokio::fs::create_dir_all(&options.progress_dir)
+		.await
+		.with_context(|| "Failed to create backfill progress directory")?;
+	let progress_path = options.progress_path(&network.slug);
+	let start_block = match read_progress(&progress_path).await? {
+		Some(last_block) => {
+			tracing::info!("Resuming backfill after block {}", last_block);
+			last_block + 1
+		}
+		None => options.from_block,
+	};
+
+	let mut summary = BackfillSummary {
+		start_block,
+		..Default::default()
+	};
+	let chunk_size = options.chunk_size.max(1);
+	let mut chunk_start = start_block;
+
+	while chunk_start <= options.to_block {
+		let chunk_end = std::cmp::min(chunk_start + chunk_size - 1, options.to_block);
+		let mut blocks = fetch_chunk(rpc_client, chunk_start, chunk_end, options).await?;
+		blocks.sort_by_key(|block| block.number().unwrap_or(0));
+
+		let mut processed_blocks = stream::iter(blocks)
+			.map(|block| (block_handler)(block, network.clone()))
+			.buffered(BACKFILL_CONCURRENCY);
+
+		while let Some(processed_block) = processed_blocks.next().await {
+			summary.blocks_processed += 1;
+			summary.matches += processed_block.processing_results.len();
+			// Wait for triggers so progress is only saved for handled blocks
+			(trigger_handler)(&processed_block)
+				.await
+				.with_context(|| "Trigger handling failed")?
+				.with_context(|| {
+					format!(
+						"Failed to handle triggers of block {}",
+						processed_block.block_number
+					)
+				})?;
+		}
+
+		tokio::fs::write(&progress_path, chunk_end.to_string())
+			.await
+			.map_err(|e| {
+				BlockWatcherError::storage_error(
+					"Failed to save backfill progress",
+					Some(e.into()),
+					Some(metadata.clone()),
+				)
+			})?;
+
+		tracing::info!(
+			"Backfilled blocks {} to {} ({} matches so far)",
+			chunk_start,
+			chunk_end,
+			summary.matches
+		);
+
+		chunk_start = chunk_end + 1;
+		if chunk_start <= options.to_block && !options.chunk_delay.is_zero() {
+			tokio::time::sleep(options.chunk_delay).await;
+		}
+	}
+
+	if progress_path.exists() {
+		tokio::fs::remove_file(&progress_path).await.map_err(|e| {
+			BlockWatcherError::storage_error(
+				"Failed to remove backfill progress",
+				Some(e.into()),
+				Some(metadata),
+			)
+		})?;
+	}
+
+	Ok(summary)
+}
+
+/// Reads the last completed block from a backfill progress file
+async fn read_progress(path: &Path) -> Result<Option<u64>, BlockWatcherError> {
+	if !path.exists() {
+		return Ok(None);
+	}
+
+	let content = tokio::fs::read_to_string(path)
+		.await
+		.with_context(|| "Failed to read backfill progress")?;
+	let block_number = content
+		.trim()
+		.parse::<u64>()
+		.with_context(|| "Failed to parse backfill progress")?;
+	Ok(Some(block_number))
+}
+
+/// Fetches a chunk of blocks, retrying with exponential backoff on failure
+async fn fetch_chunk<C: BlockChainClient>(
+	rpc_client: &C,
+	start_block: u64,
+	end_block: u64,
+	options: &BackfillOptions,
+) -> Result<Vec<BlockType>, BlockWatcherError> {
+	let mut attempt = 0;
+	let mut delay = options.chunk_delay.max(Duration::from_millis(100));
+	loop {
+		attempt += 1;
+		match rpc_client.get_blocks(start_block, Some(end_block)).await {
+			Ok(blocks) => return Ok(blocks),
+			Err(e) if attempt >= options.max_attempts.max(1) => {
+				return Err(BlockWatcherError::network_error(
+					format!(
+						"Failed to get blocks from {} to {} after {} attempts",
+						start_block, end_block, attempt
+					),
+					Some(e.into()),
+					None,
+				));
+			}
+			Err(e) => {
+				tracing::warn!(
+					"Failed to get blocks from {} to {} (attempt {}): {}",
+					start_block,
+					end_block,
+					attempt,
+					e
+				);
+				tokio::time::sleep(delay).await;
+				delay *= 2;
+			}
+		}
+	}
+}
@@ -3,17 +3,27 @@
 //! This module provides functionality to watch and process blockchain blocks across
 //! different networks. It includes:
 //! - Block watching service for multiple networks
-//! - Block storage implementations
+//! - Block storage implementations, file-based and SQLite
+//! - Historical backfill over a block range
+//! - Recovery of blocks recorded as missed
+//! - Two-phase (pending, then confirmed or dropped) alert tracking
 //! - Error handling specific to block watching operations
 
+mod backfill;
 mod error;
+mod pending;
 mod service;
+mod sqlite_storage;
 mod storage;
 mod tracker;
 
+pub use backfill::{run_backfill, BackfillOptions, BackfillSummary};
 pub use error::BlockWatcherError;
+pub use pending::{PendingAlertTracker, PendingBlockHandler};
 pub use service::{
-	process_new_blocks, BlockWatcherService, JobSchedulerTrait, NetworkBlockWatcher,
+	process_head_blocks, process_missed_blocks, process_new_blocks, BlockWatcherService,
+	JobSchedulerTrait, NetworkBlockWatcher,
 };
-pub use storage::{BlockStorage, FileBlockStorage};
+pub use sqlite_storage::{BlockImportSummary, BlockRetention, SqliteBlockStorage};
+pub use storage::{BlockStorage, BlockStorageBackend, FileBlockStorage};
 pub use tracker::{BlockTracker, BlockTrackerTrait};
@@ -0,0 +1,624 @@
+//! Two-phase alert tracking for the block watcher service.
+//!
+//! Monitors with `two_phase_alerts` enabled are evaluated twice:
+//! - At head depth, where each match is sent as a `pending` alert
+//! - At the network's confirmation depth, where the match is sent as `confirmed`
+//!
+//! Pending matches whose transaction is no longer found once their block height reaches
+//! confirmation depth (e.g. after a reorg) are sent as `dropped`. Every phase of an alert
+//! carries the same correlation ID so receivers can tie them together.
+//!
+//! The tracker can persist its state to a file next to the block storage, so pending alerts
+//! still get their follow-up after a restart. Its state only changes once the trigger handler
+//! has accepted the alerts of a block, so alerts that fail to be queued are sent again on the
+//! next run.
+
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::{
+	models::{AlertContext, AlertPhase, BlockType, MonitorMatch, Network, ProcessedBlock},
+	services::trigger::TriggerError,
+};
+
+/// Handler used to evaluate blocks at head depth for monitors with two-phase alerts
+pub type PendingBlockHandler =
+	Arc<dyn Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync>;
+
+/// Pending alerts and head progress for a single network
+#[derive(Default, Serialize, Deserialize)]
+struct NetworkPendingAlerts {
+	/// Highest block number evaluated at head depth
+	last_head_block: Option<u64>,
+	/// Pending matches keyed by block number, then by correlation ID
+	blocks: BTreeMap<u64, HashMap<String, MonitorMatch>>,
+	/// Correlation IDs resolved into confirmed or dropped alerts whose trigger handler has not
+	/// returned yet
+	#[serde(skip)]
+	resolving: HashSet<String>,
+}
+
+/// Tracks pending alerts until their block reaches confirmation depth
+///
+/// The tracker is shared between the head-depth pass, which records pending matches, and the
+/// confirmed pass, which resolves them into confirmed or dropped alerts. Both passes work in two
+/// steps: the alerts of a block are tagged first, and the state is only updated once the trigger
+/// handler succeeded.
+#[derive(Clone, Default)]
+pub struct PendingAlertTracker {
+	networks: Arc<Mutex<HashMap<String, NetworkPendingAlerts>>>,
+	/// File the state is persisted to
+	storage_path: Option<Arc<PathBuf>>,
+}
+
+impl PendingAlertTracker {
+	/// Creates a new, empty tracker keeping its state in memory only
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a tracker persisting its state to a file
+	///
+	/// The state saved by a previous run is loaded if the file exists. The state is saved after
+	/// every change, once the trigger handler accepted the alerts of a block.
+	///
+	/// # Arguments
+	/// * `storage_path` - File the state is persisted to
+	///
+	/// # Errors
+	/// Returns an error if the file exists but cannot be read
+	pub fn with_persistence(storage_path: PathBuf) -> Result<Self, anyhow::Error> {
+		let networks = if storage_path.exists() {
+			let content = std::fs::read(&storage_path)
+				.map_err(|e| anyhow::anyhow!("Failed to read pending alerts: {}", e))?;
+			serde_json::from_slice(&content)
+				.map_err(|e| anyhow::anyhow!("Failed to parse pending alerts: {}", e))?
+		} else {
+			HashMap::new()
+		};
+
+		Ok(Self {
+			networks: Arc::new(Mutex::new(networks)),
+			storage_path: Some(Arc::new(storage_path)),
+		})
+	}
+
+	/// Saves the state if persistence is enabled
+	///
+	/// The state is written to a temporary file which then replaces the previous one, so an
+	/// interrupted write never loses pending alerts. Failures are logged, the in-memory state
+	/// stays authoritative.
+	fn persist(&self, networks: &HashMap<String, NetworkPendingAlerts>) {
+		let Some(storage_path) = &self.storage_path else {
+			return;
+		};
+		if let Err(e) = write_state(storage_path, networks) {
+			tracing::error!(
+				"Failed to persist pending alerts to {}: {}",
+				storage_path.display(),
+				e
+			);
+		}
+	}
+
+	/// Returns the highest block number evaluated at head depth for a network
+	pub fn last_head_block(&self, network_slug: &str) -> Option<u64> {
+		self.networks
+			.lock()
+			.unwrap()
+			.get(network_slug)
+			.and_then(|n| n.last_head_block)
+	}
+
+	/// Returns the number of pending alerts awaiting confirmation for a network
+	pub fn pending_count(&self, network_slug: &str) -> usize {
+		self.networks
+			.lock()
+			.unwrap()
+			.get(network_slug)
+			.map(|n| n.blocks.values().map(|b| b.len()).sum())
+			.unwrap_or(0)
+	}
+
+	/// Tags the matches of a block evaluated at head depth as pending alerts
+	///
+	/// The tracker is not changed, the alerts are only remembered once [`Self::record_pending`]
+	/// is called after the trigger handler succeeded. Matches already pending under the same
+	/// correlation ID are not alerted twice.
+	///
+	/// # Arguments
+	/// * `block` - Block processed at head depth
+	///
+	/// # Returns
+	/// * `ProcessedBlock` - The block with only new pending matches, tagged with their context
+	pub fn tag_pending(&self, block: &ProcessedBlock) -> ProcessedBlock {
+		let networks = self.networks.lock().unwrap();
+		let network = networks.get(&block.network_slug);
+
+		let mut tagged = HashSet::new();
+		let mut results = Vec::new();
+		for monitor_match in &block.processing_results {
+			let alert = AlertContext::new(AlertPhase::Pending, &block.network_slug, monitor_match);
+			let already_pending = network.is_some_and(|network| {
+				network
+					.blocks
+					.values()
+					.any(|pending| pending.contains_key(&alert.correlation_id))
+			});
+			if already_pending || !tagged.insert(alert.correlation_id.clone()) {
+				continue;
+			}
+
+			let mut monitor_match = monitor_match.clone();
+			monitor_match.set_alert(Some(alert));
+			results.push(monitor_match);
+		}
+
+		ProcessedBlock {
+			block_number: block.block_number,
+			network_slug: block.network_slug.clone(),
+			processing_results: results,
+		}
+	}
+
+	/// Records the pending alerts of a block once the trigger handler accepted them
+	///
+	/// The alerts are remembered until their block height is confirmed, and the block becomes
+	/// the last one evaluated at head depth.
+	///
+	/// # Arguments
+	/// * `pending_block` - Block returned by [`Self::tag_pending`]
+	pub fn record_pending(&self, pending_block: &ProcessedBlock) {
+		let mut networks = self.networks.lock().unwrap();
+		let network = networks
+			.entry(pending_block.network_slug.clone())
+			.or_default();
+
+		network.last_head_block = Some(
+			network
+				.last_head_block
+				.map_or(pending_block.block_number, |last| {
+					last.max(pending_block.block_number)
+				}),
+		);
+
+		for monitor_match in &pending_block.processing_results {
+			if let Some(alert) = monitor_match.alert() {
+				network
+					.blocks
+					.entry(pending_block.block_number)
+					.or_default()
+					.insert(alert.correlation_id.clone(), monitor_match.clone());
+			}
+		}
+		self.persist(&networks);
+	}
+
+	/// Resolves pending alerts against a block processed at confirmation depth
+	///
+	/// Matches from monitors with two-phase alerts are tagged as `confirmed`. Pending alerts
+	/// recorded at or below the confirmed block height that were not confirmed are appended to
+	/// the block as `dropped`.
+	///
+	/// The resolved alerts stay pending until [`Self::commit_resolved`] is called after the
+	/// trigger handler succeeded, or are released by [`Self::release_resolved`] so the next run
+	/// resolves them again. They are not resolved a second time in the meantime.
+	///
+	/// # Arguments
+	/// * `block` - Block processed at confirmation depth
+	///
+	/// # Returns
+	/// * `ProcessedBlock` - The block with confirmed and dropped alerts tagged
+	pub fn resolve_confirmed(&self, block: &ProcessedBlock) -> ProcessedBlock {
+		let mut networks = self.networks.lock().unwrap();
+		let network = networks.entry(block.network_slug.clone()).or_default();
+
+		let mut results = Vec::with_capacity(block.processing_results.len());
+		for monitor_match in &block.processing_results {
+			let mut monitor_match = monitor_match.clone();
+			if monitor_match.monitor().two_phase_alerts {
+				let alert =
+					AlertContext::new(AlertPhase::Confirmed, &block.network_slug, &monitor_match);
+				network.resolving.insert(alert.correlation_id.clone());
+				monitor_match.set_alert(Some(alert));
+			}
+			results.push(monitor_match);
+		}
+
+		let expired = network.blocks.range(..=block.block_number);
+		let mut dropped = Vec::new();
+		for (correlation_id, monitor_match) in expired.flat_map(|(_, pending)| pending) {
+			if network.resolving.contains(correlation_id) {
+				continue;
+			}
+			let mut monitor_match = monitor_match.clone();
+			monitor_match.set_alert(Some(AlertContext {
+				phase: AlertPhase::Dropped,
+				correlation_id: correlation_id.clone(),
+			}));
+			dropped.push(monitor_match);
+		}
+		for monitor_match in dropped {
+			if let Some(alert) = monitor_match.alert() {
+				network.resolving.insert(alert.correlation_id.clone());
+			}
+			results.push(monitor_match);
+		}
+
+		ProcessedBlock {
+			block_number: block.block_number,
+			network_slug: block.network_slug.clone(),
+			processing_results: results,
+		}
+	}
+
+	/// Removes the alerts of a resolved block once the trigger handler accepted them
+	///
+	/// # Arguments
+	/// * `resolved_block` - Block returned by [`Self::resolve_confirmed`]
+	pub fn commit_resolved(&self, resolved_block: &ProcessedBlock) {
+		let mut networks = self.networks.lock().unwrap();
+		let Some(network) = networks.get_mut(&resolved_block.network_slug) else {
+			return;
+		};
+
+		for correlation_id in resolved_correlation_ids(resolved_block) {
+			network.resolving.remove(correlation_id);
+			for pending in network.blocks.values_mut() {
+				pending.remove(correlation_id);
+			}
+		}
+		network.blocks.retain(|_, pending| !pending.is_empty());
+		self.persist(&networks);
+	}
+
+	/// Releases the alerts of a resolved block whose trigger handler failed
+	///
+	/// The alerts stay pending and are resolved again with the next confirmed block.
+	///
+	/// # Arguments
+	/// * `resolved_block` - Block returned by [`Self::resolve_confirmed`]
+	pub fn release_resolved(&self, resolved_block: &ProcessedBlock) {
+		let mut networks = self.networks.lock().unwrap();
+		if let Some(network) = networks.get_mut(&resolved_block.network_slug) {
+			for correlation_id in resolved_correlation_ids(resolved_block) {
+				network.resolving.remove(correlation_id);
+			}
+		}
+	}
+
+	/// Wraps a trigger handler so confirmed blocks are resolved before reaching it
+	///
+	/// The resolution is committed once the returned task succeeds, and released otherwise so
+	/// the alerts are resolved again with the next confirmed block.
+	///
+	/// # Arguments
+	/// * `trigger_handler` - Handler receiving the resolved blocks
+	///
+	/// # Returns
+	/// * Trigger handler resolving pending alerts
+	pub fn resolving_trigger_handler<T>(
+		&self,
+		trigger_handler: Arc<T>,
+	) -> Arc<impl Fn(&ProcessedBlock) -> JoinHandle<Result<(), TriggerError>> + Send + Sync + 'static>
+	where
+		T: Fn(&ProcessedBlock) -> JoinHandle<Result<(), TriggerError>> + Send + Sync + 'static,
+	{
+		let tracker = self.clone();
+		Arc::new(move |block: &ProcessedBlock| {
+			let resolved_block = tracker.resolve_confirmed(block);
+			let handle = (trigger_handler)(&resolved_block);
+			let tracker = tracker.clone();
+			tokio::spawn(async move {
+				let result = match handle.await {
+					Ok(result) => result,
+					Err(e) => Err(TriggerError::execution_error(
+						format!("Trigger handler task failed: {}", e),
+						Some(Box::new(e)),
+						None,
+					)),
+				};
+				match &result {
+					Ok(()) => tracker.commit_resolved(&resolved_block),
+					Err(_) => tracker.release_resolved(&resolved_block),
+				}
+				result
+			})
+		})
+	}
+}
+
+/// Returns the correlation IDs of the confirmed and dropped alerts of a resolved block
+fn resolved_correlation_ids(resolved_block: &ProcessedBlock) -> impl Iterator<Item = &String> {
+	resolved_block
+		.processing_results
+		.iter()
+		.filter_map(|monitor_match| monitor_match.alert())
+		.filter(|alert| matches!(alert.phase, AlertPhase::Confirmed | AlertPhase::Dropped))
+		.map(|alert| &alert.correlation_id)
+}
+
+/// Writes the state of the tracker to a file through a temporary file
+fn write_state(
+	storage_path: &Path,
+	networks: &HashMap<String, NetworkPendingAlerts>,
+) -> Result<(), anyhow::Error> {
+	let json = serde_json::to_vec(networks)
+		.map_err(|e| anyhow::anyhow!("Failed to serialize pending alerts: {}", e))?;
+	if let Some(parent) = storage_path.parent() {
+		std::fs::create_dir_all(parent)
+			.map_err(|e| anyhow::anyhow!("Failed to create pending alerts directory: {}", e))?;
+	}
+	let tmp_path = storage_path.with_extension("json.tmp");
+	std::fs::write(&tmp_path, json)
+		.and_then(|_| std::fs::rename(&tmp_path, storage_path))
+		.map_err(|e| anyhow::anyhow!("Failed to write pending alerts: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{EVMMonitorMatch, EVMTransactionReceipt, MatchConditions, Monitor};
+	use tempfile::TempDir;
+
+	fn create_match(monitor_name: &str, tx_nonce: u64) -> MonitorMatch {
+		let tx = alloy::consensus::TxLegacy {
+			chain_id: None,
+			nonce: tx_nonce,
+			gas_price: 0,
+			gas_limit: 0,
+			to: alloy::primitives::TxKind::Call(alloy::primitives::Address::ZERO),
+			value: alloy::primitives::U256::ZERO,
+			input: alloy::primitives::Bytes::default(),
+		};
+		let signature = alloy::signers::Signature::from_scalars_and_parity(
+			alloy::primitives::B256::ZERO,
+			alloy::primitives::B256::ZERO,
+			false,
+		);
+		let hash = alloy::primitives::B256::with_last_byte(tx_nonce as u8);
+
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: Monitor {
+				name: monitor_name.to_string(),
+				two_phase_alerts: true,
+				..Default::default()
+			},
+			transaction: crate::models::EVMTransaction::from(alloy::rpc::types::Transaction {
+				inner: alloy::consensus::transaction::Recovered::new_unchecked(
+					alloy::consensus::transaction::TxEnvelope::Legacy(
+						alloy::consensus::Signed::new_unchecked(tx, signature, hash),
+					),
+					alloy::primitives::Address::ZERO,
+				),
+				block_hash: None,
+				block_number: None,
+				transaction_index: None,
+				effective_gas_price: None,
+			}),
+			receipt: EVMTransactionReceipt::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}))
+	}
+
+	fn create_block(block_number: u64, matches: Vec<MonitorMatch>) -> ProcessedBlock {
+		ProcessedBlock {
+			block_number,
+			network_slug: "ethereum_mainnet".to_string(),
+			processing_results: matches,
+		}
+	}
+
+	/// Tags and records a head block, as done once its trigger handler succeeded
+	fn record(tracker: &PendingAlertTracker, block: &ProcessedBlock) -> ProcessedBlock {
+		let pending = tracker.tag_pending(block);
+		tracker.record_pending(&pending);
+		pending
+	}
+
+	/// Resolves and commits a confirmed block, as done once its trigger handler succeeded
+	fn resolve(tracker: &PendingAlertTracker, block: &ProcessedBlock) -> ProcessedBlock {
+		let resolved = tracker.resolve_confirmed(block);
+		tracker.commit_resolved(&resolved);
+		resolved
+	}
+
+	#[test]
+	fn test_record_pending_tags_matches() {
+		let tracker = PendingAlertTracker::new();
+		let block = record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+
+		assert_eq!(block.processing_results.len(), 1);
+		let alert = block.processing_results[0].alert().unwrap();
+		assert_eq!(alert.phase, AlertPhase::Pending);
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 1);
+		assert_eq!(tracker.last_head_block("ethereum_mainnet"), Some(100));
+	}
+
+	#[test]
+	fn test_record_pending_skips_duplicates() {
+		let tracker = PendingAlertTracker::new();
+		record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+		let block = record(&tracker, &create_block(101, vec![create_match("m", 1)]));
+
+		assert!(block.processing_results.is_empty());
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 1);
+	}
+
+	#[test]
+	fn test_resolve_confirmed_shares_correlation_id() {
+		let tracker = PendingAlertTracker::new();
+		let pending = record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+		let confirmed = resolve(&tracker, &create_block(100, vec![create_match("m", 1)]));
+
+		assert_eq!(confirmed.processing_results.len(), 1);
+		let confirmed_alert = confirmed.processing_results[0].alert().unwrap();
+		assert_eq!(confirmed_alert.phase, AlertPhase::Confirmed);
+		assert_eq!(
+			confirmed_alert.correlation_id,
+			pending.processing_results[0]
+				.alert()
+				.unwrap()
+				.correlation_id
+		);
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 0);
+	}
+
+	#[test]
+	fn test_resolve_confirmed_drops_missing_matches() {
+		let tracker = PendingAlertTracker::new();
+		record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+		record(&tracker, &create_block(101, vec![create_match("m", 2)]));
+
+		let confirmed = resolve(&tracker, &create_block(100, vec![]));
+
+		assert_eq!(confirmed.processing_results.len(), 1);
+		assert_eq!(
+			confirmed.processing_results[0].alert().unwrap().phase,
+			AlertPhase::Dropped
+		);
+		// The pending alert for block 101 is not yet confirmed
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 1);
+	}
+
+	#[test]
+	fn test_resolve_confirmed_ignores_single_phase_monitors() {
+		let tracker = PendingAlertTracker::new();
+		let mut monitor_match = create_match("m", 1);
+		if let MonitorMatch::EVM(evm_match) = &mut monitor_match {
+			evm_match.monitor.two_phase_alerts = false;
+		}
+
+		let confirmed = resolve(&tracker, &create_block(100, vec![monitor_match]));
+
+		assert!(confirmed.processing_results[0].alert().is_none());
+	}
+
+	#[test]
+	fn test_pending_alerts_survive_restart() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("pending_alerts.json");
+
+		let tracker = PendingAlertTracker::with_persistence(path.clone()).unwrap();
+		let pending = record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+		record(&tracker, &create_block(101, vec![create_match("m", 2)]));
+		drop(tracker);
+
+		let tracker = PendingAlertTracker::with_persistence(path.clone()).unwrap();
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 2);
+		assert_eq!(tracker.last_head_block("ethereum_mainnet"), Some(101));
+
+		// The match of block 101 was reorged out, block 100 is confirmed
+		let confirmed = resolve(&tracker, &create_block(101, vec![create_match("m", 1)]));
+		let phases: Vec<_> = confirmed
+			.processing_results
+			.iter()
+			.map(|monitor_match| monitor_match.alert().unwrap().clone())
+			.collect();
+		assert_eq!(phases.len(), 2);
+		assert_eq!(phases[0].phase, AlertPhase::Confirmed);
+		assert_eq!(
+			phases[0].correlation_id,
+			pending.processing_results[0]
+				.alert()
+				.unwrap()
+				.correlation_id
+		);
+		assert_eq!(phases[1].phase, AlertPhase::Dropped);
+
+		let tracker = PendingAlertTracker::with_persistence(path).unwrap();
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 0);
+	}
+
+	#[test]
+	fn test_tag_pending_does_not_record() {
+		let tracker = PendingAlertTracker::new();
+		let block = tracker.tag_pending(&create_block(
+			100,
+			vec![create_match("m", 1), create_match("m", 1)],
+		));
+
+		// Duplicates within the block are only alerted once
+		assert_eq!(block.processing_results.len(), 1);
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 0);
+		assert_eq!(tracker.last_head_block("ethereum_mainnet"), None);
+	}
+
+	#[test]
+	fn test_release_resolved_keeps_alerts_pending() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("pending_alerts.json");
+
+		let tracker = PendingAlertTracker::with_persistence(path.clone()).unwrap();
+		record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+
+		let resolved = tracker.resolve_confirmed(&create_block(100, vec![]));
+		assert_eq!(resolved.processing_results.len(), 1);
+		// Alerts being handled are neither removed nor resolved a second time
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 1);
+		assert!(tracker
+			.resolve_confirmed(&create_block(101, vec![]))
+			.processing_results
+			.is_empty());
+
+		tracker.release_resolved(&resolved);
+		let tracker = PendingAlertTracker::with_persistence(path).unwrap();
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 1);
+
+		let dropped = resolve(&tracker, &create_block(101, vec![]));
+		assert_eq!(
+			dropped.processing_results[0].alert().unwrap().phase,
+			AlertPhase::Dropped
+		);
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 0);
+	}
+
+	#[tokio::test]
+	async fn test_resolving_trigger_handler_commits_on_success_only() {
+		let tracker = PendingAlertTracker::new();
+		record(&tracker, &create_block(100, vec![create_match("m", 1)]));
+
+		let failing = tracker.resolving_trigger_handler(Arc::new(|_: &ProcessedBlock| {
+			tokio::spawn(async {
+				Err(TriggerError::execution_error_without_log(
+					"queue unavailable",
+					None,
+					None,
+				))
+			})
+		}));
+		assert!((failing)(&create_block(100, vec![]))
+			.await
+			.unwrap()
+			.is_err());
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 1);
+
+		let succeeding = tracker.resolving_trigger_handler(Arc::new(|_: &ProcessedBlock| {
+			tokio::spawn(async { Ok(()) })
+		}));
+		assert!((succeeding)(&create_block(100, vec![]))
+			.await
+			.unwrap()
+			.is_ok());
+		assert_eq!(tracker.pending_count("ethereum_mainnet"), 0);
+	}
+
+	#[test]
+	fn test_with_persistence_rejects_corrupt_state() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("pending_alerts.json");
+		std::fs::write(&path, "not json").unwrap();
+
+		assert!(PendingAlertTracker::with_persistence(path).is_err());
+	}
+}
@@ -8,6 +8,7 @@ use futures::{channel::mpsc, future::BoxFuture, stream::StreamExt, SinkExt};
 use std::{
 	collections::{BTreeMap, HashMap},
 	sync::Arc,
+	time::Duration,
 };
 use tokio::sync::RwLock;
 use tokio_cron_scheduler::{Job, JobScheduler};
@@ -19,12 +20,24 @@ use crate::{
 		blockchain::BlockChainClient,
 		blockwatcher::{
 			error::BlockWatcherError,
+			pending::{PendingAlertTracker, PendingBlockHandler},
 			storage::BlockStorage,
 			tracker::{BlockTracker, BlockTrackerTrait},
 		},
+		trigger::TriggerError,
 	},
+	utils::metrics::MISSED_BLOCKS_OUTSTANDING,
 };
 
+/// Maximum number of missed blocks replayed per watcher run
+const MAX_MISSED_BLOCKS_PER_RUN: usize = 50;
+
+/// Maximum number of attempts to fetch a missed block within a single run
+const MAX_MISSED_BLOCK_ATTEMPTS: u32 = 3;
+
+/// Base delay between attempts to fetch a missed block, doubled after each failure
+const MISSED_BLOCK_RETRY_DELAY_MS: u64 = 100;
+
 /// Trait for job scheduler
 ///
 /// This trait is used to abstract the job scheduler implementation.
@@ -78,6 +91,8 @@ where
 	pub trigger_handler: Arc<T>,
 	pub scheduler: J,
 	pub block_tracker: Arc<BlockTracker<S>>,
+	pub pending_block_handler: Option<PendingBlockHandler>,
+	pub pending_alert_tracker: PendingAlertTracker,
 }
 
 /// Map of active block watchers
@@ -102,13 +117,18 @@ where
 	pub trigger_handler: Arc<T>,
 	pub active_watchers: Arc<RwLock<BlockWatchersMap<S, H, T, J>>>,
 	pub block_tracker: Arc<BlockTracker<S>>,
+	pub pending_block_handlers: HashMap<String, PendingBlockHandler>,
+	pub pending_alert_tracker: PendingAlertTracker,
 }
 
 impl<S, H, T, J> NetworkBlockWatcher<S, H, T, J>
 where
 	S: BlockStorage + Send + Sync + 'static,
 	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
-	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
 	J: JobSchedulerTrait,
 {
 	/// Creates a new network watcher instance
@@ -144,6 +164,8 @@ where
 			trigger_handler,
 			scheduler,
 			block_tracker,
+			pending_block_handler: None,
+			pending_alert_tracker: PendingAlertTracker::new(),
 		})
 	}
 
@@ -160,6 +182,8 @@ where
 		let block_handler = self.block_handler.clone();
 		let trigger_handler = self.trigger_handler.clone();
 		let block_tracker = self.block_tracker.clone();
+		let pending_block_handler = self.pending_block_handler.clone();
+		let pending_alert_tracker = self.pending_alert_tracker.clone();
 
 		let job = Job::new_async(self.network.cron_schedule.as_str(), move |_uuid, _l| {
 			let network = network.clone();
@@ -168,26 +192,56 @@ where
 			let block_tracker = block_tracker.clone();
 			let rpc_client = rpc_client.clone();
 			let trigger_handler = trigger_handler.clone();
+			let pending_block_handler = pending_block_handler.clone();
+			let pending_alert_tracker = pending_alert_tracker.clone();
 			Box::pin(async move {
-				let _ = process_new_blocks(
-					&network,
-					&rpc_client,
-					block_storage,
-					block_handler,
-					trigger_handler,
-					block_tracker,
-				)
-				.await
-				.map_err(|e| {
-					BlockWatcherError::processing_error(
-						"Failed to process blocks".to_string(),
-						Some(e.into()),
-						Some(HashMap::from([(
-							"network".to_string(),
-							network.slug.clone(),
-						)])),
+				match &pending_block_handler {
+					// Resolve pending alerts before confirmed blocks reach the trigger handler
+					Some(_) => {
+						process_confirmed_blocks(
+							&network,
+							&rpc_client,
+							block_storage,
+							block_handler,
+							pending_alert_tracker
+								.resolving_trigger_handler(trigger_handler.clone()),
+							block_tracker,
+						)
+						.await
+					}
+					None => {
+						process_confirmed_blocks(
+							&network,
+							&rpc_client,
+							block_storage,
+							block_handler,
+							trigger_handler.clone(),
+							block_tracker,
+						)
+						.await
+					}
+				}
+
+				if let Some(pending_block_handler) = pending_block_handler {
+					let _ = process_head_blocks(
+						&network,
+						&rpc_client,
+						pending_block_handler,
+						trigger_handler,
+						pending_alert_tracker,
 					)
-				});
+					.await
+					.map_err(|e| {
+						BlockWatcherError::processing_error(
+							"Failed to process head blocks".to_string(),
+							Some(e.into()),
+							Some(HashMap::from([(
+								"network".to_string(),
+								network.slug.clone(),
+							)])),
+						)
+					});
+				}
 			})
 		})
 		.with_context(|| "Failed to create job")?;
@@ -242,7 +296,10 @@ impl<S, H, T, J> BlockWatcherService<S, H, T, J>
 where
 	S: BlockStorage + Send + Sync + 'static,
 	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
-	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
 	J: JobSchedulerTrait,
 {
 	/// Creates a new block watcher service
@@ -263,9 +320,67 @@ where
 			trigger_handler,
 			active_watchers: Arc::new(RwLock::new(HashMap::new())),
 			block_tracker,
+			pending_block_handlers: HashMap::new(),
+			pending_alert_tracker: PendingAlertTracker::new(),
 		})
 	}
 
+	/// Sets the tracker holding pending alerts until confirmation
+	///
+	/// Used to share a tracker persisting its state, so pending alerts survive restarts.
+	///
+	/// # Arguments
+	/// * `pending_alert_tracker` - Tracker shared by the watchers of every network
+	pub fn with_pending_alert_tracker(
+		mut self,
+		pending_alert_tracker: PendingAlertTracker,
+	) -> Self {
+		self.pending_alert_tracker = pending_alert_tracker;
+		self
+	}
+
+	/// Enables two-phase alerts for a network
+	///
+	/// Watchers started for the network afterwards also evaluate blocks at head depth with the
+	/// given handler and resolve them once they reach the network's confirmation depth.
+	///
+	/// # Arguments
+	/// * `network_slug` - Identifier of the network
+	/// * `pending_block_handler` - Handler evaluating monitors with two-phase alerts enabled
+	pub fn with_pending_block_handler(
+		mut self,
+		network_slug: &str,
+		pending_block_handler: PendingBlockHandler,
+	) -> Self {
+		self.set_pending_block_handler(network_slug, Some(pending_block_handler));
+		self
+	}
+
+	/// Enables or disables two-phase alerts for a network
+	///
+	/// Takes effect the next time the network's watcher is started, so a running watcher has to
+	/// be restarted for the change to apply.
+	///
+	/// # Arguments
+	/// * `network_slug` - Identifier of the network
+	/// * `pending_block_handler` - Handler evaluating monitors with two-phase alerts enabled, or
+	///   `None` to disable two-phase alerts
+	pub fn set_pending_block_handler(
+		&mut self,
+		network_slug: &str,
+		pending_block_handler: Option<PendingBlockHandler>,
+	) {
+		match pending_block_handler {
+			Some(handler) => {
+				self.pending_block_handlers
+					.insert(network_slug.to_string(), handler);
+			}
+			None => {
+				self.pending_block_handlers.remove(network_slug);
+			}
+		}
+	}
+
 	/// Starts a watcher for a specific network
 	///
 	/// # Arguments
@@ -293,6 +408,8 @@ where
 			self.block_tracker.clone(),
 		)
 		.await?;
+		watcher.pending_block_handler = self.pending_block_handlers.get(&network.slug).cloned();
+		watcher.pending_alert_tracker = self.pending_alert_tracker.clone();
 
 		watcher.start(rpc_client).await?;
 		watchers.insert(network.slug.clone(), watcher);
@@ -315,6 +432,76 @@ where
 	}
 }
 
+/// Processes new confirmed blocks, then replays missed blocks if blocks are stored
+///
+/// Failures are logged, the next run of the watcher retries them.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `block_storage` - Storage implementation for blocks
+/// * `block_handler` - Handler function for processed blocks
+/// * `trigger_handler` - Handler function for processed blocks
+/// * `block_tracker` - Tracker implementation for block processing
+async fn process_confirmed_blocks<
+	S: BlockStorage,
+	C: BlockChainClient + Send + Clone + 'static,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
+	TR: BlockTrackerTrait<S>,
+>(
+	network: &Network,
+	rpc_client: &C,
+	block_storage: Arc<S>,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+	block_tracker: Arc<TR>,
+) {
+	let _ = process_new_blocks(
+		network,
+		rpc_client,
+		block_storage.clone(),
+		block_handler.clone(),
+		trigger_handler.clone(),
+		block_tracker,
+	)
+	.await
+	.map_err(|e| {
+		BlockWatcherError::processing_error(
+			"Failed to process blocks".to_string(),
+			Some(e.into()),
+			Some(HashMap::from([(
+				"network".to_string(),
+				network.slug.clone(),
+			)])),
+		)
+	});
+
+	if network.store_blocks.unwrap_or(false) {
+		let _ = process_missed_blocks(
+			network,
+			rpc_client,
+			block_storage,
+			block_handler,
+			trigger_handler,
+		)
+		.await
+		.map_err(|e| {
+			BlockWatcherError::processing_error(
+				"Failed to recover missed blocks".to_string(),
+				Some(e.into()),
+				Some(HashMap::from([(
+					"network".to_string(),
+					network.slug.clone(),
+				)])),
+			)
+		});
+	}
+}
+
 /// Processes new blocks for a network
 ///
 /// # Arguments
@@ -332,7 +519,10 @@ pub async fn process_new_blocks<
 	S: BlockStorage,
 	C: BlockChainClient + Send + Clone + 'static,
 	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
-	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
 	TR: BlockTrackerTrait<S>,
 >(
 	network: &Network,
@@ -443,6 +633,7 @@ pub async fn process_new_blocks<
 			let mut trigger_rx = trigger_rx;
 			let mut pending_blocks = BTreeMap::new();
 			let mut next_block_number = Some(start_block);
+			let mut trigger_tasks = Vec::new();
 
 			// Process all incoming blocks
 			while let Some(processed_block) = trigger_rx.next().await {
@@ -452,7 +643,7 @@ pub async fn process_new_blocks<
 				// Process blocks in order as long as we have the next expected block
 				while let Some(expected) = next_block_number {
 					if let Some(block) = pending_blocks.remove(&expected) {
-						(trigger_handler)(&block);
+						trigger_tasks.push((trigger_handler)(&block));
 						next_block_number = Some(expected + 1);
 					} else {
 						break;
@@ -463,9 +654,18 @@ pub async fn process_new_blocks<
 			// Process any remaining blocks in order after the channel is closed
 			while let Some(min_block) = pending_blocks.keys().next().copied() {
 				if let Some(block) = pending_blocks.remove(&min_block) {
-					(trigger_handler)(&block);
+					trigger_tasks.push((trigger_handler)(&block));
 				}
 			}
+
+			// Wait for the triggers of every block, so matches are handed off before the
+			// checkpoint advances. A failed handler keeps the checkpoint so the blocks are
+			// processed again.
+			for result in futures::future::join_all(trigger_tasks).await {
+				result
+					.with_context(|| "Trigger handling failed")?
+					.with_context(|| "Trigger handler failed")?;
+			}
 			Ok::<(), BlockWatcherError>(())
 		}
 	});
@@ -499,18 +699,16 @@ pub async fn process_new_blocks<
 	drop(process_tx);
 	drop(trigger_tx);
 
-	// Wait for both pipeline stages to complete
-	let (_process_result, _trigger_result) = tokio::join!(process_handle, trigger_handle);
+	// Wait for both pipeline stages to complete. The checkpoint only advances once every block
+	// was handed to the trigger handler.
+	let (_process_result, trigger_result) = tokio::join!(process_handle, trigger_handle);
+	trigger_result
+		.with_context(|| "Trigger pipeline failed")?
+		.with_context(|| format!("Failed to handle triggers for network {}", network.slug))?;
 
 	if network.store_blocks.unwrap_or(false) {
-		// Delete old blocks before saving new ones
 		block_storage
-			.delete_blocks(&network.slug)
-			.await
-			.with_context(|| "Failed to delete old blocks")?;
-
-		block_storage
-			.save_blocks(&network.slug, &blocks)
+			.store_blocks(&network.slug, &blocks)
 			.await
 			.with_context(|| "Failed to save blocks")?;
 	}
@@ -528,3 +726,188 @@ pub async fn process_new_blocks<
 
 	Ok(())
 }
+
+/// Replays blocks recorded in the missed block ledger of a network
+///
+/// Each missed block is refetched and passed through the block and trigger handlers like a
+/// newly confirmed block. Fetching is retried a bounded number of times with exponential
+/// backoff; blocks that still fail stay in the ledger and are retried on the next run.
+/// Recovered blocks are removed from the ledger and the number of outstanding blocks is
+/// exported as the `missed_blocks_outstanding` metric.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `block_storage` - Storage implementation holding the missed block ledger
+/// * `block_handler` - Handler function for processed blocks
+/// * `trigger_handler` - Handler function for processed blocks
+///
+/// # Returns
+/// * `Result<usize, BlockWatcherError>` - Number of recovered blocks or error
+#[instrument(skip_all, fields(network = network.slug))]
+pub async fn process_missed_blocks<
+	S: BlockStorage,
+	C: BlockChainClient + Send + Clone + 'static,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
+>(
+	network: &Network,
+	rpc_client: &C,
+	block_storage: Arc<S>,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+) -> Result<usize, BlockWatcherError> {
+	let missed_blocks = block_storage
+		.get_missed_blocks(&network.slug)
+		.await
+		.with_context(|| "Failed to get missed blocks")?;
+
+	let mut recovered = Vec::new();
+	for &block_number in missed_blocks.iter().take(MAX_MISSED_BLOCKS_PER_RUN) {
+		let mut attempt = 0;
+		let block = loop {
+			attempt += 1;
+			match rpc_client.get_blocks(block_number, None).await {
+				Ok(blocks) if !blocks.is_empty() => break blocks.into_iter().next(),
+				_ if attempt >= MAX_MISSED_BLOCK_ATTEMPTS => break None,
+				_ => {
+					tokio::time::sleep(Duration::from_millis(
+						MISSED_BLOCK_RETRY_DELAY_MS << (attempt - 1),
+					))
+					.await;
+				}
+			}
+		};
+
+		let Some(block) = block else {
+			tracing::warn!(
+				"Failed to fetch missed block {} after {} attempts",
+				block_number,
+				MAX_MISSED_BLOCK_ATTEMPTS
+			);
+			continue;
+		};
+
+		let processed_block = (block_handler)(block, network.clone()).await;
+		// Wait for triggers to run so the block is only resolved once it has been handled
+		match (trigger_handler)(&processed_block).await {
+			Ok(Ok(())) => recovered.push(block_number),
+			Ok(Err(e)) => tracing::warn!(
+				"Failed to handle triggers of missed block {}, keeping it: {}",
+				block_number,
+				e
+			),
+			Err(e) => tracing::warn!(
+				"Trigger handling of missed block {} failed, keeping it: {}",
+				block_number,
+				e
+			),
+		}
+	}
+
+	block_storage
+		.remove_missed_blocks(&network.slug, &recovered)
+		.await
+		.with_context(|| "Failed to resolve missed blocks")?;
+
+	MISSED_BLOCKS_OUTSTANDING
+		.with_label_values(&[&network.slug])
+		.set((missed_blocks.len() - recovered.len()) as f64);
+
+	if !missed_blocks.is_empty() {
+		tracing::info!(
+			"Recovered {} of {} missed blocks",
+			recovered.len(),
+			missed_blocks.len()
+		);
+	}
+
+	Ok(recovered.len())
+}
+
+/// Processes blocks between the confirmation depth and the chain head for two-phase alerts
+///
+/// Each head block is evaluated once with the pending block handler. Matches are passed to the
+/// trigger handler as `pending` alerts and recorded in the pending alert tracker once the
+/// handler succeeded. A failed handler stops the run, so the block is evaluated again next run.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `pending_block_handler` - Handler evaluating monitors with two-phase alerts enabled
+/// * `trigger_handler` - Handler function for processed blocks
+/// * `pending_alert_tracker` - Tracker holding pending alerts until confirmation
+///
+/// # Returns
+/// * `Result<(), BlockWatcherError>` - Success or error
+#[instrument(skip_all, fields(network = network.slug))]
+pub async fn process_head_blocks<
+	C: BlockChainClient + Send + Clone + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>>
+		+ Send
+		+ Sync
+		+ 'static,
+>(
+	network: &Network,
+	rpc_client: &C,
+	pending_block_handler: PendingBlockHandler,
+	trigger_handler: Arc<T>,
+	pending_alert_tracker: PendingAlertTracker,
+) -> Result<(), BlockWatcherError> {
+	// Head and confirmed blocks are the same, so confirmed alerts are already immediate
+	if network.confirmation_blocks == 0 {
+		return Ok(());
+	}
+
+	let latest_block = rpc_client
+		.get_latest_block_number()
+		.await
+		.with_context(|| "Failed to get latest block number")?;
+
+	let latest_confirmed_block = latest_block.saturating_sub(network.confirmation_blocks);
+	let start_block = std::cmp::max(
+		latest_confirmed_block + 1,
+		pending_alert_tracker
+			.last_head_block(&network.slug)
+			.map_or(0, |last| last + 1),
+	);
+
+	if start_block > latest_block {
+		return Ok(());
+	}
+
+	let blocks = rpc_client
+		.get_blocks(start_block, Some(latest_block))
+		.await
+		.with_context(|| {
+			format!(
+				"Failed to get head blocks from {} to {}",
+				start_block, latest_block
+			)
+		})?;
+
+	for block in blocks {
+		let processed_block = (pending_block_handler)(block, network.clone()).await;
+		let pending_block = pending_alert_tracker.tag_pending(&processed_block);
+		let block_number = pending_block.block_number;
+		// Pending alerts are only recorded once handed off, so a failed block is evaluated
+		// again on the next run
+		(trigger_handler)(&pending_block)
+			.await
+			.with_context(|| format!("Trigger handling of head block {} failed", block_number))?
+			.with_context(|| format!("Trigger handler of head block {} failed", block_number))?;
+		pending_alert_tracker.record_pending(&pending_block);
+	}
+
+	tracing::debug!(
+		"Processed head blocks {} to {} ({} pending alerts)",
+		start_block,
+		latest_block,
+		pending_alert_tracker.pending_count(&network.slug)
+	);
+
+	Ok(())
+}
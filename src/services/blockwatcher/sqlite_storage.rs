@@ -0,0 +1,647 @@
+//! SQLite implementation of the block storage.
+//!
+//! Checkpoints, stored blocks and missed blocks of every network are kept in a single SQLite
+//! database. Every write runs in a transaction, stored and missed blocks are indexed by network
+//! and block number, and stored blocks are pruned according to a retention policy.
+//!
+//! The files written by [`FileBlockStorage`](super::FileBlockStorage) can be imported, so an
+//! existing data directory can be migrated without reprocessing blocks.
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::{
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use crate::{
+	models::BlockType, services::blockwatcher::storage::BlockStorage, utils::apply_migrations,
+};
+
+/// Default path of the block storage database
+const DEFAULT_DATABASE_PATH: &str = "data/blocks.db";
+
+/// Schema migrations of the block storage database, keyed by version
+const MIGRATIONS: &[(i64, &str)] = &[(
+	1,
+	"CREATE TABLE checkpoints (
+		network TEXT PRIMARY KEY NOT NULL,
+		block_number INTEGER NOT NULL,
+		updated_at INTEGER NOT NULL
+	);
+	CREATE TABLE blocks (
+		network TEXT NOT NULL,
+		block_number INTEGER NOT NULL,
+		block TEXT NOT NULL,
+		stored_at INTEGER NOT NULL,
+		PRIMARY KEY (network, block_number)
+	);
+	CREATE INDEX blocks_stored_at ON blocks (network, stored_at);
+	CREATE TABLE missed_blocks (
+		network TEXT NOT NULL,
+		block_number INTEGER NOT NULL,
+		recorded_at INTEGER NOT NULL,
+		PRIMARY KEY (network, block_number)
+	);",
+)];
+
+/// Retention policy of the stored blocks
+///
+/// Blocks are pruned per network every time blocks are saved. Without any limit, stored blocks
+/// are kept until they are deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockRetention {
+	/// Maximum number of blocks kept per network, the highest block numbers are kept
+	pub max_blocks: Option<u64>,
+	/// Maximum time a block is kept after it was stored
+	pub max_age: Option<Duration>,
+}
+
+/// Number of records imported from the files of a [`FileBlockStorage`](super::FileBlockStorage)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockImportSummary {
+	/// Number of imported checkpoints, one per network
+	pub checkpoints: usize,
+	/// Number of imported blocks
+	pub blocks: usize,
+	/// Number of imported missed blocks
+	pub missed_blocks: usize,
+}
+
+/// SQLite implementation of block storage
+///
+/// The connection is shared by the clones of the storage, and queries run on the blocking
+/// thread pool so they don't stall the block watchers.
+#[derive(Clone)]
+pub struct SqliteBlockStorage {
+	/// Connection to the database
+	connection: Arc<Mutex<Connection>>,
+	/// Retention policy of the stored blocks
+	retention: BlockRetention,
+}
+
+impl SqliteBlockStorage {
+	/// Opens the block storage database and applies pending migrations
+	///
+	/// If no path is provided, uses the default database path. The database and its parent
+	/// directory are created if they don't exist.
+	///
+	/// # Errors
+	/// Returns an error if the database cannot be opened or migrated
+	pub fn new(path: Option<&Path>) -> Result<Self, anyhow::Error> {
+		let path = path.unwrap_or(Path::new(DEFAULT_DATABASE_PATH));
+		if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+			std::fs::create_dir_all(parent)
+				.map_err(|e| anyhow::anyhow!("Failed to create block storage directory: {}", e))?;
+		}
+
+		let mut connection = Connection::open(path)
+			.map_err(|e| anyhow::anyhow!("Failed to open block storage: {}", e))?;
+		connection
+			.pragma_update(None, "journal_mode", "WAL")
+			.and_then(|_| connection.pragma_update(None, "synchronous", "FULL"))
+			.map_err(|e| anyhow::anyhow!("Failed to configure block storage: {}", e))?;
+		apply_migrations(&mut connection, MIGRATIONS)
+			.map_err(|e| anyhow::anyhow!("Failed to migrate block storage: {}", e))?;
+
+		Ok(SqliteBlockStorage {
+			connection: Arc::new(Mutex::new(connection)),
+			retention: BlockRetention::default(),
+		})
+	}
+
+	/// Sets the retention policy of the stored blocks
+	pub fn with_retention(mut self, retention: BlockRetention) -> Self {
+		self.retention = retention;
+		self
+	}
+
+	/// Imports the files written by a [`FileBlockStorage`](super::FileBlockStorage)
+	///
+	/// Reads `{network}_last_block.txt`, `{network}_missed_blocks.txt` and
+	/// `{network}_blocks_{timestamp}.json` from the data directory and writes them in a single
+	/// transaction, so a failed import leaves the database unchanged. Imported checkpoints
+	/// replace the existing ones, and the files are left in place.
+	///
+	/// # Arguments
+	/// * `data_dir` - Directory the file storage writes to
+	///
+	/// # Errors
+	/// Returns an error if a file cannot be read or parsed, or if the import fails
+	pub async fn import_files(&self, data_dir: &Path) -> Result<BlockImportSummary, anyhow::Error> {
+		let data_dir = data_dir.to_path_buf();
+		self.with_transaction("Failed to import block storage files", move |transaction| {
+			import_files(transaction, &data_dir)
+		})
+		.await
+	}
+
+	/// Runs a function in a transaction on the blocking thread pool
+	///
+	/// The transaction is committed if the function succeeds and rolled back otherwise. Errors
+	/// are prefixed with the given message.
+	async fn with_transaction<R, F>(&self, message: &'static str, f: F) -> Result<R, anyhow::Error>
+	where
+		R: Send + 'static,
+		F: FnOnce(&Transaction) -> Result<R, anyhow::Error> + Send + 'static,
+	{
+		let connection = self.connection.clone();
+		tokio::task::spawn_blocking(move || {
+			let mut connection = connection
+				.lock()
+				.map_err(|_| anyhow::anyhow!("Block storage connection is poisoned"))?;
+			let transaction = connection.transaction()?;
+			let result = f(&transaction)?;
+			transaction.commit()?;
+			Ok(result)
+		})
+		.await
+		.map_err(|e| anyhow::anyhow!("{}: {}", message, e))?
+		.map_err(|e: anyhow::Error| anyhow::anyhow!("{}: {}", message, e))
+	}
+}
+
+/// Current time as a Unix timestamp in seconds
+fn now() -> i64 {
+	chrono::Utc::now().timestamp()
+}
+
+/// Inserts blocks of a network, replacing blocks with the same number
+fn insert_blocks(
+	transaction: &Transaction,
+	network_id: &str,
+	blocks: &[BlockType],
+	stored_at: i64,
+) -> Result<(), anyhow::Error> {
+	let mut statement = transaction.prepare_cached(
+		"INSERT OR REPLACE INTO blocks (network, block_number, block, stored_at)
+		 VALUES (?1, ?2, ?3, ?4)",
+	)?;
+	for block in blocks {
+		let number = block
+			.number()
+			.ok_or_else(|| anyhow::anyhow!("Block without a number cannot be stored"))?;
+		let json = serde_json::to_string(block)?;
+		statement.execute(params![network_id, number as i64, json, stored_at])?;
+	}
+	Ok(())
+}
+
+/// Deletes the stored blocks of a network that exceed the retention policy
+fn apply_retention(
+	transaction: &Transaction,
+	network_id: &str,
+	retention: &BlockRetention,
+) -> Result<(), anyhow::Error> {
+	if let Some(max_age) = retention.max_age {
+		transaction.execute(
+			"DELETE FROM blocks WHERE network = ?1 AND stored_at < ?2",
+			params![network_id, now() - max_age.as_secs() as i64],
+		)?;
+	}
+	if let Some(max_blocks) = retention.max_blocks {
+		transaction.execute(
+			"DELETE FROM blocks WHERE network = ?1 AND block_number NOT IN (
+				SELECT block_number FROM blocks WHERE network = ?1
+				ORDER BY block_number DESC LIMIT ?2
+			)",
+			params![network_id, max_blocks as i64],
+		)?;
+	}
+	Ok(())
+}
+
+/// Records missed blocks of a network, ignoring blocks already recorded
+fn insert_missed_blocks(
+	transaction: &Transaction,
+	network_id: &str,
+	blocks: &[u64],
+) -> Result<(), anyhow::Error> {
+	let mut statement = transaction.prepare_cached(
+		"INSERT OR IGNORE INTO missed_blocks (network, block_number, recorded_at)
+		 VALUES (?1, ?2, ?3)",
+	)?;
+	for block in blocks {
+		statement.execute(params![network_id, *block as i64, now()])?;
+	}
+	Ok(())
+}
+
+/// Upserts the checkpoint of a network
+fn upsert_checkpoint(
+	transaction: &Transaction,
+	network_id: &str,
+	block: u64,
+) -> Result<(), anyhow::Error> {
+	transaction.execute(
+		"INSERT INTO checkpoints (network, block_number, updated_at) VALUES (?1, ?2, ?3)
+		 ON CONFLICT(network) DO UPDATE SET block_number = excluded.block_number,
+		 updated_at = excluded.updated_at",
+		params![network_id, block as i64, now()],
+	)?;
+	Ok(())
+}
+
+/// Imports the files of a file storage data directory
+fn import_files(
+	transaction: &Transaction,
+	data_dir: &Path,
+) -> Result<BlockImportSummary, anyhow::Error> {
+	let mut summary = BlockImportSummary::default();
+	let mut entries: Vec<PathBuf> = std::fs::read_dir(data_dir)
+		.map_err(|e| anyhow::anyhow!("Failed to read {}: {}", data_dir.display(), e))?
+		.flatten()
+		.map(|entry| entry.path())
+		.filter(|path| path.is_file())
+		.collect();
+	entries.sort();
+
+	for path in entries {
+		let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+			continue;
+		};
+		let read = || {
+			std::fs::read_to_string(&path)
+				.map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+		};
+
+		if let Some(network_id) = file_name.strip_suffix("_last_block.txt") {
+			let block = read()?.trim().parse::<u64>().map_err(|e| {
+				anyhow::anyhow!("Failed to parse last processed block {}: {}", file_name, e)
+			})?;
+			upsert_checkpoint(transaction, network_id, block)?;
+			summary.checkpoints += 1;
+		} else if let Some(network_id) = file_name.strip_suffix("_missed_blocks.txt") {
+			let blocks = read()?
+				.lines()
+				.map(str::trim)
+				.filter(|line| !line.is_empty())
+				.map(|line| {
+					line.parse::<u64>().map_err(|e| {
+						anyhow::anyhow!("Failed to parse missed block in {}: {}", file_name, e)
+					})
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			insert_missed_blocks(transaction, network_id, &blocks)?;
+			summary.missed_blocks += blocks.len();
+		} else if let Some((network_id, stored_at)) = file_name
+			.strip_suffix(".json")
+			.and_then(|stem| stem.rsplit_once("_blocks_"))
+			.and_then(|(network_id, timestamp)| {
+				timestamp.parse::<i64>().ok().map(|ts| (network_id, ts))
+			}) {
+			let blocks: Vec<BlockType> = serde_json::from_str(&read()?)
+				.map_err(|e| anyhow::anyhow!("Failed to parse blocks {}: {}", file_name, e))?;
+			insert_blocks(transaction, network_id, &blocks, stored_at)?;
+			summary.blocks += blocks.len();
+		}
+	}
+
+	Ok(summary)
+}
+
+#[async_trait]
+impl BlockStorage for SqliteBlockStorage {
+	async fn get_last_processed_block(
+		&self,
+		network_id: &str,
+	) -> Result<Option<u64>, anyhow::Error> {
+		let network_id = network_id.to_string();
+		self.with_transaction("Failed to read last processed block", move |transaction| {
+			let block: Option<i64> = transaction
+				.query_row(
+					"SELECT block_number FROM checkpoints WHERE network = ?1",
+					[&network_id],
+					|row| row.get(0),
+				)
+				.optional()?;
+			Ok(block.map(|block| block as u64))
+		})
+		.await
+	}
+
+	/// Saves the last processed block of a network
+	///
+	/// # Note
+	/// The checkpoint is replaced in a transaction, so a crash never leaves a partially written
+	/// checkpoint behind.
+	async fn save_last_processed_block(
+		&self,
+		network_id: &str,
+		block: u64,
+	) -> Result<(), anyhow::Error> {
+		let network_id = network_id.to_string();
+		self.with_transaction("Failed to save last processed block", move |transaction| {
+			upsert_checkpoint(transaction, &network_id, block)
+		})
+		.await
+	}
+
+	/// Saves blocks of a network and prunes the stored blocks exceeding the retention policy
+	///
+	/// # Note
+	/// Blocks are keyed by number, so saving a block again replaces it.
+	async fn save_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[BlockType],
+	) -> Result<(), anyhow::Error> {
+		let network_id = network_id.to_string();
+		let blocks = blocks.to_vec();
+		let retention = self.retention;
+		self.with_transaction("Failed to save blocks", move |transaction| {
+			insert_blocks(transaction, &network_id, &blocks, now())?;
+			apply_retention(transaction, &network_id, &retention)
+		})
+		.await
+	}
+
+	async fn delete_blocks(&self, network_id: &str) -> Result<(), anyhow::Error> {
+		let network_id = network_id.to_string();
+		self.with_transaction("Failed to delete blocks", move |transaction| {
+			transaction.execute("DELETE FROM blocks WHERE network = ?1", [&network_id])?;
+			Ok(())
+		})
+		.await
+	}
+
+	/// Stores processed blocks next to the ones already stored
+	///
+	/// # Note
+	/// Unlike the file storage, older blocks are kept until the retention policy prunes them.
+	async fn store_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[BlockType],
+	) -> Result<(), anyhow::Error> {
+		self.save_blocks(network_id, blocks).await
+	}
+
+	async fn save_missed_block(&self, network_id: &str, block: u64) -> Result<(), anyhow::Error> {
+		let network_id = network_id.to_string();
+		self.with_transaction("Failed to save missed block", move |transaction| {
+			insert_missed_blocks(transaction, &network_id, &[block])
+		})
+		.await
+	}
+
+	async fn get_missed_blocks(&self, network_id: &str) -> Result<Vec<u64>, anyhow::Error> {
+		let network_id = network_id.to_string();
+		self.with_transaction("Failed to read missed blocks", move |transaction| {
+			let mut statement = transaction.prepare(
+				"SELECT block_number FROM missed_blocks WHERE network = ?1
+				 ORDER BY block_number",
+			)?;
+			let blocks = statement
+				.query_map([&network_id], |row| row.get::<_, i64>(0))?
+				.map(|block| block.map(|block| block as u64))
+				.collect::<rusqlite::Result<Vec<_>>>()?;
+			Ok(blocks)
+		})
+		.await
+	}
+
+	async fn remove_missed_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[u64],
+	) -> Result<(), anyhow::Error> {
+		if blocks.is_empty() {
+			return Ok(());
+		}
+
+		let network_id = network_id.to_string();
+		let blocks = blocks.to_vec();
+		self.with_transaction("Failed to update missed blocks", move |transaction| {
+			let mut statement = transaction.prepare_cached(
+				"DELETE FROM missed_blocks WHERE network = ?1 AND block_number = ?2",
+			)?;
+			for block in blocks {
+				statement.execute(params![network_id, block as i64])?;
+			}
+			Ok(())
+		})
+		.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{StellarBlock, StellarLedgerInfo};
+	use tempfile::TempDir;
+
+	fn create_block(sequence: u32) -> BlockType {
+		BlockType::Stellar(Box::new(StellarBlock::from(StellarLedgerInfo {
+			sequence,
+			..Default::default()
+		})))
+	}
+
+	fn create_storage(temp_dir: &TempDir) -> SqliteBlockStorage {
+		SqliteBlockStorage::new(Some(&temp_dir.path().join("data/blocks.db"))).unwrap()
+	}
+
+	fn stored_blocks(storage: &SqliteBlockStorage, network_id: &str) -> Vec<u64> {
+		let connection = storage.connection.lock().unwrap();
+		let mut statement = connection
+			.prepare("SELECT block_number FROM blocks WHERE network = ?1 ORDER BY block_number")
+			.unwrap();
+		statement
+			.query_map([network_id], |row| row.get::<_, i64>(0))
+			.unwrap()
+			.map(|block| block.unwrap() as u64)
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn test_last_processed_block() {
+		let temp_dir = TempDir::new().unwrap();
+		let storage = create_storage(&temp_dir);
+
+		assert_eq!(
+			storage.get_last_processed_block("test").await.unwrap(),
+			None
+		);
+		storage
+			.save_last_processed_block("test", 100)
+			.await
+			.unwrap();
+		storage
+			.save_last_processed_block("test", 101)
+			.await
+			.unwrap();
+		storage.save_last_processed_block("other", 5).await.unwrap();
+		assert_eq!(
+			storage.get_last_processed_block("test").await.unwrap(),
+			Some(101)
+		);
+
+		// The checkpoint survives reopening the database
+		let reopened = create_storage(&temp_dir);
+		assert_eq!(
+			reopened.get_last_processed_block("test").await.unwrap(),
+			Some(101)
+		);
+		assert_eq!(
+			reopened.get_last_processed_block("other").await.unwrap(),
+			Some(5)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_save_and_delete_blocks() {
+		let temp_dir = TempDir::new().unwrap();
+		let storage = create_storage(&temp_dir);
+
+		storage
+			.save_blocks("test", &[create_block(1), create_block(2)])
+			.await
+			.unwrap();
+		storage
+			.store_blocks("test", &[create_block(2), create_block(3)])
+			.await
+			.unwrap();
+		storage
+			.save_blocks("other", &[create_block(1)])
+			.await
+			.unwrap();
+		assert_eq!(stored_blocks(&storage, "test"), vec![1, 2, 3]);
+
+		storage.delete_blocks("test").await.unwrap();
+		assert!(stored_blocks(&storage, "test").is_empty());
+		assert_eq!(stored_blocks(&storage, "other"), vec![1]);
+	}
+
+	#[tokio::test]
+	async fn test_retention_keeps_latest_blocks() {
+		let temp_dir = TempDir::new().unwrap();
+		let storage = create_storage(&temp_dir).with_retention(BlockRetention {
+			max_blocks: Some(2),
+			max_age: None,
+		});
+
+		storage
+			.store_blocks("test", &[create_block(1), create_block(2)])
+			.await
+			.unwrap();
+		storage
+			.store_blocks("test", &[create_block(3)])
+			.await
+			.unwrap();
+		storage
+			.store_blocks("other", &[create_block(1)])
+			.await
+			.unwrap();
+
+		assert_eq!(stored_blocks(&storage, "test"), vec![2, 3]);
+		assert_eq!(stored_blocks(&storage, "other"), vec![1]);
+	}
+
+	#[tokio::test]
+	async fn test_retention_prunes_old_blocks() {
+		let temp_dir = TempDir::new().unwrap();
+		let storage = create_storage(&temp_dir).with_retention(BlockRetention {
+			max_blocks: None,
+			max_age: Some(Duration::from_secs(60)),
+		});
+		storage
+			.connection
+			.lock()
+			.unwrap()
+			.execute(
+				"INSERT INTO blocks (network, block_number, block, stored_at)
+				 VALUES ('test', 1, '{}', 0)",
+				[],
+			)
+			.unwrap();
+
+		storage
+			.store_blocks("test", &[create_block(2)])
+			.await
+			.unwrap();
+
+		assert_eq!(stored_blocks(&storage, "test"), vec![2]);
+	}
+
+	#[tokio::test]
+	async fn test_missed_blocks() {
+		let temp_dir = TempDir::new().unwrap();
+		let storage = create_storage(&temp_dir);
+
+		for block in [7, 3, 7, 5] {
+			storage.save_missed_block("test", block).await.unwrap();
+		}
+		storage.save_missed_block("other", 1).await.unwrap();
+		assert_eq!(
+			storage.get_missed_blocks("test").await.unwrap(),
+			vec![3, 5, 7]
+		);
+
+		storage.remove_missed_blocks("test", &[3, 7]).await.unwrap();
+		storage.remove_missed_blocks("test", &[]).await.unwrap();
+		assert_eq!(storage.get_missed_blocks("test").await.unwrap(), vec![5]);
+		assert_eq!(storage.get_missed_blocks("other").await.unwrap(), vec![1]);
+	}
+
+	#[tokio::test]
+	async fn test_import_files() {
+		let temp_dir = TempDir::new().unwrap();
+		let data_dir = temp_dir.path().join("files");
+		std::fs::create_dir(&data_dir).unwrap();
+		std::fs::write(data_dir.join("stellar_mainnet_last_block.txt"), "42\n").unwrap();
+		std::fs::write(
+			data_dir.join("stellar_mainnet_missed_blocks.txt"),
+			"40\n38\n40\n",
+		)
+		.unwrap();
+		std::fs::write(
+			data_dir.join("stellar_mainnet_blocks_1700000000.json"),
+			serde_json::to_string(&[create_block(41), create_block(42)]).unwrap(),
+		)
+		.unwrap();
+		std::fs::write(data_dir.join("pending_alerts.json"), "{}").unwrap();
+
+		let storage = create_storage(&temp_dir);
+		let summary = storage.import_files(&data_dir).await.unwrap();
+
+		assert_eq!(
+			summary,
+			BlockImportSummary {
+				checkpoints: 1,
+				blocks: 2,
+				missed_blocks: 3,
+			}
+		);
+		assert_eq!(
+			storage
+				.get_last_processed_block("stellar_mainnet")
+				.await
+				.unwrap(),
+			Some(42)
+		);
+		assert_eq!(
+			storage.get_missed_blocks("stellar_mainnet").await.unwrap(),
+			vec![38, 40]
+		);
+		assert_eq!(stored_blocks(&storage, "stellar_mainnet"), vec![41, 42]);
+	}
+
+	#[tokio::test]
+	async fn test_failed_import_keeps_database() {
+		let temp_dir = TempDir::new().unwrap();
+		let data_dir = temp_dir.path().join("files");
+		std::fs::create_dir(&data_dir).unwrap();
+		std::fs::write(data_dir.join("a_last_block.txt"), "42").unwrap();
+		std::fs::write(data_dir.join("b_last_block.txt"), "not a number").unwrap();
+
+		let storage = create_storage(&temp_dir);
+		let err = storage.import_files(&data_dir).await.unwrap_err();
+
+		assert!(err
+			.to_string()
+			.contains("Failed to import block storage files"));
+		assert_eq!(storage.get_last_processed_block("a").await.unwrap(), None);
+	}
+}
@@ -5,12 +5,16 @@
 //! - File-based storage with JSON serialization
 //! - Last processed block tracking
 //! - Block deletion for cleanup
+//! - Missed block ledger for recovery
+//!
+//! The SQLite implementation lives in its own module. [`BlockStorageBackend`] selects one of
+//! them at startup.
 
 use async_trait::async_trait;
 use glob::glob;
 use std::path::PathBuf;
 
-use crate::models::BlockType;
+use crate::{models::BlockType, services::blockwatcher::sqlite_storage::SqliteBlockStorage};
 
 /// Interface for block storage implementations
 ///
@@ -67,6 +71,28 @@ pub trait BlockStorage: Clone + Send + Sync {
 	/// * `Result<(), anyhow::Error>` - Success or error
 	async fn delete_blocks(&self, network_id: &str) -> Result<(), anyhow::Error>;
 
+	/// Stores the blocks of a processed batch for a network
+	///
+	/// The default implementation deletes the stored blocks before saving the batch, so only
+	/// the latest batch is kept. Storages with a retention policy keep older blocks instead.
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `blocks` - Blocks of the processed batch
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn store_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[BlockType],
+	) -> Result<(), anyhow::Error> {
+		self.delete_blocks(network_id)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to delete old blocks: {}", e))?;
+		self.save_blocks(network_id, blocks).await
+	}
+
 	/// Saves a missed block for a network
 	///
 	/// # Arguments
@@ -76,6 +102,29 @@ pub trait BlockStorage: Clone + Send + Sync {
 	/// # Returns
 	/// * `Result<(), anyhow::Error>` - Success or error
 	async fn save_missed_block(&self, network_id: &str, block: u64) -> Result<(), anyhow::Error>;
+
+	/// Retrieves the outstanding missed blocks for a network
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	///
+	/// # Returns
+	/// * `Result<Vec<u64>, anyhow::Error>` - Sorted, deduplicated missed block numbers
+	async fn get_missed_blocks(&self, network_id: &str) -> Result<Vec<u64>, anyhow::Error>;
+
+	/// Removes resolved blocks from the missed block ledger of a network
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `blocks` - Block numbers that were successfully recovered
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn remove_missed_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[u64],
+	) -> Result<(), anyhow::Error>;
 }
 
 /// File-based implementation of block storage
@@ -225,6 +274,174 @@ impl BlockStorage for FileBlockStorage {
 
 		Ok(())
 	}
+
+	/// Retrieves the missed blocks from a network-specific file
+	///
+	/// The file is named "{network_id}_missed_blocks.txt" and holds one block number per line
+	async fn get_missed_blocks(&self, network_id: &str) -> Result<Vec<u64>, anyhow::Error> {
+		let file_path = self
+			.storage_path
+			.join(format!("{}_missed_blocks.txt", network_id));
+
+		if !file_path.exists() {
+			return Ok(Vec::new());
+		}
+
+		let content = tokio::fs::read_to_string(file_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read missed blocks: {}", e))?;
+
+		let mut blocks = content
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty())
+			.map(|line| {
+				line.parse::<u64>()
+					.map_err(|e| anyhow::anyhow!("Failed to parse missed block: {}", e))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		blocks.sort_unstable();
+		blocks.dedup();
+		Ok(blocks)
+	}
+
+	/// Removes resolved blocks from the network-specific missed blocks file
+	///
+	/// # Note
+	/// The remaining blocks are written to a temporary file which then replaces the ledger, so
+	/// an interrupted write never loses outstanding blocks. The file is deleted once empty.
+	async fn remove_missed_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[u64],
+	) -> Result<(), anyhow::Error> {
+		if blocks.is_empty() {
+			return Ok(());
+		}
+
+		let remaining: Vec<u64> = self
+			.get_missed_blocks(network_id)
+			.await?
+			.into_iter()
+			.filter(|block| !blocks.contains(block))
+			.collect();
+
+		let file_path = self
+			.storage_path
+			.join(format!("{}_missed_blocks.txt", network_id));
+
+		if remaining.is_empty() {
+			if file_path.exists() {
+				tokio::fs::remove_file(file_path)
+					.await
+					.map_err(|e| anyhow::anyhow!("Failed to update missed blocks: {}", e))?;
+			}
+			return Ok(());
+		}
+
+		let tmp_path = self
+			.storage_path
+			.join(format!("{}_missed_blocks.txt.tmp", network_id));
+		let content: String = remaining.iter().map(|b| format!("{}\n", b)).collect();
+		tokio::fs::write(&tmp_path, content)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to update missed blocks: {}", e))?;
+		tokio::fs::rename(tmp_path, file_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to update missed blocks: {}", e))?;
+
+		Ok(())
+	}
+}
+
+/// Block storage selected when the service starts
+///
+/// Delegates every operation to the file-based or SQLite storage, so the block watchers and
+/// the management API don't depend on the storage in use.
+#[derive(Clone)]
+pub enum BlockStorageBackend {
+	/// Files in the data directory
+	File(FileBlockStorage),
+	/// SQLite database
+	Sqlite(SqliteBlockStorage),
+}
+
+#[async_trait]
+impl BlockStorage for BlockStorageBackend {
+	async fn get_last_processed_block(
+		&self,
+		network_id: &str,
+	) -> Result<Option<u64>, anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.get_last_processed_block(network_id).await,
+			Self::Sqlite(storage) => storage.get_last_processed_block(network_id).await,
+		}
+	}
+
+	async fn save_last_processed_block(
+		&self,
+		network_id: &str,
+		block: u64,
+	) -> Result<(), anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.save_last_processed_block(network_id, block).await,
+			Self::Sqlite(storage) => storage.save_last_processed_block(network_id, block).await,
+		}
+	}
+
+	async fn save_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[BlockType],
+	) -> Result<(), anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.save_blocks(network_id, blocks).await,
+			Self::Sqlite(storage) => storage.save_blocks(network_id, blocks).await,
+		}
+	}
+
+	async fn delete_blocks(&self, network_id: &str) -> Result<(), anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.delete_blocks(network_id).await,
+			Self::Sqlite(storage) => storage.delete_blocks(network_id).await,
+		}
+	}
+
+	async fn store_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[BlockType],
+	) -> Result<(), anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.store_blocks(network_id, blocks).await,
+			Self::Sqlite(storage) => storage.store_blocks(network_id, blocks).await,
+		}
+	}
+
+	async fn save_missed_block(&self, network_id: &str, block: u64) -> Result<(), anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.save_missed_block(network_id, block).await,
+			Self::Sqlite(storage) => storage.save_missed_block(network_id, block).await,
+		}
+	}
+
+	async fn get_missed_blocks(&self, network_id: &str) -> Result<Vec<u64>, anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.get_missed_blocks(network_id).await,
+			Self::Sqlite(storage) => storage.get_missed_blocks(network_id).await,
+		}
+	}
+
+	async fn remove_missed_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[u64],
+	) -> Result<(), anyhow::Error> {
+		match self {
+			Self::File(storage) => storage.remove_missed_blocks(network_id, blocks).await,
+			Self::Sqlite(storage) => storage.remove_missed_blocks(network_id, blocks).await,
+		}
+	}
 }
 
 #[cfg(test)]
@@ -414,4 +631,60 @@ mod tests {
 			assert!(err.to_string().contains("Permission denied"));
 		}
 	}
+
+	#[tokio::test]
+	async fn test_get_missed_blocks() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let storage = FileBlockStorage::new(temp_dir.path().to_path_buf());
+
+		// Test 1: Non-existent ledger
+		let result = storage.get_missed_blocks("test").await;
+		assert_eq!(result.unwrap(), Vec::<u64>::new());
+
+		// Test 2: Unsorted ledger with duplicates
+		for block in [102, 100, 102, 101] {
+			storage.save_missed_block("test", block).await.unwrap();
+		}
+		let result = storage.get_missed_blocks("test").await;
+		assert_eq!(result.unwrap(), vec![100, 101, 102]);
+
+		// Test 3: Invalid content
+		tokio::fs::write(temp_dir.path().join("invalid_missed_blocks.txt"), "abc\n")
+			.await
+			.unwrap();
+		let result = storage.get_missed_blocks("invalid").await;
+		assert!(result
+			.unwrap_err()
+			.to_string()
+			.contains("Failed to parse missed block"));
+	}
+
+	#[tokio::test]
+	async fn test_remove_missed_blocks() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let storage = FileBlockStorage::new(temp_dir.path().to_path_buf());
+		let ledger = temp_dir.path().join("test_missed_blocks.txt");
+
+		for block in [100, 101, 102] {
+			storage.save_missed_block("test", block).await.unwrap();
+		}
+
+		// Test 1: Remove some blocks
+		storage.remove_missed_blocks("test", &[101]).await.unwrap();
+		let content = tokio::fs::read_to_string(&ledger).await.unwrap();
+		assert_eq!(content, "100\n102\n");
+
+		// Test 2: New misses are appended to the rewritten ledger
+		storage.save_missed_block("test", 105).await.unwrap();
+		let result = storage.get_missed_blocks("test").await;
+		assert_eq!(result.unwrap(), vec![100, 102, 105]);
+
+		// Test 3: Removing all blocks deletes the ledger
+		storage
+			.remove_missed_blocks("test", &[100, 102, 105])
+			.await
+			.unwrap();
+		assert!(!ledger.exists());
+		assert!(storage.get_missed_blocks("test").await.unwrap().is_empty());
+	}
 }
@@ -180,6 +180,8 @@ mod tests {
 			async fn get_last_processed_block(&self, network_slug: &str) -> Result<Option<u64>, anyhow::Error>;
 			async fn save_blocks(&self, network_slug: &str, blocks: &[BlockType]) -> Result<(), anyhow::Error>;
 			async fn delete_blocks(&self, network_slug: &str) -> Result<(), anyhow::Error>;
+			async fn get_missed_blocks(&self, network_slug: &str) -> Result<Vec<u64>, anyhow::Error>;
+			async fn remove_missed_blocks(&self, network_slug: &str, blocks: &[u64]) -> Result<(), anyhow::Error>;
 		}
 
 		impl Clone for BlockStorage {
@@ -201,6 +203,7 @@ mod tests {
 			cron_schedule: "*/5 * * * * *".to_string(),
 			confirmation_blocks: 1,
 			store_blocks: Some(store_blocks),
+			explorer_url: None,
 			chain_id: Some(1),
 			network_passphrase: None,
 			block_time_ms: 1000,
@@ -0,0 +1,548 @@
+//! Structured evaluation traces for monitor matching.
+//!
+//! Explains why a monitor did or did not match a transaction by recording each step of the
+//! filter evaluation:
+//! - Monitored and involved addresses
+//! - Signatures and statuses compared against the monitor conditions
+//! - Expression clauses with their resolved left and right values
+//! - The final match decision and the rule that produced it
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{MatchConditions, TransactionStatus},
+	utils::split_expression,
+};
+
+/// Evaluation of a single monitor against a single transaction
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EvaluationTrace {
+	/// Name of the evaluated monitor
+	pub monitor_name: String,
+	/// Network the transaction was found on
+	pub network_slug: String,
+	/// Block containing the transaction
+	pub block_number: u64,
+	/// Hash of the evaluated transaction
+	pub transaction_hash: String,
+	/// Execution status of the transaction
+	pub transaction_status: TransactionStatus,
+	/// Addresses configured on the monitor
+	pub monitored_addresses: Vec<String>,
+	/// Addresses involved in the transaction
+	pub involved_addresses: Vec<String>,
+	/// Whether a monitored address is involved in the transaction
+	///
+	/// `None` for chains where addresses are checked per function call and event instead.
+	pub address_match: Option<bool>,
+	/// Evaluation of the transaction conditions
+	pub transactions: Vec<ConditionTrace>,
+	/// Evaluation of the function conditions
+	pub functions: Vec<ConditionTrace>,
+	/// Evaluation of the event conditions
+	pub events: Vec<ConditionTrace>,
+	/// Decision combining the condition results
+	pub decision: MatchDecision,
+	/// Whether the monitor matched the transaction
+	pub matched: bool,
+}
+
+/// Evaluation of a monitor condition against an observed function call, event or transaction
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConditionTrace {
+	/// Signature (functions and events) or status (transactions) required by the condition
+	pub condition: String,
+	/// Signature or status observed in the transaction
+	pub observed: String,
+	/// Whether the observed signature or status satisfies the condition
+	pub signature_match: bool,
+	/// Evaluation of the condition expression, if the signature matched and one is set
+	pub expression: Option<ExpressionTrace>,
+	/// Whether the condition matched
+	pub matched: bool,
+}
+
+impl ConditionTrace {
+	/// Creates a condition trace, deriving the result from the signature and expression
+	pub fn new(
+		condition: impl Into<String>,
+		observed: impl Into<String>,
+		signature_match: bool,
+		expression: Option<ExpressionTrace>,
+	) -> Self {
+		let matched = signature_match && expression.as_ref().is_none_or(|e| e.result);
+		Self {
+			condition: condition.into(),
+			observed: observed.into(),
+			signature_match,
+			expression,
+			matched,
+		}
+	}
+}
+
+/// Evaluation of a match expression
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExpressionTrace {
+	/// The expression as configured on the monitor
+	pub expression: String,
+	/// Evaluation of every clause of the expression
+	pub clauses: Vec<ClauseTrace>,
+	/// Error preventing the expression from being evaluated
+	pub error: Option<String>,
+	/// Whether the expression evaluated to true
+	pub result: bool,
+}
+
+/// Evaluation of a single `parameter operator value` clause
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ClauseTrace {
+	/// Index of the OR group the clause belongs to
+	pub group: usize,
+	/// The clause as written in the expression
+	pub clause: String,
+	/// Parameter referenced on the left side of the clause
+	pub parameter: String,
+	/// Comparison operator
+	pub operator: String,
+	/// Resolved value of the parameter
+	pub left: Option<String>,
+	/// Value the parameter is compared against
+	pub right: String,
+	/// Type used for the comparison
+	pub kind: Option<String>,
+	/// Error preventing the clause from being evaluated
+	pub error: Option<String>,
+	/// Whether the clause evaluated to true
+	pub result: bool,
+}
+
+/// Reason a clause could not be evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClauseError {
+	/// Description of the error
+	pub message: String,
+	/// Level the error is logged at outside of explain mode
+	pub level: tracing::Level,
+}
+
+impl ClauseError {
+	/// Creates an error logged as a warning, e.g. for malformed expressions
+	pub fn warn(message: impl Into<String>) -> Self {
+		Self {
+			message: message.into(),
+			level: tracing::Level::WARN,
+		}
+	}
+
+	/// Creates an error logged at debug level, e.g. for values missing from a transaction
+	pub fn debug(message: impl Into<String>) -> Self {
+		Self {
+			message: message.into(),
+			level: tracing::Level::DEBUG,
+		}
+	}
+
+	fn log(&self) {
+		if self.level == tracing::Level::WARN {
+			tracing::warn!("{}", self.message);
+		} else {
+			tracing::debug!("{}", self.message);
+		}
+	}
+}
+
+impl ExpressionTrace {
+	/// Creates an empty trace for an expression
+	pub fn new(expression: &str) -> Self {
+		Self {
+			expression: expression.to_string(),
+			..Default::default()
+		}
+	}
+}
+
+/// Condition traces collected while a transaction is matched in explain mode
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionTraces {
+	/// Evaluation of the transaction conditions
+	pub transactions: Vec<ConditionTrace>,
+	/// Evaluation of the function conditions
+	pub functions: Vec<ConditionTrace>,
+	/// Evaluation of the event conditions
+	pub events: Vec<ConditionTrace>,
+}
+
+/// Evaluates a match expression against provided arguments
+///
+/// The expression is split into OR groups of AND clauses. Without a trace, evaluation stops as
+/// soon as the result is known and clause errors are logged. With a trace, every clause is
+/// evaluated and recorded, and errors are kept in the trace instead of being logged.
+///
+/// # Arguments
+/// * `expression` - The expression to evaluate
+/// * `args` - The arguments to evaluate against
+/// * `trace` - Trace to record the evaluation in, when explaining
+/// * `evaluate_clause` - Compares a parameter with a value using an operator, recording the
+///   resolved parameter in the clause trace if one is given
+///
+/// # Returns
+/// `true` if the expression matches, `false` otherwise
+pub fn evaluate_expression<A>(
+	expression: &str,
+	args: Option<&[A]>,
+	mut trace: Option<&mut ExpressionTrace>,
+	mut evaluate_clause: impl FnMut(
+		&[A],
+		&str,
+		&str,
+		&str,
+		Option<&mut ClauseTrace>,
+	) -> Result<bool, ClauseError>,
+) -> bool {
+	let Some(args) = args else {
+		if let Some(trace) = trace {
+			trace.error = Some("No arguments available".to_string());
+		}
+		return false;
+	};
+
+	let mut result = false;
+	for (group, or_condition) in expression.split(" OR ").enumerate() {
+		let mut group_result = true;
+		for condition in or_condition.trim().split(" AND ") {
+			// Remove any surrounding parentheses and trim
+			let clean_condition = condition.trim().trim_matches(|c| c == '(' || c == ')');
+
+			let mut clause = trace.is_some().then(|| ClauseTrace {
+				group,
+				clause: clean_condition.to_string(),
+				..Default::default()
+			});
+
+			// Split into parts while preserving quoted strings
+			let evaluated = match split_expression(clean_condition) {
+				Some((parameter, operator, value)) => {
+					if let Some(clause) = clause.as_mut() {
+						clause.parameter = parameter.to_string();
+						clause.operator = operator.to_string();
+						clause.right = value.to_string();
+					}
+					evaluate_clause(args, parameter, operator, value, clause.as_mut())
+				}
+				None => Err(ClauseError::warn(format!(
+					"Invalid expression format: {}",
+					clean_condition
+				))),
+			};
+			let clause_result = match evaluated {
+				Ok(clause_result) => clause_result,
+				Err(error) => {
+					match clause.as_mut() {
+						Some(clause) => clause.error = Some(error.message),
+						None => error.log(),
+					}
+					false
+				}
+			};
+
+			if let (Some(trace), Some(mut clause)) = (trace.as_deref_mut(), clause) {
+				clause.result = clause_result;
+				trace.clauses.push(clause);
+			}
+
+			group_result &= clause_result;
+			// All AND conditions must be true, so the group is decided by a false clause
+			if !group_result && trace.is_none() {
+				break;
+			}
+		}
+
+		result |= group_result;
+		// Any OR condition being true makes the whole expression true
+		if result && trace.is_none() {
+			break;
+		}
+	}
+
+	if let Some(trace) = trace {
+		trace.result = result;
+	}
+	result
+}
+
+/// Decision combining condition results into a match
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MatchDecision {
+	/// Whether the monitor defines event conditions
+	pub has_event_conditions: bool,
+	/// Whether the monitor defines function conditions
+	pub has_function_conditions: bool,
+	/// Whether the monitor defines transaction conditions
+	pub has_transaction_conditions: bool,
+	/// Whether a defined event condition matched
+	pub event_match: bool,
+	/// Whether a defined function condition matched
+	pub function_match: bool,
+	/// Whether a defined transaction condition matched
+	pub transaction_match: bool,
+	/// Rule applied to the condition results
+	pub rule: String,
+	/// Whether the condition results satisfy the rule
+	pub should_match: bool,
+}
+
+impl MatchDecision {
+	/// Decides whether the matched conditions satisfy the monitor
+	///
+	/// # Arguments
+	/// * `conditions` - Match conditions defined on the monitor
+	/// * `events_matched` - Whether any event condition matched
+	/// * `functions_matched` - Whether any function condition matched
+	/// * `transactions_matched` - Whether any transaction condition matched
+	pub fn evaluate(
+		conditions: &MatchConditions,
+		events_matched: bool,
+		functions_matched: bool,
+		transactions_matched: bool,
+	) -> Self {
+		let has_event_conditions = !conditions.events.is_empty();
+		let has_function_conditions = !conditions.functions.is_empty();
+		let has_transaction_conditions = !conditions.transactions.is_empty();
+		let event_match = has_event_conditions && events_matched;
+		let function_match = has_function_conditions && functions_matched;
+		let transaction_match = has_transaction_conditions && transactions_matched;
+
+		let (rule, should_match) = match (
+			has_event_conditions,
+			has_function_conditions,
+			has_transaction_conditions,
+		) {
+			// Case 1: No conditions defined, match everything
+			(false, false, false) => ("No conditions defined, match everything", true),
+
+			// Case 2: Only transaction conditions defined
+			(false, false, true) => ("Only transaction conditions defined", transaction_match),
+
+			// Case 3: No transaction conditions, match based on events/functions
+			(_, _, false) => (
+				"No transaction conditions, an event or function condition must match",
+				event_match || function_match,
+			),
+
+			// Case 4: Transaction conditions exist, they must be satisfied along with
+			// events/functions
+			_ => (
+				"A transaction condition and an event or function condition must match",
+				(event_match || function_match) && transaction_match,
+			),
+		};
+
+		Self {
+			has_event_conditions,
+			has_function_conditions,
+			has_transaction_conditions,
+			event_match,
+			function_match,
+			transaction_match,
+			rule: rule.to_string(),
+			should_match,
+		}
+	}
+}
+
+impl fmt::Display for EvaluationTrace {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"Monitor '{}' on {} block {}, transaction {} ({:?}): {}",
+			self.monitor_name,
+			self.network_slug,
+			self.block_number,
+			self.transaction_hash,
+			self.transaction_status,
+			if self.matched { "MATCH" } else { "NO MATCH" }
+		)?;
+		writeln!(
+			f,
+			"  Monitored addresses: {}",
+			self.monitored_addresses.join(", ")
+		)?;
+		writeln!(
+			f,
+			"  Involved addresses: {}",
+			self.involved_addresses.join(", ")
+		)?;
+		if let Some(address_match) = self.address_match {
+			writeln!(f, "  Address match: {}", address_match)?;
+		}
+
+		for (label, conditions) in [
+			("Transaction conditions", &self.transactions),
+			("Function conditions", &self.functions),
+			("Event conditions", &self.events),
+		] {
+			if conditions.is_empty() {
+				continue;
+			}
+			writeln!(f, "  {}:", label)?;
+			for condition in conditions {
+				writeln!(
+					f,
+					"    [{}] {} vs observed {}",
+					mark(condition.matched),
+					condition.condition,
+					condition.observed
+				)?;
+				if let Some(expression) = &condition.expression {
+					write!(f, "{}", expression)?;
+				}
+			}
+		}
+
+		let decision = &self.decision;
+		writeln!(
+			f,
+			"  Decision: {} (events: {}, functions: {}, transactions: {}) => {}",
+			decision.rule,
+			decision.event_match,
+			decision.function_match,
+			decision.transaction_match,
+			decision.should_match
+		)
+	}
+}
+
+impl fmt::Display for ExpressionTrace {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"      expression `{}` => {}",
+			self.expression, self.result
+		)?;
+		if let Some(error) = &self.error {
+			writeln!(f, "        error: {}", error)?;
+		}
+		for clause in &self.clauses {
+			write!(
+				f,
+				"        [{}] group {}: {} {} {} ({} {} {})",
+				mark(clause.result),
+				clause.group,
+				clause.parameter,
+				clause.operator,
+				clause.right,
+				clause.kind.as_deref().unwrap_or("?"),
+				clause.left.as_deref().unwrap_or("<unresolved>"),
+				clause.operator
+			)?;
+			match &clause.error {
+				Some(error) => writeln!(f, " error: {}", error)?,
+				None => writeln!(f)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+fn mark(result: bool) -> char {
+	if result {
+		'x'
+	} else {
+		' '
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{EventCondition, TransactionCondition};
+
+	/// Evaluates an expression where only parameters starting with `a` or `c` are true,
+	/// returning the result and the evaluated parameters
+	fn evaluate(expression: &str, trace: Option<&mut ExpressionTrace>) -> (bool, Vec<String>) {
+		let mut evaluated = Vec::new();
+		let args: &[()] = &[];
+		let result = evaluate_expression(expression, Some(args), trace, |_, parameter, _, _, _| {
+			evaluated.push(parameter.to_string());
+			Ok(parameter.starts_with('a') || parameter.starts_with('c'))
+		});
+		(result, evaluated)
+	}
+
+	#[test]
+	fn test_evaluate_expression_short_circuits_without_trace() {
+		let (result, evaluated) = evaluate("(b > 1 AND a > 2) OR c > 3 OR d > 4", None);
+		assert!(result);
+		assert_eq!(evaluated, ["b", "c"]);
+
+		let (result, evaluated) = evaluate("a > 1 AND b > 2 AND c > 3", None);
+		assert!(!result);
+		assert_eq!(evaluated, ["a", "b"]);
+	}
+
+	#[test]
+	fn test_evaluate_expression_records_every_clause_with_trace() {
+		let mut trace = ExpressionTrace::new("(b > 1 AND a > 2) OR c > 3 OR d");
+		let (result, evaluated) = evaluate(&trace.expression.clone(), Some(&mut trace));
+
+		assert!(result);
+		assert!(trace.result);
+		assert_eq!(evaluated, ["b", "a", "c"]);
+		assert_eq!(trace.clauses.len(), 4);
+		assert_eq!(trace.clauses[0].clause, "b > 1");
+		assert_eq!(trace.clauses[0].parameter, "b");
+		assert_eq!(trace.clauses[1].right, "2");
+		assert_eq!(trace.clauses[2].group, 1);
+		assert!(trace.clauses[3].error.is_some());
+	}
+
+	#[test]
+	fn test_evaluate_expression_without_arguments() {
+		let mut trace = ExpressionTrace::new("a > 1");
+		let args: Option<&[()]> = None;
+		assert!(!evaluate_expression(
+			"a > 1",
+			args,
+			Some(&mut trace),
+			|_, _, _, _, _| Ok(true)
+		));
+		assert!(trace.error.is_some());
+	}
+
+	#[test]
+	fn test_match_decision_rules() {
+		let mut conditions = MatchConditions::default();
+		let decision = MatchDecision::evaluate(&conditions, false, false, false);
+		assert!(decision.should_match);
+
+		conditions.transactions.push(TransactionCondition {
+			status: TransactionStatus::Success,
+			expression: None,
+		});
+		assert!(MatchDecision::evaluate(&conditions, false, false, true).should_match);
+		assert!(!MatchDecision::evaluate(&conditions, false, false, false).should_match);
+
+		conditions.events.push(EventCondition {
+			signature: "Transfer(address,address,uint256)".to_string(),
+			expression: None,
+		});
+		let decision = MatchDecision::evaluate(&conditions, false, false, true);
+		assert!(!decision.should_match);
+		assert!(!decision.event_match);
+		assert!(MatchDecision::evaluate(&conditions, true, false, true).should_match);
+	}
+
+	#[test]
+	fn test_condition_trace_requires_signature_and_expression() {
+		let expression = ExpressionTrace {
+			result: false,
+			..Default::default()
+		};
+		assert!(!ConditionTrace::new("a()", "a()", true, Some(expression)).matched);
+		assert!(ConditionTrace::new("a()", "a()", true, None).matched);
+		assert!(!ConditionTrace::new("a()", "b()", false, None).matched);
+	}
+}
@@ -11,7 +11,7 @@ use std::collections::HashMap;
 use alloy::primitives::Address;
 
 use crate::{
-	models::{MonitorMatch, ScriptLanguage},
+	models::{MatchContext, MonitorMatch, ScriptLanguage},
 	services::{
 		filter::{
 			evm_helpers::{b256_to_string, h160_to_string},
@@ -39,6 +39,9 @@ use crate::{
 /// The function converts blockchain data into template variables like:
 /// ```text
 /// "monitor_name": "Transfer USDT Token"
+/// "severity": "warning" (monitors with a severity)
+/// "alert_phase": "pending" (two-phase alerts only)
+/// "correlation_id": "6f1ed002ab5595859014ebf0951522d9" (two-phase alerts only)
 /// "transaction_hash": "0x99139c8f64b9b939678e261e1553660b502d9fd01c2ab1516e699ee6c8cc5791"
 /// "transaction_from": "0xf401346fd255e034a2e43151efe1d68c1e0f8ca5"
 /// "transaction_to": "0x0000000000001ff3684f28c67538d4d072c22734"
@@ -47,13 +50,48 @@ use crate::{
 /// "event_0_to": "0x70bf6634ee8cb27d04478f184b9b8bb13e5f4710"
 /// "event_0_from": "0x2e8135be71230c6b1b4045696d41c09db0414226"
 /// "event_0_value": "88248701"
+/// "network_slug": "ethereum_mainnet"
+/// "block_number": "21234567"
+/// "transaction_link": "https://etherscan.io/tx/0x99139c8f64b9..." (networks with an explorer_url)
+/// "transaction_from_link": "https://etherscan.io/address/0xf401346f..."
 /// ```
 pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 	matching_monitor: MonitorMatch,
 	trigger_service: &T,
 	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 ) -> Result<(), FilterError> {
-	match &matching_monitor {
+	let data = build_match_variables(&matching_monitor);
+
+	// Swallow any errors since it's logged in the trigger service and we want to continue
+	// processing other matches
+	let _ = trigger_service
+		.execute(
+			&matching_monitor
+				.monitor()
+				.triggers
+				.iter()
+				.map(|s| s.to_string())
+				.collect::<Vec<_>>(),
+			data,
+			&matching_monitor,
+			trigger_scripts,
+		)
+		.await;
+	Ok(())
+}
+
+/// Builds the template variables for a monitor match.
+///
+/// These are the variables substituted into trigger message templates when the match is
+/// handled. See [`handle_match`] for an example.
+///
+/// # Arguments
+/// * `matching_monitor` - The matched monitor event
+///
+/// # Returns
+/// Template variables keyed by name
+pub fn build_match_variables(matching_monitor: &MonitorMatch) -> HashMap<String, String> {
+	match matching_monitor {
 		MonitorMatch::EVM(evm_monitor_match) => {
 			let transaction = evm_monitor_match.transaction.clone();
 			// If sender does not exist, we replace with 0x0000000000000000000000000000000000000000
@@ -76,65 +114,98 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				"monitor_name".to_string(),
 				evm_monitor_match.monitor.name.clone(),
 			);
+			if let Some(severity) = evm_monitor_match.monitor.severity {
+				data.insert("severity".to_string(), severity.to_string());
+			}
+			if let Some(alert) = &evm_monitor_match.alert {
+				data.insert("alert_phase".to_string(), alert.phase.to_string());
+				data.insert("correlation_id".to_string(), alert.correlation_id.clone());
+			}
 
-			let matched_args: HashMap<String, String> =
-				if let Some(args) = &evm_monitor_match.matched_on_args {
-					let mut map = HashMap::new();
-					if let Some(functions) = &args.functions {
-						for (idx, func) in functions.iter().enumerate() {
-							// First add the signature
-							map.insert(
-								format!("function_{}_signature", idx),
-								func.signature.clone(),
-							);
-							// Then add all arguments
-							if let Some(func_args) = &func.args {
-								for arg in func_args {
-									map.insert(
-										format!("function_{}_{}", idx, arg.name),
-										arg.value.clone(),
-									);
-								}
+			let receipt = &evm_monitor_match.receipt;
+			if let Some(block_number) = receipt.block_number.or(transaction.block_number) {
+				data.insert("block_number".to_string(), block_number.to_string());
+			}
+			if let Some(status) = receipt.status {
+				let status = if status.to::<u64>() == 1 {
+					"success"
+				} else {
+					"failed"
+				};
+				data.insert("transaction_status".to_string(), status.to_string());
+			}
+			if let Some(gas_used) = receipt.gas_used {
+				data.insert("gas_used".to_string(), gas_used.to_string());
+			}
+
+			// The emitting contract of the first matched event, or the receiver of the
+			// transaction
+			let first_event = evm_monitor_match
+				.matched_on_args
+				.as_ref()
+				.and_then(|args| args.events.as_ref())
+				.and_then(|events| events.first());
+			if let Some(log_index) = first_event.and_then(|event| event.log_index) {
+				data.insert("log_index".to_string(), log_index.to_string());
+			}
+			if let Some(contract_address) = first_event
+				.and_then(|event| event.address.clone())
+				.or_else(|| transaction.to().map(|to| h160_to_string(*to)))
+			{
+				data.insert("contract_address".to_string(), contract_address);
+			}
+
+			let matched_args: HashMap<String, String> = if let Some(args) =
+				&evm_monitor_match.matched_on_args
+			{
+				let mut map = HashMap::new();
+				if let Some(functions) = &args.functions {
+					for (idx, func) in functions.iter().enumerate() {
+						// First add the signature
+						map.insert(
+							format!("function_{}_signature", idx),
+							func.signature.clone(),
+						);
+						// Then add all arguments
+						if let Some(func_args) = &func.args {
+							for arg in func_args {
+								map.insert(
+									format!("function_{}_{}", idx, arg.name),
+									arg.value.clone(),
+								);
 							}
 						}
 					}
-					if let Some(events) = &args.events {
-						for (idx, event) in events.iter().enumerate() {
-							// First add the signature
-							map.insert(format!("event_{}_signature", idx), event.signature.clone());
-							// Then add all arguments
-							if let Some(event_args) = &event.args {
-								for arg in event_args {
-									map.insert(
-										format!("event_{}_{}", idx, arg.name),
-										arg.value.clone(),
-									);
-								}
+				}
+				if let Some(events) = &args.events {
+					for (idx, event) in events.iter().enumerate() {
+						// First add the signature
+						map.insert(format!("event_{}_signature", idx), event.signature.clone());
+						if let Some(address) = &event.address {
+							map.insert(format!("event_{}_contract_address", idx), address.clone());
+						}
+						if let Some(log_index) = event.log_index {
+							map.insert(format!("event_{}_log_index", idx), log_index.to_string());
+						}
+						// Then add all arguments
+						if let Some(event_args) = &event.args {
+							for arg in event_args {
+								map.insert(
+									format!("event_{}_{}", idx, arg.name),
+									arg.value.clone(),
+								);
 							}
 						}
 					}
-					map
-				} else {
-					HashMap::new()
-				};
+				}
+				map
+			} else {
+				HashMap::new()
+			};
 
 			data.extend(matched_args);
-
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&evm_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					data,
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			add_context_variables(&mut data, evm_monitor_match.context.as_ref());
+			data
 		}
 		MonitorMatch::Stellar(stellar_monitor_match) => {
 			let transaction = stellar_monitor_match.transaction.clone();
@@ -161,6 +232,25 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				"monitor_name".to_string(),
 				stellar_monitor_match.monitor.name.clone(),
 			);
+			if let Some(severity) = stellar_monitor_match.monitor.severity {
+				data.insert("severity".to_string(), severity.to_string());
+			}
+			if let Some(alert) = &stellar_monitor_match.alert {
+				data.insert("alert_phase".to_string(), alert.phase.to_string());
+				data.insert("correlation_id".to_string(), alert.correlation_id.clone());
+			}
+			data.insert(
+				"block_number".to_string(),
+				stellar_monitor_match.ledger.sequence.to_string(),
+			);
+			if !transaction.status.is_empty() {
+				let status = match transaction.status.as_str() {
+					"SUCCESS" => "success".to_string(),
+					"FAILED" => "failed".to_string(),
+					status => status.to_lowercase(),
+				};
+				data.insert("transaction_status".to_string(), status);
+			}
 
 			let matched_args: HashMap<String, String> =
 				if let Some(args) = &stellar_monitor_match.matched_on_args {
@@ -204,23 +294,80 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				};
 
 			data.extend(matched_args);
+			add_context_variables(&mut data, stellar_monitor_match.context.as_ref());
+			data
+		}
+	}
+}
+
+/// Adds the network and block variables of a match, and the explorer links of its transaction
+/// and addresses
+///
+/// With an explorer URL, `transaction_link` links the transaction, `address_link` the contract
+/// of the match and `{variable}_link` every variable whose value is an address.
+fn add_context_variables(data: &mut HashMap<String, String>, context: Option<&MatchContext>) {
+	let Some(context) = context else {
+		return;
+	};
+	data.insert("network_slug".to_string(), context.network_slug.clone());
+	data.insert("network_name".to_string(), context.network_name.clone());
+	if let Some(chain_id) = context.chain_id {
+		data.insert("chain_id".to_string(), chain_id.to_string());
+	}
+	if let Some(block_timestamp) = context.block_timestamp {
+		data.insert("block_timestamp".to_string(), block_timestamp.to_string());
+	}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&stellar_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					data,
-					&matching_monitor,
-					trigger_scripts,
+	let Some(explorer_url) = &context.explorer_url else {
+		return;
+	};
+	let explorer_url = explorer_url.trim_end_matches('/');
+	let mut links: HashMap<String, String> = data
+		.iter()
+		.filter_map(|(name, value)| {
+			address_path(value).map(|path| {
+				(
+					format!("{}_link", name),
+					format!("{}/{}/{}", explorer_url, path, value),
 				)
-				.await;
-		}
+			})
+		})
+		.collect();
+	if let Some(link) = links.get("contract_address_link").cloned() {
+		links.insert("address_link".to_string(), link);
 	}
-	Ok(())
+	if let Some(hash) = data.get("transaction_hash") {
+		links.insert(
+			"transaction_link".to_string(),
+			format!("{}/tx/{}", explorer_url, hash),
+		);
+	}
+	for (name, link) in links {
+		data.entry(name).or_insert(link);
+	}
+}
+
+/// Returns the explorer path of an address, or None if the value is not an address
+///
+/// EVM addresses are linked under `address`, Stellar accounts under `account` and Stellar
+/// contracts under `contract`.
+fn address_path(value: &str) -> Option<&'static str> {
+	if value.len() == 42
+		&& value.starts_with("0x")
+		&& value[2..].chars().all(|c| c.is_ascii_hexdigit())
+	{
+		return Some("address");
+	}
+	if value.len() == 56
+		&& value
+			.chars()
+			.all(|c| c.is_ascii_uppercase() || ('2'..='7').contains(&c))
+	{
+		return match value.as_bytes()[0] {
+			b'G' => Some("account"),
+			b'C' => Some("contract"),
+			_ => None,
+		};
+	}
+	None
 }
@@ -19,8 +19,8 @@ use crate::{
 	models::{
 		AddressWithABI, BlockType, EVMMatchArguments, EVMMatchParamEntry, EVMMatchParamsMap,
 		EVMMonitorMatch, EVMReceiptLog, EVMTransaction, EVMTransactionReceipt, EventCondition,
-		FunctionCondition, MatchConditions, Monitor, MonitorMatch, Network, TransactionCondition,
-		TransactionStatus,
+		FunctionCondition, MatchConditions, MatchContext, Monitor, MonitorMatch, Network,
+		TransactionCondition, TransactionStatus,
 	},
 	services::{
 		blockchain::{BlockChainClient, EvmClientTrait},
@@ -29,10 +29,13 @@ use crate::{
 				are_same_address, are_same_signature, b256_to_string, format_token_value,
 				h160_to_string, h256_to_string, normalize_address,
 			},
+			explain::{
+				evaluate_expression, ClauseError, ClauseTrace, ConditionTrace, ConditionTraces,
+				EvaluationTrace, ExpressionTrace, MatchDecision,
+			},
 			BlockFilter, FilterError,
 		},
 	},
-	utils::split_expression,
 };
 
 /// Filter implementation for EVM-compatible blockchains
@@ -40,6 +43,18 @@ pub struct EVMBlockFilter<T> {
 	pub _client: PhantomData<T>,
 }
 
+/// Outcome of matching a monitor against a single transaction
+struct TransactionMatches {
+	tx_status: TransactionStatus,
+	involved_addresses: Vec<String>,
+	address_match: bool,
+	matched_events: Vec<EventCondition>,
+	matched_functions: Vec<FunctionCondition>,
+	matched_transactions: Vec<TransactionCondition>,
+	matched_on_args: EVMMatchArguments,
+	decision: MatchDecision,
+}
+
 impl<T> EVMBlockFilter<T> {
 	/// Finds transactions that match the monitor's conditions.
 	///
@@ -48,12 +63,14 @@ impl<T> EVMBlockFilter<T> {
 	/// * `transaction` - The transaction to check
 	/// * `monitor` - Monitor containing match conditions
 	/// * `matched_transactions` - Vector to store matching transactions
+	/// * `trace` - Collects the evaluation of every condition when explaining
 	pub fn find_matching_transaction(
 		&self,
 		tx_status: &TransactionStatus,
 		transaction: &EVMTransaction,
 		monitor: &Monitor,
 		matched_transactions: &mut Vec<TransactionCondition>,
+		mut trace: Option<&mut ConditionTraces>,
 	) {
 		if monitor.match_conditions.transactions.is_empty() {
 			// Match all transactions
@@ -70,48 +87,32 @@ impl<T> EVMBlockFilter<T> {
 					required_status => *required_status == *tx_status,
 				};
 
-				if status_matches {
-					if let Some(expr) = &condition.expression {
-						let tx_params = vec![
-							EVMMatchParamEntry {
-								name: "value".to_string(),
-								value: transaction.value.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "from".to_string(),
-								value: transaction.from.map_or("".to_string(), h160_to_string),
-								kind: "address".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "to".to_string(),
-								value: transaction.to.map_or("".to_string(), h160_to_string),
-								kind: "address".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "hash".to_string(),
-								value: b256_to_string(transaction.hash),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-						];
-
-						if self.evaluate_expression(expr, &Some(tx_params)) {
-							matched_transactions.push(TransactionCondition {
-								expression: Some(expr.to_string()),
-								status: *tx_status,
-							});
-							break;
-						}
-					} else {
-						// No expression but status matched
-						matched_transactions.push(TransactionCondition {
-							expression: None,
-							status: *tx_status,
-						});
+				let (matched, expression_trace) = match &condition.expression {
+					Some(expr) if status_matches => self.check_expression(
+						expr,
+						&Some(self.transaction_params(transaction)),
+						trace.is_some(),
+					),
+					// No expression but status matched
+					_ => (status_matches, None),
+				};
+
+				if let Some(trace) = trace.as_deref_mut() {
+					trace.transactions.push(ConditionTrace::new(
+						format!("{:?}", condition.status),
+						format!("{:?}", tx_status),
+						status_matches,
+						expression_trace,
+					));
+				}
+
+				if matched {
+					matched_transactions.push(TransactionCondition {
+						expression: condition.expression.clone(),
+						status: *tx_status,
+					});
+					// Keep evaluating the remaining conditions when explaining
+					if trace.is_none() {
 						break;
 					}
 				}
@@ -119,6 +120,51 @@ impl<T> EVMBlockFilter<T> {
 		}
 	}
 
+	/// Builds the parameters available to transaction condition expressions.
+	///
+	/// # Arguments
+	/// * `transaction` - The transaction to build parameters for
+	///
+	/// # Returns
+	/// The `value`, `from`, `to` and `hash` parameters of the transaction
+	pub fn transaction_params(&self, transaction: &EVMTransaction) -> Vec<EVMMatchParamEntry> {
+		vec![
+			EVMMatchParamEntry {
+				name: "value".to_string(),
+				value: transaction.value.to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "from".to_string(),
+				value: transaction.from.map_or("".to_string(), h160_to_string),
+				kind: "address".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "to".to_string(),
+				value: transaction.to.map_or("".to_string(), h160_to_string),
+				kind: "address".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "hash".to_string(),
+				value: b256_to_string(transaction.hash),
+				kind: "string".to_string(),
+				indexed: false,
+			},
+		]
+	}
+
+	/// Returns the execution status recorded in a transaction receipt.
+	pub fn transaction_status(&self, receipt: &EVMTransactionReceipt) -> TransactionStatus {
+		if receipt.status.map(|s| s.to::<u64>() == 1).unwrap_or(false) {
+			TransactionStatus::Success
+		} else {
+			TransactionStatus::Failure
+		}
+	}
+
 	/// Finds function calls in a transaction that match the monitor's conditions.
 	///
 	/// Decodes the transaction input data using the contract ABI and matches against
@@ -129,126 +175,147 @@ impl<T> EVMBlockFilter<T> {
 	/// * `monitor` - Monitor containing function match conditions
 	/// * `matched_functions` - Vector to store matching functions
 	/// * `matched_on_args` - Arguments from matched function calls
+	/// * `trace` - Collects the evaluation of every condition when explaining
 	pub fn find_matching_functions_for_transaction(
 		&self,
 		transaction: &EVMTransaction,
 		monitor: &Monitor,
 		matched_functions: &mut Vec<FunctionCondition>,
 		matched_on_args: &mut EVMMatchArguments,
+		mut trace: Option<&mut ConditionTraces>,
 	) {
-		if !monitor.match_conditions.functions.is_empty() {
-			// Try to decode the function call if there's input data
-			let input_data = &transaction.input;
-			// Find the matching monitored address for the transaction
-			if let Some(monitored_addr) = monitor.addresses.iter().find(|addr| {
+		if monitor.match_conditions.functions.is_empty() {
+			return;
+		}
+
+		// Try to decode the function call if there's input data
+		let input_data = &transaction.input;
+		// Find the matching monitored address for the transaction and create a contract object
+		// from its ABI
+		let contract = monitor
+			.addresses
+			.iter()
+			.find(|addr| {
 				transaction
 					.to
 					.is_some_and(|to| are_same_address(&addr.address, &h160_to_string(to)))
-			}) {
-				// Process the matching address's ABI
-				if let Some(abi) = &monitored_addr.abi {
-					// Create contract object from ABI
-					let contract = match Contract::load(abi.to_string().as_bytes()) {
-						Ok(c) => c,
-						Err(e) => {
-							FilterError::internal_error(
-								format!("Failed to parse ABI: {}", e),
-								None,
-								None,
-							);
-							return;
-						}
-					};
-
-					// Get the function selector (first 4 bytes of input data)
-					if input_data.0.len() >= 4 {
-						let selector = &input_data.0[..4];
-
-						// Try to find matching function in ABI
-						if let Some(function) = contract
-							.functions()
-							.find(|f| f.short_signature().as_slice() == selector)
-						{
-							let function_signature_with_params = format!(
-								"{}({})",
-								function.name,
-								function
-									.inputs
-									.iter()
-									.map(|p| p.kind.to_string())
-									.collect::<Vec<String>>()
-									.join(",")
-							);
-
-							// Check each function condition
-							for condition in &monitor.match_conditions.functions {
-								if are_same_signature(
-									&condition.signature,
-									&function_signature_with_params,
-								) {
-									let decoded = function
-										.decode_input(&input_data.0[4..])
-										.unwrap_or_else(|e| {
-											FilterError::internal_error(
-												format!("Failed to decode function input: {}", e),
-												None,
-												None,
-											);
-											vec![]
-										});
-
-									let params: Vec<EVMMatchParamEntry> = function
-										.inputs
-										.iter()
-										.zip(decoded.iter())
-										.map(|(input, value)| EVMMatchParamEntry {
-											name: input.name.clone(),
-											value: format_token_value(value),
-											kind: input.kind.to_string(),
-											indexed: false,
-										})
-										.collect();
-									if let Some(expr) = &condition.expression {
-										if self.evaluate_expression(expr, &Some(params.clone())) {
-											matched_functions.push(FunctionCondition {
-												signature: function_signature_with_params.clone(),
-												expression: Some(expr.to_string()),
-											});
-											if let Some(functions) = &mut matched_on_args.functions
-											{
-												functions.push(EVMMatchParamsMap {
-													signature: function_signature_with_params
-														.clone(),
-													args: Some(params.clone()),
-													hex_signature: Some(format!(
-														"0x{}",
-														hex::encode(function.short_signature())
-													)),
-												});
-											}
-											break;
-										}
-									} else {
-										// No expression, just match on function name
-										matched_functions.push(FunctionCondition {
-											signature: function_signature_with_params.clone(),
-											expression: None,
-										});
-										if let Some(functions) = &mut matched_on_args.functions {
-											functions.push(EVMMatchParamsMap {
-												signature: function_signature_with_params.clone(),
-												args: Some(params.clone()),
-												hex_signature: Some(hex::encode(
-													function.short_signature(),
-												)),
-											});
-										}
-										break;
-									}
-								}
-							}
-						}
-					}
+			})
+			.and_then(|monitored_addr| monitored_addr.abi.as_ref())
+			.and_then(|abi| match Contract::load(abi.to_string().as_bytes()) {
+				Ok(c) => Some(c),
+				Err(e) => {
+					FilterError::internal_error(format!("Failed to parse ABI: {}", e), None, None);
+					None
+				}
+			});
+
+		// Find the function matching the selector (first 4 bytes of input data)
+		let function = contract.as_ref().and_then(|contract| {
+			let selector = input_data.0.get(..4)?;
+			contract
+				.functions()
+				.find(|f| f.short_signature().as_slice() == selector)
+		});
+		let Some(function) = function else {
+			if let Some(trace) = trace {
+				for condition in &monitor.match_conditions.functions {
+					trace.functions.push(ConditionTrace::new(
+						condition.signature.clone(),
+						"no decodable call to a monitored address",
+						false,
+						None,
+					));
+				}
+			}
+			return;
+		};
+
+		let function_signature_with_params = format!(
+			"{}({})",
+			function.name,
+			function
+				.inputs
+				.iter()
+				.map(|p| p.kind.to_string())
+				.collect::<Vec<String>>()
+				.join(",")
+		);
+
+		// Check each function condition
+		for condition in &monitor.match_conditions.functions {
+			let signature_match =
+				are_same_signature(&condition.signature, &function_signature_with_params);
+			if !signature_match {
+				if let Some(trace) = trace.as_deref_mut() {
+					trace.functions.push(ConditionTrace::new(
+						condition.signature.clone(),
+						function_signature_with_params.clone(),
+						false,
+						None,
+					));
+				}
+				continue;
+			}
+
+			let decoded = function
+				.decode_input(&input_data.0[4..])
+				.unwrap_or_else(|e| {
+					FilterError::internal_error(
+						format!("Failed to decode function input: {}", e),
+						None,
+						None,
+					);
+					vec![]
+				});
+
+			let params: Vec<EVMMatchParamEntry> = function
+				.inputs
+				.iter()
+				.zip(decoded.iter())
+				.map(|(input, value)| EVMMatchParamEntry {
+					name: input.name.clone(),
+					value: format_token_value(value),
+					kind: input.kind.to_string(),
+					indexed: false,
+				})
+				.collect();
+
+			// Without an expression, match on function name alone
+			let (matched, expression_trace) = match &condition.expression {
+				Some(expr) => self.check_expression(expr, &Some(params.clone()), trace.is_some()),
+				None => (true, None),
+			};
+
+			if let Some(trace) = trace.as_deref_mut() {
+				trace.functions.push(ConditionTrace::new(
+					condition.signature.clone(),
+					function_signature_with_params.clone(),
+					true,
+					expression_trace,
+				));
+			}
+
+			if matched {
+				matched_functions.push(FunctionCondition {
+					signature: function_signature_with_params.clone(),
+					expression: condition.expression.clone(),
+				});
+				if let Some(functions) = &mut matched_on_args.functions {
+					let hex_signature = hex::encode(function.short_signature());
+					functions.push(EVMMatchParamsMap {
+						signature: function_signature_with_params.clone(),
+						args: Some(params),
+						hex_signature: Some(match condition.expression {
+							Some(_) => format!("0x{}", hex_signature),
+							None => hex_signature,
+						}),
+						address: None,
+						log_index: None,
+					});
+				}
+				// Keep evaluating the remaining conditions when explaining
+				if trace.is_none() {
+					break;
 				}
 			}
 		}
@@ -265,6 +332,7 @@ impl<T> EVMBlockFilter<T> {
 	/// * `matched_events` - Vector to store matching events
 	/// * `matched_on_args` - Arguments from matched events
 	/// * `involved_addresses` - Addresses involved in matched events
+	/// * `trace` - Collects the evaluation of every condition when explaining
 	pub async fn find_matching_events_for_transaction(
 		&self,
 		receipt: &EVMTransactionReceipt,
@@ -272,6 +340,7 @@ impl<T> EVMBlockFilter<T> {
 		matched_events: &mut Vec<EventCondition>,
 		matched_on_args: &mut EVMMatchArguments,
 		involved_addresses: &mut Vec<String>,
+		mut trace: Option<&mut ConditionTraces>,
 	) {
 		for log in &receipt.logs {
 			// Find the specific monitored address that matches the log address
@@ -289,56 +358,178 @@ impl<T> EVMBlockFilter<T> {
 			involved_addresses.push(h160_to_string(log.address));
 
 			// Process the matching address's ABI
-			if let Some(abi) = &monitored_addr.abi {
-				let decoded_log = self.decode_events(abi, log).await;
-
-				if let Some(event_condition) = decoded_log {
-					if monitor.match_conditions.events.is_empty() {
-						// Match all events
-						matched_events.push(EventCondition {
-							signature: event_condition.signature.clone(),
-							expression: None,
-						});
-						if let Some(events) = &mut matched_on_args.events {
-							events.push(event_condition);
-						}
-					} else {
-						// Check if this event matches any of the conditions
-						for condition in &monitor.match_conditions.events {
-							// Remove any whitespaces to ensure accurate matching
-							// For example: Transfer(address, address, uint256) ==
-							// Transfer(address,address,uint256)
-							if are_same_signature(&condition.signature, &event_condition.signature)
-							{
-								if condition.expression.is_none() {
-									matched_events.push(EventCondition {
-										signature: event_condition.signature.clone(),
-										expression: None,
-									});
-									if let Some(events) = &mut matched_on_args.events {
-										events.push(event_condition);
-									}
-									break;
-								} else {
-									// Evaluate the expression condition
-									if let Some(expr) = &condition.expression {
-										if self.evaluate_expression(expr, &event_condition.args) {
-											matched_events.push(EventCondition {
-												signature: event_condition.signature.clone(),
-												expression: Some(expr.to_string()),
-											});
-											if let Some(events) = &mut matched_on_args.events {
-												events.push(event_condition);
-											}
-											break;
-										}
-									}
-								}
-							}
-						}
+			let decoded_log = match &monitored_addr.abi {
+				Some(abi) => self.decode_events(abi, log).await,
+				None => None,
+			};
+
+			let Some(event_condition) = decoded_log else {
+				if let Some(trace) = trace.as_deref_mut() {
+					for condition in &monitor.match_conditions.events {
+						trace.events.push(ConditionTrace::new(
+							condition.signature.clone(),
+							format!(
+								"undecodable log from {} with topic {}",
+								h160_to_string(log.address),
+								log.topics
+									.first()
+									.map_or("none".to_string(), |t| b256_to_string(*t))
+							),
+							false,
+							None,
+						));
 					}
 				}
+				continue;
+			};
+
+			if monitor.match_conditions.events.is_empty() {
+				// Match all events
+				matched_events.push(EventCondition {
+					signature: event_condition.signature.clone(),
+					expression: None,
+				});
+				if let Some(events) = &mut matched_on_args.events {
+					events.push(event_condition);
+				}
+				continue;
 			}
+
+			// Check if this event matches any of the conditions
+			for condition in &monitor.match_conditions.events {
+				// Remove any whitespaces to ensure accurate matching
+				// For example: Transfer(address, address, uint256) ==
+				// Transfer(address,address,uint256)
+				let signature_match =
+					are_same_signature(&condition.signature, &event_condition.signature);
+				let (matched, expression_trace) = match &condition.expression {
+					Some(expr) if signature_match => {
+						self.check_expression(expr, &event_condition.args, trace.is_some())
+					}
+					_ => (signature_match, None),
+				};
+
+				if let Some(trace) = trace.as_deref_mut() {
+					trace.events.push(ConditionTrace::new(
+						condition.signature.clone(),
+						event_condition.signature.clone(),
+						signature_match,
+						expression_trace,
+					));
+				}
+
+				if matched {
+					matched_events.push(EventCondition {
+						signature: event_condition.signature.clone(),
+						expression: condition.expression.clone(),
+					});
+					if let Some(events) = &mut matched_on_args.events {
+						events.push(event_condition.clone());
+					}
+					// Keep evaluating the remaining conditions when explaining
+					if trace.is_none() {
+						break;
+					}
+				}
+			}
+		}
+	}
+
+	/// Matches a monitor against a single transaction.
+	///
+	/// Shared by [`BlockFilter::filter_block`] and [`Self::explain_transaction`], which passes a
+	/// trace collector to record every condition.
+	///
+	/// # Arguments
+	/// * `transaction` - The transaction to check
+	/// * `receipt` - Receipt of the transaction
+	/// * `monitor` - Monitor containing match conditions
+	/// * `trace` - Collects the evaluation of every condition when explaining
+	///
+	/// # Returns
+	/// Conditions and arguments that matched, and the resulting decision
+	async fn match_transaction(
+		&self,
+		transaction: &EVMTransaction,
+		receipt: &EVMTransactionReceipt,
+		monitor: &Monitor,
+		mut trace: Option<&mut ConditionTraces>,
+	) -> TransactionMatches {
+		let tx_status = self.transaction_status(receipt);
+		let mut matched_on_args = EVMMatchArguments {
+			events: Some(Vec::new()),
+			functions: Some(Vec::new()),
+		};
+
+		// Collect all involved addresses from receipt logs, transaction.to, and
+		// transaction.from
+		let mut involved_addresses = Vec::new();
+		// Add transaction addresses
+		if let Some(from) = transaction.from {
+			involved_addresses.push(h160_to_string(from));
+		}
+		if let Some(to) = transaction.to {
+			involved_addresses.push(h160_to_string(to));
+		}
+		let mut matched_events = Vec::<EventCondition>::new();
+		let mut matched_transactions = Vec::<TransactionCondition>::new();
+		let mut matched_functions = Vec::<FunctionCondition>::new();
+
+		// Check transaction match conditions
+		self.find_matching_transaction(
+			&tx_status,
+			transaction,
+			monitor,
+			&mut matched_transactions,
+			trace.as_deref_mut(),
+		);
+
+		// Check for event match conditions
+		self.find_matching_events_for_transaction(
+			receipt,
+			monitor,
+			&mut matched_events,
+			&mut matched_on_args,
+			&mut involved_addresses,
+			trace.as_deref_mut(),
+		)
+		.await;
+
+		// Check function match conditions
+		self.find_matching_functions_for_transaction(
+			transaction,
+			monitor,
+			&mut matched_functions,
+			&mut matched_on_args,
+			trace,
+		);
+
+		// Remove duplicates
+		involved_addresses.sort_unstable();
+		involved_addresses.dedup();
+
+		let address_match = monitor.addresses.iter().any(|addr| {
+			involved_addresses
+				.iter()
+				.any(|a| normalize_address(a) == normalize_address(&addr.address))
+		});
+
+		let decision = MatchDecision::evaluate(
+			&monitor.match_conditions,
+			!matched_events.is_empty(),
+			!matched_functions.is_empty(),
+			!matched_transactions.is_empty(),
+		);
+
+		TransactionMatches {
+			tx_status,
+			involved_addresses,
+			address_match,
+			matched_events,
+			matched_functions,
+			matched_transactions,
+			matched_on_args,
+			decision,
 		}
 	}
 
@@ -355,93 +546,121 @@ impl<T> EVMBlockFilter<T> {
 		expression: &str,
 		args: &Option<Vec<EVMMatchParamEntry>>,
 	) -> bool {
-		let Some(args) = args else {
-			return false;
-		};
+		evaluate_expression(
+			expression,
+			args.as_deref(),
+			None,
+			|args, param, op, value, _| self.evaluate_condition(args, param, op, value, None),
+		)
+	}
 
-		// First split by OR to get the highest level conditions
-		let or_conditions: Vec<&str> = expression.split(" OR ").collect();
-
-		// For OR logic, any condition being true makes the whole expression true
-		for or_condition in or_conditions {
-			// Split each OR condition by AND
-			let and_conditions: Vec<&str> = or_condition.trim().split(" AND ").collect();
-
-			// All AND conditions must be true
-			let and_result = and_conditions.iter().all(|condition| {
-				// Remove any surrounding parentheses and trim
-				let clean_condition = condition.trim().trim_matches(|c| c == '(' || c == ')');
-
-				// Split into parts while preserving quoted strings
-				let parts = if let Some((left, operator, right)) = split_expression(clean_condition)
-				{
-					vec![left, operator, right]
-				} else {
-					tracing::warn!("Invalid expression format: {}", clean_condition);
-					return false;
-				};
+	/// Evaluates a match expression and records how each clause was resolved.
+	///
+	/// Unlike [`Self::evaluate_expression`], every clause is evaluated so the trace is complete.
+	///
+	/// # Arguments
+	/// * `expression` - The expression to evaluate
+	/// * `args` - Optional parameters to use in evaluation
+	///
+	/// # Returns
+	/// Trace of the evaluation, including the overall result
+	pub fn explain_expression(
+		&self,
+		expression: &str,
+		args: &Option<Vec<EVMMatchParamEntry>>,
+	) -> ExpressionTrace {
+		let mut trace = ExpressionTrace::new(expression);
+		evaluate_expression(
+			expression,
+			args.as_deref(),
+			Some(&mut trace),
+			|args, param, op, value, clause| {
+				self.evaluate_condition(args, param, op, value, clause)
+			},
+		);
+		trace
+	}
 
-				if parts.len() != 3 {
-					tracing::warn!("Invalid expression format: {}", clean_condition);
-					return false;
-				}
+	/// Evaluates a condition expression, tracing it when explaining.
+	fn check_expression(
+		&self,
+		expression: &str,
+		args: &Option<Vec<EVMMatchParamEntry>>,
+		explain: bool,
+	) -> (bool, Option<ExpressionTrace>) {
+		if explain {
+			let trace = self.explain_expression(expression, args);
+			(trace.result, Some(trace))
+		} else {
+			(self.evaluate_expression(expression, args), None)
+		}
+	}
 
-				let [param_name, operator, value] = [parts[0], parts[1], parts[2]];
+	/// Evaluates a single `parameter operator value` condition against provided parameters.
+	///
+	/// The resolved parameter is recorded in `clause` when explaining.
+	fn evaluate_condition(
+		&self,
+		args: &[EVMMatchParamEntry],
+		param_name: &str,
+		operator: &str,
+		value: &str,
+		clause: Option<&mut ClauseTrace>,
+	) -> Result<bool, ClauseError> {
+		// Find the parameter in args
+		let Some(param) = args.iter().find(|p| p.name == param_name) else {
+			return Err(ClauseError::warn(format!(
+				"Parameter {} not found in event args",
+				param_name
+			)));
+		};
+		if let Some(clause) = clause {
+			clause.left = Some(param.value.clone());
+			clause.kind = Some(param.kind.clone());
+		}
 
-				// Find the parameter in args
-				let Some(param) = args.iter().find(|p| p.name == param_name) else {
-					tracing::warn!("Parameter {} not found in event args", param_name);
-					return false;
+		// Evaluate single condition
+		match param.kind.as_str() {
+			"uint256" | "uint" => {
+				let Ok(param_value) = u128::from_str(&param.value) else {
+					return Err(ClauseError::warn(format!(
+						"Failed to parse parameter value: {}",
+						param.value
+					)));
+				};
+				let Ok(compare_value) = u128::from_str(value) else {
+					return Err(ClauseError::warn(format!(
+						"Failed to parse comparison value: {}",
+						value
+					)));
 				};
 
-				// Evaluate single condition
-				match param.kind.as_str() {
-					"uint256" | "uint" => {
-						let Ok(param_value) = u128::from_str(&param.value) else {
-							tracing::warn!("Failed to parse parameter value: {}", param.value);
-							return false;
-						};
-						let Ok(compare_value) = u128::from_str(value) else {
-							tracing::warn!("Failed to parse comparison value: {}", value);
-							return false;
-						};
-
-						match operator {
-							">" => param_value > compare_value,
-							">=" => param_value >= compare_value,
-							"<" => param_value < compare_value,
-							"<=" => param_value <= compare_value,
-							"==" => param_value == compare_value,
-							"!=" => param_value != compare_value,
-							_ => {
-								tracing::warn!("Unsupported operator: {}", operator);
-								false
-							}
-						}
-					}
-					"address" => match operator {
-						"==" => are_same_address(&param.value, value),
-						"!=" => !are_same_address(&param.value, value),
-						_ => {
-							tracing::warn!("Unsupported operator for address type: {}", operator);
-							false
-						}
-					},
-					_ => {
-						tracing::warn!("Unsupported parameter type: {}", param.kind);
-						false
-					}
+				match operator {
+					">" => Ok(param_value > compare_value),
+					">=" => Ok(param_value >= compare_value),
+					"<" => Ok(param_value < compare_value),
+					"<=" => Ok(param_value <= compare_value),
+					"==" => Ok(param_value == compare_value),
+					"!=" => Ok(param_value != compare_value),
+					_ => Err(ClauseError::warn(format!(
+						"Unsupported operator: {}",
+						operator
+					))),
 				}
-			});
-
-			// If any OR condition is true, return true
-			if and_result {
-				return true;
 			}
+			"address" => match operator {
+				"==" => Ok(are_same_address(&param.value, value)),
+				"!=" => Ok(!are_same_address(&param.value, value)),
+				_ => Err(ClauseError::warn(format!(
+					"Unsupported operator for address type: {}",
+					operator
+				))),
+			},
+			_ => Err(ClauseError::warn(format!(
+				"Unsupported parameter type: {}",
+				param.kind
+			))),
 		}
-
-		// No conditions were true
-		false
 	}
 
 	/// Decodes event logs using the provided ABI.
@@ -507,6 +726,8 @@ impl<T> EVMBlockFilter<T> {
 									.collect(),
 							),
 							hex_signature: Some(h256_to_string(event.signature())),
+							address: Some(h160_to_string(log.address)),
+							log_index: log.log_index.and_then(|index| u64::try_from(index).ok()),
 						};
 						event_params_map
 					})
@@ -514,6 +735,54 @@ impl<T> EVMBlockFilter<T> {
 
 		decoded_log
 	}
+
+	/// Explains how a monitor is evaluated against a transaction.
+	///
+	/// Runs the same matching as [`BlockFilter::filter_block`] and records every comparison made
+	/// along the way.
+	///
+	/// # Arguments
+	/// * `block_number` - Block containing the transaction
+	/// * `network_slug` - Network the transaction belongs to
+	/// * `transaction` - The transaction to evaluate
+	/// * `receipt` - Receipt of the transaction
+	/// * `monitor` - Monitor containing match conditions
+	///
+	/// # Returns
+	/// Trace of the evaluation
+	pub async fn explain_transaction(
+		&self,
+		block_number: u64,
+		network_slug: &str,
+		transaction: &EVMTransaction,
+		receipt: &EVMTransactionReceipt,
+		monitor: &Monitor,
+	) -> EvaluationTrace {
+		let mut traces = ConditionTraces::default();
+		let matches = self
+			.match_transaction(transaction, receipt, monitor, Some(&mut traces))
+			.await;
+
+		EvaluationTrace {
+			monitor_name: monitor.name.clone(),
+			network_slug: network_slug.to_string(),
+			block_number,
+			transaction_hash: b256_to_string(transaction.hash),
+			transaction_status: matches.tx_status,
+			monitored_addresses: monitor
+				.addresses
+				.iter()
+				.map(|a| a.address.clone())
+				.collect(),
+			involved_addresses: matches.involved_addresses,
+			address_match: Some(matches.address_match),
+			transactions: traces.transactions,
+			functions: traces.functions,
+			events: traces.events,
+			matched: matches.address_match && matches.decision.should_match,
+			decision: matches.decision,
+		}
+	}
 }
 
 #[async_trait]
@@ -529,11 +798,11 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 	///
 	/// # Returns
 	/// Vector of matches found in the block
-	#[instrument(skip_all, fields(network = %_network.slug))]
+	#[instrument(skip_all, fields(network = %network.slug))]
 	async fn filter_block(
 		&self,
 		client: &T,
-		_network: &Network,
+		network: &Network,
 		block: &BlockType,
 		monitors: &[Monitor],
 	) -> Result<Vec<MonitorMatch>, FilterError> {
@@ -596,11 +865,6 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 
 		for monitor in monitors {
 			tracing::debug!("Processing monitor: {:?}", monitor.name);
-			let monitored_addresses: Vec<String> = monitor
-				.addresses
-				.iter()
-				.map(|a| a.address.clone())
-				.collect();
 			// Check each receipt and transaction for matches
 			tracing::debug!("Processing {} receipt(s)", receipts.len());
 			for receipt in &receipts {
@@ -610,101 +874,25 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 					.find(|tx| tx.hash == receipt.transaction_hash);
 
 				if let Some(transaction) = matching_transaction {
-					// Reset matched_on_args for each transaction
-					let mut matched_on_args = EVMMatchArguments {
-						events: Some(Vec::new()),
-						functions: Some(Vec::new()),
-					};
-
-					// Get transaction status from receipt
-					let tx_status = if receipt.status.map(|s| s.to::<u64>() == 1).unwrap_or(false) {
-						TransactionStatus::Success
-					} else {
-						TransactionStatus::Failure
-					};
-
-					// Collect all involved addresses from receipt logs, transaction.to, and
-					// transaction.from
-					let mut involved_addresses = Vec::new();
-					// Add transaction addresses
-					if let Some(from) = transaction.from {
-						involved_addresses.push(h160_to_string(from));
-					}
-					if let Some(to) = transaction.to {
-						involved_addresses.push(h160_to_string(to));
-					}
-					let mut matched_events = Vec::<EventCondition>::new();
-					let mut matched_transactions = Vec::<TransactionCondition>::new();
-					let mut matched_functions = Vec::<FunctionCondition>::new();
-
-					// Check transaction match conditions
-					self.find_matching_transaction(
-						&tx_status,
-						transaction,
-						monitor,
-						&mut matched_transactions,
-					);
-
-					// Check for event match conditions
-					self.find_matching_events_for_transaction(
-						receipt,
-						monitor,
-						&mut matched_events,
-						&mut matched_on_args,
-						&mut involved_addresses,
-					)
-					.await;
-
-					// Check function match conditions
-					self.find_matching_functions_for_transaction(
-						transaction,
-						monitor,
-						&mut matched_functions,
-						&mut matched_on_args,
-					);
-
-					// Remove duplicates
-					involved_addresses.sort_unstable();
-					involved_addresses.dedup();
-
-					let has_address_match = monitored_addresses.iter().any(|addr| {
-						involved_addresses
-							.iter()
-							.map(|a| normalize_address(a))
-							.collect::<Vec<String>>()
-							.contains(&normalize_address(addr))
-					});
+					let TransactionMatches {
+						address_match,
+						matched_events,
+						matched_functions,
+						matched_transactions,
+						matched_on_args,
+						decision,
+						..
+					} = self
+						.match_transaction(transaction, receipt, monitor, None)
+						.await;
 
 					// Only proceed if we have a matching address
-					if has_address_match {
-						let monitor_conditions = &monitor.match_conditions;
-						let has_event_match =
-							!monitor_conditions.events.is_empty() && !matched_events.is_empty();
-						let has_function_match = !monitor_conditions.functions.is_empty()
-							&& !matched_functions.is_empty();
-						let has_transaction_match = !monitor_conditions.transactions.is_empty()
-							&& !matched_transactions.is_empty();
-
-						let should_match = match (
-							monitor_conditions.events.is_empty(),
-							monitor_conditions.functions.is_empty(),
-							monitor_conditions.transactions.is_empty(),
-						) {
-							// Case 1: No conditions defined, match everything
-							(true, true, true) => true,
-
-							// Case 2: Only transaction conditions defined
-							(true, true, false) => has_transaction_match,
-
-							// Case 3: No transaction conditions, match based on events/functions
-							(_, _, true) => has_event_match || has_function_match,
-
-							// Case 4: Transaction conditions exist, they must be satisfied along
-							// with events/functions
-							_ => (has_event_match || has_function_match) && has_transaction_match,
-						};
+					if address_match {
+						let has_event_match = decision.event_match;
+						let has_function_match = decision.function_match;
+						let has_transaction_match = decision.transaction_match;
 
-						if should_match {
+						if decision.should_match {
 							matching_results.push(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
 								monitor: Monitor {
 									// Omit ABI from monitor since we do not need it here
@@ -749,6 +937,11 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 										None
 									},
 								}),
+								alert: None,
+								context: Some(MatchContext::new(
+									network,
+									u64::try_from(evm_block.timestamp).ok(),
+								)),
 							})));
 						}
 					}
@@ -758,6 +951,70 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 
 		Ok(matching_results)
 	}
+
+	/// Explains how monitors are evaluated against the transactions of a block.
+	///
+	/// # Arguments
+	/// * `client` - Blockchain client for additional data fetching
+	/// * `network` - Network of the blockchain
+	/// * `block` - The block to process
+	/// * `monitors` - Monitors to explain
+	/// * `transaction_hash` - Only explain this transaction, if set
+	///
+	/// # Returns
+	/// One trace per monitor and transaction
+	async fn explain_block(
+		&self,
+		client: &T,
+		network: &Network,
+		block: &BlockType,
+		monitors: &[Monitor],
+		transaction_hash: Option<&str>,
+	) -> Result<Vec<EvaluationTrace>, FilterError> {
+		let BlockType::EVM(evm_block) = block else {
+			return Err(FilterError::block_type_mismatch(
+				"Expected EVM block",
+				None,
+				None,
+			));
+		};
+		let block_number = evm_block.number.unwrap_or(U64::from(0)).to::<u64>();
+
+		let mut traces = Vec::new();
+		for transaction in &evm_block.transactions {
+			let tx_hash = b256_to_string(transaction.hash);
+			// Hashes are compared like addresses, ignoring the 0x prefix and case
+			if transaction_hash
+				.is_some_and(|hash| normalize_address(hash) != normalize_address(&tx_hash))
+			{
+				continue;
+			}
+			let receipt = client
+				.get_transaction_receipt(tx_hash.clone())
+				.await
+				.map_err(|e| {
+					FilterError::network_error(
+						format!("Failed to get transaction receipt for {}", tx_hash),
+						Some(e.into()),
+						None,
+					)
+				})?;
+			for monitor in monitors {
+				traces.push(
+					self.explain_transaction(
+						block_number,
+						&network.slug,
+						transaction,
+						&receipt,
+						monitor,
+					)
+					.await,
+				);
+			}
+		}
+
+		Ok(traces)
+	}
 }
 
 #[cfg(test)]
@@ -971,6 +1228,7 @@ mod tests {
 			&create_test_transaction(U256::ZERO, None, None, vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 1);
@@ -999,6 +1257,7 @@ mod tests {
 			&create_test_transaction(U256::ZERO, None, None, vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 1);
@@ -1011,6 +1270,7 @@ mod tests {
 			&create_test_transaction(U256::ZERO, None, None, vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 0);
@@ -1036,6 +1296,7 @@ mod tests {
 			&create_test_transaction(U256::from(150), None, None, vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 1);
@@ -1048,6 +1309,7 @@ mod tests {
 			&create_test_transaction(U256::from(50), None, None, vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 0);
@@ -1075,6 +1337,7 @@ mod tests {
 			&create_test_transaction(U256::ZERO, None, Some(test_address), vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 1);
@@ -1091,6 +1354,7 @@ mod tests {
 			),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 0);
@@ -1118,6 +1382,7 @@ mod tests {
 			&create_test_transaction(U256::ZERO, Some(test_address), None, vec![]),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 1);
@@ -1134,6 +1399,7 @@ mod tests {
 			),
 			&monitor,
 			&mut matched,
+			None,
 		);
 
 		assert_eq!(matched.len(), 0);
@@ -1211,6 +1477,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_on_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 1);
@@ -1290,6 +1557,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_on_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 1);
@@ -1324,6 +1592,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_on_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 0);
@@ -1396,6 +1665,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_on_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 0);
@@ -1428,6 +1698,10 @@ mod tests {
 			paused: false,
 			trigger_conditions: vec![],
 			triggers: vec![],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		};
 
 		// Test with invalid input data (less than 4 bytes)
@@ -1443,6 +1717,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_on_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 0);
@@ -1493,6 +1768,7 @@ mod tests {
 				&mut matched_events,
 				&mut matched_on_args,
 				&mut involved_addresses,
+				None,
 			)
 			.await;
 
@@ -1550,6 +1826,7 @@ mod tests {
 				&mut matched_events,
 				&mut matched_on_args,
 				&mut involved_addresses,
+				None,
 			)
 			.await;
 
@@ -1580,6 +1857,7 @@ mod tests {
 				&mut matched_events,
 				&mut matched_on_args,
 				&mut involved_addresses,
+				None,
 			)
 			.await;
 
@@ -1626,6 +1904,7 @@ mod tests {
 				&mut matched_events,
 				&mut matched_on_args,
 				&mut involved_addresses,
+				None,
 			)
 			.await;
 
@@ -1773,6 +2052,76 @@ mod tests {
 		assert!(!filter.evaluate_expression("> 1000", &args));
 	}
 
+	#[test]
+	fn test_explain_expression_records_clause_values() {
+		let filter = create_test_filter();
+		let args = Some(vec![
+			create_test_param("amount", "1000", "uint256"),
+			create_test_param(
+				"to",
+				"0x0000000000000000000000000000000000001234",
+				"address",
+			),
+		]);
+
+		let trace =
+			filter.explain_expression("amount > 5000 OR (amount > 500 AND missing == 1)", &args);
+
+		assert!(!trace.result);
+		assert_eq!(trace.clauses.len(), 3);
+		assert_eq!(trace.clauses[0].left, Some("1000".to_string()));
+		assert_eq!(trace.clauses[0].right, "5000");
+		assert_eq!(trace.clauses[0].kind, Some("uint256".to_string()));
+		assert!(!trace.clauses[0].result);
+		assert!(trace.clauses[1].result);
+		assert_eq!(trace.clauses[1].group, 1);
+		assert!(trace.clauses[2].left.is_none());
+		assert!(trace.clauses[2].error.is_some());
+
+		let trace = filter.explain_expression("amount > 500", &None);
+		assert!(!trace.result);
+		assert!(trace.error.is_some());
+	}
+
+	#[tokio::test]
+	async fn test_explain_transaction_event_condition() {
+		let filter = create_test_filter();
+		let monitor = create_test_monitor(
+			vec![EventCondition {
+				signature: "Transfer(address,address,uint256)".to_string(),
+				expression: Some("value > 5000".to_string()),
+			}],
+			vec![],
+			vec![],
+			vec![create_test_address(
+				"0x0000000000000000000000000000000000004321",
+				Some(create_test_abi("event")),
+			)],
+		);
+		let contract_address =
+			Address::from_str("0x0000000000000000000000000000000000004321").unwrap();
+		let receipt = create_test_transfer_receipt(
+			contract_address,
+			Address::from_str("0x0000000000000000000000000000000000001234").unwrap(),
+			Address::from_str("0x0000000000000000000000000000000000005678").unwrap(),
+			1000,
+		);
+		let transaction = create_test_transaction(U256::ZERO, None, None, vec![]);
+
+		let trace = filter
+			.explain_transaction(1, "ethereum_mainnet", &transaction, &receipt, &monitor)
+			.await;
+
+		assert_eq!(trace.address_match, Some(true));
+		assert_eq!(trace.events.len(), 1);
+		assert!(trace.events[0].signature_match);
+		let expression = trace.events[0].expression.as_ref().unwrap();
+		assert_eq!(expression.clauses[0].left, Some("1000".to_string()));
+		assert!(!expression.result);
+		assert!(!trace.decision.event_match);
+		assert!(!trace.matched);
+	}
+
 	//////////////////////////////////////////////////////////////////////////////
 	// Test cases for decode_events method:
 	//////////////////////////////////////////////////////////////////////////////
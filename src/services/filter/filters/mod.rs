@@ -22,7 +22,10 @@ pub use stellar::filter::StellarBlockFilter;
 
 use crate::{
 	models::{BlockType, Monitor, MonitorMatch, Network},
-	services::{blockchain::BlockFilterFactory, filter::error::FilterError},
+	services::{
+		blockchain::BlockFilterFactory,
+		filter::{error::FilterError, explain::EvaluationTrace},
+	},
 };
 
 /// Trait for filtering blockchain data
@@ -39,6 +42,16 @@ pub trait BlockFilter {
 		block: &BlockType,
 		monitors: &[Monitor],
 	) -> Result<Vec<MonitorMatch>, FilterError>;
+
+	/// Explains how each monitor is evaluated against the transactions of a block
+	async fn explain_block(
+		&self,
+		client: &Self::Client,
+		network: &Network,
+		block: &BlockType,
+		monitors: &[Monitor],
+		transaction_hash: Option<&str>,
+	) -> Result<Vec<EvaluationTrace>, FilterError>;
 }
 
 /// Service for filtering blockchain data
@@ -69,4 +82,18 @@ impl FilterService {
 		let filter = T::filter();
 		filter.filter_block(client, network, block, monitors).await
 	}
+
+	pub async fn explain_block<T: BlockFilterFactory<T>>(
+		&self,
+		client: &T,
+		network: &Network,
+		block: &BlockType,
+		monitors: &[Monitor],
+		transaction_hash: Option<&str>,
+	) -> Result<Vec<EvaluationTrace>, FilterError> {
+		let filter = T::filter();
+		filter
+			.explain_block(client, network, block, monitors, transaction_hash)
+			.await
+	}
 }
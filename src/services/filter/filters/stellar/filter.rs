@@ -6,7 +6,7 @@
 //! - Compare different types of parameter values
 //! - Evaluate complex matching expressions
 
-use std::marker::PhantomData;
+use std::{borrow::Cow, marker::PhantomData};
 
 use async_trait::async_trait;
 use base64::Engine;
@@ -16,14 +16,18 @@ use tracing::instrument;
 
 use crate::{
 	models::{
-		BlockType, EventCondition, FunctionCondition, MatchConditions, Monitor, MonitorMatch,
-		Network, StellarEvent, StellarMatchArguments, StellarMatchParamEntry,
+		BlockType, EventCondition, FunctionCondition, MatchConditions, MatchContext, Monitor,
+		MonitorMatch, Network, StellarEvent, StellarMatchArguments, StellarMatchParamEntry,
 		StellarMatchParamsMap, StellarMonitorMatch, StellarTransaction, TransactionCondition,
 		TransactionStatus,
 	},
 	services::{
 		blockchain::{BlockChainClient, StellarClientTrait},
 		filter::{
+			explain::{
+				evaluate_expression, ClauseError, ClauseTrace, ConditionTrace, ConditionTraces,
+				EvaluationTrace, ExpressionTrace, MatchDecision,
+			},
 			stellar_helpers::{
 				are_same_signature, compare_json_values, compare_json_values_vs_string,
 				compare_strings, get_kind_from_value, get_nested_value, normalize_address,
@@ -32,7 +36,6 @@ use crate::{
 			BlockFilter, FilterError,
 		},
 	},
-	utils::split_expression,
 };
 
 /// Represents a mapping between a Stellar event and its transaction hash
@@ -42,11 +45,28 @@ pub struct EventMap {
 	pub tx_hash: String,
 }
 
+/// Payment or contract invocation operation of a transaction
+struct TxOperation {
+	_operation_type: String,
+	sender: String,
+	receiver: String,
+	value: Option<String>,
+}
+
 /// Implementation of the block filter for Stellar blockchain
 pub struct StellarBlockFilter<T> {
 	pub _client: PhantomData<T>,
 }
 
+/// Outcome of matching a monitor against a single transaction
+struct TransactionMatches {
+	matched_events: Vec<EventCondition>,
+	matched_functions: Vec<FunctionCondition>,
+	matched_transactions: Vec<TransactionCondition>,
+	matched_on_args: StellarMatchArguments,
+	decision: MatchDecision,
+}
+
 impl<T> StellarBlockFilter<T> {
 	/// Finds matching transactions based on monitor conditions
 	///
@@ -54,26 +74,75 @@ impl<T> StellarBlockFilter<T> {
 	/// * `transaction` - The Stellar transaction to check
 	/// * `monitor` - The monitor containing match conditions
 	/// * `matched_transactions` - Vector to store matching transactions
+	/// * `trace` - Collects the evaluation of every condition when explaining
 	pub fn find_matching_transaction(
 		&self,
 		transaction: &StellarTransaction,
 		monitor: &Monitor,
 		matched_transactions: &mut Vec<TransactionCondition>,
+		mut trace: Option<&mut ConditionTraces>,
 	) {
-		let tx_status: TransactionStatus = match transaction.status.as_str() {
+		let tx_status = self.transaction_status(transaction);
+
+		// Check transaction match conditions
+		if monitor.match_conditions.transactions.is_empty() {
+			// Match all transactions
+			matched_transactions.push(TransactionCondition {
+				expression: None,
+				status: TransactionStatus::Any,
+			});
+		} else {
+			// Check each transaction condition
+			for condition in &monitor.match_conditions.transactions {
+				// First check if status matches (if specified)
+				let status_matches = match &condition.status {
+					TransactionStatus::Any => true,
+					required_status => *required_status == tx_status,
+				};
+
+				let (matched, expression_trace) = match &condition.expression {
+					Some(expr) if status_matches => {
+						self.check_transaction_expression(expr, transaction, trace.is_some())
+					}
+					// No expression but status matched
+					_ => (status_matches, None),
+				};
+
+				if let Some(trace) = trace.as_deref_mut() {
+					trace.transactions.push(ConditionTrace::new(
+						format!("{:?}", condition.status),
+						format!("{:?}", tx_status),
+						status_matches,
+						expression_trace,
+					));
+				}
+
+				if matched {
+					matched_transactions.push(TransactionCondition {
+						expression: condition.expression.clone(),
+						status: tx_status,
+					});
+					// A status-only match settles the transaction, unless explaining
+					if condition.expression.is_none() && trace.is_none() {
+						break;
+					}
+				}
+			}
+		}
+	}
+
+	/// Returns the execution status of a transaction
+	pub fn transaction_status(&self, transaction: &StellarTransaction) -> TransactionStatus {
+		match transaction.status.as_str() {
 			"SUCCESS" => TransactionStatus::Success,
 			"FAILED" => TransactionStatus::Failure,
 			"NOT FOUND" => TransactionStatus::Failure,
 			_ => TransactionStatus::Any,
-		};
-
-		struct TxOperation {
-			_operation_type: String,
-			sender: String,
-			receiver: String,
-			value: Option<String>,
 		}
+	}
 
+	/// Extracts the payment and contract invocation operations of a transaction
+	fn transaction_operations(&self, transaction: &StellarTransaction) -> Vec<TxOperation> {
 		let mut tx_operations: Vec<TxOperation> = vec![];
 
 		if let Some(decoded) = transaction.decoded() {
@@ -107,102 +176,78 @@ impl<T> StellarBlockFilter<T> {
 			}
 		}
 
-		// Check transaction match conditions
-		if monitor.match_conditions.transactions.is_empty() {
-			// Match all transactions
-			matched_transactions.push(TransactionCondition {
-				expression: None,
-				status: TransactionStatus::Any,
-			});
-		} else {
-			// Check each transaction condition
-			for condition in &monitor.match_conditions.transactions {
-				// First check if status matches (if specified)
-				let status_matches = match &condition.status {
-					TransactionStatus::Any => true,
-					required_status => *required_status == tx_status,
-				};
+		tx_operations
+	}
 
-				if status_matches {
-					if let Some(expr) = &condition.expression {
-						// Create base transaction parameters outside operation loop
-						let base_params = vec![
-							StellarMatchParamEntry {
-								name: "hash".to_string(),
-								value: transaction.hash().clone(),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-							StellarMatchParamEntry {
-								name: "ledger".to_string(),
-								value: transaction.ledger.to_string(),
-								kind: "i64".to_string(),
-								indexed: false,
-							},
-							// Default value for value
-							StellarMatchParamEntry {
-								name: "value".to_string(),
-								value: "0".to_string(),
-								kind: "i64".to_string(),
-								indexed: false,
-							},
-						];
-
-						// If we have operations, check each one
-						if !tx_operations.is_empty() {
-							for operation in &tx_operations {
-								let mut tx_params = base_params.clone();
-								// Remove default value for value
-								tx_params.remove(tx_params.len() - 1);
-								tx_params.extend(vec![
-									StellarMatchParamEntry {
-										name: "value".to_string(),
-										value: operation.value.clone().unwrap_or("0".to_string()),
-										kind: "i64".to_string(),
-										indexed: false,
-									},
-									StellarMatchParamEntry {
-										name: "from".to_string(),
-										value: operation.sender.clone(),
-										kind: "address".to_string(),
-										indexed: false,
-									},
-									StellarMatchParamEntry {
-										name: "to".to_string(),
-										value: operation.receiver.clone(),
-										kind: "address".to_string(),
-										indexed: false,
-									},
-								]);
-
-								if self.evaluate_expression(expr, &Some(tx_params)) {
-									matched_transactions.push(TransactionCondition {
-										expression: Some(expr.clone()),
-										status: tx_status,
-									});
-									break;
-								}
-							}
-						} else {
-							// Even with no operations, still evaluate base parameters
-							if self.evaluate_expression(expr, &Some(base_params)) {
-								matched_transactions.push(TransactionCondition {
-									expression: Some(expr.clone()),
-									status: tx_status,
-								});
-							}
-						}
-					} else {
-						// No expression but status matched
-						matched_transactions.push(TransactionCondition {
-							expression: None,
-							status: tx_status,
-						});
-						break;
-					}
-				}
-			}
+	/// Builds the parameters available to transaction condition expressions
+	///
+	/// # Arguments
+	/// * `transaction` - The transaction to build parameters for
+	///
+	/// # Returns
+	/// One parameter set per operation, or a single set for the transaction alone if it has no
+	/// payment or contract invocation operations
+	pub fn transaction_params(
+		&self,
+		transaction: &StellarTransaction,
+	) -> Vec<Vec<StellarMatchParamEntry>> {
+		// Create base transaction parameters outside operation loop
+		let base_params = vec![
+			StellarMatchParamEntry {
+				name: "hash".to_string(),
+				value: transaction.hash().clone(),
+				kind: "string".to_string(),
+				indexed: false,
+			},
+			StellarMatchParamEntry {
+				name: "ledger".to_string(),
+				value: transaction.ledger.to_string(),
+				kind: "i64".to_string(),
+				indexed: false,
+			},
+			// Default value for value
+			StellarMatchParamEntry {
+				name: "value".to_string(),
+				value: "0".to_string(),
+				kind: "i64".to_string(),
+				indexed: false,
+			},
+		];
+
+		let tx_operations = self.transaction_operations(transaction);
+		if tx_operations.is_empty() {
+			return vec![base_params];
 		}
+
+		tx_operations
+			.iter()
+			.map(|operation| {
+				let mut tx_params = base_params.clone();
+				// Remove default value for value
+				tx_params.remove(tx_params.len() - 1);
+				tx_params.extend(vec![
+					StellarMatchParamEntry {
+						name: "value".to_string(),
+						value: operation.value.clone().unwrap_or("0".to_string()),
+						kind: "i64".to_string(),
+						indexed: false,
+					},
+					StellarMatchParamEntry {
+						name: "from".to_string(),
+						value: operation.sender.clone(),
+						kind: "address".to_string(),
+						indexed: false,
+					},
+					StellarMatchParamEntry {
+						name: "to".to_string(),
+						value: operation.receiver.clone(),
+						kind: "address".to_string(),
+						indexed: false,
+					},
+				]);
+				tx_params
+			})
+			.collect()
 	}
 
 	/// Finds matching functions within a transaction
@@ -213,6 +258,7 @@ impl<T> StellarBlockFilter<T> {
 	/// * `monitor` - The monitor containing match conditions
 	/// * `matched_functions` - Vector to store matching functions
 	/// * `matched_on_args` - Arguments that matched the conditions
+	/// * `trace` - Collects the evaluation of every condition when explaining
 	pub fn find_matching_functions_for_transaction(
 		&self,
 		monitored_addresses: &[String],
@@ -220,71 +266,79 @@ impl<T> StellarBlockFilter<T> {
 		monitor: &Monitor,
 		matched_functions: &mut Vec<FunctionCondition>,
 		matched_on_args: &mut StellarMatchArguments,
+		mut trace: Option<&mut ConditionTraces>,
 	) {
-		if let Some(decoded) = transaction.decoded() {
-			if let Some(TransactionEnvelope::Tx(tx)) = &decoded.envelope {
-				for operation in tx.tx.operations.iter() {
-					if let OperationBody::InvokeHostFunction(invoke_host_function) = &operation.body
-					{
-						let parsed_operation = process_invoke_host_function(invoke_host_function);
+		let Some(decoded) = transaction.decoded() else {
+			return;
+		};
+		let Some(TransactionEnvelope::Tx(tx)) = &decoded.envelope else {
+			return;
+		};
 
-						// Skip if contract address doesn't match
-						if !monitored_addresses
-							.contains(&normalize_address(&parsed_operation.contract_address))
-						{
-							continue;
-						}
+		for operation in tx.tx.operations.iter() {
+			let OperationBody::InvokeHostFunction(invoke_host_function) = &operation.body else {
+				continue;
+			};
+			let parsed_operation = process_invoke_host_function(invoke_host_function);
 
-						// Convert parsed operation arguments into param entries
-						let param_entries = self
-							.convert_arguments_to_match_param_entry(&parsed_operation.arguments);
-
-						if monitor.match_conditions.functions.is_empty() {
-							// Match on all functions
-							matched_functions.push(FunctionCondition {
-								signature: parsed_operation.function_signature.clone(),
-								expression: None,
-							});
-						} else {
-							// Check function conditions
-							for condition in &monitor.match_conditions.functions {
-								// Check if function signature matches
-								if are_same_signature(
-									&condition.signature,
-									&parsed_operation.function_signature,
-								) {
-									// Evaluate expression if it exists
-									if let Some(expr) = &condition.expression {
-										if self
-											.evaluate_expression(expr, &Some(param_entries.clone()))
-										{
-											matched_functions.push(FunctionCondition {
-												signature: parsed_operation
-													.function_signature
-													.clone(),
-												expression: Some(expr.clone()),
-											});
-											if let Some(functions) = &mut matched_on_args.functions
-											{
-												functions.push(StellarMatchParamsMap {
-													signature: parsed_operation
-														.function_signature
-														.clone(),
-													args: Some(param_entries.clone()),
-												});
-											}
-											break;
-										}
-									} else {
-										// If no expression, match on function name alone
-										matched_functions.push(FunctionCondition {
-											signature: parsed_operation.function_signature.clone(),
-											expression: None,
-										});
-									}
-								}
-							}
-						}
+			// Skip if contract address doesn't match
+			if !monitored_addresses.contains(&normalize_address(&parsed_operation.contract_address))
+			{
+				continue;
+			}
+
+			// Convert parsed operation arguments into param entries
+			let param_entries =
+				Some(self.convert_arguments_to_match_param_entry(&parsed_operation.arguments));
+
+			if monitor.match_conditions.functions.is_empty() {
+				// Match on all functions
+				matched_functions.push(FunctionCondition {
+					signature: parsed_operation.function_signature.clone(),
+					expression: None,
+				});
+				continue;
+			}
+
+			// Check function conditions
+			for condition in &monitor.match_conditions.functions {
+				// Check if function signature matches
+				let signature_match =
+					are_same_signature(&condition.signature, &parsed_operation.function_signature);
+				// If no expression, match on function name alone
+				let (matched, expression_trace) = match &condition.expression {
+					Some(expr) if signature_match => {
+						self.check_expression(expr, &param_entries, trace.is_some())
+					}
+					_ => (signature_match, None),
+				};
+
+				if let Some(trace) = trace.as_deref_mut() {
+					trace.functions.push(ConditionTrace::new(
+						condition.signature.clone(),
+						parsed_operation.function_signature.clone(),
+						signature_match,
+						expression_trace,
+					));
+				}
+
+				if !matched {
+					continue;
+				}
+				matched_functions.push(FunctionCondition {
+					signature: parsed_operation.function_signature.clone(),
+					expression: condition.expression.clone(),
+				});
+				if condition.expression.is_some() {
+					if let Some(functions) = &mut matched_on_args.functions {
+						functions.push(StellarMatchParamsMap {
+							signature: parsed_operation.function_signature.clone(),
+							args: param_entries.clone(),
+						});
+					}
+					// Keep evaluating the remaining conditions when explaining
+					if trace.is_none() {
+						break;
 					}
 				}
 			}
@@ -299,6 +353,7 @@ impl<T> StellarBlockFilter<T> {
 	/// * `monitor` - The monitor containing match conditions
 	/// * `matched_events` - Vector to store matching events
 	/// * `matched_on_args` - Arguments that matched the conditions
+	/// * `trace` - Collects the evaluation of every condition when explaining
 	pub fn find_matching_events_for_transaction(
 		&self,
 		events: &[EventMap],
@@ -306,15 +361,15 @@ impl<T> StellarBlockFilter<T> {
 		monitor: &Monitor,
 		matched_events: &mut Vec<EventCondition>,
 		matched_on_args: &mut StellarMatchArguments,
+		mut trace: Option<&mut ConditionTraces>,
 	) {
 		let events_for_transaction = events
 			.iter()
 			.filter(|event| event.tx_hash == *transaction.hash())
-			.map(|event| event.event.clone())
-			.collect::<Vec<_>>();
+			.map(|event| &event.event);
 
 		// Check event conditions
-		for event in &events_for_transaction {
+		for event in events_for_transaction {
 			if monitor.match_conditions.events.is_empty() {
 				// Match all events
 				matched_events.push(EventCondition {
@@ -324,40 +379,117 @@ impl<T> StellarBlockFilter<T> {
 				if let Some(events) = &mut matched_on_args.events {
 					events.push(event.clone());
 				}
-			} else {
-				// Find all matching conditions for this event
-				let matching_conditions =
-					monitor.match_conditions.events.iter().filter(|condition| {
-						are_same_signature(&condition.signature, &event.signature)
-					});
+				continue;
+			}
 
-				for condition in matching_conditions {
-					match &condition.expression {
-						Some(expr) => {
-							if let Some(args) = &event.args {
-								if self.evaluate_expression(expr, &Some(args.clone())) {
-									matched_events.push(EventCondition {
-										signature: event.signature.clone(),
-										expression: Some(expr.clone()),
-									});
-									if let Some(events) = &mut matched_on_args.events {
-										events.push(event.clone());
-									}
-								}
-							}
-						}
-						None => {
-							matched_events.push(EventCondition {
-								signature: event.signature.clone(),
-								expression: None,
-							});
-						}
+			// Find all matching conditions for this event
+			for condition in &monitor.match_conditions.events {
+				let signature_match = are_same_signature(&condition.signature, &event.signature);
+				let (matched, expression_trace) = match &condition.expression {
+					Some(expr) if signature_match => {
+						self.check_expression(expr, &event.args, trace.is_some())
+					}
+					_ => (signature_match, None),
+				};
+
+				if let Some(trace) = trace.as_deref_mut() {
+					trace.events.push(ConditionTrace::new(
+						condition.signature.clone(),
+						event.signature.clone(),
+						signature_match,
+						expression_trace,
+					));
+				}
+
+				if !matched {
+					continue;
+				}
+				matched_events.push(EventCondition {
+					signature: event.signature.clone(),
+					expression: condition.expression.clone(),
+				});
+				if condition.expression.is_some() {
+					if let Some(events) = &mut matched_on_args.events {
+						events.push(event.clone());
 					}
 				}
 			}
 		}
 	}
 
+	/// Matches a monitor against a single transaction
+	///
+	/// Shared by [`BlockFilter::filter_block`] and [`Self::explain_transaction`], which passes a
+	/// trace collector to record every condition.
+	///
+	/// # Arguments
+	/// * `transaction` - The transaction to check
+	/// * `decoded_events` - Events of the ledger decoded for the monitored addresses
+	/// * `monitored_addresses` - Normalized addresses of the monitor
+	/// * `monitor` - The monitor containing match conditions
+	/// * `trace` - Collects the evaluation of every condition when explaining
+	///
+	/// # Returns
+	/// Conditions and arguments that matched, and the resulting decision
+	fn match_transaction(
+		&self,
+		transaction: &StellarTransaction,
+		decoded_events: &[EventMap],
+		monitored_addresses: &[String],
+		monitor: &Monitor,
+		mut trace: Option<&mut ConditionTraces>,
+	) -> TransactionMatches {
+		let mut matched_transactions = Vec::<TransactionCondition>::new();
+		let mut matched_functions = Vec::<FunctionCondition>::new();
+		let mut matched_events = Vec::<EventCondition>::new();
+		let mut matched_on_args = StellarMatchArguments {
+			events: Some(Vec::new()),
+			functions: Some(Vec::new()),
+		};
+
+		self.find_matching_transaction(
+			transaction,
+			monitor,
+			&mut matched_transactions,
+			trace.as_deref_mut(),
+		);
+
+		// Decoded events already account for monitored addresses, so no need to pass in
+		// monitored_addresses
+		self.find_matching_events_for_transaction(
+			decoded_events,
+			transaction,
+			monitor,
+			&mut matched_events,
+			&mut matched_on_args,
+			trace.as_deref_mut(),
+		);
+
+		self.find_matching_functions_for_transaction(
+			monitored_addresses,
+			transaction,
+			monitor,
+			&mut matched_functions,
+			&mut matched_on_args,
+			trace,
+		);
+
+		let decision = MatchDecision::evaluate(
+			&monitor.match_conditions,
+			!matched_events.is_empty(),
+			!matched_functions.is_empty(),
+			!matched_transactions.is_empty(),
+		);
+
+		TransactionMatches {
+			matched_events,
+			matched_functions,
+			matched_transactions,
+			matched_on_args,
+			decision,
+		}
+	}
+
 	/// Decodes Stellar events into a more processable format
 	///
 	/// # Arguments
@@ -826,124 +958,181 @@ impl<T> StellarBlockFilter<T> {
 		expression: &str,
 		args: &Option<Vec<StellarMatchParamEntry>>,
 	) -> bool {
-		let Some(args) = args else {
-			return false;
-		};
-
-		// Split by OR to get highest level conditions
-		let or_conditions: Vec<&str> = expression.split(" OR ").collect();
-
-		// For OR logic, any condition being true makes the whole expression true
-		for or_condition in or_conditions {
-			// Split each OR condition by AND
-			let and_conditions: Vec<&str> = or_condition.trim().split(" AND ").collect();
-
-			// All AND conditions must be true
-			let and_result = and_conditions.iter().all(|condition| {
-				// Remove surrounding parentheses and trim
-				let clean_condition = condition.trim().trim_matches(|c| c == '(' || c == ')');
+		evaluate_expression(
+			expression,
+			args.as_deref(),
+			None,
+			|args, param, op, value, _| self.evaluate_condition(args, param, op, value, None),
+		)
+	}
 
-				// Split into parts while preserving quoted strings
-				let parts = if let Some((left, operator, right)) = split_expression(clean_condition)
-				{
-					vec![left, operator, right]
-				} else {
-					tracing::warn!("Invalid expression format: {}", clean_condition);
-					return false;
-				};
+	/// Evaluates a matching expression and records how each clause was resolved
+	///
+	/// Unlike [`Self::evaluate_expression`], every clause is evaluated so the trace is complete.
+	///
+	/// # Arguments
+	/// * `expression` - The expression to evaluate (supports AND/OR operations)
+	/// * `args` - The arguments to evaluate against
+	///
+	/// # Returns
+	/// Trace of the evaluation, including the overall result
+	pub fn explain_expression(
+		&self,
+		expression: &str,
+		args: &Option<Vec<StellarMatchParamEntry>>,
+	) -> ExpressionTrace {
+		let mut trace = ExpressionTrace::new(expression);
+		evaluate_expression(
+			expression,
+			args.as_deref(),
+			Some(&mut trace),
+			|args, param, op, value, clause| {
+				self.evaluate_condition(args, param, op, value, clause)
+			},
+		);
+		trace
+	}
 
-				if parts.len() != 3 {
-					tracing::warn!("Invalid expression format: {}", clean_condition);
-					return false;
-				}
+	/// Evaluates a condition expression, tracing it when explaining
+	fn check_expression(
+		&self,
+		expression: &str,
+		args: &Option<Vec<StellarMatchParamEntry>>,
+		explain: bool,
+	) -> (bool, Option<ExpressionTrace>) {
+		if explain {
+			let trace = self.explain_expression(expression, args);
+			(trace.result, Some(trace))
+		} else {
+			(self.evaluate_expression(expression, args), None)
+		}
+	}
 
-				let [param_expr, operator, value] = [parts[0], parts[1], parts[2]];
+	/// Evaluates a transaction condition expression against each operation of a transaction
+	///
+	/// When explaining, the trace of the first operation satisfying the expression is returned,
+	/// or the trace of the first operation if none does.
+	fn check_transaction_expression(
+		&self,
+		expression: &str,
+		transaction: &StellarTransaction,
+		explain: bool,
+	) -> (bool, Option<ExpressionTrace>) {
+		let mut first_trace = None;
+		// Parameters are checked per operation, or for the transaction alone if it has no
+		// operations
+		for tx_params in self.transaction_params(transaction) {
+			let (matched, expression_trace) =
+				self.check_expression(expression, &Some(tx_params), explain);
+			if matched {
+				return (true, expression_trace);
+			}
+			first_trace = first_trace.or(expression_trace);
+		}
+		(false, first_trace)
+	}
 
-				// Find the parameter and its type
-				if param_expr.contains('[') {
-					// Array indexing: arguments[0][0]
-					let indices: Vec<usize> = param_expr
-						.split('[')
-						.skip(1)
-						.filter_map(|s| s.trim_end_matches(']').parse::<usize>().ok())
-						.collect();
+	/// Evaluates a single `parameter operator value` condition against provided arguments
+	///
+	/// The resolved parameter is recorded in `clause` when explaining.
+	fn evaluate_condition(
+		&self,
+		args: &[StellarMatchParamEntry],
+		param_expr: &str,
+		operator: &str,
+		value: &str,
+		clause: Option<&mut ClauseTrace>,
+	) -> Result<bool, ClauseError> {
+		// Find the parameter and its type
+		let (kind, left) = if param_expr.contains('[') {
+			// Array indexing: arguments[0][0]
+			let indices: Vec<usize> = param_expr
+				.split('[')
+				.skip(1)
+				.filter_map(|s| s.trim_end_matches(']').parse::<usize>().ok())
+				.collect();
+
+			if indices.len() != 2 || indices[0] >= args.len() {
+				return Err(ClauseError::debug(format!(
+					"Invalid array indices: {:?}",
+					indices
+				)));
+			}
 
-					if indices.len() != 2 || indices[0] >= args.len() {
-						tracing::debug!("Invalid array indices: {:?}", indices);
-						return false;
-					}
+			let param = &args[indices[0]];
+			let array_values: Vec<&str> = param.value.split(',').collect();
+			if indices[1] >= array_values.len() {
+				return Err(ClauseError::debug(format!(
+					"Array index out of bounds: {}",
+					indices[1]
+				)));
+			}
 
-					let param = &args[indices[0]];
-					let array_values: Vec<&str> = param.value.split(',').collect();
-					if indices[1] >= array_values.len() {
-						tracing::debug!("Array index out of bounds: {}", indices[1]);
-						return false;
-					}
+			(
+				Cow::Borrowed(param.kind.as_str()),
+				Cow::Borrowed(array_values[indices[1]].trim()),
+			)
+		} else if param_expr.contains('.') {
+			// Map access: map.key
+			let parts: Vec<&str> = param_expr.split('.').collect();
+			if parts.len() != 2 {
+				return Err(ClauseError::debug(format!(
+					"Invalid map access format: {}",
+					param_expr
+				)));
+			}
 
-					self.compare_values(
-						&param.kind,
-						array_values[indices[1]].trim(),
-						operator,
-						value,
-					)
-				} else if param_expr.contains('.') {
-					// Map access: map.key
-					let parts: Vec<&str> = param_expr.split('.').collect();
-					if parts.len() != 2 {
-						tracing::debug!("Invalid map access format: {}", param_expr);
-						return false;
-					}
+			let [map_name, key] = [parts[0], parts[1]];
 
-					let [map_name, key] = [parts[0], parts[1]];
+			let Some(param) = args.iter().find(|p| p.name == map_name) else {
+				return Err(ClauseError::debug(format!("Map {} not found", map_name)));
+			};
 
-					let Some(param) = args.iter().find(|p| p.name == map_name) else {
-						tracing::debug!("Map {} not found", map_name);
-						return false;
-					};
+			let Ok(mut map_value) = serde_json::from_str::<serde_json::Value>(&param.value) else {
+				return Err(ClauseError::debug(format!(
+					"Failed to parse map: {}",
+					param.value
+				)));
+			};
 
-					let Ok(mut map_value) = serde_json::from_str::<serde_json::Value>(&param.value)
-					else {
-						tracing::debug!("Failed to parse map: {}", param.value);
-						return false;
-					};
+			// Unescape the keys in the map_value
+			if let serde_json::Value::Object(ref mut map) = map_value {
+				let unescaped_map: serde_json::Map<String, serde_json::Value> = map
+					.iter()
+					.map(|(k, v)| (k.trim_matches('"').to_string(), v.clone()))
+					.collect();
+				*map = unescaped_map;
+			}
 
-					// Unescape the keys in the map_value
-					if let serde_json::Value::Object(ref mut map) = map_value {
-						let unescaped_map: serde_json::Map<String, serde_json::Value> = map
-							.iter()
-							.map(|(k, v)| (k.trim_matches('"').to_string(), v.clone()))
-							.collect();
-						*map = unescaped_map;
-					}
+			let Some(key_value) = map_value.get(key) else {
+				return Err(ClauseError::debug(format!("Key {} not found in map", key)));
+			};
 
-					let Some(key_value) = map_value.get(key) else {
-						tracing::debug!("Key {} not found in map", key);
-						return false;
-					};
-
-					self.compare_values(
-						&get_kind_from_value(key_value),
-						&key_value.to_string(),
-						operator,
-						value,
-					)
-				} else {
-					// Regular parameter
-					let Some(param) = args.iter().find(|p| p.name == param_expr) else {
-						tracing::warn!("Parameter {} not found", param_expr);
-						return false;
-					};
+			(
+				Cow::Owned(get_kind_from_value(key_value)),
+				Cow::Owned(key_value.to_string()),
+			)
+		} else {
+			// Regular parameter
+			let Some(param) = args.iter().find(|p| p.name == param_expr) else {
+				return Err(ClauseError::warn(format!(
+					"Parameter {} not found",
+					param_expr
+				)));
+			};
 
-					self.compare_values(&param.kind, &param.value, operator, value)
-				}
-			});
+			(
+				Cow::Borrowed(param.kind.as_str()),
+				Cow::Borrowed(param.value.as_str()),
+			)
+		};
 
-			if and_result {
-				return true;
-			}
+		let result = self.compare_values(&kind, &left, operator, value);
+		if let Some(clause) = clause {
+			clause.kind = Some(kind.into_owned());
+			clause.left = Some(left.into_owned());
 		}
-
-		false
+		Ok(result)
 	}
 
 	/// Converts Stellar function arguments into match parameter entries
@@ -1009,6 +1198,68 @@ impl<T> StellarBlockFilter<T> {
 
 		params
 	}
+
+	/// Explains how a monitor is evaluated against a transaction
+	///
+	/// Runs the same matching as [`BlockFilter::filter_block`] and records every comparison made
+	/// along the way.
+	///
+	/// # Arguments
+	/// * `block_number` - Ledger containing the transaction
+	/// * `network_slug` - Network the transaction belongs to
+	/// * `transaction` - The transaction to evaluate
+	/// * `decoded_events` - Events of the ledger decoded for the monitored addresses
+	/// * `monitor` - The monitor containing match conditions
+	///
+	/// # Returns
+	/// Trace of the evaluation
+	pub fn explain_transaction(
+		&self,
+		block_number: u64,
+		network_slug: &str,
+		transaction: &StellarTransaction,
+		decoded_events: &[EventMap],
+		monitor: &Monitor,
+	) -> EvaluationTrace {
+		let monitored_addresses = monitor
+			.addresses
+			.iter()
+			.map(|addr| normalize_address(&addr.address))
+			.collect::<Vec<String>>();
+		let mut traces = ConditionTraces::default();
+		let matches = self.match_transaction(
+			transaction,
+			decoded_events,
+			&monitored_addresses,
+			monitor,
+			Some(&mut traces),
+		);
+
+		let mut involved_addresses = self
+			.transaction_operations(transaction)
+			.into_iter()
+			.flat_map(|operation| [operation.sender, operation.receiver])
+			.collect::<Vec<String>>();
+		involved_addresses.sort_unstable();
+		involved_addresses.dedup();
+
+		EvaluationTrace {
+			monitor_name: monitor.name.clone(),
+			network_slug: network_slug.to_string(),
+			block_number,
+			transaction_hash: transaction.hash().clone(),
+			transaction_status: self.transaction_status(transaction),
+			monitored_addresses,
+			involved_addresses,
+			// Addresses are checked per function call and event rather than per transaction
+			address_match: None,
+			transactions: traces.transactions,
+			functions: traces.functions,
+			events: traces.events,
+			matched: matches.decision.should_match,
+			decision: matches.decision,
+		}
+	}
 }
 
 #[async_trait]
@@ -1024,11 +1275,11 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 	///
 	/// # Returns
 	/// Result containing vector of matching monitors or a filter error
-	#[instrument(skip_all, fields(network = %_network.slug))]
+	#[instrument(skip_all, fields(network = %network.slug))]
 	async fn filter_block(
 		&self,
 		client: &Self::Client,
-		_network: &Network,
+		network: &Network,
 		block: &BlockType,
 		monitors: &[Monitor],
 	) -> Result<Vec<MonitorMatch>, FilterError> {
@@ -1094,64 +1345,26 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 
 			// Then process transactions for this monitor
 			for transaction in &transactions {
-				let mut matched_transactions = Vec::<TransactionCondition>::new();
-				let mut matched_functions = Vec::<FunctionCondition>::new();
-				let mut matched_events = Vec::<EventCondition>::new();
-				let mut matched_on_args = StellarMatchArguments {
-					events: Some(Vec::new()),
-					functions: Some(Vec::new()),
-				};
-
 				tracing::debug!("Processing transaction: {:?}", transaction.hash());
 
-				self.find_matching_transaction(transaction, monitor, &mut matched_transactions);
-
-				// Decoded events already account for monitored addresses, so no need to pass in
-				// monitored_addresses
-				self.find_matching_events_for_transaction(
-					&decoded_events,
+				let TransactionMatches {
+					matched_events,
+					matched_functions,
+					matched_transactions,
+					matched_on_args,
+					decision,
+				} = self.match_transaction(
 					transaction,
-					monitor,
-					&mut matched_events,
-					&mut matched_on_args,
-				);
-
-				self.find_matching_functions_for_transaction(
+					&decoded_events,
 					&monitored_addresses,
-					transaction,
 					monitor,
-					&mut matched_functions,
-					&mut matched_on_args,
+					None,
 				);
+				let has_event_match = decision.event_match;
+				let has_function_match = decision.function_match;
+				let has_transaction_match = decision.transaction_match;
 
-				let monitor_conditions = &monitor.match_conditions;
-				let has_event_match =
-					!monitor_conditions.events.is_empty() && !matched_events.is_empty();
-				let has_function_match =
-					!monitor_conditions.functions.is_empty() && !matched_functions.is_empty();
-				let has_transaction_match =
-					!monitor_conditions.transactions.is_empty() && !matched_transactions.is_empty();
-
-				let should_match = match (
-					monitor_conditions.events.is_empty(),
-					monitor_conditions.functions.is_empty(),
-					monitor_conditions.transactions.is_empty(),
-				) {
-					// Case 1: No conditions defined, match everything
-					(true, true, true) => true,
-
-					// Case 2: Only transaction conditions defined
-					(true, true, false) => has_transaction_match,
-
-					// Case 3: No transaction conditions, match based on events/functions
-					(_, _, true) => has_event_match || has_function_match,
-
-					// Case 4: Transaction conditions exist, they must be satisfied along with
-					// events/functions
-					_ => (has_event_match || has_function_match) && has_transaction_match,
-				};
-
-				if should_match {
+				if decision.should_match {
 					matching_results.push(MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 						monitor: monitor.clone(),
 						// The conversion to StellarTransaction triggers decoding of the transaction
@@ -1187,12 +1400,94 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 								None
 							},
 						}),
+						alert: None,
+						context: Some(MatchContext::new(
+							network,
+							stellar_block.ledger_close_time.parse().ok(),
+						)),
 					})));
 				}
 			}
 		}
 		Ok(matching_results)
 	}
+
+	/// Explains how monitors are evaluated against the transactions of a ledger
+	///
+	/// # Arguments
+	/// * `client` - The blockchain client to use
+	/// * `network` - The network being monitored
+	/// * `block` - The block to explain
+	/// * `monitors` - Monitors to explain
+	/// * `transaction_hash` - Only explain this transaction, if set
+	///
+	/// # Returns
+	/// One trace per monitor and transaction
+	async fn explain_block(
+		&self,
+		client: &Self::Client,
+		network: &Network,
+		block: &BlockType,
+		monitors: &[Monitor],
+		transaction_hash: Option<&str>,
+	) -> Result<Vec<EvaluationTrace>, FilterError> {
+		let BlockType::Stellar(stellar_block) = block else {
+			return Err(FilterError::block_type_mismatch(
+				"Expected Stellar block".to_string(),
+				None,
+				None,
+			));
+		};
+
+		let transactions = client
+			.get_transactions(stellar_block.sequence, None)
+			.await
+			.map_err(|e| {
+				FilterError::network_error(
+					format!(
+						"Failed to get transactions for block {}",
+						stellar_block.sequence
+					),
+					Some(e.into()),
+					None,
+				)
+			})?;
+		let events = client
+			.get_events(stellar_block.sequence, None)
+			.await
+			.map_err(|e| {
+				FilterError::network_error(
+					format!("Failed to get events for block {}", stellar_block.sequence),
+					Some(e.into()),
+					None,
+				)
+			})?;
+
+		let mut traces = Vec::new();
+		for monitor in monitors {
+			let monitored_addresses = monitor
+				.addresses
+				.iter()
+				.map(|addr| normalize_address(&addr.address))
+				.collect::<Vec<String>>();
+			let decoded_events = self.decode_events(&events, &monitored_addresses).await;
+
+			for transaction in transactions
+				.iter()
+				.filter(|tx| transaction_hash.is_none_or(|hash| tx.hash() == hash))
+			{
+				traces.push(self.explain_transaction(
+					stellar_block.sequence as u64,
+					&network.slug,
+					transaction,
+					&decoded_events,
+					monitor,
+				));
+			}
+		}
+
+		Ok(traces)
+	}
 }
 
 #[cfg(test)]
@@ -1237,6 +1532,10 @@ mod tests {
 			paused: false,
 			trigger_conditions: vec![],
 			triggers: vec![],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		}
 	}
 
@@ -1461,7 +1760,7 @@ mod tests {
 			None,
 		);
 
-		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions, None);
 
 		assert_eq!(matched_transactions.len(), 1);
 		assert_eq!(matched_transactions[0].status, TransactionStatus::Any);
@@ -1492,7 +1791,7 @@ mod tests {
 			vec![],
 		);
 
-		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions, None);
 
 		assert_eq!(matched_transactions.len(), 1);
 		assert_eq!(matched_transactions[0].status, TransactionStatus::Success);
@@ -1523,7 +1822,7 @@ mod tests {
 			vec![],
 		);
 
-		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions, None);
 
 		assert_eq!(matched_transactions.len(), 1);
 		assert_eq!(matched_transactions[0].status, TransactionStatus::Success);
@@ -1557,7 +1856,7 @@ mod tests {
 			vec![],
 		);
 
-		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions, None);
 
 		assert_eq!(matched_transactions.len(), 0);
 	}
@@ -1586,7 +1885,7 @@ mod tests {
 			vec![],
 		);
 
-		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions, None);
 
 		assert_eq!(matched_transactions.len(), 0);
 	}
@@ -1619,7 +1918,7 @@ mod tests {
 			vec![],
 		);
 
-		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&transaction, &monitor, &mut matched_transactions, None);
 
 		assert_eq!(matched_transactions.len(), 1);
 		assert_eq!(matched_transactions[0].status, TransactionStatus::Success);
@@ -1674,6 +1973,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 1);
@@ -1727,6 +2027,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 1);
@@ -1778,6 +2079,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_args,
+			None,
 		);
 
 		// Now this assertion is correct since 123 is not less than 50
@@ -1829,6 +2131,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 0);
@@ -1884,6 +2187,7 @@ mod tests {
 			&monitor,
 			&mut matched_functions,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_functions.len(), 1);
@@ -1935,6 +2239,7 @@ mod tests {
 			&monitor,
 			&mut matched_events,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_events.len(), 1);
@@ -1983,6 +2288,7 @@ mod tests {
 			&monitor,
 			&mut matched_events,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_events.len(), 1);
@@ -2030,6 +2336,7 @@ mod tests {
 			&monitor,
 			&mut matched_events,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_events.len(), 1);
@@ -2077,6 +2384,7 @@ mod tests {
 			&monitor,
 			&mut matched_events,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_events.len(), 0);
@@ -2123,6 +2431,7 @@ mod tests {
 			&monitor,
 			&mut matched_events,
 			&mut matched_args,
+			None,
 		);
 
 		assert_eq!(matched_events.len(), 0);
@@ -3150,6 +3459,39 @@ mod tests {
 		assert!(!filter.evaluate_expression("arguments[0] == 10", &args));
 	}
 
+	#[test]
+	fn test_explain_expression_resolves_nested_values() {
+		let filter = create_test_filter();
+		let args = Some(vec![
+			StellarMatchParamEntry {
+				name: "0".to_string(),
+				value: "10,20,30".to_string(),
+				kind: "Vec".to_string(),
+				indexed: false,
+			},
+			StellarMatchParamEntry {
+				name: "1".to_string(),
+				value: "{\"amount\":500}".to_string(),
+				kind: "Map".to_string(),
+				indexed: false,
+			},
+		]);
+
+		let trace = filter.explain_expression("arguments[0][1] == 20 AND 1.amount > 1000", &args);
+
+		assert!(!trace.result);
+		assert_eq!(trace.clauses.len(), 2);
+		assert_eq!(trace.clauses[0].left, Some("20".to_string()));
+		assert_eq!(trace.clauses[0].kind, Some("Vec".to_string()));
+		assert!(trace.clauses[0].result);
+		assert_eq!(trace.clauses[1].left, Some("500".to_string()));
+		assert_eq!(trace.clauses[1].right, "1000");
+		assert!(!trace.clauses[1].result);
+
+		let trace = filter.explain_expression("arguments[0][5] == 10", &args);
+		assert!(trace.clauses[0].error.is_some());
+	}
+
 	#[test]
 	fn test_evaluate_expression_logical_operators() {
 		let filter = create_test_filter();
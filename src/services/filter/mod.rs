@@ -3,14 +3,17 @@
 //! Implements the core filtering logic for monitoring blockchain activity:
 //! - Block filtering for different chain types
 //! - Match handling and processing
+//! - Evaluation traces explaining match decisions
 //! - Chain-specific helper functions
 
 mod error;
+mod explain;
 mod filter_match;
 mod filters;
 
 pub use error::FilterError;
-pub use filter_match::handle_match;
+pub use explain::{ClauseTrace, ConditionTrace, EvaluationTrace, ExpressionTrace, MatchDecision};
+pub use filter_match::{build_match_variables, handle_match};
 
 pub use filters::{
 	evm::helpers as evm_helpers, stellar::helpers as stellar_helpers, BlockFilter, EVMBlockFilter,
@@ -5,10 +5,14 @@
 //! - `blockwatcher`: Block monitoring and processing
 //! - `filter`: Transaction and event filtering logic
 //! - `notification`: Alert and notification handling
+//! - `report`: Match history and scheduled summary reports
+//! - `stream`: Live stream of monitor matches
 //! - `trigger`: Trigger evaluation and execution
 
 pub mod blockchain;
 pub mod blockwatcher;
 pub mod filter;
 pub mod notification;
+pub mod report;
+pub mod stream;
 pub mod trigger;
@@ -2,17 +2,30 @@
 //!
 //! Provides functionality to send formatted messages to Discord channels
 //! via incoming webhooks, supporting message templates with variable substitution.
+//! Messages can be laid out with an embed template, colored by the severity of the monitor, and
+//! fall back to text when the rendered embed exceeds the limits of Discord.
 
 use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat};
 use reqwest::Client;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
 
 use crate::{
 	models::TriggerTypeConfig,
-	services::notification::{NotificationError, Notifier},
+	services::notification::{
+		severity, severity_color, HttpStatusError, NotificationError, Notifier,
+	},
+	utils::{render_json_template, render_template},
 };
 
+/// Maximum number of characters of the texts of an embed
+const MAX_EMBED_TEXT: usize = 6000;
+
+/// Maximum number of fields of an embed
+const MAX_EMBED_FIELDS: usize = 25;
+
 /// Implementation of Discord notifications via webhooks
 pub struct DiscordNotifier {
 	/// Discord webhook URL for message delivery
@@ -23,57 +36,22 @@ pub struct DiscordNotifier {
 	body_template: String,
 	/// HTTP client for webhook requests
 	client: Client,
-}
-
-/// Represents a field in a Discord embed message
-#[derive(Serialize)]
-struct DiscordField {
-	/// The name of the field (max 256 characters)
-	name: String,
-	/// The value of the field (max 1024 characters)
-	value: String,
-	/// Indicates whether the field should be displayed inline with other fields (optional)
-	inline: Option<bool>,
-}
-
-/// Represents an embed message in Discord
-#[derive(Serialize)]
-struct DiscordEmbed {
-	/// The title of the embed (max 256 characters)
-	title: String,
-	/// The description of the embed (max 4096 characters)
-	description: Option<String>,
-	/// A URL that the title links to (optional)
-	url: Option<String>,
-	/// The color of the embed represented as a hexadecimal integer (optional)
-	color: Option<u32>,
-	/// A list of fields included in the embed (max 25 fields, optional)
-	fields: Option<Vec<DiscordField>>,
-	/// Indicates whether text-to-speech is enabled for the embed (optional)
-	tts: Option<bool>,
-	/// A thumbnail image for the embed (optional)
-	thumbnail: Option<String>,
-	/// An image for the embed (optional)
-	image: Option<String>,
-	/// Footer information for the embed (max 2048 characters, optional)
-	footer: Option<String>,
-	/// Author information for the embed (max 256 characters, optional)
-	author: Option<String>,
-	/// A timestamp for the embed (optional)
-	timestamp: Option<String>,
+	/// Embed template of the message
+	embed: Option<Value>,
 }
 
 /// Represents a formatted Discord message
 #[derive(Serialize)]
 struct DiscordMessage {
-	/// The content of the message
-	content: String,
+	/// The content of the message, not set when the message is an embed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	content: Option<String>,
 	/// The username to display as the sender of the message (optional)
 	username: Option<String>,
 	/// The avatar URL to display for the sender (optional)
 	avatar_url: Option<String>,
 	/// A list of embeds included in the message (max 10 embeds, optional)
-	embeds: Option<Vec<DiscordEmbed>>,
+	embeds: Option<Vec<Value>>,
 }
 
 impl DiscordNotifier {
@@ -93,10 +71,17 @@ impl DiscordNotifier {
 			title,
 			body_template,
 			client: Client::new(),
+			embed: None,
 		})
 	}
 
-	/// Formats a message by substituting variables in the template
+	/// Lays out the messages with the given embed template
+	pub fn with_embed(mut self, embed: Value) -> Self {
+		self.embed = Some(embed);
+		self
+	}
+
+	/// Formats a message by rendering the body template with the given variables
 	///
 	/// # Arguments
 	/// * `variables` - Map of variable names to values
@@ -104,13 +89,121 @@ impl DiscordNotifier {
 	/// # Returns
 	/// * `String` - Formatted message with variables replaced
 	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
-		let mut message = self.body_template.clone();
-		for (key, value) in variables {
-			message = message.replace(&format!("${{{}}}", key), value);
-		}
+		let message = render_template(&self.body_template, variables);
 		format!("*{}*\n\n{}", self.title, message)
 	}
 
+	/// Renders the embed template with the given variables
+	///
+	/// Fields with an empty name or value are removed. The color defaults to the one of the
+	/// `severity` variable and the timestamp to the `block_timestamp` variable.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Option<Value>` - Rendered embed, or None without a template or if the embed exceeds
+	///   the limits of Discord
+	pub fn format_embed(&self, variables: &HashMap<String, String>) -> Option<Value> {
+		let mut embed = render_json_template(self.embed.as_ref()?, variables);
+		let fields = embed.as_object_mut()?;
+
+		if let Some(Value::Array(items)) = fields.get_mut("fields") {
+			items.retain(|field| {
+				["name", "value"].iter().all(|name| {
+					field
+						.get(*name)
+						.and_then(Value::as_str)
+						.is_some_and(|text| !text.trim().is_empty())
+				})
+			});
+		}
+		let color = match fields.get("color") {
+			Some(Value::String(color)) => parse_color(color),
+			Some(Value::Number(color)) => color.as_u64(),
+			_ => None,
+		}
+		.or_else(|| severity(variables).map(severity_color));
+		match color {
+			Some(color) => fields.insert("color".to_string(), color.into()),
+			None => fields.remove("color"),
+		};
+		if !fields.contains_key("timestamp") {
+			let timestamp = variables
+				.get("block_timestamp")
+				.and_then(|timestamp| timestamp.parse().ok())
+				.and_then(|timestamp| DateTime::from_timestamp(timestamp, 0));
+			if let Some(timestamp) = timestamp {
+				fields.insert(
+					"timestamp".to_string(),
+					timestamp.to_rfc3339_opts(SecondsFormat::Secs, true).into(),
+				);
+			}
+		}
+
+		if !within_limits(&embed) {
+			tracing::warn!(
+				"Discord embed exceeds the limits of Discord, sending the message as text"
+			);
+			return None;
+		}
+		Some(embed)
+	}
+
+	/// Sends a message rendered with the given variables, as an embed if set
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<(), anyhow::Error> {
+		let payload = match self.format_embed(variables) {
+			Some(embed) => DiscordMessage {
+				content: None,
+				username: None,
+				avatar_url: None,
+				embeds: Some(vec![embed]),
+			},
+			None => DiscordMessage {
+				content: Some(self.format_message(variables)),
+				username: None,
+				avatar_url: None,
+				embeds: None,
+			},
+		};
+		self.post(&payload).await
+	}
+
+	/// Posts a message to the webhook
+	async fn post(&self, payload: &DiscordMessage) -> Result<(), anyhow::Error> {
+		let response = match self
+			.client
+			.post(&self.url)
+			.header("Content-Type", "application/json")
+			.json(payload)
+			.send()
+			.await
+		{
+			Ok(resp) => resp,
+			Err(e) => {
+				return Err(anyhow::anyhow!(
+					"Failed to send Discord notification: {}",
+					e
+				));
+			}
+		};
+
+		if !response.status().is_success() {
+			return Err(HttpStatusError::from_response("Discord webhook", &response).into());
+		}
+
+		Ok(())
+	}
+
 	/// Creates a Discord notifier from a trigger configuration
 	///
 	/// # Arguments
@@ -123,11 +216,13 @@ impl DiscordNotifier {
 			TriggerTypeConfig::Discord {
 				discord_url,
 				message,
+				embed,
 			} => Some(Self {
 				url: discord_url.clone(),
 				title: message.title.clone(),
 				body_template: message.body.clone(),
 				client: Client::new(),
+				embed: embed.clone(),
 			}),
 			_ => None,
 		}
@@ -145,40 +240,56 @@ impl Notifier for DiscordNotifier {
 	/// * `Result<(), anyhow::Error>` - Success or error
 	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
 		let payload = DiscordMessage {
-			content: message.to_string(),
+			content: Some(message.to_string()),
 			username: None,
 			avatar_url: None,
 			embeds: None,
 		};
+		self.post(&payload).await
+	}
+}
 
-		let response = match self
-			.client
-			.post(&self.url)
-			.header("Content-Type", "application/json")
-			.json(&payload)
-			.send()
-			.await
-		{
-			Ok(resp) => resp,
-			Err(e) => {
-				return Err(anyhow::anyhow!(
-					"Failed to send Discord notification: {}",
-					e
-				));
-			}
-		};
-
-		if !response.status().is_success() {
-			return Err(anyhow::anyhow!(
-				"Discord webhook returned error status: {}",
-				response.status()
-			));
-		}
-
-		Ok(())
+/// Parses a color written as `#rrggbb` or as a decimal number
+fn parse_color(color: &str) -> Option<u64> {
+	match color.trim().strip_prefix('#') {
+		Some(hex) => u64::from_str_radix(hex, 16).ok(),
+		None => color.trim().parse().ok(),
 	}
 }
 
+/// Returns whether an embed is within the limits of Discord
+fn within_limits(embed: &Value) -> bool {
+	let text = |value: Option<&Value>, name: &str| {
+		value
+			.and_then(|value| value.get(name))
+			.and_then(Value::as_str)
+			.map_or(0, |text| text.chars().count())
+	};
+	let fields = embed
+		.get("fields")
+		.and_then(Value::as_array)
+		.map_or(&[][..], Vec::as_slice);
+
+	let limits = [
+		(text(Some(embed), "title"), 256),
+		(text(Some(embed), "description"), 4096),
+		(text(embed.get("footer"), "text"), 2048),
+		(text(embed.get("author"), "name"), 256),
+	];
+	let total = limits.iter().map(|(length, _)| length).sum::<usize>()
+		+ fields
+			.iter()
+			.map(|field| text(Some(field), "name") + text(Some(field), "value"))
+			.sum::<usize>();
+
+	limits.iter().all(|(length, limit)| length <= limit)
+		&& fields.len() <= MAX_EMBED_FIELDS
+		&& fields
+			.iter()
+			.all(|field| text(Some(field), "name") <= 256 && text(Some(field), "value") <= 1024)
+		&& total <= MAX_EMBED_TEXT
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::models::NotificationMessage;
@@ -201,6 +312,7 @@ mod tests {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
 			},
+			embed: None,
 		}
 	}
 
@@ -241,6 +353,68 @@ mod tests {
 		assert_eq!(result, "*Alert*\n\n");
 	}
 
+	////////////////////////////////////////////////////////////
+	// format_embed tests
+	////////////////////////////////////////////////////////////
+
+	fn create_test_embed() -> Value {
+		serde_json::json!({
+			"title": "{{ monitor_name }}",
+			"description": "{{ description }}",
+			"url": "{{ transaction_link }}",
+			"fields": [
+				{ "name": "Value", "value": "{{ value }}", "inline": true },
+				{ "name": "Missing", "value": "{{ missing }}", "inline": true }
+			],
+			"footer": { "text": "{{ network_name }}" }
+		})
+	}
+
+	#[test]
+	fn test_format_embed() {
+		let notifier = create_test_notifier("Test message").with_embed(create_test_embed());
+		let variables = HashMap::from([
+			("monitor_name".to_string(), "Large transfer".to_string()),
+			("description".to_string(), "A large transfer".to_string()),
+			("value".to_string(), "100".to_string()),
+			("network_name".to_string(), "Ethereum Mainnet".to_string()),
+			("severity".to_string(), "critical".to_string()),
+			("block_timestamp".to_string(), "1733036783".to_string()),
+		]);
+
+		let embed = notifier.format_embed(&variables).unwrap();
+		assert_eq!(embed["title"], "Large transfer");
+		assert_eq!(embed["fields"].as_array().unwrap().len(), 1);
+		assert_eq!(embed["fields"][0]["value"], "100");
+		assert_eq!(embed["footer"]["text"], "Ethereum Mainnet");
+		assert_eq!(embed["color"], 0xE74C3C);
+		assert_eq!(embed["timestamp"], "2024-12-01T07:06:23Z");
+
+		// A color set by the template wins over the severity
+		let notifier = create_test_notifier("Test message")
+			.with_embed(serde_json::json!({ "title": "Alert", "color": "#00ff00" }));
+		let embed = notifier.format_embed(&variables).unwrap();
+		assert_eq!(embed["color"], 0x00FF00);
+	}
+
+	#[test]
+	fn test_format_embed_exceeding_limits() {
+		let notifier = create_test_notifier("Test message").with_embed(create_test_embed());
+		let variables = HashMap::from([("description".to_string(), "x".repeat(4097))]);
+		assert!(notifier.format_embed(&variables).is_none());
+
+		let variables = HashMap::from([
+			("description".to_string(), "x".repeat(4000)),
+			("value".to_string(), "x".repeat(1000)),
+			("network_name".to_string(), "x".repeat(2000)),
+		]);
+		assert!(notifier.format_embed(&variables).is_none());
+
+		assert!(create_test_notifier("Test message")
+			.format_embed(&HashMap::new())
+			.is_none());
+	}
+
 	////////////////////////////////////////////////////////////
 	// from_config tests
 	////////////////////////////////////////////////////////////
@@ -18,6 +18,7 @@ use std::collections::HashMap;
 use crate::{
 	models::TriggerTypeConfig,
 	services::notification::{NotificationError, Notifier},
+	utils::render_template,
 };
 
 /// Implementation of email notifications via SMTP
@@ -32,6 +33,8 @@ pub struct EmailNotifier<T: Transport + Send + Sync> {
 	sender: EmailAddress,
 	/// Email recipients
 	recipients: Vec<EmailAddress>,
+	/// Whether the body is sent as HTML instead of plain text
+	html: bool,
 }
 
 /// Configuration for SMTP connection
@@ -71,8 +74,15 @@ where
 			sender: email_content.sender,
 			recipients: email_content.recipients,
 			client: transport,
+			html: false,
 		}
 	}
+
+	/// Sends the body as HTML instead of plain text
+	pub fn with_html(mut self) -> Self {
+		self.html = true;
+		self
+	}
 }
 
 impl EmailNotifier<SmtpTransport> {
@@ -106,10 +116,11 @@ impl EmailNotifier<SmtpTransport> {
 			sender: email_content.sender,
 			recipients: email_content.recipients,
 			client,
+			html: false,
 		})
 	}
 
-	/// Formats a message by substituting variables in the template
+	/// Formats a message by rendering the body template with the given variables
 	///
 	/// # Arguments
 	/// * `variables` - Map of variable names to values
@@ -117,11 +128,7 @@ impl EmailNotifier<SmtpTransport> {
 	/// # Returns
 	/// * `String` - Formatted message with variables replaced
 	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
-		variables
-			.iter()
-			.fold(self.body_template.clone(), |message, (key, value)| {
-				message.replace(&format!("${{{}}}", key), value)
-			})
+		render_template(&self.body_template, variables)
 	}
 
 	/// Creates an email notifier from a trigger configuration
@@ -203,7 +210,11 @@ where
 					.map_err(|e| anyhow::anyhow!(e.to_string()))?,
 			)
 			.subject(&self.subject)
-			.header(ContentType::TEXT_PLAIN)
+			.header(if self.html {
+				ContentType::TEXT_HTML
+			} else {
+				ContentType::TEXT_PLAIN
+			})
 			.body(message.to_owned())
 			.map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
@@ -4,7 +4,8 @@
 //! including network issues and configuration problems.
 
 use crate::utils::logging::error::{ErrorContext, TraceableError};
-use std::collections::HashMap;
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use std::{collections::HashMap, time::Duration};
 use thiserror::Error as ThisError;
 use uuid::Uuid;
 
@@ -70,6 +71,60 @@ impl NotificationError {
 	}
 }
 
+impl NotificationError {
+	/// Returns the HTTP status the notification endpoint answered with, if that caused the error
+	pub fn http_status(&self) -> Option<&HttpStatusError> {
+		match self {
+			Self::Other(e) => e.downcast_ref::<HttpStatusError>(),
+			_ => None,
+		}
+	}
+}
+
+/// Error status returned by the endpoint of an HTTP notifier
+///
+/// Keeps the status and the `Retry-After` delay of the response so failed deliveries can be
+/// retried according to the trigger's retry policy.
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+#[error("{endpoint} returned error status: {status}")]
+pub struct HttpStatusError {
+	/// Name of the endpoint, e.g. "Slack webhook"
+	pub endpoint: &'static str,
+	/// Status of the response
+	pub status: StatusCode,
+	/// Delay the endpoint asked for before retrying
+	pub retry_after: Option<Duration>,
+}
+
+impl HttpStatusError {
+	/// Creates the error from an unsuccessful response
+	pub fn from_response(endpoint: &'static str, response: &Response) -> Self {
+		Self {
+			endpoint,
+			status: response.status(),
+			retry_after: response
+				.headers()
+				.get(RETRY_AFTER)
+				.and_then(|value| value.to_str().ok())
+				.and_then(parse_retry_after),
+		}
+	}
+}
+
+/// Parses a `Retry-After` header given in seconds or as an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+	let value = value.trim();
+	if let Ok(seconds) = value.parse::<u64>() {
+		return Some(Duration::from_secs(seconds));
+	}
+	let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+	Some(
+		(date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+			.to_std()
+			.unwrap_or_default(),
+	)
+}
+
 impl TraceableError for NotificationError {
 	fn trace_id(&self) -> String {
 		match self {
@@ -87,6 +142,41 @@ mod tests {
 	use super::*;
 	use std::io::{Error as IoError, ErrorKind};
 
+	#[test]
+	fn test_parse_retry_after() {
+		assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+		assert_eq!(
+			parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+			Some(Duration::ZERO)
+		);
+		let in_a_minute = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+		let delay = parse_retry_after(&in_a_minute).unwrap();
+		assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+		assert_eq!(parse_retry_after("soon"), None);
+	}
+
+	#[test]
+	fn test_http_status_error_is_found_through_context() {
+		let error: NotificationError = anyhow::Error::new(HttpStatusError {
+			endpoint: "Webhook",
+			status: StatusCode::SERVICE_UNAVAILABLE,
+			retry_after: Some(Duration::from_secs(5)),
+		})
+		.context("Failed to execute notification test")
+		.into();
+
+		let status = error.http_status().unwrap();
+		assert_eq!(status.status, StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(status.retry_after, Some(Duration::from_secs(5)));
+		assert_eq!(
+			status.to_string(),
+			"Webhook returned error status: 503 Service Unavailable"
+		);
+		assert!(NotificationError::config_error("test error", None, None)
+			.http_status()
+			.is_none());
+	}
+
 	#[test]
 	fn test_network_error_formatting() {
 		let error = NotificationError::network_error("test error", None, None);
@@ -0,0 +1,550 @@
+//! File notification implementation.
+//!
+//! Provides functionality to append monitor matches to a local file, for archival and audits.
+//! Records are JSONL lines of the serialized match or of a payload rendered from a template, or
+//! CSV lines rendered from column templates. Like the log files, files are rotated by size and
+//! period into files named after the period and a sequence number, and rotated files can be
+//! compressed with gzip. Records are flushed to disk according to the fsync policy.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	fs::{self, File, OpenOptions},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	models::{
+		FileColumn, FileFormat, FileRotation, FileRotationInterval, FileSync, MonitorMatch,
+		TriggerTypeConfig,
+	},
+	services::notification::Notifier,
+	utils::{render_json_template, render_template},
+};
+
+lazy_static::lazy_static! {
+	/// Locks serializing the appends and rotations of every file
+	static ref FILE_LOCKS: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+/// Implementation of file notifications appending records to a local file
+#[derive(Clone)]
+pub struct FileNotifier {
+	/// Path to the file the records are appended to
+	path: PathBuf,
+	/// Format of the records
+	format: FileFormat,
+	/// Template of the JSONL records
+	payload: Option<Value>,
+	/// Columns of the CSV records
+	columns: Vec<FileColumn>,
+	/// Rotation of the file
+	rotation: FileRotation,
+	/// When the records are flushed to disk
+	fsync: FileSync,
+}
+
+impl FileNotifier {
+	/// Creates a file notifier from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing file parameters
+	///
+	/// # Returns
+	/// * `Option<Self>` - Notifier instance if config is File type
+	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
+		match config {
+			TriggerTypeConfig::File {
+				path,
+				format,
+				payload,
+				columns,
+				rotation,
+				fsync,
+			} => Some(Self {
+				path: PathBuf::from(path),
+				format: format.unwrap_or_default(),
+				payload: payload.clone(),
+				columns: columns.clone().unwrap_or_default(),
+				rotation: rotation.clone().unwrap_or_default(),
+				fsync: fsync.unwrap_or_default(),
+			}),
+			_ => None,
+		}
+	}
+
+	/// Renders the record with the given variables
+	///
+	/// # Returns
+	/// * `Option<String>` - Rendered record, or None for JSONL records without template as the
+	///   record is the match
+	pub fn format_record(&self, variables: &HashMap<String, String>) -> Option<String> {
+		match self.format {
+			FileFormat::Jsonl => self
+				.payload
+				.as_ref()
+				.map(|template| render_json_template(template, variables).to_string()),
+			FileFormat::Csv => Some(csv_line(
+				self.columns
+					.iter()
+					.map(|column| render_template(&column.value, variables)),
+			)),
+		}
+	}
+
+	/// Builds the record of a match rendered with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	/// * `monitor_match` - Match written by JSONL records without template
+	///
+	/// # Returns
+	/// * `Result<String, anyhow::Error>` - Record to append, or an error without template nor
+	///   match
+	pub fn build_record(
+		&self,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+	) -> Result<String, anyhow::Error> {
+		match (self.format_record(variables), monitor_match) {
+			(Some(record), _) => Ok(record),
+			(None, Some(monitor_match)) => serde_json::to_string(monitor_match)
+				.map_err(|e| anyhow::anyhow!("Failed to serialize match: {}", e)),
+			(None, None) => Err(anyhow::anyhow!("No match to write")),
+		}
+	}
+
+	/// Appends the record of a match rendered with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	/// * `monitor_match` - Match written by JSONL records without template
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+	) -> Result<(), anyhow::Error> {
+		let record = self.build_record(variables, monitor_match)?;
+		self.append(record).await
+	}
+
+	/// Appends a record on the blocking thread pool
+	async fn append(&self, record: String) -> Result<(), anyhow::Error> {
+		let notifier = self.clone();
+		tokio::task::spawn_blocking(move || notifier.append_record(&record, Utc::now()))
+			.await
+			.map_err(|e| anyhow::anyhow!("File write task failed: {}", e))?
+	}
+
+	/// Appends a record to the file, rotating the file first if needed
+	///
+	/// Blocks the calling thread until the record is written, and flushed to disk with the
+	/// `always` fsync policy.
+	///
+	/// # Arguments
+	/// * `record` - Record to append, without line break
+	/// * `now` - Current time, deciding the period of the record
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub fn append_record(&self, record: &str, now: DateTime<Utc>) -> Result<(), anyhow::Error> {
+		let lock = FILE_LOCKS
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.entry(self.path.clone())
+			.or_default()
+			.clone();
+		let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+		if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+			fs::create_dir_all(parent).map_err(|e| {
+				anyhow::anyhow!("Failed to create directory {}: {}", parent.display(), e)
+			})?;
+		}
+
+		let mut size = match fs::metadata(&self.path) {
+			Ok(metadata) => {
+				let last_write = metadata.modified().map(DateTime::<Utc>::from)?;
+				if self.needs_rotation(metadata.len(), record.len() as u64, last_write, now) {
+					self.rotate(last_write)?;
+					0
+				} else {
+					metadata.len()
+				}
+			}
+			Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+			Err(e) => {
+				return Err(anyhow::anyhow!(
+					"Failed to read {}: {}",
+					self.path.display(),
+					e
+				))
+			}
+		};
+
+		let mut content = String::new();
+		if size == 0 && self.format == FileFormat::Csv {
+			content.push_str(&csv_line(
+				self.columns.iter().map(|column| column.name.clone()),
+			));
+			content.push('\n');
+		}
+		content.push_str(record);
+		content.push('\n');
+		size += content.len() as u64;
+
+		let mut file = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)
+			.map_err(|e| anyhow::anyhow!("Failed to open {}: {}", self.path.display(), e))?;
+		file.write_all(content.as_bytes())
+			.map_err(|e| anyhow::anyhow!("Failed to write {}: {}", self.path.display(), e))?;
+		if self.fsync == FileSync::Always {
+			file.sync_data()
+				.map_err(|e| anyhow::anyhow!("Failed to sync {}: {}", self.path.display(), e))?;
+		}
+		tracing::debug!(
+			"Appended record to {} ({} bytes)",
+			self.path.display(),
+			size
+		);
+		Ok(())
+	}
+
+	/// Returns whether the file must be rotated before a record is appended
+	///
+	/// # Arguments
+	/// * `size` - Current size of the file
+	/// * `record_size` - Size of the record to append
+	/// * `last_write` - Time of the last write to the file
+	/// * `now` - Current time
+	fn needs_rotation(
+		&self,
+		size: u64,
+		record_size: u64,
+		last_write: DateTime<Utc>,
+		now: DateTime<Utc>,
+	) -> bool {
+		if size == 0 {
+			return false;
+		}
+		let too_large = self
+			.rotation
+			.max_size_bytes
+			.is_some_and(|max_size| size + record_size > max_size);
+		let period_ended = self.rotation.interval.is_some_and(|interval| {
+			period_label(Some(interval), last_write) != period_label(Some(interval), now)
+		});
+		too_large || period_ended
+	}
+
+	/// Renames the file after the period of its last write, and compresses it if configured
+	fn rotate(&self, last_write: DateTime<Utc>) -> Result<(), anyhow::Error> {
+		if self.fsync == FileSync::Rotation {
+			File::open(&self.path)
+				.and_then(|file| file.sync_all())
+				.map_err(|e| anyhow::anyhow!("Failed to sync {}: {}", self.path.display(), e))?;
+		}
+
+		let label = period_label(self.rotation.interval, last_write);
+		let rotated = (1..)
+			.map(|index| rotated_path(&self.path, &label, index))
+			.find(|path| !path.exists() && !gzip_path(path).exists())
+			.expect("an index is free");
+		fs::rename(&self.path, &rotated).map_err(|e| {
+			anyhow::anyhow!(
+				"Failed to rotate {} to {}: {}",
+				self.path.display(),
+				rotated.display(),
+				e
+			)
+		})?;
+		tracing::info!("Rotated {} to {}", self.path.display(), rotated.display());
+
+		if self.rotation.compress.unwrap_or(false) {
+			// The rotated file is kept uncompressed if it cannot be compressed
+			if let Err(e) = compress(&rotated, self.fsync != FileSync::Never) {
+				tracing::error!("Failed to compress {}: {}", rotated.display(), e);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Returns the label of the period of a time, the day if there is no rotation interval
+fn period_label(interval: Option<FileRotationInterval>, time: DateTime<Utc>) -> String {
+	match interval {
+		Some(FileRotationInterval::Hourly) => time.format("%Y-%m-%d-%H").to_string(),
+		Some(FileRotationInterval::Daily) | None => time.format("%Y-%m-%d").to_string(),
+	}
+}
+
+/// Computes the path of a rotated file, e.g. `alerts-2025-01-01.1.jsonl` for `alerts.jsonl`
+fn rotated_path(path: &Path, label: &str, index: u32) -> PathBuf {
+	let stem = path
+		.file_stem()
+		.map(|stem| stem.to_string_lossy().into_owned())
+		.unwrap_or_default();
+	let name = match path.extension() {
+		Some(extension) => format!(
+			"{}-{}.{}.{}",
+			stem,
+			label,
+			index,
+			extension.to_string_lossy()
+		),
+		None => format!("{}-{}.{}", stem, label, index),
+	};
+	path.with_file_name(name)
+}
+
+/// Returns the path of the compressed copy of a file
+fn gzip_path(path: &Path) -> PathBuf {
+	let mut name = path.as_os_str().to_owned();
+	name.push(".gz");
+	PathBuf::from(name)
+}
+
+/// Compresses a file with gzip and removes it
+fn compress(path: &Path, sync: bool) -> io::Result<()> {
+	let target = gzip_path(path);
+	let mut encoder = GzEncoder::new(File::create(&target)?, Compression::default());
+	io::copy(&mut File::open(path)?, &mut encoder)?;
+	let file = encoder.finish()?;
+	if sync {
+		file.sync_all()?;
+	}
+	fs::remove_file(path)
+}
+
+/// Formats the fields of a CSV line, quoting the fields with separators, quotes or line breaks
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+	fields
+		.map(|field| {
+			if field.contains([',', '"', '\n', '\r']) {
+				format!("\"{}\"", field.replace('"', "\"\""))
+			} else {
+				field
+			}
+		})
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+	/// Appends a record of the given message
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to append
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
+		let record = match self.format {
+			FileFormat::Jsonl => serde_json::json!({ "message": message }).to_string(),
+			FileFormat::Csv => csv_line(std::iter::once(message.to_string())),
+		};
+		self.append(record).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::TimeZone;
+	use flate2::read::GzDecoder;
+	use serde_json::json;
+	use std::io::Read;
+	use tempfile::TempDir;
+
+	use super::*;
+
+	fn create_test_notifier(
+		path: &Path,
+		format: FileFormat,
+		rotation: Option<FileRotation>,
+	) -> FileNotifier {
+		FileNotifier::from_config(&TriggerTypeConfig::File {
+			path: path.to_string_lossy().into_owned(),
+			format: Some(format),
+			payload: None,
+			columns: Some(vec![
+				FileColumn {
+					name: "monitor".to_string(),
+					value: "{{ monitor_name }}".to_string(),
+				},
+				FileColumn {
+					name: "transaction".to_string(),
+					value: "{{ transaction_hash }}".to_string(),
+				},
+			]),
+			rotation,
+			fsync: None,
+		})
+		.unwrap()
+	}
+
+	fn create_test_variables() -> HashMap<String, String> {
+		HashMap::from([
+			(
+				"monitor_name".to_string(),
+				"Large, \"Transfer\"".to_string(),
+			),
+			("transaction_hash".to_string(), "0xabc".to_string()),
+		])
+	}
+
+	fn time(hour: u32) -> DateTime<Utc> {
+		Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap()
+	}
+
+	////////////////////////////////////////////////////////////
+	// build_record tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_build_csv_record_escapes_fields() {
+		let temp_dir = TempDir::new().unwrap();
+		let notifier =
+			create_test_notifier(&temp_dir.path().join("alerts.csv"), FileFormat::Csv, None);
+
+		let record = notifier
+			.build_record(&create_test_variables(), None)
+			.unwrap();
+		assert_eq!(record, "\"Large, \"\"Transfer\"\"\",0xabc");
+	}
+
+	#[test]
+	fn test_build_jsonl_record() {
+		let temp_dir = TempDir::new().unwrap();
+		let mut notifier = create_test_notifier(
+			&temp_dir.path().join("alerts.jsonl"),
+			FileFormat::Jsonl,
+			None,
+		);
+		assert!(notifier
+			.build_record(&create_test_variables(), None)
+			.is_err());
+
+		notifier.payload = Some(json!({ "tx": "{{ transaction_hash }}" }));
+		let record = notifier
+			.build_record(&create_test_variables(), None)
+			.unwrap();
+		assert_eq!(
+			serde_json::from_str::<Value>(&record).unwrap(),
+			json!({ "tx": "0xabc" })
+		);
+	}
+
+	////////////////////////////////////////////////////////////
+	// append_record tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_append_writes_csv_header_once() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("audit").join("alerts.csv");
+		let notifier = create_test_notifier(&path, FileFormat::Csv, None);
+
+		notifier.append_record("a,1", time(0)).unwrap();
+		notifier.append_record("b,2", time(1)).unwrap();
+
+		assert_eq!(
+			fs::read_to_string(&path).unwrap(),
+			"monitor,transaction\na,1\nb,2\n"
+		);
+	}
+
+	#[test]
+	fn test_rotation_by_size() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("alerts.jsonl");
+		let notifier = create_test_notifier(
+			&path,
+			FileFormat::Jsonl,
+			Some(FileRotation {
+				max_size_bytes: Some(10),
+				..Default::default()
+			}),
+		);
+
+		for record in ["{\"n\":1}", "{\"n\":2}", "{\"n\":3}"] {
+			notifier.append_record(record, Utc::now()).unwrap();
+		}
+
+		let today = Utc::now().format("%Y-%m-%d");
+		assert_eq!(fs::read_to_string(&path).unwrap(), "{\"n\":3}\n");
+		for (index, record) in [(1, "{\"n\":1}\n"), (2, "{\"n\":2}\n")] {
+			let rotated = temp_dir
+				.path()
+				.join(format!("alerts-{}.{}.jsonl", today, index));
+			assert_eq!(fs::read_to_string(rotated).unwrap(), record);
+		}
+	}
+
+	#[test]
+	fn test_rotation_by_period_with_compression() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("alerts.csv");
+		let notifier = create_test_notifier(
+			&path,
+			FileFormat::Csv,
+			Some(FileRotation {
+				interval: Some(FileRotationInterval::Hourly),
+				compress: Some(true),
+				..Default::default()
+			}),
+		);
+		// The period of the file is the one of its last write
+		let last_write = Utc::now() - chrono::Duration::hours(2);
+		fs::write(&path, "monitor,transaction\na,1\n").unwrap();
+		File::options()
+			.write(true)
+			.open(&path)
+			.unwrap()
+			.set_modified(last_write.into())
+			.unwrap();
+
+		notifier.append_record("b,2", Utc::now()).unwrap();
+
+		assert_eq!(
+			fs::read_to_string(&path).unwrap(),
+			"monitor,transaction\nb,2\n"
+		);
+		let rotated = temp_dir.path().join(format!(
+			"alerts-{}.1.csv.gz",
+			last_write.format("%Y-%m-%d-%H")
+		));
+		let mut content = String::new();
+		GzDecoder::new(File::open(&rotated).unwrap())
+			.read_to_string(&mut content)
+			.unwrap();
+		assert_eq!(content, "monitor,transaction\na,1\n");
+		assert!(!rotated.with_extension("").exists());
+	}
+
+	#[test]
+	fn test_rotated_path() {
+		assert_eq!(
+			rotated_path(Path::new("data/alerts.jsonl"), "2025-01-01", 2),
+			PathBuf::from("data/alerts-2025-01-01.2.jsonl")
+		);
+		assert_eq!(
+			rotated_path(Path::new("alerts"), "2025-01-01-13", 1),
+			PathBuf::from("alerts-2025-01-01-13.1")
+		);
+		assert_eq!(
+			period_label(Some(FileRotationInterval::Hourly), time(13)),
+			"2025-01-01-13"
+		);
+	}
+}
@@ -0,0 +1,335 @@
+//! Kafka notification implementation.
+//!
+//! Provides functionality to publish monitor matches as records of a Kafka topic. The value of
+//! a record is the serialized match, or a payload rendered from a template, and its key and
+//! headers are rendered from templates. Records are published by a minimal producer speaking
+//! the Kafka protocol, with SASL authentication and TLS.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::{
+	collections::{BTreeMap, HashMap},
+	time::Duration,
+};
+use thiserror::Error as ThisError;
+
+mod producer;
+mod protocol;
+mod sasl;
+
+use crate::{
+	models::{MonitorMatch, TriggerTypeConfig},
+	services::notification::Notifier,
+	utils::{render_json_template, render_template},
+};
+
+pub use producer::KafkaProducer;
+pub use protocol::KafkaRecord;
+
+/// Default timeout of a delivery in milliseconds
+const DEFAULT_TIMEOUT_MS: u32 = 10_000;
+
+/// Errors of the deliveries of Kafka records
+#[derive(ThisError, Debug)]
+pub enum KafkaError {
+	/// Broker unreachable or connection lost
+	#[error("Kafka connection error: {0}")]
+	Connection(String),
+
+	/// Invalid TLS certificates or keys
+	#[error("Kafka TLS error: {0}")]
+	Tls(String),
+
+	/// SASL authentication rejected
+	#[error("Kafka authentication error: {0}")]
+	Authentication(String),
+
+	/// Malformed response of a broker
+	#[error("Kafka protocol error: {0}")]
+	Protocol(String),
+
+	/// Request version the broker does not support
+	#[error("Kafka broker does not support {0}")]
+	UnsupportedVersion(String),
+
+	/// Error code returned by a broker
+	#[error("Kafka broker error: {name} ({code})")]
+	Broker {
+		/// Error code of the response
+		code: i16,
+		/// Name of the error code
+		name: &'static str,
+		/// Whether the request can be retried
+		retriable: bool,
+	},
+
+	/// Delivery not acknowledged in time
+	#[error("Kafka delivery timed out after {0} ms")]
+	Timeout(u64),
+
+	/// Record that cannot be built
+	#[error("Kafka record error: {0}")]
+	Record(String),
+}
+
+impl KafkaError {
+	/// Creates the error of an error code returned by a broker
+	pub fn broker(code: i16) -> Self {
+		let (name, retriable) = protocol::error_name(code);
+		Self::Broker {
+			code,
+			name,
+			retriable,
+		}
+	}
+
+	/// Returns whether the delivery may succeed if retried
+	pub fn is_retriable(&self) -> bool {
+		match self {
+			Self::Connection(_) | Self::Protocol(_) | Self::Timeout(_) => true,
+			Self::Broker { retriable, .. } => *retriable,
+			Self::Tls(_)
+			| Self::Authentication(_)
+			| Self::UnsupportedVersion(_)
+			| Self::Record(_) => false,
+		}
+	}
+}
+
+impl From<std::io::Error> for KafkaError {
+	fn from(error: std::io::Error) -> Self {
+		Self::Connection(error.to_string())
+	}
+}
+
+/// Implementation of Kafka notifications publishing records to a topic
+pub struct KafkaNotifier {
+	/// Producer publishing the records
+	producer: KafkaProducer,
+	/// Topic the records are published to
+	topic: String,
+	/// Template of the record key
+	key: Option<String>,
+	/// Templates of the record headers
+	headers: BTreeMap<String, String>,
+	/// Template of the record value
+	payload: Option<Value>,
+}
+
+impl KafkaNotifier {
+	/// Creates a Kafka notifier from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing Kafka parameters
+	///
+	/// # Returns
+	/// * `Option<Self>` - Notifier instance if config is Kafka type
+	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
+		match config {
+			TriggerTypeConfig::Kafka {
+				brokers,
+				topic,
+				key,
+				headers,
+				payload,
+				acks,
+				timeout_ms,
+				sasl,
+				tls,
+			} => Some(Self {
+				producer: KafkaProducer::new(
+					brokers.clone(),
+					acks.unwrap_or_default(),
+					Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS) as u64),
+					sasl.clone(),
+					tls.clone(),
+				),
+				topic: topic.clone(),
+				key: key.clone(),
+				headers: headers.clone().unwrap_or_default().into_iter().collect(),
+				payload: payload.clone(),
+			}),
+			_ => None,
+		}
+	}
+
+	/// Renders the payload template with the given variables
+	///
+	/// # Returns
+	/// * `Option<String>` - Rendered payload, or None without a template as the record value is
+	///   the match
+	pub fn format_payload(&self, variables: &HashMap<String, String>) -> Option<String> {
+		self.payload
+			.as_ref()
+			.map(|template| render_json_template(template, variables).to_string())
+	}
+
+	/// Builds the record of a match rendered with the given variables
+	///
+	/// Keys rendering empty are omitted.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	/// * `monitor_match` - Match published without a payload template
+	///
+	/// # Returns
+	/// * `Result<KafkaRecord, KafkaError>` - Record to publish, or an error without a payload
+	///   template nor a match
+	pub fn build_record(
+		&self,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+	) -> Result<KafkaRecord, KafkaError> {
+		let value = match (self.format_payload(variables), monitor_match) {
+			(Some(payload), _) => payload,
+			(None, Some(monitor_match)) => serde_json::to_string(monitor_match)
+				.map_err(|e| KafkaError::Record(format!("failed to serialize match: {}", e)))?,
+			(None, None) => return Err(KafkaError::Record("no match to publish".to_string())),
+		};
+		let key = self
+			.key
+			.as_ref()
+			.map(|template| render_template(template, variables))
+			.filter(|key| !key.trim().is_empty());
+
+		Ok(KafkaRecord {
+			key: key.map(String::into_bytes),
+			value: value.into_bytes(),
+			headers: self
+				.headers
+				.iter()
+				.map(|(name, template)| {
+					(
+						name.clone(),
+						render_template(template, variables).into_bytes(),
+					)
+				})
+				.collect(),
+			timestamp: chrono::Utc::now().timestamp_millis(),
+		})
+	}
+
+	/// Publishes the record of a match rendered with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	/// * `monitor_match` - Match published without a payload template
+	///
+	/// # Returns
+	/// * `Result<(), KafkaError>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+	) -> Result<(), KafkaError> {
+		let record = self.build_record(variables, monitor_match)?;
+		self.producer.produce(&self.topic, record).await
+	}
+}
+
+#[async_trait]
+impl Notifier for KafkaNotifier {
+	/// Publishes a record of the given message
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
+		let record = KafkaRecord {
+			key: None,
+			value: message.as_bytes().to_vec(),
+			headers: Vec::new(),
+			timestamp: chrono::Utc::now().timestamp_millis(),
+		};
+		Ok(self.producer.produce(&self.topic, record).await?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	fn create_test_notifier(key: Option<&str>, payload: Option<Value>) -> KafkaNotifier {
+		KafkaNotifier::from_config(&TriggerTypeConfig::Kafka {
+			brokers: vec!["localhost:9092".to_string()],
+			topic: "alerts".to_string(),
+			key: key.map(str::to_string),
+			headers: Some(HashMap::from([(
+				"monitor".to_string(),
+				"{{ monitor_name }}".to_string(),
+			)])),
+			payload,
+			acks: None,
+			timeout_ms: None,
+			sasl: None,
+			tls: None,
+		})
+		.unwrap()
+	}
+
+	fn create_test_variables() -> HashMap<String, String> {
+		HashMap::from([
+			("monitor_name".to_string(), "Large Transfer".to_string()),
+			("transaction_hash".to_string(), "0xabc".to_string()),
+		])
+	}
+
+	////////////////////////////////////////////////////////////
+	// build_record tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_build_record_with_templates() {
+		let notifier = create_test_notifier(
+			Some("{{ transaction_hash }}"),
+			Some(json!({ "tx": "{{ transaction_hash }}" })),
+		);
+		let record = notifier
+			.build_record(&create_test_variables(), None)
+			.unwrap();
+
+		assert_eq!(record.key, Some(b"0xabc".to_vec()));
+		assert_eq!(
+			serde_json::from_slice::<Value>(&record.value).unwrap(),
+			json!({ "tx": "0xabc" })
+		);
+		assert_eq!(
+			record.headers,
+			vec![("monitor".to_string(), b"Large Transfer".to_vec())]
+		);
+	}
+
+	#[test]
+	fn test_build_record_omits_empty_key() {
+		let notifier = create_test_notifier(Some("{{ missing }}"), Some(json!({})));
+		let record = notifier
+			.build_record(&create_test_variables(), None)
+			.unwrap();
+
+		assert_eq!(record.key, None);
+	}
+
+	#[test]
+	fn test_build_record_without_match() {
+		let notifier = create_test_notifier(None, None);
+		let error = notifier
+			.build_record(&create_test_variables(), None)
+			.unwrap_err();
+
+		assert!(matches!(error, KafkaError::Record(_)));
+		assert!(!error.is_retriable());
+	}
+
+	#[test]
+	fn test_error_retriability() {
+		assert!(KafkaError::broker(6).is_retriable());
+		assert!(!KafkaError::broker(29).is_retriable());
+		assert!(KafkaError::Timeout(1000).is_retriable());
+		assert!(!KafkaError::Authentication("rejected".to_string()).is_retriable());
+		assert!(!KafkaError::UnsupportedVersion("Produce v3".to_string()).is_retriable());
+	}
+}
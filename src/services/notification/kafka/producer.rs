@@ -0,0 +1,521 @@
+//! Kafka producer.
+//!
+//! Publishes records one at a time to the leader of the partition of the record, found in the
+//! metadata of the topic. The metadata of the topics and the idle connections to the brokers
+//! are cached per cluster and shared by the producers of all triggers with the same brokers,
+//! SASL and TLS settings. Cached metadata is refreshed once it is a few minutes old or after
+//! a delivery fails because of a stale leader or connection, in which case the record is sent
+//! once more. Connections are authenticated with SASL and encrypted with TLS when configured.
+//!
+//! New connections first ask the broker for the API versions it supports and are refused when it
+//! does not support the request versions of the producer. When a broker throttles a connection,
+//! the next request on it waits until the throttle time of the last response elapsed.
+
+use native_tls::{Certificate, Identity};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
+};
+use tokio::{
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+	net::TcpStream,
+};
+use uuid::Uuid;
+
+use super::{
+	protocol::{
+		decode_api_versions_response, decode_metadata_response, decode_produce_response,
+		encode_metadata_request, encode_produce_request, encode_request, error_name,
+		partition_of_key, Decoder, Encoder, KafkaRecord, Metadata, API_VERSIONS, METADATA, PRODUCE,
+		SASL_AUTHENTICATE, SASL_HANDSHAKE,
+	},
+	sasl::{plain_message, ScramClient},
+	KafkaError,
+};
+use crate::models::{KafkaAcks, KafkaSasl, KafkaSaslMechanism, KafkaTls};
+
+/// ID of the client in the requests
+const CLIENT_ID: &str = "openzeppelin-monitor";
+
+/// Maximum size of a response
+const MAX_RESPONSE_SIZE: usize = 100 * 1024 * 1024;
+
+/// Age after which the metadata of a topic is fetched again
+const METADATA_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// Time after which an idle connection is closed instead of reused
+const CONNECTION_MAX_IDLE: Duration = Duration::from_secs(300);
+
+/// Maximum number of idle connections kept per broker
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// API key, version and name of the requests sent by the producer
+const REQUIRED_VERSIONS: [(i16, i16, &str); 2] =
+	[(PRODUCE, 3, "Produce"), (METADATA, 4, "Metadata")];
+
+/// API key, version and name of the requests sent to authenticate with SASL
+const SASL_VERSIONS: [(i16, i16, &str); 2] = [
+	(SASL_HANDSHAKE, 1, "SaslHandshake"),
+	(SASL_AUTHENTICATE, 1, "SaslAuthenticate"),
+];
+
+/// Partition counter spreading the records without key over the partitions
+static NEXT_PARTITION: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static::lazy_static! {
+	/// Caches of every cluster, keyed by the brokers, SASL and TLS settings of the producers
+	static ref CLUSTERS: Mutex<HashMap<String, Arc<ClusterCache>>> = Mutex::new(HashMap::new());
+}
+
+/// Metadata and idle connections of a cluster
+#[derive(Default)]
+struct ClusterCache {
+	/// Metadata of the topics and the time it was fetched
+	topics: Mutex<HashMap<String, (Metadata, Instant)>>,
+	/// Idle connections and the time they were last used, by broker address
+	connections: Mutex<HashMap<String, Vec<(Connection, Instant)>>>,
+}
+
+/// Byte stream of a connection, plaintext or TLS
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// Connection to a broker
+struct Connection {
+	stream: Box<dyn Stream>,
+	correlation_id: i32,
+	/// Time until which the broker throttles the connection
+	throttled_until: Option<Instant>,
+}
+
+impl Connection {
+	/// Sends a request without waiting for its response
+	///
+	/// # Returns
+	/// * `Result<i32, KafkaError>` - Correlation ID of the request
+	async fn send(
+		&mut self,
+		api_key: i16,
+		api_version: i16,
+		body: &[u8],
+	) -> Result<i32, KafkaError> {
+		if let Some(throttled_until) = self.throttled_until.take() {
+			tokio::time::sleep_until(throttled_until.into()).await;
+		}
+		self.correlation_id = self.correlation_id.wrapping_add(1);
+		let request = encode_request(api_key, api_version, self.correlation_id, CLIENT_ID, body);
+		self.stream.write_all(&request).await?;
+		self.stream.flush().await?;
+		Ok(self.correlation_id)
+	}
+
+	/// Sends a request and returns the body of its response
+	async fn request(
+		&mut self,
+		api_key: i16,
+		api_version: i16,
+		body: &[u8],
+	) -> Result<Vec<u8>, KafkaError> {
+		let correlation_id = self.send(api_key, api_version, body).await?;
+
+		let size = self.stream.read_i32().await?;
+		if size < 4 || size as usize > MAX_RESPONSE_SIZE {
+			return Err(KafkaError::Protocol(format!(
+				"invalid response size {}",
+				size
+			)));
+		}
+		let mut response = vec![0; size as usize];
+		self.stream.read_exact(&mut response).await?;
+		let mut decoder = Decoder::new(&response);
+		if decoder.i32()? != correlation_id {
+			return Err(KafkaError::Protocol(
+				"unexpected correlation ID".to_string(),
+			));
+		}
+		Ok(response.split_off(4))
+	}
+
+	/// Delays the next request for the throttle time of a response
+	fn throttle(&mut self, throttle_time_ms: i32) {
+		if throttle_time_ms > 0 {
+			tracing::debug!(
+				"Kafka broker throttled the connection for {}ms",
+				throttle_time_ms
+			);
+			self.throttled_until =
+				Some(Instant::now() + Duration::from_millis(throttle_time_ms as u64));
+		}
+	}
+
+	/// Checks that the broker supports the request versions sent on the connection
+	async fn negotiate_versions(&mut self, sasl: bool) -> Result<(), KafkaError> {
+		let response = self.request(API_VERSIONS, 0, &[]).await?;
+		let (error_code, versions) = decode_api_versions_response(&response)?;
+		if error_code != 0 {
+			return Err(KafkaError::UnsupportedVersion(format!(
+				"ApiVersions v0 ({})",
+				error_name(error_code).0
+			)));
+		}
+
+		let sasl_versions: &[_] = if sasl { &SASL_VERSIONS } else { &[] };
+		for (api_key, version, name) in REQUIRED_VERSIONS.iter().chain(sasl_versions) {
+			match versions.get(api_key) {
+				Some((min_version, max_version))
+					if (*min_version..=*max_version).contains(version) => {}
+				_ => {
+					return Err(KafkaError::UnsupportedVersion(format!(
+						"{} v{}",
+						name, version
+					)))
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Authenticates the connection with SASL
+	async fn authenticate(&mut self, sasl: &KafkaSasl) -> Result<(), KafkaError> {
+		let mut body = Encoder::new();
+		body.string(sasl.mechanism.name());
+		let response = self.request(SASL_HANDSHAKE, 1, &body.into_bytes()).await?;
+		let error_code = Decoder::new(&response).i16()?;
+		if error_code != 0 {
+			return Err(KafkaError::Authentication(format!(
+				"{} handshake failed with {}",
+				sasl.mechanism.name(),
+				error_name(error_code).0
+			)));
+		}
+
+		match sasl.mechanism {
+			KafkaSaslMechanism::Plain => {
+				self.sasl_authenticate(&plain_message(&sasl.username, &sasl.password))
+					.await?;
+			}
+			KafkaSaslMechanism::ScramSha256 | KafkaSaslMechanism::ScramSha512 => {
+				let mut client = ScramClient::new(
+					sasl.mechanism,
+					&sasl.username,
+					&sasl.password,
+					&Uuid::new_v4().simple().to_string(),
+				);
+				let server_first = self
+					.sasl_authenticate(client.client_first().as_bytes())
+					.await?;
+				let client_final = client.client_final(&String::from_utf8_lossy(&server_first))?;
+				let server_final = self.sasl_authenticate(client_final.as_bytes()).await?;
+				client.verify_server_final(&String::from_utf8_lossy(&server_final))?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Sends a SASL message and returns the message of the broker
+	async fn sasl_authenticate(&mut self, message: &[u8]) -> Result<Vec<u8>, KafkaError> {
+		let mut body = Encoder::new();
+		body.bytes(message);
+		let response = self
+			.request(SASL_AUTHENTICATE, 1, &body.into_bytes())
+			.await?;
+		let mut decoder = Decoder::new(&response);
+		let error_code = decoder.i16()?;
+		let error_message = decoder.nullable_string()?;
+		if error_code != 0 {
+			return Err(KafkaError::Authentication(
+				error_message.unwrap_or_else(|| error_name(error_code).0.to_string()),
+			));
+		}
+		decoder.bytes()
+	}
+}
+
+/// Producer publishing records to the partition leaders of a cluster
+pub struct KafkaProducer {
+	/// Bootstrap brokers as `host:port`
+	brokers: Vec<String>,
+	/// Acknowledgements awaited for every record
+	acks: KafkaAcks,
+	/// Timeout of a delivery
+	timeout: Duration,
+	/// SASL authentication of the connections
+	sasl: Option<KafkaSasl>,
+	/// TLS of the connections
+	tls: Option<KafkaTls>,
+	/// Metadata and idle connections of the cluster
+	cache: Arc<ClusterCache>,
+}
+
+impl KafkaProducer {
+	/// Creates a producer
+	///
+	/// Producers with the same brokers, SASL and TLS settings share the cached metadata and
+	/// connections of the cluster.
+	///
+	/// # Arguments
+	/// * `brokers` - Bootstrap brokers as `host:port`
+	/// * `acks` - Acknowledgements awaited for every record
+	/// * `timeout` - Timeout of a delivery
+	/// * `sasl` - SASL authentication of the connections
+	/// * `tls` - TLS of the connections, plaintext if not set
+	pub fn new(
+		brokers: Vec<String>,
+		acks: KafkaAcks,
+		timeout: Duration,
+		sasl: Option<KafkaSasl>,
+		tls: Option<KafkaTls>,
+	) -> Self {
+		let cluster = serde_json::to_string(&(&brokers, &sasl, &tls)).unwrap_or_default();
+		let cache = CLUSTERS
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.entry(cluster)
+			.or_default()
+			.clone();
+
+		Self {
+			brokers,
+			acks,
+			timeout,
+			sasl,
+			tls,
+			cache,
+		}
+	}
+
+	/// Publishes a record to a topic
+	///
+	/// Records with a key are published to the partition of the hash of their key, records
+	/// without key are spread over the partitions.
+	///
+	/// # Arguments
+	/// * `topic` - Topic to publish to
+	/// * `record` - Record to publish
+	///
+	/// # Returns
+	/// * `Result<(), KafkaError>` - Success once the record is acknowledged as configured
+	pub async fn produce(&self, topic: &str, record: KafkaRecord) -> Result<(), KafkaError> {
+		tokio::time::timeout(self.timeout, self.produce_record(topic, &record))
+			.await
+			.map_err(|_| KafkaError::Timeout(self.timeout.as_millis() as u64))?
+	}
+
+	async fn produce_record(&self, topic: &str, record: &KafkaRecord) -> Result<(), KafkaError> {
+		match self.send_record(topic, record).await {
+			// The leader may have moved or a broker may have closed an idle connection since
+			// they were cached, so the record is sent once more with fresh ones
+			Err(e) if is_stale(&e) => {
+				tracing::debug!("Retrying Kafka delivery with fresh metadata: {}", e);
+				self.cache
+					.topics
+					.lock()
+					.unwrap_or_else(|e| e.into_inner())
+					.remove(topic);
+				self.cache
+					.connections
+					.lock()
+					.unwrap_or_else(|e| e.into_inner())
+					.clear();
+				self.send_record(topic, record).await
+			}
+			result => result,
+		}
+	}
+
+	/// Sends a record to the leader of its partition
+	async fn send_record(&self, topic: &str, record: &KafkaRecord) -> Result<(), KafkaError> {
+		let metadata = self.metadata(topic).await?;
+		let index = match &record.key {
+			Some(key) => partition_of_key(key, metadata.partitions.len()),
+			None => NEXT_PARTITION.fetch_add(1, Ordering::Relaxed) % metadata.partitions.len(),
+		};
+		let partition = &metadata.partitions[index];
+		let leader = metadata
+			.brokers
+			.iter()
+			.find(|broker| partition.leader >= 0 && broker.node_id == partition.leader)
+			.ok_or_else(|| KafkaError::broker(5))?;
+		let leader_address = format!("{}:{}", leader.host, leader.port);
+		let mut connection = self.checkout(&leader_address).await?;
+
+		let body = encode_produce_request(
+			topic,
+			partition.index,
+			self.acks.as_i16(),
+			self.timeout.as_millis().min(i32::MAX as u128) as i32,
+			std::slice::from_ref(record),
+		);
+		if self.acks == KafkaAcks::None {
+			// Brokers do not respond to produce requests without acknowledgement
+			connection.send(PRODUCE, 3, &body).await?;
+			self.checkin(&leader_address, connection);
+			return Ok(());
+		}
+		let response = connection.request(PRODUCE, 3, &body).await?;
+		let (error_code, offset, throttle_time_ms) = decode_produce_response(&response)?;
+		connection.throttle(throttle_time_ms);
+		self.checkin(&leader_address, connection);
+		check(error_code)?;
+		tracing::debug!(
+			"Published Kafka record to {}-{} at offset {}",
+			topic,
+			partition.index,
+			offset
+		);
+		Ok(())
+	}
+
+	/// Returns the metadata of a topic, fetched from the first reachable bootstrap broker
+	/// unless it is cached
+	async fn metadata(&self, topic: &str) -> Result<Metadata, KafkaError> {
+		if let Some((metadata, fetched_at)) = self
+			.cache
+			.topics
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.get(topic)
+		{
+			if fetched_at.elapsed() < METADATA_MAX_AGE {
+				return Ok(metadata.clone());
+			}
+		}
+
+		let mut last_error = None;
+		for address in &self.brokers {
+			let mut connection = match self.checkout(address).await {
+				Ok(connection) => connection,
+				Err(e) => {
+					tracing::warn!("Failed to connect to Kafka broker {}: {}", address, e);
+					last_error = Some(e);
+					continue;
+				}
+			};
+			let response = connection
+				.request(METADATA, 4, &encode_metadata_request(topic))
+				.await?;
+			let (metadata, throttle_time_ms) = decode_metadata_response(&response, topic)?;
+			connection.throttle(throttle_time_ms);
+			self.checkin(address, connection);
+
+			check(metadata.error_code)?;
+			if metadata.partitions.is_empty() {
+				return Err(KafkaError::broker(3));
+			}
+			self.cache
+				.topics
+				.lock()
+				.unwrap_or_else(|e| e.into_inner())
+				.insert(topic.to_string(), (metadata.clone(), Instant::now()));
+			return Ok(metadata);
+		}
+		Err(last_error.unwrap_or_else(|| KafkaError::Connection("no broker".to_string())))
+	}
+
+	/// Takes an idle connection to a broker, or opens one if there is none
+	///
+	/// Connections are only returned with [`Self::checkin`] once their request completed, so
+	/// connections of failed or timed out requests are closed.
+	async fn checkout(&self, address: &str) -> Result<Connection, KafkaError> {
+		let idle = {
+			let mut connections = self
+				.cache
+				.connections
+				.lock()
+				.unwrap_or_else(|e| e.into_inner());
+			let idle = connections.entry(address.to_string()).or_default();
+			idle.retain(|(_, last_used)| last_used.elapsed() < CONNECTION_MAX_IDLE);
+			idle.pop()
+		};
+		match idle {
+			Some((connection, _)) => Ok(connection),
+			None => self.connect(address).await,
+		}
+	}
+
+	/// Keeps a connection to a broker for the next requests
+	fn checkin(&self, address: &str, connection: Connection) {
+		let mut connections = self
+			.cache
+			.connections
+			.lock()
+			.unwrap_or_else(|e| e.into_inner());
+		let idle = connections.entry(address.to_string()).or_default();
+		if idle.len() < MAX_IDLE_CONNECTIONS {
+			idle.push((connection, Instant::now()));
+		}
+	}
+
+	/// Opens an authenticated connection to a broker
+	async fn connect(&self, address: &str) -> Result<Connection, KafkaError> {
+		let stream = TcpStream::connect(address).await?;
+		let stream: Box<dyn Stream> = match &self.tls {
+			Some(tls) => {
+				let host = address
+					.rsplit_once(':')
+					.map_or(address, |(host, _)| host)
+					.trim_start_matches('[')
+					.trim_end_matches(']');
+				let stream = tls_connector(tls)?
+					.connect(host, stream)
+					.await
+					.map_err(|e| KafkaError::Connection(format!("TLS handshake failed: {}", e)))?;
+				Box::new(stream)
+			}
+			None => Box::new(stream),
+		};
+
+		let mut connection = Connection {
+			stream,
+			correlation_id: 0,
+			throttled_until: None,
+		};
+		connection.negotiate_versions(self.sasl.is_some()).await?;
+		if let Some(sasl) = &self.sasl {
+			connection.authenticate(sasl).await?;
+		}
+		Ok(connection)
+	}
+}
+
+/// Builds the TLS connector of the connections
+fn tls_connector(tls: &KafkaTls) -> Result<tokio_native_tls::TlsConnector, KafkaError> {
+	let read = |path: &str| {
+		std::fs::read(path).map_err(|e| KafkaError::Tls(format!("failed to read {}: {}", path, e)))
+	};
+	let invalid = |e: native_tls::Error| KafkaError::Tls(e.to_string());
+
+	let mut builder = native_tls::TlsConnector::builder();
+	if let Some(path) = &tls.ca_path {
+		builder.add_root_certificate(Certificate::from_pem(&read(path)?).map_err(invalid)?);
+	}
+	if let (Some(cert_path), Some(key_path)) = (&tls.cert_path, &tls.key_path) {
+		builder
+			.identity(Identity::from_pkcs8(&read(cert_path)?, &read(key_path)?).map_err(invalid)?);
+	}
+	Ok(builder.build().map_err(invalid)?.into())
+}
+
+/// Returns whether a delivery may have failed because of stale metadata or connections
+fn is_stale(error: &KafkaError) -> bool {
+	match error {
+		KafkaError::Connection(_) | KafkaError::Protocol(_) => true,
+		// UNKNOWN_TOPIC_OR_PARTITION, LEADER_NOT_AVAILABLE, NOT_LEADER_OR_FOLLOWER,
+		// FENCED_LEADER_EPOCH and UNKNOWN_LEADER_EPOCH
+		KafkaError::Broker { code, .. } => matches!(code, 3 | 5 | 6 | 74 | 75),
+		_ => false,
+	}
+}
+
+/// Converts a non-zero error code of a response into an error
+fn check(error_code: i16) -> Result<(), KafkaError> {
+	match error_code {
+		0 => Ok(()),
+		code => Err(KafkaError::broker(code)),
+	}
+}
@@ -0,0 +1,695 @@
+//! Kafka wire protocol.
+//!
+//! Encodes the requests and decodes the responses of the few API versions used by the producer:
+//! ApiVersions v0, Metadata v4, Produce v3, SaslHandshake v1 and SaslAuthenticate v1. Records are
+//! written as uncompressed v2 record batches. The throttle time of Metadata and Produce responses
+//! is returned to the caller, which must not send requests to the broker before it elapsed.
+
+use std::collections::HashMap;
+
+use super::KafkaError;
+
+/// API key of the Produce requests
+pub const PRODUCE: i16 = 0;
+
+/// API key of the Metadata requests
+pub const METADATA: i16 = 3;
+
+/// API key of the SaslHandshake requests
+pub const SASL_HANDSHAKE: i16 = 17;
+
+/// API key of the ApiVersions requests
+pub const API_VERSIONS: i16 = 18;
+
+/// API key of the SaslAuthenticate requests
+pub const SASL_AUTHENTICATE: i16 = 36;
+
+/// Record of a topic partition
+#[derive(Debug, Clone, PartialEq)]
+pub struct KafkaRecord {
+	/// Key of the record, used to choose its partition
+	pub key: Option<Vec<u8>>,
+	/// Value of the record
+	pub value: Vec<u8>,
+	/// Headers of the record
+	pub headers: Vec<(String, Vec<u8>)>,
+	/// Creation time of the record in milliseconds
+	pub timestamp: i64,
+}
+
+/// Broker of a cluster
+#[derive(Debug, Clone, PartialEq)]
+pub struct Broker {
+	/// Node ID of the broker
+	pub node_id: i32,
+	/// Host of the broker
+	pub host: String,
+	/// Port of the broker
+	pub port: i32,
+}
+
+/// Partition of a topic
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partition {
+	/// Index of the partition
+	pub index: i32,
+	/// Node ID of the leader of the partition, -1 without leader
+	pub leader: i32,
+}
+
+/// Metadata of a topic and the brokers hosting it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metadata {
+	/// Brokers of the cluster
+	pub brokers: Vec<Broker>,
+	/// Error code of the topic
+	pub error_code: i16,
+	/// Partitions of the topic
+	pub partitions: Vec<Partition>,
+}
+
+/// Writer of protocol primitives
+#[derive(Debug, Default)]
+pub struct Encoder {
+	buf: Vec<u8>,
+}
+
+impl Encoder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn i8(&mut self, value: i8) -> &mut Self {
+		self.buf.push(value as u8);
+		self
+	}
+
+	pub fn i16(&mut self, value: i16) -> &mut Self {
+		self.buf.extend_from_slice(&value.to_be_bytes());
+		self
+	}
+
+	pub fn i32(&mut self, value: i32) -> &mut Self {
+		self.buf.extend_from_slice(&value.to_be_bytes());
+		self
+	}
+
+	pub fn i64(&mut self, value: i64) -> &mut Self {
+		self.buf.extend_from_slice(&value.to_be_bytes());
+		self
+	}
+
+	/// Writes a string prefixed by its 16-bit length
+	pub fn string(&mut self, value: &str) -> &mut Self {
+		self.i16(value.len() as i16);
+		self.raw(value.as_bytes())
+	}
+
+	/// Writes a string prefixed by its 16-bit length, -1 for null
+	pub fn nullable_string(&mut self, value: Option<&str>) -> &mut Self {
+		match value {
+			Some(value) => self.string(value),
+			None => self.i16(-1),
+		}
+	}
+
+	/// Writes bytes prefixed by their 32-bit length
+	pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+		self.i32(value.len() as i32);
+		self.raw(value)
+	}
+
+	/// Writes a zigzag variable-length integer
+	pub fn varint(&mut self, value: i64) -> &mut Self {
+		let mut value = ((value << 1) ^ (value >> 63)) as u64;
+		while value >= 0x80 {
+			self.buf.push((value as u8) | 0x80);
+			value >>= 7;
+		}
+		self.buf.push(value as u8);
+		self
+	}
+
+	/// Writes bytes prefixed by their variable length, -1 for null
+	pub fn varbytes(&mut self, value: Option<&[u8]>) -> &mut Self {
+		match value {
+			Some(value) => {
+				self.varint(value.len() as i64);
+				self.raw(value)
+			}
+			None => self.varint(-1),
+		}
+	}
+
+	pub fn raw(&mut self, value: &[u8]) -> &mut Self {
+		self.buf.extend_from_slice(value);
+		self
+	}
+
+	pub fn len(&self) -> usize {
+		self.buf.len()
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.buf
+	}
+}
+
+/// Reader of protocol primitives
+pub struct Decoder<'a> {
+	buf: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+	pub fn new(buf: &'a [u8]) -> Self {
+		Self { buf }
+	}
+
+	fn take(&mut self, length: usize) -> Result<&'a [u8], KafkaError> {
+		if self.buf.len() < length {
+			return Err(KafkaError::Protocol("truncated response".to_string()));
+		}
+		let (value, rest) = self.buf.split_at(length);
+		self.buf = rest;
+		Ok(value)
+	}
+
+	pub fn i8(&mut self) -> Result<i8, KafkaError> {
+		Ok(self.take(1)?[0] as i8)
+	}
+
+	pub fn i16(&mut self) -> Result<i16, KafkaError> {
+		Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+	}
+
+	pub fn i32(&mut self) -> Result<i32, KafkaError> {
+		Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+	}
+
+	pub fn i64(&mut self) -> Result<i64, KafkaError> {
+		Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+	}
+
+	/// Reads a string prefixed by its 16-bit length, None for null
+	pub fn nullable_string(&mut self) -> Result<Option<String>, KafkaError> {
+		let length = self.i16()?;
+		if length < 0 {
+			return Ok(None);
+		}
+		let value = self.take(length as usize)?;
+		String::from_utf8(value.to_vec())
+			.map(Some)
+			.map_err(|_| KafkaError::Protocol("invalid string".to_string()))
+	}
+
+	pub fn string(&mut self) -> Result<String, KafkaError> {
+		Ok(self.nullable_string()?.unwrap_or_default())
+	}
+
+	/// Reads bytes prefixed by their 32-bit length, empty for null
+	pub fn bytes(&mut self) -> Result<Vec<u8>, KafkaError> {
+		let length = self.i32()?;
+		if length < 0 {
+			return Ok(Vec::new());
+		}
+		Ok(self.take(length as usize)?.to_vec())
+	}
+
+	/// Reads the length of an array, 0 for null
+	pub fn array_len(&mut self) -> Result<usize, KafkaError> {
+		Ok(self.i32()?.max(0) as usize)
+	}
+}
+
+/// Frames a request with its size and header
+///
+/// # Arguments
+/// * `api_key` - API of the request
+/// * `api_version` - Version of the API
+/// * `correlation_id` - ID matching the response to the request
+/// * `client_id` - ID of the client
+/// * `body` - Encoded body of the request
+pub fn encode_request(
+	api_key: i16,
+	api_version: i16,
+	correlation_id: i32,
+	client_id: &str,
+	body: &[u8],
+) -> Vec<u8> {
+	let mut request = Encoder::new();
+	request
+		.i16(api_key)
+		.i16(api_version)
+		.i32(correlation_id)
+		.nullable_string(Some(client_id))
+		.raw(body);
+	let request = request.into_bytes();
+
+	let mut frame = Encoder::new();
+	frame.bytes(&request);
+	frame.into_bytes()
+}
+
+/// Encodes the body of a Metadata v4 request of a topic
+pub fn encode_metadata_request(topic: &str) -> Vec<u8> {
+	let mut body = Encoder::new();
+	body.i32(1).string(topic).i8(1);
+	body.into_bytes()
+}
+
+/// Minimum and maximum versions supported by a broker, keyed by API key
+pub type ApiVersions = HashMap<i16, (i16, i16)>;
+
+/// Decodes the body of an ApiVersions v0 response into its error code and the versions the broker
+/// supports
+pub fn decode_api_versions_response(body: &[u8]) -> Result<(i16, ApiVersions), KafkaError> {
+	let mut decoder = Decoder::new(body);
+	let error_code = decoder.i16()?;
+	let mut versions = HashMap::new();
+	for _ in 0..decoder.array_len()? {
+		let api_key = decoder.i16()?;
+		let min_version = decoder.i16()?;
+		let max_version = decoder.i16()?;
+		versions.insert(api_key, (min_version, max_version));
+	}
+	Ok((error_code, versions))
+}
+
+/// Decodes the body of a Metadata v4 response, keeping the metadata of the given topic
+///
+/// # Returns
+/// * `Result<(Metadata, i32), KafkaError>` - Metadata of the topic and throttle time in
+///   milliseconds
+pub fn decode_metadata_response(body: &[u8], topic: &str) -> Result<(Metadata, i32), KafkaError> {
+	let mut decoder = Decoder::new(body);
+	let throttle_time_ms = decoder.i32()?;
+
+	let mut brokers = Vec::new();
+	for _ in 0..decoder.array_len()? {
+		let node_id = decoder.i32()?;
+		let host = decoder.string()?;
+		let port = decoder.i32()?;
+		decoder.nullable_string()?; // rack
+		brokers.push(Broker {
+			node_id,
+			host,
+			port,
+		});
+	}
+	decoder.nullable_string()?; // cluster ID
+	decoder.i32()?; // controller ID
+
+	for _ in 0..decoder.array_len()? {
+		let error_code = decoder.i16()?;
+		let name = decoder.string()?;
+		decoder.i8()?; // is internal
+		let mut partitions = Vec::new();
+		for _ in 0..decoder.array_len()? {
+			decoder.i16()?; // error code, a partition without leader is not available
+			let index = decoder.i32()?;
+			let leader = decoder.i32()?;
+			for _ in 0..2 {
+				// replicas and in-sync replicas
+				for _ in 0..decoder.array_len()? {
+					decoder.i32()?;
+				}
+			}
+			partitions.push(Partition { index, leader });
+		}
+		if name == topic {
+			partitions.sort_by_key(|partition| partition.index);
+			let metadata = Metadata {
+				brokers,
+				error_code,
+				partitions,
+			};
+			return Ok((metadata, throttle_time_ms));
+		}
+	}
+	Err(KafkaError::Protocol(format!(
+		"metadata response without topic {}",
+		topic
+	)))
+}
+
+/// Encodes the body of a Produce v3 request of records of a topic partition
+pub fn encode_produce_request(
+	topic: &str,
+	partition: i32,
+	acks: i16,
+	timeout_ms: i32,
+	records: &[KafkaRecord],
+) -> Vec<u8> {
+	let mut body = Encoder::new();
+	body.nullable_string(None) // transactional ID
+		.i16(acks)
+		.i32(timeout_ms)
+		.i32(1)
+		.string(topic)
+		.i32(1)
+		.i32(partition)
+		.bytes(&encode_record_batch(records));
+	body.into_bytes()
+}
+
+/// Decodes the body of a Produce v3 response into the error code of the partition, the offset of
+/// the record and the throttle time in milliseconds
+pub fn decode_produce_response(body: &[u8]) -> Result<(i16, i64, i32), KafkaError> {
+	// The request holds a single partition of a single topic
+	let mut decoder = Decoder::new(body);
+	if decoder.array_len()? == 0 {
+		return Err(KafkaError::Protocol(
+			"produce response without topic".to_string(),
+		));
+	}
+	decoder.string()?; // topic
+	if decoder.array_len()? == 0 {
+		return Err(KafkaError::Protocol(
+			"produce response without partition".to_string(),
+		));
+	}
+	decoder.i32()?; // partition
+	let error_code = decoder.i16()?;
+	let offset = decoder.i64()?;
+	decoder.i64()?; // log append time
+	Ok((error_code, offset, decoder.i32()?))
+}
+
+/// Encodes records into a v2 record batch
+pub fn encode_record_batch(records: &[KafkaRecord]) -> Vec<u8> {
+	let base_timestamp = records.first().map_or(0, |record| record.timestamp);
+	let max_timestamp = records
+		.iter()
+		.map(|record| record.timestamp)
+		.max()
+		.unwrap_or(base_timestamp);
+
+	// Part of the batch covered by its checksum
+	let mut batch = Encoder::new();
+	batch
+		.i16(0) // attributes
+		.i32(records.len() as i32 - 1) // last offset delta
+		.i64(base_timestamp)
+		.i64(max_timestamp)
+		.i64(-1) // producer ID
+		.i16(-1) // producer epoch
+		.i32(-1) // base sequence
+		.i32(records.len() as i32);
+	for (offset, record) in records.iter().enumerate() {
+		let mut encoded = Encoder::new();
+		encoded
+			.i8(0) // attributes
+			.varint(record.timestamp - base_timestamp)
+			.varint(offset as i64)
+			.varbytes(record.key.as_deref())
+			.varbytes(Some(&record.value))
+			.varint(record.headers.len() as i64);
+		for (name, value) in &record.headers {
+			encoded
+				.varbytes(Some(name.as_bytes()))
+				.varbytes(Some(value));
+		}
+		batch
+			.varint(encoded.len() as i64)
+			.raw(&encoded.into_bytes());
+	}
+	let batch = batch.into_bytes();
+
+	let mut encoded = Encoder::new();
+	encoded
+		.i64(0) // base offset
+		.i32(batch.len() as i32 + 9) // length after this field
+		.i32(-1) // partition leader epoch
+		.i8(2) // magic
+		.raw(&crc32c(&batch).to_be_bytes())
+		.raw(&batch);
+	encoded.into_bytes()
+}
+
+/// Computes the CRC-32C checksum of record batches
+pub fn crc32c(data: &[u8]) -> u32 {
+	let mut crc = !0u32;
+	for byte in data {
+		crc ^= *byte as u32;
+		for _ in 0..8 {
+			crc = if crc & 1 == 1 {
+				(crc >> 1) ^ 0x82F6_3B78
+			} else {
+				crc >> 1
+			};
+		}
+	}
+	!crc
+}
+
+/// Computes the murmur2 hash of keys, as the default partitioner of the Java client
+pub fn murmur2(data: &[u8]) -> i32 {
+	const SEED: u32 = 0x9747_B28C;
+	const M: u32 = 0x5BD1_E995;
+
+	let mut hash = SEED ^ data.len() as u32;
+	let chunks = data.chunks_exact(4);
+	let rest = chunks.remainder();
+	for chunk in chunks {
+		let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+		k = k.wrapping_mul(M);
+		k ^= k >> 24;
+		k = k.wrapping_mul(M);
+		hash = hash.wrapping_mul(M) ^ k;
+	}
+	if rest.len() >= 3 {
+		hash ^= (rest[2] as u32) << 16;
+	}
+	if rest.len() >= 2 {
+		hash ^= (rest[1] as u32) << 8;
+	}
+	if !rest.is_empty() {
+		hash ^= rest[0] as u32;
+		hash = hash.wrapping_mul(M);
+	}
+	hash ^= hash >> 13;
+	hash = hash.wrapping_mul(M);
+	hash ^= hash >> 15;
+	hash as i32
+}
+
+/// Returns the partition of a key, as the default partitioner of the Java client
+pub fn partition_of_key(key: &[u8], partitions: usize) -> usize {
+	(murmur2(key) & 0x7FFF_FFFF) as usize % partitions
+}
+
+/// Returns the name of an error code and whether the request can be retried
+pub fn error_name(code: i16) -> (&'static str, bool) {
+	match code {
+		1 => ("OFFSET_OUT_OF_RANGE", false),
+		2 => ("CORRUPT_MESSAGE", true),
+		3 => ("UNKNOWN_TOPIC_OR_PARTITION", true),
+		5 => ("LEADER_NOT_AVAILABLE", true),
+		6 => ("NOT_LEADER_OR_FOLLOWER", true),
+		7 => ("REQUEST_TIMED_OUT", true),
+		8 => ("BROKER_NOT_AVAILABLE", true),
+		9 => ("REPLICA_NOT_AVAILABLE", true),
+		10 => ("MESSAGE_TOO_LARGE", false),
+		13 => ("NETWORK_EXCEPTION", true),
+		14 => ("COORDINATOR_LOAD_IN_PROGRESS", true),
+		15 => ("COORDINATOR_NOT_AVAILABLE", true),
+		17 => ("INVALID_TOPIC_EXCEPTION", false),
+		18 => ("RECORD_LIST_TOO_LARGE", false),
+		19 => ("NOT_ENOUGH_REPLICAS", true),
+		20 => ("NOT_ENOUGH_REPLICAS_AFTER_APPEND", true),
+		21 => ("INVALID_REQUIRED_ACKS", false),
+		29 => ("TOPIC_AUTHORIZATION_FAILED", false),
+		31 => ("CLUSTER_AUTHORIZATION_FAILED", false),
+		33 => ("UNSUPPORTED_SASL_MECHANISM", false),
+		34 => ("ILLEGAL_SASL_STATE", false),
+		35 => ("UNSUPPORTED_VERSION", false),
+		43 => ("UNSUPPORTED_FOR_MESSAGE_FORMAT", false),
+		44 => ("POLICY_VIOLATION", false),
+		56 => ("KAFKA_STORAGE_ERROR", true),
+		58 => ("SASL_AUTHENTICATION_FAILED", false),
+		74 => ("FENCED_LEADER_EPOCH", true),
+		75 => ("UNKNOWN_LEADER_EPOCH", true),
+		87 => ("INVALID_RECORD", false),
+		_ => ("UNKNOWN_SERVER_ERROR", false),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn decode_varint(buf: &[u8]) -> (i64, usize) {
+		let mut value = 0u64;
+		for (index, byte) in buf.iter().enumerate() {
+			value |= ((byte & 0x7F) as u64) << (7 * index);
+			if byte & 0x80 == 0 {
+				return (((value >> 1) as i64) ^ -((value & 1) as i64), index + 1);
+			}
+		}
+		panic!("unterminated varint");
+	}
+
+	#[test]
+	fn test_varint() {
+		for (value, encoded) in [
+			(0, vec![0x00]),
+			(-1, vec![0x01]),
+			(1, vec![0x02]),
+			(63, vec![0x7E]),
+			(-64, vec![0x7F]),
+			(64, vec![0x80, 0x01]),
+			(300, vec![0xD8, 0x04]),
+		] {
+			let mut encoder = Encoder::new();
+			encoder.varint(value);
+			assert_eq!(encoder.into_bytes(), encoded);
+			assert_eq!(decode_varint(&encoded), (value, encoded.len()));
+		}
+	}
+
+	#[test]
+	fn test_crc32c() {
+		assert_eq!(crc32c(b""), 0);
+		assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+	}
+
+	#[test]
+	fn test_murmur2() {
+		// Test vectors of the Java client
+		for (key, hash) in [
+			("21", -973932308),
+			("foobar", -790332482),
+			("a-little-bit-long-string", -985981536),
+			("a-little-bit-longer-string", -1486304829),
+			(
+				"lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8",
+				-58897971,
+			),
+			("abc", 479470107),
+		] {
+			assert_eq!(murmur2(key.as_bytes()), hash, "key {}", key);
+		}
+		assert!(partition_of_key(b"21", 3) < 3);
+	}
+
+	#[test]
+	fn test_encode_record_batch() {
+		let batch = encode_record_batch(&[KafkaRecord {
+			key: Some(b"key".to_vec()),
+			value: b"value".to_vec(),
+			headers: vec![("name".to_string(), b"header".to_vec())],
+			timestamp: 1_700_000_000_000,
+		}]);
+
+		let mut decoder = Decoder::new(&batch);
+		assert_eq!(decoder.i64().unwrap(), 0);
+		assert_eq!(decoder.i32().unwrap() as usize, batch.len() - 12);
+		assert_eq!(decoder.i32().unwrap(), -1);
+		assert_eq!(decoder.i8().unwrap(), 2);
+		assert_eq!(decoder.i32().unwrap() as u32, crc32c(&batch[21..]));
+		assert_eq!(decoder.i16().unwrap(), 0);
+		assert_eq!(decoder.i32().unwrap(), 0);
+		assert_eq!(decoder.i64().unwrap(), 1_700_000_000_000);
+		assert_eq!(decoder.i64().unwrap(), 1_700_000_000_000);
+		assert_eq!(decoder.i64().unwrap(), -1);
+		assert_eq!(decoder.i16().unwrap(), -1);
+		assert_eq!(decoder.i32().unwrap(), -1);
+		assert_eq!(decoder.i32().unwrap(), 1);
+
+		let record = &batch[61..];
+		let (length, size) = decode_varint(record);
+		assert_eq!(size + length as usize, record.len());
+		assert_eq!(
+			&record[size..],
+			[
+				&[0x00, 0x00, 0x00, 0x06][..],
+				b"key",
+				&[0x0A],
+				b"value",
+				&[0x02, 0x08],
+				b"name",
+				&[0x0C],
+				b"header"
+			]
+			.concat()
+		);
+	}
+
+	#[test]
+	fn test_decode_api_versions_response() {
+		let mut body = Encoder::new();
+		body.i16(0)
+			.i32(2)
+			.i16(PRODUCE)
+			.i16(0)
+			.i16(9)
+			.i16(METADATA)
+			.i16(0)
+			.i16(12);
+
+		let (error_code, versions) = decode_api_versions_response(&body.into_bytes()).unwrap();
+		assert_eq!(error_code, 0);
+		assert_eq!(versions[&PRODUCE], (0, 9));
+		assert_eq!(versions[&METADATA], (0, 12));
+		assert!(!versions.contains_key(&SASL_HANDSHAKE));
+	}
+
+	#[test]
+	fn test_decode_produce_response() {
+		let mut body = Encoder::new();
+		body.i32(1)
+			.string("alerts")
+			.i32(1)
+			.i32(0)
+			.i16(0)
+			.i64(42)
+			.i64(-1)
+			.i32(250);
+
+		assert_eq!(
+			decode_produce_response(&body.into_bytes()).unwrap(),
+			(0, 42, 250)
+		);
+	}
+
+	#[test]
+	fn test_decode_metadata_response() {
+		let mut body = Encoder::new();
+		body.i32(0)
+			.i32(1)
+			.i32(1)
+			.string("localhost")
+			.i32(9092)
+			.nullable_string(None)
+			.nullable_string(Some("cluster"))
+			.i32(1)
+			.i32(1)
+			.i16(0)
+			.string("alerts")
+			.i8(0)
+			.i32(2);
+		for index in [1, 0] {
+			body.i16(0).i32(index).i32(1).i32(1).i32(1).i32(1).i32(1);
+		}
+
+		let (metadata, throttle_time_ms) =
+			decode_metadata_response(&body.into_bytes(), "alerts").unwrap();
+		assert_eq!(throttle_time_ms, 0);
+		assert_eq!(
+			metadata.brokers,
+			vec![Broker {
+				node_id: 1,
+				host: "localhost".to_string(),
+				port: 9092,
+			}]
+		);
+		assert_eq!(
+			metadata
+				.partitions
+				.iter()
+				.map(|partition| partition.index)
+				.collect::<Vec<_>>(),
+			vec![0, 1]
+		);
+	}
+}
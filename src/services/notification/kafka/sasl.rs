@@ -0,0 +1,211 @@
+//! SASL authentication of Kafka connections.
+//!
+//! Implements the client side of the PLAIN mechanism and of the SCRAM mechanisms of RFC 5802
+//! with SHA-256 and SHA-512.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+
+use super::KafkaError;
+use crate::models::KafkaSaslMechanism;
+
+/// Returns the message authenticating a user with the PLAIN mechanism
+pub fn plain_message(username: &str, password: &str) -> Vec<u8> {
+	format!("\0{}\0{}", username, password).into_bytes()
+}
+
+/// Client side of a SCRAM exchange
+pub struct ScramClient {
+	/// SCRAM mechanism, either SHA-256 or SHA-512
+	mechanism: KafkaSaslMechanism,
+	/// Password of the user
+	password: String,
+	/// Nonce of the client
+	nonce: String,
+	/// First message of the client, without its GS2 header
+	client_first_bare: String,
+	/// Signature expected from the server once the proof is sent
+	server_signature: Option<Vec<u8>>,
+}
+
+impl ScramClient {
+	/// Creates a SCRAM exchange for a user
+	///
+	/// # Arguments
+	/// * `mechanism` - SCRAM mechanism
+	/// * `username` - Name of the user
+	/// * `password` - Password of the user
+	/// * `nonce` - Random nonce of the client
+	pub fn new(mechanism: KafkaSaslMechanism, username: &str, password: &str, nonce: &str) -> Self {
+		let username = username.replace('=', "=3D").replace(',', "=2C");
+		Self {
+			mechanism,
+			password: password.to_string(),
+			nonce: nonce.to_string(),
+			client_first_bare: format!("n={},r={}", username, nonce),
+			server_signature: None,
+		}
+	}
+
+	/// Returns the first message of the client
+	pub fn client_first(&self) -> String {
+		format!("n,,{}", self.client_first_bare)
+	}
+
+	/// Returns the final message of the client, proving the password to the server
+	///
+	/// # Arguments
+	/// * `server_first` - First message of the server, with its nonce, salt and iterations
+	pub fn client_final(&mut self, server_first: &str) -> Result<String, KafkaError> {
+		let attribute = |name: &str| {
+			server_first
+				.split(',')
+				.find_map(|attribute| attribute.strip_prefix(name))
+				.ok_or_else(|| KafkaError::Authentication("invalid SCRAM server message".into()))
+		};
+		let nonce = attribute("r=")?;
+		if !nonce.starts_with(&self.nonce) {
+			return Err(KafkaError::Authentication(
+				"SCRAM server nonce does not extend the client nonce".into(),
+			));
+		}
+		let salt = STANDARD
+			.decode(attribute("s=")?)
+			.map_err(|_| KafkaError::Authentication("invalid SCRAM salt".into()))?;
+		let iterations: u32 = attribute("i=")?
+			.parse()
+			.map_err(|_| KafkaError::Authentication("invalid SCRAM iterations".into()))?;
+
+		let salted_password = self.salted_password(&salt, iterations);
+		let client_key = self.hmac(&salted_password, b"Client Key");
+		let stored_key = self.hash(&client_key);
+		let client_final_without_proof = format!("c=biws,r={}", nonce);
+		let auth_message = format!(
+			"{},{},{}",
+			self.client_first_bare, server_first, client_final_without_proof
+		);
+		let client_signature = self.hmac(&stored_key, auth_message.as_bytes());
+		let proof: Vec<u8> = client_key
+			.iter()
+			.zip(&client_signature)
+			.map(|(key, signature)| key ^ signature)
+			.collect();
+
+		let server_key = self.hmac(&salted_password, b"Server Key");
+		self.server_signature = Some(self.hmac(&server_key, auth_message.as_bytes()));
+		Ok(format!(
+			"{},p={}",
+			client_final_without_proof,
+			STANDARD.encode(proof)
+		))
+	}
+
+	/// Verifies the final message of the server, proving it knows the password
+	pub fn verify_server_final(&self, server_final: &str) -> Result<(), KafkaError> {
+		if let Some(error) = server_final.strip_prefix("e=") {
+			return Err(KafkaError::Authentication(format!(
+				"SCRAM authentication failed: {}",
+				error
+			)));
+		}
+		let signature = server_final
+			.strip_prefix("v=")
+			.and_then(|signature| STANDARD.decode(signature).ok());
+		if signature.is_none() || signature != self.server_signature {
+			return Err(KafkaError::Authentication(
+				"invalid SCRAM server signature".into(),
+			));
+		}
+		Ok(())
+	}
+
+	/// Derives the salted password with PBKDF2
+	fn salted_password(&self, salt: &[u8], iterations: u32) -> Vec<u8> {
+		let password = self.password.as_bytes();
+		let mut block = self.hmac(password, &[salt, &1u32.to_be_bytes()].concat());
+		let mut result = block.clone();
+		for _ in 1..iterations {
+			block = self.hmac(password, &block);
+			for (byte, value) in result.iter_mut().zip(&block) {
+				*byte ^= value;
+			}
+		}
+		result
+	}
+
+	fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
+		match self.mechanism {
+			KafkaSaslMechanism::ScramSha512 => {
+				let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts any key");
+				mac.update(data);
+				mac.finalize().into_bytes().to_vec()
+			}
+			_ => {
+				let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key");
+				mac.update(data);
+				mac.finalize().into_bytes().to_vec()
+			}
+		}
+	}
+
+	fn hash(&self, data: &[u8]) -> Vec<u8> {
+		match self.mechanism {
+			KafkaSaslMechanism::ScramSha512 => Sha512::digest(data).to_vec(),
+			_ => Sha256::digest(data).to_vec(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_plain_message() {
+		assert_eq!(plain_message("user", "pass"), b"\0user\0pass");
+	}
+
+	#[test]
+	fn test_scram_sha256_exchange() {
+		// Example exchange of RFC 7677
+		let mut client = ScramClient::new(
+			KafkaSaslMechanism::ScramSha256,
+			"user",
+			"pencil",
+			"rOprNGfwEbeRWgbNEkqO",
+		);
+		assert_eq!(client.client_first(), "n,,n=user,r=rOprNGfwEbeRWgbNEkqO");
+
+		let client_final = client
+			.client_final(
+				"r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+				 s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096",
+			)
+			.unwrap();
+		assert_eq!(
+			client_final,
+			"c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+			 p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+		);
+		assert!(client
+			.verify_server_final("v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=")
+			.is_ok());
+		assert!(client.verify_server_final("v=AAAA").is_err());
+		assert!(client.verify_server_final("e=invalid-proof").is_err());
+	}
+
+	#[test]
+	fn test_scram_rejects_foreign_nonce() {
+		let mut client = ScramClient::new(KafkaSaslMechanism::ScramSha512, "user", "pencil", "abc");
+		assert!(client
+			.client_final("r=xyz123,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096")
+			.is_err());
+	}
+
+	#[test]
+	fn test_scram_escapes_username() {
+		let client = ScramClient::new(KafkaSaslMechanism::ScramSha256, "a=b,c", "pass", "n");
+		assert_eq!(client.client_first(), "n,,n=a=3Db=2Cc,r=n");
+	}
+}
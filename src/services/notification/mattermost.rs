@@ -0,0 +1,347 @@
+//! Mattermost notification implementation.
+//!
+//! Provides functionality to send formatted messages to Mattermost channels
+//! via incoming webhooks, supporting message templates with variable substitution.
+//! Messages can carry attachments rendered from a template, colored by the severity of the
+//! monitor, and fall back to text when the rendered attachments exceed the limits of Mattermost.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{
+	models::TriggerTypeConfig,
+	services::notification::{
+		severity, severity_color, HttpStatusError, NotificationError, Notifier,
+	},
+	utils::{render_json_template, render_template},
+};
+
+/// Maximum number of characters of a post, including the texts of its attachments
+const MAX_POST_TEXT: usize = 16383;
+
+/// Properties of an attachment holding text
+const ATTACHMENT_TEXTS: [&str; 5] = ["pretext", "title", "text", "author_name", "footer"];
+
+/// Implementation of Mattermost notifications via incoming webhooks
+pub struct MattermostNotifier {
+	/// Mattermost webhook URL for message delivery
+	url: String,
+	/// Title to display in the message
+	title: String,
+	/// Message template with variable placeholders
+	body_template: String,
+	/// HTTP client for webhook requests
+	client: Client,
+	/// Attachments template of the message
+	attachments: Option<Value>,
+}
+
+/// Represents a formatted Mattermost message
+#[derive(Serialize)]
+struct MattermostMessage {
+	/// The formatted text of the post
+	text: String,
+	/// Attachments of the post (optional)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	attachments: Option<Vec<Value>>,
+}
+
+impl MattermostNotifier {
+	/// Creates a new Mattermost notifier instance
+	///
+	/// # Arguments
+	/// * `url` - Mattermost webhook URL
+	/// * `title` - Message title
+	/// * `body_template` - Message template with variables
+	pub fn new(
+		url: String,
+		title: String,
+		body_template: String,
+	) -> Result<Self, Box<NotificationError>> {
+		Ok(Self {
+			url,
+			title,
+			body_template,
+			client: Client::new(),
+			attachments: None,
+		})
+	}
+
+	/// Adds attachments rendered from the given template to the messages
+	pub fn with_attachments(mut self, attachments: Value) -> Self {
+		self.attachments = Some(attachments);
+		self
+	}
+
+	/// Formats a message by rendering the body template with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `String` - Formatted message with variables replaced
+	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
+		let message = render_template(&self.body_template, variables);
+		format!("**{}**\n\n{}", self.title, message)
+	}
+
+	/// Renders the attachments template with the given variables
+	///
+	/// Fields with an empty title or value are removed, as well as the attachments left without
+	/// content. The color defaults to the one of the `severity` variable and the fallback text
+	/// to the title of the message.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Option<Vec<Value>>` - Rendered attachments, or None without a template or if the
+	///   attachments exceed the limits of Mattermost
+	pub fn format_attachments(&self, variables: &HashMap<String, String>) -> Option<Vec<Value>> {
+		let Value::Array(attachments) = render_json_template(self.attachments.as_ref()?, variables)
+		else {
+			return None;
+		};
+		let color =
+			severity(variables).map(|severity| format!("#{:06X}", severity_color(severity)));
+		let attachments: Vec<Value> = attachments
+			.into_iter()
+			.filter_map(|attachment| prune_attachment(attachment, &self.title, color.as_deref()))
+			.collect();
+
+		let text = self.format_message(variables).chars().count()
+			+ attachments.iter().map(text_length).sum::<usize>();
+		if attachments.is_empty() || text > MAX_POST_TEXT {
+			tracing::warn!(
+				"Mattermost attachments exceed the limits of Mattermost, sending the message as text"
+			);
+			return None;
+		}
+		Some(attachments)
+	}
+
+	/// Sends a message rendered with the given variables, with attachments if set
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<(), anyhow::Error> {
+		self.post(&MattermostMessage {
+			text: self.format_message(variables),
+			attachments: self.format_attachments(variables),
+		})
+		.await
+	}
+
+	/// Posts a message to the webhook
+	async fn post(&self, payload: &MattermostMessage) -> Result<(), anyhow::Error> {
+		let response = self
+			.client
+			.post(&self.url)
+			.json(payload)
+			.send()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to send Mattermost notification: {}", e))?;
+
+		if !response.status().is_success() {
+			return Err(HttpStatusError::from_response("Mattermost webhook", &response).into());
+		}
+
+		Ok(())
+	}
+
+	/// Creates a Mattermost notifier from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing Mattermost parameters
+	///
+	/// # Returns
+	/// * `Option<Self>` - Notifier instance if config is Mattermost type
+	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
+		match config {
+			TriggerTypeConfig::Mattermost {
+				mattermost_url,
+				message,
+				attachments,
+			} => Some(Self {
+				url: mattermost_url.clone(),
+				title: message.title.clone(),
+				body_template: message.body.clone(),
+				client: Client::new(),
+				attachments: attachments.clone(),
+			}),
+			_ => None,
+		}
+	}
+}
+
+#[async_trait]
+impl Notifier for MattermostNotifier {
+	/// Sends a formatted message to Mattermost
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
+		self.post(&MattermostMessage {
+			text: message.to_string(),
+			attachments: None,
+		})
+		.await
+	}
+}
+
+/// Removes the empty fields and texts of an attachment, and the attachment if nothing is left
+/// of it, and sets its default color and fallback text
+fn prune_attachment(mut attachment: Value, title: &str, color: Option<&str>) -> Option<Value> {
+	let fields = attachment.as_object_mut()?;
+
+	if let Some(Value::Array(items)) = fields.get_mut("fields") {
+		items.retain(|field| {
+			["title", "value"].iter().all(|name| {
+				field
+					.get(*name)
+					.and_then(Value::as_str)
+					.is_some_and(|text| !text.trim().is_empty())
+			})
+		});
+		if items.is_empty() {
+			fields.remove("fields");
+		}
+	}
+	for name in ATTACHMENT_TEXTS.iter().chain(&["title_link", "image_url"]) {
+		if fields
+			.get(*name)
+			.is_some_and(|value| value.as_str().is_none_or(|text| text.trim().is_empty()))
+		{
+			fields.remove(*name);
+		}
+	}
+	let has_content = ATTACHMENT_TEXTS
+		.iter()
+		.chain(&["fields", "image_url"])
+		.any(|name| fields.contains_key(*name));
+	if !has_content {
+		return None;
+	}
+
+	if !fields.contains_key("fallback") {
+		fields.insert("fallback".to_string(), title.into());
+	}
+	if let Some(color) = color.filter(|_| !fields.contains_key("color")) {
+		fields.insert("color".to_string(), color.into());
+	}
+	Some(attachment)
+}
+
+/// Returns the number of characters of the texts of an attachment
+fn text_length(attachment: &Value) -> usize {
+	let text = |value: &Value, name: &str| {
+		value
+			.get(name)
+			.and_then(Value::as_str)
+			.map_or(0, |text| text.chars().count())
+	};
+	ATTACHMENT_TEXTS
+		.iter()
+		.map(|name| text(attachment, name))
+		.sum::<usize>()
+		+ attachment
+			.get("fields")
+			.and_then(Value::as_array)
+			.map_or(0, |fields| {
+				fields
+					.iter()
+					.map(|field| text(field, "title") + text(field, "value"))
+					.sum()
+			})
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::models::NotificationMessage;
+	use serde_json::json;
+
+	use super::*;
+
+	fn create_test_notifier(attachments: Option<Value>) -> MattermostNotifier {
+		MattermostNotifier::from_config(&TriggerTypeConfig::Mattermost {
+			mattermost_url: "https://mattermost.example.com/hooks/abc".to_string(),
+			message: NotificationMessage {
+				title: "Alert".to_string(),
+				body: "Test message ${value}".to_string(),
+			},
+			attachments,
+		})
+		.unwrap()
+	}
+
+	////////////////////////////////////////////////////////////
+	// format_attachments tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_format_attachments() {
+		let notifier = create_test_notifier(Some(json!([
+			{
+				"title": "{{ monitor_name }}",
+				"title_link": "{{ transaction_url }}",
+				"fields": [
+					{ "short": true, "title": "Value", "value": "{{ value }}" },
+					{ "short": true, "title": "From", "value": "{{ missing }}" }
+				]
+			},
+			{ "text": "{{ missing }}" }
+		])));
+		let variables = HashMap::from([
+			("monitor_name".to_string(), "Large Transfer".to_string()),
+			("value".to_string(), "42".to_string()),
+			("severity".to_string(), "critical".to_string()),
+		]);
+
+		let attachments = notifier.format_attachments(&variables).unwrap();
+		assert_eq!(
+			attachments,
+			vec![json!({
+				"title": "Large Transfer",
+				"fields": [{ "short": true, "title": "Value", "value": "42" }],
+				"fallback": "Alert",
+				"color": "#E74C3C"
+			})]
+		);
+
+		// Without a template, no attachments are rendered
+		assert!(create_test_notifier(None)
+			.format_attachments(&variables)
+			.is_none());
+	}
+
+	#[test]
+	fn test_format_attachments_exceeding_limits() {
+		let notifier = create_test_notifier(Some(json!([{ "text": "{{ details }}" }])));
+		let variables = HashMap::from([("details".to_string(), "a".repeat(20_000))]);
+
+		assert!(notifier.format_attachments(&variables).is_none());
+	}
+
+	#[test]
+	fn test_format_message() {
+		let notifier = create_test_notifier(None);
+		let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+
+		assert_eq!(
+			notifier.format_message(&variables),
+			"**Alert**\n\nTest message 42"
+		);
+	}
+}
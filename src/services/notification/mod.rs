@@ -1,30 +1,61 @@
 //! Notification service implementation.
 //!
 //! This module provides functionality to send notifications through various channels
-//! Supports variable substitution in message templates.
+//! Message bodies are rendered by the template engine of `utils::template`, after expanding the
+//! loop sections repeated for every match of a digest.
 
 use anyhow::Context;
 use async_trait::async_trait;
 
-use std::collections::HashMap;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 
 mod discord;
 mod email;
 mod error;
+mod file;
+mod kafka;
+mod mattermost;
+mod opsgenie;
+mod pagerduty;
 mod script;
 mod slack;
+mod teams;
 mod telegram;
 mod webhook;
 
-use crate::models::{MonitorMatch, ScriptLanguage, Trigger, TriggerType, TriggerTypeConfig};
+use crate::{
+	models::{
+		FileColumn, MonitorMatch, NotificationMessage, ScriptLanguage, Severity, Trigger,
+		TriggerType, TriggerTypeConfig,
+	},
+	services::report::MonitorReport,
+	utils::render_template,
+};
 
 pub use discord::DiscordNotifier;
 pub use email::{EmailContent, EmailNotifier, SmtpConfig};
-pub use error::NotificationError;
+pub use error::{HttpStatusError, NotificationError};
+pub use file::FileNotifier;
+pub use kafka::{KafkaError, KafkaNotifier, KafkaProducer, KafkaRecord};
+pub use mattermost::MattermostNotifier;
+pub use opsgenie::{OpsgenieNotifier, OpsgenieRequest};
+pub use pagerduty::PagerDutyNotifier;
 pub use script::ScriptNotifier;
 pub use slack::SlackNotifier;
+pub use teams::TeamsNotifier;
 pub use telegram::TelegramNotifier;
-pub use webhook::WebhookNotifier;
+pub use webhook::{
+	compute_webhook_signature, verify_webhook_signature, WebhookNotifier, WebhookRequest,
+	WEBHOOK_SIGNATURE_VERSION,
+};
+
+/// Opening tag of the loop section repeated for every match of a digest
+const MATCHES_SECTION_START: &str = "${#matches}";
+
+/// Closing tag of the loop section repeated for every match of a digest
+const MATCHES_SECTION_END: &str = "${/matches}";
 
 /// Interface for notification implementations
 ///
@@ -89,13 +120,152 @@ impl NotificationService {
 		variables: HashMap<String, String>,
 		monitor_match: &MonitorMatch,
 		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), NotificationError> {
+		let summary;
+		let trigger = match variables.get("suppressed_count") {
+			Some(count) => {
+				summary = with_suppressed_summary(trigger, count);
+				&summary
+			}
+			None => trigger,
+		};
+		let trigger = &with_expanded_sections(trigger, &variables);
+
+		match &trigger.trigger_type {
+			TriggerType::Script => {
+				let notifier = ScriptNotifier::from_config(&trigger.config);
+				if let Some(notifier) = notifier {
+					let monitor_name = match monitor_match {
+						MonitorMatch::EVM(evm_match) => &evm_match.monitor.name,
+						MonitorMatch::Stellar(stellar_match) => &stellar_match.monitor.name,
+					};
+					let script_path = match &trigger.config {
+						TriggerTypeConfig::Script { script_path, .. } => script_path,
+						_ => {
+							return Err(NotificationError::config_error(
+								"Invalid script configuration".to_string(),
+								None,
+								None,
+							))
+						}
+					};
+					let script = trigger_scripts
+						.get(&format!("{}|{}", monitor_name, script_path))
+						.ok_or_else(|| {
+							NotificationError::config_error(
+								"Script content not found".to_string(),
+								None,
+								None,
+							)
+						});
+					let script_content = match &script {
+						Ok(content) => content,
+						Err(e) => {
+							return Err(NotificationError::config_error(e.to_string(), None, None))
+						}
+					};
+
+					notifier
+						.script_notify(monitor_match, script_content)
+						.await
+						.with_context(|| {
+							format!("Failed to execute notification {}", trigger.name)
+						})?;
+				} else {
+					return Err(NotificationError::config_error(
+						"Invalid script configuration".to_string(),
+						None,
+						None,
+					));
+				}
+			}
+			_ => self.send(trigger, &variables, Some(monitor_match)).await?,
+		}
+		Ok(())
+	}
+
+	/// Renders the notification message of a trigger without sending it
+	///
+	/// # Arguments
+	/// * `trigger` - Trigger containing the notification type and parameters
+	/// * `variables` - Variables to substitute in message templates
+	///
+	/// # Returns
+	/// * `Result<Option<String>, NotificationError>` - The formatted message, or None for
+	///   custom script triggers, and Kafka and JSONL file triggers without payload template,
+	///   which receive the monitor match instead of a message
+	pub fn render(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+	) -> Result<Option<String>, NotificationError> {
+		let trigger = &with_expanded_sections(trigger, variables);
+		let message = match &trigger.trigger_type {
+			TriggerType::Slack => SlackNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.format_message(variables)),
+			TriggerType::Email => EmailNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.format_message(variables)),
+			TriggerType::Webhook => WebhookNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.render_body(variables))
+				.transpose()
+				.map_err(|e| *e)?,
+			TriggerType::Discord => DiscordNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.format_message(variables)),
+			TriggerType::Telegram => TelegramNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.format_message(variables)),
+			TriggerType::Teams => TeamsNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.format_message(variables)),
+			TriggerType::Mattermost => MattermostNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.format_message(variables)),
+			TriggerType::PagerDuty => PagerDutyNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.build_event(variables).to_string()),
+			TriggerType::Opsgenie => OpsgenieNotifier::from_config(&trigger.config)
+				.map(|notifier| notifier.build_request(variables).body.to_string()),
+			TriggerType::Kafka => match KafkaNotifier::from_config(&trigger.config) {
+				Some(notifier) => return Ok(notifier.format_payload(variables)),
+				None => None,
+			},
+			TriggerType::File => match FileNotifier::from_config(&trigger.config) {
+				Some(notifier) => return Ok(notifier.format_record(variables)),
+				None => None,
+			},
+			TriggerType::Script => return Ok(None),
+		};
+
+		match message {
+			Some(message) => Ok(Some(message)),
+			None => Err(NotificationError::config_error(
+				format!("Invalid configuration for trigger {}", trigger.name),
+				None,
+				None,
+			)),
+		}
+	}
+
+	/// Sends the message of a trigger
+	///
+	/// # Arguments
+	/// * `trigger` - Trigger containing the notification type and parameters
+	/// * `variables` - Variables to substitute in message templates
+	/// * `monitor_match` - Monitor match sent by webhooks with the `match` payload format,
+	///   published by Kafka triggers without payload template and written by JSONL file
+	///   triggers without payload template
+	///
+	/// # Returns
+	/// * `Result<(), NotificationError>` - Success or error, script triggers have no message
+	///   and fail with a configuration error
+	async fn send(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
 	) -> Result<(), NotificationError> {
 		match &trigger.trigger_type {
 			TriggerType::Slack => {
 				let notifier = SlackNotifier::from_config(&trigger.config);
 				if let Some(notifier) = notifier {
 					notifier
-						.notify(&notifier.format_message(&variables))
+						.notify_with_variables(variables)
 						.await
 						.with_context(|| {
 							format!("Failed to execute notification {}", trigger.name)
@@ -112,7 +282,7 @@ impl NotificationService {
 				let notifier = EmailNotifier::from_config(&trigger.config);
 				if let Some(notifier) = notifier {
 					notifier
-						.notify(&notifier.format_message(&variables))
+						.notify(&notifier.format_message(variables))
 						.await
 						.with_context(|| {
 							format!("Failed to execute notification {}", trigger.name)
@@ -129,7 +299,8 @@ impl NotificationService {
 				let notifier = WebhookNotifier::from_config(&trigger.config);
 				if let Some(notifier) = notifier {
 					notifier
-						.notify(&notifier.format_message(&variables))
+						.with_alert_headers(variables)
+						.notify_with_variables(variables, monitor_match)
 						.await
 						.with_context(|| {
 							format!("Failed to execute notification {}", trigger.name)
@@ -147,7 +318,7 @@ impl NotificationService {
 
 				if let Some(notifier) = notifier {
 					notifier
-						.notify(&notifier.format_message(&variables))
+						.notify_with_variables(variables)
 						.await
 						.with_context(|| {
 							format!("Failed to execute notification {}", trigger.name)
@@ -164,7 +335,7 @@ impl NotificationService {
 				let notifier = TelegramNotifier::from_config(&trigger.config);
 				if let Some(notifier) = notifier {
 					notifier
-						.notify(&notifier.format_message(&variables))
+						.notify(&notifier.format_message(variables))
 						.await
 						.with_context(|| {
 							format!("Failed to execute notification {}", trigger.name)
@@ -177,56 +348,444 @@ impl NotificationService {
 					));
 				}
 			}
-			TriggerType::Script => {
-				let notifier = ScriptNotifier::from_config(&trigger.config);
+			TriggerType::Teams => {
+				let notifier = TeamsNotifier::from_config(&trigger.config);
 				if let Some(notifier) = notifier {
-					let monitor_name = match monitor_match {
-						MonitorMatch::EVM(evm_match) => &evm_match.monitor.name,
-						MonitorMatch::Stellar(stellar_match) => &stellar_match.monitor.name,
-					};
-					let script_path = match &trigger.config {
-						TriggerTypeConfig::Script { script_path, .. } => script_path,
-						_ => {
-							return Err(NotificationError::config_error(
-								"Invalid script configuration".to_string(),
-								None,
-								None,
-							))
-						}
-					};
-					let script = trigger_scripts
-						.get(&format!("{}|{}", monitor_name, script_path))
-						.ok_or_else(|| {
-							NotificationError::config_error(
-								"Script content not found".to_string(),
-								None,
-								None,
-							)
-						});
-					let script_content = match &script {
-						Ok(content) => content,
-						Err(e) => {
-							return Err(NotificationError::config_error(e.to_string(), None, None))
-						}
-					};
-
 					notifier
-						.script_notify(monitor_match, script_content)
+						.notify_with_variables(variables)
 						.await
 						.with_context(|| {
 							format!("Failed to execute notification {}", trigger.name)
 						})?;
 				} else {
 					return Err(NotificationError::config_error(
-						"Invalid script configuration".to_string(),
+						"Invalid teams configuration",
 						None,
 						None,
 					));
 				}
 			}
+			TriggerType::Mattermost => {
+				let notifier = MattermostNotifier::from_config(&trigger.config);
+				if let Some(notifier) = notifier {
+					notifier
+						.notify_with_variables(variables)
+						.await
+						.with_context(|| {
+							format!("Failed to execute notification {}", trigger.name)
+						})?;
+				} else {
+					return Err(NotificationError::config_error(
+						"Invalid mattermost configuration",
+						None,
+						None,
+					));
+				}
+			}
+			TriggerType::PagerDuty => {
+				let notifier = PagerDutyNotifier::from_config(&trigger.config);
+				if let Some(notifier) = notifier {
+					notifier
+						.notify_with_variables(variables)
+						.await
+						.with_context(|| {
+							format!("Failed to execute notification {}", trigger.name)
+						})?;
+				} else {
+					return Err(NotificationError::config_error(
+						"Invalid pagerduty configuration",
+						None,
+						None,
+					));
+				}
+			}
+			TriggerType::Opsgenie => {
+				let notifier = OpsgenieNotifier::from_config(&trigger.config);
+				if let Some(notifier) = notifier {
+					notifier
+						.notify_with_variables(variables)
+						.await
+						.with_context(|| {
+							format!("Failed to execute notification {}", trigger.name)
+						})?;
+				} else {
+					return Err(NotificationError::config_error(
+						"Invalid opsgenie configuration",
+						None,
+						None,
+					));
+				}
+			}
+			TriggerType::Kafka => {
+				let notifier = KafkaNotifier::from_config(&trigger.config);
+				if let Some(notifier) = notifier {
+					notifier
+						.notify_with_variables(variables, monitor_match)
+						.await
+						.map_err(|e| {
+							let message =
+								format!("Failed to execute notification {}", trigger.name);
+							// Broker errors such as authorization failures fail the delivery
+							if e.is_retriable() {
+								NotificationError::network_error(message, Some(Box::new(e)), None)
+							} else {
+								NotificationError::config_error(message, Some(Box::new(e)), None)
+							}
+						})?;
+				} else {
+					return Err(NotificationError::config_error(
+						"Invalid kafka configuration",
+						None,
+						None,
+					));
+				}
+			}
+			TriggerType::File => {
+				let notifier = FileNotifier::from_config(&trigger.config);
+				if let Some(notifier) = notifier {
+					notifier
+						.notify_with_variables(variables, monitor_match)
+						.await
+						.with_context(|| {
+							format!("Failed to execute notification {}", trigger.name)
+						})?;
+				} else {
+					return Err(NotificationError::config_error(
+						"Invalid file configuration",
+						None,
+						None,
+					));
+				}
+			}
+			TriggerType::Script => {
+				return Err(NotificationError::config_error(
+					format!("Script trigger {} has no message to send", trigger.name),
+					None,
+					None,
+				));
+			}
 		}
 		Ok(())
 	}
+
+	/// Sends a summary report through a trigger
+	///
+	/// The report replaces the message of the trigger. Email triggers receive it as HTML tables,
+	/// other triggers as plain text.
+	///
+	/// # Arguments
+	/// * `trigger` - Trigger to send the report through
+	/// * `report` - Report to send
+	///
+	/// # Returns
+	/// * `Result<(), NotificationError>` - Success or error, script triggers cannot send reports
+	///   and fail with a configuration error
+	pub async fn send_report(
+		&self,
+		trigger: &Trigger,
+		report: &MonitorReport,
+	) -> Result<(), NotificationError> {
+		match &trigger.trigger_type {
+			TriggerType::Email => {
+				let trigger = with_message(trigger, report.title(), report.to_html());
+				let notifier = EmailNotifier::from_config(&trigger.config).ok_or_else(|| {
+					NotificationError::config_error("Invalid email configuration", None, None)
+				})?;
+				let notifier = notifier.with_html();
+				notifier
+					.notify(&notifier.format_message(&HashMap::new()))
+					.await
+					.with_context(|| format!("Failed to send report through {}", trigger.name))?;
+				Ok(())
+			}
+			_ => {
+				self.send(
+					&with_message(trigger, report.title(), report.to_text()),
+					&HashMap::new(),
+					None,
+				)
+				.await
+			}
+		}
+	}
+}
+
+/// Appends the number of suppressed alerts to the message of a trigger
+///
+/// Used for the summaries of alerts suppressed by an alert policy. Kafka, file and script
+/// triggers are returned unchanged as they have no message.
+fn with_suppressed_summary(trigger: &Trigger, count: &str) -> Trigger {
+	let mut trigger = trigger.clone();
+	match &mut trigger.config {
+		TriggerTypeConfig::Slack { message, .. }
+		| TriggerTypeConfig::Email { message, .. }
+		| TriggerTypeConfig::Webhook { message, .. }
+		| TriggerTypeConfig::Telegram { message, .. }
+		| TriggerTypeConfig::Discord { message, .. }
+		| TriggerTypeConfig::Teams { message, .. }
+		| TriggerTypeConfig::Mattermost { message, .. }
+		| TriggerTypeConfig::PagerDuty { message, .. }
+		| TriggerTypeConfig::Opsgenie { message, .. } => {
+			message
+				.body
+				.push_str(&format!("\n\n{} more similar alert(s) suppressed", count));
+		}
+		TriggerTypeConfig::Kafka { .. }
+		| TriggerTypeConfig::File { .. }
+		| TriggerTypeConfig::Script { .. } => {}
+	}
+	trigger
+}
+
+/// Expands the loop sections of the message body of a trigger
+///
+/// The content of a `${#matches}...${/matches}` section is repeated for every match rendered by
+/// a digest, with its variables referring to that match. Outside of a digest, the content is
+/// rendered once for the single match.
+fn with_expanded_sections(trigger: &Trigger, variables: &HashMap<String, String>) -> Trigger {
+	let mut trigger = trigger.clone();
+	match &mut trigger.config {
+		TriggerTypeConfig::Slack { message, .. }
+		| TriggerTypeConfig::Email { message, .. }
+		| TriggerTypeConfig::Webhook { message, .. }
+		| TriggerTypeConfig::Telegram { message, .. }
+		| TriggerTypeConfig::Discord { message, .. }
+		| TriggerTypeConfig::Teams { message, .. }
+		| TriggerTypeConfig::Mattermost { message, .. }
+		| TriggerTypeConfig::PagerDuty { message, .. }
+		| TriggerTypeConfig::Opsgenie { message, .. } => {
+			message.body = expand_match_sections(&message.body, variables);
+		}
+		TriggerTypeConfig::Kafka { .. }
+		| TriggerTypeConfig::File { .. }
+		| TriggerTypeConfig::Script { .. } => {}
+	}
+	trigger
+}
+
+/// Expands the `${#matches}...${/matches}` sections of a template
+fn expand_match_sections(template: &str, variables: &HashMap<String, String>) -> String {
+	// Number of matches rendered by a digest, None outside of a digest
+	let items = variables
+		.get("match_count")
+		.and_then(|count| count.parse::<usize>().ok())
+		.map(|count| {
+			let omitted = variables
+				.get("omitted_count")
+				.and_then(|omitted| omitted.parse::<usize>().ok())
+				.unwrap_or(0);
+			count.saturating_sub(omitted)
+		});
+
+	let mut output = String::new();
+	let mut rest = template;
+	while let Some(start) = rest.find(MATCHES_SECTION_START) {
+		let section_start = start + MATCHES_SECTION_START.len();
+		let Some(length) = rest[section_start..].find(MATCHES_SECTION_END) else {
+			break;
+		};
+		let section = &rest[section_start..section_start + length];
+		output.push_str(&rest[..start]);
+		match items {
+			Some(items) => {
+				for index in 0..items {
+					output.push_str(&render_match_section(section, index, variables));
+				}
+			}
+			None => output.push_str(section),
+		}
+		rest = &rest[section_start + length + MATCHES_SECTION_END.len()..];
+	}
+	output.push_str(rest);
+	output
+}
+
+/// Substitutes the variables of the match at `index` of a digest in a loop section
+///
+/// Variables the match does not have are left for the substitution of the whole message.
+fn render_match_section(
+	section: &str,
+	index: usize,
+	variables: &HashMap<String, String>,
+) -> String {
+	let mut output = String::new();
+	let mut rest = section;
+	while let Some(start) = rest.find("${") {
+		let Some(length) = rest[start..].find('}') else {
+			break;
+		};
+		let placeholder = &rest[start..start + length + 1];
+		let name = &placeholder[2..placeholder.len() - 1];
+		output.push_str(&rest[..start]);
+		match variables.get(&format!("matches_{}_{}", index, name)) {
+			Some(value) => output.push_str(value),
+			None => output.push_str(placeholder),
+		}
+		rest = &rest[start + length + 1..];
+	}
+	output.push_str(rest);
+	output
+}
+
+/// Replaces the message of a trigger
+///
+/// Webhooks, Slack, Discord, Teams and Mattermost send the message in place of their payload,
+/// blocks, embed, card or attachments template, PagerDuty and Opsgenie in place of their details
+/// template and without dedup key. Kafka triggers publish the title and body as a JSON object in
+/// place of their payload template, file triggers write it as JSONL record or as CSV record of a
+/// title and a body column. Script triggers are returned unchanged as they have no message.
+fn with_message(trigger: &Trigger, title: String, body: String) -> Trigger {
+	let mut trigger = trigger.clone();
+	match &mut trigger.config {
+		TriggerTypeConfig::Webhook {
+			message, payload, ..
+		} => {
+			*message = NotificationMessage { title, body };
+			*payload = None;
+		}
+		TriggerTypeConfig::Slack {
+			message, blocks, ..
+		} => {
+			*message = NotificationMessage { title, body };
+			*blocks = None;
+		}
+		TriggerTypeConfig::Discord { message, embed, .. } => {
+			*message = NotificationMessage { title, body };
+			*embed = None;
+		}
+		TriggerTypeConfig::Teams { message, card, .. } => {
+			*message = NotificationMessage { title, body };
+			*card = None;
+		}
+		TriggerTypeConfig::Mattermost {
+			message,
+			attachments,
+			..
+		} => {
+			*message = NotificationMessage { title, body };
+			*attachments = None;
+		}
+		TriggerTypeConfig::PagerDuty {
+			message,
+			dedup_key,
+			custom_details,
+			..
+		} => {
+			*message = NotificationMessage { title, body };
+			*dedup_key = None;
+			*custom_details = None;
+		}
+		TriggerTypeConfig::Opsgenie {
+			message,
+			dedup_key,
+			details,
+			..
+		} => {
+			*message = NotificationMessage { title, body };
+			*dedup_key = None;
+			*details = None;
+		}
+		TriggerTypeConfig::Email { message, .. } | TriggerTypeConfig::Telegram { message, .. } => {
+			*message = NotificationMessage { title, body };
+		}
+		TriggerTypeConfig::Kafka { payload, .. } => {
+			*payload = Some(serde_json::json!({ "title": title, "body": body }));
+		}
+		TriggerTypeConfig::File {
+			payload, columns, ..
+		} => {
+			*columns = Some(vec![
+				FileColumn {
+					name: "title".to_string(),
+					value: title.clone(),
+				},
+				FileColumn {
+					name: "body".to_string(),
+					value: body.clone(),
+				},
+			]);
+			*payload = Some(serde_json::json!({ "title": title, "body": body }));
+		}
+		TriggerTypeConfig::Script { .. } => {}
+	}
+	trigger
+}
+
+/// Renders the dedup key of an incident trigger match
+///
+/// Without a template, the key is made of the network, monitor and transaction of the match, so
+/// that the alerts of a two-phase alert share it. Keys longer than `max_length` are replaced by
+/// their SHA-256 hash.
+///
+/// # Returns
+/// * `Option<String>` - The key, or None if it renders empty or the match has no transaction
+///   to derive it from
+fn dedup_key(
+	template: Option<&str>,
+	variables: &HashMap<String, String>,
+	max_length: usize,
+) -> Option<String> {
+	let key = match template {
+		Some(template) => render_template(template, variables),
+		None => format!(
+			"{}:{}:{}",
+			variables.get("network_slug")?,
+			variables.get("monitor_name")?,
+			variables.get("transaction_hash")?
+		),
+	};
+	let key = key.trim();
+	if key.is_empty() {
+		return None;
+	}
+	if key.len() > max_length {
+		return Some(hex::encode(Sha256::digest(key.as_bytes())));
+	}
+	Some(key.to_string())
+}
+
+/// Returns the severity of the monitor of a match, if set
+fn severity(variables: &HashMap<String, String>) -> Option<Severity> {
+	variables
+		.get("severity")
+		.and_then(|severity| serde_json::from_value(Value::String(severity.clone())).ok())
+}
+
+/// Returns the color of a severity, as an RGB number
+fn severity_color(severity: Severity) -> u64 {
+	match severity {
+		Severity::Info => 0x3498DB,
+		Severity::Warning => 0xF1C40F,
+		Severity::Error => 0xE67E22,
+		Severity::Critical => 0xE74C3C,
+	}
+}
+
+/// Returns whether the variables are those of a dropped two-phase alert
+fn is_dropped(variables: &HashMap<String, String>) -> bool {
+	variables.get("alert_phase").map(String::as_str) == Some("dropped")
+}
+
+/// Returns the variables as a JSON object sorted by name
+fn variables_object(variables: &HashMap<String, String>) -> Map<String, Value> {
+	variables
+		.iter()
+		.collect::<BTreeMap<_, _>>()
+		.into_iter()
+		.map(|(name, value)| (name.clone(), Value::String(value.clone())))
+		.collect()
+}
+
+/// Truncates a text to at most `max_length` bytes on a character boundary
+fn truncate(text: &str, max_length: usize) -> String {
+	if text.len() <= max_length {
+		return text.to_string();
+	}
+	let mut end = max_length;
+	while !text.is_char_boundary(end) {
+		end -= 1;
+	}
+	text[..end].to_string()
 }
 
 impl Default for NotificationService {
@@ -308,6 +867,8 @@ mod tests {
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}))
 	}
 
@@ -325,6 +886,9 @@ mod tests {
 				arguments: None,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		let variables = HashMap::new();
@@ -359,6 +923,9 @@ mod tests {
 				arguments: None,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		let variables = HashMap::new();
@@ -394,6 +961,9 @@ mod tests {
 				arguments: None,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		let variables = HashMap::new();
@@ -428,6 +998,9 @@ mod tests {
 				arguments: None,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		let variables = HashMap::new();
@@ -462,6 +1035,9 @@ mod tests {
 				arguments: None,
 				timeout_ms: 1000,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		let variables = HashMap::new();
@@ -499,6 +1075,9 @@ mod tests {
 					body: "invalid".to_string(),
 				},
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		};
 
 		let variables = HashMap::new();
@@ -520,4 +1099,155 @@ mod tests {
 			_ => panic!("Expected ConfigError"),
 		}
 	}
+
+	#[test]
+	fn test_render_formats_message() {
+		let service = NotificationService::new();
+
+		let trigger = Trigger {
+			name: "test_telegram".to_string(),
+			trigger_type: TriggerType::Telegram,
+			config: TriggerTypeConfig::Telegram {
+				token: "token".to_string(),
+				chat_id: "chat".to_string(),
+				disable_web_preview: None,
+				message: NotificationMessage {
+					title: "Alert".to_string(),
+					body: "Value: ${value}".to_string(),
+				},
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		};
+		let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+
+		let message = service.render(&trigger, &variables).unwrap().unwrap();
+		assert!(message.contains("Value: 42"));
+	}
+
+	#[test]
+	fn test_render_script_and_invalid_config() {
+		let service = NotificationService::new();
+
+		let script_trigger = Trigger {
+			name: "test_script".to_string(),
+			trigger_type: TriggerType::Script,
+			config: TriggerTypeConfig::Script {
+				script_path: "script.py".to_string(),
+				language: ScriptLanguage::Python,
+				arguments: None,
+				timeout_ms: 1000,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		};
+		assert!(service
+			.render(&script_trigger, &HashMap::new())
+			.unwrap()
+			.is_none());
+
+		let invalid_trigger = Trigger {
+			name: "test_slack".to_string(),
+			trigger_type: TriggerType::Slack,
+			// Intentionally wrong config type
+			config: script_trigger.config.clone(),
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		};
+		match service.render(&invalid_trigger, &HashMap::new()) {
+			Err(NotificationError::ConfigError(ctx)) => {
+				assert!(ctx
+					.message
+					.contains("Invalid configuration for trigger test_slack"));
+			}
+			_ => panic!("Expected ConfigError"),
+		}
+	}
+
+	#[test]
+	fn test_with_suppressed_summary() {
+		let trigger = Trigger {
+			name: "test_slack".to_string(),
+			trigger_type: TriggerType::Slack,
+			config: TriggerTypeConfig::Slack {
+				slack_url: "https://hooks.slack.com/services/xxx".to_string(),
+				message: NotificationMessage {
+					title: "Alert".to_string(),
+					body: "Transfer from ${from}".to_string(),
+				},
+				blocks: None,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		};
+
+		let summary = with_suppressed_summary(&trigger, "12");
+		let variables = HashMap::from([("from".to_string(), "alice".to_string())]);
+		assert_eq!(
+			NotificationService::new()
+				.render(&summary, &variables)
+				.unwrap()
+				.unwrap(),
+			"*Alert*\n\nTransfer from alice\n\n12 more similar alert(s) suppressed"
+		);
+	}
+
+	#[test]
+	fn test_expand_match_sections() {
+		let template = "${match_count} transfers:\n${#matches}- ${transaction_hash} (${value})\n${/matches}Total ${total_value}";
+		let variables = HashMap::from([
+			("match_count".to_string(), "3".to_string()),
+			("omitted_count".to_string(), "1".to_string()),
+			("matches_0_transaction_hash".to_string(), "0x1".to_string()),
+			("matches_0_value".to_string(), "100".to_string()),
+			("matches_1_transaction_hash".to_string(), "0x2".to_string()),
+			("matches_1_value".to_string(), "250".to_string()),
+		]);
+		assert_eq!(
+			expand_match_sections(template, &variables),
+			"${match_count} transfers:\n- 0x1 (100)\n- 0x2 (250)\nTotal ${total_value}"
+		);
+
+		// Outside of a digest the section is rendered once
+		assert_eq!(
+			expand_match_sections(template, &HashMap::new()),
+			"${match_count} transfers:\n- ${transaction_hash} (${value})\nTotal ${total_value}"
+		);
+
+		// An unclosed section is left as is
+		assert_eq!(
+			expand_match_sections("${#matches}${value}", &variables),
+			"${#matches}${value}"
+		);
+	}
+
+	#[test]
+	fn test_dedup_key() {
+		let variables = HashMap::from([
+			("network_slug".to_string(), "ethereum_mainnet".to_string()),
+			("monitor_name".to_string(), "Large Transfer".to_string()),
+			("transaction_hash".to_string(), "0xabc".to_string()),
+		]);
+
+		assert_eq!(
+			dedup_key(None, &variables, 255).as_deref(),
+			Some("ethereum_mainnet:Large Transfer:0xabc")
+		);
+
+		assert_eq!(dedup_key(Some("{{ missing }}"), &variables, 255), None);
+		assert_eq!(dedup_key(None, &HashMap::new(), 255), None);
+		// Long keys are hashed
+		let key = dedup_key(Some(&"a".repeat(300)), &variables, 255).unwrap();
+		assert_eq!(key.len(), 64);
+	}
+
+	#[test]
+	fn test_truncate() {
+		assert_eq!(truncate("short", 10), "short");
+		assert_eq!(truncate("héllo", 2), "h");
+	}
 }
@@ -0,0 +1,327 @@
+//! Opsgenie notification implementation.
+//!
+//! Provides functionality to create alerts through the Opsgenie Alert API. Alerts are
+//! deduplicated by an alias rendered from the match variables, and the alert of a dropped
+//! two-phase alert is closed.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::{
+	models::{OpsgeniePriority, Severity, TriggerTypeConfig},
+	services::notification::{
+		dedup_key, is_dropped, severity, truncate, variables_object, HttpStatusError, Notifier,
+	},
+	utils::render_template,
+};
+
+/// Default base URL of the Alert API
+const DEFAULT_API_URL: &str = "https://api.opsgenie.com";
+
+/// Maximum length of the message of an alert
+const MAX_MESSAGE: usize = 130;
+
+/// Maximum length of the description of an alert
+const MAX_DESCRIPTION: usize = 15000;
+
+/// Maximum length of the alias of an alert
+const MAX_ALIAS: usize = 512;
+
+/// Source of the alerts
+const SOURCE: &str = "OpenZeppelin Monitor";
+
+/// Request to send to the Alert API
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpsgenieRequest {
+	/// Path of the request relative to the API URL
+	pub path: String,
+	/// Body of the request
+	pub body: Value,
+}
+
+/// Implementation of Opsgenie notifications via the Alert API
+pub struct OpsgenieNotifier {
+	/// Base URL of the Alert API
+	url: String,
+	/// API key of the Opsgenie integration
+	api_key: String,
+	/// Title of the message, the message of the alert
+	title: String,
+	/// Message template with variable placeholders
+	body_template: String,
+	/// Template of the alias deduplicating alerts
+	dedup_key: Option<String>,
+	/// Templates of the details of the alert
+	details: Option<HashMap<String, String>>,
+	/// Alert priorities of the monitor severities
+	severity_map: HashMap<Severity, OpsgeniePriority>,
+	/// Whether dropped two-phase alerts close their alert
+	auto_resolve: bool,
+	/// HTTP client for API requests
+	client: Client,
+}
+
+impl OpsgenieNotifier {
+	/// Creates an Opsgenie notifier from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing Opsgenie parameters
+	///
+	/// # Returns
+	/// * `Option<Self>` - Notifier instance if config is Opsgenie type
+	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
+		match config {
+			TriggerTypeConfig::Opsgenie {
+				api_key,
+				message,
+				dedup_key,
+				details,
+				severity_map,
+				auto_resolve,
+				api_url,
+			} => Some(Self {
+				url: api_url
+					.as_deref()
+					.unwrap_or(DEFAULT_API_URL)
+					.trim_end_matches('/')
+					.to_string(),
+				api_key: api_key.clone(),
+				title: message.title.clone(),
+				body_template: message.body.clone(),
+				dedup_key: dedup_key.clone(),
+				details: details.clone(),
+				severity_map: severity_map.clone().unwrap_or_default(),
+				auto_resolve: auto_resolve.unwrap_or(true),
+				client: Client::new(),
+			}),
+			_ => None,
+		}
+	}
+
+	/// Builds the request of a match rendered with the given variables
+	///
+	/// A dropped two-phase alert closes the alert of its alias when auto-resolve is enabled,
+	/// other matches create an alert.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `OpsgenieRequest` - Request to send to the Alert API
+	pub fn build_request(&self, variables: &HashMap<String, String>) -> OpsgenieRequest {
+		let alias = dedup_key(self.dedup_key.as_deref(), variables, MAX_ALIAS);
+		let description = truncate(
+			&render_template(&self.body_template, variables),
+			MAX_DESCRIPTION,
+		);
+
+		if let Some(alias) = &alias {
+			if self.auto_resolve && is_dropped(variables) {
+				return OpsgenieRequest {
+					path: format!(
+						"/v2/alerts/{}/close?identifierType=alias",
+						urlencoding::encode(alias)
+					),
+					body: json!({ "source": SOURCE, "note": description }),
+				};
+			}
+		}
+
+		let details = match &self.details {
+			Some(templates) => templates
+				.iter()
+				.map(|(name, template)| {
+					(
+						name.clone(),
+						Value::String(render_template(template, variables)),
+					)
+				})
+				.collect(),
+			None => variables_object(variables),
+		};
+
+		let mut body = json!({
+			"message": truncate(&render_template(&self.title, variables), MAX_MESSAGE),
+			"description": description,
+			"priority": self.priority(variables),
+			"details": details,
+			"source": SOURCE,
+		});
+		if let Some(alias) = alias {
+			body["alias"] = alias.into();
+		}
+		if let Some(network) = variables.get("network_slug") {
+			body["tags"] = json!([network]);
+		}
+		OpsgenieRequest {
+			path: "/v2/alerts".to_string(),
+			body,
+		}
+	}
+
+	/// Returns the alert priority of the monitor severity of the variables
+	///
+	/// Matches of monitors without a severity are mapped as warnings.
+	fn priority(&self, variables: &HashMap<String, String>) -> OpsgeniePriority {
+		let severity = severity(variables).unwrap_or(Severity::Warning);
+		self.severity_map
+			.get(&severity)
+			.copied()
+			.unwrap_or_else(|| OpsgeniePriority::from_severity(severity))
+	}
+
+	/// Sends the request of a match rendered with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<(), anyhow::Error> {
+		self.post(&self.build_request(variables)).await
+	}
+
+	/// Posts a request to the Alert API
+	async fn post(&self, request: &OpsgenieRequest) -> Result<(), anyhow::Error> {
+		let response = self
+			.client
+			.post(format!("{}{}", self.url, request.path))
+			.header("Authorization", format!("GenieKey {}", self.api_key))
+			.json(&request.body)
+			.send()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to send Opsgenie alert: {}", e))?;
+
+		if !response.status().is_success() {
+			return Err(HttpStatusError::from_response("Opsgenie Alert API", &response).into());
+		}
+
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl Notifier for OpsgenieNotifier {
+	/// Creates an alert with the given message
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
+		self.post(&OpsgenieRequest {
+			path: "/v2/alerts".to_string(),
+			body: json!({
+				"message": truncate(&self.title, MAX_MESSAGE),
+				"description": truncate(message, MAX_DESCRIPTION),
+				"priority": self.priority(&HashMap::new()),
+				"source": SOURCE,
+			}),
+		})
+		.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::models::NotificationMessage;
+
+	use super::*;
+
+	fn create_test_notifier(
+		details: Option<HashMap<String, String>>,
+		severity_map: Option<HashMap<Severity, OpsgeniePriority>>,
+	) -> OpsgenieNotifier {
+		OpsgenieNotifier::from_config(&TriggerTypeConfig::Opsgenie {
+			api_key: "api-key".to_string(),
+			message: NotificationMessage {
+				title: "Large transfer".to_string(),
+				body: "{{ monitor_name }} matched {{ transaction_hash }}".to_string(),
+			},
+			dedup_key: None,
+			details,
+			severity_map,
+			auto_resolve: None,
+			api_url: None,
+		})
+		.unwrap()
+	}
+
+	fn create_test_variables() -> HashMap<String, String> {
+		HashMap::from([
+			("monitor_name".to_string(), "Large Transfer".to_string()),
+			("network_slug".to_string(), "ethereum_mainnet".to_string()),
+			("transaction_hash".to_string(), "0xabc".to_string()),
+			("severity".to_string(), "error".to_string()),
+		])
+	}
+
+	////////////////////////////////////////////////////////////
+	// build_request tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_build_request() {
+		let notifier = create_test_notifier(None, None);
+		let request = notifier.build_request(&create_test_variables());
+
+		assert_eq!(request.path, "/v2/alerts");
+		assert_eq!(request.body["message"], "Large transfer");
+		assert_eq!(request.body["description"], "Large Transfer matched 0xabc");
+		assert_eq!(request.body["priority"], "P2");
+		assert_eq!(
+			request.body["alias"],
+			"ethereum_mainnet:Large Transfer:0xabc"
+		);
+		assert_eq!(request.body["details"]["transaction_hash"], "0xabc");
+		assert_eq!(request.body["tags"], json!(["ethereum_mainnet"]));
+	}
+
+	#[test]
+	fn test_build_request_with_templates() {
+		let notifier = create_test_notifier(
+			Some(HashMap::from([(
+				"tx".to_string(),
+				"{{ transaction_hash }}".to_string(),
+			)])),
+			Some(HashMap::from([(Severity::Error, OpsgeniePriority::P1)])),
+		);
+		let request = notifier.build_request(&create_test_variables());
+
+		assert_eq!(request.body["priority"], "P1");
+		assert_eq!(request.body["details"], json!({ "tx": "0xabc" }));
+	}
+
+	#[test]
+	fn test_build_request_closes_dropped_alerts() {
+		let notifier = create_test_notifier(None, None);
+		let mut variables = create_test_variables();
+		variables.insert("alert_phase".to_string(), "dropped".to_string());
+
+		let request = notifier.build_request(&variables);
+		assert_eq!(
+			request.path,
+			"/v2/alerts/ethereum_mainnet%3ALarge%20Transfer%3A0xabc/close?identifierType=alias"
+		);
+		assert_eq!(request.body["note"], "Large Transfer matched 0xabc");
+	}
+
+	#[test]
+	fn test_priority_defaults() {
+		let notifier = create_test_notifier(None, None);
+		let mut variables = create_test_variables();
+
+		variables.insert("severity".to_string(), "critical".to_string());
+		assert_eq!(notifier.priority(&variables), OpsgeniePriority::P1);
+		variables.insert("severity".to_string(), "info".to_string());
+		assert_eq!(notifier.priority(&variables), OpsgeniePriority::P5);
+		variables.remove("severity");
+		assert_eq!(notifier.priority(&variables), OpsgeniePriority::P3);
+	}
+}
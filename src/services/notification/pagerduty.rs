@@ -0,0 +1,346 @@
+//! PagerDuty notification implementation.
+//!
+//! Provides functionality to send events to PagerDuty services through the Events API v2.
+//! Events are grouped into incidents by a dedup key rendered from the match variables, and the
+//! incident of a dropped two-phase alert is resolved.
+
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::{
+	models::{Severity, TriggerTypeConfig},
+	services::notification::{
+		dedup_key, is_dropped, severity, truncate, variables_object, HttpStatusError, Notifier,
+	},
+	utils::{render_json_template, render_template},
+};
+
+/// Default base URL of the Events API
+const DEFAULT_API_URL: &str = "https://events.pagerduty.com";
+
+/// Maximum length of the summary of an event
+const MAX_SUMMARY: usize = 1024;
+
+/// Maximum length of a dedup key
+const MAX_DEDUP_KEY: usize = 255;
+
+/// Source of events of matches without a network
+const DEFAULT_SOURCE: &str = "openzeppelin-monitor";
+
+/// Implementation of PagerDuty notifications via the Events API v2
+pub struct PagerDutyNotifier {
+	/// Base URL of the Events API
+	url: String,
+	/// Integration key of the PagerDuty service
+	routing_key: String,
+	/// Title of the message, the summary of the event
+	title: String,
+	/// Message template with variable placeholders
+	body_template: String,
+	/// Template of the key grouping events into incidents
+	dedup_key: Option<String>,
+	/// Template of the custom details of the event
+	custom_details: Option<Value>,
+	/// Event severities of the monitor severities
+	severity_map: HashMap<Severity, Severity>,
+	/// Whether dropped two-phase alerts resolve their incident
+	auto_resolve: bool,
+	/// HTTP client for API requests
+	client: Client,
+}
+
+impl PagerDutyNotifier {
+	/// Creates a PagerDuty notifier from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing PagerDuty parameters
+	///
+	/// # Returns
+	/// * `Option<Self>` - Notifier instance if config is PagerDuty type
+	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
+		match config {
+			TriggerTypeConfig::PagerDuty {
+				routing_key,
+				message,
+				dedup_key,
+				custom_details,
+				severity_map,
+				auto_resolve,
+				api_url,
+			} => Some(Self {
+				url: api_url
+					.as_deref()
+					.unwrap_or(DEFAULT_API_URL)
+					.trim_end_matches('/')
+					.to_string(),
+				routing_key: routing_key.clone(),
+				title: message.title.clone(),
+				body_template: message.body.clone(),
+				dedup_key: dedup_key.clone(),
+				custom_details: custom_details.clone(),
+				severity_map: severity_map.clone().unwrap_or_default(),
+				auto_resolve: auto_resolve.unwrap_or(true),
+				client: Client::new(),
+			}),
+			_ => None,
+		}
+	}
+
+	/// Builds the event of a match rendered with the given variables
+	///
+	/// A dropped two-phase alert resolves the incident of its dedup key when auto-resolve is
+	/// enabled, other matches trigger an event.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Value` - Event to send to the Events API
+	pub fn build_event(&self, variables: &HashMap<String, String>) -> Value {
+		let dedup_key = dedup_key(self.dedup_key.as_deref(), variables, MAX_DEDUP_KEY);
+
+		if let Some(dedup_key) = &dedup_key {
+			if self.auto_resolve && is_dropped(variables) {
+				return json!({
+					"routing_key": self.routing_key,
+					"event_action": "resolve",
+					"dedup_key": dedup_key,
+				});
+			}
+		}
+
+		let body = render_template(&self.body_template, variables);
+		let custom_details = match &self.custom_details {
+			Some(template) => render_json_template(template, variables),
+			None => {
+				let mut details = variables_object(variables);
+				details.insert("body".to_string(), body.into());
+				Value::Object(details)
+			}
+		};
+		let severity = self.severity(variables);
+
+		let mut payload = json!({
+			"summary": truncate(&render_template(&self.title, variables), MAX_SUMMARY),
+			"source": variables
+				.get("network_slug")
+				.map(String::as_str)
+				.unwrap_or(DEFAULT_SOURCE),
+			"severity": severity.to_string(),
+			"custom_details": custom_details,
+		});
+		if let Some(name) = variables.get("monitor_name") {
+			payload["component"] = name.as_str().into();
+		}
+		let timestamp = variables
+			.get("block_timestamp")
+			.and_then(|timestamp| timestamp.parse().ok())
+			.and_then(|timestamp| DateTime::from_timestamp(timestamp, 0));
+		if let Some(timestamp) = timestamp {
+			payload["timestamp"] = timestamp.to_rfc3339_opts(SecondsFormat::Secs, true).into();
+		}
+
+		let mut event = json!({
+			"routing_key": self.routing_key,
+			"event_action": "trigger",
+			"payload": payload,
+		});
+		if let Some(dedup_key) = dedup_key {
+			event["dedup_key"] = dedup_key.into();
+		}
+		if let Some(url) = variables
+			.get("transaction_url")
+			.filter(|url| !url.is_empty())
+		{
+			event["links"] = json!([{ "href": url, "text": "Transaction" }]);
+		}
+		event
+	}
+
+	/// Returns the event severity of the monitor severity of the variables
+	///
+	/// Matches of monitors without a severity are mapped as warnings.
+	fn severity(&self, variables: &HashMap<String, String>) -> Severity {
+		let severity = severity(variables).unwrap_or(Severity::Warning);
+		self.severity_map
+			.get(&severity)
+			.copied()
+			.unwrap_or(severity)
+	}
+
+	/// Sends the event of a match rendered with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<(), anyhow::Error> {
+		self.post(&self.build_event(variables)).await
+	}
+
+	/// Posts an event to the Events API
+	async fn post(&self, event: &Value) -> Result<(), anyhow::Error> {
+		let response = self
+			.client
+			.post(format!("{}/v2/enqueue", self.url))
+			.json(event)
+			.send()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to send PagerDuty event: {}", e))?;
+
+		if !response.status().is_success() {
+			return Err(HttpStatusError::from_response("PagerDuty Events API", &response).into());
+		}
+
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl Notifier for PagerDutyNotifier {
+	/// Triggers an event summarized by the given message
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
+		self.post(&json!({
+			"routing_key": self.routing_key,
+			"event_action": "trigger",
+			"payload": {
+				"summary": truncate(message, MAX_SUMMARY),
+				"source": DEFAULT_SOURCE,
+				"severity": self.severity(&HashMap::new()).to_string(),
+			},
+		}))
+		.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::models::NotificationMessage;
+
+	use super::*;
+
+	fn create_test_notifier(
+		dedup_key: Option<&str>,
+		custom_details: Option<Value>,
+		severity_map: Option<HashMap<Severity, Severity>>,
+	) -> PagerDutyNotifier {
+		PagerDutyNotifier::from_config(&TriggerTypeConfig::PagerDuty {
+			routing_key: "routing-key".to_string(),
+			message: NotificationMessage {
+				title: "Large transfer on {{ network_name }}".to_string(),
+				body: "{{ monitor_name }} matched {{ transaction_hash }}".to_string(),
+			},
+			dedup_key: dedup_key.map(str::to_string),
+			custom_details,
+			severity_map,
+			auto_resolve: None,
+			api_url: None,
+		})
+		.unwrap()
+	}
+
+	fn create_test_variables() -> HashMap<String, String> {
+		HashMap::from([
+			("monitor_name".to_string(), "Large Transfer".to_string()),
+			("network_slug".to_string(), "ethereum_mainnet".to_string()),
+			("network_name".to_string(), "Ethereum Mainnet".to_string()),
+			("transaction_hash".to_string(), "0xabc".to_string()),
+			("severity".to_string(), "critical".to_string()),
+			("block_timestamp".to_string(), "1733036783".to_string()),
+			(
+				"transaction_url".to_string(),
+				"https://etherscan.io/tx/0xabc".to_string(),
+			),
+		])
+	}
+
+	////////////////////////////////////////////////////////////
+	// build_event tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_build_event() {
+		let notifier = create_test_notifier(None, None, None);
+		let event = notifier.build_event(&create_test_variables());
+
+		assert_eq!(event["routing_key"], "routing-key");
+		assert_eq!(event["event_action"], "trigger");
+		assert_eq!(event["dedup_key"], "ethereum_mainnet:Large Transfer:0xabc");
+		assert_eq!(
+			event["payload"]["summary"],
+			"Large transfer on Ethereum Mainnet"
+		);
+		assert_eq!(event["payload"]["source"], "ethereum_mainnet");
+		assert_eq!(event["payload"]["severity"], "critical");
+		assert_eq!(event["payload"]["component"], "Large Transfer");
+		assert_eq!(event["payload"]["timestamp"], "2024-12-01T07:06:23Z");
+		assert_eq!(
+			event["payload"]["custom_details"]["body"],
+			"Large Transfer matched 0xabc"
+		);
+		assert_eq!(
+			event["payload"]["custom_details"]["transaction_hash"],
+			"0xabc"
+		);
+		assert_eq!(event["links"][0]["href"], "https://etherscan.io/tx/0xabc");
+	}
+
+	#[test]
+	fn test_build_event_with_templates() {
+		let notifier = create_test_notifier(
+			Some("{{ monitor_name }}"),
+			Some(json!({ "tx": "{{ transaction_hash }}" })),
+			Some(HashMap::from([(Severity::Critical, Severity::Error)])),
+		);
+		let event = notifier.build_event(&create_test_variables());
+
+		assert_eq!(event["dedup_key"], "Large Transfer");
+		assert_eq!(event["payload"]["severity"], "error");
+		assert_eq!(event["payload"]["custom_details"], json!({ "tx": "0xabc" }));
+	}
+
+	#[test]
+	fn test_build_event_defaults_to_warning() {
+		let notifier = create_test_notifier(None, None, None);
+		let mut variables = create_test_variables();
+		variables.remove("severity");
+
+		let event = notifier.build_event(&variables);
+		assert_eq!(event["payload"]["severity"], "warning");
+	}
+
+	#[test]
+	fn test_build_event_resolves_dropped_alerts() {
+		let notifier = create_test_notifier(None, None, None);
+		let mut variables = create_test_variables();
+		variables.insert("alert_phase".to_string(), "dropped".to_string());
+
+		let event = notifier.build_event(&variables);
+		assert_eq!(
+			event,
+			json!({
+				"routing_key": "routing-key",
+				"event_action": "resolve",
+				"dedup_key": "ethereum_mainnet:Large Transfer:0xabc",
+			})
+		);
+
+		// Without auto-resolve, the dropped alert triggers an event
+		let mut notifier = create_test_notifier(None, None, None);
+		notifier.auto_resolve = false;
+		assert_eq!(notifier.build_event(&variables)["event_action"], "trigger");
+	}
+}
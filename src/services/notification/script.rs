@@ -141,6 +141,8 @@ mod tests {
 			receipt: EVMTransactionReceipt::default(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}))
 	}
 
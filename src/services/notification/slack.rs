@@ -2,17 +2,36 @@
 //!
 //! Provides functionality to send formatted messages to Slack channels
 //! via incoming webhooks, supporting message templates with variable substitution.
+//! Messages can be laid out with a Block Kit template, and fall back to text when the rendered
+//! blocks exceed the limits of Slack.
 
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
 
 use crate::{
 	models::TriggerTypeConfig,
-	services::notification::{NotificationError, Notifier},
+	services::notification::{HttpStatusError, NotificationError, Notifier},
+	utils::{render_json_template, render_template},
 };
 
+/// Maximum number of blocks of a message
+const MAX_BLOCKS: usize = 50;
+
+/// Maximum length of the text of a section
+const MAX_SECTION_TEXT: usize = 3000;
+
+/// Maximum number of fields of a section
+const MAX_SECTION_FIELDS: usize = 10;
+
+/// Maximum length of a field of a section
+const MAX_FIELD_TEXT: usize = 2000;
+
+/// Maximum length of the text of a header
+const MAX_HEADER_TEXT: usize = 150;
+
 /// Implementation of Slack notifications via webhooks
 pub struct SlackNotifier {
 	/// Slack webhook URL for message delivery
@@ -23,13 +42,18 @@ pub struct SlackNotifier {
 	body_template: String,
 	/// HTTP client for webhook requests
 	client: Client,
+	/// Block Kit template of the message
+	blocks: Option<Value>,
 }
 
 /// Represents a formatted Slack message
 #[derive(Serialize)]
 struct SlackMessage {
-	/// The formatted text to send to Slack
+	/// The formatted text to send to Slack, shown in notifications when blocks are set
 	text: String,
+	/// Block Kit layout of the message (optional)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	blocks: Option<Vec<Value>>,
 }
 
 impl SlackNotifier {
@@ -49,10 +73,17 @@ impl SlackNotifier {
 			title,
 			body_template,
 			client: Client::new(),
+			blocks: None,
 		})
 	}
 
-	/// Formats a message by substituting variables in the template
+	/// Lays out the messages with the given Block Kit template
+	pub fn with_blocks(mut self, blocks: Value) -> Self {
+		self.blocks = Some(blocks);
+		self
+	}
+
+	/// Formats a message by rendering the body template with the given variables
 	///
 	/// # Arguments
 	/// * `variables` - Map of variable names to values
@@ -60,13 +91,69 @@ impl SlackNotifier {
 	/// # Returns
 	/// * `String` - Formatted message with variables replaced
 	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
-		let mut message = self.body_template.clone();
-		for (key, value) in variables {
-			message = message.replace(&format!("${{{}}}", key), value);
-		}
+		let message = render_template(&self.body_template, variables);
 		format!("*{}*\n\n{}", self.title, message)
 	}
 
+	/// Renders the Block Kit template with the given variables
+	///
+	/// Buttons without a URL and empty text elements are removed, so that links to an explorer
+	/// are only shown when known.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Option<Vec<Value>>` - Rendered blocks, or None without a template or if the blocks
+	///   exceed the limits of Slack
+	pub fn format_blocks(&self, variables: &HashMap<String, String>) -> Option<Vec<Value>> {
+		let Value::Array(blocks) = render_json_template(self.blocks.as_ref()?, variables) else {
+			return None;
+		};
+		let blocks: Vec<Value> = blocks.into_iter().filter_map(prune_block).collect();
+
+		if blocks.is_empty() || blocks.len() > MAX_BLOCKS || !blocks.iter().all(within_limits) {
+			tracing::warn!("Slack blocks exceed the limits of Slack, sending the message as text");
+			return None;
+		}
+		Some(blocks)
+	}
+
+	/// Sends a message rendered with the given variables, laid out with blocks if set
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<(), anyhow::Error> {
+		self.post(&SlackMessage {
+			text: self.format_message(variables),
+			blocks: self.format_blocks(variables),
+		})
+		.await
+	}
+
+	/// Posts a message to the webhook
+	async fn post(&self, payload: &SlackMessage) -> Result<(), anyhow::Error> {
+		let response = self
+			.client
+			.post(&self.url)
+			.json(payload)
+			.send()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to send Slack notification: {}", e))?;
+
+		if !response.status().is_success() {
+			return Err(HttpStatusError::from_response("Slack webhook", &response).into());
+		}
+
+		Ok(())
+	}
+
 	/// Creates a Slack notifier from a trigger configuration
 	///
 	/// # Arguments
@@ -76,11 +163,16 @@ impl SlackNotifier {
 	/// * `Option<Self>` - Notifier instance if config is Slack type
 	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
 		match config {
-			TriggerTypeConfig::Slack { slack_url, message } => Some(Self {
+			TriggerTypeConfig::Slack {
+				slack_url,
+				message,
+				blocks,
+			} => Some(Self {
 				url: slack_url.clone(),
 				title: message.title.clone(),
 				body_template: message.body.clone(),
 				client: Client::new(),
+				blocks: blocks.clone(),
 			}),
 			_ => None,
 		}
@@ -97,26 +189,72 @@ impl Notifier for SlackNotifier {
 	/// # Returns
 	/// * `Result<(), anyhow::Error>` - Success or error
 	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
-		let payload = SlackMessage {
+		self.post(&SlackMessage {
 			text: message.to_string(),
-		};
-
-		let response = self
-			.client
-			.post(&self.url)
-			.json(&payload)
-			.send()
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed to send Slack notification: {}", e))?;
+			blocks: None,
+		})
+		.await
+	}
+}
 
-		if !response.status().is_success() {
-			return Err(anyhow::anyhow!(
-				"Slack webhook returned error status: {}",
-				response.status()
-			));
+/// Removes the empty elements of a block, and the block if nothing is left of it
+fn prune_block(mut block: Value) -> Option<Value> {
+	let Some(fields) = block.as_object_mut() else {
+		return Some(block);
+	};
+	for list in ["fields", "elements"] {
+		if let Some(Value::Array(items)) = fields.get_mut(list) {
+			items.retain(|item| !is_empty_element(item));
+			if items.is_empty() {
+				fields.remove(list);
+			}
 		}
+	}
+	if fields.get("text").is_some_and(is_empty_element) {
+		fields.remove("text");
+	}
+	let has_content = ["text", "fields", "elements", "image_url"]
+		.iter()
+		.any(|name| fields.contains_key(*name));
+	(has_content || fields.get("type").and_then(Value::as_str) == Some("divider")).then_some(block)
+}
 
-		Ok(())
+/// Returns whether an element is a text without content or a link without URL
+fn is_empty_element(element: &Value) -> bool {
+	let text = element.get("text");
+	let empty_text = match text {
+		Some(Value::String(text)) => text.trim().is_empty(),
+		Some(Value::Object(_)) => text.is_some_and(is_empty_element),
+		_ => false,
+	};
+	let empty_url = element
+		.get("url")
+		.is_some_and(|url| url.as_str().is_none_or(|url| url.trim().is_empty()));
+	empty_text || empty_url
+}
+
+/// Returns whether a block is within the limits of Slack
+fn within_limits(block: &Value) -> bool {
+	let text_length = |value: Option<&Value>| {
+		value
+			.and_then(|value| value.get("text"))
+			.and_then(Value::as_str)
+			.map_or(0, |text| text.chars().count())
+	};
+	let fields = block
+		.get("fields")
+		.and_then(Value::as_array)
+		.map_or(&[][..], Vec::as_slice);
+
+	match block.get("type").and_then(Value::as_str) {
+		Some("header") => text_length(block.get("text")) <= MAX_HEADER_TEXT,
+		_ => {
+			text_length(block.get("text")) <= MAX_SECTION_TEXT
+				&& fields.len() <= MAX_SECTION_FIELDS
+				&& fields
+					.iter()
+					.all(|field| text_length(Some(field)) <= MAX_FIELD_TEXT)
+		}
 	}
 }
 
@@ -142,6 +280,7 @@ mod tests {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
 			},
+			blocks: None,
 		}
 	}
 
@@ -182,6 +321,86 @@ mod tests {
 		assert_eq!(result, "*Alert*\n\n");
 	}
 
+	////////////////////////////////////////////////////////////
+	// format_blocks tests
+	////////////////////////////////////////////////////////////
+
+	fn create_test_blocks() -> Value {
+		serde_json::json!([
+			{
+				"type": "header",
+				"text": { "type": "plain_text", "text": "{{ monitor_name }}" }
+			},
+			{
+				"type": "section",
+				"fields": [
+					{ "type": "mrkdwn", "text": "*Value*\n{{ value }}" },
+					{ "type": "mrkdwn", "text": "{{ missing }}" }
+				]
+			},
+			{
+				"type": "actions",
+				"elements": [{
+					"type": "button",
+					"text": { "type": "plain_text", "text": "View transaction" },
+					"url": "{{ transaction_link }}"
+				}]
+			},
+			{
+				"type": "context",
+				"elements": [{ "type": "mrkdwn", "text": "{{ network_name }}" }]
+			}
+		])
+	}
+
+	#[test]
+	fn test_format_blocks() {
+		let notifier = create_test_notifier("Test message").with_blocks(create_test_blocks());
+		let mut variables = HashMap::from([
+			("monitor_name".to_string(), "Large transfer".to_string()),
+			("value".to_string(), "100".to_string()),
+			("network_name".to_string(), "Ethereum Mainnet".to_string()),
+			(
+				"transaction_link".to_string(),
+				"https://etherscan.io/tx/0x1".to_string(),
+			),
+		]);
+
+		let blocks = notifier.format_blocks(&variables).unwrap();
+		assert_eq!(blocks.len(), 4);
+		assert_eq!(blocks[0]["text"]["text"], "Large transfer");
+		assert_eq!(blocks[1]["fields"].as_array().unwrap().len(), 1);
+		assert_eq!(blocks[1]["fields"][0]["text"], "*Value*\n100");
+		assert_eq!(
+			blocks[2]["elements"][0]["url"],
+			"https://etherscan.io/tx/0x1"
+		);
+		assert_eq!(blocks[3]["elements"][0]["text"], "Ethereum Mainnet");
+
+		// Without an explorer link, the button and its block are removed
+		variables.remove("transaction_link");
+		let blocks = notifier.format_blocks(&variables).unwrap();
+		assert_eq!(blocks.len(), 3);
+		assert_eq!(blocks[2]["type"], "context");
+	}
+
+	#[test]
+	fn test_format_blocks_exceeding_limits() {
+		let notifier = create_test_notifier("Test message").with_blocks(create_test_blocks());
+		let variables = HashMap::from([("monitor_name".to_string(), "x".repeat(151))]);
+		assert!(notifier.format_blocks(&variables).is_none());
+
+		let notifier = create_test_notifier("Test message").with_blocks(serde_json::json!(vec![
+				serde_json::json!({ "type": "divider" });
+				MAX_BLOCKS + 1
+			]));
+		assert!(notifier.format_blocks(&HashMap::new()).is_none());
+
+		assert!(create_test_notifier("Test message")
+			.format_blocks(&HashMap::new())
+			.is_none());
+	}
+
 	////////////////////////////////////////////////////////////
 	// from_config tests
 	////////////////////////////////////////////////////////////
@@ -0,0 +1,389 @@
+//! Microsoft Teams notification implementation.
+//!
+//! Provides functionality to send formatted messages to Teams channels via incoming webhooks,
+//! supporting message templates with variable substitution. Messages are sent as Adaptive Cards,
+//! laid out with a card template if set, and fall back to a card of the message text when the
+//! rendered card exceeds the limits of Teams.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::{
+	models::{Severity, TriggerTypeConfig},
+	services::notification::{severity, HttpStatusError, NotificationError, Notifier},
+	utils::{render_json_template, render_template},
+};
+
+/// Content type of the Adaptive Card attachments
+const ADAPTIVE_CARD_CONTENT_TYPE: &str = "application/vnd.microsoft.card.adaptive";
+
+/// Schema of the Adaptive Cards
+const ADAPTIVE_CARD_SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
+
+/// Version of the Adaptive Cards, the latest supported by Teams webhooks
+const ADAPTIVE_CARD_VERSION: &str = "1.4";
+
+/// Maximum size of a message in bytes
+const MAX_MESSAGE_SIZE: usize = 28_000;
+
+/// Properties holding the nested elements of a card
+const ELEMENT_LISTS: [&str; 5] = ["body", "items", "columns", "actions", "facts"];
+
+/// Implementation of Microsoft Teams notifications via incoming webhooks
+pub struct TeamsNotifier {
+	/// Teams webhook URL for message delivery
+	url: String,
+	/// Title to display in the message
+	title: String,
+	/// Message template with variable placeholders
+	body_template: String,
+	/// HTTP client for webhook requests
+	client: Client,
+	/// Adaptive Card template of the message
+	card: Option<Value>,
+}
+
+impl TeamsNotifier {
+	/// Creates a new Teams notifier instance
+	///
+	/// # Arguments
+	/// * `url` - Teams webhook URL
+	/// * `title` - Message title
+	/// * `body_template` - Message template with variables
+	pub fn new(
+		url: String,
+		title: String,
+		body_template: String,
+	) -> Result<Self, Box<NotificationError>> {
+		Ok(Self {
+			url,
+			title,
+			body_template,
+			client: Client::new(),
+			card: None,
+		})
+	}
+
+	/// Lays out the messages with the given Adaptive Card template
+	pub fn with_card(mut self, card: Value) -> Self {
+		self.card = Some(card);
+		self
+	}
+
+	/// Formats a message by rendering the body template with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `String` - Formatted message with variables replaced
+	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
+		let message = render_template(&self.body_template, variables);
+		format!("**{}**\n\n{}", self.title, message)
+	}
+
+	/// Renders the card template with the given variables
+	///
+	/// Text blocks and facts without text, and actions and images without URL are removed, as
+	/// well as the containers left empty.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Option<Value>` - Rendered card, or None without a template or if the card exceeds the
+	///   limits of Teams
+	pub fn format_card(&self, variables: &HashMap<String, String>) -> Option<Value> {
+		let card = render_json_template(self.card.as_ref()?, variables);
+		let Some(mut card) = prune_element(card).filter(Value::is_object) else {
+			tracing::warn!("Teams card is empty, sending the message as text");
+			return None;
+		};
+		for (name, value) in [
+			("type", "AdaptiveCard"),
+			("$schema", ADAPTIVE_CARD_SCHEMA),
+			("version", ADAPTIVE_CARD_VERSION),
+		] {
+			if card.get(name).is_none() {
+				card[name] = value.into();
+			}
+		}
+
+		if webhook_message(card.clone()).to_string().len() > MAX_MESSAGE_SIZE {
+			tracing::warn!("Teams card exceeds the limits of Teams, sending the message as text");
+			return None;
+		}
+		Some(card)
+	}
+
+	/// Returns a card of the title and rendered body of the message
+	///
+	/// The title is colored by the severity of the monitor.
+	fn text_card(&self, variables: &HashMap<String, String>) -> Value {
+		let mut title = json!({
+			"type": "TextBlock",
+			"text": self.title,
+			"weight": "Bolder",
+			"size": "Medium",
+			"wrap": true,
+		});
+		if let Some(severity) = severity(variables) {
+			title["color"] = severity_text_color(severity).into();
+		}
+		text_card(vec![
+			title,
+			json!({
+				"type": "TextBlock",
+				"text": render_template(&self.body_template, variables),
+				"wrap": true,
+			}),
+		])
+	}
+
+	/// Sends a message rendered with the given variables, laid out with the card if set
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<(), anyhow::Error> {
+		let card = self
+			.format_card(variables)
+			.unwrap_or_else(|| self.text_card(variables));
+		self.post(&webhook_message(card)).await
+	}
+
+	/// Posts a message to the webhook
+	async fn post(&self, payload: &Value) -> Result<(), anyhow::Error> {
+		let response = self
+			.client
+			.post(&self.url)
+			.json(payload)
+			.send()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to send Teams notification: {}", e))?;
+
+		if !response.status().is_success() {
+			return Err(HttpStatusError::from_response("Teams webhook", &response).into());
+		}
+
+		Ok(())
+	}
+
+	/// Creates a Teams notifier from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing Teams parameters
+	///
+	/// # Returns
+	/// * `Option<Self>` - Notifier instance if config is Teams type
+	pub fn from_config(config: &TriggerTypeConfig) -> Option<Self> {
+		match config {
+			TriggerTypeConfig::Teams {
+				teams_url,
+				message,
+				card,
+			} => Some(Self {
+				url: teams_url.clone(),
+				title: message.title.clone(),
+				body_template: message.body.clone(),
+				client: Client::new(),
+				card: card.clone(),
+			}),
+			_ => None,
+		}
+	}
+}
+
+#[async_trait]
+impl Notifier for TeamsNotifier {
+	/// Sends a formatted message to Teams
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
+		let card = text_card(vec![json!({
+			"type": "TextBlock",
+			"text": message,
+			"wrap": true,
+		})]);
+		self.post(&webhook_message(card)).await
+	}
+}
+
+/// Returns the message of the webhook carrying a card
+fn webhook_message(card: Value) -> Value {
+	json!({
+		"type": "message",
+		"attachments": [{
+			"contentType": ADAPTIVE_CARD_CONTENT_TYPE,
+			"content": card,
+		}],
+	})
+}
+
+/// Returns a card made of the given elements
+fn text_card(body: Vec<Value>) -> Value {
+	json!({
+		"type": "AdaptiveCard",
+		"$schema": ADAPTIVE_CARD_SCHEMA,
+		"version": ADAPTIVE_CARD_VERSION,
+		"body": body,
+	})
+}
+
+/// Returns the text color of the title of a severity
+fn severity_text_color(severity: Severity) -> &'static str {
+	match severity {
+		Severity::Info => "Accent",
+		Severity::Warning => "Warning",
+		Severity::Error | Severity::Critical => "Attention",
+	}
+}
+
+/// Removes the empty elements nested in an element, and the element if nothing is left of it
+fn prune_element(mut element: Value) -> Option<Value> {
+	let Some(fields) = element.as_object_mut() else {
+		return Some(element);
+	};
+	let mut emptied = false;
+	for list in ELEMENT_LISTS {
+		if let Some(Value::Array(items)) = fields.remove(list) {
+			let items: Vec<Value> = items.into_iter().filter_map(prune_element).collect();
+			if items.is_empty() {
+				emptied = true;
+			} else {
+				fields.insert(list.to_string(), Value::Array(items));
+			}
+		}
+	}
+
+	let is_blank = |name: &str| {
+		fields
+			.get(name)
+			.is_some_and(|value| value.as_str().is_none_or(|text| text.trim().is_empty()))
+	};
+	if ["text", "url", "value"].iter().any(|name| is_blank(name)) {
+		return None;
+	}
+	let has_content = fields
+		.keys()
+		.any(|name| ELEMENT_LISTS.contains(&name.as_str()))
+		|| ["text", "url", "value"]
+			.iter()
+			.any(|name| fields.contains_key(*name));
+	(!emptied || has_content).then_some(element)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::models::NotificationMessage;
+
+	use super::*;
+
+	fn create_test_notifier(card: Option<Value>) -> TeamsNotifier {
+		let notifier = TeamsNotifier::from_config(&TriggerTypeConfig::Teams {
+			teams_url: "https://example.webhook.office.com/webhookb2/abc".to_string(),
+			message: NotificationMessage {
+				title: "Alert".to_string(),
+				body: "Test message ${value}".to_string(),
+			},
+			card: None,
+		})
+		.unwrap();
+		match card {
+			Some(card) => notifier.with_card(card),
+			None => notifier,
+		}
+	}
+
+	////////////////////////////////////////////////////////////
+	// format_card tests
+	////////////////////////////////////////////////////////////
+
+	#[test]
+	fn test_format_card() {
+		let notifier = create_test_notifier(Some(json!({
+			"body": [
+				{ "type": "TextBlock", "text": "{{ monitor_name }}", "weight": "Bolder" },
+				{ "type": "TextBlock", "text": "{{ missing }}" },
+				{
+					"type": "FactSet",
+					"facts": [
+						{ "title": "Value", "value": "{{ value }}" },
+						{ "title": "From", "value": "{{ missing }}" }
+					]
+				},
+				{ "type": "FactSet", "facts": [{ "title": "To", "value": "{{ missing }}" }] }
+			],
+			"actions": [
+				{ "type": "Action.OpenUrl", "title": "View", "url": "{{ transaction_url }}" }
+			]
+		})));
+		let variables = HashMap::from([
+			("monitor_name".to_string(), "Large Transfer".to_string()),
+			("value".to_string(), "42".to_string()),
+		]);
+
+		let card = notifier.format_card(&variables).unwrap();
+		assert_eq!(
+			card,
+			json!({
+				"type": "AdaptiveCard",
+				"$schema": ADAPTIVE_CARD_SCHEMA,
+				"version": "1.4",
+				"body": [
+					{ "type": "TextBlock", "text": "Large Transfer", "weight": "Bolder" },
+					{ "type": "FactSet", "facts": [{ "title": "Value", "value": "42" }] }
+				]
+			})
+		);
+
+		// Without a template, no card is rendered
+		assert!(create_test_notifier(None).format_card(&variables).is_none());
+	}
+
+	#[test]
+	fn test_format_card_exceeding_limits() {
+		let notifier = create_test_notifier(Some(json!({
+			"body": [{ "type": "TextBlock", "text": "{{ value }}" }]
+		})));
+		let variables = HashMap::from([("value".to_string(), "a".repeat(30_000))]);
+
+		assert!(notifier.format_card(&variables).is_none());
+	}
+
+	#[test]
+	fn test_text_card() {
+		let notifier = create_test_notifier(None);
+		let variables = HashMap::from([
+			("value".to_string(), "42".to_string()),
+			("severity".to_string(), "critical".to_string()),
+		]);
+
+		let card = notifier.text_card(&variables);
+		assert_eq!(card["body"][0]["text"], "Alert");
+		assert_eq!(card["body"][0]["color"], "Attention");
+		assert_eq!(card["body"][1]["text"], "Test message 42");
+	}
+
+	#[test]
+	fn test_format_message() {
+		let notifier = create_test_notifier(None);
+		let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+
+		assert_eq!(
+			notifier.format_message(&variables),
+			"**Alert**\n\nTest message 42"
+		);
+	}
+}
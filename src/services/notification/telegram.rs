@@ -9,7 +9,8 @@ use std::collections::HashMap;
 
 use crate::{
 	models::TriggerTypeConfig,
-	services::notification::{NotificationError, Notifier},
+	services::notification::{HttpStatusError, NotificationError, Notifier},
+	utils::render_template,
 };
 
 /// Implementation of Telegram notifications via webhooks
@@ -58,7 +59,7 @@ impl TelegramNotifier {
 		})
 	}
 
-	/// Formats a message by substituting variables in the template
+	/// Formats a message by rendering the body template with the given variables
 	///
 	/// # Arguments
 	/// * `variables` - Map of variable names to values
@@ -66,10 +67,7 @@ impl TelegramNotifier {
 	/// # Returns
 	/// * `String` - Formatted message with variables replaced
 	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
-		let mut message = self.body_template.clone();
-		for (key, value) in variables {
-			message = message.replace(&format!("${{{}}}", key), value);
-		}
+		let message = render_template(&self.body_template, variables);
 		// Markdown formatting for Telegram
 		// Double asterisks for bold text
 		// Double whitespaces for new line
@@ -139,10 +137,7 @@ impl Notifier for TelegramNotifier {
 		};
 
 		if !response.status().is_success() {
-			return Err(anyhow::anyhow!(
-				"Telegram webhook returned error status: {}",
-				response.status()
-			));
+			return Err(HttpStatusError::from_response("Telegram webhook", &response).into());
 		}
 
 		Ok(())
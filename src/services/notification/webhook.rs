@@ -2,6 +2,14 @@
 //!
 //! Provides functionality to send formatted messages to webhooks
 //! via incoming webhooks, supporting message templates with variable substitution.
+//! The request body defaults to the title and body of the message, and can be replaced with a
+//! JSON or form-encoded payload template, or with the serialized monitor match.
+//!
+//! Requests of webhooks with secrets are signed. The `X-Signature` header holds the hex-encoded
+//! HMAC-SHA256 of `{timestamp}.{body}`, keyed with each active secret and separated by commas,
+//! where `timestamp` is the `X-Timestamp` header, in milliseconds since the Unix epoch, and
+//! `body` the raw request body. The `X-Signature-Version` header names the scheme, currently
+//! `v1`. Receivers check the signatures with [`verify_webhook_signature`].
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -11,17 +19,22 @@ use reqwest::{
 	Client, Method,
 };
 use serde::Serialize;
+use serde_json::Value;
 use sha2::Sha256;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crate::{
-	models::TriggerTypeConfig,
-	services::notification::{NotificationError, Notifier},
+	models::{MonitorMatch, TriggerTypeConfig, WebhookPayload, WebhookPayloadFormat},
+	services::notification::{HttpStatusError, NotificationError, Notifier},
+	utils::{render_json_template, render_template},
 };
 
 /// HMAC SHA256 type alias
 type HmacSha256 = Hmac<Sha256>;
 
+/// Version of the signature scheme, sent in the `X-Signature-Version` header
+pub const WEBHOOK_SIGNATURE_VERSION: &str = "v1";
+
 /// Implementation of webhook notifications via webhooks
 pub struct WebhookNotifier {
 	/// Webhook URL for message delivery
@@ -34,10 +47,23 @@ pub struct WebhookNotifier {
 	client: Client,
 	/// HTTP method to use for the webhook request
 	method: Option<String>,
-	/// Secret to use for the webhook request
-	secret: Option<String>,
+	/// Active secrets signing the webhook requests
+	secrets: Vec<String>,
 	/// Headers to use for the webhook request
 	headers: Option<HashMap<String, String>>,
+	/// Payload sent in place of the default title and body message
+	payload: Option<WebhookPayload>,
+}
+
+/// Rendered webhook request
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookRequest {
+	/// URL with the rendered path and query parameters
+	pub url: String,
+	/// Content type of the body
+	pub content_type: String,
+	/// Serialized body
+	pub body: Vec<u8>,
 }
 
 /// Represents a formatted webhook message
@@ -72,12 +98,25 @@ impl WebhookNotifier {
 			body_template,
 			client: Client::new(),
 			method: Some(method.unwrap_or("POST".to_string())),
-			secret: secret.map(|s| s.to_string()),
+			secrets: secret.into_iter().collect(),
 			headers,
+			payload: None,
 		})
 	}
 
-	/// Formats a message by substituting variables in the template
+	/// Signs the requests with the given additional secrets
+	pub fn with_secrets(mut self, secrets: Vec<String>) -> Self {
+		self.secrets.extend(secrets);
+		self
+	}
+
+	/// Sends the given payload in place of the default title and body message
+	pub fn with_payload(mut self, payload: WebhookPayload) -> Self {
+		self.payload = Some(payload);
+		self
+	}
+
+	/// Formats a message by rendering the body template with the given variables
 	///
 	/// # Arguments
 	/// * `variables` - Map of variable names to values
@@ -85,11 +124,7 @@ impl WebhookNotifier {
 	/// # Returns
 	/// * `String` - Formatted message with variables replaced
 	pub fn format_message(&self, variables: &HashMap<String, String>) -> String {
-		let mut message = self.body_template.clone();
-		for (key, value) in variables {
-			message = message.replace(&format!("${{{}}}", key), value);
-		}
-		message
+		render_template(&self.body_template, variables)
 	}
 
 	/// Creates a Webhook notifier from a trigger configuration
@@ -106,41 +141,295 @@ impl WebhookNotifier {
 				message,
 				method,
 				secret,
+				secrets,
 				headers,
+				payload,
 			} => Some(Self {
 				url: url.clone(),
 				title: message.title.clone(),
 				body_template: message.body.clone(),
 				client: Client::new(),
 				method: method.clone(),
-				secret: secret.clone(),
+				secrets: secret
+					.iter()
+					.chain(secrets.iter().flatten())
+					.cloned()
+					.collect(),
 				headers: headers.clone(),
+				payload: payload.clone(),
 			}),
 			_ => None,
 		}
 	}
 
-	pub fn sign_request(
+	/// Adds two-phase alert headers to the request when the variables carry an alert context
+	///
+	/// The `X-Correlation-Id` and `X-Alert-Phase` headers let receivers tie a pending alert to
+	/// its confirmed or dropped follow-up without parsing the message body.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Self` - Notifier with the alert headers added
+	pub fn with_alert_headers(mut self, variables: &HashMap<String, String>) -> Self {
+		let alert_headers = [
+			("X-Correlation-Id", variables.get("correlation_id")),
+			("X-Alert-Phase", variables.get("alert_phase")),
+		];
+		for (name, value) in alert_headers {
+			if let Some(value) = value {
+				self.headers
+					.get_or_insert_with(HashMap::new)
+					.insert(name.to_string(), value.clone());
+			}
+		}
+		self
+	}
+
+	/// Renders the request of a notification
+	///
+	/// Without a payload, the body is the title and the rendered body template of the message.
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	/// * `monitor_match` - Monitor match sent by the `match` payload format
+	///
+	/// # Returns
+	/// * `Result<WebhookRequest, Box<NotificationError>>` - Rendered request or error
+	pub fn build_request(
+		&self,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+	) -> Result<WebhookRequest, Box<NotificationError>> {
+		let Some(payload) = &self.payload else {
+			return self.message_request(&self.format_message(variables));
+		};
+
+		let mut url = self.url.clone();
+		if let Some(path) = &payload.path {
+			let encoded: HashMap<String, String> = variables
+				.iter()
+				.map(|(key, value)| (key.clone(), urlencoding::encode(value).into_owned()))
+				.collect();
+			url = format!(
+				"{}/{}",
+				url.trim_end_matches('/'),
+				render_template(path, &encoded).trim_start_matches('/')
+			);
+		}
+		if let Some(query) = &payload.query {
+			let mut parsed = url::Url::parse(&url).map_err(|e| {
+				NotificationError::config_error(format!("Invalid webhook URL: {}", e), None, None)
+			})?;
+			let mut names: Vec<&String> = query.keys().collect();
+			names.sort();
+			for name in names {
+				parsed
+					.query_pairs_mut()
+					.append_pair(name, &render_template(&query[name], variables));
+			}
+			url = parsed.to_string();
+		}
+
+		let body = match payload.format {
+			WebhookPayloadFormat::Json => {
+				let template = payload.template.as_ref().unwrap_or(&Value::Null);
+				serde_json::to_vec(&render_json_template(template, variables))
+			}
+			WebhookPayloadFormat::Form => {
+				let mut form = url::form_urlencoded::Serializer::new(String::new());
+				if let Some(Value::Object(fields)) = &payload.template {
+					for (name, value) in fields {
+						let value = match render_json_template(value, variables) {
+							Value::String(value) => value,
+							Value::Null => String::new(),
+							value => value.to_string(),
+						};
+						form.append_pair(name, &value);
+					}
+				}
+				Ok(form.finish().into_bytes())
+			}
+			WebhookPayloadFormat::Match => {
+				let monitor_match = monitor_match.ok_or_else(|| {
+					NotificationError::config_error(
+						"The match payload format requires a monitor match",
+						None,
+						None,
+					)
+				})?;
+				serde_json::to_vec(monitor_match)
+			}
+		}
+		.map_err(|e| {
+			NotificationError::internal_error(
+				format!("Failed to serialize webhook payload: {}", e),
+				None,
+				None,
+			)
+		})?;
+
+		Ok(WebhookRequest {
+			url,
+			content_type: payload
+				.content_type
+				.clone()
+				.unwrap_or_else(|| payload.format.content_type().to_string()),
+			body,
+		})
+	}
+
+	/// Renders the body of a notification, the formatted message if no payload is set
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Result<String, Box<NotificationError>>` - Rendered body or error
+	pub fn render_body(
+		&self,
+		variables: &HashMap<String, String>,
+	) -> Result<String, Box<NotificationError>> {
+		if self.payload.is_none() {
+			return Ok(self.format_message(variables));
+		}
+		let request = self.build_request(variables, None)?;
+		Ok(String::from_utf8_lossy(&request.body).into_owned())
+	}
+
+	/// Sends a notification rendered with the given variables
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	/// * `monitor_match` - Monitor match sent by the `match` payload format
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	pub async fn notify_with_variables(
 		&self,
-		secret: &str,
-		payload: &WebhookMessage,
-	) -> Result<(String, String), Box<NotificationError>> {
-		let timestamp = Utc::now().timestamp_millis();
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+	) -> Result<(), anyhow::Error> {
+		let request = self.build_request(variables, monitor_match)?;
+		self.send_request(request).await
+	}
 
-		// Create HMAC instance
-		let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|e| {
-			NotificationError::config_error(format!("Invalid secret: {}", e), None, None)
-		})?; // Handle error if secret is invalid
+	/// Builds the default request carrying the title and the given message
+	fn message_request(&self, message: &str) -> Result<WebhookRequest, Box<NotificationError>> {
+		let payload = WebhookMessage {
+			title: self.title.clone(),
+			body: message.to_string(),
+		};
+		let body = serde_json::to_vec(&payload).map_err(|e| {
+			NotificationError::internal_error(
+				format!("Failed to serialize webhook payload: {}", e),
+				None,
+				None,
+			)
+		})?;
+		Ok(WebhookRequest {
+			url: self.url.clone(),
+			content_type: WebhookPayloadFormat::Json.content_type().to_string(),
+			body,
+		})
+	}
 
-		// Create the message to sign
-		let message = format!("{:?}{}", payload, timestamp);
-		mac.update(message.as_bytes());
+	/// Signs a request body with every active secret
+	///
+	/// # Arguments
+	/// * `body` - Raw request body
+	///
+	/// # Returns
+	/// * `Result<(String, String), Box<NotificationError>>` - Comma-separated signatures, one
+	///   per secret, and the timestamp they were computed with
+	pub fn sign_request(&self, body: &[u8]) -> Result<(String, String), Box<NotificationError>> {
+		let timestamp = Utc::now().timestamp_millis().to_string();
+		let signatures = self
+			.secrets
+			.iter()
+			.map(|secret| compute_webhook_signature(secret, &timestamp, body))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok((signatures.join(","), timestamp))
+	}
+}
 
-		// Get the HMAC result
-		let signature = hex::encode(mac.finalize().into_bytes());
+/// Computes the signature of a webhook request
+///
+/// # Arguments
+/// * `secret` - Secret the signature is keyed with
+/// * `timestamp` - Value of the `X-Timestamp` header
+/// * `body` - Raw request body
+///
+/// # Returns
+/// * `Result<String, Box<NotificationError>>` - Hex-encoded HMAC-SHA256 of `{timestamp}.{body}`
+pub fn compute_webhook_signature(
+	secret: &str,
+	timestamp: &str,
+	body: &[u8],
+) -> Result<String, Box<NotificationError>> {
+	Ok(hex::encode(
+		signature_mac(secret, timestamp, body)?
+			.finalize()
+			.into_bytes(),
+	))
+}
 
-		Ok((signature, timestamp.to_string()))
+/// Verifies the signature of a received webhook request
+///
+/// The request is valid if one of its signatures matches one of the secrets and, when a
+/// tolerance is given, its timestamp is within the tolerance of the current time.
+///
+/// # Arguments
+/// * `secrets` - Secrets the receiver accepts
+/// * `body` - Raw request body
+/// * `timestamp` - Value of the `X-Timestamp` header
+/// * `signature` - Value of the `X-Signature` header
+/// * `tolerance` - Maximum age of the request, protecting against replays
+///
+/// # Returns
+/// * `bool` - Whether the request is authentic
+pub fn verify_webhook_signature(
+	secrets: &[impl AsRef<str>],
+	body: &[u8],
+	timestamp: &str,
+	signature: &str,
+	tolerance: Option<Duration>,
+) -> bool {
+	if let Some(tolerance) = tolerance {
+		let Ok(sent_at) = timestamp.parse::<i64>() else {
+			return false;
+		};
+		let age = Utc::now().timestamp_millis().abs_diff(sent_at);
+		if u128::from(age) > tolerance.as_millis() {
+			return false;
+		}
 	}
+
+	signature
+		.split(',')
+		.filter_map(|signature| hex::decode(signature.trim()).ok())
+		.any(|signature| {
+			secrets.iter().any(|secret| {
+				signature_mac(secret.as_ref(), timestamp, body)
+					.is_ok_and(|mac| mac.verify_slice(&signature).is_ok())
+			})
+		})
+}
+
+/// Creates the HMAC of a request, keyed with the secret and fed with `{timestamp}.{body}`
+fn signature_mac(
+	secret: &str,
+	timestamp: &str,
+	body: &[u8],
+) -> Result<HmacSha256, Box<NotificationError>> {
+	let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|e| {
+		NotificationError::config_error(format!("Invalid secret: {}", e), None, None)
+	})?;
+	mac.update(timestamp.as_bytes());
+	mac.update(b".");
+	mac.update(body);
+	Ok(mac)
 }
 
 #[async_trait]
@@ -153,11 +442,20 @@ impl Notifier for WebhookNotifier {
 	/// # Returns
 	/// * `Result<(), anyhow::Error>` - Success or error
 	async fn notify(&self, message: &str) -> Result<(), anyhow::Error> {
-		let payload = WebhookMessage {
-			title: self.title.clone(),
-			body: message.to_string(),
-		};
+		let request = self.message_request(message)?;
+		self.send_request(request).await
+	}
+}
 
+impl WebhookNotifier {
+	/// Sends a rendered request, signed if a secret is set
+	///
+	/// # Arguments
+	/// * `request` - The rendered request to send
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn send_request(&self, request: WebhookRequest) -> Result<(), anyhow::Error> {
 		let method = if let Some(ref m) = self.method {
 			Method::from_bytes(m.as_bytes()).unwrap_or(Method::POST)
 		} else {
@@ -166,31 +464,29 @@ impl Notifier for WebhookNotifier {
 
 		let mut headers = HeaderMap::new();
 
-		if let Some(secret) = &self.secret {
+		let Ok(content_type) = HeaderValue::from_str(&request.content_type) else {
+			return Err(anyhow::anyhow!(
+				"Invalid content type: {}",
+				request.content_type
+			));
+		};
+		headers.insert(reqwest::header::CONTENT_TYPE, content_type);
+
+		if !self.secrets.is_empty() {
 			let (signature, timestamp) = self
-				.sign_request(secret, &payload)
+				.sign_request(&request.body)
 				.map_err(|e| NotificationError::internal_error(e.to_string(), None, None))?;
 
-			// Handle X-Signature header
-			if let Ok(header_name) = HeaderName::from_bytes(b"X-Signature") {
-				if let Ok(header_value) = HeaderValue::from_str(&signature) {
-					headers.insert(header_name, header_value);
-				} else {
-					return Err(anyhow::anyhow!("Invalid signature value",));
-				}
-			} else {
-				return Err(anyhow::anyhow!("Invalid signature header name",));
-			}
-
-			// Handle X-Timestamp header
-			if let Ok(header_name) = HeaderName::from_bytes(b"X-Timestamp") {
-				if let Ok(header_value) = HeaderValue::from_str(&timestamp) {
-					headers.insert(header_name, header_value);
-				} else {
-					return Err(anyhow::anyhow!("Invalid timestamp value",));
-				}
-			} else {
-				return Err(anyhow::anyhow!("Invalid timestamp header name",));
+			// Handle X-Signature, X-Timestamp and X-Signature-Version headers
+			for (name, value) in [
+				("x-signature", signature.as_str()),
+				("x-timestamp", timestamp.as_str()),
+				("x-signature-version", WEBHOOK_SIGNATURE_VERSION),
+			] {
+				let Ok(header_value) = HeaderValue::from_str(value) else {
+					return Err(anyhow::anyhow!("Invalid {} value", name));
+				};
+				headers.insert(HeaderName::from_static(name), header_value);
 			}
 		}
 
@@ -211,9 +507,9 @@ impl Notifier for WebhookNotifier {
 
 		let response = match self
 			.client
-			.request(method, self.url.as_str())
+			.request(method, request.url.as_str())
 			.headers(headers)
-			.json(&payload)
+			.body(request.body)
 			.send()
 			.await
 		{
@@ -228,10 +524,7 @@ impl Notifier for WebhookNotifier {
 		};
 
 		if !response.status().is_success() {
-			return Err(anyhow::anyhow!(
-				"Webhook returned error status: {}",
-				response.status()
-			));
+			return Err(HttpStatusError::from_response("Webhook", &response).into());
 		}
 
 		Ok(())
@@ -267,11 +560,13 @@ mod tests {
 			url: "https://webhook.example.com".to_string(),
 			method: Some("POST".to_string()),
 			secret: None,
+			secrets: None,
 			headers: None,
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
 			},
+			payload: None,
 		}
 	}
 
@@ -322,6 +617,114 @@ mod tests {
 		assert_eq!(result, "");
 	}
 
+	////////////////////////////////////////////////////////////
+	// build_request tests
+	////////////////////////////////////////////////////////////
+
+	fn create_test_payload(format: WebhookPayloadFormat, template: Value) -> WebhookPayload {
+		WebhookPayload {
+			format,
+			template: Some(template),
+			content_type: None,
+			query: None,
+			path: None,
+		}
+	}
+
+	#[test]
+	fn test_build_request_default_message() {
+		let notifier =
+			create_test_notifier("https://webhook.example.com", "Value ${value}", None, None);
+		let variables = HashMap::from([("value".to_string(), "100".to_string())]);
+
+		let request = notifier.build_request(&variables, None).unwrap();
+		assert_eq!(request.url, "https://webhook.example.com");
+		assert_eq!(request.content_type, "application/json");
+		assert_eq!(
+			serde_json::from_slice::<Value>(&request.body).unwrap(),
+			serde_json::json!({ "title": "Alert", "body": "Value 100" })
+		);
+	}
+
+	#[test]
+	fn test_build_request_json_payload() {
+		let mut payload = create_test_payload(
+			WebhookPayloadFormat::Json,
+			serde_json::json!({
+				"text": "{{ from | short_address }} sent {{ value }}",
+				"value": "{{ value | number }}",
+				"hex": "{{ number(\"0xff\") }}",
+				"failed": "{{ failed | bool }}",
+				"nested": [{ "id": 1, "hash": "{{ hash }}" }]
+			}),
+		);
+		payload.path = Some("/v1/{{ name }}/".to_string());
+		payload.query = Some(HashMap::from([
+			("b".to_string(), "{{ value }}".to_string()),
+			("a".to_string(), "x&y".to_string()),
+		]));
+		let notifier = create_test_notifier("https://webhook.example.com/", "", None, None)
+			.with_payload(payload);
+		let variables = HashMap::from([
+			(
+				"from".to_string(),
+				"0x2e8135be71230c6b1b4045696d41c09db0414226".to_string(),
+			),
+			("value".to_string(), "1500".to_string()),
+			("hash".to_string(), "0xabc".to_string()),
+			("name".to_string(), "a/b c".to_string()),
+		]);
+
+		let request = notifier.build_request(&variables, None).unwrap();
+		assert_eq!(
+			request.url,
+			"https://webhook.example.com/v1/a%2Fb%20c/?a=x%26y&b=1500"
+		);
+		assert_eq!(
+			serde_json::from_slice::<Value>(&request.body).unwrap(),
+			serde_json::json!({
+				"text": "0x2e81...4226 sent 1500",
+				"value": 1500,
+				"hex": 255,
+				"failed": false,
+				"nested": [{ "id": 1, "hash": "0xabc" }]
+			})
+		);
+	}
+
+	#[test]
+	fn test_build_request_form_payload() {
+		let mut payload = create_test_payload(
+			WebhookPayloadFormat::Form,
+			serde_json::json!({ "text": "{{ value }} & more", "count": 2 }),
+		);
+		payload.content_type = Some("application/x-www-form-urlencoded; charset=utf-8".to_string());
+		let notifier = create_test_notifier("https://webhook.example.com", "", None, None)
+			.with_payload(payload);
+		let variables = HashMap::from([("value".to_string(), "1".to_string())]);
+
+		let request = notifier.build_request(&variables, None).unwrap();
+		assert_eq!(
+			request.content_type,
+			"application/x-www-form-urlencoded; charset=utf-8"
+		);
+		assert_eq!(
+			String::from_utf8(request.body).unwrap(),
+			"count=2&text=1+%26+more"
+		);
+	}
+
+	#[test]
+	fn test_build_request_match_payload_without_match() {
+		let notifier =
+			create_test_notifier("https://webhook.example.com", "", None, None).with_payload(
+				create_test_payload(WebhookPayloadFormat::Match, Value::Null),
+			);
+
+		let error = notifier.build_request(&HashMap::new(), None).unwrap_err();
+		assert!(error.to_string().contains("requires a monitor match"));
+	}
+
 	////////////////////////////////////////////////////////////
 	// sign_request tests
 	////////////////////////////////////////////////////////////
@@ -334,17 +737,100 @@ mod tests {
 			Some("test-secret"),
 			None,
 		);
-		let payload = WebhookMessage {
-			title: "Test Title".to_string(),
-			body: "Test message".to_string(),
-		};
-		let secret = "test-secret";
+		let body = br#"{"title":"Test Title","body":"Test message"}"#;
 
-		let result = notifier.sign_request(secret, &payload).unwrap();
+		let result = notifier.sign_request(body).unwrap();
 		let (signature, timestamp) = result;
 
 		assert!(!signature.is_empty());
 		assert!(!timestamp.is_empty());
+		assert_eq!(
+			signature,
+			compute_webhook_signature("test-secret", &timestamp, body).unwrap()
+		);
+	}
+
+	#[test]
+	fn test_sign_request_with_rotated_secrets() {
+		let notifier = create_test_notifier(
+			"https://webhook.example.com",
+			"Test message",
+			Some("new-secret"),
+			None,
+		)
+		.with_secrets(vec!["old-secret".to_string()]);
+		let body = b"payload";
+
+		let (signature, timestamp) = notifier.sign_request(body).unwrap();
+		let signatures: Vec<&str> = signature.split(',').collect();
+		assert_eq!(signatures.len(), 2);
+
+		// Receivers holding either secret accept the request
+		for secret in ["new-secret", "old-secret"] {
+			assert!(verify_webhook_signature(
+				&[secret],
+				body,
+				&timestamp,
+				&signature,
+				Some(Duration::from_secs(300))
+			));
+		}
+		assert!(!verify_webhook_signature(
+			&["other-secret"],
+			body,
+			&timestamp,
+			&signature,
+			None
+		));
+	}
+
+	#[test]
+	fn test_verify_webhook_signature() {
+		let body = br#"{"title":"Alert","body":"Test"}"#;
+		let timestamp = "1700000000000";
+		let signature = "38771fdc5f551ec4a502c6d6cbe1ebd66a8cc615469ad041635f95c56ec98a7b";
+
+		assert_eq!(
+			compute_webhook_signature("test-secret", timestamp, body).unwrap(),
+			signature
+		);
+		assert!(verify_webhook_signature(
+			&["test-secret"],
+			body,
+			timestamp,
+			signature,
+			None
+		));
+		// Tampered body, timestamp or signature
+		assert!(!verify_webhook_signature(
+			&["test-secret"],
+			br#"{"title":"Alert","body":"Tampered"}"#,
+			timestamp,
+			signature,
+			None
+		));
+		assert!(!verify_webhook_signature(
+			&["test-secret"],
+			body,
+			"1700000000001",
+			signature,
+			None
+		));
+		assert!(!verify_webhook_signature(
+			&["test-secret"],
+			body,
+			timestamp,
+			"not-hex",
+			None
+		));
+		// Expired timestamp
+		assert!(!verify_webhook_signature(
+			&["test-secret"],
+			body,
+			timestamp,
+			signature,
+			Some(Duration::from_secs(300))
+		));
 	}
 
 	////////////////////////////////////////////////////////////
@@ -383,6 +869,7 @@ mod tests {
 			.mock("POST", "/")
 			.match_header("X-Signature", Matcher::Regex("^[0-9a-f]{64}$".to_string()))
 			.match_header("X-Timestamp", Matcher::Regex("^[0-9]+$".to_string()))
+			.match_header("X-Signature-Version", "v1")
 			.match_header("Content-Type", "text/plain")
 			.with_status(200)
 			.create_async()
@@ -506,12 +993,7 @@ mod tests {
 			None,
 		);
 
-		let payload = WebhookMessage {
-			title: "Test Title".to_string(),
-			body: "Test message".to_string(),
-		};
-
-		let result = notifier.sign_request("test-secret", &payload).unwrap();
+		let result = notifier.sign_request(b"Test message").unwrap();
 		let (signature, timestamp) = result;
 
 		// Validate signature format (should be a hex string)
@@ -526,4 +1008,28 @@ mod tests {
 			"Timestamp should be valid i64"
 		);
 	}
+
+	#[tokio::test]
+	async fn test_notify_with_alert_headers() {
+		let mut server = mockito::Server::new_async().await;
+		let mock = server
+			.mock("POST", "/")
+			.match_header("X-Correlation-Id", "abc123")
+			.match_header("X-Alert-Phase", "pending")
+			.with_status(200)
+			.create_async()
+			.await;
+
+		let variables = HashMap::from([
+			("correlation_id".to_string(), "abc123".to_string()),
+			("alert_phase".to_string(), "pending".to_string()),
+		]);
+		let notifier = create_test_notifier(server.url().as_str(), "Test message", None, None)
+			.with_alert_headers(&variables);
+
+		let result = notifier.notify("Test message").await;
+
+		assert!(result.is_ok());
+		mock.assert();
+	}
 }
@@ -0,0 +1,473 @@
+//! History of monitor matches.
+//!
+//! Every match queued for delivery is recorded with the fields summarized by reports, along
+//! with the match itself so the live match stream can replay it. Records are appended to one
+//! JSONL file per UTC day, and days older than the retention are removed whenever a new day
+//! starts.
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashSet,
+	path::PathBuf,
+	sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use crate::{
+	models::{MonitorMatch, Severity},
+	services::filter::build_match_variables,
+};
+
+/// Number of days of history kept
+const HISTORY_RETENTION_DAYS: i64 = 35;
+
+/// Sequence distinguishing the records created in the same millisecond
+static RECORD_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// A recorded monitor match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+	/// Unique ID of the record, `{recorded_at}-{sequence}`, used as cursor of the match stream
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub id: String,
+	/// Time the match was recorded in milliseconds since the Unix epoch
+	pub recorded_at: i64,
+	/// Name of the monitor that matched
+	pub monitor_name: String,
+	/// Network the match was found on
+	pub network_slug: String,
+	/// Block the match was found in
+	pub block_number: u64,
+	/// Hash of the matched transaction
+	pub transaction_hash: String,
+	/// Sender of the transaction, if known for the network
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub from: Option<String>,
+	/// Receiver of the transaction, if known for the network
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub to: Option<String>,
+	/// Value of the transaction, if known for the network
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub value: Option<String>,
+	/// Severity of the monitor that matched
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub severity: Option<Severity>,
+	/// The match itself, replayed by the match stream
+	#[serde(default, rename = "match", skip_serializing_if = "Option::is_none")]
+	pub monitor_match: Option<MonitorMatch>,
+}
+
+impl MatchRecord {
+	/// Creates a record of a match
+	///
+	/// # Arguments
+	/// * `network_slug` - Network the match was found on
+	/// * `block_number` - Block the match was found in
+	/// * `monitor_match` - The match to record
+	/// * `recorded_at` - Current time in milliseconds since the Unix epoch
+	pub fn new(
+		network_slug: &str,
+		block_number: u64,
+		monitor_match: &MonitorMatch,
+		recorded_at: i64,
+	) -> Self {
+		let mut variables = build_match_variables(monitor_match);
+		Self {
+			id: format!(
+				"{}-{}",
+				recorded_at,
+				RECORD_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+			),
+			recorded_at,
+			monitor_name: monitor_match.monitor().name.clone(),
+			network_slug: network_slug.to_string(),
+			block_number,
+			transaction_hash: monitor_match.transaction_hash(),
+			from: variables.remove("transaction_from"),
+			to: variables.remove("transaction_to"),
+			value: variables.remove("transaction_value"),
+			severity: monitor_match.monitor().severity,
+			monitor_match: Some(monitor_match.clone()),
+		}
+	}
+}
+
+/// Interface for match history implementations
+#[async_trait]
+pub trait MatchHistory: Send + Sync {
+	/// Records matches
+	///
+	/// # Arguments
+	/// * `records` - Records to append to the history
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn record(&self, records: Vec<MatchRecord>) -> Result<(), anyhow::Error>;
+
+	/// Retrieves the matches of a monitor recorded in a period, oldest first
+	///
+	/// A transaction recorded more than once, for example when a block was processed again
+	/// after a restart, is returned once.
+	///
+	/// # Arguments
+	/// * `monitor_name` - Name of the monitor
+	/// * `from` - Start of the period in milliseconds since the Unix epoch, inclusive
+	/// * `to` - End of the period in milliseconds since the Unix epoch, exclusive
+	///
+	/// # Returns
+	/// * `Result<Vec<MatchRecord>, anyhow::Error>` - Records of the period
+	async fn matches(
+		&self,
+		monitor_name: &str,
+		from: i64,
+		to: i64,
+	) -> Result<Vec<MatchRecord>, anyhow::Error>;
+
+	/// Retrieves the matches of all monitors recorded after a cursor, in the order they were
+	/// recorded
+	///
+	/// The cursor is the ID of a record. If that record is no longer in the history, the
+	/// matches recorded after the time of the cursor are returned.
+	///
+	/// # Arguments
+	/// * `cursor` - ID of the last record already seen
+	///
+	/// # Returns
+	/// * `Result<Vec<MatchRecord>, anyhow::Error>` - Records after the cursor
+	async fn since(&self, cursor: &str) -> Result<Vec<MatchRecord>, anyhow::Error>;
+}
+
+/// File-based implementation of the match history
+///
+/// Records are appended to `{date}.jsonl` files, one per UTC day.
+pub struct FileMatchHistory {
+	/// Base path of the history
+	storage_path: PathBuf,
+	/// Serializes appends to the history files
+	lock: Mutex<()>,
+}
+
+impl FileMatchHistory {
+	/// Creates a new file-based match history
+	///
+	/// The directory is created when the first match is recorded.
+	pub fn new(storage_path: PathBuf) -> Self {
+		FileMatchHistory {
+			storage_path,
+			lock: Mutex::new(()),
+		}
+	}
+
+	fn day_path(&self, day: NaiveDate) -> PathBuf {
+		self.storage_path
+			.join(format!("{}.jsonl", day.format("%Y-%m-%d")))
+	}
+
+	/// Lists the days with a history file, oldest first
+	async fn days(&self) -> Result<Vec<NaiveDate>, anyhow::Error> {
+		let mut dir = match tokio::fs::read_dir(&self.storage_path).await {
+			Ok(dir) => dir,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(anyhow::anyhow!("Failed to read match history: {}", e)),
+		};
+		let mut days = Vec::new();
+		while let Some(file) = dir
+			.next_entry()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read match history: {}", e))?
+		{
+			let path = file.path();
+			if path.extension().is_some_and(|ext| ext == "jsonl") {
+				if let Some(day) = path
+					.file_stem()
+					.and_then(|stem| stem.to_str())
+					.and_then(|stem| NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok())
+				{
+					days.push(day);
+				}
+			}
+		}
+		days.sort();
+		Ok(days)
+	}
+
+	/// Reads the records of a day in the order they were recorded
+	async fn read_day(&self, day: NaiveDate) -> Result<Vec<MatchRecord>, anyhow::Error> {
+		let content = match tokio::fs::read_to_string(self.day_path(day)).await {
+			Ok(content) => content,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(anyhow::anyhow!("Failed to read match history: {}", e)),
+		};
+		Ok(content
+			.lines()
+			.filter_map(|line| match serde_json::from_str::<MatchRecord>(line) {
+				Ok(record) => Some(record),
+				Err(e) => {
+					tracing::error!("Skipping unreadable match record: {}", e);
+					None
+				}
+			})
+			.collect())
+	}
+
+	/// Removes the files of the days older than the retention
+	async fn prune(&self, today: NaiveDate) -> Result<(), anyhow::Error> {
+		for day in self.days().await? {
+			if (today - day).num_days() > HISTORY_RETENTION_DAYS {
+				tokio::fs::remove_file(self.day_path(day))
+					.await
+					.map_err(|e| anyhow::anyhow!("Failed to prune match history: {}", e))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Default for FileMatchHistory {
+	/// Default implementation for FileMatchHistory
+	///
+	/// Initializes the history with the default path "data/history"
+	fn default() -> Self {
+		FileMatchHistory::new(PathBuf::from("data").join("history"))
+	}
+}
+
+/// Returns the UTC day of a timestamp in milliseconds since the Unix epoch
+fn day_of(timestamp: i64) -> NaiveDate {
+	DateTime::<Utc>::from_timestamp_millis(timestamp)
+		.map(|time| time.date_naive())
+		.unwrap_or(if timestamp < 0 {
+			NaiveDate::MIN
+		} else {
+			NaiveDate::MAX
+		})
+}
+
+#[async_trait]
+impl MatchHistory for FileMatchHistory {
+	async fn record(&self, records: Vec<MatchRecord>) -> Result<(), anyhow::Error> {
+		let Some(first) = records.first() else {
+			return Ok(());
+		};
+		let _guard = self.lock.lock().await;
+		tokio::fs::create_dir_all(&self.storage_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to create match history directory: {}", e))?;
+
+		let today = day_of(first.recorded_at);
+		let path = self.day_path(today);
+		if !path.exists() {
+			self.prune(today).await?;
+		}
+
+		let mut lines = String::new();
+		for record in &records {
+			let line = serde_json::to_string(record)
+				.map_err(|e| anyhow::anyhow!("Failed to serialize match record: {}", e))?;
+			lines.push_str(&line);
+			lines.push('\n');
+		}
+		let mut file = tokio::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to open match history: {}", e))?;
+		file.write_all(lines.as_bytes())
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to record matches: {}", e))?;
+		Ok(())
+	}
+
+	async fn matches(
+		&self,
+		monitor_name: &str,
+		from: i64,
+		to: i64,
+	) -> Result<Vec<MatchRecord>, anyhow::Error> {
+		let mut records = Vec::new();
+		let mut seen = HashSet::new();
+		let (first_day, last_day) = (day_of(from), day_of(to));
+		let days = self.days().await?;
+		for day in days
+			.into_iter()
+			.filter(|day| (first_day..=last_day).contains(day))
+		{
+			for record in self.read_day(day).await? {
+				if record.monitor_name == monitor_name
+					&& (from..to).contains(&record.recorded_at)
+					&& seen.insert((record.network_slug.clone(), record.transaction_hash.clone()))
+				{
+					records.push(record);
+				}
+			}
+		}
+		Ok(records)
+	}
+
+	async fn since(&self, cursor: &str) -> Result<Vec<MatchRecord>, anyhow::Error> {
+		let after = cursor
+			.split_once('-')
+			.map_or(cursor, |(time, _)| time)
+			.parse::<i64>()
+			.map_err(|_| anyhow::anyhow!("Invalid match cursor '{}'", cursor))?;
+		let first_day = day_of(after);
+
+		let mut records = Vec::new();
+		let mut found = false;
+		for day in self
+			.days()
+			.await?
+			.into_iter()
+			.filter(|day| *day >= first_day)
+		{
+			for record in self.read_day(day).await? {
+				if record.id == cursor {
+					found = true;
+				} else if found || record.recorded_at > after {
+					records.push(record);
+				}
+			}
+		}
+		Ok(records)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	fn create_record(monitor_name: &str, hash: &str, recorded_at: i64) -> MatchRecord {
+		MatchRecord {
+			id: format!("{}-{}", recorded_at, hash),
+			recorded_at,
+			monitor_name: monitor_name.to_string(),
+			network_slug: "ethereum_mainnet".to_string(),
+			block_number: 1,
+			transaction_hash: hash.to_string(),
+			from: Some("0xaaa".to_string()),
+			to: Some("0xbbb".to_string()),
+			value: Some("100".to_string()),
+			severity: None,
+			monitor_match: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_record_and_query_matches() {
+		let temp_dir = TempDir::new().unwrap();
+		let history = FileMatchHistory::new(temp_dir.path().to_path_buf());
+		let day = 24 * 60 * 60 * 1000;
+
+		history
+			.record(vec![
+				create_record("transfers", "0x1", day),
+				create_record("transfers", "0x2", day + 1),
+				create_record("other", "0x3", day + 2),
+			])
+			.await
+			.unwrap();
+		// Recording a match again does not count it twice
+		history
+			.record(vec![create_record("transfers", "0x2", day + 3)])
+			.await
+			.unwrap();
+		history
+			.record(vec![create_record("transfers", "0x4", 2 * day + 5)])
+			.await
+			.unwrap();
+
+		let records = history.matches("transfers", 0, 3 * day).await.unwrap();
+		let hashes: Vec<&str> = records
+			.iter()
+			.map(|record| record.transaction_hash.as_str())
+			.collect();
+		assert_eq!(hashes, vec!["0x1", "0x2", "0x4"]);
+
+		let records = history
+			.matches("transfers", day + 1, 2 * day)
+			.await
+			.unwrap();
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].transaction_hash, "0x2");
+	}
+
+	#[tokio::test]
+	async fn test_records_since_cursor() {
+		let temp_dir = TempDir::new().unwrap();
+		let history = FileMatchHistory::new(temp_dir.path().to_path_buf());
+		let day = 24 * 60 * 60 * 1000;
+
+		history
+			.record(vec![
+				create_record("transfers", "0x1", day),
+				create_record("other", "0x2", day),
+				create_record("transfers", "0x3", day + 1),
+			])
+			.await
+			.unwrap();
+		history
+			.record(vec![create_record("other", "0x4", 2 * day)])
+			.await
+			.unwrap();
+
+		let hashes = |records: Vec<MatchRecord>| -> Vec<String> {
+			records
+				.into_iter()
+				.map(|record| record.transaction_hash)
+				.collect()
+		};
+		// Records of the same millisecond after the cursor are replayed
+		assert_eq!(
+			hashes(history.since(&format!("{}-0x1", day)).await.unwrap()),
+			vec!["0x2", "0x3", "0x4"]
+		);
+		assert_eq!(
+			hashes(history.since(&format!("{}-0x3", day + 1)).await.unwrap()),
+			vec!["0x4"]
+		);
+		// Unknown cursors fall back to the time of the cursor
+		assert_eq!(
+			hashes(history.since(&format!("{}-0x9", day)).await.unwrap()),
+			vec!["0x3", "0x4"]
+		);
+		assert!(history.since("not-a-cursor").await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_prune_old_days() {
+		let temp_dir = TempDir::new().unwrap();
+		let history = FileMatchHistory::new(temp_dir.path().to_path_buf());
+		let day = 24 * 60 * 60 * 1000;
+
+		history
+			.record(vec![create_record("transfers", "0x1", day)])
+			.await
+			.unwrap();
+		history
+			.record(vec![create_record(
+				"transfers",
+				"0x2",
+				(HISTORY_RETENTION_DAYS + 2) * day,
+			)])
+			.await
+			.unwrap();
+
+		assert!(history
+			.matches("transfers", 0, 2 * day)
+			.await
+			.unwrap()
+			.is_empty());
+		assert_eq!(
+			history
+				.matches("transfers", 0, (HISTORY_RETENTION_DAYS + 3) * day)
+				.await
+				.unwrap()
+				.len(),
+			1
+		);
+	}
+}
@@ -0,0 +1,11 @@
+//! Summary report service implementation.
+//!
+//! This module records the history of monitor matches and summarizes it into
+//! reports, which are sent through triggers on the schedules configured by
+//! the monitors.
+
+mod history;
+mod summary;
+
+pub use history::{FileMatchHistory, MatchHistory, MatchRecord};
+pub use summary::{MonitorReport, RankedValue};
@@ -0,0 +1,332 @@
+//! Summary reports of monitors.
+//!
+//! A report summarizes the matches of a monitor over a period: the number of matches, the most
+//! frequent senders and receivers, the largest transaction values and the number of deliveries
+//! that failed for good. Reports render as plain text for chat triggers and as HTML tables for
+//! email.
+
+use alloy::primitives::U256;
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, fmt::Write};
+
+use crate::services::{report::MatchRecord, trigger::OutboxEntry};
+
+/// A transaction value ranked by a report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedValue {
+	/// Value of the transaction
+	pub value: String,
+	/// Hash of the transaction
+	pub transaction_hash: String,
+	/// Network of the transaction
+	pub network_slug: String,
+}
+
+/// Summary of the matches of a monitor over a period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorReport {
+	/// Name of the reported monitor
+	pub monitor_name: String,
+	/// Start of the period in milliseconds since the Unix epoch
+	pub period_start: i64,
+	/// End of the period in milliseconds since the Unix epoch
+	pub period_end: i64,
+	/// Number of matches in the period
+	pub match_count: usize,
+	/// Number of deliveries of the monitor's matches dead-lettered in the period
+	pub failed_deliveries: usize,
+	/// Most frequent senders with their number of matches
+	pub top_senders: Vec<(String, usize)>,
+	/// Most frequent receivers with their number of matches
+	pub top_receivers: Vec<(String, usize)>,
+	/// Largest transaction values, largest first
+	pub largest_values: Vec<RankedValue>,
+}
+
+impl MonitorReport {
+	/// Summarizes the matches and failed deliveries of a monitor
+	///
+	/// # Arguments
+	/// * `monitor_name` - Name of the monitor
+	/// * `period_start` - Start of the period in milliseconds since the Unix epoch
+	/// * `period_end` - End of the period in milliseconds since the Unix epoch
+	/// * `records` - Matches of the monitor recorded in the period
+	/// * `dead_letters` - Entries of the dead-letter queue, of any monitor
+	/// * `top` - Number of entries of the top lists
+	pub fn new(
+		monitor_name: &str,
+		period_start: i64,
+		period_end: i64,
+		records: &[MatchRecord],
+		dead_letters: &[OutboxEntry],
+		top: usize,
+	) -> Self {
+		let failed_deliveries = dead_letters
+			.iter()
+			.filter(|entry| {
+				entry.monitor_match.monitor().name == monitor_name
+					&& entry
+						.dead_lettered_at
+						.is_some_and(|at| (period_start..period_end).contains(&at))
+			})
+			.count();
+
+		let mut values: Vec<(U256, RankedValue)> = records
+			.iter()
+			.filter_map(|record| {
+				let value = U256::from_str_radix(record.value.as_deref()?, 10).ok()?;
+				(!value.is_zero()).then(|| {
+					(
+						value,
+						RankedValue {
+							value: value.to_string(),
+							transaction_hash: record.transaction_hash.clone(),
+							network_slug: record.network_slug.clone(),
+						},
+					)
+				})
+			})
+			.collect();
+		values.sort_by_key(|(value, _)| std::cmp::Reverse(*value));
+		values.truncate(top);
+
+		Self {
+			monitor_name: monitor_name.to_string(),
+			period_start,
+			period_end,
+			match_count: records.len(),
+			failed_deliveries,
+			top_senders: rank(
+				records.iter().filter_map(|record| record.from.as_deref()),
+				top,
+			),
+			top_receivers: rank(
+				records.iter().filter_map(|record| record.to.as_deref()),
+				top,
+			),
+			largest_values: values.into_iter().map(|(_, value)| value).collect(),
+		}
+	}
+
+	/// Returns the title of the report
+	pub fn title(&self) -> String {
+		format!("Report for {}", self.monitor_name)
+	}
+
+	/// Renders the report as plain text
+	pub fn to_text(&self) -> String {
+		let mut text = format!(
+			"Period: {} - {}\nMatches: {}\nFailed deliveries: {}\n",
+			format_time(self.period_start),
+			format_time(self.period_end),
+			self.match_count,
+			self.failed_deliveries
+		);
+
+		for (heading, ranking) in [
+			("Top senders", &self.top_senders),
+			("Top receivers", &self.top_receivers),
+		] {
+			let _ = write!(text, "\n{}:\n", heading);
+			if ranking.is_empty() {
+				text.push_str("None\n");
+			}
+			for (index, (address, count)) in ranking.iter().enumerate() {
+				let _ = writeln!(text, "{}. {} ({} matches)", index + 1, address, count);
+			}
+		}
+
+		text.push_str("\nLargest values:\n");
+		if self.largest_values.is_empty() {
+			text.push_str("None\n");
+		}
+		for (index, value) in self.largest_values.iter().enumerate() {
+			let _ = writeln!(
+				text,
+				"{}. {} in {} on {}",
+				index + 1,
+				value.value,
+				value.transaction_hash,
+				value.network_slug
+			);
+		}
+		text
+	}
+
+	/// Renders the report as an HTML document of tables
+	pub fn to_html(&self) -> String {
+		let mut html = format!(
+			"<html><body>\n<h2>{}</h2>\n<table border=\"1\" cellpadding=\"4\" \
+			 cellspacing=\"0\">\n<tr><th>Period</th><td>{} - {}</td></tr>\n\
+			 <tr><th>Matches</th><td>{}</td></tr>\n<tr><th>Failed deliveries</th><td>{}</td></tr>\n\
+			 </table>\n",
+			escape_html(&self.title()),
+			format_time(self.period_start),
+			format_time(self.period_end),
+			self.match_count,
+			self.failed_deliveries
+		);
+
+		for (heading, ranking) in [
+			("Top senders", &self.top_senders),
+			("Top receivers", &self.top_receivers),
+		] {
+			let rows: Vec<Vec<String>> = ranking
+				.iter()
+				.map(|(address, count)| vec![escape_html(address), count.to_string()])
+				.collect();
+			push_html_table(&mut html, heading, &["Address", "Matches"], &rows);
+		}
+
+		let rows: Vec<Vec<String>> = self
+			.largest_values
+			.iter()
+			.map(|value| {
+				vec![
+					escape_html(&value.value),
+					escape_html(&value.transaction_hash),
+					escape_html(&value.network_slug),
+				]
+			})
+			.collect();
+		push_html_table(
+			&mut html,
+			"Largest values",
+			&["Value", "Transaction", "Network"],
+			&rows,
+		);
+
+		html.push_str("</body></html>\n");
+		html
+	}
+}
+
+/// Returns the most frequent values with their count, most frequent first
+fn rank<'a>(values: impl Iterator<Item = &'a str>, top: usize) -> Vec<(String, usize)> {
+	let mut counts: HashMap<&str, usize> = HashMap::new();
+	for value in values {
+		*counts.entry(value).or_default() += 1;
+	}
+	let mut ranking: Vec<(String, usize)> = counts
+		.into_iter()
+		.map(|(value, count)| (value.to_string(), count))
+		.collect();
+	ranking.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+	ranking.truncate(top);
+	ranking
+}
+
+/// Appends a numbered HTML table with a heading
+fn push_html_table(html: &mut String, heading: &str, columns: &[&str], rows: &[Vec<String>]) {
+	let _ = write!(
+		html,
+		"<h3>{}</h3>\n<table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\n<tr><th>#</th>",
+		heading
+	);
+	for column in columns {
+		let _ = write!(html, "<th>{}</th>", column);
+	}
+	html.push_str("</tr>\n");
+	if rows.is_empty() {
+		let _ = writeln!(
+			html,
+			"<tr><td colspan=\"{}\">None</td></tr>",
+			columns.len() + 1
+		);
+	}
+	for (index, row) in rows.iter().enumerate() {
+		let _ = write!(html, "<tr><td>{}</td>", index + 1);
+		for cell in row {
+			let _ = write!(html, "<td>{}</td>", cell);
+		}
+		html.push_str("</tr>\n");
+	}
+	html.push_str("</table>\n");
+}
+
+/// Formats a timestamp in milliseconds since the Unix epoch as a UTC date and time
+fn format_time(timestamp: i64) -> String {
+	DateTime::<Utc>::from_timestamp_millis(timestamp)
+		.unwrap_or_default()
+		.format("%Y-%m-%d %H:%M UTC")
+		.to_string()
+}
+
+/// Escapes the characters with a special meaning in HTML
+fn escape_html(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn create_record(hash: &str, from: &str, to: &str, value: &str) -> MatchRecord {
+		MatchRecord {
+			id: String::new(),
+			recorded_at: 1_000,
+			monitor_name: "transfers".to_string(),
+			network_slug: "ethereum_mainnet".to_string(),
+			block_number: 1,
+			transaction_hash: hash.to_string(),
+			from: Some(from.to_string()),
+			to: Some(to.to_string()),
+			value: Some(value.to_string()),
+			severity: None,
+			monitor_match: None,
+		}
+	}
+
+	#[test]
+	fn test_report_summarizes_matches() {
+		let records = vec![
+			create_record("0x1", "0xaaa", "0xbbb", "100"),
+			create_record("0x2", "0xaaa", "0xccc", "0"),
+			create_record("0x3", "0xddd", "0xbbb", "5000"),
+			create_record("0x4", "0xaaa", "0xbbb", "250"),
+		];
+		let report = MonitorReport::new("transfers", 0, 86_400_000, &records, &[], 2);
+
+		assert_eq!(report.match_count, 4);
+		assert_eq!(report.failed_deliveries, 0);
+		assert_eq!(
+			report.top_senders,
+			vec![("0xaaa".to_string(), 3), ("0xddd".to_string(), 1)]
+		);
+		assert_eq!(
+			report.top_receivers,
+			vec![("0xbbb".to_string(), 3), ("0xccc".to_string(), 1)]
+		);
+		let values: Vec<&str> = report
+			.largest_values
+			.iter()
+			.map(|value| value.value.as_str())
+			.collect();
+		assert_eq!(values, vec!["5000", "250"]);
+
+		let text = report.to_text();
+		assert!(text.contains("Period: 1970-01-01 00:00 UTC - 1970-01-02 00:00 UTC"));
+		assert!(text.contains("Matches: 4"));
+		assert!(text.contains("1. 0xaaa (3 matches)"));
+		assert!(text.contains("1. 5000 in 0x3 on ethereum_mainnet"));
+
+		let html = report.to_html();
+		assert!(html.contains("<tr><th>Matches</th><td>4</td></tr>"));
+		assert!(html.contains("<tr><td>1</td><td>0xaaa</td><td>3</td></tr>"));
+	}
+
+	#[test]
+	fn test_empty_report() {
+		let report = MonitorReport::new("<monitor>", 0, 1_000, &[], &[], 5);
+		assert_eq!(report.match_count, 0);
+		assert!(report.to_text().contains("Top senders:\nNone\n"));
+
+		let html = report.to_html();
+		assert!(html.contains("<h2>Report for &lt;monitor&gt;</h2>"));
+		assert!(html.contains("<tr><td colspan=\"3\">None</td></tr>"));
+	}
+}
@@ -0,0 +1,393 @@
+//! Live stream of monitor matches.
+//!
+//! Matches recorded in the match history are broadcast to the subscribers of the stream, which
+//! the metrics server exposes over server-sent events and WebSocket. Every event carries the ID
+//! of its history record as cursor, so a subscriber reconnecting with the cursor of the last
+//! event it received is replayed the matches it missed from the history before the live ones.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{HashSet, VecDeque},
+	sync::Arc,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+	models::{MonitorMatch, Severity},
+	services::report::{MatchHistory, MatchRecord},
+};
+
+/// Number of events buffered for the subscribers of the stream
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// A match published on the stream
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchEvent {
+	/// Cursor of the event, the ID of its history record
+	pub id: String,
+	/// Time the match was recorded in milliseconds since the Unix epoch
+	pub recorded_at: i64,
+	/// Name of the monitor that matched
+	pub monitor_name: String,
+	/// Network the match was found on
+	pub network_slug: String,
+	/// Block the match was found in
+	pub block_number: u64,
+	/// Hash of the matched transaction
+	pub transaction_hash: String,
+	/// Severity of the monitor that matched
+	pub severity: Option<Severity>,
+	/// The match itself
+	#[serde(rename = "match")]
+	pub monitor_match: MonitorMatch,
+}
+
+impl MatchEvent {
+	/// Creates the event of a history record
+	///
+	/// # Returns
+	/// * `Option<Self>` - Event, or None if the record has no ID or match, like the records of
+	///   earlier versions
+	pub fn from_record(record: MatchRecord) -> Option<Self> {
+		if record.id.is_empty() {
+			return None;
+		}
+		Some(Self {
+			id: record.id,
+			recorded_at: record.recorded_at,
+			monitor_name: record.monitor_name,
+			network_slug: record.network_slug,
+			block_number: record.block_number,
+			transaction_hash: record.transaction_hash,
+			severity: record.severity,
+			monitor_match: record.monitor_match?,
+		})
+	}
+}
+
+/// Filter of the events a subscriber receives
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MatchFilter {
+	/// Slug of the network of the matches
+	pub network: Option<String>,
+	/// Name of the monitor of the matches
+	pub monitor: Option<String>,
+	/// Minimum severity of the matches, matches of monitors without severity are excluded
+	pub severity: Option<Severity>,
+}
+
+impl MatchFilter {
+	/// Returns whether an event passes the filter
+	pub fn matches(&self, event: &MatchEvent) -> bool {
+		self.network
+			.as_ref()
+			.is_none_or(|network| *network == event.network_slug)
+			&& self
+				.monitor
+				.as_ref()
+				.is_none_or(|monitor| *monitor == event.monitor_name)
+			&& self
+				.severity
+				.is_none_or(|severity| event.severity.is_some_and(|s| s >= severity))
+	}
+}
+
+/// Subscription to the stream, yielding the replayed events and then the live ones
+pub struct MatchSubscription {
+	/// Events replayed from the history, not sent yet
+	replay: VecDeque<Arc<MatchEvent>>,
+	/// IDs of the replayed events, skipped if they are also received live
+	replayed: HashSet<String>,
+	/// Receiver of the live events
+	receiver: broadcast::Receiver<Arc<MatchEvent>>,
+	/// Filter of the events
+	filter: MatchFilter,
+}
+
+impl MatchSubscription {
+	/// Returns the next event passing the filter
+	///
+	/// Cancelling the future does not lose events.
+	///
+	/// # Returns
+	/// * `Option<Arc<MatchEvent>>` - Next event, or None once the stream is closed or the
+	///   subscriber fell behind the buffer of the stream, in which case it should subscribe
+	///   again from its last cursor
+	pub async fn next(&mut self) -> Option<Arc<MatchEvent>> {
+		if let Some(event) = self.replay.pop_front() {
+			return Some(event);
+		}
+		loop {
+			match self.receiver.recv().await {
+				Ok(event) => {
+					if self.filter.matches(&event) && !self.replayed.contains(&event.id) {
+						return Some(event);
+					}
+				}
+				Err(RecvError::Lagged(skipped)) => {
+					tracing::warn!(
+						"Match stream subscriber fell behind by {} events, closing it",
+						skipped
+					);
+					return None;
+				}
+				Err(RecvError::Closed) => return None,
+			}
+		}
+	}
+}
+
+/// Match history broadcasting the recorded matches to the subscribers of the stream
+///
+/// Records are saved to the wrapped history first, which also serves the replays.
+pub struct MatchStream {
+	/// History the matches are recorded in and replayed from
+	history: Arc<dyn MatchHistory>,
+	/// Sender of the live events
+	sender: broadcast::Sender<Arc<MatchEvent>>,
+}
+
+impl MatchStream {
+	/// Creates a stream of the matches recorded in a history
+	pub fn new(history: Arc<dyn MatchHistory>) -> Self {
+		Self::with_capacity(history, DEFAULT_CAPACITY)
+	}
+
+	/// Creates a stream buffering up to `capacity` events for its subscribers
+	pub fn with_capacity(history: Arc<dyn MatchHistory>, capacity: usize) -> Self {
+		let (sender, _) = broadcast::channel(capacity);
+		Self { history, sender }
+	}
+
+	/// Subscribes to the stream
+	///
+	/// # Arguments
+	/// * `cursor` - Cursor of the last event received, the events recorded after it are
+	///   replayed from the history first
+	/// * `filter` - Filter of the events
+	///
+	/// # Returns
+	/// * `Result<MatchSubscription, anyhow::Error>` - Subscription, or an error if the cursor
+	///   is invalid or the history cannot be read
+	pub async fn subscribe(
+		&self,
+		cursor: Option<&str>,
+		filter: MatchFilter,
+	) -> Result<MatchSubscription, anyhow::Error> {
+		// Subscribing before reading the history avoids missing the events recorded meanwhile
+		let receiver = self.sender.subscribe();
+		let replay: VecDeque<Arc<MatchEvent>> = match cursor {
+			Some(cursor) => self
+				.history
+				.since(cursor)
+				.await?
+				.into_iter()
+				.filter_map(MatchEvent::from_record)
+				.filter(|event| filter.matches(event))
+				.map(Arc::new)
+				.collect(),
+			None => VecDeque::new(),
+		};
+		Ok(MatchSubscription {
+			replayed: replay.iter().map(|event| event.id.clone()).collect(),
+			replay,
+			receiver,
+			filter,
+		})
+	}
+
+	/// Returns the number of live subscribers
+	pub fn subscriber_count(&self) -> usize {
+		self.sender.receiver_count()
+	}
+}
+
+#[async_trait]
+impl MatchHistory for MatchStream {
+	/// Records matches in the history, then broadcasts them
+	///
+	/// Matches are broadcast even if they cannot be recorded.
+	async fn record(&self, records: Vec<MatchRecord>) -> Result<(), anyhow::Error> {
+		let result = self.history.record(records.clone()).await;
+		for event in records.into_iter().filter_map(MatchEvent::from_record) {
+			// Sending only fails without subscribers
+			let _ = self.sender.send(Arc::new(event));
+		}
+		result
+	}
+
+	async fn matches(
+		&self,
+		monitor_name: &str,
+		from: i64,
+		to: i64,
+	) -> Result<Vec<MatchRecord>, anyhow::Error> {
+		self.history.matches(monitor_name, from, to).await
+	}
+
+	async fn since(&self, cursor: &str) -> Result<Vec<MatchRecord>, anyhow::Error> {
+		self.history.since(cursor).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{
+			EVMMonitorMatch, EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor,
+		},
+		services::report::FileMatchHistory,
+	};
+	use tempfile::TempDir;
+
+	fn create_test_match(monitor_name: &str, severity: Option<Severity>) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: Monitor {
+				name: monitor_name.to_string(),
+				severity,
+				..Default::default()
+			},
+			transaction: EVMTransaction::default(),
+			receipt: EVMTransactionReceipt::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}))
+	}
+
+	fn create_record(network_slug: &str, monitor_match: &MonitorMatch) -> MatchRecord {
+		MatchRecord::new(
+			network_slug,
+			1,
+			monitor_match,
+			chrono::Utc::now().timestamp_millis(),
+		)
+	}
+
+	fn create_stream(temp_dir: &TempDir) -> MatchStream {
+		MatchStream::new(Arc::new(FileMatchHistory::new(
+			temp_dir.path().to_path_buf(),
+		)))
+	}
+
+	#[test]
+	fn test_filter_matches_events() {
+		let event = |network: &str, severity| {
+			MatchEvent::from_record(create_record(
+				network,
+				&create_test_match("transfers", severity),
+			))
+			.unwrap()
+		};
+		let filter = MatchFilter {
+			network: Some("ethereum_mainnet".to_string()),
+			monitor: Some("transfers".to_string()),
+			severity: Some(Severity::Error),
+		};
+
+		assert!(filter.matches(&event("ethereum_mainnet", Some(Severity::Critical))));
+		assert!(filter.matches(&event("ethereum_mainnet", Some(Severity::Error))));
+		assert!(!filter.matches(&event("ethereum_mainnet", Some(Severity::Warning))));
+		assert!(!filter.matches(&event("ethereum_mainnet", None)));
+		assert!(!filter.matches(&event("stellar_mainnet", Some(Severity::Critical))));
+		assert!(MatchFilter::default().matches(&event("stellar_mainnet", None)));
+	}
+
+	#[tokio::test]
+	async fn test_subscribers_receive_recorded_matches() {
+		let temp_dir = TempDir::new().unwrap();
+		let stream = create_stream(&temp_dir);
+		let mut all = stream
+			.subscribe(None, MatchFilter::default())
+			.await
+			.unwrap();
+		let mut stellar = stream
+			.subscribe(
+				None,
+				MatchFilter {
+					network: Some("stellar_mainnet".to_string()),
+					..Default::default()
+				},
+			)
+			.await
+			.unwrap();
+		assert_eq!(stream.subscriber_count(), 2);
+
+		let monitor_match = create_test_match("transfers", None);
+		stream
+			.record(vec![
+				create_record("ethereum_mainnet", &monitor_match),
+				create_record("stellar_mainnet", &monitor_match),
+			])
+			.await
+			.unwrap();
+
+		assert_eq!(all.next().await.unwrap().network_slug, "ethereum_mainnet");
+		assert_eq!(all.next().await.unwrap().network_slug, "stellar_mainnet");
+		assert_eq!(
+			stellar.next().await.unwrap().network_slug,
+			"stellar_mainnet"
+		);
+	}
+
+	#[tokio::test]
+	async fn test_subscribe_replays_from_cursor() {
+		let temp_dir = TempDir::new().unwrap();
+		let stream = create_stream(&temp_dir);
+		let records: Vec<MatchRecord> = ["first", "second", "third"]
+			.iter()
+			.map(|name| create_record("ethereum_mainnet", &create_test_match(name, None)))
+			.collect();
+		let cursor = records[0].id.clone();
+		stream.record(records).await.unwrap();
+
+		let mut subscription = stream
+			.subscribe(Some(&cursor), MatchFilter::default())
+			.await
+			.unwrap();
+		assert_eq!(subscription.next().await.unwrap().monitor_name, "second");
+		assert_eq!(subscription.next().await.unwrap().monitor_name, "third");
+
+		// Live events follow the replay
+		stream
+			.record(vec![create_record(
+				"ethereum_mainnet",
+				&create_test_match("fourth", None),
+			)])
+			.await
+			.unwrap();
+		assert_eq!(subscription.next().await.unwrap().monitor_name, "fourth");
+
+		assert!(stream
+			.subscribe(Some("invalid"), MatchFilter::default())
+			.await
+			.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_lagging_subscriber_is_closed() {
+		let temp_dir = TempDir::new().unwrap();
+		let stream = MatchStream::with_capacity(
+			Arc::new(FileMatchHistory::new(temp_dir.path().to_path_buf())),
+			1,
+		);
+		let mut subscription = stream
+			.subscribe(None, MatchFilter::default())
+			.await
+			.unwrap();
+
+		let monitor_match = create_test_match("transfers", None);
+		stream
+			.record(vec![
+				create_record("ethereum_mainnet", &monitor_match),
+				create_record("ethereum_mainnet", &monitor_match),
+			])
+			.await
+			.unwrap();
+
+		assert!(subscription.next().await.is_none());
+	}
+}
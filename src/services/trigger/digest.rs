@@ -0,0 +1,238 @@
+//! Digests of monitor matches.
+//!
+//! Triggers with a [`DigestPolicy`] deliver the matches of a processed block, or of a time window,
+//! as a single notification. The delivery worker folds every match into a digest entry of the
+//! outbox, which becomes due once the block or window is complete. The template variables of a
+//! digest give every match a prefixed copy of its variables for the loop section of the template,
+//! and sum the numeric variables across all matches.
+
+use alloy::primitives::U256;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::{
+	models::DigestPolicy,
+	services::{
+		filter::build_match_variables,
+		trigger::{DigestItem, OutboxEntry},
+	},
+};
+
+/// Time in milliseconds the remaining matches of a block are given to arrive before its digest
+/// is due
+const BLOCK_DIGEST_DELAY_MS: i64 = 5_000;
+
+/// Returns the idempotency key and due time of the digest an entry belongs to
+///
+/// Without a window, there is one digest per trigger and block. With a window, there is one
+/// digest per trigger and window, due when the window ends.
+///
+/// # Arguments
+/// * `entry` - Entry to aggregate
+/// * `policy` - Digest policy of the entry's trigger
+/// * `now` - Current time in milliseconds since the Unix epoch
+pub fn digest_slot(entry: &OutboxEntry, policy: &DigestPolicy, now: i64) -> (String, i64) {
+	let (slot, due_at) = match policy.window_ms {
+		Some(window_ms) => {
+			let window_ms = window_ms as i64;
+			let start = now - now.rem_euclid(window_ms);
+			(format!("window|{}", start), start + window_ms)
+		}
+		None => (
+			format!("block|{}|{}", entry.network_slug, entry.block_number),
+			now + BLOCK_DIGEST_DELAY_MS,
+		),
+	};
+
+	let mut hasher = Sha256::new();
+	hasher.update(format!("{}|digest|{}", entry.trigger, slot).as_bytes());
+	(hex::encode(hasher.finalize()), due_at)
+}
+
+/// Adds the match of an entry to a digest
+///
+/// Matches already in the digest are not added twice, so an entry can be aggregated again if
+/// the worker stopped before completing it.
+///
+/// # Arguments
+/// * `digest` - Pending digest, or None to start a new one
+/// * `entry` - Entry to aggregate
+/// * `key` - Idempotency key of the digest
+/// * `due_at` - Time the digest is due in milliseconds since the Unix epoch
+pub fn add_to_digest(
+	digest: Option<OutboxEntry>,
+	entry: &OutboxEntry,
+	key: &str,
+	due_at: i64,
+) -> OutboxEntry {
+	let mut digest = digest.unwrap_or_else(|| {
+		let mut digest = OutboxEntry::new(
+			&entry.network_slug,
+			entry.block_number,
+			entry.monitor_match.clone(),
+			&entry.trigger,
+		);
+		digest.key = key.to_string();
+		digest.next_attempt_at = due_at;
+		digest
+	});
+
+	let items = digest.digest.get_or_insert_with(Vec::new);
+	if !items.iter().any(|item| item.key == entry.key) {
+		items.push(DigestItem {
+			key: entry.key.clone(),
+			network_slug: entry.network_slug.clone(),
+			monitor_match: entry.monitor_match.clone(),
+		});
+	}
+	digest
+}
+
+/// Builds the template variables summarizing the matches of a digest
+///
+/// The variables are:
+/// * `match_count` - Number of matches in the digest
+/// * `omitted_count` - Number of matches left out of the loop section
+/// * `matches_{index}_{variable}` - Variables of each of the first `max_items` matches
+/// * `total_{variable}` - Sum of a variable over all matches, for variables whose values are all
+///   unsigned integers, e.g. `total_transaction_value`
+///
+/// # Arguments
+/// * `items` - Matches of the digest
+/// * `max_items` - Maximum number of matches rendered by the loop section
+pub fn build_digest_variables(items: &[DigestItem], max_items: usize) -> HashMap<String, String> {
+	let mut variables = HashMap::new();
+	let mut totals: HashMap<String, Option<U256>> = HashMap::new();
+
+	for (index, item) in items.iter().enumerate() {
+		let match_variables = build_match_variables(&item.monitor_match);
+		for (name, value) in &match_variables {
+			let total = totals.entry(name.clone()).or_insert(Some(U256::ZERO));
+			*total = total.and_then(|total| {
+				U256::from_str_radix(value, 10)
+					.ok()
+					.and_then(|value| total.checked_add(value))
+			});
+		}
+		if index < max_items {
+			variables.extend(
+				match_variables
+					.into_iter()
+					.map(|(name, value)| (format!("matches_{}_{}", index, name), value)),
+			);
+		}
+	}
+
+	variables.extend(totals.into_iter().filter_map(|(name, total)| {
+		total.map(|total| (format!("total_{}", name), total.to_string()))
+	}));
+	variables.insert("match_count".to_string(), items.len().to_string());
+	variables.insert(
+		"omitted_count".to_string(),
+		items.len().saturating_sub(max_items).to_string(),
+	);
+	variables
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{
+		MatchConditions, Monitor, MonitorMatch, StellarBlock, StellarMatchArguments,
+		StellarMatchParamEntry, StellarMatchParamsMap, StellarMonitorMatch, StellarTransaction,
+		StellarTransactionInfo,
+	};
+
+	fn create_entry(hash: &str, block_number: u64, amount: &str) -> OutboxEntry {
+		let monitor_match = MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+			monitor: Monitor {
+				name: "transfers".to_string(),
+				..Default::default()
+			},
+			transaction: StellarTransaction::from(StellarTransactionInfo {
+				transaction_hash: hash.to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock::default(),
+			matched_on: MatchConditions {
+				functions: vec![],
+				events: vec![],
+				transactions: vec![],
+			},
+			matched_on_args: Some(StellarMatchArguments {
+				functions: None,
+				events: Some(vec![StellarMatchParamsMap {
+					signature: "transfer(Address,Address,I128)".to_string(),
+					args: Some(vec![StellarMatchParamEntry {
+						name: "amount".to_string(),
+						value: amount.to_string(),
+						kind: "I128".to_string(),
+						indexed: false,
+					}]),
+				}]),
+			}),
+			alert: None,
+			context: None,
+		}));
+		OutboxEntry::new("stellar_mainnet", block_number, monitor_match, "slack")
+	}
+
+	#[test]
+	fn test_digest_slot() {
+		let entry = create_entry("tx1", 1, "100");
+		let per_block = DigestPolicy::default();
+		let (key, due_at) = digest_slot(&entry, &per_block, 1_000);
+		assert_eq!(due_at, 1_000 + BLOCK_DIGEST_DELAY_MS);
+		assert_eq!(
+			key,
+			digest_slot(&create_entry("tx2", 1, "100"), &per_block, 2_000).0
+		);
+		assert_ne!(
+			key,
+			digest_slot(&create_entry("tx2", 2, "100"), &per_block, 1_000).0
+		);
+
+		let windowed = DigestPolicy {
+			window_ms: Some(60_000),
+			..Default::default()
+		};
+		let (key, due_at) = digest_slot(&entry, &windowed, 61_000);
+		assert_eq!(due_at, 120_000);
+		assert_eq!(
+			key,
+			digest_slot(&create_entry("tx2", 5, "100"), &windowed, 119_999).0
+		);
+		assert_ne!(key, digest_slot(&entry, &windowed, 120_000).0);
+	}
+
+	#[test]
+	fn test_add_to_digest_and_variables() {
+		let first = create_entry("tx1", 1, "100");
+		let second = create_entry("tx2", 1, "250");
+		let third = create_entry("tx3", 1, "-5");
+
+		let digest = add_to_digest(None, &first, "digest", 42);
+		assert_eq!(digest.key, "digest");
+		assert_eq!(digest.next_attempt_at, 42);
+		let digest = add_to_digest(Some(digest), &second, "digest", 42);
+		// Aggregating an entry again does not duplicate its match
+		let digest = add_to_digest(Some(digest), &second, "digest", 42);
+		let items = digest.digest.clone().unwrap();
+		assert_eq!(items.len(), 2);
+
+		let variables = build_digest_variables(&items, 1);
+		assert_eq!(variables["match_count"], "2");
+		assert_eq!(variables["omitted_count"], "1");
+		assert_eq!(variables["matches_0_transaction_hash"], "tx1");
+		assert!(!variables.contains_key("matches_1_transaction_hash"));
+		assert_eq!(variables["total_event_0_amount"], "350");
+		assert!(!variables.contains_key("total_transaction_hash"));
+
+		// Variables with a value that is not an unsigned integer are not summed
+		let digest = add_to_digest(Some(digest), &third, "digest", 42);
+		let variables = build_digest_variables(digest.digest.as_ref().unwrap(), 20);
+		assert_eq!(variables["match_count"], "3");
+		assert_eq!(variables["omitted_count"], "0");
+		assert!(!variables.contains_key("total_event_0_amount"));
+	}
+}
@@ -2,10 +2,19 @@
 //!
 //! This module provides functionality to manage and execute triggers,
 //! which are configurable actions that can be initiated based on
-//! various conditions.
+//! various conditions. Matches can be queued in a durable outbox and
+//! delivered with retries, subject to the alert policies of monitors
+//! and triggers, either one by one or aggregated into digests.
 
+mod digest;
 mod error;
+mod outbox;
 mod service;
+mod throttle;
 
 pub use error::TriggerError;
-pub use service::{TriggerExecutionService, TriggerExecutionServiceTrait};
+pub use outbox::{
+	deliver_due_matches, DeliverySummary, DigestItem, FileMatchOutbox, MatchOutbox, OutboxEntry,
+};
+pub use service::{DeliveryOutcome, TriggerExecutionService, TriggerExecutionServiceTrait};
+pub use throttle::AlertThrottle;
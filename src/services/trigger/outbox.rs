@@ -0,0 +1,1002 @@
+//! Durable outbox for monitor matches.
+//!
+//! Matches are written to the outbox before the block watcher advances its checkpoint, and a
+//! delivery worker drains the outbox by executing the triggers, retrying failed deliveries
+//! according to the retry policy of each trigger. Together this gives at-least-once delivery
+//! across restarts. Deliveries that fail permanently or run out of attempts are moved to a
+//! dead-letter queue, from which they can be inspected and replayed.
+//!
+//! Each entry is a single (match, trigger) delivery identified by an idempotency key derived
+//! from the network, monitor, transaction, trigger and alert phase. Keys of delivered entries
+//! are kept for a while so a block processed again after a crash does not alert twice.
+//!
+//! Matches of triggers with a digest policy are folded into a digest entry instead of being
+//! delivered one by one, see the `digest` module.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+	models::{MonitorMatch, ScriptLanguage},
+	services::{
+		filter::build_match_variables,
+		trigger::{
+			digest::{add_to_digest, build_digest_variables, digest_slot},
+			AlertThrottle, DeliveryOutcome, TriggerExecutionServiceTrait,
+		},
+	},
+	utils::metrics::TRIGGER_DELIVERIES,
+};
+
+/// Maximum number of entries delivered per call to [`deliver_due_matches`]
+const DELIVERY_BATCH_SIZE: usize = 100;
+
+/// A pending delivery of a monitor match through one trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+	/// Idempotency key of the delivery
+	pub key: String,
+	/// Slug of the trigger to execute
+	pub trigger: String,
+	/// Network the match was found on
+	pub network_slug: String,
+	/// Block the match was found in
+	pub block_number: u64,
+	/// The match to deliver
+	pub monitor_match: MonitorMatch,
+	/// Number of failed delivery attempts
+	pub attempts: u32,
+	/// Creation time in milliseconds since the Unix epoch
+	pub created_at: i64,
+	/// Earliest time of the next delivery attempt in milliseconds since the Unix epoch
+	pub next_attempt_at: i64,
+	/// Error of the last failed attempt
+	pub last_error: Option<String>,
+	/// Time the entry was moved to the dead-letter queue in milliseconds since the Unix epoch
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub dead_lettered_at: Option<i64>,
+	/// Number of suppressed alerts if the entry is a summary of them
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub suppressed: Option<u64>,
+	/// Matches aggregated into the entry if it is a digest
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub digest: Option<Vec<DigestItem>>,
+}
+
+/// A match aggregated into a digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestItem {
+	/// Idempotency key of the entry the match was queued as
+	pub key: String,
+	/// Network the match was found on
+	pub network_slug: String,
+	/// The aggregated match
+	pub monitor_match: MonitorMatch,
+}
+
+impl OutboxEntry {
+	/// Creates an entry that is due immediately
+	///
+	/// # Arguments
+	/// * `network_slug` - Network the match was found on
+	/// * `block_number` - Block the match was found in
+	/// * `monitor_match` - The match to deliver
+	/// * `trigger` - Slug of the trigger to execute
+	pub fn new(
+		network_slug: &str,
+		block_number: u64,
+		monitor_match: MonitorMatch,
+		trigger: &str,
+	) -> Self {
+		let now = chrono::Utc::now().timestamp_millis();
+		Self {
+			key: Self::idempotency_key(network_slug, &monitor_match, trigger),
+			trigger: trigger.to_string(),
+			network_slug: network_slug.to_string(),
+			block_number,
+			monitor_match,
+			attempts: 0,
+			created_at: now,
+			next_attempt_at: now,
+			last_error: None,
+			dead_lettered_at: None,
+			suppressed: None,
+			digest: None,
+		}
+	}
+
+	/// Creates an entry summarizing alerts suppressed by an alert policy
+	///
+	/// The summary delivers the last suppressed match through `trigger`, with the number of
+	/// suppressed alerts available to templates as `suppressed_count`.
+	///
+	/// # Arguments
+	/// * `entry` - Entry of the last suppressed alert
+	/// * `trigger` - Slug of the trigger to execute
+	/// * `suppressed` - Number of suppressed alerts
+	pub fn suppressed_summary(entry: &OutboxEntry, trigger: &str, suppressed: u64) -> Self {
+		let mut summary = Self::new(
+			&entry.network_slug,
+			entry.block_number,
+			entry.monitor_match.clone(),
+			trigger,
+		);
+		let mut hasher = Sha256::new();
+		hasher.update(format!("{}|suppressed|{}", summary.key, summary.created_at).as_bytes());
+		summary.key = hex::encode(hasher.finalize());
+		summary.suppressed = Some(suppressed);
+		summary
+	}
+
+	/// Derives the idempotency key of a delivery
+	///
+	/// The alert phase is part of the key, so the pending and confirmed alerts of a two-phase
+	/// monitor are delivered separately.
+	pub fn idempotency_key(
+		network_slug: &str,
+		monitor_match: &MonitorMatch,
+		trigger: &str,
+	) -> String {
+		let phase = monitor_match
+			.alert()
+			.map(|alert| alert.phase.to_string())
+			.unwrap_or_default();
+		let mut hasher = Sha256::new();
+		hasher.update(
+			format!(
+				"{}|{}|{}|{}|{}",
+				network_slug,
+				monitor_match.monitor().name,
+				monitor_match.transaction_hash(),
+				trigger,
+				phase
+			)
+			.as_bytes(),
+		);
+		hex::encode(hasher.finalize())
+	}
+
+	/// Records a failed delivery attempt, scheduling the next attempt after `delay`
+	fn record_failure(&mut self, error: String, delay: Duration, now: i64) {
+		self.attempts += 1;
+		self.last_error = Some(error);
+		self.next_attempt_at = now + delay.as_millis() as i64;
+	}
+}
+
+/// Interface for match outbox implementations
+#[async_trait]
+pub trait MatchOutbox: Send + Sync {
+	/// Adds entries to the outbox
+	///
+	/// Entries whose key is already pending, dead-lettered or was recently delivered are
+	/// skipped.
+	///
+	/// # Arguments
+	/// * `entries` - Entries to add
+	///
+	/// # Returns
+	/// * `Result<usize, anyhow::Error>` - Number of entries added
+	async fn enqueue(&self, entries: Vec<OutboxEntry>) -> Result<usize, anyhow::Error>;
+
+	/// Retrieves the entries due for delivery, oldest first
+	///
+	/// # Arguments
+	/// * `now` - Current time in milliseconds since the Unix epoch
+	/// * `limit` - Maximum number of entries to return
+	///
+	/// # Returns
+	/// * `Result<Vec<OutboxEntry>, anyhow::Error>` - Due entries
+	async fn due(&self, now: i64, limit: usize) -> Result<Vec<OutboxEntry>, anyhow::Error>;
+
+	/// Saves a pending entry, after a failed delivery attempt or when a match is added to a
+	/// digest
+	///
+	/// # Arguments
+	/// * `entry` - Entry with its updated attempt count, schedule or matches
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn reschedule(&self, entry: &OutboxEntry) -> Result<(), anyhow::Error>;
+
+	/// Retrieves a pending entry
+	///
+	/// # Arguments
+	/// * `key` - Idempotency key of the entry
+	///
+	/// # Returns
+	/// * `Result<Option<OutboxEntry>, anyhow::Error>` - The entry, or None if no entry with the
+	///   key is pending
+	async fn pending(&self, key: &str) -> Result<Option<OutboxEntry>, anyhow::Error>;
+
+	/// Removes a delivered entry, remembering its key
+	///
+	/// # Arguments
+	/// * `key` - Idempotency key of the delivered entry
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn complete(&self, key: &str) -> Result<(), anyhow::Error>;
+
+	/// Forgets the keys of entries delivered longer ago than `retention`
+	///
+	/// # Arguments
+	/// * `retention` - How long delivered keys are remembered
+	///
+	/// # Returns
+	/// * `Result<usize, anyhow::Error>` - Number of keys forgotten
+	async fn prune_delivered(&self, retention: Duration) -> Result<usize, anyhow::Error>;
+
+	/// Moves an entry whose delivery failed for good to the dead-letter queue
+	///
+	/// # Arguments
+	/// * `entry` - Entry with its final attempt count and error
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn dead_letter(&self, entry: &OutboxEntry) -> Result<(), anyhow::Error>;
+
+	/// Retrieves the entries of the dead-letter queue, oldest first
+	///
+	/// # Returns
+	/// * `Result<Vec<OutboxEntry>, anyhow::Error>` - Dead-lettered entries
+	async fn dead_letters(&self) -> Result<Vec<OutboxEntry>, anyhow::Error>;
+
+	/// Moves a dead-lettered entry back to the outbox for immediate delivery
+	///
+	/// The attempt count is reset, so the entry gets the full retry policy again.
+	///
+	/// # Arguments
+	/// * `key` - Idempotency key of the dead-lettered entry
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - Whether a dead-lettered entry with the key existed
+	async fn replay_dead_letter(&self, key: &str) -> Result<bool, anyhow::Error>;
+}
+
+/// File-based implementation of the match outbox
+///
+/// Pending entries are stored as `pending/{key}.json`, dead-lettered entries as
+/// `dead/{key}.json` and delivered keys as empty `delivered/{key}` marker files. Entries are
+/// written to a temporary file, synced and renamed, so a crash never leaves a partially written
+/// entry.
+#[derive(Clone)]
+pub struct FileMatchOutbox {
+	/// Base path of the outbox
+	storage_path: PathBuf,
+}
+
+impl FileMatchOutbox {
+	/// Creates a new file-based outbox
+	///
+	/// The directories are created when the first entry is written.
+	pub fn new(storage_path: PathBuf) -> Self {
+		FileMatchOutbox { storage_path }
+	}
+
+	fn pending_path(&self, key: &str) -> PathBuf {
+		self.storage_path
+			.join("pending")
+			.join(format!("{}.json", key))
+	}
+
+	fn delivered_path(&self, key: &str) -> PathBuf {
+		self.storage_path.join("delivered").join(key)
+	}
+
+	fn dead_letter_path(&self, key: &str) -> PathBuf {
+		self.storage_path.join("dead").join(format!("{}.json", key))
+	}
+
+	async fn write_entry(&self, entry: &OutboxEntry) -> Result<(), anyhow::Error> {
+		self.write_entry_to(self.pending_path(&entry.key), entry)
+			.await
+	}
+
+	async fn write_entry_to(
+		&self,
+		path: PathBuf,
+		entry: &OutboxEntry,
+	) -> Result<(), anyhow::Error> {
+		if let Some(parent) = path.parent() {
+			tokio::fs::create_dir_all(parent)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to create outbox directory: {}", e))?;
+		}
+
+		let json = serde_json::to_vec(entry)
+			.map_err(|e| anyhow::anyhow!("Failed to serialize outbox entry: {}", e))?;
+		let tmp_path = path.with_extension("json.tmp");
+		let mut file = tokio::fs::File::create(&tmp_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to write outbox entry: {}", e))?;
+		file.write_all(&json)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to write outbox entry: {}", e))?;
+		file.sync_all()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to write outbox entry: {}", e))?;
+		tokio::fs::rename(tmp_path, path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to write outbox entry: {}", e))?;
+		Ok(())
+	}
+
+	/// Reads the entries of a directory of the outbox, oldest first
+	async fn read_entries(&self, directory: &str) -> Result<Vec<OutboxEntry>, anyhow::Error> {
+		let directory = self.storage_path.join(directory);
+		if !directory.exists() {
+			return Ok(Vec::new());
+		}
+
+		let mut dir = tokio::fs::read_dir(&directory)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read outbox: {}", e))?;
+		let mut entries = Vec::new();
+		while let Some(file) = dir
+			.next_entry()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read outbox: {}", e))?
+		{
+			let path = file.path();
+			if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+				continue;
+			}
+			let content = tokio::fs::read(&path)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to read outbox entry: {}", e))?;
+			match serde_json::from_slice::<OutboxEntry>(&content) {
+				Ok(entry) => entries.push(entry),
+				Err(e) => {
+					tracing::error!("Skipping unreadable outbox entry {}: {}", path.display(), e)
+				}
+			}
+		}
+
+		entries.sort_by(|a, b| {
+			(a.created_at, a.block_number, &a.key).cmp(&(b.created_at, b.block_number, &b.key))
+		});
+		Ok(entries)
+	}
+}
+
+impl Default for FileMatchOutbox {
+	/// Default implementation for FileMatchOutbox
+	///
+	/// Initializes the outbox with the default path "data/outbox"
+	fn default() -> Self {
+		FileMatchOutbox::new(PathBuf::from("data").join("outbox"))
+	}
+}
+
+#[async_trait]
+impl MatchOutbox for FileMatchOutbox {
+	async fn enqueue(&self, entries: Vec<OutboxEntry>) -> Result<usize, anyhow::Error> {
+		let mut added = 0;
+		for entry in entries {
+			if self.pending_path(&entry.key).exists()
+				|| self.delivered_path(&entry.key).exists()
+				|| self.dead_letter_path(&entry.key).exists()
+			{
+				continue;
+			}
+			self.write_entry(&entry).await?;
+			added += 1;
+		}
+		Ok(added)
+	}
+
+	async fn due(&self, now: i64, limit: usize) -> Result<Vec<OutboxEntry>, anyhow::Error> {
+		let mut entries = self.read_entries("pending").await?;
+		entries.retain(|entry| entry.next_attempt_at <= now);
+		entries.truncate(limit);
+		Ok(entries)
+	}
+
+	async fn reschedule(&self, entry: &OutboxEntry) -> Result<(), anyhow::Error> {
+		self.write_entry(entry).await
+	}
+
+	async fn pending(&self, key: &str) -> Result<Option<OutboxEntry>, anyhow::Error> {
+		let content = match tokio::fs::read(self.pending_path(key)).await {
+			Ok(content) => content,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(anyhow::anyhow!("Failed to read outbox entry: {}", e)),
+		};
+		serde_json::from_slice(&content)
+			.map(Some)
+			.map_err(|e| anyhow::anyhow!("Failed to parse outbox entry: {}", e))
+	}
+
+	async fn complete(&self, key: &str) -> Result<(), anyhow::Error> {
+		let delivered_path = self.delivered_path(key);
+		if let Some(parent) = delivered_path.parent() {
+			tokio::fs::create_dir_all(parent)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to create outbox directory: {}", e))?;
+		}
+		tokio::fs::write(delivered_path, b"")
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to record delivered entry: {}", e))?;
+
+		match tokio::fs::remove_file(self.pending_path(key)).await {
+			Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(anyhow::anyhow!(
+				"Failed to remove delivered outbox entry: {}",
+				e
+			)),
+			_ => Ok(()),
+		}
+	}
+
+	async fn prune_delivered(&self, retention: Duration) -> Result<usize, anyhow::Error> {
+		let delivered_dir = self.storage_path.join("delivered");
+		if !delivered_dir.exists() {
+			return Ok(0);
+		}
+
+		let mut dir = tokio::fs::read_dir(&delivered_dir)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read delivered outbox entries: {}", e))?;
+		let mut pruned = 0;
+		while let Some(file) = dir
+			.next_entry()
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read delivered outbox entries: {}", e))?
+		{
+			let expired = file
+				.metadata()
+				.await
+				.and_then(|metadata| metadata.modified())
+				.map(|modified| modified.elapsed().unwrap_or_default() > retention)
+				.unwrap_or(false);
+			if expired {
+				tokio::fs::remove_file(file.path()).await.map_err(|e| {
+					anyhow::anyhow!("Failed to prune delivered outbox entry: {}", e)
+				})?;
+				pruned += 1;
+			}
+		}
+		Ok(pruned)
+	}
+
+	async fn dead_letter(&self, entry: &OutboxEntry) -> Result<(), anyhow::Error> {
+		let mut entry = entry.clone();
+		entry.dead_lettered_at = Some(chrono::Utc::now().timestamp_millis());
+		self.write_entry_to(self.dead_letter_path(&entry.key), &entry)
+			.await?;
+
+		match tokio::fs::remove_file(self.pending_path(&entry.key)).await {
+			Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(anyhow::anyhow!(
+				"Failed to remove dead-lettered outbox entry: {}",
+				e
+			)),
+			_ => Ok(()),
+		}
+	}
+
+	async fn dead_letters(&self) -> Result<Vec<OutboxEntry>, anyhow::Error> {
+		self.read_entries("dead").await
+	}
+
+	async fn replay_dead_letter(&self, key: &str) -> Result<bool, anyhow::Error> {
+		let path = self.dead_letter_path(key);
+		let content = match tokio::fs::read(&path).await {
+			Ok(content) => content,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+			Err(e) => return Err(anyhow::anyhow!("Failed to read dead letter: {}", e)),
+		};
+		let mut entry = serde_json::from_slice::<OutboxEntry>(&content)
+			.map_err(|e| anyhow::anyhow!("Failed to parse dead letter: {}", e))?;
+
+		entry.attempts = 0;
+		entry.next_attempt_at = chrono::Utc::now().timestamp_millis();
+		entry.dead_lettered_at = None;
+		self.write_entry(&entry).await?;
+		tokio::fs::remove_file(path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to remove dead letter: {}", e))?;
+		Ok(true)
+	}
+}
+
+/// Result of a delivery run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeliverySummary {
+	/// Entries delivered and removed from the outbox
+	pub delivered: usize,
+	/// Entries whose delivery failed and that were rescheduled
+	pub failed: usize,
+	/// Entries whose delivery failed for good and that were moved to the dead-letter queue
+	pub dead_lettered: usize,
+	/// Entries suppressed by an alert policy
+	pub suppressed: usize,
+	/// Entries aggregated into a digest
+	pub digested: usize,
+}
+
+/// Delivers the due entries of an outbox
+///
+/// Entries are delivered oldest first by executing their trigger. The idempotency key is added
+/// to the template variables as `idempotency_key`, so receivers can drop duplicates. Failed
+/// entries stay in the outbox and are retried according to the retry policy of their trigger,
+/// until the error is permanent or no attempt is left and they are dead-lettered.
+///
+/// Before its first attempt, an entry goes through the alert policies of its monitor and
+/// trigger. Suppressed entries are completed without being sent, and the summaries of
+/// suppressed alerts are queued once due. Entries of a trigger with a digest policy are then
+/// added to their digest and completed, the digest being delivered once due.
+///
+/// # Arguments
+/// * `outbox` - Outbox to drain
+/// * `trigger_service` - Service executing the triggers
+/// * `trigger_scripts` - Scripts of the monitors, needed by script triggers
+/// * `throttle` - Throttle applying the alert policies
+///
+/// # Returns
+/// * `Result<DeliverySummary, anyhow::Error>` - Counts of delivered, failed, dead-lettered,
+///   suppressed and digested entries, or an error if the outbox cannot be read or updated
+pub async fn deliver_due_matches<O: MatchOutbox, T: TriggerExecutionServiceTrait + Sync>(
+	outbox: &O,
+	trigger_service: &T,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	throttle: &AlertThrottle,
+) -> Result<DeliverySummary, anyhow::Error> {
+	let summaries = throttle.take_summaries(chrono::Utc::now().timestamp_millis());
+	if !summaries.is_empty() {
+		outbox.enqueue(summaries).await?;
+	}
+
+	let entries = outbox
+		.due(chrono::Utc::now().timestamp_millis(), DELIVERY_BATCH_SIZE)
+		.await?;
+
+	let mut summary = DeliverySummary::default();
+	for mut entry in entries {
+		let mut variables = build_match_variables(&entry.monitor_match);
+		variables.insert("idempotency_key".to_string(), entry.key.clone());
+
+		if let Some(suppressed) = entry.suppressed {
+			variables.insert("suppressed_count".to_string(), suppressed.to_string());
+		} else if let Some(items) = &entry.digest {
+			let policy = trigger_service
+				.digest_policy(&entry.trigger)
+				.await
+				.unwrap_or_default();
+			variables.extend(build_digest_variables(items, policy.max_items));
+		} else if entry.attempts == 0 && entry.last_error.is_none() {
+			// Retries and replays were already let through
+			let now = chrono::Utc::now().timestamp_millis();
+			let trigger_policy = trigger_service.alert_policy(&entry.trigger).await;
+			if !throttle.allow(
+				&entry,
+				entry.monitor_match.monitor().alert_policy.as_ref(),
+				trigger_policy.as_ref(),
+				&variables,
+				now,
+			) {
+				TRIGGER_DELIVERIES
+					.with_label_values(&[&entry.trigger, "suppressed"])
+					.inc();
+				outbox.complete(&entry.key).await?;
+				summary.suppressed += 1;
+				continue;
+			}
+
+			if let Some(policy) = trigger_service.digest_policy(&entry.trigger).await {
+				let (key, due_at) = digest_slot(&entry, &policy, now);
+				let digest = add_to_digest(outbox.pending(&key).await?, &entry, &key, due_at);
+				outbox.reschedule(&digest).await?;
+				TRIGGER_DELIVERIES
+					.with_label_values(&[&entry.trigger, "digested"])
+					.inc();
+				outbox.complete(&entry.key).await?;
+				summary.digested += 1;
+				continue;
+			}
+		}
+
+		match trigger_service
+			.deliver(
+				&entry.trigger,
+				variables,
+				&entry.monitor_match,
+				trigger_scripts,
+				entry.attempts + 1,
+			)
+			.await
+		{
+			DeliveryOutcome::Delivered => {
+				TRIGGER_DELIVERIES
+					.with_label_values(&[&entry.trigger, "delivered"])
+					.inc();
+				outbox.complete(&entry.key).await?;
+				summary.delivered += 1;
+			}
+			DeliveryOutcome::Retry { error, delay } => {
+				TRIGGER_DELIVERIES
+					.with_label_values(&[&entry.trigger, "retried"])
+					.inc();
+				entry.record_failure(error, delay, chrono::Utc::now().timestamp_millis());
+				tracing::warn!(
+					"Delivery of {} through trigger {} failed (attempt {}), retrying in {}s",
+					entry.key,
+					entry.trigger,
+					entry.attempts,
+					delay.as_secs()
+				);
+				outbox.reschedule(&entry).await?;
+				summary.failed += 1;
+			}
+			DeliveryOutcome::Failed { error } => {
+				TRIGGER_DELIVERIES
+					.with_label_values(&[&entry.trigger, "failed"])
+					.inc();
+				entry.record_failure(error, Duration::ZERO, chrono::Utc::now().timestamp_millis());
+				tracing::error!(
+					"Delivery of {} through trigger {} failed after {} attempt(s), moved to the \
+					 dead-letter queue: {}",
+					entry.key,
+					entry.trigger,
+					entry.attempts,
+					entry.last_error.as_deref().unwrap_or_default()
+				);
+				outbox.dead_letter(&entry).await?;
+				summary.dead_lettered += 1;
+			}
+		}
+	}
+
+	throttle.persist().await?;
+	Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{
+			AlertContext, AlertPhase, AlertPolicy, DigestPolicy, MatchConditions, Monitor,
+			StellarBlock, StellarMonitorMatch, StellarTransaction, StellarTransactionInfo,
+		},
+		services::{report::MonitorReport, trigger::TriggerError},
+	};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use tempfile::TempDir;
+
+	/// Trigger service failing the first `failures` executions
+	struct FlakyTriggerService {
+		failures: usize,
+		calls: AtomicUsize,
+		digest: Option<DigestPolicy>,
+	}
+
+	#[async_trait]
+	impl TriggerExecutionServiceTrait for FlakyTriggerService {
+		async fn execute(
+			&self,
+			_trigger_slugs: &[String],
+			variables: HashMap<String, String>,
+			_monitor_match: &MonitorMatch,
+			_trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+		) -> Result<(), TriggerError> {
+			assert!(variables.contains_key("idempotency_key"));
+			if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+				return Err(TriggerError::execution_error_without_log(
+					"webhook unavailable",
+					None,
+					None,
+				));
+			}
+			Ok(())
+		}
+
+		async fn load_scripts(
+			&self,
+			_monitors: &[Monitor],
+		) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+			Ok(HashMap::new())
+		}
+
+		async fn digest_policy(&self, _trigger_slug: &str) -> Option<DigestPolicy> {
+			self.digest.clone()
+		}
+
+		async fn send_report(
+			&self,
+			_trigger_slugs: &[String],
+			_report: &MonitorReport,
+		) -> Result<(), TriggerError> {
+			Ok(())
+		}
+	}
+
+	fn create_monitor_match(hash: &str) -> MonitorMatch {
+		MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+			monitor: Monitor {
+				name: "test".to_string(),
+				..Default::default()
+			},
+			transaction: StellarTransaction::from(StellarTransactionInfo {
+				transaction_hash: hash.to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock::default(),
+			matched_on: MatchConditions {
+				functions: vec![],
+				events: vec![],
+				transactions: vec![],
+			},
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}))
+	}
+
+	#[test]
+	fn test_idempotency_key() {
+		let monitor_match = create_monitor_match("tx1");
+		let key = OutboxEntry::idempotency_key("stellar_mainnet", &monitor_match, "webhook");
+
+		assert_eq!(
+			key,
+			OutboxEntry::idempotency_key("stellar_mainnet", &monitor_match, "webhook")
+		);
+		assert_ne!(
+			key,
+			OutboxEntry::idempotency_key("stellar_mainnet", &monitor_match, "slack")
+		);
+		assert_ne!(
+			key,
+			OutboxEntry::idempotency_key(
+				"stellar_mainnet",
+				&create_monitor_match("tx2"),
+				"webhook"
+			)
+		);
+
+		let mut pending_match = monitor_match.clone();
+		pending_match.set_alert(Some(AlertContext::new(
+			AlertPhase::Pending,
+			"stellar_mainnet",
+			&monitor_match,
+		)));
+		assert_ne!(
+			key,
+			OutboxEntry::idempotency_key("stellar_mainnet", &pending_match, "webhook")
+		);
+	}
+
+	#[tokio::test]
+	async fn test_enqueue_skips_known_keys() {
+		let temp_dir = TempDir::new().unwrap();
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let entry = OutboxEntry::new("stellar_mainnet", 1, create_monitor_match("tx1"), "webhook");
+
+		assert_eq!(outbox.enqueue(vec![entry.clone()]).await.unwrap(), 1);
+		assert_eq!(outbox.enqueue(vec![entry.clone()]).await.unwrap(), 0);
+
+		outbox.complete(&entry.key).await.unwrap();
+		assert!(outbox.due(i64::MAX, 10).await.unwrap().is_empty());
+		// Processing the block again does not deliver the match twice
+		assert_eq!(outbox.enqueue(vec![entry.clone()]).await.unwrap(), 0);
+
+		assert_eq!(
+			outbox
+				.prune_delivered(Duration::from_secs(60))
+				.await
+				.unwrap(),
+			0
+		);
+		assert_eq!(outbox.prune_delivered(Duration::ZERO).await.unwrap(), 1);
+		assert_eq!(outbox.enqueue(vec![entry]).await.unwrap(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_deliver_due_matches_retries_failures() {
+		let temp_dir = TempDir::new().unwrap();
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let trigger_service = FlakyTriggerService {
+			failures: 1,
+			calls: AtomicUsize::new(0),
+			digest: None,
+		};
+		outbox
+			.enqueue(vec![
+				OutboxEntry::new("stellar_mainnet", 1, create_monitor_match("tx1"), "webhook"),
+				OutboxEntry::new("stellar_mainnet", 2, create_monitor_match("tx2"), "webhook"),
+			])
+			.await
+			.unwrap();
+
+		let summary = deliver_due_matches(
+			&outbox,
+			&trigger_service,
+			&HashMap::new(),
+			&AlertThrottle::new(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(
+			summary,
+			DeliverySummary {
+				delivered: 1,
+				failed: 1,
+				dead_lettered: 0,
+				suppressed: 0,
+				digested: 0,
+			}
+		);
+
+		// The failed entry waits for its retry
+		assert!(outbox
+			.due(chrono::Utc::now().timestamp_millis(), 10)
+			.await
+			.unwrap()
+			.is_empty());
+		let retried = outbox.due(i64::MAX, 10).await.unwrap();
+		assert_eq!(retried.len(), 1);
+		assert_eq!(retried[0].attempts, 1);
+		assert_eq!(retried[0].block_number, 1);
+		assert!(retried[0]
+			.last_error
+			.as_ref()
+			.unwrap()
+			.contains("webhook unavailable"));
+
+		// Entries survive a restart of the service
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let mut entry = retried[0].clone();
+		entry.next_attempt_at = 0;
+		outbox.reschedule(&entry).await.unwrap();
+		let summary = deliver_due_matches(
+			&outbox,
+			&trigger_service,
+			&HashMap::new(),
+			&AlertThrottle::new(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(summary.delivered, 1);
+		assert!(outbox.due(i64::MAX, 10).await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_dead_letter_and_replay() {
+		let temp_dir = TempDir::new().unwrap();
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let trigger_service = FlakyTriggerService {
+			failures: usize::MAX,
+			calls: AtomicUsize::new(0),
+			digest: None,
+		};
+		let mut entry =
+			OutboxEntry::new("stellar_mainnet", 1, create_monitor_match("tx1"), "webhook");
+		// The default retry policy allows five attempts
+		entry.attempts = 4;
+		outbox.enqueue(vec![entry.clone()]).await.unwrap();
+
+		let summary = deliver_due_matches(
+			&outbox,
+			&trigger_service,
+			&HashMap::new(),
+			&AlertThrottle::new(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(summary.dead_lettered, 1);
+		assert!(outbox.due(i64::MAX, 10).await.unwrap().is_empty());
+
+		let dead_letters = outbox.dead_letters().await.unwrap();
+		assert_eq!(dead_letters.len(), 1);
+		assert_eq!(dead_letters[0].attempts, 5);
+		assert!(dead_letters[0].dead_lettered_at.is_some());
+		// A dead-lettered match is not queued again when its block is processed again
+		assert_eq!(outbox.enqueue(vec![entry.clone()]).await.unwrap(), 0);
+
+		assert!(!outbox.replay_dead_letter("unknown").await.unwrap());
+		assert!(outbox.replay_dead_letter(&entry.key).await.unwrap());
+		assert!(outbox.dead_letters().await.unwrap().is_empty());
+		let replayed = outbox
+			.due(chrono::Utc::now().timestamp_millis(), 10)
+			.await
+			.unwrap();
+		assert_eq!(replayed.len(), 1);
+		assert_eq!(replayed[0].attempts, 0);
+		assert_eq!(replayed[0].dead_lettered_at, None);
+	}
+
+	#[tokio::test]
+	async fn test_deliver_due_matches_applies_alert_policies() {
+		let temp_dir = TempDir::new().unwrap();
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let trigger_service = FlakyTriggerService {
+			failures: 0,
+			calls: AtomicUsize::new(0),
+			digest: None,
+		};
+		let throttle = AlertThrottle::new();
+		let create_entry = |hash: &str| {
+			let mut monitor_match = create_monitor_match(hash);
+			if let MonitorMatch::Stellar(stellar_match) = &mut monitor_match {
+				stellar_match.monitor.triggers = vec!["webhook".to_string()];
+				stellar_match.monitor.alert_policy = Some(AlertPolicy {
+					max_alerts: Some(1),
+					window_ms: 50,
+					..Default::default()
+				});
+			}
+			OutboxEntry::new("stellar_mainnet", 1, monitor_match, "webhook")
+		};
+		outbox
+			.enqueue(vec![
+				create_entry("tx1"),
+				create_entry("tx2"),
+				create_entry("tx3"),
+			])
+			.await
+			.unwrap();
+
+		let summary = deliver_due_matches(&outbox, &trigger_service, &HashMap::new(), &throttle)
+			.await
+			.unwrap();
+		assert_eq!(summary.delivered, 1);
+		assert_eq!(summary.suppressed, 2);
+		assert!(outbox.due(i64::MAX, 10).await.unwrap().is_empty());
+
+		// Once the window ends, the suppressed alerts are summarized
+		tokio::time::sleep(Duration::from_millis(60)).await;
+		let summary = deliver_due_matches(&outbox, &trigger_service, &HashMap::new(), &throttle)
+			.await
+			.unwrap();
+		assert_eq!(summary.delivered, 1);
+		assert_eq!(summary.suppressed, 0);
+		assert_eq!(trigger_service.calls.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn test_deliver_due_matches_aggregates_digests() {
+		let temp_dir = TempDir::new().unwrap();
+		let outbox = FileMatchOutbox::new(temp_dir.path().to_path_buf());
+		let trigger_service = FlakyTriggerService {
+			failures: 0,
+			calls: AtomicUsize::new(0),
+			digest: Some(DigestPolicy {
+				window_ms: Some(500),
+				..Default::default()
+			}),
+		};
+		let throttle = AlertThrottle::new();
+		// Both matches are aggregated at the start of the same window
+		let now = chrono::Utc::now().timestamp_millis();
+		tokio::time::sleep(Duration::from_millis(500 - now.rem_euclid(500) as u64)).await;
+		outbox
+			.enqueue(vec![
+				OutboxEntry::new("stellar_mainnet", 1, create_monitor_match("tx1"), "webhook"),
+				OutboxEntry::new("stellar_mainnet", 2, create_monitor_match("tx2"), "webhook"),
+			])
+			.await
+			.unwrap();
+
+		let summary = deliver_due_matches(&outbox, &trigger_service, &HashMap::new(), &throttle)
+			.await
+			.unwrap();
+		assert_eq!(summary.digested, 2);
+		assert_eq!(summary.delivered, 0);
+
+		let digests = outbox.due(i64::MAX, 10).await.unwrap();
+		assert_eq!(digests.len(), 1);
+		assert_eq!(digests[0].digest.as_ref().unwrap().len(), 2);
+
+		// The digest is delivered as a single notification once its window ends
+		tokio::time::sleep(Duration::from_millis(510)).await;
+		let summary = deliver_due_matches(&outbox, &trigger_service, &HashMap::new(), &throttle)
+			.await
+			.unwrap();
+		assert_eq!(summary.delivered, 1);
+		assert_eq!(trigger_service.calls.load(Ordering::SeqCst), 1);
+		assert!(outbox.due(i64::MAX, 10).await.unwrap().is_empty());
+	}
+}
@@ -3,17 +3,63 @@
 //! Provides functionality to execute triggers with variable substitution
 //! and notification delivery. Manages trigger lookup and execution flow.
 
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, path::Path, time::Duration};
 
 use anyhow::Context;
 use async_trait::async_trait;
+use tokio::sync::RwLock;
 
 use crate::{
-	models::{Monitor, MonitorMatch, ScriptLanguage, TriggerTypeConfig},
+	models::{
+		AlertPolicy, DigestPolicy, Monitor, MonitorMatch, RetryPolicy, ScriptLanguage,
+		TriggerTypeConfig,
+	},
 	repositories::{TriggerRepositoryTrait, TriggerService},
-	services::{notification::NotificationService, trigger::error::TriggerError},
+	services::{
+		notification::{NotificationError, NotificationService},
+		report::MonitorReport,
+		trigger::error::TriggerError,
+	},
 };
 
+/// Outcome of a single delivery attempt through a trigger
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeliveryOutcome {
+	/// The notification was sent
+	Delivered,
+	/// The delivery failed and should be attempted again after `delay`
+	Retry { error: String, delay: Duration },
+	/// The delivery failed for good, because the error is permanent or no attempt is left
+	Failed { error: String },
+}
+
+impl DeliveryOutcome {
+	/// Classifies a failed attempt according to a retry policy
+	///
+	/// # Arguments
+	/// * `policy` - Retry policy of the trigger
+	/// * `attempt` - Number of the failed attempt, starting at 1
+	/// * `error` - Error of the attempt
+	/// * `retryable` - Whether the error is worth retrying
+	/// * `retry_after` - Delay requested by the endpoint, used if longer than the backoff
+	pub fn from_failure(
+		policy: &RetryPolicy,
+		attempt: u32,
+		error: String,
+		retryable: bool,
+		retry_after: Option<Duration>,
+	) -> Self {
+		if !retryable || attempt >= policy.max_attempts {
+			return Self::Failed { error };
+		}
+		let delay = policy.backoff(attempt);
+		Self::Retry {
+			error,
+			delay: retry_after.map_or(delay, |retry_after| retry_after.max(delay)),
+		}
+	}
+}
+
 /// Trait for executing triggers
 ///
 /// This trait must be implemented by all trigger execution services to provide
@@ -31,6 +77,72 @@ pub trait TriggerExecutionServiceTrait {
 		&self,
 		monitors: &[Monitor],
 	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError>;
+
+	/// Sends a summary report through triggers
+	///
+	/// # Arguments
+	/// * `trigger_slugs` - Identifiers of the triggers to send the report through
+	/// * `report` - Report to send
+	///
+	/// # Returns
+	/// * `Result<(), TriggerError>` - Success, or an error if any trigger failed
+	async fn send_report(
+		&self,
+		trigger_slugs: &[String],
+		report: &MonitorReport,
+	) -> Result<(), TriggerError>;
+
+	/// Returns the alert policy of a trigger, if it has one
+	async fn alert_policy(&self, _trigger_slug: &str) -> Option<AlertPolicy> {
+		None
+	}
+
+	/// Returns the digest policy of a trigger, if it has one
+	async fn digest_policy(&self, _trigger_slug: &str) -> Option<DigestPolicy> {
+		None
+	}
+
+	/// Attempts a single delivery through one trigger
+	///
+	/// Failures are classified with the default retry policy unless the implementation knows
+	/// the policy of the trigger.
+	///
+	/// # Arguments
+	/// * `trigger_slug` - Identifier of the trigger to execute
+	/// * `variables` - Variables to substitute in the trigger template
+	/// * `monitor_match` - The match to deliver
+	/// * `trigger_scripts` - Scripts of the monitors, needed by script triggers
+	/// * `attempt` - Number of this attempt, starting at 1
+	///
+	/// # Returns
+	/// * `DeliveryOutcome` - Whether the delivery succeeded, should be retried or failed
+	async fn deliver(
+		&self,
+		trigger_slug: &str,
+		variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+		attempt: u32,
+	) -> DeliveryOutcome {
+		match self
+			.execute(
+				&[trigger_slug.to_string()],
+				variables,
+				monitor_match,
+				trigger_scripts,
+			)
+			.await
+		{
+			Ok(()) => DeliveryOutcome::Delivered,
+			Err(e) => DeliveryOutcome::from_failure(
+				&RetryPolicy::default(),
+				attempt,
+				e.to_string(),
+				true,
+				None,
+			),
+		}
+	}
 }
 
 /// Service for executing triggers with notifications
@@ -39,7 +151,7 @@ pub trait TriggerExecutionServiceTrait {
 /// delivery across different notification channels
 pub struct TriggerExecutionService<T: TriggerRepositoryTrait> {
 	/// Service for trigger management and lookup
-	trigger_service: TriggerService<T>,
+	trigger_service: RwLock<TriggerService<T>>,
 	/// Service for sending notifications
 	notification_service: NotificationService,
 }
@@ -58,10 +170,21 @@ impl<T: TriggerRepositoryTrait> TriggerExecutionService<T> {
 		notification_service: NotificationService,
 	) -> Self {
 		Self {
-			trigger_service,
+			trigger_service: RwLock::new(trigger_service),
 			notification_service,
 		}
 	}
+
+	/// Replaces the triggers used for lookup
+	///
+	/// Used when the configuration is reloaded while the service is running. Executions that
+	/// already looked up their triggers are not affected.
+	///
+	/// # Arguments
+	/// * `trigger_service` - Service holding the new trigger configurations
+	pub async fn replace_triggers(&self, trigger_service: TriggerService<T>) {
+		*self.trigger_service.write().await = trigger_service;
+	}
 }
 
 #[async_trait]
@@ -92,6 +215,8 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 		let futures = trigger_slugs.iter().map(|trigger_slug| async {
 			let trigger = self
 				.trigger_service
+				.read()
+				.await
 				.get(trigger_slug)
 				.ok_or_else(|| TriggerError::not_found(trigger_slug.to_string(), None, None))?;
 
@@ -131,6 +256,101 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 			))
 		}
 	}
+	/// Attempts a single delivery through one trigger
+	///
+	/// Failures are classified with the trigger's retry policy. HTTP error statuses are retried
+	/// if the policy lists them, honouring `Retry-After`, configuration errors are never retried
+	/// and any other error, such as a connection failure, is retried.
+	async fn deliver(
+		&self,
+		trigger_slug: &str,
+		variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+		attempt: u32,
+	) -> DeliveryOutcome {
+		let Some(trigger) = self.trigger_service.read().await.get(trigger_slug) else {
+			return DeliveryOutcome::Failed {
+				error: format!("Trigger {} not found", trigger_slug),
+			};
+		};
+		let policy = trigger.retry.clone().unwrap_or_default();
+
+		match self
+			.notification_service
+			.execute(&trigger, variables, monitor_match, trigger_scripts)
+			.await
+		{
+			Ok(()) => DeliveryOutcome::Delivered,
+			Err(e) => {
+				let (retryable, retry_after) = match e.http_status() {
+					Some(status) => (
+						policy.is_retryable_status(status.status.as_u16()),
+						status.retry_after,
+					),
+					None => (!matches!(e, NotificationError::ConfigError(_)), None),
+				};
+				let error = match e.http_status() {
+					Some(status) => status.to_string(),
+					None => e.to_string(),
+				};
+				DeliveryOutcome::from_failure(&policy, attempt, error, retryable, retry_after)
+			}
+		}
+	}
+
+	async fn alert_policy(&self, trigger_slug: &str) -> Option<AlertPolicy> {
+		self.trigger_service
+			.read()
+			.await
+			.get(trigger_slug)
+			.and_then(|trigger| trigger.alert_policy)
+	}
+
+	async fn digest_policy(&self, trigger_slug: &str) -> Option<DigestPolicy> {
+		self.trigger_service
+			.read()
+			.await
+			.get(trigger_slug)
+			.and_then(|trigger| trigger.digest)
+	}
+
+	async fn send_report(
+		&self,
+		trigger_slugs: &[String],
+		report: &MonitorReport,
+	) -> Result<(), TriggerError> {
+		let mut errors = Vec::new();
+		for trigger_slug in trigger_slugs {
+			let Some(trigger) = self.trigger_service.read().await.get(trigger_slug) else {
+				errors.push(format!("Trigger {} not found", trigger_slug));
+				continue;
+			};
+			if let Err(e) = self
+				.notification_service
+				.send_report(&trigger, report)
+				.await
+			{
+				errors.push(e.to_string());
+			}
+		}
+
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(TriggerError::execution_error(
+				format!(
+					"Failed to send report of {} ({} failure(s)): {}",
+					report.monitor_name,
+					errors.len(),
+					errors.join(", ")
+				),
+				None,
+				None,
+			))
+		}
+	}
+
 	/// Loads trigger condition scripts for monitors
 	///
 	/// # Arguments
@@ -173,8 +393,12 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 
 			// For each trigger, we'll load the script
 			for trigger in &monitor.triggers {
-				let trigger_config =
-					self.trigger_service.get(trigger.as_str()).ok_or_else(|| {
+				let trigger_config = self
+					.trigger_service
+					.read()
+					.await
+					.get(trigger.as_str())
+					.ok_or_else(|| {
 						TriggerError::configuration_error(
 							format!("Failed to get trigger: {}", trigger),
 							None,
@@ -215,3 +439,63 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 		Ok(scripts)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_delivery_outcome_from_failure() {
+		let policy = RetryPolicy {
+			max_attempts: 3,
+			..Default::default()
+		};
+
+		assert_eq!(
+			DeliveryOutcome::from_failure(&policy, 1, "error".to_string(), true, None),
+			DeliveryOutcome::Retry {
+				error: "error".to_string(),
+				delay: Duration::from_secs(1),
+			}
+		);
+		// Retry-After only extends the backoff
+		assert_eq!(
+			DeliveryOutcome::from_failure(
+				&policy,
+				2,
+				"error".to_string(),
+				true,
+				Some(Duration::from_secs(30))
+			),
+			DeliveryOutcome::Retry {
+				error: "error".to_string(),
+				delay: Duration::from_secs(30),
+			}
+		);
+		assert_eq!(
+			DeliveryOutcome::from_failure(
+				&policy,
+				2,
+				"error".to_string(),
+				true,
+				Some(Duration::from_millis(10))
+			),
+			DeliveryOutcome::Retry {
+				error: "error".to_string(),
+				delay: Duration::from_secs(2),
+			}
+		);
+		assert_eq!(
+			DeliveryOutcome::from_failure(&policy, 3, "error".to_string(), true, None),
+			DeliveryOutcome::Failed {
+				error: "error".to_string()
+			}
+		);
+		assert_eq!(
+			DeliveryOutcome::from_failure(&policy, 1, "error".to_string(), false, None),
+			DeliveryOutcome::Failed {
+				error: "error".to_string()
+			}
+		);
+	}
+}
@@ -0,0 +1,594 @@
+//! Deduplication, cooldowns and rate limits of alerts.
+//!
+//! Monitors and triggers can set an [`AlertPolicy`]. Alerts are identified by a key rendered from
+//! the policy's `dedup_key` template, and an alert is suppressed while its key is in cooldown or
+//! once the rate limit of its window is reached. Monitor policies apply across all triggers of
+//! the monitor, trigger policies to the alerts of a single trigger.
+//!
+//! Suppressed alerts are counted, and once the cooldown or window that suppressed them ends, a
+//! summary entry carrying the count is returned so it can be delivered in their place. The state
+//! is kept in memory and can be persisted to a file so it survives restarts.
+
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{HashMap, HashSet},
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex,
+	},
+};
+
+use crate::{models::AlertPolicy, services::trigger::OutboxEntry, utils::render_template};
+
+/// Throttling state of a monitor or trigger
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct ScopeState {
+	/// Cooldowns of the alerted keys
+	keys: HashMap<String, Cooldown>,
+	/// Current rate limit window
+	window: Option<Window>,
+	/// Matches suppressed since the last summary
+	suppressed: HashSet<String>,
+	/// Entry of the last suppressed alert, delivered as the summary
+	last_suppressed: Option<OutboxEntry>,
+	/// Time the summary is due in milliseconds since the Unix epoch
+	summary_due_at: i64,
+}
+
+impl ScopeState {
+	fn suppress(&mut self, entry: &OutboxEntry, match_id: String, until: i64) {
+		self.suppressed.insert(match_id);
+		self.last_suppressed = Some(entry.clone());
+		self.summary_due_at = self.summary_due_at.max(until);
+	}
+
+	fn is_empty(&self) -> bool {
+		self.keys.is_empty() && self.window.is_none() && self.suppressed.is_empty()
+	}
+}
+
+/// Cooldown of an alerted key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Cooldown {
+	/// Match that started the cooldown, its alerts through other triggers are not suppressed
+	match_id: String,
+	/// End of the cooldown in milliseconds since the Unix epoch
+	expires_at: i64,
+}
+
+/// Rate limit window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Window {
+	/// End of the window in milliseconds since the Unix epoch
+	ends_at: i64,
+	/// Matches alerted in the window
+	matches: Vec<String>,
+}
+
+/// Applies alert policies to the deliveries of the match outbox
+#[derive(Debug, Default)]
+pub struct AlertThrottle {
+	/// State of each monitor and trigger with a policy, keyed by scope
+	scopes: Mutex<HashMap<String, ScopeState>>,
+	/// File the state is persisted to
+	storage_path: Option<PathBuf>,
+	/// Whether the state changed since it was last persisted
+	changed: AtomicBool,
+}
+
+impl AlertThrottle {
+	/// Creates a throttle keeping its state in memory only
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a throttle persisting its state to a file
+	///
+	/// The state saved by a previous run is loaded if the file exists.
+	///
+	/// # Arguments
+	/// * `storage_path` - File the state is persisted to
+	///
+	/// # Errors
+	/// Returns an error if the file exists but cannot be read
+	pub fn with_persistence(storage_path: PathBuf) -> Result<Self, anyhow::Error> {
+		let scopes = if storage_path.exists() {
+			let content = std::fs::read(&storage_path)
+				.map_err(|e| anyhow::anyhow!("Failed to read alert state: {}", e))?;
+			serde_json::from_slice(&content)
+				.map_err(|e| anyhow::anyhow!("Failed to parse alert state: {}", e))?
+		} else {
+			HashMap::new()
+		};
+
+		Ok(Self {
+			scopes: Mutex::new(scopes),
+			storage_path: Some(storage_path),
+			changed: AtomicBool::new(false),
+		})
+	}
+
+	/// Decides whether an alert is sent, recording it against the policies
+	///
+	/// The monitor policy is checked before the trigger policy. The alert is only recorded once
+	/// both policies allow it, so an alert suppressed by one policy does not count against the
+	/// other.
+	///
+	/// # Arguments
+	/// * `entry` - Outbox entry of the alert
+	/// * `monitor_policy` - Policy of the entry's monitor
+	/// * `trigger_policy` - Policy of the entry's trigger
+	/// * `variables` - Template variables of the match, used to render the dedup keys
+	/// * `now` - Current time in milliseconds since the Unix epoch
+	///
+	/// # Returns
+	/// * `bool` - Whether the alert is sent
+	pub fn allow(
+		&self,
+		entry: &OutboxEntry,
+		monitor_policy: Option<&AlertPolicy>,
+		trigger_policy: Option<&AlertPolicy>,
+		variables: &HashMap<String, String>,
+		now: i64,
+	) -> bool {
+		let mut scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+		let monitor_scope = monitor_policy.map(|policy| {
+			(
+				format!("monitor:{}", entry.monitor_match.monitor().name),
+				policy,
+			)
+		});
+		let trigger_scope =
+			trigger_policy.map(|policy| (format!("trigger:{}", entry.trigger), policy));
+		if monitor_scope.is_some() || trigger_scope.is_some() {
+			self.changed.store(true, Ordering::Relaxed);
+		}
+
+		let match_id = match_id(entry);
+		let checks: Vec<_> = monitor_scope
+			.into_iter()
+			.chain(trigger_scope)
+			.map(|(scope, policy)| {
+				let key = render_key(&policy.dedup_key, variables);
+				(scope, policy, key)
+			})
+			.collect();
+
+		for (scope, policy, key) in &checks {
+			let suppressed_until = scopes
+				.get(scope)
+				.and_then(|state| check(state, policy, &match_id, key, now));
+			if let Some(until) = suppressed_until {
+				scopes
+					.entry(scope.clone())
+					.or_default()
+					.suppress(entry, match_id, until);
+				return false;
+			}
+		}
+		for (scope, policy, key) in checks {
+			record(
+				scopes.entry(scope).or_default(),
+				policy,
+				match_id.clone(),
+				key,
+				now,
+			);
+		}
+		true
+	}
+
+	/// Returns the summaries that are due and forgets expired state
+	///
+	/// A summary is a new outbox entry for the last suppressed alert carrying the number of
+	/// suppressed alerts. Summaries of a monitor policy are created for every trigger of the
+	/// monitor.
+	///
+	/// # Arguments
+	/// * `now` - Current time in milliseconds since the Unix epoch
+	///
+	/// # Returns
+	/// * `Vec<OutboxEntry>` - Summary entries to deliver
+	pub fn take_summaries(&self, now: i64) -> Vec<OutboxEntry> {
+		let mut scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+		let mut summaries = Vec::new();
+
+		let scope_count = scopes.len();
+		for (scope, state) in scopes.iter_mut() {
+			if !state.suppressed.is_empty() && state.summary_due_at <= now {
+				let suppressed = state.suppressed.len() as u64;
+				state.suppressed.clear();
+				if let Some(entry) = state.last_suppressed.take() {
+					if scope.starts_with("monitor:") {
+						summaries.extend(entry.monitor_match.monitor().triggers.iter().map(
+							|trigger| OutboxEntry::suppressed_summary(&entry, trigger, suppressed),
+						));
+					} else {
+						summaries.push(OutboxEntry::suppressed_summary(
+							&entry,
+							&entry.trigger,
+							suppressed,
+						));
+					}
+				}
+			}
+
+			state.keys.retain(|_, cooldown| cooldown.expires_at > now);
+			if state
+				.window
+				.as_ref()
+				.is_some_and(|window| window.ends_at <= now)
+			{
+				state.window = None;
+			}
+		}
+		scopes.retain(|_, state| !state.is_empty());
+		if !summaries.is_empty() || scopes.len() != scope_count {
+			self.changed.store(true, Ordering::Relaxed);
+		}
+
+		summaries
+	}
+
+	/// Saves the state if persistence is enabled and the state changed
+	///
+	/// # Errors
+	/// Returns an error if the state cannot be written
+	pub async fn persist(&self) -> Result<(), anyhow::Error> {
+		let Some(storage_path) = &self.storage_path else {
+			return Ok(());
+		};
+		if !self.changed.swap(false, Ordering::Relaxed) {
+			return Ok(());
+		}
+
+		let json = {
+			let scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+			serde_json::to_vec(&*scopes)
+				.map_err(|e| anyhow::anyhow!("Failed to serialize alert state: {}", e))?
+		};
+		if let Some(parent) = storage_path.parent() {
+			tokio::fs::create_dir_all(parent)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to create alert state directory: {}", e))?;
+		}
+		let tmp_path = storage_path.with_extension("json.tmp");
+		tokio::fs::write(&tmp_path, json)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to write alert state: {}", e))?;
+		tokio::fs::rename(tmp_path, storage_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to write alert state: {}", e))?;
+		Ok(())
+	}
+}
+
+/// Checks an alert against a policy without recording it
+///
+/// # Returns
+/// * `Option<i64>` - End of the cooldown or window suppressing the alert, if any
+fn check(
+	state: &ScopeState,
+	policy: &AlertPolicy,
+	match_id: &str,
+	key: &str,
+	now: i64,
+) -> Option<i64> {
+	// Alerts of the match that started the cooldown are still sent through its other triggers
+	if let Some(cooldown) = state.keys.get(key) {
+		if cooldown.expires_at > now && cooldown.match_id != match_id {
+			return Some(cooldown.expires_at);
+		}
+	}
+
+	if let (Some(max_alerts), Some(window)) = (policy.max_alerts, &state.window) {
+		if window.ends_at > now
+			&& !window.matches.iter().any(|id| id == match_id)
+			&& window.matches.len() >= max_alerts as usize
+		{
+			return Some(window.ends_at);
+		}
+	}
+	None
+}
+
+/// Records a sent alert against a policy
+fn record(state: &mut ScopeState, policy: &AlertPolicy, match_id: String, key: String, now: i64) {
+	if policy.max_alerts.is_some() {
+		let window = match &mut state.window {
+			Some(window) if window.ends_at > now => window,
+			window => window.insert(Window {
+				ends_at: now + policy.window_ms as i64,
+				matches: Vec::new(),
+			}),
+		};
+		if !window.matches.contains(&match_id) {
+			window.matches.push(match_id.clone());
+		}
+	}
+
+	if let Some(cooldown_ms) = policy.cooldown_ms {
+		state.keys.insert(
+			key,
+			Cooldown {
+				match_id,
+				expires_at: now + cooldown_ms as i64,
+			},
+		);
+	}
+}
+
+/// Identifies the match of an entry, shared by its entries for the different triggers
+fn match_id(entry: &OutboxEntry) -> String {
+	let phase = entry
+		.monitor_match
+		.alert()
+		.map(|alert| alert.phase.to_string())
+		.unwrap_or_default();
+	format!(
+		"{}|{}|{}|{}",
+		entry.network_slug,
+		entry.monitor_match.monitor().name,
+		entry.monitor_match.transaction_hash(),
+		phase
+	)
+}
+
+/// Renders a dedup key template with the variables of a match
+fn render_key(template: &str, variables: &HashMap<String, String>) -> String {
+	render_template(template, variables)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::{
+		MatchConditions, Monitor, MonitorMatch, StellarBlock, StellarMonitorMatch,
+		StellarTransaction, StellarTransactionInfo,
+	};
+	use tempfile::TempDir;
+
+	fn create_entry(hash: &str, trigger: &str) -> OutboxEntry {
+		let monitor_match = MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+			monitor: Monitor {
+				name: "noisy".to_string(),
+				triggers: vec!["slack".to_string(), "webhook".to_string()],
+				..Default::default()
+			},
+			transaction: StellarTransaction::from(StellarTransactionInfo {
+				transaction_hash: hash.to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}));
+		OutboxEntry::new("stellar_mainnet", 1, monitor_match, trigger)
+	}
+
+	fn variables(from: &str) -> HashMap<String, String> {
+		HashMap::from([
+			("monitor_name".to_string(), "noisy".to_string()),
+			("event_0_from".to_string(), from.to_string()),
+		])
+	}
+
+	#[test]
+	fn test_cooldown_suppresses_repeated_keys() {
+		let throttle = AlertThrottle::new();
+		let policy = AlertPolicy {
+			dedup_key: "${monitor_name}:${event_0_from}".to_string(),
+			cooldown_ms: Some(1000),
+			..Default::default()
+		};
+
+		assert!(throttle.allow(
+			&create_entry("tx1", "slack"),
+			None,
+			Some(&policy),
+			&variables("alice"),
+			0
+		));
+		assert!(!throttle.allow(
+			&create_entry("tx2", "slack"),
+			None,
+			Some(&policy),
+			&variables("alice"),
+			500
+		));
+		assert!(!throttle.allow(
+			&create_entry("tx3", "slack"),
+			None,
+			Some(&policy),
+			&variables("alice"),
+			600
+		));
+		// Other keys and other triggers are not affected
+		assert!(throttle.allow(
+			&create_entry("tx4", "slack"),
+			None,
+			Some(&policy),
+			&variables("bob"),
+			600
+		));
+		assert!(throttle.allow(
+			&create_entry("tx5", "webhook"),
+			None,
+			None,
+			&variables("alice"),
+			600
+		));
+
+		assert!(throttle.take_summaries(999).is_empty());
+		let summaries = throttle.take_summaries(1000);
+		assert_eq!(summaries.len(), 1);
+		assert_eq!(summaries[0].trigger, "slack");
+		assert_eq!(summaries[0].suppressed, Some(2));
+		assert_eq!(summaries[0].monitor_match.transaction_hash(), "tx3");
+
+		assert!(throttle.allow(
+			&create_entry("tx6", "slack"),
+			None,
+			Some(&policy),
+			&variables("alice"),
+			1000
+		));
+	}
+
+	#[test]
+	fn test_monitor_policy_applies_across_triggers() {
+		let throttle = AlertThrottle::new();
+		let policy = AlertPolicy {
+			max_alerts: Some(1),
+			window_ms: 1000,
+			..Default::default()
+		};
+
+		// The first match is sent through both triggers of the monitor
+		for trigger in ["slack", "webhook"] {
+			assert!(throttle.allow(
+				&create_entry("tx1", trigger),
+				Some(&policy),
+				None,
+				&variables("alice"),
+				0
+			));
+		}
+		for trigger in ["slack", "webhook"] {
+			assert!(!throttle.allow(
+				&create_entry("tx2", trigger),
+				Some(&policy),
+				None,
+				&variables("alice"),
+				10
+			));
+		}
+
+		let summaries = throttle.take_summaries(1000);
+		assert_eq!(summaries.len(), 2);
+		assert!(summaries
+			.iter()
+			.all(|summary| summary.suppressed == Some(1)));
+		assert_ne!(summaries[0].key, create_entry("tx2", "slack").key);
+
+		// A new window starts after the summary
+		assert!(throttle.allow(
+			&create_entry("tx3", "slack"),
+			Some(&policy),
+			None,
+			&variables("alice"),
+			1000
+		));
+		assert!(throttle.take_summaries(2000).is_empty());
+		assert!(throttle.scopes.lock().unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_suppressed_alert_does_not_count_against_other_policy() {
+		let throttle = AlertThrottle::new();
+		let monitor_policy = AlertPolicy {
+			max_alerts: Some(2),
+			window_ms: 1000,
+			..Default::default()
+		};
+		let trigger_policy = AlertPolicy {
+			max_alerts: Some(1),
+			window_ms: 1000,
+			..Default::default()
+		};
+
+		assert!(throttle.allow(
+			&create_entry("tx1", "slack"),
+			Some(&monitor_policy),
+			Some(&trigger_policy),
+			&variables("alice"),
+			0
+		));
+		// Suppressed by the trigger policy, so it takes no slot of the monitor window
+		assert!(!throttle.allow(
+			&create_entry("tx2", "slack"),
+			Some(&monitor_policy),
+			Some(&trigger_policy),
+			&variables("alice"),
+			10
+		));
+		assert!(throttle.allow(
+			&create_entry("tx3", "webhook"),
+			Some(&monitor_policy),
+			None,
+			&variables("alice"),
+			20
+		));
+		assert!(!throttle.allow(
+			&create_entry("tx4", "webhook"),
+			Some(&monitor_policy),
+			None,
+			&variables("alice"),
+			30
+		));
+
+		let summaries = throttle.take_summaries(1000);
+		assert_eq!(summaries.len(), 3);
+		assert!(summaries
+			.iter()
+			.all(|summary| summary.suppressed == Some(1)));
+	}
+
+	#[test]
+	fn test_dedup_key_uses_template_engine() {
+		let throttle = AlertThrottle::new();
+		let policy = AlertPolicy {
+			dedup_key: "{{ event_0_from | lowercase }}".to_string(),
+			cooldown_ms: Some(1000),
+			..Default::default()
+		};
+
+		assert!(throttle.allow(
+			&create_entry("tx1", "slack"),
+			None,
+			Some(&policy),
+			&variables("Alice"),
+			0
+		));
+		assert!(!throttle.allow(
+			&create_entry("tx2", "slack"),
+			None,
+			Some(&policy),
+			&variables("ALICE"),
+			10
+		));
+	}
+
+	#[tokio::test]
+	async fn test_state_persistence() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("alert_state.json");
+		let policy = AlertPolicy {
+			cooldown_ms: Some(1000),
+			..Default::default()
+		};
+
+		let throttle = AlertThrottle::with_persistence(path.clone()).unwrap();
+		assert!(throttle.allow(
+			&create_entry("tx1", "slack"),
+			Some(&policy),
+			None,
+			&variables("alice"),
+			0
+		));
+		throttle.persist().await.unwrap();
+
+		let throttle = AlertThrottle::with_persistence(path).unwrap();
+		assert!(!throttle.allow(
+			&create_entry("tx2", "slack"),
+			Some(&policy),
+			None,
+			&variables("alice"),
+			500
+		));
+		assert_eq!(throttle.take_summaries(1000).len(), 2);
+	}
+}
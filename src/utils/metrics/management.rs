@@ -0,0 +1,1300 @@
+//! Management API module
+//!
+//! This module provides authenticated REST endpoints on the metrics server to manage monitors
+//! and triggers and to inspect the block watchers of the running service. A change is validated
+//! together with the rest of the configuration, saved through the repositories of the
+//! configuration storage and applied by reloading the configuration of the service. If the reload fails, the change is reverted and
+//! the failure is returned to the caller. Deliveries in the dead-letter queue of the match outbox
+//! can be listed and replayed.
+//!
+//! Every request must carry the configured token as `Authorization: Bearer <token>`.
+
+#![allow(clippy::result_large_err)]
+
+use actix_web::{
+	body::MessageBody,
+	dev::{ServiceRequest, ServiceResponse},
+	http::header,
+	middleware::{from_fn, Next},
+	web, HttpResponse, Responder,
+};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::{
+	bootstrap::{has_active_monitors, ReloadRequest},
+	models::{BlockChainType, Monitor, Network, Trigger},
+	repositories::{
+		validate_config, ConfigStorage, IssueSeverity, MonitorRepositoryTrait,
+		NetworkRepositoryTrait, RepositoryError, TriggerRepositoryTrait,
+	},
+	services::{
+		blockchain::{BlockChainClient, ClientPool, ClientPoolTrait},
+		blockwatcher::{BlockStorage, BlockStorageBackend},
+		trigger::{FileMatchOutbox, MatchOutbox},
+	},
+	utils::metrics::server::{
+		explain_handler, MonitorServiceData, NetworkServiceData, TriggerServiceData,
+	},
+};
+
+/// Token clients of the authenticated endpoints must send as bearer token
+#[derive(Clone)]
+pub struct BearerToken(pub String);
+
+/// State shared by the management API handlers
+#[derive(Clone)]
+pub struct ManagementState {
+	/// Token clients must send as bearer token
+	pub token: String,
+	/// Storage changes are saved to, read by the configuration reload
+	pub storage: ConfigStorage,
+	/// Channel used to request a configuration reload after a change
+	pub reload_tx: mpsc::Sender<ReloadRequest>,
+	/// Client pool of the block watchers
+	pub client_pool: Arc<ClientPool>,
+	/// Block storage of the block watchers
+	pub block_storage: Arc<BlockStorageBackend>,
+	/// Match outbox holding the dead-lettered deliveries
+	pub outbox: Arc<FileMatchOutbox>,
+}
+
+impl ManagementState {
+	/// Reloads the configuration so a saved change is applied, waiting for the outcome
+	async fn reload(&self) -> Result<(), String> {
+		let (request, response) = ReloadRequest::with_response();
+		if self.reload_tx.send(request).await.is_err() {
+			return Err("The service no longer accepts configuration reloads".to_string());
+		}
+		response
+			.await
+			.unwrap_or_else(|_| Err("The configuration reload was cancelled".to_string()))
+	}
+}
+
+/// Status of the block watcher of a network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+	/// Network slug
+	pub slug: String,
+	/// Network name
+	pub name: String,
+	/// Blockchain type of the network
+	pub network_type: BlockChainType,
+	/// Number of active monitors on the network, the network is watched if there is any
+	pub active_monitors: usize,
+	/// Last block processed by the watcher
+	pub last_processed_block: Option<u64>,
+	/// Latest block of the network
+	pub latest_block: Option<u64>,
+	/// Number of blocks the watcher is behind the latest block
+	pub lag: Option<u64>,
+	/// RPC URL the watcher currently uses
+	pub rpc_url: Option<String>,
+	/// Error encountered while fetching the status
+	pub error: Option<String>,
+}
+
+/// Registers the management API routes under `/api`
+///
+/// The repository types must be the ones of the services registered with the app, changes are
+/// saved with them.
+///
+/// # Arguments
+/// * `cfg` - Service configuration of the metrics server app
+/// * `state` - State shared by the handlers
+pub fn configure<M, N, T>(cfg: &mut web::ServiceConfig, state: ManagementState)
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	cfg.app_data(web::Data::new(BearerToken(state.token.clone())))
+		.app_data(web::Data::new(state))
+		.service(
+			web::scope("/api")
+				.wrap(from_fn(authorize))
+				.route("/monitors", web::get().to(list_monitors::<M, N, T>))
+				.route("/monitors", web::post().to(create_monitor::<M, N, T>))
+				.route("/monitors/{id}", web::get().to(get_monitor::<M, N, T>))
+				.route("/monitors/{id}", web::put().to(update_monitor::<M, N, T>))
+				.route(
+					"/monitors/{id}",
+					web::delete().to(delete_monitor::<M, N, T>),
+				)
+				.route(
+					"/monitors/{id}/pause",
+					web::post().to(pause_monitor::<M, N, T>),
+				)
+				.route(
+					"/monitors/{id}/resume",
+					web::post().to(resume_monitor::<M, N, T>),
+				)
+				.route("/triggers", web::get().to(list_triggers::<T>))
+				.route("/triggers", web::post().to(create_trigger::<M, N, T>))
+				.route("/triggers/{id}", web::get().to(get_trigger::<T>))
+				.route("/triggers/{id}", web::put().to(update_trigger::<M, N, T>))
+				.route(
+					"/triggers/{id}",
+					web::delete().to(delete_trigger::<M, N, T>),
+				)
+				.route("/networks/status", web::get().to(network_status::<M, N, T>))
+				.route("/dead-letters", web::get().to(list_dead_letters))
+				.route(
+					"/dead-letters/{key}/replay",
+					web::post().to(replay_dead_letter),
+				)
+				.route("/debug/explain", web::get().to(explain_handler::<M, N, T>)),
+		);
+}
+
+/// Rejects requests without the configured bearer token
+///
+/// The token is read from the [`BearerToken`] app data, requests are rejected if it is not set.
+pub async fn authorize(
+	req: ServiceRequest,
+	next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+	let authorized = match req.app_data::<web::Data<BearerToken>>() {
+		Some(expected) => req
+			.headers()
+			.get(header::AUTHORIZATION)
+			.and_then(|value| value.to_str().ok())
+			.and_then(|value| value.strip_prefix("Bearer "))
+			.is_some_and(|token| tokens_match(token, &expected.0)),
+		None => false,
+	};
+
+	if !authorized {
+		return Ok(req
+			.into_response(HttpResponse::Unauthorized().body("Missing or invalid token"))
+			.map_into_right_body());
+	}
+
+	next.call(req)
+		.await
+		.map(ServiceResponse::map_into_left_body)
+}
+
+/// Compares tokens in constant time
+fn tokens_match(provided: &str, expected: &str) -> bool {
+	provided.len() == expected.len()
+		&& provided
+			.bytes()
+			.zip(expected.bytes())
+			.fold(0, |diff, (a, b)| diff | (a ^ b))
+			== 0
+}
+
+/// Derives the ID of a new configuration from its name
+///
+/// Characters that are not allowed in IDs are replaced with `_`.
+fn config_id(name: &str) -> String {
+	name.trim()
+		.chars()
+		.map(|c| {
+			if c.is_ascii_alphanumeric() || c == '-' {
+				c.to_ascii_lowercase()
+			} else {
+				'_'
+			}
+		})
+		.collect()
+}
+
+/// Validates the configuration the service would load once a change is saved
+///
+/// The candidate is the current configuration with the change applied, so references between
+/// monitors, networks and triggers and the scripts they use are checked like on a reload.
+fn validate_candidate(
+	monitors: &HashMap<String, Monitor>,
+	triggers: &HashMap<String, Trigger>,
+	networks: &HashMap<String, Network>,
+) -> Result<(), HttpResponse> {
+	let report = validate_config(monitors, triggers, networks);
+	if !report.has_errors() {
+		return Ok(());
+	}
+
+	let errors = report
+		.issues
+		.iter()
+		.filter(|issue| issue.severity == IssueSeverity::Error)
+		.map(|issue| format!("[{}] {}", issue.kind, issue.message))
+		.collect::<Vec<_>>();
+	Err(HttpResponse::UnprocessableEntity().body(errors.join("\n")))
+}
+
+/// Applies a saved change by reloading the configuration
+///
+/// If the reload fails, the change is reverted so the configuration directory keeps matching
+/// the running configuration, and the failure is returned to the caller.
+async fn apply_change(
+	state: &ManagementState,
+	change: &str,
+	revert: impl FnOnce() -> Result<(), RepositoryError>,
+) -> Result<(), HttpResponse> {
+	let Err(reload_error) = state.reload().await else {
+		info!("{} through the management API", change);
+		return Ok(());
+	};
+
+	error!("Failed to apply change '{}': {}", change, reload_error);
+	if let Err(e) = revert() {
+		error!("Failed to revert change '{}': {}", change, e);
+		return Err(HttpResponse::InternalServerError().body(format!(
+			"Configuration reload failed and the change could not be reverted: {}\n{}",
+			reload_error, e
+		)));
+	}
+	Err(HttpResponse::UnprocessableEntity().body(format!(
+		"Configuration reload failed, the change was reverted: {}",
+		reload_error
+	)))
+}
+
+/// Saves a monitor and reloads the configuration to apply it
+///
+/// # Arguments
+/// * `state` - State of the management API
+/// * `id` - ID of the monitor
+/// * `monitor` - Monitor to save
+/// * `previous` - Monitor saved under the ID before, restored if the reload fails
+async fn save_monitor<M, N, T>(
+	state: &ManagementState,
+	id: &str,
+	monitor: &Monitor,
+	previous: Option<Monitor>,
+) -> Result<(), HttpResponse>
+where
+	M: MonitorRepositoryTrait<N, T>,
+	N: NetworkRepositoryTrait,
+	T: TriggerRepositoryTrait,
+{
+	let path = state.storage.path("monitors");
+	M::save(Some(&path), id, monitor).map_err(|e| {
+		error!("Failed to save monitor {}: {}", id, e);
+		HttpResponse::InternalServerError().body(e.to_string())
+	})?;
+
+	apply_change(state, &format!("Monitor {} saved", id), || match previous {
+		Some(previous) => M::save(Some(&path), id, &previous),
+		None => M::delete(Some(&path), id),
+	})
+	.await
+}
+
+/// Saves a trigger and reloads the configuration to apply it
+///
+/// # Arguments
+/// * `state` - State of the management API
+/// * `id` - ID of the trigger
+/// * `trigger` - Trigger to save
+/// * `previous` - Trigger saved under the ID before, restored if the reload fails
+async fn save_trigger<T: TriggerRepositoryTrait>(
+	state: &ManagementState,
+	id: &str,
+	trigger: &Trigger,
+	previous: Option<Trigger>,
+) -> Result<(), HttpResponse> {
+	let path = state.storage.path("triggers");
+	T::save(Some(&path), id, trigger).map_err(|e| {
+		error!("Failed to save trigger {}: {}", id, e);
+		HttpResponse::InternalServerError().body(e.to_string())
+	})?;
+
+	apply_change(state, &format!("Trigger {} saved", id), || match previous {
+		Some(previous) => T::save(Some(&path), id, &previous),
+		None => T::delete(Some(&path), id),
+	})
+	.await
+}
+
+fn monitor_not_found(id: &str) -> HttpResponse {
+	HttpResponse::NotFound().body(format!("Monitor '{}' not found", id))
+}
+
+fn trigger_not_found(id: &str) -> HttpResponse {
+	HttpResponse::NotFound().body(format!("Trigger '{}' not found", id))
+}
+
+/// Lists all monitors keyed by ID
+async fn list_monitors<M, N, T>(monitor_service: MonitorServiceData<M, N, T>) -> impl Responder
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	HttpResponse::Ok().json(monitor_service.lock().await.get_all())
+}
+
+/// Returns a single monitor
+async fn get_monitor<M, N, T>(
+	id: web::Path<String>,
+	monitor_service: MonitorServiceData<M, N, T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	match monitor_service.lock().await.get(&id) {
+		Some(monitor) => HttpResponse::Ok().json(monitor),
+		None => monitor_not_found(&id),
+	}
+}
+
+/// Creates a monitor, using an ID derived from its name
+async fn create_monitor<M, N, T>(
+	monitor: web::Json<Monitor>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let monitor = monitor.into_inner();
+	let id = config_id(&monitor.name);
+	if monitor_service.lock().await.get(&id).is_some() {
+		return HttpResponse::Conflict().body(format!("Monitor '{}' already exists", id));
+	}
+
+	let mut monitors = monitor_service.lock().await.get_all();
+	monitors.insert(id.clone(), monitor.clone());
+	let networks = network_service.lock().await.get_all();
+	let triggers = trigger_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+	if let Err(response) = save_monitor::<M, N, T>(&state, &id, &monitor, None).await {
+		return response;
+	}
+
+	HttpResponse::Created().json(HashMap::from([(id, monitor)]))
+}
+
+/// Replaces an existing monitor
+async fn update_monitor<M, N, T>(
+	id: web::Path<String>,
+	monitor: web::Json<Monitor>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let mut monitors = monitor_service.lock().await.get_all();
+	let monitor = monitor.into_inner();
+	let Some(previous) = monitors.insert(id.to_string(), monitor.clone()) else {
+		return monitor_not_found(&id);
+	};
+
+	let networks = network_service.lock().await.get_all();
+	let triggers = trigger_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+	if let Err(response) = save_monitor::<M, N, T>(&state, &id, &monitor, Some(previous)).await {
+		return response;
+	}
+
+	HttpResponse::Ok().json(monitor)
+}
+
+/// Deletes a monitor
+async fn delete_monitor<M, N, T>(
+	id: web::Path<String>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let mut monitors = monitor_service.lock().await.get_all();
+	let Some(previous) = monitors.remove(id.as_str()) else {
+		return monitor_not_found(&id);
+	};
+
+	let networks = network_service.lock().await.get_all();
+	let triggers = trigger_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+
+	let path = state.storage.path("monitors");
+	if let Err(e) = M::delete(Some(&path), &id) {
+		error!("Failed to delete monitor {}: {}", id, e);
+		return HttpResponse::InternalServerError().body(e.to_string());
+	}
+	let change = format!("Monitor {} deleted", id);
+	if let Err(response) =
+		apply_change(&state, &change, || M::save(Some(&path), &id, &previous)).await
+	{
+		return response;
+	}
+
+	HttpResponse::NoContent().finish()
+}
+
+/// Pauses a monitor
+async fn pause_monitor<M, N, T>(
+	id: web::Path<String>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let services = (&monitor_service, &network_service, &trigger_service);
+	set_monitor_paused(&id, true, &state, services).await
+}
+
+/// Resumes a paused monitor
+async fn resume_monitor<M, N, T>(
+	id: web::Path<String>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let services = (&monitor_service, &network_service, &trigger_service);
+	set_monitor_paused(&id, false, &state, services).await
+}
+
+async fn set_monitor_paused<M, N, T>(
+	id: &str,
+	paused: bool,
+	state: &ManagementState,
+	(monitor_service, network_service, trigger_service): (
+		&MonitorServiceData<M, N, T>,
+		&NetworkServiceData<N>,
+		&TriggerServiceData<T>,
+	),
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let mut monitors = monitor_service.lock().await.get_all();
+	let Some(monitor) = monitors.get_mut(id) else {
+		return monitor_not_found(id);
+	};
+	let previous = monitor.clone();
+	monitor.paused = paused;
+	let monitor = monitor.clone();
+
+	let networks = network_service.lock().await.get_all();
+	let triggers = trigger_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+	if let Err(response) = save_monitor::<M, N, T>(state, id, &monitor, Some(previous)).await {
+		return response;
+	}
+
+	HttpResponse::Ok().json(monitor)
+}
+
+/// Lists all triggers keyed by ID
+async fn list_triggers<T: TriggerRepositoryTrait + 'static>(
+	trigger_service: TriggerServiceData<T>,
+) -> impl Responder {
+	HttpResponse::Ok().json(trigger_service.lock().await.get_all())
+}
+
+/// Returns a single trigger
+async fn get_trigger<T: TriggerRepositoryTrait + 'static>(
+	id: web::Path<String>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse {
+	match trigger_service.lock().await.get(&id) {
+		Some(trigger) => HttpResponse::Ok().json(trigger),
+		None => trigger_not_found(&id),
+	}
+}
+
+/// Creates a trigger, using its name as ID
+async fn create_trigger<M, N, T>(
+	trigger: web::Json<Trigger>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let trigger = trigger.into_inner();
+	let id = trigger.name.clone();
+	let mut triggers = trigger_service.lock().await.get_all();
+	if triggers.insert(id.clone(), trigger.clone()).is_some() {
+		return HttpResponse::Conflict().body(format!("Trigger '{}' already exists", id));
+	}
+
+	let monitors = monitor_service.lock().await.get_all();
+	let networks = network_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+	if let Err(response) = save_trigger::<T>(&state, &id, &trigger, None).await {
+		return response;
+	}
+
+	HttpResponse::Created().json(HashMap::from([(id, trigger)]))
+}
+
+/// Replaces an existing trigger
+async fn update_trigger<M, N, T>(
+	id: web::Path<String>,
+	trigger: web::Json<Trigger>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let mut triggers = trigger_service.lock().await.get_all();
+	let trigger = trigger.into_inner();
+	let Some(previous) = triggers.insert(id.to_string(), trigger.clone()) else {
+		return trigger_not_found(&id);
+	};
+
+	let monitors = monitor_service.lock().await.get_all();
+	let networks = network_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+	if let Err(response) = save_trigger::<T>(&state, &id, &trigger, Some(previous)).await {
+		return response;
+	}
+
+	HttpResponse::Ok().json(trigger)
+}
+
+/// Deletes a trigger that no monitor uses
+async fn delete_trigger<M, N, T>(
+	id: web::Path<String>,
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> HttpResponse
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let mut triggers = trigger_service.lock().await.get_all();
+	let Some(previous) = triggers.remove(id.as_str()) else {
+		return trigger_not_found(&id);
+	};
+
+	let monitors = monitor_service.lock().await.get_all();
+	let mut used_by: Vec<String> = monitors
+		.iter()
+		.filter(|(_, monitor)| monitor.triggers.contains(&id))
+		.map(|(monitor_id, _)| monitor_id.clone())
+		.collect();
+	if !used_by.is_empty() {
+		used_by.sort();
+		return HttpResponse::Conflict().body(format!(
+			"Trigger '{}' is used by monitor(s): {}",
+			id,
+			used_by.join(", ")
+		));
+	}
+
+	let networks = network_service.lock().await.get_all();
+	if let Err(response) = validate_candidate(&monitors, &triggers, &networks) {
+		return response;
+	}
+
+	let path = state.storage.path("triggers");
+	if let Err(e) = T::delete(Some(&path), &id) {
+		error!("Failed to delete trigger {}: {}", id, e);
+		return HttpResponse::InternalServerError().body(e.to_string());
+	}
+	let change = format!("Trigger {} deleted", id);
+	if let Err(response) =
+		apply_change(&state, &change, || T::save(Some(&path), &id, &previous)).await
+	{
+		return response;
+	}
+
+	HttpResponse::NoContent().finish()
+}
+
+/// Returns the block watcher status of every network, sorted by slug
+///
+/// The latest block and RPC URL are only fetched for networks with active monitors.
+async fn network_status<M, N, T>(
+	state: web::Data<ManagementState>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+) -> impl Responder
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	let active_monitors: Vec<Monitor> = monitor_service
+		.lock()
+		.await
+		.get_all()
+		.into_values()
+		.filter(|monitor| !monitor.paused)
+		.collect();
+	let mut networks: Vec<Network> = network_service
+		.lock()
+		.await
+		.get_all()
+		.into_values()
+		.collect();
+	networks.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+	let mut statuses = Vec::with_capacity(networks.len());
+	for network in networks {
+		let mut status = NetworkStatus {
+			slug: network.slug.clone(),
+			name: network.name.clone(),
+			network_type: network.network_type.clone(),
+			active_monitors: active_monitors
+				.iter()
+				.filter(|monitor| monitor.networks.contains(&network.slug))
+				.count(),
+			last_processed_block: None,
+			latest_block: None,
+			lag: None,
+			rpc_url: None,
+			error: None,
+		};
+
+		match state
+			.block_storage
+			.get_last_processed_block(&network.slug)
+			.await
+		{
+			Ok(block) => status.last_processed_block = block,
+			Err(e) => status.error = Some(e.to_string()),
+		}
+
+		if has_active_monitors(&active_monitors, &network.slug) {
+			let result = match network.network_type {
+				BlockChainType::EVM => match state.client_pool.get_evm_client(&network).await {
+					Ok(client) => Ok(client_status(client.as_ref()).await),
+					Err(e) => Err(e),
+				},
+				BlockChainType::Stellar => {
+					match state.client_pool.get_stellar_client(&network).await {
+						Ok(client) => Ok(client_status(client.as_ref()).await),
+						Err(e) => Err(e),
+					}
+				}
+				_ => Err(anyhow::anyhow!("Unsupported network type")),
+			};
+
+			match result {
+				Ok((latest_block, rpc_url)) => {
+					status.rpc_url = rpc_url;
+					match latest_block {
+						Ok(latest_block) => {
+							status.latest_block = Some(latest_block);
+							status.lag = status
+								.last_processed_block
+								.map(|last| latest_block.saturating_sub(last));
+						}
+						Err(e) => status.error = Some(e.to_string()),
+					}
+				}
+				Err(e) => status.error = Some(e.to_string()),
+			}
+		}
+
+		statuses.push(status);
+	}
+
+	HttpResponse::Ok().json(statuses)
+}
+
+/// Lists the dead-lettered deliveries, oldest first
+async fn list_dead_letters(state: web::Data<ManagementState>) -> HttpResponse {
+	match state.outbox.dead_letters().await {
+		Ok(entries) => HttpResponse::Ok().json(entries),
+		Err(e) => {
+			error!("Failed to read dead letters: {}", e);
+			HttpResponse::InternalServerError().body(e.to_string())
+		}
+	}
+}
+
+/// Moves a dead-lettered delivery back to the outbox for immediate delivery
+async fn replay_dead_letter(
+	key: web::Path<String>,
+	state: web::Data<ManagementState>,
+) -> HttpResponse {
+	// Keys are hex digests, anything else cannot name a dead letter
+	if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+		return dead_letter_not_found(&key);
+	}
+
+	match state.outbox.replay_dead_letter(&key).await {
+		Ok(true) => {
+			info!("Dead letter {} replayed through the management API", key);
+			HttpResponse::Accepted().finish()
+		}
+		Ok(false) => dead_letter_not_found(&key),
+		Err(e) => {
+			error!("Failed to replay dead letter {}: {}", key, e);
+			HttpResponse::InternalServerError().body(e.to_string())
+		}
+	}
+}
+
+fn dead_letter_not_found(key: &str) -> HttpResponse {
+	HttpResponse::NotFound().body(format!("Dead letter '{}' not found", key))
+}
+
+/// Fetches the latest block number and active RPC URL of a client
+async fn client_status<C: BlockChainClient>(
+	client: &C,
+) -> (Result<u64, anyhow::Error>, Option<String>) {
+	(
+		client.get_latest_block_number().await,
+		client.get_current_url().await,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{
+			MatchConditions, MonitorMatch, NotificationMessage, StellarBlock, StellarMonitorMatch,
+			StellarTransaction, StellarTransactionInfo, TriggerType, TriggerTypeConfig,
+		},
+		repositories::{
+			import_config_dir, MonitorRepository, MonitorService, NetworkRepository,
+			NetworkService, SqliteMonitorRepository, SqliteNetworkRepository,
+			SqliteTriggerRepository, TriggerRepository, TriggerService,
+		},
+		services::{blockwatcher::FileBlockStorage, filter::FilterService, trigger::OutboxEntry},
+	};
+	use actix_web::{http::StatusCode, test, App};
+	use std::{
+		fs,
+		sync::atomic::{AtomicUsize, Ordering},
+	};
+	use tempfile::TempDir;
+	use tokio::sync::Mutex;
+
+	const TOKEN: &str = "secret-token";
+
+	type FileMonitorRepository = MonitorRepository<NetworkRepository, TriggerRepository>;
+	type DatabaseMonitorRepository =
+		SqliteMonitorRepository<SqliteNetworkRepository, SqliteTriggerRepository>;
+
+	fn create_config_dir() -> TempDir {
+		let temp_dir = TempDir::new().unwrap();
+		for dir in ["networks", "monitors", "triggers"] {
+			fs::create_dir(temp_dir.path().join(dir)).unwrap();
+		}
+		fs::write(
+			temp_dir.path().join("networks/ethereum_mainnet.json"),
+			r#"{
+				"network_type": "EVM",
+				"slug": "ethereum_mainnet",
+				"name": "Ethereum Mainnet",
+				"rpc_urls": [{"type_": "rpc", "url": "http://localhost:8545", "weight": 100}],
+				"chain_id": 1,
+				"block_time_ms": 12000,
+				"confirmation_blocks": 1,
+				"cron_schedule": "0 */1 * * * *",
+				"max_past_blocks": 10,
+				"store_blocks": false
+			}"#,
+		)
+		.unwrap();
+		temp_dir
+	}
+
+	fn create_trigger(name: &str) -> Trigger {
+		Trigger {
+			name: name.to_string(),
+			trigger_type: TriggerType::Webhook,
+			config: TriggerTypeConfig::Webhook {
+				url: "https://example.com/webhook".to_string(),
+				method: Some("POST".to_string()),
+				secret: None,
+				secrets: None,
+				headers: None,
+				message: NotificationMessage {
+					title: "title".to_string(),
+					body: "body".to_string(),
+				},
+				payload: None,
+			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
+		}
+	}
+
+	fn create_monitor(name: &str, triggers: Vec<&str>) -> Monitor {
+		Monitor {
+			name: name.to_string(),
+			networks: vec!["ethereum_mainnet".to_string()],
+			triggers: triggers.into_iter().map(String::from).collect(),
+			..Default::default()
+		}
+	}
+
+	/// Loads the services from the config directory, like a reload would
+	fn app_data(
+		config_dir: &TempDir,
+	) -> (MonitorServiceData, NetworkServiceData, TriggerServiceData) {
+		let network_service =
+			NetworkService::<NetworkRepository>::new(Some(&config_dir.path().join("networks")))
+				.unwrap();
+		let trigger_service =
+			TriggerService::<TriggerRepository>::new(Some(&config_dir.path().join("triggers")))
+				.unwrap();
+		let monitor_service = MonitorService::new(
+			Some(&config_dir.path().join("monitors")),
+			Some(network_service.clone()),
+			Some(trigger_service.clone()),
+		)
+		.unwrap();
+
+		(
+			web::Data::new(Arc::new(Mutex::new(monitor_service))),
+			web::Data::new(Arc::new(Mutex::new(network_service))),
+			web::Data::new(Arc::new(Mutex::new(trigger_service))),
+		)
+	}
+
+	fn create_state(config_dir: &TempDir) -> (ManagementState, mpsc::Receiver<ReloadRequest>) {
+		let (reload_tx, reload_rx) = mpsc::channel(1);
+		(
+			ManagementState {
+				token: TOKEN.to_string(),
+				storage: ConfigStorage::Files(config_dir.path().to_path_buf()),
+				reload_tx,
+				client_pool: Arc::new(ClientPool::new()),
+				block_storage: Arc::new(BlockStorageBackend::File(FileBlockStorage::new(
+					config_dir.path().join("data"),
+				))),
+				outbox: Arc::new(FileMatchOutbox::new(config_dir.path().join("outbox"))),
+			},
+			reload_rx,
+		)
+	}
+
+	/// Answers every reload request with the given outcome, counting the requests
+	fn answer_reloads(
+		mut reload_rx: mpsc::Receiver<ReloadRequest>,
+		outcome: Result<(), String>,
+	) -> Arc<AtomicUsize> {
+		let reloads = Arc::new(AtomicUsize::new(0));
+		tokio::spawn({
+			let reloads = reloads.clone();
+			async move {
+				while let Some(request) = reload_rx.recv().await {
+					reloads.fetch_add(1, Ordering::SeqCst);
+					request.respond(outcome.clone());
+				}
+			}
+		});
+		reloads
+	}
+
+	macro_rules! init_app {
+		($config_dir:expr, $state:expr) => {{
+			let (monitor_service, network_service, trigger_service) = app_data($config_dir);
+			let state = $state.clone();
+			test::init_service(
+				App::new()
+					.app_data(monitor_service)
+					.app_data(network_service)
+					.app_data(trigger_service)
+					.configure(move |cfg| {
+						configure::<FileMonitorRepository, NetworkRepository, TriggerRepository>(
+							cfg, state,
+						)
+					}),
+			)
+			.await
+		}};
+	}
+
+	fn authorized(request: test::TestRequest) -> test::TestRequest {
+		request.insert_header((header::AUTHORIZATION, format!("Bearer {}", TOKEN)))
+	}
+
+	#[actix_web::test]
+	async fn test_config_id_and_token_comparison() {
+		assert_eq!(config_id(" Large Transfer-USDC "), "large_transfer-usdc");
+		assert!(tokens_match(TOKEN, TOKEN));
+		assert!(!tokens_match("secret-tokem", TOKEN));
+		assert!(!tokens_match("secret", TOKEN));
+	}
+
+	#[actix_web::test]
+	async fn test_requests_require_token() {
+		let config_dir = create_config_dir();
+		let (state, _reload_rx) = create_state(&config_dir);
+		let app = init_app!(&config_dir, state);
+
+		let req = test::TestRequest::get().uri("/api/monitors").to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::UNAUTHORIZED
+		);
+
+		let req = test::TestRequest::get()
+			.uri("/api/monitors")
+			.insert_header((header::AUTHORIZATION, "Bearer wrong-token"))
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::UNAUTHORIZED
+		);
+
+		let req = authorized(test::TestRequest::get().uri("/api/monitors")).to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+	}
+
+	#[actix_web::test]
+	async fn test_explain_requires_token() {
+		let config_dir = create_config_dir();
+		let (state, _reload_rx) = create_state(&config_dir);
+		let (monitor_service, network_service, _) = app_data(&config_dir);
+		let app = test::init_service(
+			App::new()
+				.app_data(monitor_service)
+				.app_data(network_service)
+				.app_data(web::Data::new(state.client_pool.clone()))
+				.app_data(web::Data::new(Arc::new(FilterService::new())))
+				.configure(move |cfg| {
+					configure::<FileMonitorRepository, NetworkRepository, TriggerRepository>(
+						cfg, state,
+					)
+				}),
+		)
+		.await;
+
+		let req = test::TestRequest::get()
+			.uri("/api/debug/explain?monitor=missing&network=ethereum_mainnet&block=1")
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::UNAUTHORIZED
+		);
+
+		let req = authorized(
+			test::TestRequest::get()
+				.uri("/api/debug/explain?monitor=missing&network=ethereum_mainnet&block=1"),
+		)
+		.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::NOT_FOUND
+		);
+	}
+
+	#[actix_web::test]
+	async fn test_create_and_manage_monitor() {
+		let config_dir = create_config_dir();
+		let (state, reload_rx) = create_state(&config_dir);
+		let reloads = answer_reloads(reload_rx, Ok(()));
+
+		let app = init_app!(&config_dir, state);
+		let req = authorized(test::TestRequest::post().uri("/api/triggers"))
+			.set_json(create_trigger("webhook"))
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::CREATED
+		);
+		assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+		// Monitors referencing unknown triggers are rejected
+		let app = init_app!(&config_dir, state);
+		let req = authorized(test::TestRequest::post().uri("/api/monitors"))
+			.set_json(create_monitor("Large Transfer", vec!["missing"]))
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::UNPROCESSABLE_ENTITY
+		);
+		assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+		let req = authorized(test::TestRequest::post().uri("/api/monitors"))
+			.set_json(create_monitor("Large Transfer", vec!["webhook"]))
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::CREATED
+		);
+		assert_eq!(reloads.load(Ordering::SeqCst), 2);
+		assert!(config_dir
+			.path()
+			.join("monitors/large_transfer.json")
+			.exists());
+
+		let app = init_app!(&config_dir, state);
+		let req = authorized(test::TestRequest::post().uri("/api/monitors/large_transfer/pause"))
+			.to_request();
+		let resp: Monitor = test::call_and_read_body_json(&app, req).await;
+		assert!(resp.paused);
+
+		// The trigger is in use and cannot be deleted
+		let req = authorized(test::TestRequest::delete().uri("/api/triggers/webhook")).to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::CONFLICT
+		);
+
+		let req = authorized(test::TestRequest::delete().uri("/api/monitors/large_transfer"))
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::NO_CONTENT
+		);
+		assert!(!config_dir
+			.path()
+			.join("monitors/large_transfer.json")
+			.exists());
+
+		let req =
+			authorized(test::TestRequest::get().uri("/api/monitors/large_transfer")).to_request();
+		let app = init_app!(&config_dir, state);
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::NOT_FOUND
+		);
+	}
+
+	#[actix_web::test]
+	async fn test_failed_reload_reverts_change() {
+		let config_dir = create_config_dir();
+		let (state, reload_rx) = create_state(&config_dir);
+		let reloads = answer_reloads(reload_rx, Err("Failed to load scripts".to_string()));
+		TriggerRepository::save(
+			Some(&config_dir.path().join("triggers")),
+			"webhook",
+			&create_trigger("webhook"),
+		)
+		.unwrap();
+
+		// A new trigger is removed again
+		let app = init_app!(&config_dir, state);
+		let req = authorized(test::TestRequest::post().uri("/api/triggers"))
+			.set_json(create_trigger("other"))
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+		let body = test::read_body(resp).await;
+		assert!(String::from_utf8_lossy(&body).contains("Failed to load scripts"));
+		assert!(!config_dir.path().join("triggers/other.json").exists());
+
+		// An updated trigger is restored
+		let mut trigger = create_trigger("webhook");
+		if let TriggerTypeConfig::Webhook { url, .. } = &mut trigger.config {
+			*url = "https://example.com/changed".to_string();
+		}
+		let req = authorized(test::TestRequest::put().uri("/api/triggers/webhook"))
+			.set_json(trigger)
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::UNPROCESSABLE_ENTITY
+		);
+		let triggers =
+			TriggerRepository::load_all(Some(&config_dir.path().join("triggers"))).unwrap();
+		assert_eq!(triggers["webhook"], create_trigger("webhook"));
+
+		// A deleted trigger is saved again
+		let req = authorized(test::TestRequest::delete().uri("/api/triggers/webhook")).to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::UNPROCESSABLE_ENTITY
+		);
+		assert!(config_dir.path().join("triggers/webhook.json").exists());
+		assert_eq!(reloads.load(Ordering::SeqCst), 3);
+	}
+
+	#[actix_web::test]
+	async fn test_change_is_validated_with_the_whole_configuration() {
+		let config_dir = create_config_dir();
+		let (state, reload_rx) = create_state(&config_dir);
+		let reloads = answer_reloads(reload_rx, Ok(()));
+		TriggerRepository::save(
+			Some(&config_dir.path().join("triggers")),
+			"webhook",
+			&create_trigger("webhook"),
+		)
+		.unwrap();
+		FileMonitorRepository::save(
+			Some(&config_dir.path().join("monitors")),
+			"large_transfer",
+			&create_monitor("Large Transfer", vec!["webhook"]),
+		)
+		.unwrap();
+
+		// The monitor would reference an unknown network
+		let app = init_app!(&config_dir, state);
+		let mut monitor = create_monitor("Large Transfer", vec!["webhook"]);
+		monitor.networks.push("unknown".to_string());
+		let req = authorized(test::TestRequest::put().uri("/api/monitors/large_transfer"))
+			.set_json(monitor)
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+		let body = test::read_body(resp).await;
+		assert!(String::from_utf8_lossy(&body).contains("[invalid_reference]"));
+
+		assert_eq!(reloads.load(Ordering::SeqCst), 0);
+		let saved =
+			fs::read_to_string(config_dir.path().join("monitors/large_transfer.json")).unwrap();
+		assert!(!saved.contains("unknown"));
+	}
+
+	#[actix_web::test]
+	async fn test_changes_are_saved_to_the_database() {
+		let config_dir = create_config_dir();
+		let database = config_dir.path().join("config.db");
+		import_config_dir(Some(&database), config_dir.path()).unwrap();
+		let (mut state, reload_rx) = create_state(&config_dir);
+		state.storage = ConfigStorage::Sqlite(database.clone());
+		let reloads = answer_reloads(reload_rx, Ok(()));
+
+		let (monitor_service, network_service, trigger_service) = state
+			.storage
+			.load_services::<DatabaseMonitorRepository, _, _>()
+			.unwrap();
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(Arc::new(Mutex::new(monitor_service))))
+				.app_data(web::Data::new(Arc::new(Mutex::new(network_service))))
+				.app_data(web::Data::new(Arc::new(Mutex::new(trigger_service))))
+				.configure({
+					let state = state.clone();
+					move |cfg| {
+						configure::<
+							DatabaseMonitorRepository,
+							SqliteNetworkRepository,
+							SqliteTriggerRepository,
+						>(cfg, state)
+					}
+				}),
+		)
+		.await;
+
+		let req = authorized(test::TestRequest::post().uri("/api/triggers"))
+			.set_json(create_trigger("webhook"))
+			.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::CREATED
+		);
+		assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+		let triggers = SqliteTriggerRepository::load_all(Some(&database)).unwrap();
+		assert_eq!(triggers["webhook"], create_trigger("webhook"));
+		assert!(!config_dir.path().join("triggers/webhook.json").exists());
+	}
+
+	#[actix_web::test]
+	async fn test_network_status_without_active_monitors() {
+		let config_dir = create_config_dir();
+		let (state, _reload_rx) = create_state(&config_dir);
+		let app = init_app!(&config_dir, state);
+
+		let req = authorized(test::TestRequest::get().uri("/api/networks/status")).to_request();
+		let statuses: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+
+		assert_eq!(statuses.len(), 1);
+		assert_eq!(statuses[0]["slug"], "ethereum_mainnet");
+		assert_eq!(statuses[0]["active_monitors"], 0);
+		assert!(statuses[0]["rpc_url"].is_null());
+	}
+
+	#[actix_web::test]
+	async fn test_list_and_replay_dead_letters() {
+		let config_dir = create_config_dir();
+		let (state, _reload_rx) = create_state(&config_dir);
+		let monitor_match = MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+			monitor: create_monitor("Large Transfer", vec!["webhook"]),
+			transaction: StellarTransaction::from(StellarTransactionInfo {
+				transaction_hash: "tx1".to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}));
+		let entry = OutboxEntry::new("stellar_mainnet", 1, monitor_match, "webhook");
+		state.outbox.dead_letter(&entry).await.unwrap();
+		let app = init_app!(&config_dir, state);
+
+		let req = authorized(test::TestRequest::get().uri("/api/dead-letters")).to_request();
+		let dead_letters: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(dead_letters.len(), 1);
+		assert_eq!(dead_letters[0]["key"], entry.key);
+		assert_eq!(dead_letters[0]["trigger"], "webhook");
+
+		let req = authorized(
+			test::TestRequest::post().uri(&format!("/api/dead-letters/{}/replay", entry.key)),
+		)
+		.to_request();
+		assert_eq!(
+			test::call_service(&app, req).await.status(),
+			StatusCode::ACCEPTED
+		);
+		assert!(state.outbox.dead_letters().await.unwrap().is_empty());
+		assert_eq!(state.outbox.due(i64::MAX, 10).await.unwrap().len(), 1);
+
+		for key in [entry.key.as_str(), "..%2Fpending"] {
+			let req = authorized(
+				test::TestRequest::post().uri(&format!("/api/dead-letters/{}/replay", key)),
+			)
+			.to_request();
+			assert_eq!(
+				test::call_service(&app, req).await.status(),
+				StatusCode::NOT_FOUND
+			);
+		}
+	}
+}
@@ -3,9 +3,11 @@
 //! - This module contains the global Prometheus registry.
 //! - Defines specific metrics for the application.
 
+pub mod management;
 pub mod server;
+pub mod stream;
 use lazy_static::lazy_static;
-use prometheus::{Encoder, Gauge, GaugeVec, Opts, Registry, TextEncoder};
+use prometheus::{CounterVec, Encoder, Gauge, GaugeVec, Opts, Registry, TextEncoder};
 use sysinfo::{Disks, System};
 
 lazy_static! {
@@ -134,6 +136,32 @@ lazy_static! {
 		REGISTRY.register(Box::new(gauge.clone())).unwrap();
 		gauge
 	};
+
+	/// Gauge Vector for outstanding missed blocks.
+	///
+	/// Tracks the number of blocks in each network's missed block ledger that have not yet been
+	/// recovered, with the network slug as a label.
+	pub static ref MISSED_BLOCKS_OUTSTANDING: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("missed_blocks_outstanding", "Number of missed blocks awaiting recovery per network"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter Vector for trigger delivery attempts.
+	///
+	/// Counts delivery attempts of the match outbox per trigger, with the outcome (`delivered`,
+	/// `retried` or `failed`) as a label.
+	pub static ref TRIGGER_DELIVERIES: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("trigger_deliveries_total", "Number of trigger delivery attempts per trigger and outcome"),
+			&["trigger", "outcome"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
 }
 
 /// Gather all metrics and encode into the provided format.
@@ -313,6 +341,7 @@ mod tests {
 			cron_schedule: "*/15 * * * * *".to_string(),
 			max_past_blocks: Some(1000),
 			store_blocks: Some(true),
+			explorer_url: None,
 		}
 	}
 
@@ -352,6 +381,10 @@ mod tests {
 				timeout_ms: 5000,
 			}],
 			triggers: vec!["trigger1".to_string()],
+			two_phase_alerts: false,
+			alert_policy: None,
+			reports: vec![],
+			severity: None,
 		}
 	}
 
@@ -371,6 +404,9 @@ mod tests {
 				sender: "alerts@example.com".parse().unwrap(),
 				recipients: vec!["user@example.com".parse().unwrap()],
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		}
 	}
 
@@ -1,65 +1,72 @@
 //! Metrics server module
 //!
-//! This module provides an HTTP server to expose Prometheus metrics for scraping.
+//! This module provides an HTTP server to expose Prometheus metrics for scraping, along with the
+//! live stream of matches and, when configured, the management API, which includes a debug
+//! endpoint explaining how a monitor is evaluated against a transaction.
 
 use actix_web::middleware::{Compress, DefaultHeaders, NormalizePath};
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+use serde::Deserialize;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{error, info};
 
 use crate::{
+	bootstrap::explain_monitor,
 	repositories::{
-		MonitorRepository, MonitorService, NetworkRepository, NetworkService, TriggerRepository,
+		MonitorRepository, MonitorRepositoryTrait, MonitorService, NetworkRepository,
+		NetworkRepositoryTrait, NetworkService, TriggerRepository, TriggerRepositoryTrait,
 		TriggerService,
 	},
-	utils::metrics::{gather_metrics, update_monitoring_metrics, update_system_metrics},
+	services::{blockchain::ClientPool, filter::FilterService, stream::MatchStream},
+	utils::metrics::{
+		gather_metrics,
+		management::{self, ManagementState},
+		stream, update_monitoring_metrics, update_system_metrics,
+	},
 };
 
-// Type aliases to simplify complex types in function signatures
+// Type aliases to simplify complex types in function signatures. The repository types default
+// to the file-based repositories, the service uses the SQLite ones when the configuration is
+// stored in the database.
 
 //  MonitorService
-pub type MonitorServiceData = web::Data<
-	Arc<
-		Mutex<
-			MonitorService<
-				MonitorRepository<NetworkRepository, TriggerRepository>,
-				NetworkRepository,
-				TriggerRepository,
-			>,
-		>,
-	>,
->;
+pub type MonitorServiceData<
+	M = MonitorRepository<NetworkRepository, TriggerRepository>,
+	N = NetworkRepository,
+	T = TriggerRepository,
+> = web::Data<MonitorServiceArc<M, N, T>>;
 
 // NetworkService
-pub type NetworkServiceData = web::Data<Arc<Mutex<NetworkService<NetworkRepository>>>>;
+pub type NetworkServiceData<N = NetworkRepository> = web::Data<NetworkServiceArc<N>>;
 
 // TriggerService
-pub type TriggerServiceData = web::Data<Arc<Mutex<TriggerService<TriggerRepository>>>>;
+pub type TriggerServiceData<T = TriggerRepository> = web::Data<TriggerServiceArc<T>>;
 
 // For Arc<Mutex<...>> MonitorService
-pub type MonitorServiceArc = Arc<
-	Mutex<
-		MonitorService<
-			MonitorRepository<NetworkRepository, TriggerRepository>,
-			NetworkRepository,
-			TriggerRepository,
-		>,
-	>,
->;
+pub type MonitorServiceArc<
+	M = MonitorRepository<NetworkRepository, TriggerRepository>,
+	N = NetworkRepository,
+	T = TriggerRepository,
+> = Arc<Mutex<MonitorService<M, N, T>>>;
 
 // For Arc<Mutex<...>> NetworkService
-pub type NetworkServiceArc = Arc<Mutex<NetworkService<NetworkRepository>>>;
+pub type NetworkServiceArc<N = NetworkRepository> = Arc<Mutex<NetworkService<N>>>;
 
 // For Arc<Mutex<...>> TriggerService
-pub type TriggerServiceArc = Arc<Mutex<TriggerService<TriggerRepository>>>;
+pub type TriggerServiceArc<T = TriggerRepository> = Arc<Mutex<TriggerService<T>>>;
 
 /// Metrics endpoint handler
-async fn metrics_handler(
-	monitor_service: MonitorServiceData,
-	network_service: NetworkServiceData,
-	trigger_service: TriggerServiceData,
-) -> impl Responder {
+async fn metrics_handler<M, N, T>(
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	trigger_service: TriggerServiceData<T>,
+) -> impl Responder
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
 	// Update system metrics
 	update_system_metrics();
 
@@ -84,13 +91,90 @@ async fn metrics_handler(
 	}
 }
 
+/// Query parameters of the explain endpoint
+#[derive(Debug, Deserialize)]
+pub struct ExplainQuery {
+	/// Name of the monitor to explain
+	pub monitor: String,
+	/// Slug of the network to fetch the block from
+	pub network: String,
+	/// Block to evaluate
+	pub block: Option<u64>,
+	/// Transaction to evaluate, also used to find the block on EVM networks
+	pub tx: Option<String>,
+}
+
+/// Explain endpoint handler
+///
+/// Returns the evaluation traces of a monitor against a block or transaction as JSON. The
+/// endpoint is registered under the authenticated management API.
+pub(crate) async fn explain_handler<M, N, T>(
+	query: web::Query<ExplainQuery>,
+	monitor_service: MonitorServiceData<M, N, T>,
+	network_service: NetworkServiceData<N>,
+	client_pool: web::Data<Arc<ClientPool>>,
+	filter_service: web::Data<Arc<FilterService>>,
+) -> impl Responder
+where
+	M: MonitorRepositoryTrait<N, T> + 'static,
+	N: NetworkRepositoryTrait + 'static,
+	T: TriggerRepositoryTrait + 'static,
+{
+	if query.block.is_none() && query.tx.is_none() {
+		return HttpResponse::BadRequest().body("Either block or tx is required");
+	}
+
+	let Some(monitor) = monitor_service
+		.lock()
+		.await
+		.get_all()
+		.into_values()
+		.find(|m| m.name == query.monitor)
+	else {
+		return HttpResponse::NotFound().body(format!("Monitor '{}' not found", query.monitor));
+	};
+	let Some(network) = network_service
+		.lock()
+		.await
+		.get_all()
+		.into_values()
+		.find(|n| n.slug == query.network)
+	else {
+		return HttpResponse::NotFound().body(format!("Network '{}' not found", query.network));
+	};
+
+	match explain_monitor(
+		client_pool.as_ref().as_ref(),
+		filter_service.as_ref(),
+		&network,
+		&monitor,
+		query.block,
+		query.tx.as_deref(),
+	)
+	.await
+	{
+		Ok(traces) => HttpResponse::Ok().json(traces),
+		Err(e) => {
+			error!("Error explaining monitor {}: {}", query.monitor, e);
+			HttpResponse::InternalServerError().body(e.to_string())
+		}
+	}
+}
+
 // Create metrics server
-pub fn create_metrics_server(
+pub fn create_metrics_server<M, N, T>(
 	bind_address: String,
-	monitor_service: MonitorServiceArc,
-	network_service: NetworkServiceArc,
-	trigger_service: TriggerServiceArc,
-) -> std::io::Result<actix_web::dev::Server> {
+	monitor_service: MonitorServiceArc<M, N, T>,
+	network_service: NetworkServiceArc<N>,
+	trigger_service: TriggerServiceArc<T>,
+	match_stream: Option<Arc<MatchStream>>,
+	management: Option<ManagementState>,
+) -> std::io::Result<actix_web::dev::Server>
+where
+	M: MonitorRepositoryTrait<N, T> + Send + 'static,
+	N: NetworkRepositoryTrait + Send + 'static,
+	T: TriggerRepositoryTrait + Send + 'static,
+{
 	let actual_bind_address = if std::env::var("IN_DOCKER").unwrap_or_default() == "true" {
 		if let Some(port) = bind_address.split(':').nth(1) {
 			format!("0.0.0.0:{}", port)
@@ -106,6 +190,22 @@ pub fn create_metrics_server(
 		bind_address, actual_bind_address
 	);
 
+	// The match stream is authenticated with the token of the management API
+	let match_stream = match (match_stream, &management) {
+		(Some(match_stream), Some(management)) => Some((match_stream, management.token.clone())),
+		(Some(_), None) => {
+			info!("Match stream disabled, set MANAGEMENT_API_TOKEN to enable it");
+			None
+		}
+		(None, _) => None,
+	};
+
+	let client_pool = management
+		.as_ref()
+		.map(|management| management.client_pool.clone())
+		.unwrap_or_else(|| Arc::new(ClientPool::new()));
+	let filter_service = Arc::new(FilterService::new());
+
 	Ok(HttpServer::new(move || {
 		App::new()
 			.wrap(Compress::default())
@@ -114,7 +214,19 @@ pub fn create_metrics_server(
 			.app_data(web::Data::new(monitor_service.clone()))
 			.app_data(web::Data::new(network_service.clone()))
 			.app_data(web::Data::new(trigger_service.clone()))
-			.route("/metrics", web::get().to(metrics_handler))
+			.app_data(web::Data::new(client_pool.clone()))
+			.app_data(web::Data::new(filter_service.clone()))
+			.route("/metrics", web::get().to(metrics_handler::<M, N, T>))
+			.configure(|cfg| {
+				if let Some((match_stream, token)) = &match_stream {
+					stream::configure(cfg, match_stream.clone(), token.clone());
+				}
+			})
+			.configure(|cfg| {
+				if let Some(management) = &management {
+					management::configure::<M, N, T>(cfg, management.clone());
+				}
+			})
 	})
 	.workers(2)
 	.bind(actual_bind_address)?
@@ -126,11 +238,14 @@ pub fn create_metrics_server(
 mod tests {
 	use super::*;
 	use crate::repositories::{
-		MonitorService, NetworkRepository, NetworkService, TriggerRepository, TriggerService,
+		ConfigStorage, MonitorService, NetworkRepository, NetworkService, TriggerRepository,
+		TriggerService,
 	};
 	use actix_web::{test, App};
 	use tokio::net::TcpListener;
 
+	type FileMonitorRepository = MonitorRepository<NetworkRepository, TriggerRepository>;
+
 	// Helper function to create test services with mock repositories
 	fn create_test_services() -> (MonitorServiceArc, NetworkServiceArc, TriggerServiceArc) {
 		let network_service = NetworkService::<NetworkRepository>::new(None).unwrap();
@@ -160,7 +275,14 @@ mod tests {
 				.app_data(web::Data::new(monitor_service.clone()))
 				.app_data(web::Data::new(network_service.clone()))
 				.app_data(web::Data::new(trigger_service.clone()))
-				.route("/metrics", web::get().to(metrics_handler)),
+				.route(
+					"/metrics",
+					web::get().to(metrics_handler::<
+						FileMonitorRepository,
+						NetworkRepository,
+						TriggerRepository,
+					>),
+				),
 		)
 		.await;
 
@@ -190,6 +312,73 @@ mod tests {
 		assert!(body_str.contains("# HELP"));
 	}
 
+	fn create_explain_app_data() -> (web::Data<Arc<ClientPool>>, web::Data<Arc<FilterService>>) {
+		(
+			web::Data::new(Arc::new(ClientPool::new())),
+			web::Data::new(Arc::new(FilterService::new())),
+		)
+	}
+
+	#[actix_web::test]
+	async fn test_explain_handler_requires_target() {
+		let (monitor_service, network_service, _) = create_test_services();
+		let (client_pool, filter_service) = create_explain_app_data();
+
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(monitor_service))
+				.app_data(web::Data::new(network_service))
+				.app_data(client_pool)
+				.app_data(filter_service)
+				.route(
+					"/debug/explain",
+					web::get().to(explain_handler::<
+						FileMonitorRepository,
+						NetworkRepository,
+						TriggerRepository,
+					>),
+				),
+		)
+		.await;
+
+		let req = test::TestRequest::get()
+			.uri("/debug/explain?monitor=test&network=ethereum_mainnet")
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+
+		assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+	}
+
+	#[actix_web::test]
+	async fn test_explain_handler_unknown_monitor() {
+		let (monitor_service, network_service, _) = create_test_services();
+		let (client_pool, filter_service) = create_explain_app_data();
+
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(monitor_service))
+				.app_data(web::Data::new(network_service))
+				.app_data(client_pool)
+				.app_data(filter_service)
+				.route(
+					"/debug/explain",
+					web::get().to(explain_handler::<
+						FileMonitorRepository,
+						NetworkRepository,
+						TriggerRepository,
+					>),
+				),
+		)
+		.await;
+
+		let req = test::TestRequest::get()
+			.uri("/debug/explain?monitor=missing-monitor&network=ethereum_mainnet&block=1")
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+
+		assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+	}
+
 	#[tokio::test]
 	async fn test_create_metrics_server() {
 		// Create test services
@@ -208,6 +397,8 @@ mod tests {
 			monitor_service,
 			network_service,
 			trigger_service,
+			None,
+			None,
 		);
 
 		// Assert server creation is successful
@@ -244,6 +435,112 @@ mod tests {
 		server_task.abort();
 	}
 
+	#[tokio::test]
+	async fn test_match_stream_websocket() {
+		use crate::{
+			models::{
+				EVMMonitorMatch, EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor,
+				MonitorMatch,
+			},
+			services::{
+				blockwatcher::{BlockStorageBackend, FileBlockStorage},
+				report::{FileMatchHistory, MatchHistory, MatchRecord},
+				trigger::FileMatchOutbox,
+			},
+		};
+		use futures::StreamExt;
+		use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+		let (monitor_service, network_service, trigger_service) = create_test_services();
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let match_stream = Arc::new(MatchStream::new(Arc::new(FileMatchHistory::new(
+			temp_dir.path().to_path_buf(),
+		))));
+
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let bind_address = listener.local_addr().unwrap().to_string();
+		drop(listener);
+
+		let (reload_tx, _reload_rx) = tokio::sync::mpsc::channel(1);
+		let management = ManagementState {
+			token: "secret-token".to_string(),
+			storage: ConfigStorage::Files(temp_dir.path().to_path_buf()),
+			reload_tx,
+			client_pool: Arc::new(ClientPool::new()),
+			block_storage: Arc::new(BlockStorageBackend::File(FileBlockStorage::new(
+				temp_dir.path().join("data"),
+			))),
+			outbox: Arc::new(FileMatchOutbox::new(temp_dir.path().join("outbox"))),
+		};
+
+		let server = create_metrics_server(
+			bind_address.clone(),
+			monitor_service,
+			network_service,
+			trigger_service,
+			Some(match_stream.clone()),
+			Some(management),
+		)
+		.unwrap();
+		let server_task = tokio::spawn(server);
+		tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+		let url = format!("ws://{}/ws?network=ethereum_mainnet", bind_address);
+		// Subscribers must send the token of the management API
+		let error = tokio_tungstenite::connect_async(url.as_str())
+			.await
+			.unwrap_err();
+		assert!(matches!(
+			error,
+			tokio_tungstenite::tungstenite::Error::Http(response)
+				if response.status() == 401
+		));
+
+		let mut request = url.into_client_request().unwrap();
+		request
+			.headers_mut()
+			.insert("Authorization", "Bearer secret-token".parse().unwrap());
+		let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+		// Wait for the subscription before recording the match
+		while match_stream.subscriber_count() == 0 {
+			tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+		}
+
+		let monitor_match = MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: Monitor {
+				name: "transfers".to_string(),
+				..Default::default()
+			},
+			transaction: EVMTransaction::default(),
+			receipt: EVMTransactionReceipt::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}));
+		let now = chrono::Utc::now().timestamp_millis();
+		match_stream
+			.record(vec![
+				MatchRecord::new("stellar_mainnet", 1, &monitor_match, now),
+				MatchRecord::new("ethereum_mainnet", 2, &monitor_match, now),
+			])
+			.await
+			.unwrap();
+
+		let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		let event: serde_json::Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+		assert_eq!(event["network_slug"], "ethereum_mainnet");
+		assert_eq!(event["block_number"], 2);
+		assert_eq!(event["monitor_name"], "transfers");
+		assert!(event["match"]["EVM"].is_object());
+
+		server_task.abort();
+	}
+
 	#[tokio::test]
 	async fn test_docker_bind_address_handling() {
 		// Save original environment state
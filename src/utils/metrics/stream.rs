@@ -0,0 +1,340 @@
+//! Match stream endpoints
+//!
+//! This module exposes the live stream of monitor matches on the metrics server, as
+//! server-sent events on `/events/stream` and as WebSocket messages on `/ws`. Every match is
+//! sent as a JSON event, filtered by the `network`, `monitor` and `severity` query parameters.
+//! Subscribers pass the cursor of the last event they received as `cursor` query parameter, or
+//! as `Last-Event-ID` header for server-sent events, to be replayed the matches they missed.
+//!
+//! Like the management API, every request must carry the configured token as
+//! `Authorization: Bearer <token>`.
+
+use actix_web::{
+	http::header::{self, CacheControl, CacheDirective, ContentEncoding},
+	middleware::from_fn,
+	web, HttpRequest, HttpResponse, Responder,
+};
+use actix_ws::Message;
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+use tracing::error;
+
+use crate::{
+	models::Severity,
+	services::stream::{MatchEvent, MatchFilter, MatchStream, MatchSubscription},
+	utils::metrics::management::{authorize, BearerToken},
+};
+
+/// Interval of the keep-alive messages sent to idle subscribers
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Query parameters of the stream endpoints
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+	/// Slug of the network of the matches
+	pub network: Option<String>,
+	/// Name of the monitor of the matches
+	pub monitor: Option<String>,
+	/// Minimum severity of the matches
+	pub severity: Option<Severity>,
+	/// Cursor of the last event received
+	pub cursor: Option<String>,
+}
+
+/// Registers the match stream routes
+///
+/// # Arguments
+/// * `cfg` - Service configuration of the metrics server app
+/// * `stream` - Stream of the matches
+/// * `token` - Token subscribers must send as bearer token
+pub fn configure(cfg: &mut web::ServiceConfig, stream: Arc<MatchStream>, token: String) {
+	cfg.app_data(web::Data::new(stream))
+		.app_data(web::Data::new(BearerToken(token)))
+		.service(
+			web::resource("/events/stream")
+				.wrap(from_fn(authorize))
+				.route(web::get().to(events_handler)),
+		)
+		.service(
+			web::resource("/ws")
+				.wrap(from_fn(authorize))
+				.route(web::get().to(websocket_handler)),
+		);
+}
+
+/// Subscribes to the stream with the filter and cursor of a request
+async fn subscribe(
+	stream: &MatchStream,
+	query: StreamQuery,
+	cursor: Option<String>,
+) -> Result<MatchSubscription, HttpResponse> {
+	let filter = MatchFilter {
+		network: query.network,
+		monitor: query.monitor,
+		severity: query.severity,
+	};
+	stream
+		.subscribe(query.cursor.or(cursor).as_deref(), filter)
+		.await
+		.map_err(|e| {
+			error!("Failed to subscribe to the match stream: {}", e);
+			HttpResponse::BadRequest().body(e.to_string())
+		})
+}
+
+/// Formats an event as a server-sent event
+fn sse_frame(event: &MatchEvent) -> Result<web::Bytes, serde_json::Error> {
+	Ok(web::Bytes::from(format!(
+		"id: {}\nevent: match\ndata: {}\n\n",
+		event.id,
+		serde_json::to_string(event)?
+	)))
+}
+
+/// Server-sent events endpoint handler
+///
+/// Streams the matches as `match` events until the client disconnects. A comment is sent when
+/// no match was sent for a while to keep the connection open.
+async fn events_handler(
+	req: HttpRequest,
+	query: web::Query<StreamQuery>,
+	stream: web::Data<Arc<MatchStream>>,
+) -> impl Responder {
+	let last_event_id = req
+		.headers()
+		.get("Last-Event-ID")
+		.and_then(|value| value.to_str().ok())
+		.map(str::to_string);
+	let subscription = match subscribe(&stream, query.into_inner(), last_event_id).await {
+		Ok(subscription) => subscription,
+		Err(response) => return response,
+	};
+
+	let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+	keep_alive.reset();
+	let body = futures::stream::unfold(
+		(subscription, keep_alive),
+		|(mut subscription, mut keep_alive)| async move {
+			let frame = tokio::select! {
+				event = subscription.next() => sse_frame(&*event?),
+				_ = keep_alive.tick() => Ok(web::Bytes::from_static(b": keep-alive\n\n")),
+			};
+			keep_alive.reset();
+			Some((frame, (subscription, keep_alive)))
+		},
+	);
+
+	HttpResponse::Ok()
+		.content_type("text/event-stream")
+		.insert_header(CacheControl(vec![CacheDirective::NoCache]))
+		// Compressing the stream would buffer the events
+		.insert_header(ContentEncoding::Identity)
+		.insert_header((header::HeaderName::from_static("x-accel-buffering"), "no"))
+		.streaming(body)
+}
+
+/// WebSocket endpoint handler
+///
+/// Sends the matches as JSON text messages until the client disconnects. Messages received
+/// from the client are ignored, except pings and close frames.
+async fn websocket_handler(
+	req: HttpRequest,
+	body: web::Payload,
+	query: web::Query<StreamQuery>,
+	stream: web::Data<Arc<MatchStream>>,
+) -> Result<HttpResponse, actix_web::Error> {
+	let mut subscription = match subscribe(&stream, query.into_inner(), None).await {
+		Ok(subscription) => subscription,
+		Err(response) => return Ok(response),
+	};
+	let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+
+	actix_web::rt::spawn(async move {
+		let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+		keep_alive.reset();
+		loop {
+			tokio::select! {
+				event = subscription.next() => {
+					let Some(event) = event else {
+						break;
+					};
+					let sent = match serde_json::to_string(&*event) {
+						Ok(json) => session.text(json).await,
+						Err(e) => {
+							error!("Failed to serialize match event {}: {}", event.id, e);
+							continue;
+						}
+					};
+					if sent.is_err() {
+						return;
+					}
+				}
+				message = messages.recv() => match message {
+					Some(Ok(Message::Ping(bytes))) => {
+						if session.pong(&bytes).await.is_err() {
+							return;
+						}
+					}
+					Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+					Some(Ok(_)) => {}
+				},
+				_ = keep_alive.tick() => {
+					if session.ping(b"").await.is_err() {
+						return;
+					}
+				}
+			}
+		}
+		let _ = session.close(None).await;
+	});
+
+	Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{
+			EVMMonitorMatch, EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor,
+			MonitorMatch,
+		},
+		services::report::{FileMatchHistory, MatchHistory, MatchRecord},
+	};
+	use actix_web::{body::MessageBody, test, App};
+	use tempfile::TempDir;
+
+	const TOKEN: &str = "secret-token";
+	const AUTHORIZATION: (header::HeaderName, &str) =
+		(header::AUTHORIZATION, "Bearer secret-token");
+
+	fn create_record(monitor_name: &str) -> MatchRecord {
+		let monitor_match = MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: Monitor {
+				name: monitor_name.to_string(),
+				severity: Some(Severity::Error),
+				..Default::default()
+			},
+			transaction: EVMTransaction::default(),
+			receipt: EVMTransactionReceipt::default(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: None,
+			alert: None,
+			context: None,
+		}));
+		MatchRecord::new(
+			"ethereum_mainnet",
+			1,
+			&monitor_match,
+			chrono::Utc::now().timestamp_millis(),
+		)
+	}
+
+	/// Reads the next chunk of a streamed body
+	async fn next_chunk(body: &mut (impl MessageBody + Unpin)) -> String {
+		let chunk = std::future::poll_fn(|cx| std::pin::Pin::new(&mut *body).poll_next(cx))
+			.await
+			.unwrap()
+			.map_err(|_| "body error")
+			.unwrap();
+		String::from_utf8(chunk.to_vec()).unwrap()
+	}
+
+	#[actix_web::test]
+	async fn test_events_handler_replays_and_streams_matches() {
+		let temp_dir = TempDir::new().unwrap();
+		let stream = Arc::new(MatchStream::new(Arc::new(FileMatchHistory::new(
+			temp_dir.path().to_path_buf(),
+		))));
+		let records = vec![
+			create_record("first"),
+			create_record("second"),
+			create_record("other"),
+		];
+		let cursor = records[0].id.clone();
+		stream.record(records).await.unwrap();
+
+		let app = test::init_service(
+			App::new().configure(|cfg| configure(cfg, stream.clone(), TOKEN.to_string())),
+		)
+		.await;
+		let req = test::TestRequest::get()
+			.uri("/events/stream?monitor=second")
+			.insert_header(("Last-Event-ID", cursor.as_str()))
+			.insert_header(AUTHORIZATION)
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+
+		assert!(resp.status().is_success());
+		assert_eq!(
+			resp.headers().get("content-type").unwrap(),
+			"text/event-stream"
+		);
+		let mut body = Box::pin(resp.into_body());
+
+		let frame = next_chunk(&mut body).await;
+		assert!(frame.starts_with("id: "));
+		assert!(frame.contains("event: match\n"));
+		assert!(frame.contains("\"monitor_name\":\"second\""));
+
+		let live = create_record("second");
+		let live_id = live.id.clone();
+		stream
+			.record(vec![create_record("other"), live])
+			.await
+			.unwrap();
+		let frame = next_chunk(&mut body).await;
+		assert!(frame.starts_with(&format!("id: {}\n", live_id)));
+	}
+
+	#[actix_web::test]
+	async fn test_events_handler_rejects_invalid_cursor() {
+		let temp_dir = TempDir::new().unwrap();
+		let stream = Arc::new(MatchStream::new(Arc::new(FileMatchHistory::new(
+			temp_dir.path().to_path_buf(),
+		))));
+		let app = test::init_service(
+			App::new().configure(|cfg| configure(cfg, stream, TOKEN.to_string())),
+		)
+		.await;
+
+		let req = test::TestRequest::get()
+			.uri("/events/stream?cursor=invalid")
+			.insert_header(AUTHORIZATION)
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+		let req = test::TestRequest::get()
+			.uri("/events/stream?severity=unknown")
+			.insert_header(AUTHORIZATION)
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+	}
+
+	#[actix_web::test]
+	async fn test_stream_requires_token() {
+		let temp_dir = TempDir::new().unwrap();
+		let stream = Arc::new(MatchStream::new(Arc::new(FileMatchHistory::new(
+			temp_dir.path().to_path_buf(),
+		))));
+		let app = test::init_service(
+			App::new().configure(|cfg| configure(cfg, stream, TOKEN.to_string())),
+		)
+		.await;
+
+		for uri in ["/events/stream", "/ws"] {
+			let req = test::TestRequest::get().uri(uri).to_request();
+			let resp = test::call_service(&app, req).await;
+			assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+			let req = test::TestRequest::get()
+				.uri(uri)
+				.insert_header((header::AUTHORIZATION, "Bearer wrong-token"))
+				.to_request();
+			let resp = test::call_service(&app, req).await;
+			assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+		}
+	}
+}
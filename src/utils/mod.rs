@@ -9,10 +9,14 @@
 //! - logging: Logging utilities
 //! - metrics: Metrics utilities
 //! - script: Utilities for working with scripts
+//! - sqlite: Schema migrations of the SQLite databases
+//! - template: Template engine for notification messages
 
 mod cron_utils;
 mod expression;
 mod script;
+mod sqlite;
+mod template;
 
 pub mod constants;
 pub mod logging;
@@ -22,3 +26,5 @@ pub use constants::*;
 pub use cron_utils::*;
 pub use expression::*;
 pub use script::*;
+pub use sqlite::*;
+pub use template::*;
@@ -338,6 +338,8 @@ mod tests {
 				transactions: vec![],
 			},
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}))
 	}
 
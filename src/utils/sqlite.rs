@@ -0,0 +1,84 @@
+//! Schema migrations of the SQLite databases.
+
+use rusqlite::{params, Connection};
+
+/// Applies the migrations newer than the current schema version of a database
+///
+/// Migrations are `(version, sql)` pairs applied in order. Applied versions are recorded in
+/// `schema_migrations`, and each migration runs in its own transaction together with the record
+/// of its version, so an interrupted migration is retried the next time the database is opened.
+/// Released migrations must never be changed; schema changes are added as a new version
+/// instead.
+///
+/// # Arguments
+/// * `connection` - Connection to the database
+/// * `migrations` - Migrations of the database, ordered by version
+pub fn apply_migrations(
+	connection: &mut Connection,
+	migrations: &[(i64, &str)],
+) -> rusqlite::Result<()> {
+	connection.execute_batch(
+		"CREATE TABLE IF NOT EXISTS schema_migrations (
+			version INTEGER PRIMARY KEY NOT NULL,
+			applied_at INTEGER NOT NULL
+		);",
+	)?;
+	let current: i64 = connection.query_row(
+		"SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+		[],
+		|row| row.get(0),
+	)?;
+
+	for (version, sql) in migrations.iter().filter(|(v, _)| *v > current) {
+		let transaction = connection.transaction()?;
+		transaction.execute_batch(sql)?;
+		transaction.execute(
+			"INSERT INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+			params![version, chrono::Utc::now().timestamp()],
+		)?;
+		transaction.commit()?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn versions(connection: &Connection) -> Vec<i64> {
+		connection
+			.prepare("SELECT version FROM schema_migrations ORDER BY version")
+			.unwrap()
+			.query_map([], |row| row.get(0))
+			.unwrap()
+			.collect::<rusqlite::Result<_>>()
+			.unwrap()
+	}
+
+	#[test]
+	fn test_apply_migrations_once() {
+		let mut connection = Connection::open_in_memory().unwrap();
+		let migrations = [(1, "CREATE TABLE first (id INTEGER);")];
+
+		apply_migrations(&mut connection, &migrations).unwrap();
+		apply_migrations(&mut connection, &migrations).unwrap();
+		assert_eq!(versions(&connection), vec![1]);
+
+		let migrations = [
+			(1, "CREATE TABLE first (id INTEGER);"),
+			(2, "CREATE TABLE second (id INTEGER);"),
+		];
+		apply_migrations(&mut connection, &migrations).unwrap();
+		assert_eq!(versions(&connection), vec![1, 2]);
+	}
+
+	#[test]
+	fn test_failed_migration_is_rolled_back() {
+		let mut connection = Connection::open_in_memory().unwrap();
+		let migrations = [(1, "CREATE TABLE first (id INTEGER); NOT SQL;")];
+
+		assert!(apply_migrations(&mut connection, &migrations).is_err());
+		assert!(versions(&connection).is_empty());
+		assert!(connection.execute("SELECT * FROM first", []).is_err());
+	}
+}
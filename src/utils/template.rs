@@ -0,0 +1,936 @@
+//! Template engine for notification messages.
+//!
+//! Templates mix literal text with tags in double braces:
+//! - `{{ expression }}` renders an expression, e.g. `{{ transaction_value | format_units(18) }}`
+//! - `{{#if expression}}...{{else}}...{{/if}}` renders a section if the expression is truthy
+//! - `{{#each list}}...{{/each}}` renders a section for every item of a list
+//! - `{{! comment }}` is ignored
+//!
+//! Variables are flat, so the list `events` is made of the variables named
+//! `events_{index}_{field}` or, as match variables use singular names, `event_{index}_{field}`.
+//! Inside a loop, names refer to the fields of the current item first, and `@index` is the index
+//! of the item.
+//!
+//! The `${name}` placeholders of earlier templates are still substituted, and left as they are
+//! when the variable is missing.
+//!
+//! JSON templates, such as webhook payloads or Slack blocks, render every string they contain as
+//! a template.
+
+use alloy::primitives::U256;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error as ThisError;
+
+/// Error found while compiling a template
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+#[error("{message} at offset {offset}")]
+pub struct TemplateError {
+	/// Description of the error
+	pub message: String,
+	/// Byte offset in the template where the error was found
+	pub offset: usize,
+}
+
+impl TemplateError {
+	fn new(message: impl Into<String>, offset: usize) -> Self {
+		Self {
+			message: message.into(),
+			offset,
+		}
+	}
+}
+
+/// A compiled template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+	nodes: Vec<Node>,
+}
+
+/// Part of a compiled template
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+	/// Literal text
+	Text(String),
+	/// `${name}` placeholder
+	Placeholder(String),
+	/// `{{ expression }}` tag
+	Expression(Expression),
+	/// `{{#if}}` section
+	If {
+		condition: Expression,
+		then: Vec<Node>,
+		otherwise: Vec<Node>,
+	},
+	/// `{{#each}}` section
+	Each { list: String, body: Vec<Node> },
+}
+
+/// Expression of a tag
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+	/// Value of a variable, if set
+	Variable(String),
+	/// String or number literal
+	Literal(String),
+	/// Filter applied to an input, written `input | filter(args)` or `filter(input, args)`
+	Filter {
+		filter: Filter,
+		input: Box<Expression>,
+		args: Vec<Expression>,
+	},
+}
+
+/// Filters available to expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+	/// Replaces a missing or empty value, e.g. `default(value, "n/a")`
+	Default,
+	/// Converts to lowercase
+	Lowercase,
+	/// Converts to uppercase
+	Uppercase,
+	/// Shortens an address to its first 6 and last 4 characters
+	ShortAddress,
+	/// Divides an integer amount by 10^decimals, e.g. `format_units(value, 6)`
+	FormatUnits,
+	/// Encodes as a JSON string, or `null` if missing
+	Json,
+	/// Escapes HTML special characters
+	Html,
+	/// Converts a decimal or `0x` hexadecimal integer to decimal, missing if not a number
+	Number,
+	/// Converts to `true` if truthy or `false` otherwise
+	Bool,
+}
+
+impl Filter {
+	/// Returns the filter with the given name and its number of arguments besides the input
+	fn from_name(name: &str) -> Option<(Self, usize)> {
+		match name {
+			"default" => Some((Self::Default, 1)),
+			"lowercase" => Some((Self::Lowercase, 0)),
+			"uppercase" => Some((Self::Uppercase, 0)),
+			"short_address" => Some((Self::ShortAddress, 0)),
+			"format_units" => Some((Self::FormatUnits, 1)),
+			"json" => Some((Self::Json, 0)),
+			"html" => Some((Self::Html, 0)),
+			"number" => Some((Self::Number, 0)),
+			"bool" => Some((Self::Bool, 0)),
+			_ => None,
+		}
+	}
+
+	fn apply(self, value: Option<String>, args: &[Option<String>]) -> Option<String> {
+		let arg = args.first().cloned().flatten();
+		match self {
+			Self::Default => value.filter(|value| !value.is_empty()).or(arg),
+			Self::Lowercase => value.map(|value| value.to_lowercase()),
+			Self::Uppercase => value.map(|value| value.to_uppercase()),
+			Self::ShortAddress => value.map(|value| short_address(&value)),
+			Self::FormatUnits => {
+				value.map(
+					|value| match arg.and_then(|decimals| decimals.parse::<usize>().ok()) {
+						Some(decimals) => format_units(&value, decimals),
+						None => value,
+					},
+				)
+			}
+			Self::Json => Some(serde_json::to_string(&value).unwrap_or_default()),
+			Self::Html => value.map(|value| escape_html(&value)),
+			Self::Number => value.and_then(|value| to_number(&value)),
+			Self::Bool => Some(is_truthy(value.as_deref()).to_string()),
+		}
+	}
+}
+
+/// Tag or text of a template source
+enum Token<'a> {
+	Text(String),
+	Placeholder(&'a str),
+	Tag(&'a str, usize),
+}
+
+/// Nodes parsed until the end of a template or a closing tag, with the tag and its offset
+type ParsedNodes = (Vec<Node>, Option<(Closing, usize)>);
+
+/// Tags closing the sections of a template
+#[derive(Debug, PartialEq)]
+enum Closing {
+	Else,
+	EndIf,
+	EndEach,
+}
+
+/// Item of a `{{#each}}` section being rendered
+struct Scope {
+	/// Prefix of the variables of the item
+	prefix: String,
+	/// Index of the item in its list
+	index: usize,
+}
+
+impl Template {
+	/// Compiles a template
+	///
+	/// # Arguments
+	/// * `source` - Source of the template
+	///
+	/// # Returns
+	/// * `Result<Template, TemplateError>` - The compiled template, or an error for unclosed or
+	///   unbalanced tags, invalid expressions and unknown filters
+	pub fn compile(source: &str) -> Result<Self, TemplateError> {
+		let tokens = tokenize(source)?;
+		let mut tokens = tokens.into_iter();
+		let (nodes, closing) = parse_nodes(&mut tokens)?;
+		match closing {
+			None => Ok(Self { nodes }),
+			Some((closing, offset)) => Err(TemplateError::new(
+				format!("Unexpected {}", closing.tag()),
+				offset,
+			)),
+		}
+	}
+
+	/// Renders the template with the given variables
+	///
+	/// Expressions referring to missing variables render as empty strings, unless a `default`
+	/// is given.
+	///
+	/// # Arguments
+	/// * `variables` - Variables available to the template
+	pub fn render(&self, variables: &HashMap<String, String>) -> String {
+		let mut output = String::new();
+		let mut scopes = vec![Scope {
+			prefix: String::new(),
+			index: 0,
+		}];
+		render_nodes(&self.nodes, variables, &mut scopes, &mut output);
+		output
+	}
+}
+
+/// Renders a template source with the given variables
+///
+/// Sources that do not compile, which configuration validation rejects, only have their
+/// `${name}` placeholders substituted.
+///
+/// # Arguments
+/// * `source` - Source of the template
+/// * `variables` - Variables available to the template
+pub fn render_template(source: &str, variables: &HashMap<String, String>) -> String {
+	match Template::compile(source) {
+		Ok(template) => template.render(variables),
+		Err(e) => {
+			tracing::warn!("Rendering invalid template as plain text: {}", e);
+			variables
+				.iter()
+				.fold(source.to_string(), |message, (key, value)| {
+					message.replace(&format!("${{{}}}", key), value)
+				})
+		}
+	}
+}
+
+/// Renders the strings of a JSON template
+///
+/// A string made only of an expression ending with the `number` or `bool` filter is converted
+/// to a JSON number or boolean, or `null` if the value is missing.
+pub fn render_json_template(template: &Value, variables: &HashMap<String, String>) -> Value {
+	match template {
+		Value::String(source) => {
+			let rendered = render_template(source, variables);
+			match typed_filter(source) {
+				Some("number") => serde_json::from_str::<serde_json::Number>(&rendered)
+					.map(Value::Number)
+					.unwrap_or(Value::Null),
+				Some("bool") => match rendered.as_str() {
+					"true" => Value::Bool(true),
+					"false" => Value::Bool(false),
+					_ => Value::Null,
+				},
+				_ => Value::String(rendered),
+			}
+		}
+		Value::Array(items) => Value::Array(
+			items
+				.iter()
+				.map(|item| render_json_template(item, variables))
+				.collect(),
+		),
+		Value::Object(fields) => Value::Object(
+			fields
+				.iter()
+				.map(|(name, value)| (name.clone(), render_json_template(value, variables)))
+				.collect(),
+		),
+		value => value.clone(),
+	}
+}
+
+/// Returns the last filter of a string made only of an expression tag
+fn typed_filter(source: &str) -> Option<&str> {
+	let expression = source.trim().strip_prefix("{{")?.strip_suffix("}}")?.trim();
+	if expression.starts_with(['#', '/', '!']) || expression.contains("{{") {
+		return None;
+	}
+	match expression.rsplit_once('|') {
+		Some((_, filter)) => Some(filter.trim()),
+		None => expression
+			.split_once('(')
+			.filter(|_| expression.ends_with(')'))
+			.map(|(filter, _)| filter.trim()),
+	}
+}
+
+/// Compiles every string of a JSON template
+///
+/// # Returns
+/// * `Result<(), TemplateError>` - The error of the first string failing to compile
+pub fn compile_json_template(template: &Value) -> Result<(), TemplateError> {
+	match template {
+		Value::String(source) => Template::compile(source).map(|_| ()),
+		Value::Array(items) => items.iter().try_for_each(compile_json_template),
+		Value::Object(fields) => fields.values().try_for_each(compile_json_template),
+		_ => Ok(()),
+	}
+}
+
+impl Closing {
+	fn tag(&self) -> &'static str {
+		match self {
+			Self::Else => "{{else}}",
+			Self::EndIf => "{{/if}}",
+			Self::EndEach => "{{/each}}",
+		}
+	}
+}
+
+/// Splits a template source into text, placeholders and tags
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, TemplateError> {
+	let mut tokens = Vec::new();
+	let mut text = String::new();
+	let mut position = 0;
+	while let Some(ch) = source[position..].chars().next() {
+		let rest = &source[position..];
+		if rest.starts_with("{{") {
+			let end = rest
+				.find("}}")
+				.ok_or_else(|| TemplateError::new("Unclosed tag", position))?;
+			if !text.is_empty() {
+				tokens.push(Token::Text(std::mem::take(&mut text)));
+			}
+			tokens.push(Token::Tag(&rest[2..end], position + 2));
+			position += end + 2;
+			continue;
+		}
+		if rest.starts_with("${") {
+			if let Some(end) = rest.find('}') {
+				let name = &rest[2..end];
+				if is_identifier(name) {
+					if !text.is_empty() {
+						tokens.push(Token::Text(std::mem::take(&mut text)));
+					}
+					tokens.push(Token::Placeholder(name));
+					position += end + 1;
+					continue;
+				}
+			}
+		}
+		text.push(ch);
+		position += ch.len_utf8();
+	}
+	if !text.is_empty() {
+		tokens.push(Token::Text(text));
+	}
+	Ok(tokens)
+}
+
+/// Parses nodes until the end of the template or a closing tag, which is returned
+fn parse_nodes(tokens: &mut std::vec::IntoIter<Token<'_>>) -> Result<ParsedNodes, TemplateError> {
+	let mut nodes = Vec::new();
+	while let Some(token) = tokens.next() {
+		let (content, offset) = match token {
+			Token::Text(text) => {
+				nodes.push(Node::Text(text));
+				continue;
+			}
+			Token::Placeholder(name) => {
+				nodes.push(Node::Placeholder(name.to_string()));
+				continue;
+			}
+			Token::Tag(content, offset) => {
+				let trimmed = content.trim_start();
+				(trimmed.trim_end(), offset + content.len() - trimmed.len())
+			}
+		};
+
+		if content.starts_with('!') {
+			continue;
+		}
+		match content {
+			"else" => return Ok((nodes, Some((Closing::Else, offset)))),
+			"/if" => return Ok((nodes, Some((Closing::EndIf, offset)))),
+			"/each" => return Ok((nodes, Some((Closing::EndEach, offset)))),
+			_ => {}
+		}
+
+		if let Some(condition) = content.strip_prefix("#if ") {
+			let condition = parse_expression(condition, offset + "#if ".len())?;
+			let (then, closing) = parse_nodes(tokens)?;
+			let otherwise = match closing {
+				Some((Closing::EndIf, _)) => Vec::new(),
+				Some((Closing::Else, _)) => match parse_nodes(tokens)? {
+					(otherwise, Some((Closing::EndIf, _))) => otherwise,
+					_ => return Err(TemplateError::new("Unclosed {{#if}}", offset)),
+				},
+				_ => return Err(TemplateError::new("Unclosed {{#if}}", offset)),
+			};
+			nodes.push(Node::If {
+				condition,
+				then,
+				otherwise,
+			});
+		} else if let Some(list) = content.strip_prefix("#each ") {
+			let list = list.trim();
+			if !is_identifier(list) {
+				return Err(TemplateError::new(
+					format!("Invalid list name '{}'", list),
+					offset,
+				));
+			}
+			match parse_nodes(tokens)? {
+				(body, Some((Closing::EndEach, _))) => nodes.push(Node::Each {
+					list: list.to_string(),
+					body,
+				}),
+				_ => return Err(TemplateError::new("Unclosed {{#each}}", offset)),
+			}
+		} else if content.starts_with('#') || content.starts_with('/') {
+			return Err(TemplateError::new(
+				format!("Unknown block '{}'", content),
+				offset,
+			));
+		} else {
+			nodes.push(Node::Expression(parse_expression(content, offset)?));
+		}
+	}
+	Ok((nodes, None))
+}
+
+/// Parses the expression of a tag
+fn parse_expression(source: &str, offset: usize) -> Result<Expression, TemplateError> {
+	let mut parser = ExpressionParser {
+		source,
+		position: 0,
+		offset,
+	};
+	let expression = parser.parse_pipeline()?;
+	parser.skip_whitespace();
+	if parser.position < source.len() {
+		return Err(parser.error(format!(
+			"Unexpected '{}'",
+			source[parser.position..].trim_end()
+		)));
+	}
+	Ok(expression)
+}
+
+/// Recursive descent parser of tag expressions
+struct ExpressionParser<'a> {
+	source: &'a str,
+	position: usize,
+	/// Offset of the expression in the template, for errors
+	offset: usize,
+}
+
+impl ExpressionParser<'_> {
+	fn error(&self, message: impl Into<String>) -> TemplateError {
+		TemplateError::new(message, self.offset + self.position)
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.source[self.position..].chars().next()
+	}
+
+	fn skip_whitespace(&mut self) {
+		while self.peek().is_some_and(char::is_whitespace) {
+			self.position += 1;
+		}
+	}
+
+	fn eat(&mut self, expected: char) -> bool {
+		self.skip_whitespace();
+		if self.peek() == Some(expected) {
+			self.position += expected.len_utf8();
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Parses `primary (| filter)*`
+	fn parse_pipeline(&mut self) -> Result<Expression, TemplateError> {
+		let mut expression = self.parse_primary()?;
+		while self.eat('|') {
+			self.skip_whitespace();
+			let name = self.parse_identifier()?;
+			let args = if self.eat('(') {
+				self.parse_args()?
+			} else {
+				Vec::new()
+			};
+			expression = self.filter(&name, expression, args)?;
+		}
+		Ok(expression)
+	}
+
+	/// Parses a literal, a variable or a filter call
+	fn parse_primary(&mut self) -> Result<Expression, TemplateError> {
+		self.skip_whitespace();
+		match self.peek() {
+			Some(quote @ ('"' | '\'')) => self.parse_string(quote),
+			Some(ch) if ch.is_ascii_digit() || ch == '-' => {
+				let start = self.position;
+				self.position += 1;
+				while self
+					.peek()
+					.is_some_and(|ch| ch.is_ascii_digit() || ch == '.')
+				{
+					self.position += 1;
+				}
+				Ok(Expression::Literal(
+					self.source[start..self.position].to_string(),
+				))
+			}
+			Some(_) => {
+				let name = self.parse_identifier()?;
+				if self.eat('(') {
+					let mut args = self.parse_args()?;
+					if args.is_empty() {
+						return Err(self.error(format!("Filter '{}' needs an input", name)));
+					}
+					let input = args.remove(0);
+					self.filter(&name, input, args)
+				} else {
+					Ok(Expression::Variable(name))
+				}
+			}
+			None => Err(self.error("Expected an expression")),
+		}
+	}
+
+	fn parse_identifier(&mut self) -> Result<String, TemplateError> {
+		let start = self.position;
+		while self
+			.peek()
+			.is_some_and(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '@')
+		{
+			self.position += 1;
+		}
+		let name = &self.source[start..self.position];
+		if is_identifier(name) || name == "@index" {
+			Ok(name.to_string())
+		} else {
+			self.position = start;
+			Err(self.error("Expected a name"))
+		}
+	}
+
+	fn parse_string(&mut self, quote: char) -> Result<Expression, TemplateError> {
+		let start = self.position;
+		self.position += 1;
+		let mut value = String::new();
+		while let Some(ch) = self.peek() {
+			self.position += ch.len_utf8();
+			match ch {
+				'\\' => {
+					if let Some(escaped) = self.peek() {
+						self.position += escaped.len_utf8();
+						value.push(match escaped {
+							'n' => '\n',
+							't' => '\t',
+							other => other,
+						});
+					}
+				}
+				ch if ch == quote => return Ok(Expression::Literal(value)),
+				ch => value.push(ch),
+			}
+		}
+		self.position = start;
+		Err(self.error("Unclosed string"))
+	}
+
+	/// Parses the arguments of a call after its opening parenthesis
+	fn parse_args(&mut self) -> Result<Vec<Expression>, TemplateError> {
+		let mut args = Vec::new();
+		if self.eat(')') {
+			return Ok(args);
+		}
+		loop {
+			args.push(self.parse_pipeline()?);
+			if self.eat(')') {
+				return Ok(args);
+			}
+			if !self.eat(',') {
+				return Err(self.error("Expected ',' or ')'"));
+			}
+		}
+	}
+
+	fn filter(
+		&self,
+		name: &str,
+		input: Expression,
+		args: Vec<Expression>,
+	) -> Result<Expression, TemplateError> {
+		let (filter, arity) = Filter::from_name(name)
+			.ok_or_else(|| self.error(format!("Unknown filter '{}'", name)))?;
+		if args.len() != arity {
+			return Err(self.error(format!(
+				"Filter '{}' takes {} argument(s) besides its input",
+				name, arity
+			)));
+		}
+		if let (Filter::FormatUnits, Some(Expression::Literal(decimals))) = (filter, args.first()) {
+			if decimals.parse::<usize>().is_err() {
+				return Err(self.error(format!("Invalid number of decimals '{}'", decimals)));
+			}
+		}
+		Ok(Expression::Filter {
+			filter,
+			input: Box::new(input),
+			args,
+		})
+	}
+}
+
+fn render_nodes(
+	nodes: &[Node],
+	variables: &HashMap<String, String>,
+	scopes: &mut Vec<Scope>,
+	output: &mut String,
+) {
+	for node in nodes {
+		match node {
+			Node::Text(text) => output.push_str(text),
+			Node::Placeholder(name) => match lookup(name, variables, scopes) {
+				Some(value) => output.push_str(&value),
+				None => output.push_str(&format!("${{{}}}", name)),
+			},
+			Node::Expression(expression) => {
+				output.push_str(&evaluate(expression, variables, scopes).unwrap_or_default())
+			}
+			Node::If {
+				condition,
+				then,
+				otherwise,
+			} => {
+				let truthy = is_truthy(evaluate(condition, variables, scopes).as_deref());
+				let section = if truthy { then } else { otherwise };
+				render_nodes(section, variables, scopes, output);
+			}
+			Node::Each { list, body } => {
+				let Some((prefix, indices)) = list_items(list, variables, scopes) else {
+					continue;
+				};
+				for index in indices {
+					scopes.push(Scope {
+						prefix: format!("{}{}_", prefix, index),
+						index,
+					});
+					render_nodes(body, variables, scopes, output);
+					scopes.pop();
+				}
+			}
+		}
+	}
+}
+
+fn evaluate(
+	expression: &Expression,
+	variables: &HashMap<String, String>,
+	scopes: &[Scope],
+) -> Option<String> {
+	match expression {
+		Expression::Variable(name) => lookup(name, variables, scopes),
+		Expression::Literal(value) => Some(value.clone()),
+		Expression::Filter {
+			filter,
+			input,
+			args,
+		} => {
+			let args: Vec<Option<String>> = args
+				.iter()
+				.map(|arg| evaluate(arg, variables, scopes))
+				.collect();
+			filter.apply(evaluate(input, variables, scopes), &args)
+		}
+	}
+}
+
+/// Looks a variable up in the items being rendered, innermost first, then in the variables
+fn lookup(name: &str, variables: &HashMap<String, String>, scopes: &[Scope]) -> Option<String> {
+	if name == "@index" {
+		return scopes.last().map(|scope| scope.index.to_string());
+	}
+	scopes
+		.iter()
+		.rev()
+		.find_map(|scope| variables.get(&format!("{}{}", scope.prefix, name)))
+		.cloned()
+}
+
+/// Finds the prefix and item indices of a list, innermost scope first
+///
+/// A list named with a trailing `s` also matches the variables of its singular name.
+fn list_items(
+	list: &str,
+	variables: &HashMap<String, String>,
+	scopes: &[Scope],
+) -> Option<(String, BTreeSet<usize>)> {
+	let names = [Some(list), list.strip_suffix('s')];
+	scopes.iter().rev().find_map(|scope| {
+		names.iter().flatten().find_map(|name| {
+			let prefix = format!("{}{}_", scope.prefix, name);
+			let indices: BTreeSet<usize> = variables
+				.keys()
+				.filter_map(|key| key.strip_prefix(&prefix))
+				.filter_map(|rest| rest.split_once('_'))
+				.filter_map(|(index, _)| index.parse().ok())
+				.collect();
+			(!indices.is_empty()).then_some((prefix, indices))
+		})
+	})
+}
+
+fn is_identifier(name: &str) -> bool {
+	name.chars()
+		.next()
+		.is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+		&& name
+			.chars()
+			.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Shortens an address to its first 6 and last 4 characters, e.g. `0x1234...abcd`
+fn short_address(address: &str) -> String {
+	let chars: Vec<char> = address.chars().collect();
+	if chars.len() <= 12 {
+		return address.to_string();
+	}
+	format!(
+		"{}...{}",
+		chars[..6].iter().collect::<String>(),
+		chars[chars.len() - 4..].iter().collect::<String>()
+	)
+}
+
+/// Formats an integer amount of base units with the given number of decimals
+///
+/// Values that are not integers are returned unchanged.
+fn format_units(value: &str, decimals: usize) -> String {
+	let (sign, digits) = match value.strip_prefix('-') {
+		Some(digits) => ("-", digits),
+		None => ("", value),
+	};
+	if digits.is_empty() || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+		return value.to_string();
+	}
+
+	let digits = format!("{:0>width$}", digits, width = decimals + 1);
+	let (integer, fraction) = digits.split_at(digits.len() - decimals);
+	let integer = match integer.trim_start_matches('0') {
+		"" => "0",
+		integer => integer,
+	};
+	let fraction = fraction.trim_end_matches('0');
+	if fraction.is_empty() {
+		format!("{}{}", sign, integer)
+	} else {
+		format!("{}{}.{}", sign, integer, fraction)
+	}
+}
+
+/// Returns whether a value is set and neither empty, `false` nor `0`
+fn is_truthy(value: Option<&str>) -> bool {
+	value.is_some_and(|value| !matches!(value, "" | "false" | "0"))
+}
+
+/// Converts a decimal or `0x` hexadecimal number to its decimal representation
+fn to_number(value: &str) -> Option<String> {
+	let value = value.trim();
+	if let Some(hex) = value.strip_prefix("0x") {
+		return U256::from_str_radix(hex, 16)
+			.ok()
+			.map(|number| number.to_string());
+	}
+	value
+		.parse::<f64>()
+		.ok()
+		.filter(|number| number.is_finite())
+		.map(|_| value.to_string())
+}
+
+fn escape_html(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn variables(entries: &[(&str, &str)]) -> HashMap<String, String> {
+		entries
+			.iter()
+			.map(|(key, value)| (key.to_string(), value.to_string()))
+			.collect()
+	}
+
+	fn render(source: &str, entries: &[(&str, &str)]) -> String {
+		Template::compile(source)
+			.unwrap()
+			.render(&variables(entries))
+	}
+
+	#[test]
+	fn test_render_expressions() {
+		let entries = [
+			("from", "0x2E8135BE71230C6B1B4045696D41C09DB0414226"),
+			("value", "1500000"),
+			("name", "<Transfer>"),
+		];
+		assert_eq!(
+			render("{{ from | short_address | lowercase }}", &entries),
+			"0x2e81...4226"
+		);
+		assert_eq!(render("{{ format_units(value, 6) }}", &entries), "1.5");
+		assert_eq!(render("{{ value | format_units(8) }}", &entries), "0.015");
+		assert_eq!(render("{{ value|format_units(0) }}", &entries), "1500000");
+		assert_eq!(render("{{ uppercase(\"a\") }}", &entries), "A");
+		assert_eq!(render("{{ name | html }}", &entries), "&lt;Transfer&gt;");
+		assert_eq!(
+			render(
+				"{\"name\": {{ name | json }}, \"to\": {{ to | json }}}",
+				&entries
+			),
+			"{\"name\": \"<Transfer>\", \"to\": null}"
+		);
+		// Missing variables render as empty strings unless a default is given
+		assert_eq!(render("[{{ missing }}]", &entries), "[]");
+		assert_eq!(render("{{ default(missing, \"n/a\") }}", &entries), "n/a");
+		assert_eq!(
+			render("{{ value | default(\"n/a\") }}", &entries),
+			"1500000"
+		);
+		// Placeholders of earlier templates keep their behavior
+		assert_eq!(
+			render("${value} ${missing} ${#matches}", &entries),
+			"1500000 ${missing} ${#matches}"
+		);
+		assert_eq!(render("{{! a comment }}x", &entries), "x");
+		assert_eq!(render("{{ value | number }}", &entries), "1500000");
+		assert_eq!(render("{{ \"0x1f\" | number }}", &entries), "31");
+		assert_eq!(render("[{{ name | number }}]", &entries), "[]");
+		assert_eq!(
+			render("{{ value | bool }} {{ missing | bool }}", &entries),
+			"true false"
+		);
+	}
+
+	#[test]
+	fn test_render_sections() {
+		let entries = [
+			("event_0_signature", "Transfer(address,address,uint256)"),
+			("event_0_value", "1"),
+			("event_1_signature", "Approval(address,address,uint256)"),
+			("event_1_value", "2"),
+			("monitor_name", "transfers"),
+			("flag", "false"),
+		];
+		assert_eq!(
+			render(
+				"{{#each events}}{{@index}}: {{signature}} {{value}} ({{monitor_name}})\n{{/each}}",
+				&entries
+			),
+			"0: Transfer(address,address,uint256) 1 (transfers)\n\
+			 1: Approval(address,address,uint256) 2 (transfers)\n"
+		);
+		assert_eq!(render("{{#each functions}}x{{/each}}", &entries), "");
+		assert_eq!(
+			render("{{#if monitor_name}}yes{{else}}no{{/if}}", &entries),
+			"yes"
+		);
+		assert_eq!(render("{{#if flag}}yes{{else}}no{{/if}}", &entries), "no");
+		assert_eq!(render("{{#if missing}}yes{{/if}}", &entries), "");
+
+		// Lists nested in the items of a digest
+		let entries = [
+			("matches_0_transaction_hash", "0x1"),
+			("matches_0_event_0_value", "10"),
+			("matches_0_event_1_value", "20"),
+			("matches_1_transaction_hash", "0x2"),
+		];
+		assert_eq!(
+			render(
+				"{{#each matches}}{{transaction_hash}}:{{#each events}} {{value}}{{/each}};{{/each}}",
+				&entries
+			),
+			"0x1: 10 20;0x2:;"
+		);
+	}
+
+	#[test]
+	fn test_compile_errors() {
+		for (source, message) in [
+			("{{ value", "Unclosed tag"),
+			("{{#if value}}x", "Unclosed {{#if}}"),
+			("{{#each events}}x{{/if}}", "Unclosed {{#each}}"),
+			("x{{/each}}", "Unexpected {{/each}}"),
+			(
+				"{{#unless value}}{{/unless}}",
+				"Unknown block '#unless value'",
+			),
+			("{{ value | money }}", "Unknown filter 'money'"),
+			(
+				"{{ format_units(value) }}",
+				"Filter 'format_units' takes 1 argument(s)",
+			),
+			(
+				"{{ format_units(value, \"x\") }}",
+				"Invalid number of decimals 'x'",
+			),
+			("{{ default(value, \"n/a) }}", "Unclosed string"),
+			("{{ value other }}", "Unexpected 'other'"),
+		] {
+			let error = Template::compile(source).unwrap_err();
+			assert!(error.message.starts_with(message), "{}: {}", source, error);
+		}
+		assert_eq!(Template::compile("ab{{ | }}").unwrap_err().offset, 5);
+	}
+
+	#[test]
+	fn test_typed_filter() {
+		assert_eq!(typed_filter("{{ value | number }}"), Some("number"));
+		assert_eq!(typed_filter(" {{ bool(flag) }} "), Some("bool"));
+		assert_eq!(typed_filter("{{ value }}"), None);
+		assert_eq!(typed_filter("x {{ value | number }}"), None);
+		assert_eq!(typed_filter("{{#if a}}{{ b | number }}{{/if}}"), None);
+	}
+
+	#[test]
+	fn test_format_units() {
+		assert_eq!(format_units("88248701", 6), "88.248701");
+		assert_eq!(format_units("1000000", 6), "1");
+		assert_eq!(format_units("5", 3), "0.005");
+		assert_eq!(format_units("-2500", 3), "-2.5");
+		assert_eq!(format_units("0", 18), "0");
+		assert_eq!(format_units("0x10", 2), "0x10");
+	}
+}
@@ -33,6 +33,7 @@ mod integration {
 	mod mocks;
 
 	mod blockwatcher {
+		mod backfill;
 		mod service;
 	}
 	mod filters {
@@ -47,8 +48,14 @@ mod integration {
 	mod notifications {
 		mod discord;
 		mod email;
+		mod file;
+		mod kafka;
+		mod mattermost;
+		mod opsgenie;
+		mod pagerduty;
 		mod script;
 		mod slack;
+		mod teams;
 		mod telegram;
 		mod webhook;
 	}
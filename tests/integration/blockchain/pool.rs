@@ -123,6 +123,7 @@ async fn test_different_evm_networks_get_different_clients() {
 		cron_schedule: "*/5 * * * * *".to_string(),
 		confirmation_blocks: 1,
 		store_blocks: Some(false),
+		explorer_url: None,
 		chain_id: None,
 		network_passphrase: None,
 		block_time_ms: 5000,
@@ -167,6 +168,7 @@ async fn test_different_stellar_networks_get_different_clients() {
 		cron_schedule: "*/5 * * * * *".to_string(),
 		confirmation_blocks: 1,
 		store_blocks: Some(false),
+		explorer_url: None,
 		chain_id: None,
 		network_passphrase: None,
 		block_time_ms: 5000,
@@ -336,3 +338,32 @@ async fn test_get_stellar_client_handles_errors() {
 	);
 	mock.assert();
 }
+
+#[tokio::test]
+async fn test_remove_clients_recreates_client() {
+	let mut mock_server = mockito::Server::new_async().await;
+	let mock = mock_server
+		.mock("POST", "/")
+		.match_body(r#"{"method":"net_version","id":0,"jsonrpc":"2.0"}"#)
+		.with_header("content-type", "application/json")
+		.with_status(200)
+		.with_body(r#"{"jsonrpc":"2.0","id":0,"result":"1"}"#)
+		.expect(2)
+		.create();
+	let pool = ClientPool::new();
+	let network = create_evm_test_network_with_urls(vec![&mock_server.url()]);
+
+	let client1 = pool.get_evm_client(&network).await.unwrap();
+	pool.remove_clients(&network.slug).await;
+	assert_eq!(
+		pool.get_client_count::<EvmClient<AlloyTransportClient>>(BlockChainType::EVM)
+			.await,
+		0
+	);
+
+	// The next request should create a new client
+	let client2 = pool.get_evm_client(&network).await.unwrap();
+	assert!(!Arc::ptr_eq(&client1, &client2));
+
+	mock.assert();
+}
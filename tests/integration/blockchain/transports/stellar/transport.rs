@@ -384,3 +384,55 @@ async fn test_get_blocks_invalid_sequence_range() {
 		err
 	);
 }
+
+#[tokio::test]
+async fn test_get_transaction_ledger() {
+	let mut mock_stellar = MockStellarTransportClient::new();
+
+	mock_stellar
+		.expect_send_raw_request()
+		.with(
+			predicate::eq("getTransaction"),
+			predicate::function(|params: &Option<Value>| {
+				params.as_ref().unwrap() == &json!({ "hash": "abc" })
+			}),
+		)
+		.times(1)
+		.returning(|_, _| {
+			Ok(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": {
+					"status": "SUCCESS",
+					"ledger": 1234,
+					"createdAt": "1735440610"
+				}
+			}))
+		});
+	mock_stellar
+		.expect_send_raw_request()
+		.with(
+			predicate::eq("getTransaction"),
+			predicate::function(|params: &Option<Value>| {
+				params.as_ref().unwrap() == &json!({ "hash": "def" })
+			}),
+		)
+		.times(1)
+		.returning(|_, _| {
+			Ok(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"result": {
+					"status": "NOT_FOUND",
+					"latestLedger": 1300
+				}
+			}))
+		});
+
+	let client = StellarClient::new_with_transport(mock_stellar);
+	assert_eq!(
+		client.get_transaction_ledger("0xabc").await.unwrap(),
+		Some(1234)
+	);
+	assert_eq!(client.get_transaction_ledger("def").await.unwrap(), None);
+}
@@ -0,0 +1,208 @@
+use futures::future::BoxFuture;
+use mockall::predicate;
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use crate::integration::mocks::{
+	create_test_block, create_test_network, MockAlloyTransportClient, MockEvmClientTrait,
+};
+use openzeppelin_monitor::{
+	models::{BlockChainType, BlockType, Network, ProcessedBlock},
+	services::{
+		blockwatcher::{run_backfill, BackfillOptions},
+		trigger::TriggerError,
+	},
+};
+
+fn create_options(
+	progress_dir: &std::path::Path,
+	from_block: u64,
+	to_block: u64,
+) -> BackfillOptions {
+	BackfillOptions {
+		from_block,
+		to_block,
+		chunk_size: 2,
+		chunk_delay: Duration::ZERO,
+		max_attempts: 2,
+		progress_dir: progress_dir.to_path_buf(),
+	}
+}
+
+fn create_block_handler(
+) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync> {
+	Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	})
+}
+
+fn create_trigger_handler(
+	triggered: Arc<Mutex<Vec<u64>>>,
+) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<Result<(), TriggerError>> + Send + Sync>
+{
+	Arc::new(move |block: &ProcessedBlock| {
+		triggered.lock().unwrap().push(block.block_number);
+		tokio::spawn(async { Ok(()) })
+	})
+}
+
+fn expect_chunk(
+	rpc_client: &mut MockEvmClientTrait<MockAlloyTransportClient>,
+	start_block: u64,
+	end_block: u64,
+) {
+	rpc_client
+		.expect_get_blocks()
+		.with(predicate::eq(start_block), predicate::eq(Some(end_block)))
+		.returning(move |start, end| {
+			Ok((start..=end.unwrap())
+				// Blocks may arrive out of order and are processed in order
+				.rev()
+				.map(|number| create_test_block(BlockChainType::EVM, number))
+				.collect())
+		})
+		.times(1);
+}
+
+#[tokio::test]
+async fn test_run_backfill_in_chunks() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	let options = create_options(temp_dir.path(), 100, 104);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(200))
+		.times(1);
+	expect_chunk(&mut rpc_client, 100, 101);
+	expect_chunk(&mut rpc_client, 102, 103);
+	expect_chunk(&mut rpc_client, 104, 104);
+
+	let triggered = Arc::new(Mutex::new(Vec::new()));
+	let summary = run_backfill(
+		&network,
+		&rpc_client,
+		create_block_handler(),
+		create_trigger_handler(triggered.clone()),
+		&options,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(summary.start_block, 100);
+	assert_eq!(summary.blocks_processed, 5);
+	assert_eq!(*triggered.lock().unwrap(), vec![100, 101, 102, 103, 104]);
+	// Progress is cleared once the range is complete
+	assert!(!options.progress_path(&network.slug).exists());
+}
+
+#[tokio::test]
+async fn test_run_backfill_resumes_from_progress() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	let options = create_options(temp_dir.path(), 100, 104);
+	std::fs::write(options.progress_path(&network.slug), "101").unwrap();
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(200))
+		.times(1);
+	expect_chunk(&mut rpc_client, 102, 103);
+	expect_chunk(&mut rpc_client, 104, 104);
+
+	let triggered = Arc::new(Mutex::new(Vec::new()));
+	let summary = run_backfill(
+		&network,
+		&rpc_client,
+		create_block_handler(),
+		create_trigger_handler(triggered.clone()),
+		&options,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(summary.start_block, 102);
+	assert_eq!(*triggered.lock().unwrap(), vec![102, 103, 104]);
+}
+
+#[tokio::test]
+async fn test_run_backfill_keeps_progress_on_failure() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	let options = create_options(temp_dir.path(), 100, 103);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(200))
+		.times(1);
+	expect_chunk(&mut rpc_client, 100, 101);
+	rpc_client
+		.expect_get_blocks()
+		.with(predicate::eq(102), predicate::eq(Some(103)))
+		.returning(|_, _| Err(anyhow::anyhow!("RPC error")))
+		.times(2);
+
+	let triggered = Arc::new(Mutex::new(Vec::new()));
+	let result = run_backfill(
+		&network,
+		&rpc_client,
+		create_block_handler(),
+		create_trigger_handler(triggered.clone()),
+		&options,
+	)
+	.await;
+
+	assert!(result.is_err());
+	let progress = std::fs::read_to_string(options.progress_path(&network.slug)).unwrap();
+	assert_eq!(progress, "101");
+}
+
+#[tokio::test]
+async fn test_run_backfill_invalid_range() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(200));
+
+	// From block after to block
+	let result = run_backfill(
+		&network,
+		&rpc_client,
+		create_block_handler(),
+		create_trigger_handler(Arc::new(Mutex::new(Vec::new()))),
+		&create_options(temp_dir.path(), 110, 100),
+	)
+	.await;
+	assert!(result
+		.unwrap_err()
+		.to_string()
+		.contains("Invalid backfill range"));
+
+	// Range past the latest confirmed block
+	let result = run_backfill(
+		&network,
+		&rpc_client,
+		create_block_handler(),
+		create_trigger_handler(Arc::new(Mutex::new(Vec::new()))),
+		&create_options(temp_dir.path(), 100, 200),
+	)
+	.await;
+	assert!(result
+		.unwrap_err()
+		.to_string()
+		.contains("past the latest confirmed block"));
+}
@@ -9,9 +9,13 @@ use crate::integration::mocks::{
 };
 use openzeppelin_monitor::{
 	models::{BlockChainType, BlockType, Network, ProcessedBlock},
-	services::blockwatcher::{
-		process_new_blocks, BlockTracker, BlockTrackerTrait, BlockWatcherError,
-		BlockWatcherService, NetworkBlockWatcher,
+	services::{
+		blockwatcher::{
+			process_head_blocks, process_missed_blocks, process_new_blocks, BlockTracker,
+			BlockTrackerTrait, BlockWatcherError, BlockWatcherService, NetworkBlockWatcher,
+			PendingAlertTracker, PendingBlockHandler,
+		},
+		trigger::TriggerError,
 	},
 	utils::get_cron_interval_ms,
 };
@@ -168,7 +172,7 @@ async fn test_normal_block_range() {
 	});
 
 	// Create trigger handler that spawns an empty task
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let block_tracker_arc = Arc::new(block_tracker);
 
@@ -214,9 +218,8 @@ async fn test_fresh_start_processing() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_processed_block: &ProcessedBlock| {
-		tokio::spawn(async move { /* Handle trigger */ })
-	});
+	let trigger_handler =
+		Arc::new(|_processed_block: &ProcessedBlock| tokio::spawn(async move { Ok(()) }));
 
 	// Execute process_new_blocks
 	let result = process_new_blocks(
@@ -262,7 +265,7 @@ async fn test_no_new_blocks() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	// Process blocks
 	let result = process_new_blocks(
@@ -341,7 +344,7 @@ async fn test_concurrent_processing() {
 		})
 	};
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	// Process blocks
 	let result = process_new_blocks(
@@ -439,6 +442,7 @@ async fn test_ordered_trigger_handling() {
 
 			tokio::spawn(async move {
 				triggered_blocks.lock().await.push(block_number);
+				Ok(())
 			})
 		})
 	};
@@ -509,7 +513,7 @@ async fn test_block_storage_enabled() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -561,7 +565,7 @@ async fn test_max_past_blocks_limit() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -637,7 +641,7 @@ async fn test_max_past_blocks_limit_recommended() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	// Process blocks without limit
 	let result = process_new_blocks(
@@ -689,7 +693,7 @@ async fn test_confirmation_blocks() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	// Process blocks
 	let result = process_new_blocks(
@@ -737,7 +741,7 @@ async fn test_process_new_blocks_storage_error() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	// Process blocks - should fail with storage error
 	let result = process_new_blocks(
@@ -785,7 +789,7 @@ async fn test_process_new_blocks_network_errors() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	// Process blocks - should fail with network error
 	let result = process_new_blocks(
@@ -837,7 +841,7 @@ async fn test_process_new_blocks_get_blocks_error() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -905,7 +909,7 @@ async fn test_process_new_blocks_storage_save_error() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -968,7 +972,7 @@ async fn test_process_new_blocks_save_last_processed_error() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -986,6 +990,61 @@ async fn test_process_new_blocks_save_last_processed_error() {
 	}
 }
 
+#[tokio::test]
+async fn test_process_new_blocks_trigger_handler_failure() {
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+
+	// The checkpoint must not advance when triggers were not handled
+	let mut block_storage = MockBlockStorage::new();
+	block_storage
+		.expect_get_last_processed_block()
+		.returning(|_| Ok(Some(100)))
+		.times(1);
+	block_storage.expect_save_last_processed_block().times(0);
+	let block_storage = Arc::new(block_storage);
+
+	let mut block_tracker = MockBlockTracker::default();
+	block_tracker
+		.expect_record_block()
+		.returning(|_, _| Ok(()))
+		.times(1);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(105))
+		.times(1);
+	rpc_client
+		.expect_get_blocks()
+		.returning(|_, _| Ok(vec![create_test_block(BlockChainType::EVM, 101)]))
+		.times(1);
+
+	let block_handler = Arc::new(|_: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: 101,
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+
+	let trigger_handler =
+		Arc::new(|_: &ProcessedBlock| tokio::spawn(async { panic!("Failed to queue matches") }));
+
+	let result = process_new_blocks(
+		&network,
+		&rpc_client,
+		block_storage.clone(),
+		block_handler,
+		trigger_handler,
+		Arc::new(block_tracker),
+	)
+	.await;
+
+	assert!(result.is_err());
+}
+
 #[tokio::test]
 async fn test_process_new_blocks_storage_delete_error() {
 	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
@@ -1034,7 +1093,7 @@ async fn test_process_new_blocks_storage_delete_error() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -1065,7 +1124,7 @@ async fn test_network_block_watcher_new() {
 			}
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 	let block_tracker = Arc::new(BlockTracker::new(10, Some(block_storage.clone())));
 
 	let watcher = NetworkBlockWatcher::<_, _, _, JobScheduler>::new(
@@ -1100,7 +1159,7 @@ async fn test_network_block_watcher_start_stop() {
 			}
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 	let block_tracker = Arc::new(BlockTracker::new(10, Some(block_storage.clone())));
 
 	let watcher = NetworkBlockWatcher::<_, _, _, JobScheduler>::new(
@@ -1143,7 +1202,7 @@ async fn test_block_watcher_service_start_stop_network() {
 			}
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 	let block_tracker = Arc::new(BlockTracker::new(10, Some(block_storage.clone())));
 
 	let service = BlockWatcherService::<_, _, _, JobScheduler>::new(
@@ -1213,7 +1272,7 @@ async fn test_block_watcher_service_new() {
 			}
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 	let block_tracker = Arc::new(BlockTracker::new(10, Some(block_storage.clone())));
 
 	let service = BlockWatcherService::<_, _, _, JobScheduler>::new(
@@ -1262,7 +1321,7 @@ async fn test_process_new_blocks_get_blocks_error_fresh_start() {
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
 
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 
 	let result = process_new_blocks(
 		&network,
@@ -1293,7 +1352,7 @@ async fn test_scheduler_errors() {
 			}
 		}) as BoxFuture<'static, ProcessedBlock>
 	});
-	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async { Ok(()) }));
 	let block_tracker = Arc::new(BlockTracker::new(10, Some(block_storage.clone())));
 
 	// Test case 1: Scheduler fails to initialize
@@ -1437,3 +1496,244 @@ async fn test_scheduler_errors() {
 		));
 	}
 }
+
+#[tokio::test]
+async fn test_process_head_blocks() {
+	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	network.confirmation_blocks = 2;
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(105))
+		.times(2);
+	// Only the blocks above the confirmation depth are fetched, and only once
+	rpc_client
+		.expect_get_blocks()
+		.with(predicate::eq(104), predicate::eq(Some(105)))
+		.returning(|_, _| {
+			Ok(vec![
+				create_test_block(BlockChainType::EVM, 104),
+				create_test_block(BlockChainType::EVM, 105),
+			])
+		})
+		.times(1);
+
+	let pending_block_handler: PendingBlockHandler =
+		Arc::new(|block: BlockType, network: Network| {
+			Box::pin(async move {
+				ProcessedBlock {
+					block_number: block.number().unwrap_or(0),
+					network_slug: network.slug,
+					processing_results: vec![],
+				}
+			}) as BoxFuture<'static, ProcessedBlock>
+		});
+
+	let triggered = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+	let trigger_handler = Arc::new({
+		let triggered = triggered.clone();
+		move |_: &ProcessedBlock| {
+			triggered.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+			tokio::spawn(async { Ok(()) })
+		}
+	});
+
+	let pending_alert_tracker = PendingAlertTracker::new();
+
+	for _ in 0..2 {
+		let result = process_head_blocks(
+			&network,
+			&rpc_client,
+			pending_block_handler.clone(),
+			trigger_handler.clone(),
+			pending_alert_tracker.clone(),
+		)
+		.await;
+		assert!(result.is_ok());
+	}
+
+	assert_eq!(triggered.load(std::sync::atomic::Ordering::SeqCst), 2);
+	assert_eq!(
+		pending_alert_tracker.last_head_block(&network.slug),
+		Some(105)
+	);
+}
+
+#[tokio::test]
+async fn test_process_head_blocks_trigger_handler_failure() {
+	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	network.confirmation_blocks = 2;
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(105))
+		.times(1);
+	rpc_client
+		.expect_get_blocks()
+		.with(predicate::eq(104), predicate::eq(Some(105)))
+		.returning(|_, _| {
+			Ok(vec![
+				create_test_block(BlockChainType::EVM, 104),
+				create_test_block(BlockChainType::EVM, 105),
+			])
+		})
+		.times(1);
+
+	let pending_block_handler: PendingBlockHandler =
+		Arc::new(|block: BlockType, network: Network| {
+			Box::pin(async move {
+				ProcessedBlock {
+					block_number: block.number().unwrap_or(0),
+					network_slug: network.slug,
+					processing_results: vec![],
+				}
+			}) as BoxFuture<'static, ProcessedBlock>
+		});
+
+	let triggered = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+	let trigger_handler = Arc::new({
+		let triggered = triggered.clone();
+		move |_: &ProcessedBlock| {
+			triggered.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+			tokio::spawn(async {
+				Err(TriggerError::execution_error_without_log(
+					"Failed to queue matches",
+					None,
+					None,
+				))
+			})
+		}
+	});
+
+	let pending_alert_tracker = PendingAlertTracker::new();
+	let result = process_head_blocks(
+		&network,
+		&rpc_client,
+		pending_block_handler,
+		trigger_handler,
+		pending_alert_tracker.clone(),
+	)
+	.await;
+
+	// The run stops at the failed block, which is evaluated again next run
+	assert!(result.is_err());
+	assert_eq!(triggered.load(std::sync::atomic::Ordering::SeqCst), 1);
+	assert_eq!(pending_alert_tracker.last_head_block(&network.slug), None);
+}
+
+#[tokio::test]
+async fn test_process_missed_blocks() {
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+
+	let mut block_storage = MockBlockStorage::new();
+	block_storage
+		.expect_get_missed_blocks()
+		.with(predicate::eq("test-network"))
+		.returning(|_| Ok(vec![100, 101]))
+		.times(1);
+	// Only the block that could be fetched is resolved
+	block_storage
+		.expect_remove_missed_blocks()
+		.withf(|network, blocks| network == "test-network" && blocks == [100])
+		.returning(|_, _| Ok(()))
+		.times(1);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_blocks()
+		.with(predicate::eq(100), predicate::eq(None))
+		.returning(|_, _| Ok(vec![create_test_block(BlockChainType::EVM, 100)]))
+		.times(1);
+	// Fetching is retried a bounded number of times before giving up until the next run
+	rpc_client
+		.expect_get_blocks()
+		.with(predicate::eq(101), predicate::eq(None))
+		.returning(|_, _| Err(anyhow::anyhow!("RPC error")))
+		.times(3);
+
+	let block_handler = Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+
+	let triggered = Arc::new(std::sync::Mutex::new(Vec::new()));
+	let trigger_handler = Arc::new({
+		let triggered = triggered.clone();
+		move |block: &ProcessedBlock| {
+			triggered.lock().unwrap().push(block.block_number);
+			tokio::spawn(async { Ok(()) })
+		}
+	});
+
+	let result = process_missed_blocks(
+		&network,
+		&rpc_client,
+		Arc::new(block_storage),
+		block_handler,
+		trigger_handler,
+	)
+	.await;
+
+	assert_eq!(result.unwrap(), 1);
+	assert_eq!(*triggered.lock().unwrap(), vec![100]);
+}
+
+#[tokio::test]
+async fn test_process_missed_blocks_trigger_handler_failure() {
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+
+	// A block whose triggers failed stays in the ledger for the next run
+	let mut block_storage = MockBlockStorage::new();
+	block_storage
+		.expect_get_missed_blocks()
+		.returning(|_| Ok(vec![100]))
+		.times(1);
+	block_storage
+		.expect_remove_missed_blocks()
+		.withf(|_, blocks| blocks.is_empty())
+		.returning(|_, _| Ok(()))
+		.times(1);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_blocks()
+		.returning(|_, _| Ok(vec![create_test_block(BlockChainType::EVM, 100)]))
+		.times(1);
+
+	let block_handler = Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| {
+		tokio::spawn(async {
+			Err(TriggerError::execution_error(
+				"Failed to queue matches",
+				None,
+				None,
+			))
+		})
+	});
+
+	let result = process_missed_blocks(
+		&network,
+		&rpc_client,
+		Arc::new(block_storage),
+		block_handler,
+		trigger_handler,
+	)
+	.await;
+
+	assert_eq!(result.unwrap(), 0);
+}
@@ -5,22 +5,31 @@ use crate::integration::{
 	},
 	mocks::{
 		create_test_block, create_test_network, create_test_transaction, MockAlloyTransportClient,
-		MockClientPool, MockEvmClientTrait, MockMonitorRepository, MockNetworkRepository,
-		MockStellarClientTrait, MockTriggerExecutionService, MockTriggerRepository,
+		MockBlockStorage, MockBlockTracker, MockClientPool, MockEvmClientTrait,
+		MockMonitorRepository, MockNetworkRepository, MockStellarClientTrait,
+		MockTriggerExecutionService, MockTriggerRepository,
 	},
 };
 use openzeppelin_monitor::{
-	bootstrap::{create_block_handler, create_trigger_handler, initialize_services, process_block},
+	bootstrap::{
+		create_block_handler, create_match_writer_handler, create_outbox_trigger_handler,
+		create_trigger_handler, explain_monitor, initialize_services, process_block,
+		send_monitor_report,
+	},
 	models::{
-		BlockChainType, EVMMonitorMatch, EVMTransactionReceipt, MatchConditions, Monitor,
-		MonitorMatch, NotificationMessage, ProcessedBlock, ScriptLanguage, StellarBlock,
-		StellarMonitorMatch, TransactionType, Trigger, TriggerConditions, TriggerType,
-		TriggerTypeConfig,
+		BlockChainType, BlockType, EVMMonitorMatch, EVMTransactionReceipt, MatchConditions,
+		Monitor, MonitorMatch, NotificationMessage, ProcessedBlock, ReportSchedule, ScriptLanguage,
+		StellarBlock, StellarMonitorMatch, TransactionType, Trigger, TriggerConditions,
+		TriggerType, TriggerTypeConfig,
 	},
 	services::{
+		blockwatcher::process_new_blocks,
 		filter::FilterService,
 		notification::NotificationService,
-		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+		report::{FileMatchHistory, MatchHistory, MatchRecord},
+		trigger::{
+			FileMatchOutbox, MatchOutbox, TriggerExecutionService, TriggerExecutionServiceTrait,
+		},
 	},
 };
 
@@ -55,7 +64,11 @@ fn create_test_trigger(name: &str) -> Trigger {
 				title: "Test Title".to_string(),
 				body: "Test Body".to_string(),
 			},
+			blocks: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	}
 }
 
@@ -70,6 +83,8 @@ fn create_test_monitor_match(chain: BlockChainType) -> MonitorMatch {
 			receipt: EVMTransactionReceipt::default(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		})),
 		BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 			monitor: create_test_monitor("test", vec!["stellar_mainnet"], false, vec![]),
@@ -80,6 +95,8 @@ fn create_test_monitor_match(chain: BlockChainType) -> MonitorMatch {
 			ledger: StellarBlock::default(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		})),
 		_ => panic!("Unsupported chain"),
 	}
@@ -220,7 +237,8 @@ async fn test_create_trigger_handler() {
 	let handle = trigger_handler(&processed_block);
 	handle
 		.await
-		.expect("Trigger handler task should complete successfully");
+		.expect("Trigger handler task should complete successfully")
+		.expect("Trigger handler should succeed");
 }
 
 #[tokio::test]
@@ -247,7 +265,302 @@ async fn test_create_trigger_handler_empty_matches() {
 	let handle = trigger_handler(&processed_block);
 	handle
 		.await
-		.expect("Trigger handler task should complete successfully");
+		.expect("Trigger handler task should complete successfully")
+		.expect("Trigger handler should succeed");
+}
+
+#[tokio::test]
+async fn test_create_match_writer_handler() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let output_path = temp_dir.path().join("matches.jsonl");
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let match_writer = create_match_writer_handler(shutdown_tx, &output_path, HashMap::new())
+		.await
+		.unwrap();
+
+	let processed_block = ProcessedBlock {
+		block_number: 100,
+		network_slug: "ethereum_mainnet".to_string(),
+		processing_results: vec![
+			create_test_monitor_match(BlockChainType::EVM),
+			create_test_monitor_match(BlockChainType::EVM),
+		],
+	};
+
+	match_writer(&processed_block).await.unwrap().unwrap();
+	match_writer(&processed_block).await.unwrap().unwrap();
+
+	let content = std::fs::read_to_string(&output_path).unwrap();
+	let lines: Vec<&str> = content.lines().collect();
+	assert_eq!(lines.len(), 4);
+	for line in lines {
+		let monitor_match: MonitorMatch = serde_json::from_str(line).unwrap();
+		assert!(matches!(monitor_match, MonitorMatch::EVM(_)));
+	}
+}
+
+#[tokio::test]
+async fn test_create_outbox_trigger_handler() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let outbox = Arc::new(FileMatchOutbox::new(temp_dir.path().join("outbox")));
+	let history = Arc::new(FileMatchHistory::new(temp_dir.path().join("history")));
+	let trigger_handler =
+		create_outbox_trigger_handler(outbox.clone(), history.clone(), HashMap::new());
+
+	let mut monitor_match = create_test_monitor_match(BlockChainType::EVM);
+	if let MonitorMatch::EVM(evm_match) = &mut monitor_match {
+		evm_match.monitor.triggers = vec!["slack".to_string(), "webhook".to_string()];
+	}
+	let processed_block = ProcessedBlock {
+		block_number: 100,
+		network_slug: "ethereum_mainnet".to_string(),
+		processing_results: vec![monitor_match],
+	};
+
+	// Handling the same block twice queues each delivery once
+	trigger_handler(&processed_block).await.unwrap().unwrap();
+	trigger_handler(&processed_block).await.unwrap().unwrap();
+
+	let entries = outbox.due(i64::MAX, 10).await.unwrap();
+	assert_eq!(entries.len(), 2);
+	let mut triggers: Vec<&str> = entries.iter().map(|e| e.trigger.as_str()).collect();
+	triggers.sort();
+	assert_eq!(triggers, vec!["slack", "webhook"]);
+	assert!(entries
+		.iter()
+		.all(|e| e.block_number == 100 && e.attempts == 0));
+
+	// The match is recorded in the history once for the reports
+	let records = history.matches("test", 0, i64::MAX).await.unwrap();
+	assert_eq!(records.len(), 1);
+	assert_eq!(records[0].block_number, 100);
+	assert_eq!(records[0].network_slug, "ethereum_mainnet");
+}
+
+#[tokio::test]
+async fn test_outbox_trigger_handler_enqueue_failure_keeps_checkpoint() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	// The outbox directory cannot be created under a regular file, so queueing fails
+	let outbox_path = temp_dir.path().join("outbox");
+	std::fs::write(&outbox_path, "").unwrap();
+	let history = Arc::new(FileMatchHistory::new(temp_dir.path().join("history")));
+	let trigger_handler = create_outbox_trigger_handler(
+		Arc::new(FileMatchOutbox::new(outbox_path)),
+		history.clone(),
+		HashMap::new(),
+	);
+
+	let network = create_test_network("Test Network", "ethereum_mainnet", BlockChainType::EVM);
+	let mut block_storage = MockBlockStorage::new();
+	block_storage
+		.expect_get_last_processed_block()
+		.returning(|_| Ok(Some(100)))
+		.times(1);
+	block_storage.expect_save_last_processed_block().times(0);
+
+	let mut block_tracker = MockBlockTracker::default();
+	block_tracker
+		.expect_record_block()
+		.returning(|_, _| Ok(()))
+		.times(1);
+
+	let mut rpc_client = MockEvmClientTrait::<MockAlloyTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(|| Ok(105))
+		.times(1);
+	rpc_client
+		.expect_get_blocks()
+		.returning(|_, _| Ok(vec![create_test_block(BlockChainType::EVM, 101)]))
+		.times(1);
+
+	let block_handler = Arc::new(
+		|_: BlockType, network: openzeppelin_monitor::models::Network| {
+			Box::pin(async move {
+				let mut monitor_match = create_test_monitor_match(BlockChainType::EVM);
+				if let MonitorMatch::EVM(evm_match) = &mut monitor_match {
+					evm_match.monitor.triggers = vec!["slack".to_string()];
+				}
+				ProcessedBlock {
+					block_number: 101,
+					network_slug: network.slug,
+					processing_results: vec![monitor_match],
+				}
+			}) as futures::future::BoxFuture<'static, ProcessedBlock>
+		},
+	);
+
+	let result = process_new_blocks(
+		&network,
+		&rpc_client,
+		Arc::new(block_storage),
+		block_handler,
+		trigger_handler,
+		Arc::new(block_tracker),
+	)
+	.await;
+
+	assert!(result.is_err());
+	// Nothing was queued, so nothing is recorded for the reports either
+	assert!(history
+		.matches("test", 0, i64::MAX)
+		.await
+		.unwrap()
+		.is_empty());
+}
+
+#[tokio::test]
+async fn test_send_monitor_report() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let outbox = FileMatchOutbox::new(temp_dir.path().join("outbox"));
+	let history = FileMatchHistory::new(temp_dir.path().join("history"));
+	let monitor_match = create_test_monitor_match(BlockChainType::EVM);
+	let now = 10 * 86_400_000;
+	history
+		.record(vec![
+			MatchRecord::new("ethereum_mainnet", 100, &monitor_match, now - 1_000),
+			// Matches before the period are not reported
+			MatchRecord::new("ethereum_mainnet", 1, &monitor_match, now - 2 * 86_400_000),
+		])
+		.await
+		.unwrap();
+
+	let mut trigger_service = MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_service
+		.expect_send_report()
+		.withf(|triggers, report| {
+			triggers == ["email".to_string()]
+				&& report.monitor_name == "test"
+				&& report.match_count == 1
+		})
+		.times(1)
+		.returning(|_, _| Ok(()));
+
+	let schedule = ReportSchedule {
+		triggers: vec!["email".to_string()],
+		..Default::default()
+	};
+	let report = send_monitor_report("test", &schedule, &history, &outbox, &trigger_service, now)
+		.await
+		.unwrap();
+	assert_eq!(report.period_start, now - 86_400_000);
+	assert_eq!(report.failed_deliveries, 0);
+}
+
+#[tokio::test]
+async fn test_explain_monitor_evm_transaction() {
+	let transaction = match create_test_transaction(BlockChainType::EVM) {
+		TransactionType::EVM(tx) => tx,
+		_ => unreachable!(),
+	};
+	let tx_hash = format!("{:#x}", transaction.hash);
+	let mut block = create_test_block(BlockChainType::EVM, 100);
+	if let BlockType::EVM(evm_block) = &mut block {
+		evm_block.0.transactions.push(transaction);
+	}
+	let network = create_test_network("Ethereum", "ethereum_mainnet", BlockChainType::EVM);
+	let monitor = create_test_monitor("test", vec!["ethereum_mainnet"], false, vec![]);
+
+	let mut mock_client = MockEvmClientTrait::new();
+	mock_client.expect_get_transaction_receipt().returning(|_| {
+		let mut receipt = EVMTransactionReceipt::default();
+		receipt.0.block_number = Some(alloy::primitives::U64::from(100));
+		Ok(receipt)
+	});
+	mock_client
+		.expect_get_blocks()
+		.withf(|start, end| *start == 100 && end.is_none())
+		.return_once(move |_, _| Ok(vec![block]));
+	let mut mock_pool = MockClientPool::new();
+	mock_pool
+		.expect_get_evm_client()
+		.return_once(move |_| Ok(Arc::new(mock_client)));
+
+	let traces = explain_monitor(
+		&mock_pool,
+		&FilterService::new(),
+		&network,
+		&monitor,
+		None,
+		Some(&tx_hash),
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(traces.len(), 1);
+	assert_eq!(traces[0].block_number, 100);
+	assert_eq!(traces[0].monitor_name, "test");
+	// The monitor has no conditions, but none of its addresses are involved
+	assert!(traces[0].decision.should_match);
+	assert_eq!(traces[0].address_match, Some(false));
+	assert!(!traces[0].matched);
+}
+
+#[tokio::test]
+async fn test_explain_monitor_stellar_transaction() {
+	let network = create_test_network("Stellar", "stellar_mainnet", BlockChainType::Stellar);
+	let monitor = create_test_monitor("test", vec!["stellar_mainnet"], false, vec![]);
+
+	let mut mock_client = MockStellarClientTrait::new();
+	mock_client
+		.expect_get_transaction_ledger()
+		.withf(|hash| hash == "abc")
+		.returning(|_| Ok(Some(100)));
+	mock_client
+		.expect_get_blocks()
+		.withf(|start, end| *start == 100 && end.is_none())
+		.return_once(|_, _| Ok(vec![create_test_block(BlockChainType::Stellar, 100)]));
+	mock_client
+		.expect_get_transactions()
+		.return_once(|_, _| Ok(vec![]));
+	mock_client
+		.expect_get_events()
+		.return_once(|_, _| Ok(vec![]));
+	let mut mock_pool = MockClientPool::new();
+	mock_pool
+		.expect_get_stellar_client()
+		.return_once(move |_| Ok(Arc::new(mock_client)));
+
+	// The ledger of the transaction is looked up when no block is given
+	let traces = explain_monitor(
+		&mock_pool,
+		&FilterService::new(),
+		&network,
+		&monitor,
+		None,
+		Some("abc"),
+	)
+	.await
+	.unwrap();
+	assert!(traces.is_empty());
+}
+
+#[tokio::test]
+async fn test_explain_monitor_stellar_unknown_transaction() {
+	let network = create_test_network("Stellar", "stellar_mainnet", BlockChainType::Stellar);
+	let monitor = create_test_monitor("test", vec!["stellar_mainnet"], false, vec![]);
+
+	let mut mock_client = MockStellarClientTrait::new();
+	mock_client
+		.expect_get_transaction_ledger()
+		.returning(|_| Ok(None));
+	let mut mock_pool = MockClientPool::new();
+	mock_pool
+		.expect_get_stellar_client()
+		.return_once(move |_| Ok(Arc::new(mock_client)));
+
+	let result = explain_monitor(
+		&mock_pool,
+		&FilterService::new(),
+		&network,
+		&monitor,
+		None,
+		Some("abc"),
+	)
+	.await;
+
+	assert!(result.is_err());
 }
 
 #[tokio::test]
@@ -446,13 +759,16 @@ print(True)  # Always return true for test
 			receipt: EVMTransactionReceipt::default(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			alert: None,
+			context: None,
 		}))],
 	};
 
 	let handle = trigger_handler(&processed_block);
 	handle
 		.await
-		.expect("Trigger handler task should complete successfully");
+		.expect("Trigger handler task should complete successfully")
+		.expect("Trigger handler should succeed");
 }
 
 #[tokio::test]
@@ -673,6 +989,9 @@ async fn test_load_scripts_for_custom_triggers_notifications() {
 			timeout_ms: 1000,
 			arguments: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 	mocked_triggers.insert("custom_trigger".to_string(), custom_trigger.clone());
 
@@ -742,6 +1061,9 @@ async fn test_load_scripts_for_custom_triggers_notifications_error() {
 			timeout_ms: 1000,
 			arguments: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 	mocked_triggers.insert("custom_trigger".to_string(), custom_trigger.clone());
 
@@ -794,6 +1116,9 @@ async fn test_load_scripts_for_custom_triggers_notifications_failed() {
 			timeout_ms: 1000,
 			arguments: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 	mocked_triggers.insert("custom_trigger".to_string(), custom_trigger.clone());
 
@@ -842,7 +1167,11 @@ async fn test_trigger_execution_service_execute_multiple_triggers_failed() {
 					title: "Test Alert".to_string(),
 					body: "Test message with value ${value}".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 	mocked_triggers.insert(
@@ -856,12 +1185,17 @@ async fn test_trigger_execution_service_execute_multiple_triggers_failed() {
 						.to_string(),
 				method: Some("POST".to_string()),
 				secret: Some("secret".to_string()),
+				secrets: None,
 				headers: Some(HashMap::new()),
 				message: NotificationMessage {
 					title: "Test Title".to_string(),
 					body: "Test Body".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 	let script_path = "tests/integration/fixtures/evm/triggers/scripts/custom_notification.py";
@@ -876,6 +1210,9 @@ async fn test_trigger_execution_service_execute_multiple_triggers_failed() {
 				timeout_ms: 1000,
 				arguments: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 	let mock_trigger_service = setup_trigger_service(mocked_triggers);
@@ -946,7 +1283,11 @@ async fn test_trigger_execution_service_execute_multiple_triggers_success() {
 					title: "Test Alert".to_string(),
 					body: "Test message with value ${value}".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 
@@ -960,12 +1301,17 @@ async fn test_trigger_execution_service_execute_multiple_triggers_success() {
 				url: webhook_server.url(),
 				method: Some("POST".to_string()),
 				secret: Some("secret".to_string()),
+				secrets: None,
 				headers: Some(HashMap::new()),
 				message: NotificationMessage {
 					title: "Test Title".to_string(),
 					body: "Test Body".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 
@@ -1031,7 +1377,11 @@ async fn test_trigger_execution_service_execute_multiple_triggers_partial_succes
 					title: "Test Alert".to_string(),
 					body: "Test message with value ${value}".to_string(),
 				},
+				blocks: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 
@@ -1045,12 +1395,17 @@ async fn test_trigger_execution_service_execute_multiple_triggers_partial_succes
 				url: webhook_server.url(),
 				method: Some("POST".to_string()),
 				secret: Some("secret".to_string()),
+				secrets: None,
 				headers: Some(HashMap::new()),
 				message: NotificationMessage {
 					title: "Test Title".to_string(),
 					body: "Test Body".to_string(),
 				},
+				payload: None,
 			},
+			retry: None,
+			alert_policy: None,
+			digest: None,
 		},
 	);
 
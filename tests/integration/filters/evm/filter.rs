@@ -13,7 +13,7 @@ use openzeppelin_monitor::{
 	},
 	services::{
 		blockchain::EvmClient,
-		filter::{handle_match, FilterError, FilterService},
+		filter::{build_match_variables, handle_match, FilterError, FilterService},
 	},
 };
 
@@ -547,3 +547,53 @@ async fn test_handle_match() -> Result<(), Box<FilterError>> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_match_context_variables() -> Result<(), Box<FilterError>> {
+	let mut test_data = load_test_data("evm");
+	test_data.network.explorer_url = Some("https://etherscan.io/".to_string());
+	let filter_service = FilterService::new();
+	let client = EvmClient::new_with_transport(setup_mock_transport(test_data.clone()));
+
+	let matches = filter_service
+		.filter_block(
+			&client,
+			&test_data.network,
+			&test_data.blocks[0],
+			&[test_data.monitor.clone()],
+		)
+		.await?;
+	assert!(!matches.is_empty(), "Should have found matches");
+
+	let variables = build_match_variables(&matches[0]);
+	let usdc = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+	let tx_hash = "0xd5069b22a3a89a36d592d5a1f72a281bc5d11d6d0bac6f0a878c13abb764b6d8";
+	assert_eq!(variables["network_slug"], test_data.network.slug);
+	assert_eq!(variables["network_name"], test_data.network.name);
+	assert_eq!(
+		variables["chain_id"],
+		test_data.network.chain_id.unwrap().to_string()
+	);
+	assert_eq!(variables["block_number"], 0x1451aca.to_string());
+	assert_eq!(variables["block_timestamp"], 0x674c0aef.to_string());
+	assert_eq!(variables["transaction_status"], "success");
+	assert!(variables["gas_used"].parse::<u64>().is_ok());
+	assert_eq!(variables["contract_address"], usdc);
+	assert_eq!(variables["event_0_contract_address"], usdc);
+	assert_eq!(variables["log_index"], variables["event_0_log_index"]);
+	assert_eq!(
+		variables["transaction_link"],
+		format!("https://etherscan.io/tx/{}", tx_hash)
+	);
+	assert_eq!(
+		variables["address_link"],
+		format!("https://etherscan.io/address/{}", usdc)
+	);
+	assert_eq!(
+		variables["event_0_from_link"],
+		"https://etherscan.io/address/0x58b704065b7aff3ed351052f8560019e05925023"
+	);
+	assert!(!variables.contains_key("event_0_value_link"));
+
+	Ok(())
+}
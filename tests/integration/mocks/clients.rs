@@ -101,6 +101,11 @@ mock! {
 			start_sequence: u32,
 			end_sequence: Option<u32>,
 		) -> Result<Vec<StellarEvent>, anyhow::Error>;
+
+		async fn get_transaction_ledger(
+			&self,
+			transaction_hash: &str,
+		) -> Result<Option<u32>, anyhow::Error>;
 	}
 
 	impl<T: Send + Sync + Clone + 'static> Clone for StellarClientTrait<T> {
@@ -18,6 +18,7 @@ pub fn create_test_network(name: &str, slug: &str, network_type: BlockChainType)
 		cron_schedule: "*/5 * * * * *".to_string(),
 		confirmation_blocks: 1,
 		store_blocks: Some(false),
+		explorer_url: None,
 		chain_id: Some(1),
 		network_passphrase: None,
 		block_time_ms: 1000,
@@ -41,6 +42,7 @@ pub fn create_stellar_test_network_with_urls(urls: Vec<&str>, type_: &str) -> Ne
 		cron_schedule: "*/5 * * * * *".to_string(),
 		confirmation_blocks: 1,
 		store_blocks: Some(false),
+		explorer_url: None,
 		chain_id: None,
 		network_passphrase: Some("Test SDF Network ; September 2015".to_string()),
 		block_time_ms: 5000,
@@ -214,6 +216,7 @@ pub fn create_evm_test_network_with_urls(urls: Vec<&str>) -> Network {
 		cron_schedule: "*/5 * * * * *".to_string(),
 		confirmation_blocks: 1,
 		store_blocks: Some(false),
+		explorer_url: None,
 		chain_id: None,
 		network_passphrase: None,
 		block_time_ms: 5000,
@@ -33,6 +33,10 @@ mock! {
 		fn new(path: Option<&Path>) -> Result<Self, RepositoryError>;
 		#[mockall::concretize]
 		fn load_all(path: Option<&Path>) -> Result<HashMap<String, Trigger>, RepositoryError>;
+		#[mockall::concretize]
+		fn save(path: Option<&Path>, trigger_id: &str, trigger: &Trigger) -> Result<(), RepositoryError>;
+		#[mockall::concretize]
+		fn delete(path: Option<&Path>, trigger_id: &str) -> Result<(), RepositoryError>;
 		fn get(&self, trigger_id: &str) -> Option<Trigger>;
 		fn get_all(&self) -> HashMap<String, Trigger>;
 	}
@@ -89,6 +93,10 @@ mock! {
 			network_service: Option<NetworkService<N>>,
 			trigger_service: Option<TriggerService<T>>,
 		) -> Result<HashMap<String, Monitor>, RepositoryError>;
+		#[mockall::concretize]
+		fn save(path: Option<&Path>, monitor_id: &str, monitor: &Monitor) -> Result<(), RepositoryError>;
+		#[mockall::concretize]
+		fn delete(path: Option<&Path>, monitor_id: &str) -> Result<(), RepositoryError>;
 		fn get(&self, monitor_id: &str) -> Option<Monitor>;
 		fn get_all(&self) -> HashMap<String, Monitor>;
 	}
@@ -10,6 +10,7 @@ use openzeppelin_monitor::{
 		blockwatcher::{BlockStorage, BlockTrackerTrait, JobSchedulerTrait},
 		filter::FilterError,
 		notification::NotificationService,
+		report::MonitorReport,
 		trigger::{TriggerError, TriggerExecutionServiceTrait},
 	},
 };
@@ -29,6 +30,7 @@ mock! {
 			trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 		) -> Result<(), TriggerError>;
 		async fn load_scripts(&self, monitors: &[Monitor]) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError>;
+		async fn send_report(&self, trigger_slugs: &[String], report: &MonitorReport) -> Result<(), TriggerError>;
 	}
 }
 
@@ -55,6 +57,8 @@ mock! {
 		async fn get_last_processed_block(&self, network_slug: &str) -> Result<Option<u64>, anyhow::Error>;
 		async fn save_blocks(&self, network_slug: &str, blocks: &[BlockType]) -> Result<(), anyhow::Error>;
 		async fn delete_blocks(&self, network_slug: &str) -> Result<(), anyhow::Error>;
+		async fn get_missed_blocks(&self, network_slug: &str) -> Result<Vec<u64>, anyhow::Error>;
+		async fn remove_missed_blocks(&self, network_slug: &str, blocks: &[u64]) -> Result<(), anyhow::Error>;
 	}
 
 	impl Clone for BlockStorage {
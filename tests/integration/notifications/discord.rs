@@ -32,6 +32,8 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		receipt: create_test_evm_transaction_receipt(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		alert: None,
+		context: None,
 	}))
 }
 
@@ -115,7 +117,11 @@ async fn test_notification_service_discord_execution() {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
 			},
+			embed: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	let mut variables = HashMap::new();
@@ -131,6 +137,64 @@ async fn test_notification_service_discord_execution() {
 	mock.assert();
 }
 
+#[tokio::test]
+async fn test_notification_service_discord_embed_execution() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.match_body(mockito::Matcher::Json(json!({
+			"username": null,
+			"avatar_url": null,
+			"embeds": [{
+				"title": "test_monitor",
+				"fields": [{ "name": "Value", "value": "42", "inline": true }],
+				"footer": { "text": "Ethereum Mainnet" },
+				"color": 0xF1C40F,
+				"timestamp": "2024-12-01T07:06:23Z"
+			}]
+		})))
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let trigger = Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::Discord,
+		config: TriggerTypeConfig::Discord {
+			discord_url: server.url(),
+			message: NotificationMessage {
+				title: "Test Alert".to_string(),
+				body: "Test message ${value}".to_string(),
+			},
+			embed: Some(json!({
+				"title": "{{ monitor_name }}",
+				"fields": [{ "name": "Value", "value": "{{ value }}", "inline": true }],
+				"footer": { "text": "{{ network_name }}" }
+			})),
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	};
+
+	let variables = HashMap::from([
+		("value".to_string(), "42".to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+		("network_name".to_string(), "Ethereum Mainnet".to_string()),
+		("severity".to_string(), "warning".to_string()),
+		("block_timestamp".to_string(), "1733036783".to_string()),
+	]);
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let result = notification_service
+		.execute(&trigger, variables, &monitor_match, &HashMap::new())
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
 #[tokio::test]
 async fn test_notification_service_discord_execution_failure() {
 	let notification_service = NotificationService::new();
@@ -153,7 +217,11 @@ async fn test_notification_service_discord_execution_failure() {
 				title: "Test Alert".to_string(),
 				body: "Test message".to_string(),
 			},
+			embed: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
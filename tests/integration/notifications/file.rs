@@ -0,0 +1,190 @@
+use openzeppelin_monitor::{
+	models::{
+		BlockChainType, EVMMonitorMatch, FileColumn, FileFormat, FileRotation, MatchConditions,
+		Monitor, MonitorMatch, TransactionType, Trigger, TriggerType, TriggerTypeConfig,
+	},
+	services::{notification::NotificationService, report::MonitorReport},
+};
+use serde_json::{json, Value};
+use std::{collections::HashMap, path::Path, sync::Arc};
+use tempfile::TempDir;
+
+use crate::integration::mocks::{create_test_evm_transaction_receipt, create_test_transaction};
+
+fn create_test_monitor(name: &str) -> Monitor {
+	Monitor {
+		name: name.to_string(),
+		networks: vec!["ethereum_mainnet".to_string()],
+		paused: false,
+		triggers: vec!["test_trigger".to_string()],
+		..Default::default()
+	}
+}
+
+fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
+	let transaction = match create_test_transaction(BlockChainType::EVM) {
+		TransactionType::EVM(transaction) => transaction,
+		_ => panic!("Failed to create test transaction"),
+	};
+
+	MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor,
+		transaction,
+		receipt: create_test_evm_transaction_receipt(),
+		matched_on: MatchConditions::default(),
+		matched_on_args: None,
+		alert: None,
+		context: None,
+	}))
+}
+
+fn create_test_trigger(path: &Path, format: FileFormat) -> Trigger {
+	let columns = (format == FileFormat::Csv).then(|| {
+		vec![
+			FileColumn {
+				name: "monitor".to_string(),
+				value: "{{ monitor_name }}".to_string(),
+			},
+			FileColumn {
+				name: "value".to_string(),
+				value: "{{ value }}".to_string(),
+			},
+		]
+	});
+	Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::File,
+		config: TriggerTypeConfig::File {
+			path: path.to_string_lossy().into_owned(),
+			format: Some(format),
+			payload: None,
+			columns,
+			rotation: None,
+			fsync: None,
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	}
+}
+
+fn create_test_variables(value: &str) -> HashMap<String, String> {
+	HashMap::from([
+		("value".to_string(), value.to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+		("transaction_hash".to_string(), "0xabc".to_string()),
+	])
+}
+
+#[tokio::test]
+async fn test_notification_service_file_execution() {
+	let temp_dir = TempDir::new().unwrap();
+	let path = temp_dir.path().join("alerts").join("matches.jsonl");
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let trigger = create_test_trigger(&path, FileFormat::Jsonl);
+
+	for _ in 0..2 {
+		let result = notification_service
+			.execute(
+				&trigger,
+				create_test_variables("42"),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await;
+		assert!(result.is_ok());
+	}
+
+	let content = std::fs::read_to_string(&path).unwrap();
+	let lines: Vec<&str> = content.lines().collect();
+	assert_eq!(lines.len(), 2);
+	for line in lines {
+		let value: Value = serde_json::from_str(line).unwrap();
+		assert_eq!(value, serde_json::to_value(&monitor_match).unwrap());
+	}
+}
+
+#[tokio::test]
+async fn test_notification_service_file_concurrent_csv_records() {
+	let temp_dir = TempDir::new().unwrap();
+	let path = temp_dir.path().join("alerts.csv");
+	let notification_service = Arc::new(NotificationService::new());
+	let monitor_match = Arc::new(create_test_evm_match(create_test_monitor("test_monitor")));
+	let mut trigger = create_test_trigger(&path, FileFormat::Csv);
+	if let TriggerTypeConfig::File { rotation, .. } = &mut trigger.config {
+		*rotation = Some(FileRotation {
+			max_size_bytes: Some(100),
+			compress: Some(true),
+			..Default::default()
+		});
+	}
+	let trigger = Arc::new(trigger);
+
+	let tasks: Vec<_> = (0..20)
+		.map(|index| {
+			let notification_service = notification_service.clone();
+			let monitor_match = monitor_match.clone();
+			let trigger = trigger.clone();
+			tokio::spawn(async move {
+				notification_service
+					.execute(
+						&trigger,
+						create_test_variables(&index.to_string()),
+						&monitor_match,
+						&HashMap::new(),
+					)
+					.await
+			})
+		})
+		.collect();
+	for task in tasks {
+		assert!(task.await.unwrap().is_ok());
+	}
+
+	// Every file, rotated or not, starts with the header and no record is lost
+	let mut values = Vec::new();
+	for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+		let path = entry.unwrap().path();
+		let content = if path.extension().is_some_and(|ext| ext == "gz") {
+			let mut content = String::new();
+			std::io::Read::read_to_string(
+				&mut flate2::read::GzDecoder::new(std::fs::File::open(&path).unwrap()),
+				&mut content,
+			)
+			.unwrap();
+			content
+		} else {
+			std::fs::read_to_string(&path).unwrap()
+		};
+		let mut lines = content.lines();
+		assert_eq!(lines.next(), Some("monitor,value"));
+		for line in lines {
+			let (monitor, value) = line.split_once(',').unwrap();
+			assert_eq!(monitor, "test_monitor");
+			values.push(value.parse::<u32>().unwrap());
+		}
+	}
+	values.sort();
+	assert_eq!(values, (0..20).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_notification_service_file_report() {
+	let temp_dir = TempDir::new().unwrap();
+	let path = temp_dir.path().join("reports.jsonl");
+	let notification_service = NotificationService::new();
+	let report = MonitorReport::new("test_monitor", 0, 86_400_000, &[], &[], 5);
+
+	let result = notification_service
+		.send_report(&create_test_trigger(&path, FileFormat::Jsonl), &report)
+		.await;
+	assert!(result.is_ok());
+
+	let content = std::fs::read_to_string(&path).unwrap();
+	let value: Value = serde_json::from_str(content.trim_end()).unwrap();
+	assert_eq!(
+		value,
+		json!({ "title": report.title(), "body": report.to_text() })
+	);
+}
@@ -0,0 +1,793 @@
+use openzeppelin_monitor::{
+	models::{
+		BlockChainType, EVMMonitorMatch, KafkaAcks, KafkaSasl, KafkaSaslMechanism, MatchConditions,
+		Monitor, MonitorMatch, TransactionType, Trigger, TriggerType, TriggerTypeConfig,
+	},
+	services::notification::{NotificationError, NotificationService},
+};
+use serde_json::{json, Value};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
+};
+use testcontainers_modules::{kafka::apache, testcontainers::runners::AsyncRunner};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+};
+
+use crate::integration::mocks::{create_test_evm_transaction_receipt, create_test_transaction};
+
+fn create_test_monitor(name: &str) -> Monitor {
+	Monitor {
+		name: name.to_string(),
+		networks: vec!["ethereum_mainnet".to_string()],
+		paused: false,
+		triggers: vec!["test_trigger".to_string()],
+		..Default::default()
+	}
+}
+
+fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
+	let transaction = match create_test_transaction(BlockChainType::EVM) {
+		TransactionType::EVM(transaction) => transaction,
+		_ => panic!("Failed to create test transaction"),
+	};
+
+	MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor,
+		transaction,
+		receipt: create_test_evm_transaction_receipt(),
+		matched_on: MatchConditions::default(),
+		matched_on_args: None,
+		alert: None,
+		context: None,
+	}))
+}
+
+fn create_test_trigger(brokers: Vec<String>) -> Trigger {
+	Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::Kafka,
+		config: TriggerTypeConfig::Kafka {
+			brokers,
+			topic: "monitor-alerts".to_string(),
+			key: Some("{{ transaction_hash }}".to_string()),
+			headers: Some(HashMap::from([(
+				"monitor".to_string(),
+				"{{ monitor_name }}".to_string(),
+			)])),
+			payload: None,
+			acks: None,
+			timeout_ms: Some(5000),
+			sasl: None,
+			tls: None,
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	}
+}
+
+fn create_test_variables() -> HashMap<String, String> {
+	HashMap::from([
+		("value".to_string(), "42".to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+		("transaction_hash".to_string(), "0xabc".to_string()),
+	])
+}
+
+/// Record received by the fake broker
+#[derive(Debug, Clone)]
+struct ReceivedRecord {
+	partition: i32,
+	acks: i16,
+	key: Option<Vec<u8>>,
+	value: Vec<u8>,
+	headers: Vec<(String, Vec<u8>)>,
+}
+
+/// Behavior of the fake broker
+#[derive(Clone, Default)]
+struct BrokerOptions {
+	/// Number of partitions of every topic
+	partitions: i32,
+	/// Error code of the produce responses
+	produce_error: i16,
+	/// Error code of the first produce response, the next ones use `produce_error`
+	first_produce_error: i16,
+	/// Number of requests served before a connection is closed
+	requests_per_connection: Option<usize>,
+	/// Username and password accepted with SASL PLAIN
+	credentials: Option<(String, String)>,
+	/// Highest Produce version supported, 9 when not set
+	max_produce_version: Option<i16>,
+	/// Throttle time of the metadata and produce responses
+	throttle_time_ms: i32,
+}
+
+/// Connections and requests received by the fake broker
+#[derive(Default)]
+struct BrokerStats {
+	connections: AtomicUsize,
+	api_versions_requests: AtomicUsize,
+	metadata_requests: AtomicUsize,
+	produce_requests: AtomicUsize,
+}
+
+/// In-process stand-in of a single-node Kafka cluster
+struct FakeBroker {
+	address: String,
+	records: Arc<Mutex<Vec<ReceivedRecord>>>,
+	stats: Arc<BrokerStats>,
+}
+
+impl FakeBroker {
+	async fn start(options: BrokerOptions) -> Self {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let port = listener.local_addr().unwrap().port();
+		let records = Arc::new(Mutex::new(Vec::new()));
+		let stats = Arc::new(BrokerStats::default());
+
+		let received = records.clone();
+		let broker_stats = stats.clone();
+		tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				broker_stats.connections.fetch_add(1, Ordering::SeqCst);
+				let options = options.clone();
+				let received = received.clone();
+				let broker_stats = broker_stats.clone();
+				tokio::spawn(serve(stream, port, options, received, broker_stats));
+			}
+		});
+
+		Self {
+			address: format!("127.0.0.1:{}", port),
+			records,
+			stats,
+		}
+	}
+
+	fn connections(&self) -> usize {
+		self.stats.connections.load(Ordering::SeqCst)
+	}
+
+	fn api_versions_requests(&self) -> usize {
+		self.stats.api_versions_requests.load(Ordering::SeqCst)
+	}
+
+	fn metadata_requests(&self) -> usize {
+		self.stats.metadata_requests.load(Ordering::SeqCst)
+	}
+
+	fn records(&self) -> Vec<ReceivedRecord> {
+		self.records.lock().unwrap().clone()
+	}
+}
+
+/// Reader of the requests of the fake broker
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+	fn take(&mut self, length: usize) -> &[u8] {
+		let (value, rest) = self.0.split_at(length);
+		self.0 = rest;
+		value
+	}
+
+	fn i8(&mut self) -> i8 {
+		self.take(1)[0] as i8
+	}
+
+	fn i16(&mut self) -> i16 {
+		i16::from_be_bytes(self.take(2).try_into().unwrap())
+	}
+
+	fn i32(&mut self) -> i32 {
+		i32::from_be_bytes(self.take(4).try_into().unwrap())
+	}
+
+	fn string(&mut self) -> String {
+		let length = self.i16();
+		if length < 0 {
+			return String::new();
+		}
+		String::from_utf8(self.take(length as usize).to_vec()).unwrap()
+	}
+
+	fn bytes(&mut self) -> Vec<u8> {
+		let length = self.i32();
+		self.take(length as usize).to_vec()
+	}
+
+	fn varint(&mut self) -> i64 {
+		let mut value = 0u64;
+		let mut shift = 0;
+		loop {
+			let byte = self.take(1)[0];
+			value |= ((byte & 0x7F) as u64) << shift;
+			if byte & 0x80 == 0 {
+				return ((value >> 1) as i64) ^ -((value & 1) as i64);
+			}
+			shift += 7;
+		}
+	}
+
+	fn varbytes(&mut self) -> Option<Vec<u8>> {
+		let length = self.varint();
+		(length >= 0).then(|| self.take(length as usize).to_vec())
+	}
+}
+
+fn string(buf: &mut Vec<u8>, value: &str) {
+	buf.extend_from_slice(&(value.len() as i16).to_be_bytes());
+	buf.extend_from_slice(value.as_bytes());
+}
+
+/// Key, value and headers of a record
+type DecodedRecord = (Option<Vec<u8>>, Vec<u8>, Vec<(String, Vec<u8>)>);
+
+/// Decodes the records of a v2 record batch
+fn decode_records(batch: &[u8]) -> Vec<DecodedRecord> {
+	let mut reader = Reader(batch);
+	reader.take(8 + 4 + 4);
+	assert_eq!(reader.i8(), 2, "record batches are v2");
+	reader.take(4 + 2 + 4 + 8 + 8 + 8 + 2 + 4);
+	let count = reader.i32();
+
+	(0..count)
+		.map(|_| {
+			reader.varint(); // length
+			reader.i8(); // attributes
+			reader.varint(); // timestamp delta
+			reader.varint(); // offset delta
+			let key = reader.varbytes();
+			let value = reader.varbytes().unwrap();
+			let headers = (0..reader.varint())
+				.map(|_| {
+					let name = String::from_utf8(reader.varbytes().unwrap()).unwrap();
+					(name, reader.varbytes().unwrap())
+				})
+				.collect();
+			(key, value, headers)
+		})
+		.collect()
+}
+
+/// Serves the requests of a connection
+async fn serve(
+	mut stream: TcpStream,
+	port: u16,
+	options: BrokerOptions,
+	records: Arc<Mutex<Vec<ReceivedRecord>>>,
+	stats: Arc<BrokerStats>,
+) {
+	let mut served = 0;
+	while let Ok(size) = stream.read_i32().await {
+		if options.requests_per_connection == Some(served) {
+			return;
+		}
+		served += 1;
+		let mut request = vec![0; size as usize];
+		stream.read_exact(&mut request).await.unwrap();
+		let mut reader = Reader(&request);
+		let api_key = reader.i16();
+		reader.i16(); // API version
+		let correlation_id = reader.i32();
+		reader.string(); // client ID
+
+		let mut body = Vec::new();
+		match api_key {
+			// ApiVersions
+			18 => {
+				stats.api_versions_requests.fetch_add(1, Ordering::SeqCst);
+				let max_produce_version = options.max_produce_version.unwrap_or(9);
+				body.extend_from_slice(&0i16.to_be_bytes());
+				body.extend_from_slice(&5i32.to_be_bytes());
+				for (api_key, max_version) in [
+					(0i16, max_produce_version),
+					(3, 12),
+					(17, 1),
+					(18, 3),
+					(36, 2),
+				] {
+					body.extend_from_slice(&api_key.to_be_bytes());
+					body.extend_from_slice(&0i16.to_be_bytes());
+					body.extend_from_slice(&max_version.to_be_bytes());
+				}
+			}
+			// Metadata
+			3 => {
+				stats.metadata_requests.fetch_add(1, Ordering::SeqCst);
+				reader.i32();
+				let topic = reader.string();
+				body.extend_from_slice(&options.throttle_time_ms.to_be_bytes());
+				body.extend_from_slice(&1i32.to_be_bytes());
+				body.extend_from_slice(&1i32.to_be_bytes());
+				string(&mut body, "127.0.0.1");
+				body.extend_from_slice(&(port as i32).to_be_bytes());
+				body.extend_from_slice(&(-1i16).to_be_bytes());
+				body.extend_from_slice(&(-1i16).to_be_bytes());
+				body.extend_from_slice(&1i32.to_be_bytes());
+				body.extend_from_slice(&1i32.to_be_bytes());
+				body.extend_from_slice(&0i16.to_be_bytes());
+				string(&mut body, &topic);
+				body.push(0);
+				body.extend_from_slice(&options.partitions.to_be_bytes());
+				for index in 0..options.partitions {
+					body.extend_from_slice(&0i16.to_be_bytes());
+					for value in [index, 1, 1, 1, 1, 1] {
+						body.extend_from_slice(&value.to_be_bytes());
+					}
+				}
+			}
+			// SaslHandshake
+			17 => {
+				let error_code: i16 = if reader.string() == "PLAIN" { 0 } else { 33 };
+				body.extend_from_slice(&error_code.to_be_bytes());
+				body.extend_from_slice(&1i32.to_be_bytes());
+				string(&mut body, "PLAIN");
+			}
+			// SaslAuthenticate
+			36 => {
+				let (username, password) = options.credentials.clone().unwrap_or_default();
+				let accepted = reader.bytes() == format!("\0{}\0{}", username, password).as_bytes();
+				if accepted {
+					body.extend_from_slice(&0i16.to_be_bytes());
+					body.extend_from_slice(&(-1i16).to_be_bytes());
+				} else {
+					body.extend_from_slice(&58i16.to_be_bytes());
+					string(&mut body, "Authentication failed: invalid credentials");
+				}
+				body.extend_from_slice(&0i32.to_be_bytes());
+				body.extend_from_slice(&0i64.to_be_bytes());
+			}
+			// Produce
+			0 => {
+				reader.string(); // transactional ID
+				let acks = reader.i16();
+				reader.i32(); // timeout
+				reader.i32();
+				let topic = reader.string();
+				reader.i32();
+				let partition = reader.i32();
+				for (key, value, headers) in decode_records(&reader.bytes()) {
+					records.lock().unwrap().push(ReceivedRecord {
+						partition,
+						acks,
+						key,
+						value,
+						headers,
+					});
+				}
+				let error_code = match stats.produce_requests.fetch_add(1, Ordering::SeqCst) {
+					0 if options.first_produce_error != 0 => options.first_produce_error,
+					_ => options.produce_error,
+				};
+				if acks == 0 {
+					continue;
+				}
+				body.extend_from_slice(&1i32.to_be_bytes());
+				string(&mut body, &topic);
+				body.extend_from_slice(&1i32.to_be_bytes());
+				body.extend_from_slice(&partition.to_be_bytes());
+				body.extend_from_slice(&error_code.to_be_bytes());
+				body.extend_from_slice(&0i64.to_be_bytes());
+				body.extend_from_slice(&(-1i64).to_be_bytes());
+				body.extend_from_slice(&options.throttle_time_ms.to_be_bytes());
+			}
+			_ => return,
+		}
+
+		let mut response = ((body.len() + 4) as i32).to_be_bytes().to_vec();
+		response.extend_from_slice(&correlation_id.to_be_bytes());
+		response.extend_from_slice(&body);
+		if stream.write_all(&response).await.is_err() {
+			return;
+		}
+	}
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_execution() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 3,
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let result = notification_service
+		.execute(
+			&create_test_trigger(vec![broker.address.clone()]),
+			create_test_variables(),
+			&monitor_match,
+			&HashMap::new(),
+		)
+		.await;
+	assert!(result.is_ok());
+
+	let records = broker.records();
+	assert_eq!(records.len(), 1);
+	let record = &records[0];
+	assert!((0..3).contains(&record.partition));
+	assert_eq!(record.acks, -1);
+	assert_eq!(record.key.as_deref(), Some(&b"0xabc"[..]));
+	assert_eq!(
+		record.headers,
+		vec![("monitor".to_string(), b"test_monitor".to_vec())]
+	);
+	let value: Value = serde_json::from_slice(&record.value).unwrap();
+	assert_eq!(value, serde_json::to_value(&monitor_match).unwrap());
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_same_key_same_partition() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 8,
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let trigger = create_test_trigger(vec![broker.address.clone()]);
+
+	for _ in 0..3 {
+		notification_service
+			.execute(
+				&trigger,
+				create_test_variables(),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await
+			.unwrap();
+	}
+
+	let records = broker.records();
+	assert_eq!(records.len(), 3);
+	assert!(records
+		.iter()
+		.all(|record| record.partition == records[0].partition));
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_reuses_metadata_and_connections() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 2,
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let trigger = create_test_trigger(vec![broker.address.clone()]);
+
+	for _ in 0..3 {
+		notification_service
+			.execute(
+				&trigger,
+				create_test_variables(),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await
+			.unwrap();
+	}
+
+	assert_eq!(broker.records().len(), 3);
+	assert_eq!(broker.connections(), 1);
+	assert_eq!(broker.api_versions_requests(), 1);
+	assert_eq!(broker.metadata_requests(), 1);
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_refreshes_metadata_on_not_leader() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		first_produce_error: 6,
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+
+	let result = notification_service
+		.execute(
+			&create_test_trigger(vec![broker.address.clone()]),
+			create_test_variables(),
+			&create_test_evm_match(create_test_monitor("test_monitor")),
+			&HashMap::new(),
+		)
+		.await;
+	assert!(result.is_ok());
+	assert_eq!(broker.metadata_requests(), 2);
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_reconnects_closed_connection() {
+	// Every connection is closed by the broker after an API versions, a metadata and a produce
+	// request
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		requests_per_connection: Some(3),
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let trigger = create_test_trigger(vec![broker.address.clone()]);
+
+	for _ in 0..2 {
+		let result = notification_service
+			.execute(
+				&trigger,
+				create_test_variables(),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await;
+		assert!(result.is_ok());
+	}
+
+	assert_eq!(broker.records().len(), 2);
+	assert_eq!(broker.connections(), 2);
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_payload_template_with_sasl() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		credentials: Some(("monitor".to_string(), "secret".to_string())),
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let mut trigger = create_test_trigger(vec![broker.address.clone()]);
+	if let TriggerTypeConfig::Kafka {
+		payload,
+		acks,
+		sasl,
+		..
+	} = &mut trigger.config
+	{
+		*payload = Some(json!({ "value": "{{ value }}", "monitor": "{{ monitor_name }}" }));
+		*acks = Some(KafkaAcks::Leader);
+		*sasl = Some(KafkaSasl {
+			mechanism: KafkaSaslMechanism::Plain,
+			username: "monitor".to_string(),
+			password: "secret".to_string(),
+		});
+	}
+
+	let result = notification_service
+		.execute(
+			&trigger,
+			create_test_variables(),
+			&create_test_evm_match(create_test_monitor("test_monitor")),
+			&HashMap::new(),
+		)
+		.await;
+	assert!(result.is_ok());
+
+	let records = broker.records();
+	assert_eq!(records.len(), 1);
+	assert_eq!(records[0].acks, 1);
+	assert_eq!(
+		serde_json::from_slice::<Value>(&records[0].value).unwrap(),
+		json!({ "value": "42", "monitor": "test_monitor" })
+	);
+
+	// Rejected credentials fail the delivery without retry
+	if let TriggerTypeConfig::Kafka { sasl, .. } = &mut trigger.config {
+		*sasl = Some(KafkaSasl {
+			mechanism: KafkaSaslMechanism::Plain,
+			username: "monitor".to_string(),
+			password: "wrong".to_string(),
+		});
+	}
+	let result = notification_service
+		.execute(
+			&trigger,
+			create_test_variables(),
+			&create_test_evm_match(create_test_monitor("test_monitor")),
+			&HashMap::new(),
+		)
+		.await;
+	assert!(matches!(result, Err(NotificationError::ConfigError(_))));
+	assert_eq!(broker.records().len(), 1);
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_acks_none() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let mut trigger = create_test_trigger(vec![broker.address.clone()]);
+	if let TriggerTypeConfig::Kafka { acks, .. } = &mut trigger.config {
+		*acks = Some(KafkaAcks::None);
+	}
+
+	let result = notification_service
+		.execute(
+			&trigger,
+			create_test_variables(),
+			&create_test_evm_match(create_test_monitor("test_monitor")),
+			&HashMap::new(),
+		)
+		.await;
+	assert!(result.is_ok());
+
+	// The delivery completes once the record is written, before the broker reads it
+	for _ in 0..50 {
+		if !broker.records().is_empty() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(20)).await;
+	}
+	assert_eq!(broker.records()[0].acks, 0);
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_broker_errors() {
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	// TOPIC_AUTHORIZATION_FAILED is not retriable
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		produce_error: 29,
+		..Default::default()
+	})
+	.await;
+	let result = notification_service
+		.execute(
+			&create_test_trigger(vec![broker.address.clone()]),
+			create_test_variables(),
+			&monitor_match,
+			&HashMap::new(),
+		)
+		.await;
+	let error = result.unwrap_err();
+	assert!(matches!(error, NotificationError::ConfigError(_)));
+
+	// NOT_LEADER_OR_FOLLOWER is retriable
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		produce_error: 6,
+		..Default::default()
+	})
+	.await;
+	let result = notification_service
+		.execute(
+			&create_test_trigger(vec![broker.address.clone()]),
+			create_test_variables(),
+			&monitor_match,
+			&HashMap::new(),
+		)
+		.await;
+	assert!(matches!(result, Err(NotificationError::NetworkError(_))));
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_unsupported_broker_version() {
+	// Brokers older than 0.11 do not support Produce v3
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		max_produce_version: Some(2),
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+
+	let result = notification_service
+		.execute(
+			&create_test_trigger(vec![broker.address.clone()]),
+			create_test_variables(),
+			&create_test_evm_match(create_test_monitor("test_monitor")),
+			&HashMap::new(),
+		)
+		.await;
+	assert!(matches!(result, Err(NotificationError::ConfigError(_))));
+	assert_eq!(broker.metadata_requests(), 0);
+	assert!(broker.records().is_empty());
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_throttled_connection() {
+	let broker = FakeBroker::start(BrokerOptions {
+		partitions: 1,
+		throttle_time_ms: 200,
+		..Default::default()
+	})
+	.await;
+	let notification_service = NotificationService::new();
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let trigger = create_test_trigger(vec![broker.address.clone()]);
+
+	// The produce request waits for the throttle of the metadata response, and the second
+	// delivery for the throttle of the first produce response
+	let started = Instant::now();
+	for _ in 0..2 {
+		notification_service
+			.execute(
+				&trigger,
+				create_test_variables(),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await
+			.unwrap();
+	}
+	assert!(started.elapsed() >= Duration::from_millis(400));
+	assert_eq!(broker.records().len(), 2);
+	assert_eq!(broker.connections(), 1);
+}
+
+#[tokio::test]
+async fn test_notification_service_kafka_unreachable_broker() {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let address = listener.local_addr().unwrap().to_string();
+	drop(listener);
+
+	let notification_service = NotificationService::new();
+	let result = notification_service
+		.execute(
+			&create_test_trigger(vec![address]),
+			create_test_variables(),
+			&create_test_evm_match(create_test_monitor("test_monitor")),
+			&HashMap::new(),
+		)
+		.await;
+	assert!(matches!(result, Err(NotificationError::NetworkError(_))));
+}
+
+/// Publishes to a Kafka broker started in a container
+///
+/// Requires Docker, run with `cargo test -- --ignored kafka_broker_container`.
+#[tokio::test]
+#[ignore]
+async fn test_notification_service_kafka_broker_container() {
+	let node = apache::Kafka::default().start().await.unwrap();
+	let port = node.get_host_port_ipv4(apache::KAFKA_PORT).await.unwrap();
+	let notification_service = NotificationService::new();
+	let trigger = create_test_trigger(vec![format!("127.0.0.1:{}", port)]);
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	// The first deliveries may fail while the broker creates the topic
+	let mut result = Ok(());
+	for _ in 0..10 {
+		result = notification_service
+			.execute(
+				&trigger,
+				create_test_variables(),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await;
+		if result.is_ok() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_secs(1)).await;
+	}
+	assert!(result.is_ok(), "{:?}", result);
+
+	// Records with the same key are published with the cached metadata and connection
+	for _ in 0..3 {
+		let result = notification_service
+			.execute(
+				&trigger,
+				create_test_variables(),
+				&monitor_match,
+				&HashMap::new(),
+			)
+			.await;
+		assert!(result.is_ok(), "{:?}", result);
+	}
+}
@@ -0,0 +1,147 @@
+use openzeppelin_monitor::{
+	models::{
+		BlockChainType, EVMMonitorMatch, MatchConditions, Monitor, MonitorMatch,
+		NotificationMessage, TransactionType, Trigger, TriggerType, TriggerTypeConfig,
+	},
+	services::notification::{MattermostNotifier, NotificationService, Notifier},
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::integration::mocks::{create_test_evm_transaction_receipt, create_test_transaction};
+
+fn create_test_monitor(name: &str) -> Monitor {
+	Monitor {
+		name: name.to_string(),
+		networks: vec!["ethereum_mainnet".to_string()],
+		paused: false,
+		triggers: vec!["test_trigger".to_string()],
+		..Default::default()
+	}
+}
+
+fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
+	let transaction = match create_test_transaction(BlockChainType::EVM) {
+		TransactionType::EVM(transaction) => transaction,
+		_ => panic!("Failed to create test transaction"),
+	};
+
+	MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor,
+		transaction,
+		receipt: create_test_evm_transaction_receipt(),
+		matched_on: MatchConditions::default(),
+		matched_on_args: None,
+		alert: None,
+		context: None,
+	}))
+}
+
+#[tokio::test]
+async fn test_mattermost_notification_success() {
+	// Setup async mock server
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.match_body(mockito::Matcher::Json(json!({
+			"text": "**Test Alert**\n\nTest message with value 42",
+		})))
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let notifier = MattermostNotifier::new(
+		server.url(),
+		"Test Alert".to_string(),
+		"Test message with value ${value}".to_string(),
+	)
+	.unwrap();
+
+	// Prepare and send test message
+	let mut variables = HashMap::new();
+	variables.insert("value".to_string(), "42".to_string());
+	let message = notifier.format_message(&variables);
+
+	let result = notifier.notify(&message).await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_mattermost_notification_failure() {
+	// Setup async mock server to simulate failure
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.with_status(500)
+		.with_body("Internal Server Error")
+		.create_async()
+		.await;
+
+	let notifier = MattermostNotifier::new(
+		server.url(),
+		"Test Alert".to_string(),
+		"Test message".to_string(),
+	)
+	.unwrap();
+
+	let result = notifier.notify("Test message").await;
+
+	assert!(result.is_err());
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_notification_service_mattermost_attachments_execution() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.match_body(mockito::Matcher::Json(json!({
+			"text": "**Test Alert**\n\nTest message 42",
+			"attachments": [{
+				"title": "test_monitor",
+				"fields": [{ "short": true, "title": "Value", "value": "42" }],
+				"fallback": "Test Alert",
+				"color": "#F1C40F"
+			}]
+		})))
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let trigger = Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::Mattermost,
+		config: TriggerTypeConfig::Mattermost {
+			mattermost_url: server.url(),
+			message: NotificationMessage {
+				title: "Test Alert".to_string(),
+				body: "Test message ${value}".to_string(),
+			},
+			attachments: Some(json!([{
+				"title": "{{ monitor_name }}",
+				"title_link": "{{ transaction_url }}",
+				"fields": [{ "short": true, "title": "Value", "value": "{{ value }}" }]
+			}])),
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	};
+
+	let variables = HashMap::from([
+		("value".to_string(), "42".to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+		("severity".to_string(), "warning".to_string()),
+	]);
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let result = notification_service
+		.execute(&trigger, variables, &monitor_match, &HashMap::new())
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
@@ -0,0 +1,166 @@
+use openzeppelin_monitor::{
+	models::{
+		BlockChainType, EVMMonitorMatch, MatchConditions, Monitor, MonitorMatch,
+		NotificationMessage, Severity, TransactionType, Trigger, TriggerType, TriggerTypeConfig,
+	},
+	services::notification::NotificationService,
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::integration::mocks::{create_test_evm_transaction_receipt, create_test_transaction};
+
+fn create_test_monitor(name: &str) -> Monitor {
+	Monitor {
+		name: name.to_string(),
+		networks: vec!["ethereum_mainnet".to_string()],
+		paused: false,
+		triggers: vec!["test_trigger".to_string()],
+		..Default::default()
+	}
+}
+
+fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
+	let transaction = match create_test_transaction(BlockChainType::EVM) {
+		TransactionType::EVM(transaction) => transaction,
+		_ => panic!("Failed to create test transaction"),
+	};
+
+	MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor,
+		transaction,
+		receipt: create_test_evm_transaction_receipt(),
+		matched_on: MatchConditions::default(),
+		matched_on_args: None,
+		alert: None,
+		context: None,
+	}))
+}
+
+fn create_test_trigger(api_url: String) -> Trigger {
+	Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::PagerDuty,
+		config: TriggerTypeConfig::PagerDuty {
+			routing_key: "test-routing-key".to_string(),
+			message: NotificationMessage {
+				title: "Large transfer".to_string(),
+				body: "Transfer of ${value}".to_string(),
+			},
+			dedup_key: None,
+			custom_details: Some(json!({ "value": "{{ value | number }}" })),
+			severity_map: Some(HashMap::from([(Severity::Critical, Severity::Error)])),
+			auto_resolve: None,
+			api_url: Some(api_url),
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	}
+}
+
+fn create_test_variables() -> HashMap<String, String> {
+	HashMap::from([
+		("value".to_string(), "42".to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+		("network_slug".to_string(), "ethereum_mainnet".to_string()),
+		("transaction_hash".to_string(), "0xabc".to_string()),
+		("severity".to_string(), "critical".to_string()),
+	])
+}
+
+#[tokio::test]
+async fn test_notification_service_pagerduty_execution() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/v2/enqueue")
+		.match_body(mockito::Matcher::Json(json!({
+			"routing_key": "test-routing-key",
+			"event_action": "trigger",
+			"dedup_key": "ethereum_mainnet:test_monitor:0xabc",
+			"payload": {
+				"summary": "Large transfer",
+				"source": "ethereum_mainnet",
+				"severity": "error",
+				"component": "test_monitor",
+				"custom_details": { "value": 42 }
+			}
+		})))
+		.with_status(202)
+		.create_async()
+		.await;
+
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let result = notification_service
+		.execute(
+			&create_test_trigger(server.url()),
+			create_test_variables(),
+			&monitor_match,
+			&HashMap::new(),
+		)
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_notification_service_pagerduty_resolves_dropped_alert() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/v2/enqueue")
+		.match_body(mockito::Matcher::Json(json!({
+			"routing_key": "test-routing-key",
+			"event_action": "resolve",
+			"dedup_key": "ethereum_mainnet:test_monitor:0xabc"
+		})))
+		.with_status(202)
+		.create_async()
+		.await;
+
+	let mut variables = create_test_variables();
+	variables.insert("alert_phase".to_string(), "dropped".to_string());
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let result = notification_service
+		.execute(
+			&create_test_trigger(server.url()),
+			variables,
+			&monitor_match,
+			&HashMap::new(),
+		)
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_notification_service_pagerduty_execution_failure() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/v2/enqueue")
+		.with_status(400)
+		.with_body(r#"{"status":"invalid event"}"#)
+		.create_async()
+		.await;
+
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let result = notification_service
+		.execute(
+			&create_test_trigger(server.url()),
+			create_test_variables(),
+			&monitor_match,
+			&HashMap::new(),
+		)
+		.await;
+
+	let error = result.unwrap_err();
+	assert_eq!(
+		error.http_status().map(|status| status.status.as_u16()),
+		Some(400)
+	);
+	mock.assert();
+}
@@ -31,6 +31,8 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		receipt: create_test_evm_transaction_receipt(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		alert: None,
+		context: None,
 	}))
 }
 
@@ -57,6 +59,9 @@ async fn test_notification_service_script_execution() {
 			arguments: Some(vec!["arg1".to_string()]),
 			timeout_ms: 1000,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	// Create monitor match and trigger scripts
@@ -85,6 +90,9 @@ async fn test_notification_service_script_execution_failure() {
 			arguments: Some(vec!["arg1".to_string()]),
 			timeout_ms: 1000,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
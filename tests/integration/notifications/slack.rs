@@ -32,6 +32,8 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		receipt: create_test_evm_transaction_receipt(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		alert: None,
+		context: None,
 	}))
 }
 
@@ -123,7 +125,11 @@ async fn test_notification_service_slack_execution_success() {
 				title: "Test Alert".to_string(),
 				body: "Test message with value ${value}".to_string(),
 			},
+			blocks: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	// Prepare and send test message
@@ -143,6 +149,82 @@ async fn test_notification_service_slack_execution_success() {
 	mock.assert();
 }
 
+#[tokio::test]
+async fn test_notification_service_slack_blocks_execution() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.match_body(mockito::Matcher::Json(json!({
+			"text": "*Test Alert*\n\nTest message with value 42",
+			"blocks": [
+				{
+					"type": "section",
+					"text": { "type": "mrkdwn", "text": "Value *42*" },
+					"fields": [{ "type": "mrkdwn", "text": "*Monitor*\ntest_monitor" }]
+				},
+				{
+					"type": "actions",
+					"elements": [{
+						"type": "button",
+						"text": { "type": "plain_text", "text": "View" },
+						"url": "https://etherscan.io/tx/0x1"
+					}]
+				}
+			]
+		})))
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let trigger = Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::Slack,
+		config: TriggerTypeConfig::Slack {
+			slack_url: server.url(),
+			message: openzeppelin_monitor::models::NotificationMessage {
+				title: "Test Alert".to_string(),
+				body: "Test message with value ${value}".to_string(),
+			},
+			blocks: Some(json!([
+				{
+					"type": "section",
+					"text": { "type": "mrkdwn", "text": "Value *{{ value }}*" },
+					"fields": [{ "type": "mrkdwn", "text": "*Monitor*\n{{ monitor_name }}" }]
+				},
+				{
+					"type": "actions",
+					"elements": [{
+						"type": "button",
+						"text": { "type": "plain_text", "text": "View" },
+						"url": "{{ transaction_link }}"
+					}]
+				}
+			])),
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	};
+
+	let variables = HashMap::from([
+		("value".to_string(), "42".to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+		(
+			"transaction_link".to_string(),
+			"https://etherscan.io/tx/0x1".to_string(),
+		),
+	]);
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let result = notification_service
+		.execute(&trigger, variables, &monitor_match, &HashMap::new())
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
 #[tokio::test]
 async fn test_notification_service_slack_execution_failure() {
 	let notification_service = NotificationService::new();
@@ -165,7 +247,11 @@ async fn test_notification_service_slack_execution_failure() {
 				title: "Test Alert".to_string(),
 				body: "Test message with value ${value}".to_string(),
 			},
+			blocks: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	// Prepare and send test message
@@ -2,13 +2,20 @@ use openzeppelin_monitor::{
 	models::{
 		BlockChainType, EVMMonitorMatch, MatchConditions, Monitor, MonitorMatch,
 		NotificationMessage, TransactionType, Trigger, TriggerType, TriggerTypeConfig,
+		WebhookPayload, WebhookPayloadFormat,
+	},
+	services::{
+		notification::{verify_webhook_signature, NotificationService, Notifier, WebhookNotifier},
+		trigger::{DeliveryOutcome, TriggerExecutionService, TriggerExecutionServiceTrait},
 	},
-	services::notification::{NotificationService, Notifier, WebhookNotifier},
 };
 use serde_json::json;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
-use crate::integration::mocks::{create_test_evm_transaction_receipt, create_test_transaction};
+use crate::integration::{
+	filters::common::setup_trigger_service,
+	mocks::{create_test_evm_transaction_receipt, create_test_transaction},
+};
 
 fn create_test_monitor(name: &str) -> Monitor {
 	Monitor {
@@ -32,6 +39,8 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		receipt: create_test_evm_transaction_receipt(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		alert: None,
+		context: None,
 	}))
 }
 
@@ -119,11 +128,16 @@ async fn test_notification_service_webhook_execution() {
 			method: Some("GET".to_string()),
 			headers: None,
 			secret: None,
+			secrets: None,
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
 			},
+			payload: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	let mut variables = HashMap::new();
@@ -138,6 +152,150 @@ async fn test_notification_service_webhook_execution() {
 	mock.assert();
 }
 
+#[tokio::test]
+async fn test_notification_service_webhook_payload_execution() {
+	let notification_service = NotificationService::new();
+	let mut server = mockito::Server::new_async().await;
+
+	let templated_mock = server
+		.mock("POST", "/alerts/ethereum%20mainnet")
+		.match_query(mockito::Matcher::UrlEncoded(
+			"monitor".to_string(),
+			"test_monitor".to_string(),
+		))
+		.match_header("content-type", "application/vnd.alert+json")
+		.match_body(mockito::Matcher::Json(json!({
+			"summary": "Transfer on ethereum mainnet",
+			"value": 42,
+			"confirmed": true,
+			"missing": null,
+			"tags": ["test_monitor"]
+		})))
+		.with_status(200)
+		.create_async()
+		.await;
+	let match_mock = server
+		.mock("POST", "/matches")
+		.match_header("content-type", "application/json")
+		.match_body(mockito::Matcher::PartialJson(json!({
+			"EVM": { "monitor": { "name": "test_monitor" } }
+		})))
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let mut trigger = Trigger {
+		name: "test_trigger".to_string(),
+		trigger_type: TriggerType::Webhook,
+		config: TriggerTypeConfig::Webhook {
+			url: server.url(),
+			method: Some("POST".to_string()),
+			headers: None,
+			secret: None,
+			secrets: None,
+			message: NotificationMessage {
+				title: "Test Alert".to_string(),
+				body: "Test message".to_string(),
+			},
+			payload: Some(WebhookPayload {
+				format: WebhookPayloadFormat::Json,
+				template: Some(json!({
+					"summary": "Transfer on {{ network }}",
+					"value": "{{ value | number }}",
+					"confirmed": "{{ confirmed | bool }}",
+					"missing": "{{ missing | number }}",
+					"tags": ["{{ monitor_name }}"]
+				})),
+				content_type: Some("application/vnd.alert+json".to_string()),
+				query: Some(HashMap::from([(
+					"monitor".to_string(),
+					"{{ monitor_name }}".to_string(),
+				)])),
+				path: Some("/alerts/{{ network }}".to_string()),
+			}),
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	};
+
+	let variables = HashMap::from([
+		("value".to_string(), "42".to_string()),
+		("confirmed".to_string(), "yes".to_string()),
+		("network".to_string(), "ethereum mainnet".to_string()),
+		("monitor_name".to_string(), "test_monitor".to_string()),
+	]);
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let result = notification_service
+		.execute(&trigger, variables.clone(), &monitor_match, &HashMap::new())
+		.await;
+	assert!(result.is_ok(), "{:?}", result);
+	templated_mock.assert();
+
+	// The match format sends the serialized monitor match
+	if let TriggerTypeConfig::Webhook { payload, .. } = &mut trigger.config {
+		*payload = Some(WebhookPayload {
+			format: WebhookPayloadFormat::Match,
+			template: None,
+			content_type: None,
+			query: None,
+			path: Some("matches".to_string()),
+		});
+	}
+	let result = notification_service
+		.execute(&trigger, variables, &monitor_match, &HashMap::new())
+		.await;
+	assert!(result.is_ok(), "{:?}", result);
+	match_mock.assert();
+}
+
+#[tokio::test]
+async fn test_webhook_signature_verified_by_receiver() {
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.match_header("X-Signature-Version", "v1")
+		.match_request(|request| {
+			let header = |name: &str| {
+				request
+					.header(name)
+					.first()
+					.and_then(|value| value.to_str().ok())
+					.unwrap_or_default()
+					.to_string()
+			};
+			let body = request.body().cloned().unwrap_or_default();
+			// A receiver which only knows the old secret still accepts the request
+			verify_webhook_signature(
+				&["old-secret"],
+				&body,
+				&header("X-Timestamp"),
+				&header("X-Signature"),
+				Some(Duration::from_secs(60)),
+			)
+		})
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let notifier = WebhookNotifier::new(
+		server.url(),
+		"Test Alert".to_string(),
+		"Test message".to_string(),
+		Some("POST".to_string()),
+		Some("new-secret".to_string()),
+		None,
+	)
+	.unwrap()
+	.with_secrets(vec!["old-secret".to_string()]);
+
+	let result = notifier.notify("Test message").await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
 #[tokio::test]
 async fn test_notification_service_webhook_execution_failure() {
 	let notification_service = NotificationService::new();
@@ -159,11 +317,16 @@ async fn test_notification_service_webhook_execution_failure() {
 			method: Some("GET".to_string()),
 			headers: None,
 			secret: None,
+			secrets: None,
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message".to_string(),
 			},
+			payload: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
@@ -189,7 +352,11 @@ async fn test_notification_service_webhook_execution_invalid_config() {
 				title: "Test Alert".to_string(),
 				body: "Test message".to_string(),
 			},
+			blocks: None,
 		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
 	};
 
 	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
@@ -205,3 +372,107 @@ async fn test_notification_service_webhook_execution_invalid_config() {
 		.to_string()
 		.contains("Invalid webhook configuration"));
 }
+
+#[tokio::test]
+async fn test_trigger_delivery_classifies_http_statuses() {
+	let mut server = mockito::Server::new_async().await;
+	let unavailable = server
+		.mock("POST", "/unavailable")
+		.with_status(503)
+		.with_header("retry-after", "30")
+		.create_async()
+		.await;
+	let bad_request = server
+		.mock("POST", "/bad-request")
+		.with_status(400)
+		.create_async()
+		.await;
+
+	let create_trigger = |name: &str, path: &str| Trigger {
+		name: name.to_string(),
+		trigger_type: TriggerType::Webhook,
+		config: TriggerTypeConfig::Webhook {
+			url: format!("{}{}", server.url(), path),
+			method: Some("POST".to_string()),
+			headers: None,
+			secret: None,
+			secrets: None,
+			message: NotificationMessage {
+				title: "Test Alert".to_string(),
+				body: "Test message".to_string(),
+			},
+			payload: None,
+		},
+		retry: None,
+		alert_policy: None,
+		digest: None,
+	};
+	let triggers = HashMap::from([
+		(
+			"unavailable".to_string(),
+			create_trigger("unavailable", "/unavailable"),
+		),
+		(
+			"bad_request".to_string(),
+			create_trigger("bad_request", "/bad-request"),
+		),
+	]);
+	let trigger_execution_service =
+		TriggerExecutionService::new(setup_trigger_service(triggers), NotificationService::new());
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let outcome = trigger_execution_service
+		.deliver(
+			"unavailable",
+			HashMap::new(),
+			&monitor_match,
+			&HashMap::new(),
+			1,
+		)
+		.await;
+	match outcome {
+		DeliveryOutcome::Retry { error, delay } => {
+			assert!(error.contains("503"));
+			assert_eq!(delay, Duration::from_secs(30));
+		}
+		outcome => panic!("Expected a retry, got {:?}", outcome),
+	}
+
+	// The last attempt of the default policy is not retried
+	let outcome = trigger_execution_service
+		.deliver(
+			"unavailable",
+			HashMap::new(),
+			&monitor_match,
+			&HashMap::new(),
+			5,
+		)
+		.await;
+	assert!(matches!(outcome, DeliveryOutcome::Failed { .. }));
+
+	// Client errors are permanent
+	let outcome = trigger_execution_service
+		.deliver(
+			"bad_request",
+			HashMap::new(),
+			&monitor_match,
+			&HashMap::new(),
+			1,
+		)
+		.await;
+	assert!(matches!(outcome, DeliveryOutcome::Failed { .. }));
+
+	let outcome = trigger_execution_service
+		.deliver(
+			"unknown",
+			HashMap::new(),
+			&monitor_match,
+			&HashMap::new(),
+			1,
+		)
+		.await;
+	assert!(matches!(outcome, DeliveryOutcome::Failed { .. }));
+
+	unavailable.expect(2).assert_async().await;
+	bad_request.assert_async().await;
+}
@@ -542,7 +542,7 @@ proptest! {
 				&status,
 				&tx,
 				&monitor,
-				&mut matched_transactions
+				&mut matched_transactions, None
 			);
 
 			// Verify matches based on monitor conditions and transaction status
@@ -595,7 +595,7 @@ proptest! {
 			&TransactionStatus::Success,
 			&tx,
 			&monitor,
-			&mut matched_transactions
+			&mut matched_transactions, None
 		);
 
 		prop_assert_eq!(matched_transactions.len(), 1);
@@ -639,7 +639,7 @@ proptest! {
 			&tx,
 			&monitor,
 			&mut matched_functions,
-			&mut matched_args
+			&mut matched_args, None
 		);
 
 		let should_match = monitor.match_conditions.functions.iter().any(|f|
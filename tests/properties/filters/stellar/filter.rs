@@ -726,7 +726,7 @@ proptest! {
 			_client: PhantomData,
 		};
 
-		filter.find_matching_transaction(&tx, &monitor, &mut matched_transactions);
+		filter.find_matching_transaction(&tx, &monitor, &mut matched_transactions, None);
 
 		// Determine match by checking:
 		// 1. Status match (Any, Success, or Failure)
@@ -790,7 +790,7 @@ proptest! {
 		filter.find_matching_transaction(
 			&tx,
 			&monitor,
-			&mut matched_transactions
+			&mut matched_transactions, None
 		);
 
 		// Should match when no conditions are specified
@@ -846,7 +846,7 @@ proptest! {
 			&transaction,
 			&updated_monitor,
 			&mut matched_functions,
-			&mut matched_args,
+			&mut matched_args, None
 		);
 
 		// Determine if transaction should match by checking:
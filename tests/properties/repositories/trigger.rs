@@ -90,7 +90,7 @@ proptest! {
 			// Test invalid cases
 			match &trigger.trigger_type {
 				TriggerType::Slack => {
-					if let TriggerTypeConfig::Slack { slack_url: _, message: _ } = &trigger.config {
+					if let TriggerTypeConfig::Slack { .. } = &trigger.config {
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Slack { slack_url, .. } = &mut invalid_trigger.config {
 							*slack_url = "not-a-url".to_string(); // Invalid URL format
@@ -147,7 +147,7 @@ proptest! {
 					}
 				}
 				TriggerType::Webhook => {
-					if let TriggerTypeConfig::Webhook { url: _, method: _, headers: _, secret: _, message: _ } = &trigger.config {
+					if let TriggerTypeConfig::Webhook { .. } = &trigger.config {
 						// Test invalid method
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Webhook { method: m, .. } = &mut invalid_trigger.config {
@@ -184,7 +184,7 @@ proptest! {
 					}
 				}
 				TriggerType::Discord => {
-					if let TriggerTypeConfig::Discord { discord_url: _, message: _ } = &trigger.config {
+					if let TriggerTypeConfig::Discord { .. } = &trigger.config {
 						// Test invalid URL
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Discord { discord_url: u, .. } = &mut invalid_trigger.config {
@@ -267,6 +267,54 @@ proptest! {
 						prop_assert!(invalid_trigger.validate().is_err());
 					}
 				}
+				TriggerType::Teams => {
+					// Test invalid webhook URL
+					invalid_trigger = trigger.clone();
+					if let TriggerTypeConfig::Teams { teams_url: u, .. } = &mut invalid_trigger.config {
+						*u = "not-a-url".to_string();
+					}
+					prop_assert!(invalid_trigger.validate().is_err());
+				}
+				TriggerType::Mattermost => {
+					// Test invalid webhook URL
+					invalid_trigger = trigger.clone();
+					if let TriggerTypeConfig::Mattermost { mattermost_url: u, .. } = &mut invalid_trigger.config {
+						*u = "not-a-url".to_string();
+					}
+					prop_assert!(invalid_trigger.validate().is_err());
+				}
+				TriggerType::PagerDuty => {
+					// Test empty routing key
+					invalid_trigger = trigger.clone();
+					if let TriggerTypeConfig::PagerDuty { routing_key: k, .. } = &mut invalid_trigger.config {
+						*k = "".to_string();
+					}
+					prop_assert!(invalid_trigger.validate().is_err());
+				}
+				TriggerType::Opsgenie => {
+					// Test empty API key
+					invalid_trigger = trigger.clone();
+					if let TriggerTypeConfig::Opsgenie { api_key: k, .. } = &mut invalid_trigger.config {
+						*k = "".to_string();
+					}
+					prop_assert!(invalid_trigger.validate().is_err());
+				}
+				TriggerType::Kafka => {
+					// Test empty brokers
+					invalid_trigger = trigger.clone();
+					if let TriggerTypeConfig::Kafka { brokers: b, .. } = &mut invalid_trigger.config {
+						b.clear();
+					}
+					prop_assert!(invalid_trigger.validate().is_err());
+				}
+				TriggerType::File => {
+					// Test empty path
+					invalid_trigger = trigger.clone();
+					if let TriggerTypeConfig::File { path: p, .. } = &mut invalid_trigger.config {
+						*p = "".to_string();
+					}
+					prop_assert!(invalid_trigger.validate().is_err());
+				}
 			}
 		}
 	}
@@ -51,6 +51,10 @@ pub fn monitor_strategy(
 				addresses,
 				match_conditions,
 				trigger_conditions,
+				two_phase_alerts: false,
+				alert_policy: None,
+				reports: vec![],
+				severity: None,
 			},
 		)
 }
@@ -73,12 +77,19 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 				"https://hooks\\.slack\\.com/[a-zA-Z0-9/]+".prop_map(|s| s.to_string()),
 				notification_message_strategy(),
 			)
-				.prop_map(|(slack_url, message)| TriggerTypeConfig::Slack { slack_url, message })
+				.prop_map(|(slack_url, message)| TriggerTypeConfig::Slack {
+					slack_url,
+					message,
+					blocks: None,
+				})
 		)
 			.prop_map(|(name, trigger_type, config)| Trigger {
 				name,
 				trigger_type,
 				config,
+				retry: None,
+				alert_policy: None,
+				digest: None,
 			}),
 		// Email strategy
 		(
@@ -114,6 +125,9 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 				name,
 				trigger_type,
 				config,
+				retry: None,
+				alert_policy: None,
+				digest: None,
 			}),
 		// Webhook strategy
 		(
@@ -136,7 +150,9 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 						method,
 						headers,
 						secret,
+						secrets: None,
 						message,
+						payload: None,
 					}
 				})
 		)
@@ -144,6 +160,9 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 				name,
 				trigger_type,
 				config,
+				retry: None,
+				alert_policy: None,
+				digest: None,
 			}),
 		// Script strategy
 		// Disabled for now as it requires a script to be present
@@ -214,6 +233,7 @@ pub fn network_strategy() -> impl Strategy<Value = Network> {
 				cron_schedule,
 				max_past_blocks,
 				store_blocks,
+				explorer_url: None,
 			},
 		)
 }